@@ -0,0 +1,185 @@
+use std::{
+    fs,
+    io::{stdout, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use argp::FromArgs;
+use objdiff_core::{
+    build::{run_compile_command, run_make, BuildConfig, BuildMonitor},
+    config::{compile_commands::CompileCommand, try_project_config, ProjectObject},
+    util::CancellationToken,
+};
+use tracing::{error, info};
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Build the target and/or base objects of project units, without diffing.
+///
+/// Build output is streamed to stderr. A JSON summary of each unit's build steps is written to
+/// the output file, or stdout if none is given.
+#[argp(subcommand, name = "build")]
+pub struct Args {
+    #[argp(option, short = 'p')]
+    /// Project directory
+    project: Option<PathBuf>,
+    #[argp(option, short = 'u')]
+    /// Unit name within project, may be repeated (default: all units)
+    unit: Vec<String>,
+    #[argp(option)]
+    /// Project version to build (default: first configured version)
+    project_version: Option<String>,
+    #[argp(switch)]
+    /// Build target objects (default: project's build_target setting)
+    target: bool,
+    #[argp(switch)]
+    /// Build base objects (default: project's build_base setting)
+    base: bool,
+    #[argp(switch, short = 'k')]
+    /// Keep building after a unit fails
+    keep_going: bool,
+    #[argp(option)]
+    /// WSL distro to run the build system in (Windows only)
+    wsl_distro: Option<String>,
+    #[argp(option, short = 'o')]
+    /// Output file for the JSON summary ("-" for stdout)
+    output: Option<PathBuf>,
+}
+
+#[derive(serde::Serialize)]
+struct BuildSummary {
+    success: bool,
+    units: Vec<UnitBuildResult>,
+}
+
+#[derive(serde::Serialize)]
+struct UnitBuildResult {
+    unit: String,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<StepResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base: Option<StepResult>,
+}
+
+#[derive(serde::Serialize)]
+struct StepResult {
+    path: PathBuf,
+    success: bool,
+    cmdline: String,
+    stdout: String,
+    stderr: String,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let project_dir = match &args.project {
+        Some(p) => p.clone(),
+        None => std::env::current_dir().context("Failed to get the current directory")?,
+    };
+    let Some((result, info)) = try_project_config(&project_dir) else {
+        bail!("Project config not found in {}", project_dir.display())
+    };
+    let config = result
+        .with_context(|| format!("Reading project config {}", info.path.display()))?
+        .for_version(args.project_version.as_deref())?;
+    let (build_target, build_base) = if args.target || args.base {
+        (args.target, args.base)
+    } else {
+        (config.build_target.unwrap_or(false), config.build_base.unwrap_or(true))
+    };
+    if !build_target && !build_base {
+        bail!("Nothing to build: the project disables building both target and base objects");
+    }
+
+    let mut units = Vec::<ProjectObject>::new();
+    if args.unit.is_empty() {
+        units.extend(config.units().iter().cloned());
+    } else {
+        for name in &args.unit {
+            let Some(unit) = config.units().iter().find(|u| u.name() == name.as_str()) else {
+                bail!("Unit not found: {name}")
+            };
+            units.push(unit.clone());
+        }
+    }
+
+    let build_config = BuildConfig {
+        project_dir: Some(project_dir.clone()),
+        custom_make: config.custom_make.clone(),
+        custom_args: config.custom_args.clone(),
+        selected_wsl_distro: args.wsl_distro.clone(),
+    };
+    let cancel = CancellationToken::default();
+    let mut summary = BuildSummary { success: true, units: vec![] };
+    for mut unit in units {
+        unit.resolve_paths(&project_dir, config.target_dir.as_deref(), config.base_dir.as_deref());
+        let mut result = UnitBuildResult {
+            unit: unit.name().to_string(),
+            success: true,
+            target: None,
+            base: None,
+        };
+        if build_target {
+            if let Some(path) = &unit.target_path {
+                result.target = Some(build_step(&build_config, &project_dir, path, None, &cancel)?);
+            }
+        }
+        if build_base {
+            if let Some(path) = &unit.base_path {
+                let command = unit.resolved_compile_command.as_ref();
+                result.base =
+                    Some(build_step(&build_config, &project_dir, path, command, &cancel)?);
+            }
+        }
+        result.success = [&result.target, &result.base].into_iter().flatten().all(|s| s.success);
+        if !result.success {
+            error!("Failed to build {}", result.unit);
+            summary.success = false;
+        }
+        summary.units.push(result);
+        if !summary.success && !args.keep_going {
+            break;
+        }
+    }
+
+    let json = serde_json::to_string_pretty(&summary)?;
+    match &args.output {
+        Some(path) if path.as_os_str() != "-" => {
+            fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))?;
+            info!("Wrote {}", path.display());
+        }
+        _ => writeln!(stdout(), "{json}")?,
+    }
+    if !summary.success {
+        bail!("Build failed");
+    }
+    Ok(())
+}
+
+fn build_step(
+    config: &BuildConfig,
+    project_dir: &Path,
+    path: &Path,
+    command: Option<&CompileCommand>,
+    cancel: &CancellationToken,
+) -> Result<StepResult> {
+    info!("Building {}", path.display());
+    let on_output = &mut |line: &str| eprintln!("{line}");
+    let monitor = Some(BuildMonitor { on_output, cancel });
+    let status = match command {
+        Some(command) => run_compile_command(&command.directory, &command.arguments, monitor),
+        None => {
+            let Ok(path_rel) = path.strip_prefix(project_dir) else {
+                bail!("Path '{}' doesn't begin with '{}'", path.display(), project_dir.display())
+            };
+            run_make(config, path_rel, monitor)
+        }
+    };
+    Ok(StepResult {
+        path: path.to_path_buf(),
+        success: status.success,
+        cmdline: status.cmdline,
+        stdout: status.stdout,
+        stderr: status.stderr,
+    })
+}
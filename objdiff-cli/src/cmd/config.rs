@@ -0,0 +1,95 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use argp::FromArgs;
+use objdiff_core::config::{
+    import::{import_dtk, import_splat},
+    ProjectConfig,
+};
+use tracing::info;
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Manage the project configuration.
+#[argp(subcommand, name = "config")]
+pub struct Args {
+    #[argp(subcommand)]
+    command: SubCommand,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argp(subcommand)]
+pub enum SubCommand {
+    ImportDtk(ImportDtkArgs),
+    ImportSplat(ImportSplatArgs),
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Generate objdiff.json from a decomp-toolkit project's config.yml.
+#[argp(subcommand, name = "import-dtk")]
+pub struct ImportDtkArgs {
+    #[argp(positional)]
+    /// decomp-toolkit config file (e.g. config/GALE01/config.yml)
+    config: PathBuf,
+    #[argp(option, short = 'p')]
+    /// Project directory (default: current directory)
+    project: Option<PathBuf>,
+    #[argp(option, short = 'o')]
+    /// Output file (default: objdiff.json in the project directory)
+    output: Option<PathBuf>,
+    #[argp(switch, short = 'f')]
+    /// Overwrite an existing output file
+    force: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Generate objdiff.json from a splat project's splat.yaml.
+#[argp(subcommand, name = "import-splat")]
+pub struct ImportSplatArgs {
+    #[argp(positional)]
+    /// splat config file
+    config: PathBuf,
+    #[argp(option, short = 'p')]
+    /// Project directory (default: current directory)
+    project: Option<PathBuf>,
+    #[argp(option, short = 'o')]
+    /// Output file (default: objdiff.json in the project directory)
+    output: Option<PathBuf>,
+    #[argp(switch, short = 'f')]
+    /// Overwrite an existing output file
+    force: bool,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    match args.command {
+        SubCommand::ImportDtk(args) => {
+            let project_dir = args.project.as_deref().unwrap_or_else(|| Path::new("."));
+            let config = import_dtk(project_dir, &args.config)?;
+            write_config(&config, project_dir, args.output.as_deref(), args.force)
+        }
+        SubCommand::ImportSplat(args) => {
+            let project_dir = args.project.as_deref().unwrap_or_else(|| Path::new("."));
+            let config = import_splat(&args.config)?;
+            write_config(&config, project_dir, args.output.as_deref(), args.force)
+        }
+    }
+}
+
+fn write_config(
+    config: &ProjectConfig,
+    project_dir: &Path,
+    output: Option<&Path>,
+    force: bool,
+) -> Result<()> {
+    let output = output.map(Path::to_path_buf).unwrap_or_else(|| project_dir.join("objdiff.json"));
+    if output.exists() && !force {
+        bail!("{} already exists (use --force to overwrite)", output.display());
+    }
+    let data = serde_json::to_string_pretty(config).context("Failed to serialize config")?;
+    fs::write(&output, data + "\n")
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+    info!("Wrote {} units to {}", config.units().len(), output.display());
+    Ok(())
+}
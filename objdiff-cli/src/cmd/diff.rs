@@ -1,7 +1,6 @@
 use std::{
-    fs,
+    env, fs,
     io::stdout,
-    mem,
     path::{Path, PathBuf},
     str::FromStr,
     sync::{
@@ -9,7 +8,7 @@ use std::{
         Arc,
     },
     task::{Wake, Waker},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use anyhow::{bail, Context, Result};
@@ -23,28 +22,25 @@ use crossterm::{
 };
 use objdiff_core::{
     bindings::diff::DiffResult,
-    build::{
-        watcher::{create_watcher, Watcher},
-        BuildConfig,
-    },
-    config::{build_globset, default_watch_patterns, ProjectConfig, ProjectObject},
+    config::{ProjectConfig, ProjectObject, SymbolImport, SymbolMatching},
     diff,
-    diff::ObjDiff,
-    jobs::{
-        objdiff::{start_build, ObjDiffConfig},
-        Job, JobQueue, JobResult,
+    diff::{
+        properties::{ConfigProperty, ConfigPropertyKind},
+        ObjDiff,
     },
     obj,
-    obj::ObjInfo,
+    obj::{abi_info::diff_abi_sections, symbol_import::import_symbols, ObjInfo},
+    session::{Session, SessionEvent},
 };
 use ratatui::prelude::*;
+use tracing::{debug, info};
 
 use crate::{
     util::{
         output::{write_output, OutputFormat},
         term::crossterm_panic_handler,
     },
-    views::{function_diff::FunctionDiffUi, EventControlFlow, EventResult, UiView},
+    views::{function_diff::FunctionDiffUi, theme::Theme, EventControlFlow, EventResult, UiView},
 };
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -63,28 +59,69 @@ pub struct Args {
     #[argp(option, short = 'u')]
     /// Unit name within project
     unit: Option<String>,
+    #[argp(option)]
+    /// Project version to diff (default: first configured version)
+    project_version: Option<String>,
     #[argp(switch, short = 'x')]
     /// Relax relocation diffs
     relax_reloc_diffs: bool,
+    #[argp(switch, short = 'q')]
+    /// Ignore known codegen quirks
+    ignore_codegen_quirks: bool,
+    #[argp(switch, short = 'a')]
+    /// Match symbols by address instead of by name
+    match_by_address: bool,
     #[argp(option, short = 'o')]
     /// Output file (one-shot mode) ("-" for stdout)
     output: Option<PathBuf>,
     #[argp(option)]
     /// Output format (json, json-pretty, proto) (default: json)
     format: Option<String>,
+    #[argp(option, short = 'c')]
+    /// Diff option as key=value, may be repeated ("help" lists all options)
+    config: Vec<String>,
+    #[argp(switch, short = 'v')]
+    /// Log skipped sections, symbols and relocations (one-shot mode)
+    verbose: bool,
+    #[argp(option)]
+    /// Diff the symbol against another function in the same target object
+    self_diff: Option<String>,
+    #[argp(option)]
+    /// TUI color theme file (JSON) (env: OBJDIFF_TUI_THEME)
+    theme: Option<PathBuf>,
+    #[argp(switch)]
+    /// Show markers (!, +, -, ~) before differing instructions in the TUI
+    markers: bool,
     #[argp(positional)]
     /// Function symbol to diff
     symbol: Option<String>,
 }
 
-pub fn run(args: Args) -> Result<()> {
-    let (target_path, base_path, project_config) = match (
+pub fn run(args: Args, use_colors: bool) -> Result<()> {
+    if args.config.iter().any(|option| option == "help") {
+        print_config_help();
+        return Ok(());
+    }
+    let mut diff_config = diff::DiffObjConfig {
+        relax_reloc_diffs: args.relax_reloc_diffs,
+        ignore_codegen_quirks: args.ignore_codegen_quirks,
+        ..Default::default()
+    };
+    for option in &args.config {
+        diff_config.set_property_str(option)?;
+    }
+    if let Some(other) = &args.self_diff {
+        let Some(symbol_name) = &args.symbol else { bail!("Self-diff requires a symbol name") };
+        diff_config.symbol_mappings.mappings.insert(symbol_name.clone(), other.clone());
+    }
+
+    let (target_path, base_path, project, unit) = match (
         &args.target,
         &args.base,
         &args.project,
         &args.unit,
     ) {
-        (Some(t), Some(b), None, None) => (Some(t.clone()), Some(b.clone()), None),
+        (Some(t), Some(b), None, None) => (Some(t.clone()), Some(b.clone()), None, None),
         (None, None, p, u) => {
             let project = match p {
                 Some(project) => project.clone(),
@@ -95,9 +132,11 @@ pub fn run(args: Args) -> Result<()> {
             else {
                 bail!("Project config not found in {}", &project.display())
             };
-            let mut project_config = project_config.with_context(|| {
-                format!("Reading project config {}", project_config_info.path.display())
-            })?;
+            let mut project_config = project_config
+                .with_context(|| {
+                    format!("Reading project config {}", project_config_info.path.display())
+                })?
+                .for_version(args.project_version.as_deref())?;
             let object = {
                 let resolve_paths = |o: &mut ProjectObject| {
                     o.resolve_paths(
@@ -181,15 +220,48 @@ pub fn run(args: Args) -> Result<()> {
             };
             let target_path = object.target_path.clone();
             let base_path = object.base_path.clone();
-            (target_path, base_path, Some(project_config))
+            let unit = object.clone();
+            (target_path, base_path, Some((project, project_config)), Some(unit))
         }
         _ => bail!("Either target and base or project and unit must be specified"),
     };
+    // Both sides come from the target object, paired by the symbol mapping above
+    let base_path = if args.self_diff.is_some() {
+        let Some(target_path) = &target_path else { bail!("Self-diff requires a target object") };
+        Some(target_path.clone())
+    } else {
+        base_path
+    };
 
+    let symbol_matching = if args.match_by_address {
+        SymbolMatching::Address
+    } else {
+        unit.as_ref().and_then(|u| u.symbol_matching()).unwrap_or_default()
+    };
+    diff_config.compiler =
+        unit.as_ref().and_then(|u| u.scratch.as_ref()).and_then(|s| s.compiler.clone());
     if let Some(output) = &args.output {
-        run_oneshot(&args, output, target_path.as_deref(), base_path.as_deref())
+        run_oneshot(
+            &args,
+            output,
+            target_path.as_deref(),
+            base_path.as_deref(),
+            project.as_ref().map(|(_, config)| config),
+            unit.as_ref().and_then(|u| u.symbol_import.as_ref()),
+            diff_config,
+            symbol_matching,
+        )
     } else {
-        run_interactive(args, target_path, base_path, project_config)
+        let diff_config = diff::DiffObjConfig { symbol_matching, ..diff_config };
+        run_interactive(
+            args,
+            target_path,
+            base_path,
+            project.map(|(dir, _)| dir),
+            unit.map(|u| u.name().to_string()),
+            diff_config,
+            use_colors,
+        )
     }
 }
 
@@ -198,101 +270,134 @@ fn run_oneshot(
     output: &Path,
     target_path: Option<&Path>,
     base_path: Option<&Path>,
+    project_config: Option<&ProjectConfig>,
+    symbol_import: Option<&SymbolImport>,
+    diff_config: diff::DiffObjConfig,
+    symbol_matching: SymbolMatching,
 ) -> Result<()> {
     let output_format = OutputFormat::from_option(args.format.as_deref())?;
     let config = diff::DiffObjConfig {
-        relax_reloc_diffs: args.relax_reloc_diffs,
-        ..Default::default() // TODO
+        symbol_matching,
+        codegen_quirks: project_config.and_then(|c| c.codegen_quirks.clone()).unwrap_or_default(),
+        ppc_gqrs: project_config.and_then(|c| c.ppc_gqrs.clone()).unwrap_or_default(),
+        reloc_renames: project_config.and_then(|c| c.reloc_renames.clone()).unwrap_or_default(),
+        global_symbols_only: project_config
+            .and_then(|c| c.global_symbols_only)
+            .unwrap_or(diff_config.global_symbols_only),
+        ..diff_config
     };
-    let target = target_path
+    let mut target = target_path
         .map(|p| obj::read::read(p, &config).with_context(|| format!("Loading {}", p.display())))
         .transpose()?;
+    if let (Some(target), Some(import)) = (&mut target, symbol_import) {
+        import_symbols(target, import)?;
+    }
     let base = base_path
         .map(|p| obj::read::read(p, &config).with_context(|| format!("Loading {}", p.display())))
         .transpose()?;
+    if args.verbose {
+        for (path, obj) in [(target_path, &target), (base_path, &base)] {
+            if let (Some(path), Some(obj)) = (path, obj) {
+                log_diagnostics(path, obj);
+            }
+        }
+        let abi_diffs = diff_abi_sections(
+            target.as_ref().map(|o| o.abi_sections.as_slice()).unwrap_or_default(),
+            base.as_ref().map(|o| o.abi_sections.as_slice()).unwrap_or_default(),
+        );
+        for diff in abi_diffs.iter().filter(|d| !d.is_match()) {
+            info!(
+                "ABI mismatch in {} {}: target {}, base {}",
+                diff.section,
+                diff.field,
+                diff.left.as_deref().unwrap_or("(missing)"),
+                diff.right.as_deref().unwrap_or("(missing)")
+            );
+        }
+    }
     let result = diff::diff_objs(&config, target.as_ref(), base.as_ref(), None)?;
     let left = target.as_ref().and_then(|o| result.left.as_ref().map(|d| (o, d)));
     let right = base.as_ref().and_then(|o| result.right.as_ref().map(|d| (o, d)));
-    write_output(&DiffResult::new(left, right), Some(output), output_format)?;
+    let start = Instant::now();
+    let diff_result = DiffResult::new(left, right);
+    debug!("Display: {:.2}ms", start.elapsed().as_secs_f64() * 1000.0);
+    write_output(&diff_result, Some(output), output_format)?;
     Ok(())
 }
 
+fn print_config_help() {
+    let defaults = diff::DiffObjConfig::default();
+    let mut group = None;
+    for &property in ConfigProperty::ALL {
+        if group != Some(property.group()) {
+            if group.is_some() {
+                println!();
+            }
+            println!("{}:", property.group());
+            group = Some(property.group());
+        }
+        let values = match property.kind() {
+            ConfigPropertyKind::Boolean => "true|false".to_string(),
+            ConfigPropertyKind::Number => "<number>".to_string(),
+            ConfigPropertyKind::Choice => {
+                property.choices().iter().map(|c| c.value.as_str()).collect::<Vec<_>>().join("|")
+            }
+        };
+        let default = property.display_value(defaults.property_value(property));
+        println!("  {}={} (default: {})", property.id(), values, default);
+        for line in property.description().lines() {
+            println!("      {line}");
+        }
+        for choice in property.choices() {
+            match choice.description {
+                Some(description) => println!("      {}: {}", choice.value, description),
+                None => println!("      {}: {}", choice.value, choice.name),
+            }
+        }
+    }
+}
+
+fn log_diagnostics(path: &Path, obj: &ObjInfo) {
+    info!("{}: {} load diagnostic(s)", path.display(), obj.diagnostics.len());
+    for diagnostic in &obj.diagnostics {
+        info!("  {diagnostic}");
+    }
+}
+
 pub struct AppState {
-    pub jobs: JobQueue,
+    pub session: Session,
     pub waker: Arc<TermWaker>,
-    pub project_dir: Option<PathBuf>,
-    pub project_config: Option<ProjectConfig>,
-    pub target_path: Option<PathBuf>,
-    pub base_path: Option<PathBuf>,
-    pub left_obj: Option<(ObjInfo, ObjDiff)>,
-    pub right_obj: Option<(ObjInfo, ObjDiff)>,
     pub prev_obj: Option<(ObjInfo, ObjDiff)>,
-    pub reload_time: Option<time::OffsetDateTime>,
+    /// Error of the last build that couldn't be run
+    pub last_error: Option<String>,
     pub time_format: Vec<time::format_description::FormatItem<'static>>,
-    pub relax_reloc_diffs: bool,
-    pub watcher: Option<Watcher>,
-    pub modified: Arc<AtomicBool>,
+    pub theme: Theme,
 }
 
-fn create_objdiff_config(state: &AppState) -> ObjDiffConfig {
-    ObjDiffConfig {
-        build_config: BuildConfig {
-            project_dir: state.project_dir.clone(),
-            custom_make: state
-                .project_config
-                .as_ref()
-                .and_then(|c| c.custom_make.as_ref())
-                .cloned(),
-            custom_args: state
-                .project_config
-                .as_ref()
-                .and_then(|c| c.custom_args.as_ref())
-                .cloned(),
-            selected_wsl_distro: None,
-        },
-        build_base: state.project_config.as_ref().is_some_and(|p| p.build_base.unwrap_or(true)),
-        build_target: state
-            .project_config
-            .as_ref()
-            .is_some_and(|p| p.build_target.unwrap_or(false)),
-        target_path: state.target_path.clone(),
-        base_path: state.base_path.clone(),
-        diff_obj_config: diff::DiffObjConfig {
-            relax_reloc_diffs: state.relax_reloc_diffs,
-            ..Default::default() // TODO
-        },
-        symbol_mappings: Default::default(),
-        selecting_left: None,
-        selecting_right: None,
+impl AppState {
+    pub fn left_obj(&self) -> Option<&(ObjInfo, ObjDiff)> {
+        self.session.get_diff()?.first_obj.as_ref()
     }
-}
 
-impl AppState {
-    fn reload(&mut self) -> Result<()> {
-        let config = create_objdiff_config(self);
-        self.jobs.push_once(Job::ObjDiff, || start_build(Waker::from(self.waker.clone()), config));
-        Ok(())
+    pub fn right_obj(&self) -> Option<&(ObjInfo, ObjDiff)> {
+        self.session.get_diff()?.second_obj.as_ref()
+    }
+
+    pub fn reload_time(&self) -> Option<time::OffsetDateTime> {
+        self.session.get_diff().map(|result| result.time)
     }
 
-    fn check_jobs(&mut self) -> Result<bool> {
+    fn check_jobs(&mut self) -> bool {
         let mut redraw = false;
-        self.jobs.collect_results();
-        for result in mem::take(&mut self.jobs.results) {
-            match result {
-                JobResult::None => unreachable!("Unexpected JobResult::None"),
-                JobResult::ObjDiff(result) => {
-                    let result = result.unwrap();
-                    self.left_obj = result.first_obj;
-                    self.right_obj = result.second_obj;
-                    self.reload_time = Some(result.time);
-                    redraw = true;
-                }
-                JobResult::CheckUpdate(_) => todo!("CheckUpdate"),
-                JobResult::Update(_) => todo!("Update"),
-                JobResult::CreateScratch(_) => todo!("CreateScratch"),
+        for event in self.session.poll() {
+            match event {
+                SessionEvent::FilesChanged => {}
+                SessionEvent::DiffUpdated => self.last_error = None,
+                SessionEvent::BuildFailed(e) => self.last_error = Some(format!("{e:#}")),
             }
+            redraw = true;
         }
-        Ok(redraw)
+        redraw
     }
 }
 
@@ -309,44 +414,45 @@ fn run_interactive(
     args: Args,
     target_path: Option<PathBuf>,
     base_path: Option<PathBuf>,
-    project_config: Option<ProjectConfig>,
+    project_dir: Option<PathBuf>,
+    unit_name: Option<String>,
+    diff_config: diff::DiffObjConfig,
+    use_colors: bool,
 ) -> Result<()> {
     let Some(symbol_name) = &args.symbol else { bail!("Interactive mode requires a symbol name") };
+    let theme_path =
+        args.theme.clone().or_else(|| env::var_os("OBJDIFF_TUI_THEME").map(PathBuf::from));
+    let mut theme = if !use_colors {
+        Theme::monochrome()
+    } else if let Some(path) = &theme_path {
+        Theme::load(path)?
+    } else {
+        Theme::default()
+    };
+    theme.markers |= args.markers;
     let time_format = time::format_description::parse_borrowed::<2>("[hour]:[minute]:[second]")
         .context("Failed to parse time format")?;
-    let mut state = AppState {
-        jobs: Default::default(),
-        waker: Default::default(),
-        project_dir: args.project.clone(),
-        project_config,
-        target_path,
-        base_path,
-        left_obj: None,
-        right_obj: None,
-        prev_obj: None,
-        reload_time: None,
-        time_format,
-        relax_reloc_diffs: args.relax_reloc_diffs,
-        watcher: None,
-        modified: Default::default(),
-    };
-    if let Some(project_dir) = &state.project_dir {
-        let watch_patterns = state
-            .project_config
-            .as_ref()
-            .and_then(|c| c.watch_patterns.as_ref())
-            .cloned()
-            .unwrap_or_else(default_watch_patterns);
-        state.watcher = Some(create_watcher(
-            state.modified.clone(),
-            project_dir,
-            build_globset(&watch_patterns)?,
-            Waker::from(state.waker.clone()),
-        )?);
+    let waker = Arc::<TermWaker>::default();
+    let mut session = Session::new(Waker::from(waker.clone()));
+    session.set_diff_config(diff_config);
+    if let Some(project_dir) = &project_dir {
+        session.open_project(project_dir, args.project_version.as_deref())?;
+    }
+    if let Some(unit_name) = &unit_name {
+        session.select_unit(unit_name)?;
     }
-    let mut view: Box<dyn UiView> =
-        Box::new(FunctionDiffUi { symbol_name: symbol_name.clone(), ..Default::default() });
-    state.reload()?;
+    // In self-diff mode, both sides are the target object
+    if unit_name.is_none() || args.self_diff.is_some() {
+        session.set_objects(target_path, base_path);
+    }
+    let mut state =
+        AppState { session, waker, prev_obj: None, last_error: None, time_format, theme };
+    let mut view: Box<dyn UiView> = Box::new(FunctionDiffUi {
+        symbol_name: symbol_name.clone(),
+        right_symbol_name: args.self_diff.clone(),
+        ..Default::default()
+    });
+    state.session.rebuild();
 
     crossterm_panic_handler();
     enable_raw_mode()?;
@@ -379,20 +485,17 @@ fn run_interactive(
                     EventControlFlow::Break => break 'outer,
                     EventControlFlow::Continue(r) => result = r,
                     EventControlFlow::Reload => {
-                        state.reload()?;
+                        state.session.rebuild();
                         result.redraw = true;
                     }
                 }
                 break;
             } else if state.waker.0.swap(false, Ordering::Relaxed) {
-                if state.modified.swap(false, Ordering::Relaxed) {
-                    state.reload()?;
-                }
                 result.redraw = true;
                 break;
             }
         }
-        if state.check_jobs()? {
+        if state.check_jobs() {
             result.redraw = true;
             view.reload(&state)?;
         }
@@ -1,2 +1,7 @@
+pub mod build;
+pub mod config;
 pub mod diff;
+pub mod order;
 pub mod report;
+pub mod search;
+pub mod serve;
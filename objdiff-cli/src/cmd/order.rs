@@ -0,0 +1,106 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{bail, Context, Result};
+use argp::FromArgs;
+use objdiff_core::{
+    config::try_project_config,
+    diff::{self, order::suggest_symbol_order},
+    obj,
+};
+use tracing::info;
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Suggest a linker symbol order using the target object's layout.
+///
+/// The output lists one symbol per line, as read by e.g. lld's --symbol-ordering-file.
+#[argp(subcommand, name = "order")]
+pub struct Args {
+    #[argp(option, short = '1')]
+    /// Target object file
+    target: Option<PathBuf>,
+    #[argp(option, short = '2')]
+    /// Base object file
+    base: Option<PathBuf>,
+    #[argp(option, short = 'p')]
+    /// Project directory
+    project: Option<PathBuf>,
+    #[argp(option, short = 'u')]
+    /// Unit name within project
+    unit: Option<String>,
+    #[argp(option, short = 'o')]
+    /// Output file (default: stdout)
+    output: Option<PathBuf>,
+    #[argp(switch, short = 'f')]
+    /// Write the order even if the base object's order already matches
+    force: bool,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let (target_path, base_path, symbol_matching) = match (&args.target, &args.base, &args.unit) {
+        (Some(t), b, None) => (t.clone(), b.clone(), None),
+        (None, None, Some(u)) => {
+            let project_dir = match &args.project {
+                Some(p) => p.clone(),
+                None => std::env::current_dir().context("Failed to get the current directory")?,
+            };
+            let Some((result, info)) = try_project_config(&project_dir) else {
+                bail!("Project config not found in {}", project_dir.display())
+            };
+            let config = result
+                .with_context(|| format!("Reading project config {}", info.path.display()))?;
+            let Some(unit) = config.units().iter().find(|o| o.name.as_deref() == Some(u)) else {
+                bail!("Unit not found: {u}")
+            };
+            let mut unit = unit.clone();
+            unit.resolve_paths(
+                &project_dir,
+                config.target_dir.as_deref(),
+                config.base_dir.as_deref(),
+            );
+            let Some(target_path) = unit.target_path.clone() else {
+                bail!("Unit {u} has no target path")
+            };
+            (target_path, unit.base_path.clone(), unit.symbol_matching())
+        }
+        _ => bail!("Either target and base or project and unit must be specified"),
+    };
+
+    let config = diff::DiffObjConfig {
+        relax_reloc_diffs: true,
+        symbol_matching: symbol_matching.unwrap_or_default(),
+        ..Default::default()
+    };
+    let target = obj::read::read(&target_path, &config)
+        .with_context(|| format!("Failed to open {}", target_path.display()))?;
+    let base = base_path
+        .as_ref()
+        .map(|p| {
+            obj::read::read(p, &config).with_context(|| format!("Failed to open {}", p.display()))
+        })
+        .transpose()?;
+    let result = diff::diff_objs(&config, Some(&target), base.as_ref(), None)?;
+    let target_diff = result.left.as_ref().context("Missing target diff")?;
+    let suggestion = suggest_symbol_order(&target, target_diff, base.as_ref());
+
+    for section in &suggestion.sections {
+        if section.reordered && section.all_functions_match {
+            info!("{}: all functions match, but symbols are out of order", section.name);
+        } else if section.reordered {
+            info!("{}: symbols are out of order", section.name);
+        }
+    }
+    if !args.force && base.is_some() && !suggestion.sections.iter().any(|s| s.reordered) {
+        info!("Base symbol order already matches the target");
+        return Ok(());
+    }
+
+    let out = suggestion.to_order_file();
+    match &args.output {
+        Some(path) if path.as_os_str() != "-" => {
+            fs::write(path, out).with_context(|| format!("Failed to write {}", path.display()))?;
+            info!("Wrote {}", path.display());
+        }
+        _ => print!("{out}"),
+    }
+    Ok(())
+}
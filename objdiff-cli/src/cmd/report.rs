@@ -1,28 +1,36 @@
 use std::{
-    collections::HashSet,
+    collections::{BTreeMap, HashSet},
+    fmt::Write,
     fs::File,
     io::Read,
     path::{Path, PathBuf},
     time::Instant,
 };
 
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, bail, ensure, Context, Result};
 use argp::FromArgs;
 use objdiff_core::{
-    bindings::report::{
-        ChangeItem, ChangeItemInfo, ChangeUnit, Changes, ChangesInput, Measures, Report,
-        ReportCategory, ReportItem, ReportItemMetadata, ReportUnit, ReportUnitMetadata,
-        REPORT_VERSION,
+    bindings::{
+        report::{
+            Changes, ChangesInput, Measures, Report, ReportCategory, ReportUnit,
+            ReportUnitMetadata, REPORT_VERSION,
+        },
+        report_summary::SummaryFormat,
     },
-    config::ProjectObject,
-    diff, obj,
-    obj::{ObjSectionKind, ObjSymbolFlags},
+    config::{
+        match_history::{unix_now, MatchHistory},
+        ProjectConfig, ProjectObject,
+    },
+    diff,
+    diff::properties::{ConfigProperty, ConfigPropertyValue},
+    obj,
+    obj::symbol_import::import_symbols,
 };
 use prost::Message;
 use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
-use tracing::{info, warn};
+use tracing::{debug_span, info, warn};
 
-use crate::util::output::{write_output, OutputFormat};
+use crate::util::output::{write_output, write_text, OutputFormat};
 
 #[derive(FromArgs, PartialEq, Debug)]
 /// Generate a progress report for a project.
@@ -37,6 +45,8 @@ pub struct Args {
 pub enum SubCommand {
     Generate(GenerateArgs),
     Changes(ChangesArgs),
+    Lookup(LookupArgs),
+    Sweep(SweepArgs),
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -53,8 +63,23 @@ pub struct GenerateArgs {
     /// Deduplicate global and weak symbols (runs single-threaded)
     deduplicate: bool,
     #[argp(option, short = 'f')]
-    /// Output format (json, json-pretty, proto) (default: json)
+    /// Output format (json, json-pretty, proto, markdown, html) (default: json)
     format: Option<String>,
+    #[argp(option)]
+    /// Project version to report on (default: first configured version)
+    project_version: Option<String>,
+    #[argp(switch)]
+    /// Generate a report for every project version (output file names are suffixed with the version)
+    all_versions: bool,
+    #[argp(switch)]
+    /// Omit fields that change when unrelated code moves (e.g. virtual addresses)
+    omit_volatile: bool,
+    #[argp(switch)]
+    /// List functions duplicated across units (likely inlined or copy-pasted code)
+    clones: bool,
+    #[argp(switch)]
+    /// Record newly matched functions in .objdiff/match_history.json and report recent matches
+    match_history: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -75,33 +100,324 @@ pub struct ChangesArgs {
     format: Option<String>,
 }
 
+#[derive(FromArgs, PartialEq, Debug)]
+/// Find the units containing a function, with its match percent.
+#[argp(subcommand, name = "lookup")]
+pub struct LookupArgs {
+    #[argp(positional)]
+    /// Function name or demangled name (falls back to a substring match)
+    name: String,
+    #[argp(option, short = 'r')]
+    /// Report file to search (default: the project's latest report.json, if any)
+    report: Option<PathBuf>,
+    #[argp(switch)]
+    /// Generate a fresh report instead of reading the project's latest report.json
+    generate: bool,
+    #[argp(option, short = 'p')]
+    /// Project directory
+    project: Option<PathBuf>,
+    #[argp(option)]
+    /// Project version to search (default: first configured version)
+    project_version: Option<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Compare overall progress across combinations of diff options.
+#[argp(subcommand, name = "sweep")]
+pub struct SweepArgs {
+    #[argp(option, short = 'p')]
+    /// Project directory
+    project: Option<PathBuf>,
+    #[argp(option)]
+    /// Project version to report on (default: first configured version)
+    project_version: Option<String>,
+    #[argp(option)]
+    /// Diff option and the values to try, as key=value1,value2; may be repeated
+    /// (see `objdiff-cli diff -c help`)
+    option: Vec<String>,
+    #[argp(option, short = 'o')]
+    /// Output file
+    output: Option<PathBuf>,
+    #[argp(option, short = 'f')]
+    /// Output format (markdown, json, json-pretty) (default: markdown)
+    format: Option<String>,
+}
+
 pub fn run(args: Args) -> Result<()> {
     match args.command {
         SubCommand::Generate(args) => generate(args),
         SubCommand::Changes(args) => changes(args),
+        SubCommand::Lookup(args) => lookup(args),
+        SubCommand::Sweep(args) => sweep(args),
     }
 }
 
 fn generate(args: GenerateArgs) -> Result<()> {
     let output_format = OutputFormat::from_option(args.format.as_deref())?;
     let project_dir = args.project.as_deref().unwrap_or_else(|| Path::new("."));
-    info!("Loading project {}", project_dir.display());
+    let project = load_project(project_dir)?;
+    if args.all_versions {
+        ensure!(args.project_version.is_none(), "--project-version conflicts with --all-versions");
+        ensure!(!project.versions().is_empty(), "Project does not define any versions");
+        let Some(output) = args.output.as_deref().filter(|p| p != &Path::new("-")) else {
+            bail!("--all-versions requires an output file");
+        };
+        for version in project.versions() {
+            info!("Generating report for version {}", version);
+            let mut versioned = project.for_version(Some(version))?;
+            let report = generate_report(
+                &mut versioned,
+                project_dir,
+                args.deduplicate,
+                args.omit_volatile,
+                args.clones,
+                args.match_history,
+            )?;
+            write_report(&report, Some(&versioned_output_path(output, version)), output_format)?;
+        }
+        return Ok(());
+    }
+    let mut project = project.for_version(args.project_version.as_deref())?;
+    let report = generate_report(
+        &mut project,
+        project_dir,
+        args.deduplicate,
+        args.omit_volatile,
+        args.clones,
+        args.match_history,
+    )?;
+    write_report(&report, args.output.as_deref(), output_format)
+}
 
-    let mut project = match objdiff_core::config::try_project_config(project_dir) {
-        Some((Ok(config), _)) => config,
+/// Writes a report in a serialized format, or as a human-readable summary.
+fn write_report(report: &Report, output: Option<&Path>, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Markdown => write_text(&report.summary(SummaryFormat::Markdown), output),
+        OutputFormat::Html => write_text(&report.summary(SummaryFormat::Html), output),
+        _ => write_output(report, output, format),
+    }
+}
+
+fn load_project(project_dir: &Path) -> Result<ProjectConfig> {
+    info!("Loading project {}", project_dir.display());
+    match objdiff_core::config::try_project_config(project_dir) {
+        Some((Ok(config), _)) => Ok(config),
         Some((Err(err), _)) => bail!("Failed to load project configuration: {}", err),
         None => bail!("No project configuration found"),
+    }
+}
+
+fn lookup(args: LookupArgs) -> Result<()> {
+    let report = if let Some(path) = &args.report {
+        ensure!(args.project_version.is_none(), "--project-version conflicts with --report");
+        ensure!(!args.generate, "--generate conflicts with --report");
+        read_report(path)?
+    } else {
+        let project_dir = args.project.as_deref().unwrap_or_else(|| Path::new("."));
+        let mut project =
+            load_project(project_dir)?.for_version(args.project_version.as_deref())?;
+        match latest_report_path(&project, project_dir).filter(|_| !args.generate) {
+            Some(path) => {
+                info!("Using report {}", path.display());
+                read_report(&path)?
+            }
+            // Match history isn't recorded, so this has no side effects
+            None => generate_report(&mut project, project_dir, false, false, false, false)?,
+        }
     };
+    let results = report.lookup_symbol(&args.name);
+    if results.is_empty() {
+        bail!("No function matching {} found", args.name);
+    }
+    for result in results {
+        let name = result.demangled_name().unwrap_or(&result.function.name);
+        println!("{}: {} ({:.2}%)", result.unit.name, name, result.function.fuzzy_match_percent);
+    }
+    Ok(())
+}
+
+/// The most recently written `report.json` in the project's build directory: next to or in a
+/// parent of the target and base object directories (e.g. `build/GALE01/report.json`).
+fn latest_report_path(project: &ProjectConfig, project_dir: &Path) -> Option<PathBuf> {
+    let mut dirs = Vec::new();
+    for dir in [&project.target_dir, &project.base_dir].into_iter().flatten() {
+        let dir = project_dir.join(dir);
+        dirs.extend(dir.ancestors().take(3).map(Path::to_path_buf));
+    }
+    dirs.into_iter()
+        .map(|dir| dir.join("report.json"))
+        .filter_map(|path| {
+            let modified = path.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .max()
+        .map(|(_, path)| path)
+}
+
+/// Option keys may be given as in `objdiff-cli diff -c` (`relax_reloc_diffs`) or in camelCase
+/// (`relaxRelocDiffs`).
+fn sweep_property(key: &str) -> Result<ConfigProperty> {
+    let key = key.trim();
+    let mut id = String::with_capacity(key.len() + 4);
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            id.push('_');
+            id.push(c.to_ascii_lowercase());
+        } else {
+            id.push(c);
+        }
+    }
+    ConfigProperty::from_id(&id).ok_or_else(|| anyhow!("Unknown option {key}"))
+}
+
+/// Parses a `key=value1,value2` sweep option.
+fn parse_sweep_option(option: &str) -> Result<(ConfigProperty, Vec<ConfigPropertyValue>)> {
+    let (key, values) = option
+        .split_once('=')
+        .ok_or_else(|| anyhow!("Invalid option {option}, expected key=value1,value2"))?;
+    let property = sweep_property(key)?;
+    let values = values
+        .split(',')
+        .map(|value| property.parse_value(value.trim()))
+        .collect::<Result<Vec<_>>>()?;
+    Ok((property, values))
+}
+
+#[derive(serde::Serialize)]
+struct SweepResult {
+    options: BTreeMap<&'static str, String>,
+    measures: Measures,
+}
+
+fn sweep(args: SweepArgs) -> Result<()> {
+    let format = args.format.as_deref().unwrap_or("markdown").to_ascii_lowercase();
+    ensure!(
+        matches!(format.as_str(), "md" | "markdown" | "json" | "json-pretty" | "json_pretty"),
+        "Invalid output format: {format}"
+    );
+    ensure!(!args.option.is_empty(), "At least one --option is required");
+    let mut options = Vec::<(ConfigProperty, Vec<ConfigPropertyValue>)>::new();
+    for option in &args.option {
+        let (property, values) = parse_sweep_option(option)?;
+        ensure!(
+            !options.iter().any(|(p, _)| *p == property),
+            "Option {} is given more than once",
+            property.id()
+        );
+        options.push((property, values));
+    }
+    let mut combinations = vec![vec![]];
+    for (_, values) in &options {
+        combinations = combinations
+            .into_iter()
+            .flat_map(|combination: Vec<ConfigPropertyValue>| {
+                values.iter().map(move |&value| {
+                    let mut combination = combination.clone();
+                    combination.push(value);
+                    combination
+                })
+            })
+            .collect();
+    }
+
+    let project_dir = args.project.as_deref().unwrap_or_else(|| Path::new("."));
+    let project = load_project(project_dir)?.for_version(args.project_version.as_deref())?;
     info!(
-        "Generating report for {} units (using {} threads)",
-        project.units().len(),
-        if args.deduplicate { 1 } else { rayon::current_num_threads() }
+        "Generating reports for {} combinations of options ({} units each)",
+        combinations.len(),
+        project.units().len()
     );
+    let mut results = vec![];
+    for combination in &combinations {
+        let mut diff_config = report_diff_config(&project);
+        let mut result_options = BTreeMap::new();
+        for ((property, _), &value) in options.iter().zip(combination) {
+            diff_config.set_property_value(*property, value)?;
+            result_options.insert(property.id(), property.display_value(value));
+        }
+        info!(
+            "Generating report with {}",
+            result_options.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>().join(", ")
+        );
+        // Unit paths are resolved in place, so every report starts from a fresh copy
+        let mut project = project.clone();
+        let units = report_units(&mut project, project_dir, false, &diff_config)?;
+        let measures = units.iter().flat_map(|u| u.measures.into_iter()).collect();
+        results.push(SweepResult { options: result_options, measures });
+    }
 
-    let start = Instant::now();
+    let text = match format.as_str() {
+        "json" => serde_json::to_string(&results)?,
+        "json-pretty" | "json_pretty" => serde_json::to_string_pretty(&results)?,
+        _ => sweep_table(&options, &results),
+    };
+    write_text(&text, args.output.as_deref())
+}
+
+/// A markdown table with a row per combination of options. The best fuzzy match is emphasized.
+fn sweep_table(
+    options: &[(ConfigProperty, Vec<ConfigPropertyValue>)],
+    results: &[SweepResult],
+) -> String {
+    let best =
+        results.iter().map(|r| r.measures.fuzzy_match_percent).fold(f32::NEG_INFINITY, f32::max);
+    let mut out = String::new();
+    let mut header = options.iter().map(|(p, _)| p.id()).collect::<Vec<_>>();
+    header.extend(["Fuzzy match", "Matched code", "Matched data", "Functions"]);
+    let _ = writeln!(out, "| {} |", header.join(" | "));
+    let _ = writeln!(out, "|{}{}", ":---|".repeat(options.len()), "---:|".repeat(4));
+    for result in results {
+        let measures = &result.measures;
+        let mut cells = options
+            .iter()
+            .map(|(p, _)| result.options.get(p.id()).cloned().unwrap_or_default())
+            .collect::<Vec<_>>();
+        let fuzzy = format!("{:.2}%", measures.fuzzy_match_percent);
+        cells.push(if measures.fuzzy_match_percent == best {
+            format!("**{fuzzy}**")
+        } else {
+            fuzzy
+        });
+        cells.push(format!("{:.2}%", measures.matched_code_percent));
+        cells.push(format!("{:.2}%", measures.matched_data_percent));
+        cells.push(format!("{} / {}", measures.matched_functions, measures.total_functions));
+        let _ = writeln!(out, "| {} |", cells.join(" | "));
+    }
+    out
+}
+
+/// Inserts the version name before the file extension, e.g. `report.json` -> `report.GALE01.json`.
+fn versioned_output_path(path: &Path, version: &str) -> PathBuf {
+    let mut file_name = path.file_stem().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(version);
+    if let Some(ext) = path.extension() {
+        file_name.push(".");
+        file_name.push(ext);
+    }
+    path.with_file_name(file_name)
+}
+
+/// Diff options used for reports, unless overridden by a sweep.
+pub(crate) fn report_diff_config(project: &ProjectConfig) -> diff::DiffObjConfig {
+    diff::DiffObjConfig {
+        relax_reloc_diffs: true,
+        reloc_renames: project.reloc_renames.clone().unwrap_or_default(),
+        global_symbols_only: project.global_symbols_only.unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+/// Diffs every unit of the project, sorted by name.
+fn report_units(
+    project: &mut ProjectConfig,
+    project_dir: &Path,
+    deduplicate: bool,
+    diff_config: &diff::DiffObjConfig,
+) -> Result<Vec<ReportUnit>> {
     let mut units = vec![];
     let mut existing_functions: HashSet<String> = HashSet::new();
-    if args.deduplicate {
+    if deduplicate {
         // If deduplicating, we need to run single-threaded
         for object in project.units.as_deref_mut().unwrap_or_default() {
             if let Some(unit) = report_object(
@@ -109,6 +425,7 @@ fn generate(args: GenerateArgs) -> Result<()> {
                 project_dir,
                 project.target_dir.as_deref(),
                 project.base_dir.as_deref(),
+                diff_config,
                 Some(&mut existing_functions),
             )? {
                 units.push(unit);
@@ -126,12 +443,52 @@ fn generate(args: GenerateArgs) -> Result<()> {
                     project_dir,
                     project.target_dir.as_deref(),
                     project.base_dir.as_deref(),
+                    diff_config,
                     None,
                 )
             })
             .collect::<Result<Vec<Option<ReportUnit>>>>()?;
         units = vec.into_iter().flatten().collect();
     }
+    // Sort before summing measures, so that totals don't depend on the unit order
+    units.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(units)
+}
+
+fn generate_report(
+    project: &mut ProjectConfig,
+    project_dir: &Path,
+    deduplicate: bool,
+    omit_volatile: bool,
+    clones: bool,
+    match_history: bool,
+) -> Result<Report> {
+    info!(
+        "Generating report for {} units (using {} threads)",
+        project.units().len(),
+        if deduplicate { 1 } else { rayon::current_num_threads() }
+    );
+
+    let start = Instant::now();
+    let diff_config = report_diff_config(project);
+    let mut units = report_units(project, project_dir, deduplicate, &diff_config)?;
+    let now = unix_now();
+    let history = match_history.then(|| {
+        let mut history = MatchHistory::load(project_dir).unwrap_or_else(|e| {
+            warn!("Failed to load match history: {e:#}");
+            MatchHistory::default()
+        });
+        let mut history_changed = false;
+        for unit in &mut units {
+            history_changed |= unit.apply_match_history(&mut history, now);
+        }
+        if history_changed {
+            if let Err(e) = history.save(project_dir) {
+                warn!("Failed to save match history: {e:#}");
+            }
+        }
+        history
+    });
     let measures = units.iter().flat_map(|u| u.measures.into_iter()).collect();
     let mut categories = Vec::new();
     for category in project.progress_categories() {
@@ -141,22 +498,58 @@ fn generate(args: GenerateArgs) -> Result<()> {
             measures: Some(Default::default()),
         });
     }
-    let mut report =
-        Report { measures: Some(measures), units, version: REPORT_VERSION, categories };
+    let mut report = Report {
+        measures: Some(measures),
+        units,
+        version: REPORT_VERSION,
+        categories,
+        clones: vec![],
+    };
     report.calculate_progress_categories();
+    report.sort();
+    if clones {
+        report.clones = report.find_clones();
+        info!("{} group(s) of functions duplicated across units", report.clones.len());
+    }
+    if omit_volatile {
+        report.strip_volatile();
+    }
     let duration = start.elapsed();
     info!("Report generated in {}.{:03}s", duration.as_secs(), duration.subsec_millis());
-    write_output(&report, args.output.as_deref(), output_format)?;
-    Ok(())
+    let stats = history.map(|history| history.stats(now)).unwrap_or_default();
+    if stats.total_functions > 0 {
+        info!(
+            "{} function(s) ({} bytes) matched in the last week, {:.0} bytes per day on average",
+            stats.week_functions, stats.week_code, stats.average_code_per_day
+        );
+    }
+    if let Some(measures) = &report.measures {
+        if measures.size_inferred_functions > 0 || measures.ignored_symbols > 0 {
+            info!(
+                "{} function(s) with inferred sizes, {} symbol(s) ignored",
+                measures.size_inferred_functions, measures.ignored_symbols
+            );
+        }
+        if measures.reviewed_matching_functions > 0 || measures.verified_equivalent_functions > 0 {
+            info!(
+                "{} function(s) reviewed as matching, {} verified as equivalent",
+                measures.reviewed_matching_functions, measures.verified_equivalent_functions
+            );
+        }
+    }
+    Ok(report)
 }
 
-fn report_object(
+pub(crate) fn report_object(
     object: &mut ProjectObject,
     project_dir: &Path,
     target_dir: Option<&Path>,
     base_dir: Option<&Path>,
-    mut existing_functions: Option<&mut HashSet<String>>,
+    diff_config: &diff::DiffObjConfig,
+    existing_functions: Option<&mut HashSet<String>>,
 ) -> Result<Option<ReportUnit>> {
+    // Gives the load and diff timings logged at debug level the unit's name
+    let _span = debug_span!("unit", name = object.name()).entered();
     object.resolve_paths(project_dir, target_dir, base_dir);
     match (&object.target_path, &object.base_path) {
         (None, Some(_)) if !object.complete().unwrap_or(false) => {
@@ -169,14 +562,21 @@ fn report_object(
         }
         _ => {}
     }
-    let config = diff::DiffObjConfig { relax_reloc_diffs: true, ..Default::default() };
-    let target = object
+    let config = diff::DiffObjConfig {
+        symbol_matching: object.symbol_matching().unwrap_or_default(),
+        compiler: object.scratch.as_ref().and_then(|s| s.compiler.clone()),
+        ..diff_config.clone()
+    };
+    let mut target = object
         .target_path
         .as_ref()
         .map(|p| {
             obj::read::read(p, &config).with_context(|| format!("Failed to open {}", p.display()))
         })
         .transpose()?;
+    if let (Some(target), Some(import)) = (&mut target, &object.symbol_import) {
+        import_symbols(target, import)?;
+    }
     let base = object
         .base_path
         .as_ref()
@@ -188,11 +588,6 @@ fn report_object(
 
     let metadata = ReportUnitMetadata {
         complete: object.complete(),
-        module_name: target
-            .as_ref()
-            .and_then(|o| o.split_meta.as_ref())
-            .and_then(|m| m.module_name.clone()),
-        module_id: target.as_ref().and_then(|o| o.split_meta.as_ref()).and_then(|m| m.module_id),
         source_path: object.metadata.as_ref().and_then(|m| m.source_path.clone()),
         progress_categories: object
             .metadata
@@ -200,99 +595,20 @@ fn report_object(
             .and_then(|m| m.progress_categories.clone())
             .unwrap_or_default(),
         auto_generated: object.metadata.as_ref().and_then(|m| m.auto_generated),
+        ..Default::default()
     };
-    let mut measures = Measures { total_units: 1, ..Default::default() };
-    let mut sections = vec![];
-    let mut functions = vec![];
-
-    let obj = target.as_ref().or(base.as_ref()).unwrap();
-    let obj_diff = result.left.as_ref().or(result.right.as_ref()).unwrap();
-    for (section, section_diff) in obj.sections.iter().zip(&obj_diff.sections) {
-        let section_match_percent = section_diff.match_percent.unwrap_or_else(|| {
-            // Support cases where we don't have a target object,
-            // assume complete means 100% match
-            if object.complete().unwrap_or(false) {
-                100.0
-            } else {
-                0.0
-            }
-        });
-        sections.push(ReportItem {
-            name: section.name.clone(),
-            fuzzy_match_percent: section_match_percent,
-            size: section.size,
-            metadata: Some(ReportItemMetadata {
-                demangled_name: None,
-                virtual_address: section.virtual_address,
-            }),
-        });
-
-        match section.kind {
-            ObjSectionKind::Data | ObjSectionKind::Bss => {
-                measures.total_data += section.size;
-                if section_match_percent == 100.0 {
-                    measures.matched_data += section.size;
-                }
-                continue;
-            }
-            ObjSectionKind::Code => (),
-        }
-
-        for (symbol, symbol_diff) in section.symbols.iter().zip(&section_diff.symbols) {
-            if symbol.size == 0 || symbol.flags.0.contains(ObjSymbolFlags::Hidden) {
-                continue;
-            }
-            if let Some(existing_functions) = &mut existing_functions {
-                if (symbol.flags.0.contains(ObjSymbolFlags::Global)
-                    || symbol.flags.0.contains(ObjSymbolFlags::Weak))
-                    && !existing_functions.insert(symbol.name.clone())
-                {
-                    continue;
-                }
-            }
-            let match_percent = symbol_diff.match_percent.unwrap_or_else(|| {
-                // Support cases where we don't have a target object,
-                // assume complete means 100% match
-                if object.complete().unwrap_or(false) {
-                    100.0
-                } else {
-                    0.0
-                }
-            });
-            measures.fuzzy_match_percent += match_percent * symbol.size as f32;
-            measures.total_code += symbol.size;
-            if match_percent == 100.0 {
-                measures.matched_code += symbol.size;
-            }
-            functions.push(ReportItem {
-                name: symbol.name.clone(),
-                size: symbol.size,
-                fuzzy_match_percent: match_percent,
-                metadata: Some(ReportItemMetadata {
-                    demangled_name: symbol.demangled_name.clone(),
-                    virtual_address: symbol.virtual_address,
-                }),
-            });
-            if match_percent == 100.0 {
-                measures.matched_functions += 1;
-            }
-            measures.total_functions += 1;
-        }
-    }
-    if metadata.complete.unwrap_or(false) {
-        measures.complete_code = measures.total_code;
-        measures.complete_data = measures.total_data;
-        measures.complete_units = 1;
+    let mut unit = ReportUnit::from_diff(
+        object.name().to_string(),
+        target.as_ref(),
+        base.as_ref(),
+        &result,
+        metadata,
+        existing_functions,
+    );
+    if let Some(review_states) = object.review_states() {
+        unit.apply_review_states(review_states);
     }
-    measures.calc_fuzzy_match_percent();
-    measures.calc_matched_percent();
-    Ok(Some(ReportUnit {
-        name: object.name().to_string(),
-        measures: Some(measures),
-        sections,
-        functions,
-        metadata: Some(metadata),
-    }))
+    Ok(Some(unit))
 }
 
 fn changes(args: ChangesArgs) -> Result<()> {
@@ -308,110 +624,11 @@ fn changes(args: ChangesArgs) -> Result<()> {
         let current = read_report(&args.current)?;
         (previous, current)
     };
-    let mut changes = Changes { from: previous.measures, to: current.measures, units: vec![] };
-    for prev_unit in &previous.units {
-        let curr_unit = current.units.iter().find(|u| u.name == prev_unit.name);
-        let sections = process_items(prev_unit, curr_unit, |u| &u.sections);
-        let functions = process_items(prev_unit, curr_unit, |u| &u.functions);
-
-        let prev_measures = prev_unit.measures;
-        let curr_measures = curr_unit.and_then(|u| u.measures);
-        if !functions.is_empty() || prev_measures != curr_measures {
-            changes.units.push(ChangeUnit {
-                name: prev_unit.name.clone(),
-                from: prev_measures,
-                to: curr_measures,
-                sections,
-                functions,
-                metadata: curr_unit
-                    .as_ref()
-                    .and_then(|u| u.metadata.clone())
-                    .or_else(|| prev_unit.metadata.clone()),
-            });
-        }
-    }
-    for curr_unit in &current.units {
-        if !previous.units.iter().any(|u| u.name == curr_unit.name) {
-            changes.units.push(ChangeUnit {
-                name: curr_unit.name.clone(),
-                from: None,
-                to: curr_unit.measures,
-                sections: process_new_items(&curr_unit.sections),
-                functions: process_new_items(&curr_unit.functions),
-                metadata: curr_unit.metadata.clone(),
-            });
-        }
-    }
+    let changes = Changes::between(&previous, &current);
     write_output(&changes, args.output.as_deref(), output_format)?;
     Ok(())
 }
 
-fn process_items<F: Fn(&ReportUnit) -> &Vec<ReportItem>>(
-    prev_unit: &ReportUnit,
-    curr_unit: Option<&ReportUnit>,
-    getter: F,
-) -> Vec<ChangeItem> {
-    let prev_items = getter(prev_unit);
-    let mut items = vec![];
-    if let Some(curr_unit) = curr_unit {
-        let curr_items = getter(curr_unit);
-        for prev_func in prev_items {
-            let prev_func_info = ChangeItemInfo::from(prev_func);
-            let curr_func = curr_items.iter().find(|f| f.name == prev_func.name);
-            let curr_func_info = curr_func.map(ChangeItemInfo::from);
-            if let Some(curr_func_info) = curr_func_info {
-                if prev_func_info != curr_func_info {
-                    items.push(ChangeItem {
-                        name: prev_func.name.clone(),
-                        from: Some(prev_func_info),
-                        to: Some(curr_func_info),
-                        metadata: curr_func.as_ref().unwrap().metadata.clone(),
-                    });
-                }
-            } else {
-                items.push(ChangeItem {
-                    name: prev_func.name.clone(),
-                    from: Some(prev_func_info),
-                    to: None,
-                    metadata: prev_func.metadata.clone(),
-                });
-            }
-        }
-        for curr_func in curr_items {
-            if !prev_items.iter().any(|f| f.name == curr_func.name) {
-                items.push(ChangeItem {
-                    name: curr_func.name.clone(),
-                    from: None,
-                    to: Some(ChangeItemInfo::from(curr_func)),
-                    metadata: curr_func.metadata.clone(),
-                });
-            }
-        }
-    } else {
-        for prev_func in prev_items {
-            items.push(ChangeItem {
-                name: prev_func.name.clone(),
-                from: Some(ChangeItemInfo::from(prev_func)),
-                to: None,
-                metadata: prev_func.metadata.clone(),
-            });
-        }
-    }
-    items
-}
-
-fn process_new_items(items: &[ReportItem]) -> Vec<ChangeItem> {
-    items
-        .iter()
-        .map(|item| ChangeItem {
-            name: item.name.clone(),
-            from: None,
-            to: Some(ChangeItemInfo::from(item)),
-            metadata: item.metadata.clone(),
-        })
-        .collect()
-}
-
 fn read_report(path: &Path) -> Result<Report> {
     if path == Path::new("-") {
         let mut data = vec![];
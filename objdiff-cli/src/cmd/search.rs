@@ -0,0 +1,90 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use argp::FromArgs;
+use objdiff_core::{
+    config::try_project_config,
+    diff,
+    obj::{
+        self,
+        search::{search_bytes, BytePattern},
+    },
+};
+use tracing::{info, warn};
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Search objects for a byte pattern or immediate value.
+///
+/// Patterns are hex bytes with optional ?? wildcards (e.g. "3c 60 ?? ??"), or an immediate
+/// value prefixed with # (e.g. "#0x80001234"), encoded in each object's byte order.
+#[argp(subcommand, name = "search")]
+pub struct Args {
+    #[argp(positional)]
+    /// Byte pattern or immediate value
+    pattern: String,
+    #[argp(positional)]
+    /// Object files to search
+    files: Vec<PathBuf>,
+    #[argp(option, short = 'p')]
+    /// Search the target and base objects of all units in a project
+    project: Option<PathBuf>,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let mut files = args.files.clone();
+    if let Some(project_dir) = &args.project {
+        let Some((result, info)) = try_project_config(project_dir) else {
+            bail!("Project config not found in {}", project_dir.display())
+        };
+        let config =
+            result.with_context(|| format!("Reading project config {}", info.path.display()))?;
+        for unit in config.units() {
+            let mut unit = unit.clone();
+            unit.resolve_paths(
+                project_dir,
+                config.target_dir.as_deref(),
+                config.base_dir.as_deref(),
+            );
+            files.extend(unit.target_path.into_iter().chain(unit.base_path).filter(|p| p.exists()));
+        }
+    }
+    if files.is_empty() {
+        bail!("No object files specified");
+    }
+    let config = diff::DiffObjConfig::default();
+    let mut total = 0;
+    for path in &files {
+        let obj = match obj::read::read(path, &config) {
+            Ok(obj) => obj,
+            Err(e) => {
+                warn!("Failed to open {}: {e:#}", path.display());
+                continue;
+            }
+        };
+        let pattern = BytePattern::parse(&args.pattern, obj.endianness)?;
+        for hit in search_bytes(&obj, &pattern) {
+            print_hit(path, &obj, &hit);
+            total += 1;
+        }
+    }
+    info!("{total} hit(s) in {} object(s)", files.len());
+    Ok(())
+}
+
+fn print_hit(path: &Path, obj: &obj::ObjInfo, hit: &obj::search::SearchHit) {
+    let section = &obj.sections[hit.section_idx];
+    match hit.symbol {
+        Some(symbol_ref) => {
+            let (_, symbol) = obj.section_symbol(symbol_ref);
+            println!(
+                "{}: {}:{:x} {}+{:#x}",
+                path.display(),
+                section.name,
+                hit.address,
+                symbol.name,
+                hit.symbol_offset
+            );
+        }
+        None => println!("{}: {}:{:x}", path.display(), section.name, hit.address),
+    }
+}
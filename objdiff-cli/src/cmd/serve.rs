@@ -0,0 +1,510 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex,
+    },
+    task::{Wake, Waker},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use argp::FromArgs;
+use objdiff_core::{
+    bindings::{
+        diff::{DiffResult, FunctionDiff},
+        report::ReportUnit,
+    },
+    build::{
+        watcher::{create_watcher, Watcher, WatcherOptions},
+        BuildStatus,
+    },
+    config::{
+        build_globset, default_watch_patterns, try_project_config, ProjectConfig, ProjectObject,
+    },
+    diff::{DiffObjConfig, ObjDiff},
+    jobs::objdiff::{run_build_blocking, ObjDiffResult},
+    obj::ObjInfo,
+    session::DiffInputs,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+use crate::cmd::report::{report_diff_config, report_object};
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Run a JSON-RPC server for editor integrations.
+///
+/// Messages are newline-delimited JSON-RPC 2.0 objects, exchanged over stdio
+/// unless --listen is specified.
+#[argp(subcommand, name = "serve")]
+pub struct Args {
+    #[argp(option, short = 'p')]
+    /// Project directory
+    project: Option<PathBuf>,
+    #[argp(option, short = 'l')]
+    /// Listen for connections on a TCP address (e.g. 127.0.0.1:7100) instead of stdio
+    listen: Option<String>,
+}
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+pub fn run(args: Args) -> Result<()> {
+    let project_dir = match args.project {
+        Some(project) => project,
+        None => std::env::current_dir().context("Failed to get the current directory")?,
+    };
+    if let Some(addr) = &args.listen {
+        let listener =
+            TcpListener::bind(addr).with_context(|| format!("Failed to listen on {addr}"))?;
+        info!("Listening on {}", listener.local_addr()?);
+        // Clients are served one at a time
+        for stream in listener.incoming() {
+            // A failing client only ends its own connection
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    warn!("Failed to accept connection: {e}");
+                    continue;
+                }
+            };
+            let peer = stream.peer_addr().map_or_else(|_| "?".to_string(), |a| a.to_string());
+            info!("Client connected: {peer}");
+            let reader = match stream.try_clone() {
+                Ok(reader) => reader,
+                Err(e) => {
+                    warn!("Failed to set up connection with {peer}: {e}");
+                    continue;
+                }
+            };
+            match serve_client(&project_dir, reader, stream) {
+                Ok(true) => break,
+                Ok(false) => info!("Client disconnected: {peer}"),
+                Err(e) => warn!("Client {peer} disconnected with an error: {e:#}"),
+            }
+        }
+        Ok(())
+    } else {
+        serve_client(&project_dir, std::io::stdin(), std::io::stdout()).map(|_| ())
+    }
+}
+
+enum Event {
+    Message(String),
+    Closed,
+    FilesChanged,
+}
+
+/// Forwards wake-ups from the file watcher to the server loop.
+struct EventWaker(Mutex<Sender<Event>>);
+
+impl Wake for EventWaker {
+    fn wake(self: Arc<Self>) { self.wake_by_ref(); }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        if let Ok(tx) = self.0.lock() {
+            tx.send(Event::FilesChanged).ok();
+        }
+    }
+}
+
+struct Server<W: Write> {
+    writer: W,
+    tx: Sender<Event>,
+    project_dir: PathBuf,
+    project_config: Option<ProjectConfig>,
+    watcher: Option<Watcher>,
+    modified: Arc<AtomicBool>,
+    subscribed: Option<Vec<String>>,
+}
+
+/// Serves a single client until it disconnects. Returns whether a shutdown was requested.
+fn serve_client<R, W>(project_dir: &Path, reader: R, writer: W) -> Result<bool>
+where
+    R: Read + Send + 'static,
+    W: Write,
+{
+    let (tx, rx) = channel();
+    let reader_tx = tx.clone();
+    std::thread::spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            let Ok(line) = line else { break };
+            if reader_tx.send(Event::Message(line)).is_err() {
+                return;
+            }
+        }
+        reader_tx.send(Event::Closed).ok();
+    });
+    let mut server = Server {
+        writer,
+        tx,
+        project_dir: project_dir.to_path_buf(),
+        project_config: None,
+        watcher: None,
+        modified: Default::default(),
+        subscribed: None,
+    };
+    if let Err(e) = server.load_project(None, None) {
+        warn!("Failed to load project: {e:#}");
+    }
+    server.run(rx)
+}
+
+impl<W: Write> Server<W> {
+    fn run(&mut self, rx: Receiver<Event>) -> Result<bool> {
+        while let Ok(event) = rx.recv() {
+            match event {
+                Event::Message(line) => {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    if self.handle_message(&line)? {
+                        return Ok(true);
+                    }
+                }
+                Event::Closed => break,
+                Event::FilesChanged => {
+                    if self.modified.swap(false, Ordering::Relaxed) {
+                        self.files_changed()?;
+                    }
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Handles a single JSON-RPC message. Returns whether the server should shut down.
+    fn handle_message(&mut self, line: &str) -> Result<bool> {
+        let message: Value = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(e) => {
+                self.send_error(Value::Null, PARSE_ERROR, format!("{e}"))?;
+                return Ok(false);
+            }
+        };
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            self.send_error(id.unwrap_or(Value::Null), INVALID_REQUEST, "Missing method")?;
+            return Ok(false);
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let shutdown = method == "shutdown";
+        let result = match method {
+            "shutdown" => Ok(Value::Null),
+            "loadProject" => self.handle_load_project(params),
+            "getUnits" => self.handle_get_units(),
+            "getUnitReport" => self.handle_get_unit_report(params),
+            "getUnitDiff" => self.handle_get_unit_diff(params),
+            "getFunctionDiff" => self.handle_get_function_diff(params),
+            "subscribe" => self.handle_subscribe(params),
+            "unsubscribe" => {
+                self.subscribed = None;
+                Ok(Value::Null)
+            }
+            _ => {
+                if let Some(id) = id {
+                    self.send_error(id, METHOD_NOT_FOUND, format!("Unknown method: {method}"))?;
+                }
+                return Ok(false);
+            }
+        };
+        // Notifications (no ID) don't receive a response
+        if let Some(id) = id {
+            match result {
+                Ok(result) => self.send(json!({ "jsonrpc": "2.0", "id": id, "result": result }))?,
+                Err(e) => {
+                    let code =
+                        if e.is::<InvalidParams>() { INVALID_PARAMS } else { INTERNAL_ERROR };
+                    self.send_error(id, code, format!("{e:#}"))?
+                }
+            }
+        }
+        Ok(shutdown)
+    }
+
+    fn send(&mut self, value: Value) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, &value)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn send_error(&mut self, id: Value, code: i64, message: impl Into<String>) -> Result<()> {
+        let message = message.into();
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        }))
+    }
+
+    fn notify(&mut self, method: &str, params: Value) -> Result<()> {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+    }
+
+    fn load_project(&mut self, project_dir: Option<PathBuf>, version: Option<&str>) -> Result<()> {
+        if let Some(project_dir) = project_dir {
+            self.project_dir = project_dir;
+        }
+        self.project_config = None;
+        self.watcher = None;
+        let Some((result, info)) = try_project_config(&self.project_dir) else {
+            bail!("Project config not found in {}", self.project_dir.display());
+        };
+        let config = result
+            .with_context(|| format!("Reading project config {}", info.path.display()))?
+            .for_version(version)?;
+        let watch_patterns = config.watch_patterns.clone().unwrap_or_else(default_watch_patterns);
+        let waker = Waker::from(Arc::new(EventWaker(Mutex::new(self.tx.clone()))));
+        self.watcher = Some(create_watcher(
+            self.modified.clone(),
+            &self.project_dir,
+            build_globset(&watch_patterns)?,
+            WatcherOptions::with_debounce_ms(config.watch_debounce_ms),
+            waker,
+        )?);
+        info!("Loaded project {} ({} units)", self.project_dir.display(), config.units().len());
+        self.project_config = Some(config);
+        Ok(())
+    }
+
+    fn project_config(&self) -> Result<&ProjectConfig> {
+        self.project_config.as_ref().ok_or_else(|| anyhow!("No project loaded"))
+    }
+
+    fn handle_load_project(&mut self, params: Value) -> Result<Value> {
+        #[derive(Deserialize, Default)]
+        #[serde(default)]
+        struct Params {
+            project_dir: Option<PathBuf>,
+            version: Option<String>,
+        }
+        let params: Params = parse_params(params)?;
+        self.load_project(params.project_dir, params.version.as_deref())?;
+        Ok(json!({
+            "project_dir": self.project_dir,
+            "units": self.project_config()?.units().len(),
+        }))
+    }
+
+    fn handle_get_units(&mut self) -> Result<Value> {
+        let config = self.project_config()?;
+        let units = config
+            .units()
+            .iter()
+            .map(|unit| {
+                let mut unit = unit.clone();
+                unit.resolve_paths(
+                    &self.project_dir,
+                    config.target_dir.as_deref(),
+                    config.base_dir.as_deref(),
+                );
+                json!({
+                    "name": unit.name(),
+                    "target_path": unit.target_path,
+                    "base_path": unit.base_path,
+                    "complete": unit.complete(),
+                    "source_path": unit.source_path(),
+                    "hidden": unit.hidden(),
+                })
+            })
+            .collect::<Vec<_>>();
+        Ok(Value::Array(units))
+    }
+
+    fn handle_get_unit_report(&mut self, params: Value) -> Result<Value> {
+        let params: UnitParams = parse_params(params)?;
+        let report = self.unit_report(&params.unit)?;
+        Ok(serde_json::to_value(report)?)
+    }
+
+    fn handle_get_unit_diff(&mut self, params: Value) -> Result<Value> {
+        let params: UnitParams = parse_params(params)?;
+        let result = self.build_unit(&params.unit, params.build)?;
+        let diff = DiffResult::new(
+            result.first_obj.as_ref().map(|(o, d)| (o, d)),
+            result.second_obj.as_ref().map(|(o, d)| (o, d)),
+        );
+        Ok(json!({
+            "target_status": build_status_json(&result.first_status),
+            "base_status": build_status_json(&result.second_status),
+            "diff": serde_json::to_value(diff)?,
+        }))
+    }
+
+    fn handle_get_function_diff(&mut self, params: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Params {
+            unit: String,
+            symbol: String,
+            #[serde(default)]
+            build: bool,
+        }
+        let params: Params = parse_params(params)?;
+        let result = self.build_unit(&params.unit, params.build)?;
+        let left = function_diff(result.first_obj.as_ref(), &params.symbol);
+        let right = function_diff(result.second_obj.as_ref(), &params.symbol);
+        if left.is_none() && right.is_none() {
+            bail!("Symbol {} not found in unit {}", params.symbol, params.unit);
+        }
+        Ok(json!({
+            "target_status": build_status_json(&result.first_status),
+            "base_status": build_status_json(&result.second_status),
+            "left": left.map(serde_json::to_value).transpose()?,
+            "right": right.map(serde_json::to_value).transpose()?,
+        }))
+    }
+
+    fn handle_subscribe(&mut self, params: Value) -> Result<Value> {
+        #[derive(Deserialize, Default)]
+        #[serde(default)]
+        struct Params {
+            units: Vec<String>,
+        }
+        let params: Params = parse_params(params)?;
+        for unit in &params.units {
+            self.find_unit(unit)?;
+        }
+        self.subscribed = Some(params.units);
+        Ok(Value::Null)
+    }
+
+    /// Notifies subscribers of file changes, rebuilding and reporting subscribed units.
+    fn files_changed(&mut self) -> Result<()> {
+        let Some(units) = self.subscribed.clone() else { return Ok(()) };
+        self.notify("filesChanged", Value::Null)?;
+        for unit in units {
+            let params = match self.build_unit(&unit, true) {
+                Ok(result) if result.first_status.success && result.second_status.success => {
+                    match self.unit_report(&unit) {
+                        Ok(report) => json!({
+                            "unit": unit,
+                            "success": true,
+                            "report": serde_json::to_value(report)?,
+                        }),
+                        Err(e) => {
+                            json!({ "unit": unit, "success": false, "error": format!("{e:#}") })
+                        }
+                    }
+                }
+                Ok(result) => json!({
+                    "unit": unit,
+                    "success": false,
+                    "target_status": build_status_json(&result.first_status),
+                    "base_status": build_status_json(&result.second_status),
+                }),
+                Err(e) => json!({ "unit": unit, "success": false, "error": format!("{e:#}") }),
+            };
+            self.notify("unitUpdated", params)?;
+        }
+        Ok(())
+    }
+
+    fn find_unit(&self, name: &str) -> Result<ProjectObject> {
+        let config = self.project_config()?;
+        let mut unit = config
+            .units()
+            .iter()
+            .find(|u| u.name() == name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unit {name} not found"))?;
+        unit.resolve_paths(
+            &self.project_dir,
+            config.target_dir.as_deref(),
+            config.base_dir.as_deref(),
+        );
+        Ok(unit)
+    }
+
+    fn unit_report(&self, name: &str) -> Result<ReportUnit> {
+        let config = self.project_config()?;
+        let mut unit = config
+            .units()
+            .iter()
+            .find(|u| u.name() == name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unit {name} not found"))?;
+        report_object(
+            &mut unit,
+            &self.project_dir,
+            config.target_dir.as_deref(),
+            config.base_dir.as_deref(),
+            &report_diff_config(config),
+            None,
+        )?
+        .ok_or_else(|| anyhow!("Unit {name} has no objects to report"))
+    }
+
+    /// Runs the build & diff job for a unit, waiting for it to complete.
+    fn build_unit(&self, name: &str, build: bool) -> Result<Box<ObjDiffResult>> {
+        let config = self.project_config()?;
+        let unit = self.find_unit(name)?;
+        let mut diff_config = DiffInputs {
+            project_dir: Some(&self.project_dir),
+            project: Some(config),
+            unit: Some(&unit),
+            target_path: unit.target_path.clone(),
+            base_path: unit.base_path.clone(),
+            diff_obj_config: &DiffObjConfig::default(),
+        }
+        .objdiff_config();
+        if !build {
+            diff_config.build_base = false;
+            diff_config.build_target = false;
+        }
+        run_build_blocking(diff_config)
+    }
+}
+
+#[derive(Deserialize)]
+struct UnitParams {
+    unit: String,
+    #[serde(default)]
+    build: bool,
+}
+
+/// Params of a request that don't match the method, reported as [`INVALID_PARAMS`].
+#[derive(Debug)]
+struct InvalidParams(serde_json::Error);
+
+impl std::fmt::Display for InvalidParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid params: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidParams {}
+
+fn parse_params<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T> {
+    // Omitted params are the same as an empty object
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|e| InvalidParams(e).into())
+}
+
+fn build_status_json(status: &BuildStatus) -> Value {
+    json!({
+        "success": status.success,
+        "cmdline": status.cmdline,
+        "stdout": status.stdout,
+        "stderr": status.stderr,
+    })
+}
+
+fn function_diff(obj: Option<&(ObjInfo, ObjDiff)>, symbol_name: &str) -> Option<FunctionDiff> {
+    let (obj, diff) = obj?;
+    for (section, section_diff) in obj.sections.iter().zip(&diff.sections) {
+        for (symbol, symbol_diff) in section.symbols.iter().zip(&section_diff.symbols) {
+            if symbol.name == symbol_name {
+                return Some(FunctionDiff::new(obj, symbol_diff));
+            }
+        }
+    }
+    None
+}
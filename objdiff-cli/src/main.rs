@@ -83,8 +83,13 @@ struct TopLevel {
 #[derive(FromArgs, PartialEq, Debug)]
 #[argp(subcommand)]
 enum SubCommand {
+    Build(cmd::build::Args),
+    Config(cmd::config::Args),
     Diff(cmd::diff::Args),
+    Order(cmd::order::Args),
     Report(cmd::report::Args),
+    Search(cmd::search::Args),
+    Serve(cmd::serve::Args),
 }
 
 // Duplicated from supports-color so we can check early.
@@ -138,8 +143,13 @@ fn main() {
         });
     }
     result = result.and_then(|_| match args.command {
-        SubCommand::Diff(c_args) => cmd::diff::run(c_args),
+        SubCommand::Build(c_args) => cmd::build::run(c_args),
+        SubCommand::Config(c_args) => cmd::config::run(c_args),
+        SubCommand::Diff(c_args) => cmd::diff::run(c_args, use_colors),
+        SubCommand::Order(c_args) => cmd::order::run(c_args),
         SubCommand::Report(c_args) => cmd::report::run(c_args),
+        SubCommand::Search(c_args) => cmd::search::run(c_args),
+        SubCommand::Serve(c_args) => cmd::serve::run(c_args),
     });
     if let Err(e) = result {
         eprintln!("Failed: {e:?}");
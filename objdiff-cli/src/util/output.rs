@@ -14,6 +14,10 @@ pub enum OutputFormat {
     Json,
     JsonPretty,
     Proto,
+    /// Human-readable summary, only supported for reports
+    Markdown,
+    /// Human-readable summary, only supported for reports
+    Html,
 }
 
 impl OutputFormat {
@@ -22,6 +26,8 @@ impl OutputFormat {
             "json" => Ok(Self::Json),
             "json-pretty" | "json_pretty" => Ok(Self::JsonPretty),
             "binpb" | "pb" | "proto" | "protobuf" => Ok(Self::Proto),
+            "md" | "markdown" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
             _ => bail!("Invalid output format: {}", s),
         }
     }
@@ -36,6 +42,9 @@ impl OutputFormat {
 
 pub fn write_output<T>(input: &T, output: Option<&Path>, format: OutputFormat) -> Result<()>
 where T: serde::Serialize + prost::Message {
+    if matches!(format, OutputFormat::Markdown | OutputFormat::Html) {
+        bail!("Output format {format:?} is only supported when generating reports");
+    }
     match output {
         Some(output) if output != Path::new("-") => {
             info!("Writing to {}", output.display());
@@ -59,6 +68,7 @@ where T: serde::Serialize + prost::Message {
                         .context("Failed to write output file")?;
                     output.flush().context("Failed to flush output file")?;
                 }
+                OutputFormat::Markdown | OutputFormat::Html => unreachable!(),
                 OutputFormat::Proto => {
                     file.set_len(input.encoded_len() as u64)?;
                     let map = unsafe { memmap2::Mmap::map(&file) }
@@ -78,7 +88,20 @@ where T: serde::Serialize + prost::Message {
             OutputFormat::Proto => {
                 std::io::stdout().write_all(&input.encode_to_vec())?;
             }
+            OutputFormat::Markdown | OutputFormat::Html => unreachable!(),
         },
     }
     Ok(())
 }
+
+/// Writes text output (e.g. a report summary) to a file, or stdout if `output` is `None` or `-`.
+pub fn write_text(text: &str, output: Option<&Path>) -> Result<()> {
+    match output {
+        Some(output) if output != Path::new("-") => {
+            info!("Writing to {}", output.display());
+            std::fs::write(output, text)
+                .with_context(|| format!("Failed to write file {}", output.display()))
+        }
+        _ => Ok(std::io::stdout().write_all(text.as_bytes())?),
+    }
+}
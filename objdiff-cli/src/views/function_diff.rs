@@ -1,8 +1,10 @@
+use std::time::{Duration, Instant};
+
 use anyhow::{bail, Result};
 use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind};
 use objdiff_core::{
     diff::{
-        display::{display_diff, DiffText, HighlightKind},
+        display::{display_diff, DiffText, DiffTextSearch, HighlightKind},
         ObjDiff, ObjInsDiffKind, ObjSymbolDiff,
     },
     obj::{ObjInfo, ObjSectionKind, ObjSymbol, SymbolRef},
@@ -13,13 +15,15 @@ use ratatui::{
     Frame,
 };
 
-use super::{EventControlFlow, EventResult, UiView};
+use super::{theme::Theme, EventControlFlow, EventResult, UiView};
 use crate::cmd::diff::AppState;
 
 #[allow(dead_code)]
 #[derive(Default)]
 pub struct FunctionDiffUi {
     pub symbol_name: String,
+    /// The right symbol, if it differs from the left (self-diff mode)
+    pub right_symbol_name: Option<String>,
     pub left_highlight: HighlightKind,
     pub right_highlight: HighlightKind,
     pub scroll_x: usize,
@@ -33,10 +37,51 @@ pub struct FunctionDiffUi {
     pub prev_sym: Option<SymbolRef>,
     pub open_options: bool,
     pub three_way: bool,
+    /// Input for the jump-to-address prompt, if open
+    pub jump_prompt: Option<String>,
+    pub jump_error: bool,
+    /// Input for the search prompt, if open
+    pub search_prompt: Option<String>,
+    pub search: Option<DiffTextSearch>,
+    /// Rows matching the search
+    pub search_rows: Vec<usize>,
+    /// The current search match
+    pub search_row: Option<usize>,
+    pub momentum: ScrollMomentum,
+}
+
+/// Accelerates mouse wheel scrolling when wheel events arrive in quick succession,
+/// as sent by trackpads and high-resolution wheels in terminals like kitty and iTerm2.
+#[derive(Default)]
+pub struct ScrollMomentum {
+    last_event: Option<Instant>,
+    down: bool,
+    step: usize,
+}
+
+impl ScrollMomentum {
+    const BASE_STEP: usize = 3;
+    const WINDOW: Duration = Duration::from_millis(60);
+
+    /// Returns the number of rows to scroll for a wheel event in the given direction.
+    fn next_step(&mut self, down: bool, max_step: usize) -> usize {
+        let now = Instant::now();
+        let continued = self.down == down
+            && self.last_event.is_some_and(|last| now.duration_since(last) < Self::WINDOW);
+        self.step = if continued {
+            (self.step + self.step / 2).min(max_step.max(Self::BASE_STEP))
+        } else {
+            Self::BASE_STEP
+        };
+        self.down = down;
+        self.last_event = Some(now);
+        self.step
+    }
 }
 
 impl UiView for FunctionDiffUi {
     fn draw(&mut self, state: &AppState, f: &mut Frame, result: &mut EventResult) {
+        let theme = &state.theme;
         let chunks = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).split(f.area());
         let header_chunks = Layout::horizontal([
             Constraint::Fill(1),
@@ -74,39 +119,99 @@ impl UiView for FunctionDiffUi {
             self.scroll_state_y.content_length(max_scroll_y).position(self.scroll_y);
 
         let mut line_l = Line::default();
-        line_l
-            .spans
-            .push(Span::styled(self.symbol_name.clone(), Style::new().fg(Color::White).bold()));
+        if let Some(input) = &self.jump_prompt {
+            line_l.spans.push(Span::styled("Jump to address: ", Style::new().fg(theme.highlight)));
+            line_l.spans.push(Span::styled(
+                format!("{input}_"),
+                Style::new().fg(if self.jump_error { theme.error } else { theme.prompt }),
+            ));
+        } else if let Some(input) = &self.search_prompt {
+            line_l.spans.push(Span::styled("/", Style::new().fg(theme.highlight)));
+            line_l.spans.push(Span::styled(format!("{input}_"), Style::new().fg(theme.prompt)));
+        } else {
+            line_l.spans.push(Span::styled(
+                self.symbol_name.clone(),
+                Style::new().fg(theme.highlight).bold(),
+            ));
+            if let Some(right_symbol_name) = &self.right_symbol_name {
+                line_l.spans.push(Span::styled(
+                    format!(" ↔ {right_symbol_name}"),
+                    Style::new().fg(theme.highlight).bold(),
+                ));
+            }
+            // Keep track of the position within long functions
+            let first_row = self.scroll_y.min(self.num_rows);
+            let last_row = (self.scroll_y + self.per_page).min(self.num_rows);
+            line_l.spans.push(Span::styled(
+                format!(" [{}-{}/{}]", first_row + 1, last_row, self.num_rows),
+                Style::new().fg(theme.deemphasized),
+            ));
+            if let Some(address) = self.top_address(state) {
+                line_l.spans.push(Span::styled(
+                    format!(" @ {address:x}"),
+                    Style::new().fg(theme.deemphasized),
+                ));
+            }
+            if self.search.is_some() {
+                let position = self
+                    .search_row
+                    .and_then(|row| self.search_rows.iter().position(|&r| r == row))
+                    .map_or(0, |i| i + 1);
+                line_l.spans.push(Span::styled(
+                    format!(" [match {position}/{}]", self.search_rows.len()),
+                    Style::new().fg(if self.search_rows.is_empty() {
+                        theme.error
+                    } else {
+                        theme.prompt
+                    }),
+                ));
+            }
+        }
         f.render_widget(line_l, header_chunks[0]);
 
         let mut line_r = Line::default();
-        if let Some(percent) =
-            get_symbol(state.right_obj.as_ref(), self.right_sym).and_then(|(_, d)| d.match_percent)
-        {
-            line_r.spans.push(Span::styled(
-                format!("{:.2}% ", percent),
-                Style::new().fg(match_percent_color(percent)),
-            ));
+        if let Some((_, diff)) = get_symbol(state.right_obj(), self.right_sym) {
+            if let Some(percent) = diff.match_percent {
+                line_r.spans.push(Span::styled(
+                    format!("{:.2}% ", percent),
+                    Style::new().fg(theme.match_color(percent)),
+                ));
+            }
+            if diff.quirk_count > 0 {
+                line_r.spans.push(Span::styled(
+                    format!("({} quirk) ", diff.quirk_count),
+                    Style::new().fg(theme.quirk),
+                ));
+            }
         }
         let reload_time = state
-            .reload_time
-            .as_ref()
+            .reload_time()
             .and_then(|t| t.format(&state.time_format).ok())
             .unwrap_or_else(|| "N/A".to_string());
         line_r.spans.push(Span::styled(
             format!("Last reload: {}", reload_time),
-            Style::new().fg(Color::White),
+            Style::new().fg(theme.highlight),
         ));
         line_r.spans.push(Span::styled(
-            format!(" ({} jobs)", state.jobs.jobs.len()),
-            Style::new().fg(Color::LightYellow),
+            format!(" ({} jobs)", state.session.jobs().jobs.len()),
+            Style::new().fg(theme.prompt),
         ));
+        // Show the last build error, or live output of a running build
+        if let Some(error) = &state.last_error {
+            line_r.spans.push(Span::styled(format!(" {error}"), Style::new().fg(theme.error)));
+        } else if let Some(line) = state.session.jobs().jobs.iter().find_map(|job| {
+            job.context.status.read().ok().and_then(|status| status.output.back().cloned())
+        }) {
+            line_r
+                .spans
+                .push(Span::styled(format!(" {line}"), Style::new().fg(theme.deemphasized)));
+        }
         f.render_widget(line_r, header_chunks[2]);
 
         let mut left_text = None;
         let mut left_highlight = None;
         let mut max_width = 0;
-        if let Some((symbol, symbol_diff)) = get_symbol(state.left_obj.as_ref(), self.left_sym) {
+        if let Some((symbol, symbol_diff)) = get_symbol(state.left_obj(), self.left_sym) {
             let mut text = Text::default();
             let rect = content_chunks[0].inner(Margin::new(0, 1));
             left_highlight = self.print_sym(
@@ -117,6 +222,7 @@ impl UiView for FunctionDiffUi {
                 &self.left_highlight,
                 result,
                 false,
+                theme,
             );
             max_width = max_width.max(text.width());
             left_text = Some(text);
@@ -125,7 +231,7 @@ impl UiView for FunctionDiffUi {
         let mut right_text = None;
         let mut right_highlight = None;
         let mut margin_text = None;
-        if let Some((symbol, symbol_diff)) = get_symbol(state.right_obj.as_ref(), self.right_sym) {
+        if let Some((symbol, symbol_diff)) = get_symbol(state.right_obj(), self.right_sym) {
             let mut text = Text::default();
             let rect = content_chunks[2].inner(Margin::new(0, 1));
             right_highlight = self.print_sym(
@@ -136,6 +242,7 @@ impl UiView for FunctionDiffUi {
                 &self.right_highlight,
                 result,
                 false,
+                theme,
             );
             max_width = max_width.max(text.width());
             right_text = Some(text);
@@ -162,6 +269,7 @@ impl UiView for FunctionDiffUi {
                     &self.right_highlight,
                     result,
                     true,
+                    theme,
                 );
                 max_width = max_width.max(text.width());
                 prev_text = Some(text);
@@ -189,7 +297,7 @@ impl UiView for FunctionDiffUi {
                     .block(
                         Block::new()
                             .borders(Borders::TOP)
-                            .border_style(Style::new().fg(Color::Gray))
+                            .border_style(Style::new().fg(theme.border))
                             .title_style(Style::new().bold())
                             .title("TARGET"),
                     )
@@ -206,7 +314,7 @@ impl UiView for FunctionDiffUi {
                     .block(
                         Block::new()
                             .borders(Borders::TOP)
-                            .border_style(Style::new().fg(Color::Gray))
+                            .border_style(Style::new().fg(theme.border))
                             .title_style(Style::new().bold())
                             .title("CURRENT"),
                     )
@@ -221,7 +329,7 @@ impl UiView for FunctionDiffUi {
             }
             let block = Block::new()
                 .borders(Borders::TOP)
-                .border_style(Style::new().fg(Color::Gray))
+                .border_style(Style::new().fg(theme.border))
                 .title_style(Style::new().bold())
                 .title("SAVED");
             if let Some(text) = prev_text {
@@ -291,6 +399,24 @@ impl UiView for FunctionDiffUi {
 
     fn handle_event(&mut self, state: &mut AppState, event: Event) -> EventControlFlow {
         let mut result = EventResult::default();
+        if self.search_prompt.is_some() {
+            if let Event::Key(event) = event {
+                if matches!(event.kind, KeyEventKind::Press | KeyEventKind::Repeat) {
+                    self.handle_search_prompt_key(state, event.code);
+                    result.redraw = true;
+                }
+                return EventControlFlow::Continue(result);
+            }
+        }
+        if self.jump_prompt.is_some() {
+            if let Event::Key(event) = event {
+                if matches!(event.kind, KeyEventKind::Press | KeyEventKind::Repeat) {
+                    self.handle_jump_prompt_key(state, event.code);
+                    result.redraw = true;
+                }
+                return EventControlFlow::Continue(result);
+            }
+        }
         match event {
             Event::Key(event)
                 if matches!(event.kind, KeyEventKind::Press | KeyEventKind::Repeat) =>
@@ -344,12 +470,12 @@ impl UiView for FunctionDiffUi {
                         result.redraw = true;
                     }
                     // Scroll to start
-                    KeyCode::Char('g') => {
+                    KeyCode::Char('g') | KeyCode::Home => {
                         self.scroll_y = 0;
                         result.redraw = true;
                     }
                     // Scroll to end
-                    KeyCode::Char('G') => {
+                    KeyCode::Char('G') | KeyCode::End => {
                         self.scroll_y = self.num_rows;
                         result.redraw = true;
                     }
@@ -370,7 +496,8 @@ impl UiView for FunctionDiffUi {
                     }
                     // Toggle relax relocation diffs
                     KeyCode::Char('x') => {
-                        state.relax_reloc_diffs = !state.relax_reloc_diffs;
+                        let config = state.session.diff_config_mut();
+                        config.relax_reloc_diffs = !config.relax_reloc_diffs;
                         result.redraw = true;
                         return EventControlFlow::Reload;
                     }
@@ -384,16 +511,38 @@ impl UiView for FunctionDiffUi {
                         self.open_options = !self.open_options;
                         result.redraw = true;
                     }
+                    // Jump to address
+                    KeyCode::Char(':') | KeyCode::Char('@') => {
+                        self.jump_prompt = Some(String::new());
+                        self.jump_error = false;
+                        result.redraw = true;
+                    }
+                    // Search
+                    KeyCode::Char('/') => {
+                        self.search_prompt = Some(String::new());
+                        result.redraw = true;
+                    }
+                    // Next search match
+                    KeyCode::Char('n') => {
+                        self.step_search(true);
+                        result.redraw = true;
+                    }
+                    // Previous search match
+                    KeyCode::Char('N') => {
+                        self.step_search(false);
+                        result.redraw = true;
+                    }
                     _ => {}
                 }
             }
             Event::Mouse(event) => match event.kind {
                 MouseEventKind::ScrollDown => {
-                    self.scroll_y += 3;
+                    self.scroll_y += self.momentum.next_step(true, self.per_page / 2);
                     result.redraw = true;
                 }
                 MouseEventKind::ScrollUp => {
-                    self.scroll_y = self.scroll_y.saturating_sub(3);
+                    let step = self.momentum.next_step(false, self.per_page / 2);
+                    self.scroll_y = self.scroll_y.saturating_sub(step);
                     result.redraw = true;
                 }
                 MouseEventKind::ScrollRight => {
@@ -419,15 +568,14 @@ impl UiView for FunctionDiffUi {
     }
 
     fn reload(&mut self, state: &AppState) -> Result<()> {
-        let left_sym =
-            state.left_obj.as_ref().and_then(|(o, _)| find_function(o, &self.symbol_name));
+        let left_sym = state.left_obj().and_then(|(o, _)| find_function(o, &self.symbol_name));
         let right_sym =
-            state.right_obj.as_ref().and_then(|(o, _)| find_function(o, &self.symbol_name));
+            state.right_obj().and_then(|(o, _)| find_function(o, self.right_symbol_name()));
         let prev_sym =
             state.prev_obj.as_ref().and_then(|(o, _)| find_function(o, &self.symbol_name));
         self.num_rows = match (
-            get_symbol(state.left_obj.as_ref(), left_sym),
-            get_symbol(state.right_obj.as_ref(), right_sym),
+            get_symbol(state.left_obj(), left_sym),
+            get_symbol(state.right_obj(), right_sym),
         ) {
             (Some((_l, ld)), Some((_r, rd))) => ld.instructions.len().max(rd.instructions.len()),
             (Some((_l, ld)), None) => ld.instructions.len(),
@@ -437,11 +585,16 @@ impl UiView for FunctionDiffUi {
         self.left_sym = left_sym;
         self.right_sym = right_sym;
         self.prev_sym = prev_sym;
+        self.update_search_rows(state);
         Ok(())
     }
 }
 
 impl FunctionDiffUi {
+    fn right_symbol_name(&self) -> &str {
+        self.right_symbol_name.as_deref().unwrap_or(&self.symbol_name)
+    }
+
     pub fn draw_options(&mut self, f: &mut Frame, _result: &mut EventResult) {
         let percent_x = 50;
         let percent_y = 50;
@@ -466,6 +619,125 @@ impl FunctionDiffUi {
         f.render_widget(popup, popup_rect);
     }
 
+    fn handle_jump_prompt_key(&mut self, state: &AppState, code: KeyCode) {
+        let Some(input) = &mut self.jump_prompt else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => self.jump_prompt = None,
+            KeyCode::Backspace => {
+                input.pop();
+                self.jump_error = false;
+            }
+            KeyCode::Char(c) if c.is_ascii_hexdigit() || c == 'x' => {
+                input.push(c);
+                self.jump_error = false;
+            }
+            KeyCode::Enter => {
+                let trimmed = input.trim_start_matches("0x");
+                match u64::from_str_radix(trimmed, 16) {
+                    Ok(address) if self.jump_to_address(state, address) => {
+                        self.jump_prompt = None;
+                    }
+                    _ => self.jump_error = true,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_search_prompt_key(&mut self, state: &AppState, code: KeyCode) {
+        let Some(input) = &mut self.search_prompt else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => self.search_prompt = None,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Enter => {
+                self.search = DiffTextSearch::new(input);
+                self.search_prompt = None;
+                self.search_row = None;
+                self.update_search_rows(state);
+                self.step_search(true);
+            }
+            _ => {}
+        }
+    }
+
+    fn update_search_rows(&mut self, state: &AppState) {
+        self.search_rows = match &self.search {
+            Some(search) => search.find_rows(
+                [(state.left_obj(), self.left_sym), (state.right_obj(), self.right_sym)]
+                    .into_iter()
+                    .filter_map(|(obj, sym)| get_symbol(obj, sym))
+                    .map(|(symbol, symbol_diff)| {
+                        (symbol_diff.instructions.as_slice(), symbol.address)
+                    }),
+            ),
+            None => Vec::new(),
+        };
+    }
+
+    /// Moves to the next (or previous) search match after the current one, or after the top of
+    /// the page if there is none, wrapping around.
+    fn step_search(&mut self, forward: bool) {
+        let from = self.search_row.unwrap_or(self.scroll_y);
+        let row = if forward {
+            let after = if self.search_row.is_some() { from + 1 } else { from };
+            self.search_rows.iter().find(|&&r| r >= after).or(self.search_rows.first())
+        } else {
+            self.search_rows.iter().rev().find(|&&r| r < from).or(self.search_rows.last())
+        };
+        let Some(&row) = row else {
+            self.search_row = None;
+            return;
+        };
+        self.search_row = Some(row);
+        // Keep the match within the middle of the page
+        if row < self.scroll_y || row >= self.scroll_y + self.per_page {
+            self.scroll_y = row.saturating_sub(self.per_page / 2);
+        }
+    }
+
+    /// Scrolls to the first row at or after the given address. The address can either be
+    /// relative to the start of the function (as displayed) or absolute.
+    fn jump_to_address(&mut self, state: &AppState, address: u64) -> bool {
+        for (obj, sym) in [(state.left_obj(), self.left_sym), (state.right_obj(), self.right_sym)] {
+            let Some((symbol, symbol_diff)) = get_symbol(obj, sym) else {
+                continue;
+            };
+            let row = symbol_diff.instructions.iter().position(|ins_diff| {
+                ins_diff.ins.as_ref().is_some_and(|ins| {
+                    let relative = ins.address - symbol.address;
+                    relative >= address || ins.address == address
+                })
+            });
+            if let Some(row) = row {
+                self.scroll_y = row;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns the relative address of the first instruction currently visible.
+    fn top_address(&self, state: &AppState) -> Option<u64> {
+        [(state.left_obj(), self.left_sym), (state.right_obj(), self.right_sym)]
+            .into_iter()
+            .find_map(|(obj, sym)| {
+                let (symbol, symbol_diff) = get_symbol(obj, sym)?;
+                symbol_diff
+                    .instructions
+                    .iter()
+                    .skip(self.scroll_y)
+                    .find_map(|ins_diff| ins_diff.ins.as_ref())
+                    .map(|ins| ins.address - symbol.address)
+            })
+    }
+
     fn page_up(&mut self, half: bool) {
         self.scroll_y = self.scroll_y.saturating_sub(self.per_page / if half { 2 } else { 1 });
     }
@@ -484,6 +756,7 @@ impl FunctionDiffUi {
         highlight: &HighlightKind,
         result: &EventResult,
         only_changed: bool,
+        theme: &Theme,
     ) -> Option<HighlightKind> {
         let base_addr = symbol.address;
         let mut new_highlight = None;
@@ -494,6 +767,7 @@ impl FunctionDiffUi {
             .take(rect.height as usize)
             .enumerate()
         {
+            let current_match = self.search_row == Some(self.scroll_y + y);
             if only_changed && ins_diff.kind == ObjInsDiffKind::None {
                 out.lines.push(Line::default());
                 continue;
@@ -501,16 +775,16 @@ impl FunctionDiffUi {
             let mut sx = rect.x;
             let sy = rect.y + y as u16;
             let mut line = Line::default();
+            if theme.markers {
+                line.spans.push(Span::styled(
+                    format!("{} ", Theme::diff_marker(ins_diff.kind)),
+                    Style::new().fg(theme.diff_color(ins_diff.kind)),
+                ));
+                sx += 2;
+            }
             display_diff(ins_diff, base_addr, |text| -> Result<()> {
                 let label_text;
-                let mut base_color = match ins_diff.kind {
-                    ObjInsDiffKind::None
-                    | ObjInsDiffKind::OpMismatch
-                    | ObjInsDiffKind::ArgMismatch => Color::Gray,
-                    ObjInsDiffKind::Replace => Color::Cyan,
-                    ObjInsDiffKind::Delete => Color::Red,
-                    ObjInsDiffKind::Insert => Color::Green,
-                };
+                let mut base_color = theme.diff_color(ins_diff.kind);
                 let mut pad_to = 0;
                 match text {
                     DiffText::Basic(text) => {
@@ -518,11 +792,11 @@ impl FunctionDiffUi {
                     }
                     DiffText::BasicColor(s, idx) => {
                         label_text = s.to_string();
-                        base_color = COLOR_ROTATION[idx % COLOR_ROTATION.len()];
+                        base_color = theme.rotation_color(idx);
                     }
                     DiffText::Line(num) => {
                         label_text = format!("{num} ");
-                        base_color = Color::DarkGray;
+                        base_color = theme.deemphasized;
                         pad_to = 5;
                     }
                     DiffText::Address(addr) => {
@@ -532,29 +806,29 @@ impl FunctionDiffUi {
                     DiffText::Opcode(mnemonic, _op) => {
                         label_text = mnemonic.to_string();
                         if ins_diff.kind == ObjInsDiffKind::OpMismatch {
-                            base_color = Color::Blue;
+                            base_color = theme.op_mismatch;
                         }
                         pad_to = 8;
                     }
                     DiffText::Argument(arg, diff) => {
                         label_text = arg.to_string();
                         if let Some(diff) = diff {
-                            base_color = COLOR_ROTATION[diff.idx % COLOR_ROTATION.len()]
+                            base_color = theme.rotation_color(diff.idx)
                         }
                     }
                     DiffText::BranchDest(addr, diff) => {
                         label_text = format!("{addr:x}");
                         if let Some(diff) = diff {
-                            base_color = COLOR_ROTATION[diff.idx % COLOR_ROTATION.len()]
+                            base_color = theme.rotation_color(diff.idx)
                         }
                     }
                     DiffText::Symbol(sym, diff) => {
                         let name = sym.demangled_name.as_ref().unwrap_or(&sym.name);
                         label_text = name.clone();
                         if let Some(diff) = diff {
-                            base_color = COLOR_ROTATION[diff.idx % COLOR_ROTATION.len()]
+                            base_color = theme.rotation_color(diff.idx)
                         } else {
-                            base_color = Color::White;
+                            base_color = theme.highlight;
                         }
                     }
                     DiffText::Spacing(n) => {
@@ -575,7 +849,9 @@ impl FunctionDiffUi {
                 }
                 let mut style = Style::new().fg(base_color);
                 if highlighted {
-                    style = style.bg(Color::DarkGray);
+                    style = theme.selected(style);
+                } else if self.search.as_ref().is_some_and(|s| s.matches_text(text)) {
+                    style = theme.search_match(style, current_match);
                 }
                 line.spans.push(Span::styled(label_text, style));
                 sx += len as u16;
@@ -587,6 +863,9 @@ impl FunctionDiffUi {
                 Ok(())
             })
             .unwrap();
+            if current_match {
+                line.style = Style::new().add_modifier(Modifier::UNDERLINED);
+            }
             out.lines.push(line);
         }
         new_highlight
@@ -598,6 +877,8 @@ impl FunctionDiffUi {
                 out.lines.push(Line::raw(match ins_diff.kind {
                     ObjInsDiffKind::Delete => "<",
                     ObjInsDiffKind::Insert => ">",
+                    ObjInsDiffKind::Quirk => "~",
+                    ObjInsDiffKind::SoftMismatch => "≈",
                     _ => "|",
                 }));
             } else {
@@ -607,26 +888,6 @@ impl FunctionDiffUi {
     }
 }
 
-pub const COLOR_ROTATION: [Color; 7] = [
-    Color::Magenta,
-    Color::Cyan,
-    Color::Green,
-    Color::Red,
-    Color::Yellow,
-    Color::Blue,
-    Color::Green,
-];
-
-pub fn match_percent_color(match_percent: f32) -> Color {
-    if match_percent == 100.0 {
-        Color::Green
-    } else if match_percent >= 50.0 {
-        Color::LightBlue
-    } else {
-        Color::LightRed
-    }
-}
-
 #[inline]
 fn get_symbol(
     obj: Option<&(ObjInfo, ObjDiff)>,
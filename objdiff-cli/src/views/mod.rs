@@ -5,6 +5,7 @@ use ratatui::Frame;
 use crate::cmd::diff::AppState;
 
 pub mod function_diff;
+pub mod theme;
 
 #[derive(Default)]
 pub struct EventResult {
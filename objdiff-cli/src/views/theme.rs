@@ -0,0 +1,214 @@
+use std::{fs, path::Path, str::FromStr};
+
+use anyhow::{Context, Result};
+use objdiff_core::diff::ObjInsDiffKind;
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Deserializer};
+
+/// Colors used by the TUI, loaded from a JSON theme file. Colors are names (e.g. `"light-blue"`),
+/// indexed colors (e.g. `"42"`) or hex colors (e.g. `"#ff8000"`), and omitted colors keep their
+/// defaults.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    /// Instruction text
+    #[serde(deserialize_with = "deserialize_color")]
+    pub text: Color,
+    /// Symbol names and titles
+    #[serde(deserialize_with = "deserialize_color")]
+    pub highlight: Color,
+    /// Line numbers and secondary information
+    #[serde(deserialize_with = "deserialize_color")]
+    pub deemphasized: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub border: Color,
+    /// Prompt input
+    #[serde(deserialize_with = "deserialize_color")]
+    pub prompt: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub error: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub replace: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub delete: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub insert: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub quirk: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub soft_mismatch: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub op_mismatch: Color,
+    /// Background of the highlighted text and search matches
+    #[serde(deserialize_with = "deserialize_color")]
+    pub selection: Color,
+    /// Background of the current search match
+    #[serde(deserialize_with = "deserialize_color")]
+    pub current_match: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub match_complete: Color,
+    /// Match percent of at least 50%
+    #[serde(deserialize_with = "deserialize_color")]
+    pub match_partial: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub match_low: Color,
+    /// Colors of differing arguments, rotated to tell them apart
+    #[serde(deserialize_with = "deserialize_colors")]
+    pub rotation: Vec<Color>,
+    /// Show a marker (`!`, `+`, `-`, `~`) before each differing instruction
+    pub markers: bool,
+    /// Use text attributes instead of colors, for terminals without color support
+    #[serde(skip)]
+    pub monochrome: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            text: Color::Gray,
+            highlight: Color::White,
+            deemphasized: Color::DarkGray,
+            border: Color::Gray,
+            prompt: Color::LightYellow,
+            error: Color::LightRed,
+            replace: Color::Cyan,
+            delete: Color::Red,
+            insert: Color::Green,
+            quirk: Color::Yellow,
+            soft_mismatch: Color::LightYellow,
+            op_mismatch: Color::Blue,
+            selection: Color::DarkGray,
+            current_match: Color::Yellow,
+            match_complete: Color::Green,
+            match_partial: Color::LightBlue,
+            match_low: Color::LightRed,
+            rotation: vec![
+                Color::Magenta,
+                Color::Cyan,
+                Color::Green,
+                Color::Red,
+                Color::Yellow,
+                Color::Blue,
+                Color::Green,
+            ],
+            markers: false,
+            monochrome: false,
+        }
+    }
+}
+
+impl Theme {
+    /// A theme without colors, showing diff markers instead.
+    pub fn monochrome() -> Self {
+        Self {
+            text: Color::Reset,
+            highlight: Color::Reset,
+            deemphasized: Color::Reset,
+            border: Color::Reset,
+            prompt: Color::Reset,
+            error: Color::Reset,
+            replace: Color::Reset,
+            delete: Color::Reset,
+            insert: Color::Reset,
+            quirk: Color::Reset,
+            soft_mismatch: Color::Reset,
+            op_mismatch: Color::Reset,
+            selection: Color::Reset,
+            current_match: Color::Reset,
+            match_complete: Color::Reset,
+            match_partial: Color::Reset,
+            match_low: Color::Reset,
+            rotation: vec![Color::Reset],
+            markers: true,
+            monochrome: true,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let data =
+            fs::read(path).with_context(|| format!("Failed to read theme {}", path.display()))?;
+        let mut theme: Theme = serde_json::from_slice(&data)
+            .with_context(|| format!("Failed to parse theme {}", path.display()))?;
+        if theme.rotation.is_empty() {
+            theme.rotation = Self::default().rotation;
+        }
+        Ok(theme)
+    }
+
+    pub fn diff_color(&self, kind: ObjInsDiffKind) -> Color {
+        match kind {
+            ObjInsDiffKind::None | ObjInsDiffKind::OpMismatch | ObjInsDiffKind::ArgMismatch => {
+                self.text
+            }
+            ObjInsDiffKind::Replace => self.replace,
+            ObjInsDiffKind::Delete => self.delete,
+            ObjInsDiffKind::Insert => self.insert,
+            ObjInsDiffKind::Quirk => self.quirk,
+            ObjInsDiffKind::SoftMismatch => self.soft_mismatch,
+        }
+    }
+
+    /// The marker shown before an instruction when [`markers`](Self::markers) is enabled.
+    pub fn diff_marker(kind: ObjInsDiffKind) -> char {
+        match kind {
+            ObjInsDiffKind::None => ' ',
+            ObjInsDiffKind::OpMismatch | ObjInsDiffKind::ArgMismatch | ObjInsDiffKind::Replace => {
+                '!'
+            }
+            ObjInsDiffKind::Delete => '-',
+            ObjInsDiffKind::Insert => '+',
+            ObjInsDiffKind::Quirk | ObjInsDiffKind::SoftMismatch => '~',
+        }
+    }
+
+    pub fn rotation_color(&self, idx: usize) -> Color { self.rotation[idx % self.rotation.len()] }
+
+    pub fn match_color(&self, match_percent: f32) -> Color {
+        if match_percent == 100.0 {
+            self.match_complete
+        } else if match_percent >= 50.0 {
+            self.match_partial
+        } else {
+            self.match_low
+        }
+    }
+
+    /// Style for the highlighted text.
+    pub fn selected(&self, style: Style) -> Style {
+        if self.monochrome {
+            style.add_modifier(Modifier::REVERSED)
+        } else {
+            style.bg(self.selection)
+        }
+    }
+
+    /// Style for a search match, or the current search match.
+    pub fn search_match(&self, style: Style, current: bool) -> Style {
+        match (self.monochrome, current) {
+            (true, true) => style.add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            (true, false) => style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            (false, true) => style.bg(self.current_match).fg(Color::Black),
+            (false, false) => style.bg(self.selection),
+        }
+    }
+}
+
+fn parse_color(s: &str) -> Result<Color, String> {
+    Color::from_str(s).map_err(|_| format!("invalid color: {s}"))
+}
+
+fn deserialize_color<'de, D>(deserializer: D) -> Result<Color, D::Error>
+where D: Deserializer<'de> {
+    let s = String::deserialize(deserializer)?;
+    parse_color(&s).map_err(serde::de::Error::custom)
+}
+
+fn deserialize_colors<'de, D>(deserializer: D) -> Result<Vec<Color>, D::Error>
+where D: Deserializer<'de> {
+    let colors = Vec::<String>::deserialize(deserializer)?;
+    colors
+        .iter()
+        .map(|s| parse_color(s))
+        .collect::<Result<_, _>>()
+        .map_err(serde::de::Error::custom)
+}
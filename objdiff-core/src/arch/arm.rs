@@ -7,8 +7,8 @@ use anyhow::{bail, Result};
 use arm_attr::{enums::CpuArch, tag::Tag, BuildAttrs};
 use object::{
     elf::{self, SHT_ARM_ATTRIBUTES},
-    Endian, File, Object, ObjectSection, ObjectSymbol, Relocation, RelocationFlags, SectionIndex,
-    SectionKind, Symbol, SymbolKind,
+    Endian, File, FileFlags, Object, ObjectSection, ObjectSymbol, Relocation, RelocationFlags,
+    RelocationTarget, SectionIndex, SectionKind, Symbol, SymbolKind,
 };
 use unarm::{
     args::{Argument, OffsetImm, OffsetReg, Register},
@@ -17,7 +17,7 @@ use unarm::{
 };
 
 use crate::{
-    arch::{ObjArch, ProcessCodeResult},
+    arch::{bitfield, ObjArch, ProcessCodeResult},
     diff::{ArmArchVersion, ArmR9Usage, DiffObjConfig},
     obj::{ObjIns, ObjInsArg, ObjInsArgValue, ObjReloc, ObjSection},
 };
@@ -26,9 +26,23 @@ pub struct ObjArchArm {
     /// Maps section index, to list of disasm modes (arm, thumb or data) sorted by address
     disasm_modes: HashMap<SectionIndex, Vec<DisasmMode>>,
     detected_version: Option<ArmVersion>,
+    /// Byte order of data words
     endianness: object::Endianness,
+    /// Byte order of instruction words. This differs from the data byte order for BE8 images,
+    /// where the linker stores instructions little-endian and data big-endian.
+    code_endianness: object::Endianness,
+    /// Built by armcc (RVCT / ARM Compiler 5), which references Thumb functions through section
+    /// symbols with the interworking bit in the addend.
+    armcc: bool,
 }
 
+/// Named R_ARM_THM_PC11 and R_ARM_THM_PC9 in older headers.
+const R_ARM_THM_JUMP11: u32 = 102;
+const R_ARM_THM_JUMP8: u32 = 103;
+
+/// Sign-extends the low `bits` bits of `value`.
+fn sign_extend(value: u32, bits: u32) -> i32 { ((value << (32 - bits)) as i32) >> (32 - bits) }
+
 impl ObjArchArm {
     pub fn new(file: &File) -> Result<Self> {
         let endianness = file.endianness();
@@ -36,7 +50,17 @@ impl ObjArchArm {
             File::Elf32(_) => {
                 let disasm_modes = Self::elf_get_mapping_symbols(file);
                 let detected_version = Self::elf_detect_arm_version(file)?;
-                Ok(Self { disasm_modes, detected_version, endianness })
+                let code_endianness = match file.flags() {
+                    FileFlags::Elf { e_flags, .. }
+                        if endianness == object::Endianness::Big
+                            && e_flags & elf::EF_ARM_BE8 != 0 =>
+                    {
+                        object::Endianness::Little
+                    }
+                    _ => endianness,
+                };
+                let armcc = Self::elf_is_armcc(file);
+                Ok(Self { disasm_modes, detected_version, endianness, code_endianness, armcc })
             }
             _ => bail!("Unsupported file format {:?}", file.format()),
         }
@@ -78,6 +102,42 @@ impl ObjArchArm {
         Ok(None)
     }
 
+    fn elf_is_armcc(file: &File) -> bool {
+        let Some(data) = file.section_by_name(".comment").and_then(|s| s.data().ok()) else {
+            return false;
+        };
+        data.split(|&b| b == 0).any(|entry| {
+            let entry = String::from_utf8_lossy(entry);
+            entry.contains("ARM C/C++ Compiler")
+                || entry.contains("ARM Compiler")
+                || entry.contains("ArmCC")
+        })
+    }
+
+    /// armcc relocates pointers to Thumb functions against the section symbol, with the
+    /// interworking bit set in the addend. Clear it so that the target resolves to the function
+    /// itself, as it does for other compilers that relocate against the function symbol.
+    fn strip_interworking_bit(&self, file: &File<'_>, reloc: &Relocation, addend: i32) -> i32 {
+        if !self.armcc || addend & 1 == 0 {
+            return addend;
+        }
+        let RelocationTarget::Symbol(index) = reloc.target() else {
+            return addend;
+        };
+        let is_code_section = file.symbol_by_index(index).ok().is_some_and(|symbol| {
+            symbol.kind() == SymbolKind::Section
+                && symbol
+                    .section_index()
+                    .and_then(|index| file.section_by_index(index).ok())
+                    .is_some_and(|section| section.kind() == SectionKind::Text)
+        });
+        if is_code_section {
+            addend & !1
+        } else {
+            addend
+        }
+    }
+
     fn elf_get_mapping_symbols(file: &File) -> HashMap<SectionIndex, Vec<DisasmMode>> {
         file.sections()
             .filter(|s| s.kind() == SectionKind::Text)
@@ -143,7 +203,7 @@ impl ObjArch for ObjArchArm {
             ArmArchVersion::V5TE => ArmVersion::V5Te,
             ArmArchVersion::V6K => ArmVersion::V6K,
         };
-        let endian = match self.endianness {
+        let endian = match self.code_endianness {
             object::Endianness::Little => unarm::Endian::Little,
             object::Endianness::Big => unarm::Endian::Big,
         };
@@ -187,14 +247,19 @@ impl ObjArch for ObjArchArm {
                     | RelocationFlags::Elf { r_type: elf::R_ARM_THM_PC22 }
                     | RelocationFlags::Elf { r_type: elf::R_ARM_PC24 }
                     | RelocationFlags::Elf { r_type: elf::R_ARM_XPC25 }
-                    | RelocationFlags::Elf { r_type: elf::R_ARM_CALL } => {
+                    | RelocationFlags::Elf { r_type: elf::R_ARM_CALL }
+                    | RelocationFlags::Elf { r_type: elf::R_ARM_JUMP24 }
+                    | RelocationFlags::Elf { r_type: elf::R_ARM_THM_JUMP24 }
+                    | RelocationFlags::Elf { r_type: R_ARM_THM_JUMP11 }
+                    | RelocationFlags::Elf { r_type: R_ARM_THM_JUMP8 } => {
                         reloc_arg = parsed_ins
                             .args
                             .iter()
                             .rposition(|a| matches!(a, Argument::BranchDest(_)));
                     }
                     // Data
-                    RelocationFlags::Elf { r_type: elf::R_ARM_ABS32 } => {
+                    RelocationFlags::Elf { r_type: elf::R_ARM_ABS32 }
+                    | RelocationFlags::Elf { r_type: elf::R_ARM_TARGET1 } => {
                         reloc_arg =
                             parsed_ins.args.iter().rposition(|a| matches!(a, Argument::UImm(_)));
                     }
@@ -220,6 +285,7 @@ impl ObjArch for ObjArchArm {
                 line,
                 formatted: parsed_ins.display(display_options).to_string(),
                 orig: None,
+                raw_args: None,
             });
         }
 
@@ -228,45 +294,116 @@ impl ObjArch for ObjArchArm {
 
     fn implcit_addend(
         &self,
-        _file: &File<'_>,
+        file: &File<'_>,
         section: &ObjSection,
         address: u64,
         reloc: &Relocation,
     ) -> Result<i64> {
         let address = address as usize;
+        let read_u16 = |offset: usize| -> Result<u32> {
+            let data = section.data[address + offset..address + offset + 2].try_into()?;
+            Ok(self.code_endianness.read_u16_bytes(data) as u32)
+        };
+        let read_code = || -> Result<u32> {
+            let data = section.data[address..address + 4].try_into()?;
+            Ok(self.code_endianness.read_u32_bytes(data))
+        };
+        let read_data = || -> Result<u32> {
+            let data = section.data[address..address + 4].try_into()?;
+            Ok(self.endianness.read_u32_bytes(data))
+        };
         Ok(match reloc.flags() {
-            // ARM calls
+            // ARM calls and branches
             RelocationFlags::Elf { r_type: elf::R_ARM_PC24 }
             | RelocationFlags::Elf { r_type: elf::R_ARM_XPC25 }
-            | RelocationFlags::Elf { r_type: elf::R_ARM_CALL } => {
-                let data = section.data[address..address + 4].try_into()?;
-                let addend = self.endianness.read_i32_bytes(data);
-                let imm24 = addend & 0xffffff;
-                (imm24 << 2) << 8 >> 8
+            | RelocationFlags::Elf { r_type: elf::R_ARM_CALL }
+            | RelocationFlags::Elf { r_type: elf::R_ARM_JUMP24 } => {
+                sign_extend((read_code()? & 0xffffff) << 2, 26)
             }
 
             // Thumb calls
             RelocationFlags::Elf { r_type: elf::R_ARM_THM_PC22 }
             | RelocationFlags::Elf { r_type: elf::R_ARM_THM_XPC22 } => {
-                let data = section.data[address..address + 2].try_into()?;
-                let high = self.endianness.read_i16_bytes(data) as i32;
-                let data = section.data[address + 2..address + 4].try_into()?;
-                let low = self.endianness.read_i16_bytes(data) as i32;
-
+                let high = read_u16(0)?;
+                let low = read_u16(2)?;
                 let imm22 = ((high & 0x7ff) << 11) | (low & 0x7ff);
-                (imm22 << 1) << 9 >> 9
+                sign_extend(imm22 << 1, 23)
+            }
+
+            // Thumb-2 B.W, with the J1/J2 bits
+            RelocationFlags::Elf { r_type: elf::R_ARM_THM_JUMP24 } => {
+                let high = read_u16(0)?;
+                let low = read_u16(2)?;
+                let s = (high >> 10) & 1;
+                let i1 = !((low >> 13) ^ s) & 1;
+                let i2 = !((low >> 11) ^ s) & 1;
+                let imm = (s << 24)
+                    | (i1 << 23)
+                    | (i2 << 22)
+                    | ((high & 0x3ff) << 12)
+                    | ((low & 0x7ff) << 1);
+                sign_extend(imm, 25)
+            }
+
+            // Thumb short branches
+            RelocationFlags::Elf { r_type: R_ARM_THM_JUMP11 } => {
+                sign_extend((read_u16(0)? & 0x7ff) << 1, 12)
+            }
+            RelocationFlags::Elf { r_type: R_ARM_THM_JUMP8 } => {
+                sign_extend((read_u16(0)? & 0xff) << 1, 9)
+            }
+
+            // Thumb PC-relative load. The field is unsigned, so a PC bias of -4 is encoded as
+            // 0xff.
+            RelocationFlags::Elf { r_type: elf::R_ARM_THM_PC8 } => {
+                ((((read_u16(0)? & 0xff) << 2) + 4) & 0x3ff) as i32 - 4
+            }
+
+            // MOVW/MOVT
+            RelocationFlags::Elf { r_type: elf::R_ARM_MOVW_ABS_NC }
+            | RelocationFlags::Elf { r_type: elf::R_ARM_MOVT_ABS } => {
+                let ins = read_code()?;
+                sign_extend(((ins >> 4) & 0xf000) | (ins & 0xfff), 16)
+            }
+            RelocationFlags::Elf { r_type: elf::R_ARM_THM_MOVW_ABS_NC }
+            | RelocationFlags::Elf { r_type: elf::R_ARM_THM_MOVT_ABS } => {
+                let high = read_u16(0)?;
+                let low = read_u16(2)?;
+                let imm16 = ((high & 0xf) << 12)
+                    | (((high >> 10) & 1) << 11)
+                    | (((low >> 12) & 0x7) << 8)
+                    | (low & 0xff);
+                sign_extend(imm16, 16)
             }
 
             // Data
-            RelocationFlags::Elf { r_type: elf::R_ARM_ABS32 } => {
-                let data = section.data[address..address + 4].try_into()?;
-                self.endianness.read_i32_bytes(data)
+            RelocationFlags::Elf { r_type: elf::R_ARM_ABS32 }
+            | RelocationFlags::Elf { r_type: elf::R_ARM_TARGET1 } => {
+                self.strip_interworking_bit(file, reloc, read_data()? as i32)
             }
+            RelocationFlags::Elf { r_type: elf::R_ARM_REL32 }
+            | RelocationFlags::Elf { r_type: elf::R_ARM_TARGET2 } => read_data()? as i32,
+            RelocationFlags::Elf { r_type: elf::R_ARM_PREL31 } => sign_extend(read_data()?, 31),
+            RelocationFlags::Elf { r_type: elf::R_ARM_ABS16 } => {
+                let data = section.data[address..address + 2].try_into()?;
+                self.endianness.read_i16_bytes(data) as i32
+            }
+            RelocationFlags::Elf { r_type: elf::R_ARM_ABS8 } => section.data[address] as i8 as i32,
 
             flags => bail!("Unsupported ARM implicit relocation {flags:?}"),
         } as i64)
     }
 
+    /// `R_ARM_V4BX` marks `bx` instructions for the linker's ARMv4 interworking fixups and
+    /// doesn't reference a symbol.
+    fn ignore_reloc(&self, flags: RelocationFlags) -> bool {
+        matches!(
+            flags,
+            RelocationFlags::Elf { r_type: elf::R_ARM_NONE }
+                | RelocationFlags::Elf { r_type: elf::R_ARM_V4BX }
+        )
+    }
+
     fn demangle(&self, name: &str) -> Option<String> {
         cpp_demangle::Symbol::new(name)
             .ok()
@@ -276,6 +413,25 @@ impl ObjArch for ObjArchArm {
     fn display_reloc(&self, flags: RelocationFlags) -> Cow<'static, str> {
         Cow::Owned(format!("<{flags:?}>"))
     }
+
+    fn data_reloc_size(&self, flags: RelocationFlags) -> Option<usize> {
+        match flags {
+            RelocationFlags::Elf {
+                r_type:
+                    elf::R_ARM_ABS32
+                    | elf::R_ARM_REL32
+                    | elf::R_ARM_TARGET1
+                    | elf::R_ARM_TARGET2
+                    | elf::R_ARM_PREL31,
+            } => Some(4),
+            RelocationFlags::Elf { r_type: elf::R_ARM_ABS16 } => Some(2),
+            _ => None,
+        }
+    }
+
+    fn instruction_hover(&self, ins: &ObjIns, _code: &[u8]) -> Vec<String> {
+        bitfield::describe_arm(ins, 32).into_iter().collect()
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -1,7 +1,7 @@
 use std::{borrow::Cow, cmp::Ordering, collections::BTreeMap};
 
 use anyhow::{bail, Result};
-use object::{elf, File, Relocation, RelocationFlags};
+use object::{elf, pe, File, Relocation, RelocationFlags};
 use yaxpeax_arch::{Arch, Decoder, Reader, U8Reader};
 use yaxpeax_arm::armv8::a64::{
     ARMv8, DecodeError, InstDecoder, Instruction, Opcode, Operand, SIMDSizeCode, ShiftStyle,
@@ -9,7 +9,7 @@ use yaxpeax_arm::armv8::a64::{
 };
 
 use crate::{
-    arch::{ObjArch, ProcessCodeResult},
+    arch::{bitfield, ObjArch, ProcessCodeResult},
     diff::DiffObjConfig,
     obj::{ObjIns, ObjInsArg, ObjInsArgValue, ObjReloc, ObjSection},
 };
@@ -63,6 +63,7 @@ impl ObjArch for ObjArchArm64 {
                             line: None,
                             formatted: "".to_string(),
                             orig: None,
+                            raw_args: None,
                         });
                         continue;
                     }
@@ -125,6 +126,7 @@ impl ObjArch for ObjArchArm64 {
                 line,
                 formatted: ins.to_string(),
                 orig: Some(orig),
+                raw_args: None,
             });
         }
 
@@ -173,6 +175,33 @@ impl ObjArch for ObjArchArm64 {
             _ => Cow::Owned(format!("<{flags:?}>")),
         }
     }
+
+    fn data_reloc_size(&self, flags: RelocationFlags) -> Option<usize> {
+        match flags {
+            RelocationFlags::Elf { r_type: elf::R_AARCH64_ABS64 | elf::R_AARCH64_PREL64 }
+            | RelocationFlags::Coff { typ: pe::IMAGE_REL_ARM64_ADDR64 } => Some(8),
+            RelocationFlags::Elf { r_type: elf::R_AARCH64_ABS32 | elf::R_AARCH64_PREL32 }
+            | RelocationFlags::Coff {
+                typ:
+                    pe::IMAGE_REL_ARM64_ADDR32
+                    | pe::IMAGE_REL_ARM64_ADDR32NB
+                    | pe::IMAGE_REL_ARM64_REL32,
+            } => Some(4),
+            RelocationFlags::Elf { r_type: elf::R_AARCH64_ABS16 | elf::R_AARCH64_PREL16 } => {
+                Some(2)
+            }
+            _ => None,
+        }
+    }
+
+    fn instruction_hover(&self, ins: &ObjIns, _code: &[u8]) -> Vec<String> {
+        // Registers are named w0..w30 or x0..x30 (wzr/xzr) by their size
+        let bits = match bitfield::operands(ins).0.first() {
+            Some(reg) if reg.starts_with('x') => 64,
+            _ => 32,
+        };
+        bitfield::describe_arm(ins, bits).into_iter().collect()
+    }
 }
 
 struct DisplayCtx<'a> {
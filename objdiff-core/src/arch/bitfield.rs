@@ -0,0 +1,168 @@
+//! Helpers for describing bitfield instructions as C-like expressions, shown when hovering an
+//! instruction. Operands are passed as they're displayed, so the output matches the disassembly.
+
+use crate::obj::{ObjIns, ObjInsArg, ObjInsArgValue};
+
+/// Mask of `width` bits starting at bit 0.
+pub fn low_mask(width: u32) -> u64 {
+    if width >= 64 {
+        u64::MAX
+    } else {
+        (1 << width) - 1
+    }
+}
+
+fn signed_type(bits: u32) -> &'static str {
+    if bits == 64 {
+        "s64"
+    } else {
+        "s32"
+    }
+}
+
+/// `(src >> lsb) & mask`, omitting the shift or mask when they have no effect.
+fn unsigned_field(src: &str, lsb: u32, width: u32, bits: u32) -> String {
+    if lsb == 0 {
+        format!("{src} & {:#x}", low_mask(width))
+    } else if lsb + width >= bits {
+        format!("{src} >> {lsb}")
+    } else {
+        format!("({src} >> {lsb}) & {:#x}", low_mask(width))
+    }
+}
+
+/// `dst = (src >> lsb) & mask`, or its sign-extending equivalent.
+pub fn extract(dst: &str, src: &str, lsb: u32, width: u32, signed: bool, bits: u32) -> String {
+    if !signed {
+        return format!("{dst} = {}", unsigned_field(src, lsb, width, bits));
+    }
+    let ty = signed_type(bits);
+    let left = bits.saturating_sub(lsb + width);
+    let right = bits.saturating_sub(width);
+    if left == 0 {
+        format!("{dst} = ({ty}){src} >> {right}")
+    } else {
+        format!("{dst} = (({ty}){src} << {left}) >> {right}")
+    }
+}
+
+/// `dst = (src & mask) << lsb`, sign-extending the field first if `signed`.
+pub fn extract_shifted(
+    dst: &str,
+    src: &str,
+    lsb: u32,
+    width: u32,
+    signed: bool,
+    bits: u32,
+) -> String {
+    if signed {
+        let ty = signed_type(bits);
+        let left = bits.saturating_sub(width);
+        let right = left.saturating_sub(lsb);
+        format!("{dst} = (({ty}){src} << {left}) >> {right}")
+    } else if lsb + width >= bits {
+        format!("{dst} = {src} << {lsb}")
+    } else {
+        format!("{dst} = ({src} & {:#x}) << {lsb}", low_mask(width))
+    }
+}
+
+/// Replaces bits `lsb..lsb + width` of `dst` with the low bits of `src`.
+pub fn insert(dst: &str, src: &str, lsb: u32, width: u32) -> String {
+    let mask = low_mask(width);
+    let field = if lsb == 0 {
+        format!("({src} & {mask:#x})")
+    } else {
+        format!("(({src} & {mask:#x}) << {lsb})")
+    };
+    format!("{dst} = ({dst} & ~{:#x}) | {field}", mask << lsb)
+}
+
+/// Clears bits `lsb..lsb + width` of `dst`.
+pub fn clear(dst: &str, lsb: u32, width: u32) -> String {
+    format!("{dst} &= ~{:#x}", low_mask(width) << lsb)
+}
+
+/// Describes a 32-bit rotate left by `shift` followed by an AND with `mask`, using plain shifts
+/// when the mask discards the rotated-in bits.
+pub fn rotate_and_mask(src: &str, shift: &str, shift_imm: Option<u32>, mask: u32) -> String {
+    let Some(sh) = shift_imm else {
+        return if mask == u32::MAX {
+            format!("rotl({src}, {shift})")
+        } else {
+            format!("rotl({src}, {shift}) & {mask:#x}")
+        };
+    };
+    if sh == 0 {
+        return if mask == u32::MAX { src.to_string() } else { format!("{src} & {mask:#x}") };
+    }
+    let left_mask = u32::MAX << sh;
+    let right_mask = u32::MAX >> (32 - sh);
+    if mask & !left_mask == 0 {
+        // Only bits shifted left survive
+        if mask == left_mask {
+            format!("{src} << {sh}")
+        } else {
+            format!("({src} << {sh}) & {mask:#x}")
+        }
+    } else if mask & !right_mask == 0 {
+        // Only bits rotated around from the top survive
+        let right = 32 - sh;
+        if mask == right_mask {
+            format!("{src} >> {right}")
+        } else {
+            format!("({src} >> {right}) & {mask:#x}")
+        }
+    } else if mask == u32::MAX {
+        format!("rotl({src}, {sh})")
+    } else {
+        format!("rotl({src}, {sh}) & {mask:#x}")
+    }
+}
+
+/// Splits an instruction's arguments into register names and immediates, in display order.
+pub fn operands(ins: &ObjIns) -> (Vec<&str>, Vec<u32>) {
+    let mut regs = Vec::new();
+    let mut imms = Vec::new();
+    for arg in ins.iter_args() {
+        match arg {
+            ObjInsArg::Arg(ObjInsArgValue::Opaque(s)) => regs.push(s.as_ref()),
+            ObjInsArg::Arg(ObjInsArgValue::Unsigned(v)) => imms.push(*v as u32),
+            ObjInsArg::Arg(ObjInsArgValue::Signed(v)) => imms.push(*v as u32),
+            _ => {}
+        }
+    }
+    (regs, imms)
+}
+
+/// Describes the ARM bitfield instructions (ubfx, sbfx, bfi, bfc, and the AArch64 ubfiz, sbfiz
+/// and bfxil), which display their field as `#lsb, #width`. Condition code and `.w` suffixes are
+/// ignored.
+pub fn describe_arm(ins: &ObjIns, bits: u32) -> Option<String> {
+    const MNEMONICS: [&str; 7] = ["ubfx", "sbfx", "ubfiz", "sbfiz", "bfi", "bfxil", "bfc"];
+    let base = ins.mnemonic.split('.').next().unwrap_or_default();
+    let mnemonic = MNEMONICS
+        .into_iter()
+        .find(|m| base.strip_prefix(m).is_some_and(|cond| cond.is_empty() || cond.len() == 2))?;
+    let (regs, imms) = operands(ins);
+    let [lsb, width] = imms[..] else {
+        return None;
+    };
+    if width == 0 || lsb + width > bits {
+        return None;
+    }
+    Some(match (mnemonic, &regs[..]) {
+        ("bfc", [dst, ..]) => clear(dst, lsb, width),
+        ("ubfx", [dst, src, ..]) => extract(dst, src, lsb, width, false, bits),
+        ("sbfx", [dst, src, ..]) => extract(dst, src, lsb, width, true, bits),
+        ("ubfiz", [dst, src, ..]) => extract_shifted(dst, src, lsb, width, false, bits),
+        ("sbfiz", [dst, src, ..]) => extract_shifted(dst, src, lsb, width, true, bits),
+        ("bfi", [dst, src, ..]) => insert(dst, src, lsb, width),
+        ("bfxil", [dst, src, ..]) => {
+            // Copies bits lsb..lsb + width of src into the low bits of dst
+            let field = unsigned_field(src, lsb, width, bits);
+            format!("{dst} = ({dst} & ~{:#x}) | ({field})", low_mask(width))
+        }
+        _ => return None,
+    })
+}
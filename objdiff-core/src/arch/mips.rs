@@ -8,19 +8,61 @@ use object::{
 use rabbitizer::{config, Abi, InstrCategory, Instruction, OperandType};
 
 use crate::{
-    arch::{ObjArch, ProcessCodeResult},
-    diff::{DiffObjConfig, MipsAbi, MipsInstrCategory},
+    arch::{
+        bitfield, immediate_arg, register_arg, ObjArch, ProcessCodeResult, StackFrame,
+        PROLOGUE_LIMIT,
+    },
+    diff::{DiffObjConfig, MipsAbi, MipsFpRegisterName, MipsInstrCategory},
     obj::{ObjIns, ObjInsArg, ObjInsArgValue, ObjReloc, ObjSection},
 };
 
 static RABBITIZER_MUTEX: Mutex<()> = Mutex::new(());
 
-fn configure_rabbitizer(abi: Abi) {
+fn configure_rabbitizer(abi: Abi, pseudos: bool) {
     unsafe {
+        // GPRs keep their O32 names when the ABI is unknown
+        config::RabbitizerConfig_Cfg.reg_names.gpr_abi_names =
+            if matches!(abi, Abi::NUMERIC) { Abi::O32 } else { abi };
         config::RabbitizerConfig_Cfg.reg_names.fpr_abi_names = abi;
+        config::RabbitizerConfig_Cfg.pseudos.enable_pseudos = pseudos;
     }
 }
 
+/// Renames register 30 in disassembled text to the configured name.
+fn rename_fp_register(text: String, name: MipsFpRegisterName) -> String {
+    let (from, to) = match name {
+        MipsFpRegisterName::Fp => ("$s8", "$fp"),
+        MipsFpRegisterName::S8 => ("$fp", "$s8"),
+    };
+    if !text.contains(from) {
+        return text;
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text.as_str();
+    while let Some(pos) = rest.find(from) {
+        let end = pos + from.len();
+        let is_register = !rest[end..].starts_with(|c: char| c.is_ascii_alphanumeric());
+        out.push_str(&rest[..pos]);
+        out.push_str(if is_register { to } else { from });
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Decodes the instruction with pseudo-instructions disabled. Must be called with
+/// [`RABBITIZER_MUTEX`] held.
+fn decode_raw(code: u32, address: u32, instr_category: InstrCategory) -> Instruction {
+    unsafe {
+        config::RabbitizerConfig_Cfg.pseudos.enable_pseudos = false;
+    }
+    let instruction = Instruction::new(code, address, instr_category);
+    unsafe {
+        config::RabbitizerConfig_Cfg.pseudos.enable_pseudos = true;
+    }
+    instruction
+}
+
 pub struct ObjArchMips {
     pub endianness: Endianness,
     pub abi: Abi,
@@ -89,12 +131,15 @@ impl ObjArch for ObjArchMips {
         config: &DiffObjConfig,
     ) -> Result<ProcessCodeResult> {
         let _guard = RABBITIZER_MUTEX.lock().map_err(|e| anyhow!("Failed to lock mutex: {e}"))?;
-        configure_rabbitizer(match config.mips_abi {
-            MipsAbi::Auto => self.abi,
-            MipsAbi::O32 => Abi::O32,
-            MipsAbi::N32 => Abi::N32,
-            MipsAbi::N64 => Abi::N64,
-        });
+        configure_rabbitizer(
+            match config.mips_abi {
+                MipsAbi::Auto => self.abi,
+                MipsAbi::O32 => Abi::O32,
+                MipsAbi::N32 => Abi::N32,
+                MipsAbi::N64 => Abi::N64,
+            },
+            config.mips_pseudo_instructions,
+        );
         let instr_category = match config.mips_instr_category {
             MipsInstrCategory::Auto => self.instr_category,
             MipsInstrCategory::Cpu => InstrCategory::CPU,
@@ -115,88 +160,46 @@ impl ObjArch for ObjArchMips {
             let code = self.endianness.read_u32_bytes(chunk.try_into()?);
             let instruction = Instruction::new(code, cur_addr, instr_category);
 
-            let formatted = instruction.disassemble(None, 0);
-            let op = instruction.unique_id as u16;
-            ops.push(op);
-
-            let mnemonic = instruction.opcode_name();
-            let is_branch = instruction.is_branch();
-            let branch_offset = instruction.branch_offset();
-            let mut branch_dest = if is_branch {
-                cur_addr.checked_add_signed(branch_offset).map(|a| a as u64)
+            let fp_name = config.mips_fp_register_name;
+            let formatted = rename_fp_register(instruction.disassemble(None, 0), fp_name);
+            // The raw form, if the instruction is displayed as a pseudo-instruction
+            let raw = if config.mips_pseudo_instructions {
+                let raw = decode_raw(code, cur_addr, instr_category);
+                (raw.unique_id as u16 != instruction.unique_id as u16).then_some(raw)
             } else {
                 None
             };
+            // Always compare the raw opcode, so that pseudo-instructions decoded from different
+            // encodings (e.g. `move` from `or` or `addu`) aren't considered equal.
+            let op = raw.as_ref().unwrap_or(&instruction).unique_id as u16;
+            ops.push(op);
 
-            let operands = instruction.get_operands_slice();
-            let mut args = Vec::with_capacity(operands.len() + 1);
-            for (idx, op) in operands.iter().enumerate() {
-                if idx > 0 {
-                    args.push(ObjInsArg::PlainText(config.separator().into()));
-                }
-
-                match op {
-                    OperandType::cpu_immediate
-                    | OperandType::cpu_label
-                    | OperandType::cpu_branch_target_label => {
-                        if let Some(reloc) = reloc {
-                            // If the relocation target is within the current function, we can
-                            // convert it into a relative branch target. Note that we check
-                            // target_address > start_address instead of >= so that recursive
-                            // tail calls are not considered branch targets.
-                            let target_address =
-                                reloc.target.address.checked_add_signed(reloc.addend);
-                            if reloc.target.orig_section_index == Some(section_index)
-                                && matches!(target_address, Some(addr) if addr > start_address && addr < end_address)
-                            {
-                                let target_address = target_address.unwrap();
-                                args.push(ObjInsArg::BranchDest(target_address));
-                                branch_dest = Some(target_address);
-                            } else {
-                                push_reloc(&mut args, reloc)?;
-                                branch_dest = None;
-                            }
-                        } else if let Some(branch_dest) = branch_dest {
-                            args.push(ObjInsArg::BranchDest(branch_dest));
-                        } else {
-                            args.push(ObjInsArg::Arg(ObjInsArgValue::Opaque(
-                                op.disassemble(&instruction, None).into(),
-                            )));
-                        }
-                    }
-                    OperandType::cpu_immediate_base => {
-                        if let Some(reloc) = reloc {
-                            push_reloc(&mut args, reloc)?;
-                        } else {
-                            args.push(ObjInsArg::Arg(ObjInsArgValue::Opaque(
-                                OperandType::cpu_immediate.disassemble(&instruction, None).into(),
-                            )));
-                        }
-                        args.push(ObjInsArg::PlainText("(".into()));
-                        args.push(ObjInsArg::Arg(ObjInsArgValue::Opaque(
-                            OperandType::cpu_rs.disassemble(&instruction, None).into(),
-                        )));
-                        args.push(ObjInsArg::PlainText(")".into()));
-                    }
-                    // OperandType::r5900_immediate15 => match reloc {
-                    //     Some(reloc)
-                    //         if reloc.flags == RelocationFlags::Elf { r_type: R_MIPS15_S3 } =>
-                    //     {
-                    //         push_reloc(&mut args, reloc)?;
-                    //     }
-                    //     _ => {
-                    //         args.push(ObjInsArg::Arg(ObjInsArgValue::Opaque(
-                    //             op.disassemble(&instruction, None).into(),
-                    //         )));
-                    //     }
-                    // },
-                    _ => {
-                        args.push(ObjInsArg::Arg(ObjInsArgValue::Opaque(
-                            op.disassemble(&instruction, None).into(),
-                        )));
-                    }
-                }
-            }
+            let mnemonic = instruction.opcode_name();
+            let (args, branch_dest) = instruction_args(
+                &instruction,
+                cur_addr,
+                reloc,
+                section_index,
+                start_address,
+                end_address,
+                config,
+            )?;
+            // Keep the raw form's arguments, so that a pseudo-instruction can be compared against
+            // the raw instruction it stands for
+            let raw_args = raw
+                .map(|raw| {
+                    instruction_args(
+                        &raw,
+                        cur_addr,
+                        reloc,
+                        section_index,
+                        start_address,
+                        end_address,
+                        config,
+                    )
+                })
+                .transpose()?
+                .map(|(args, _)| args);
             let line = line_info.range(..=cur_addr as u64).last().map(|(_, &b)| b);
             insts.push(ObjIns {
                 address: cur_addr as u64,
@@ -209,6 +212,7 @@ impl ObjArch for ObjArchMips {
                 line,
                 formatted,
                 orig: None,
+                raw_args,
             });
             cur_addr += 4;
         }
@@ -253,6 +257,33 @@ impl ObjArch for ObjArchMips {
         })
     }
 
+    fn stack_frame(&self, insts: &[ObjIns]) -> Option<StackFrame> {
+        let mut frame = StackFrame::default();
+        for ins in insts.iter().take(PROLOGUE_LIMIT) {
+            let args = ins.iter_args().collect::<Vec<_>>();
+            match ins.mnemonic.as_ref() {
+                "addiu" | "daddiu"
+                    if register_arg(args.first()) == Some("sp")
+                        && register_arg(args.get(1)) == Some("sp") =>
+                {
+                    if let Some(offset) = immediate_arg(args.get(2)).filter(|&v| v < 0) {
+                        frame.size = Some(offset.unsigned_abs());
+                    }
+                }
+                "sw" | "sd" | "swc1" | "sdc1" if register_arg(args.get(2)) == Some("sp") => {
+                    if let Some(ObjInsArg::Arg(ObjInsArgValue::Opaque(register))) = args.first() {
+                        if mips_saved_register(register.trim_start_matches('$')) {
+                            frame.save(register);
+                        }
+                    }
+                }
+                mnemonic if mnemonic.starts_with(['b', 'j']) => break,
+                _ => {}
+            }
+        }
+        (!frame.is_empty()).then_some(frame)
+    }
+
     fn display_reloc(&self, flags: RelocationFlags) -> Cow<'static, str> {
         match flags {
             RelocationFlags::Elf { r_type } => match r_type {
@@ -271,6 +302,139 @@ impl ObjArch for ObjArchMips {
             _ => Cow::Owned(format!("<{flags:?}>")),
         }
     }
+
+    fn data_reloc_size(&self, flags: RelocationFlags) -> Option<usize> {
+        match flags {
+            RelocationFlags::Elf { r_type: elf::R_MIPS_64 } => Some(8),
+            RelocationFlags::Elf {
+                r_type: elf::R_MIPS_32 | elf::R_MIPS_REL32 | elf::R_MIPS_GPREL32,
+            } => Some(4),
+            RelocationFlags::Elf { r_type: elf::R_MIPS_16 } => Some(2),
+            _ => None,
+        }
+    }
+
+    fn instruction_hover(&self, ins: &ObjIns, code: &[u8]) -> Vec<String> {
+        let Ok(code) = <[u8; 4]>::try_from(code) else {
+            return Vec::new();
+        };
+        let code = self.endianness.read_u32_bytes(code);
+        let (regs, _) = bitfield::operands(ins);
+        let [rt, rs, ..] = regs[..] else {
+            return Vec::new();
+        };
+        // ext/ins encode the field as (msb or size - 1, lsb)
+        let msb = (code >> 11) & 0x1F;
+        let lsb = (code >> 6) & 0x1F;
+        let line = match ins.mnemonic.as_ref() {
+            "ext" => bitfield::extract(rt, rs, lsb, msb + 1, false, 32),
+            "dext" => bitfield::extract(rt, rs, lsb, msb + 1, false, 64),
+            "ins" | "dins" if msb >= lsb => bitfield::insert(rt, rs, lsb, msb + 1 - lsb),
+            _ => return Vec::new(),
+        };
+        vec![line]
+    }
+}
+
+/// Whether a register is callee-saved (or the return address), and so saved by prologues.
+fn mips_saved_register(register: &str) -> bool {
+    match register {
+        "ra" | "fp" | "gp" => true,
+        _ => {
+            let number = |prefix| register.strip_prefix(prefix).and_then(|n| n.parse::<u32>().ok());
+            number("s").is_some_and(|n| n <= 8)
+                || number("f").is_some_and(|n| (20..=31).contains(&n))
+        }
+    }
+}
+
+/// Builds the arguments of an instruction, replacing immediates with the relocation or branch
+/// destination. Returns the arguments and the branch destination, if any.
+fn instruction_args(
+    instruction: &Instruction,
+    cur_addr: u32,
+    reloc: Option<&ObjReloc>,
+    section_index: usize,
+    start_address: u64,
+    end_address: u64,
+    config: &DiffObjConfig,
+) -> Result<(Vec<ObjInsArg>, Option<u64>)> {
+    let fp_name = config.mips_fp_register_name;
+    let is_branch = instruction.is_branch();
+    let branch_offset = instruction.branch_offset();
+    let mut branch_dest =
+        if is_branch { cur_addr.checked_add_signed(branch_offset).map(|a| a as u64) } else { None };
+
+    let operands = instruction.get_operands_slice();
+    let mut args = Vec::with_capacity(operands.len() + 1);
+    for (idx, op) in operands.iter().enumerate() {
+        if idx > 0 {
+            args.push(ObjInsArg::PlainText(config.separator().into()));
+        }
+
+        match op {
+            OperandType::cpu_immediate
+            | OperandType::cpu_label
+            | OperandType::cpu_branch_target_label => {
+                if let Some(reloc) = reloc {
+                    // If the relocation target is within the current function, we can
+                    // convert it into a relative branch target. Note that we check
+                    // target_address > start_address instead of >= so that recursive
+                    // tail calls are not considered branch targets.
+                    let target_address = reloc.target.address.checked_add_signed(reloc.addend);
+                    if reloc.target.orig_section_index == Some(section_index)
+                        && matches!(target_address, Some(addr) if addr > start_address && addr < end_address)
+                    {
+                        let target_address = target_address.unwrap();
+                        args.push(ObjInsArg::BranchDest(target_address));
+                        branch_dest = Some(target_address);
+                    } else {
+                        push_reloc(&mut args, reloc)?;
+                        branch_dest = None;
+                    }
+                } else if let Some(branch_dest) = branch_dest {
+                    args.push(ObjInsArg::BranchDest(branch_dest));
+                } else {
+                    args.push(ObjInsArg::Arg(ObjInsArgValue::Opaque(
+                        op.disassemble(instruction, None).into(),
+                    )));
+                }
+            }
+            OperandType::cpu_immediate_base => {
+                if let Some(reloc) = reloc {
+                    push_reloc(&mut args, reloc)?;
+                } else {
+                    args.push(ObjInsArg::Arg(ObjInsArgValue::Opaque(
+                        OperandType::cpu_immediate.disassemble(instruction, None).into(),
+                    )));
+                }
+                args.push(ObjInsArg::PlainText("(".into()));
+                args.push(ObjInsArg::Arg(ObjInsArgValue::Opaque(
+                    rename_fp_register(OperandType::cpu_rs.disassemble(instruction, None), fp_name)
+                        .into(),
+                )));
+                args.push(ObjInsArg::PlainText(")".into()));
+            }
+            // OperandType::r5900_immediate15 => match reloc {
+            //     Some(reloc)
+            //         if reloc.flags == RelocationFlags::Elf { r_type: R_MIPS15_S3 } =>
+            //     {
+            //         push_reloc(&mut args, reloc)?;
+            //     }
+            //     _ => {
+            //         args.push(ObjInsArg::Arg(ObjInsArgValue::Opaque(
+            //             op.disassemble(instruction, None).into(),
+            //         )));
+            //     }
+            // },
+            _ => {
+                args.push(ObjInsArg::Arg(ObjInsArgValue::Opaque(
+                    rename_fp_register(op.disassemble(instruction, None), fp_name).into(),
+                )));
+            }
+        }
+    }
+    Ok((args, branch_dest))
 }
 
 fn push_reloc(args: &mut Vec<ObjInsArg>, reloc: &ObjReloc) -> Result<()> {
@@ -6,7 +6,7 @@ use object::{Architecture, File, Object, ObjectSymbol, Relocation, RelocationFla
 
 use crate::{
     diff::DiffObjConfig,
-    obj::{ObjIns, ObjReloc, ObjSection},
+    obj::{ObjIns, ObjInsArg, ObjReloc, ObjSection},
     util::ReallySigned,
 };
 
@@ -14,14 +14,19 @@ use crate::{
 mod arm;
 #[cfg(feature = "arm64")]
 mod arm64;
+#[cfg(any(feature = "arm", feature = "arm64", feature = "mips", feature = "ppc"))]
+pub mod bitfield;
 #[cfg(feature = "mips")]
 pub mod mips;
 #[cfg(feature = "ppc")]
 pub mod ppc;
+#[cfg(feature = "riscv")]
+pub mod riscv;
 #[cfg(feature = "x86")]
 pub mod x86;
 
 /// Represents the type of data associated with an instruction
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum DataType {
     Int8,
     Int16,
@@ -110,6 +115,21 @@ impl DataType {
         .into()
     }
 
+    /// A short C-like name for the type, e.g. `u16` or `f32`.
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            DataType::Int8 => "u8",
+            DataType::Int16 => "u16",
+            DataType::Int32 => "u32",
+            DataType::Int64 => "u64",
+            DataType::Int128 => "u128",
+            DataType::Float => "f32",
+            DataType::Double => "f64",
+            DataType::Bytes => "bytes",
+            DataType::String => "string",
+        }
+    }
+
     fn required_len(&self) -> Option<usize> {
         match self {
             DataType::Int8 => Some(1),
@@ -148,19 +168,165 @@ pub trait ObjArch: Send + Sync {
 
     fn display_reloc(&self, flags: RelocationFlags) -> Cow<'static, str>;
 
+    /// Whether a relocation is skipped when loading, because it doesn't modify the instruction
+    /// or data it's placed on (e.g. linker relaxation hints).
+    fn ignore_reloc(&self, _flags: RelocationFlags) -> bool { false }
+
+    /// The size in bytes of the value a relocation writes into data (e.g. a pointer), if known.
+    fn data_reloc_size(&self, _flags: RelocationFlags) -> Option<usize> { None }
+
     fn symbol_address(&self, symbol: &Symbol) -> u64 { symbol.address() }
 
     fn guess_data_type(&self, _instruction: &ObjIns) -> Option<DataType> { None }
 
+    /// Whether an argument may differ between otherwise identical instructions without changing
+    /// their behavior. Such instructions are reported as
+    /// [`ObjInsDiffKind::SoftMismatch`](crate::diff::ObjInsDiffKind::SoftMismatch).
+    fn is_soft_arg(&self, _arg: &ObjInsArg, _config: &DiffObjConfig) -> bool { false }
+
+    /// Additional lines describing an instruction, shown on hover (e.g. bitfield instructions
+    /// as C-like expressions). `code` is the raw instruction.
+    fn instruction_hover(&self, _ins: &ObjIns, _code: &[u8]) -> Vec<String> { Vec::new() }
+
+    /// Maps an opcode to an equivalence class used when aligning instructions, so that
+    /// instructions which commonly replace each other (e.g. `add` and `addi`, or different forms
+    /// of `mov`) are aligned before their arguments are compared. Defaults to the opcode itself.
+    fn opcode_class(&self, op: u16) -> u16 { op }
+
     fn display_data_type(&self, _ty: DataType, bytes: &[u8]) -> Option<String> {
         Some(format!("Bytes: {:#x?}", bytes))
     }
 
+    /// Parses the stack frame set up by a function's prologue.
+    fn stack_frame(&self, _insts: &[ObjIns]) -> Option<StackFrame> { None }
+
     // Downcast methods
     #[cfg(feature = "ppc")]
     fn ppc(&self) -> Option<&ppc::ObjArchPpc> { None }
 }
 
+/// Instructions scanned for a function's prologue, in case it doesn't end with a branch.
+#[cfg(any(feature = "mips", feature = "ppc", feature = "x86"))]
+const PROLOGUE_LIMIT: usize = 64;
+
+/// A function's stack frame, as set up by its prologue.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct StackFrame {
+    /// Frame size in bytes, if the prologue allocates one
+    pub size: Option<u64>,
+    /// Registers saved by the prologue, in the order they're saved
+    pub saved_registers: Vec<String>,
+}
+
+impl StackFrame {
+    pub fn save(&mut self, register: &str) {
+        if !self.saved_registers.iter().any(|r| r == register) {
+            self.saved_registers.push(register.to_string());
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.size.is_none() && self.saved_registers.is_empty() }
+
+    /// The saved registers, sorted by number and with consecutive runs collapsed (e.g.
+    /// `r28-r31, f31`). Register classes are listed in the order they're first saved.
+    pub fn saved_registers_display(&self) -> String {
+        let split = |r: &str| {
+            let digits = r.len() - r.bytes().rev().take_while(u8::is_ascii_digit).count();
+            (r[..digits].to_string(), r[digits..].parse::<u32>().ok())
+        };
+        let mut classes = Vec::<String>::new();
+        let mut registers = self
+            .saved_registers
+            .iter()
+            .map(|r| {
+                let (class, number) = split(r);
+                let class_idx = classes.iter().position(|c| *c == class).unwrap_or_else(|| {
+                    classes.push(class);
+                    classes.len() - 1
+                });
+                (class_idx, number, r.as_str())
+            })
+            .collect::<Vec<_>>();
+        registers.sort_by_key(|&(class_idx, number, _)| (class_idx, number));
+
+        let mut out = Vec::<String>::new();
+        let mut i = 0;
+        while i < registers.len() {
+            let (class_idx, number, first) = registers[i];
+            let mut end = i;
+            if let Some(number) = number {
+                while registers.get(end + 1).is_some_and(|&(c, n, _)| {
+                    c == class_idx && n == Some(number + (end + 1 - i) as u32)
+                }) {
+                    end += 1;
+                }
+            }
+            if end > i {
+                out.push(format!("{first}-{}", registers[end].2));
+            } else {
+                out.push(first.to_string());
+            }
+            i = end + 1;
+        }
+        out.join(", ")
+    }
+
+    /// A compact comparison with the other side's frame, e.g. `frame 0x40 vs 0x38, saves
+    /// r28-r31 vs r29-r31`. Values that are the same are only listed once.
+    pub fn compare(&self, other: &StackFrame) -> String {
+        let size =
+            |f: &StackFrame| f.size.map_or_else(|| "none".to_string(), |s| format!("{s:#x}"));
+        let saves = |f: &StackFrame| {
+            let saves = f.saved_registers_display();
+            if saves.is_empty() {
+                "nothing".to_string()
+            } else {
+                saves
+            }
+        };
+        let pair = |l: String, r: String| if l == r { l } else { format!("{l} vs {r}") };
+        format!(
+            "frame {}, saves {}",
+            pair(size(self), size(other)),
+            pair(saves(self), saves(other))
+        )
+    }
+}
+
+/// The register name of an argument, without any sigil (e.g. `$sp` or `%esp`).
+#[cfg(any(feature = "mips", feature = "ppc", feature = "x86"))]
+fn register_arg<'a>(arg: Option<&&'a ObjInsArg>) -> Option<&'a str> {
+    match arg {
+        Some(ObjInsArg::Arg(crate::obj::ObjInsArgValue::Opaque(s))) => {
+            Some(s.trim_start_matches(['$', '%']))
+        }
+        _ => None,
+    }
+}
+
+/// The value of an immediate argument, which may be formatted as text.
+#[cfg(any(feature = "mips", feature = "ppc", feature = "x86"))]
+fn immediate_arg(arg: Option<&&ObjInsArg>) -> Option<i64> {
+    use crate::obj::ObjInsArgValue;
+    match arg {
+        Some(ObjInsArg::Arg(ObjInsArgValue::Signed(v))) => Some(*v),
+        Some(ObjInsArg::Arg(ObjInsArgValue::Unsigned(v))) => Some(*v as i64),
+        Some(ObjInsArg::Arg(ObjInsArgValue::Opaque(s))) => {
+            let (negative, s) = match s.strip_prefix('-') {
+                Some(s) => (true, s),
+                None => (false, s.as_ref()),
+            };
+            let value = match s.strip_prefix("0x") {
+                Some(hex) => i64::from_str_radix(hex, 16).ok()?,
+                None => s.parse::<i64>().ok()?,
+            };
+            Some(if negative { -value } else { value })
+        }
+        _ => None,
+    }
+}
+
 pub struct ProcessCodeResult {
     pub ops: Vec<u16>,
     pub insts: Vec<ObjIns>,
@@ -178,6 +344,10 @@ pub fn new_arch(object: &File) -> Result<Box<dyn ObjArch>> {
         Architecture::Arm => Box::new(arm::ObjArchArm::new(object)?),
         #[cfg(feature = "arm64")]
         Architecture::Aarch64 => Box::new(arm64::ObjArchArm64::new(object)?),
+        #[cfg(feature = "riscv")]
+        Architecture::Riscv32 | Architecture::Riscv64 => {
+            Box::new(riscv::ObjArchRiscv::new(object)?)
+        }
         arch => bail!("Unsupported architecture: {arch:?}"),
     })
 }
@@ -13,8 +13,11 @@ use object::{
 use ppc750cl::{Argument, InsIter, Opcode, ParsedIns, GPR};
 
 use crate::{
-    arch::{DataType, ObjArch, ProcessCodeResult},
-    diff::DiffObjConfig,
+    arch::{
+        bitfield, immediate_arg, register_arg, DataType, ObjArch, ProcessCodeResult, StackFrame,
+        PROLOGUE_LIMIT,
+    },
+    diff::{DiffObjConfig, PpcCrFieldMismatch},
     obj::{ObjIns, ObjInsArg, ObjInsArgValue, ObjReloc, ObjSection, ObjSymbol},
 };
 
@@ -30,6 +33,11 @@ fn is_rel_abs_arg(arg: &Argument) -> bool {
 
 fn is_offset_arg(arg: &Argument) -> bool { matches!(arg, Argument::Offset(_)) }
 
+// Condition register field, e.g. cr7
+fn is_cr_field(arg: &str) -> bool {
+    arg.strip_prefix("cr").is_some_and(|n| matches!(n.as_bytes(), [b'0'..=b'7']))
+}
+
 pub struct ObjArchPpc {
     /// Exception info
     pub extab: Option<BTreeMap<usize, ExceptionInfo>>,
@@ -58,15 +66,9 @@ impl ObjArch for ObjArchPpc {
             let reloc = relocations.iter().find(|r| (r.address as u32 & !3) == cur_addr);
             if let Some(reloc) = reloc {
                 // Zero out relocations
-                ins.code = match reloc.flags {
-                    RelocationFlags::Elf { r_type: elf::R_PPC_EMB_SDA21 } => ins.code & !0x1FFFFF,
-                    RelocationFlags::Elf { r_type: elf::R_PPC_REL24 } => ins.code & !0x3FFFFFC,
-                    RelocationFlags::Elf { r_type: elf::R_PPC_REL14 } => ins.code & !0xFFFC,
-                    RelocationFlags::Elf {
-                        r_type: elf::R_PPC_ADDR16_HI | elf::R_PPC_ADDR16_HA | elf::R_PPC_ADDR16_LO,
-                    } => ins.code & !0xFFFF,
-                    _ => ins.code,
-                };
+                if let RelocationFlags::Elf { r_type } = reloc.flags {
+                    ins.code &= !reloc_mask(r_type);
+                }
             }
 
             let orig = ins.basic().to_string();
@@ -79,12 +81,22 @@ impl ObjArch for ObjArchPpc {
                     RelocationFlags::Elf { r_type: elf::R_PPC_EMB_SDA21 } => {
                         reloc_arg = Some(1);
                     }
-                    RelocationFlags::Elf { r_type: elf::R_PPC_REL24 | elf::R_PPC_REL14 } => {
-                        reloc_arg = simplified.args.iter().rposition(is_relative_arg);
-                    }
                     RelocationFlags::Elf {
-                        r_type: elf::R_PPC_ADDR16_HI | elf::R_PPC_ADDR16_HA | elf::R_PPC_ADDR16_LO,
+                        r_type:
+                            elf::R_PPC_REL24
+                            | elf::R_PPC_PLTREL24
+                            | elf::R_PPC_LOCAL24PC
+                            | elf::R_PPC_ADDR24
+                            | elf::R_PPC_REL14
+                            | elf::R_PPC_REL14_BRTAKEN
+                            | elf::R_PPC_REL14_BRNTAKEN
+                            | elf::R_PPC_ADDR14
+                            | elf::R_PPC_ADDR14_BRTAKEN
+                            | elf::R_PPC_ADDR14_BRNTAKEN,
                     } => {
+                        reloc_arg = simplified.args.iter().rposition(is_relative_arg);
+                    }
+                    RelocationFlags::Elf { r_type } if reloc_mask(r_type) == 0xFFFF => {
                         reloc_arg = simplified.args.iter().rposition(is_rel_abs_arg);
                     }
                     _ => {}
@@ -143,6 +155,16 @@ impl ObjArch for ObjArchPpc {
                 }
             }
 
+            if config.ppc_gqr_annotations {
+                if let Some(access) = decode_psq(ins.code) {
+                    if let Some(&value) = config.ppc_gqrs.get(access.gqr as usize) {
+                        args.push(ObjInsArg::PlainText(
+                            format!("  # {}", describe_gqr(value, access.load)).into(),
+                        ));
+                    }
+                }
+            }
+
             ops.push(ins.op as u16);
             let line = line_info.range(..=cur_addr as u64).last().map(|(_, &b)| b);
             insts.push(ObjIns {
@@ -156,6 +178,7 @@ impl ObjArch for ObjArchPpc {
                 line,
                 formatted,
                 orig: Some(orig),
+                raw_args: None,
             });
         }
         Ok(ProcessCodeResult { ops, insts })
@@ -171,6 +194,58 @@ impl ObjArch for ObjArchPpc {
         bail!("Unsupported PPC implicit relocation {:#x}:{:?}", address, reloc.flags())
     }
 
+    fn opcode_class(&self, op: u16) -> u16 {
+        // `add`/`addi` and `or`/`ori` are often swapped depending on register allocation
+        match Opcode::from(op as u8) {
+            Opcode::Addi => Opcode::Add as u16,
+            Opcode::Ori => Opcode::Or as u16,
+            _ => op,
+        }
+    }
+
+    fn stack_frame(&self, insts: &[ObjIns]) -> Option<StackFrame> {
+        let mut frame = StackFrame::default();
+        for ins in insts.iter().take(PROLOGUE_LIMIT) {
+            let args = ins.iter_args().collect::<Vec<_>>();
+            let stack_relative = register_arg(args.get(2)) == Some("r1");
+            match ins.mnemonic.as_ref() {
+                "stwu" if stack_relative && register_arg(args.first()) == Some("r1") => {
+                    frame.size = immediate_arg(args.get(1)).map(i64::unsigned_abs);
+                }
+                "stw" | "stfd" | "psq_st" if stack_relative => {
+                    if let Some(register) =
+                        register_arg(args.first()).filter(|r| ppc_nonvolatile(r).is_some())
+                    {
+                        frame.save(register);
+                    }
+                }
+                "stmw" if stack_relative => {
+                    if let Some(first) = register_arg(args.first()).and_then(ppc_nonvolatile) {
+                        (first..=31).for_each(|n| frame.save(&format!("r{n}")));
+                    }
+                }
+                "bl" => {
+                    // Register saves may be outlined into runtime helpers
+                    let target = ins.reloc.as_ref().map_or("", |r| r.target.name.as_str());
+                    let (class, first) = if let Some(n) = target.strip_prefix("_savegpr_") {
+                        ("r", n)
+                    } else if let Some(n) = target.strip_prefix("_savefpr_") {
+                        ("f", n)
+                    } else {
+                        break;
+                    };
+                    let first = first.trim_end_matches(|c: char| !c.is_ascii_digit());
+                    if let Ok(first) = first.parse::<u32>() {
+                        (first..=31).for_each(|n| frame.save(&format!("{class}{n}")));
+                    }
+                }
+                mnemonic if mnemonic.starts_with('b') => break,
+                _ => {}
+            }
+        }
+        (!frame.is_empty()).then_some(frame)
+    }
+
     fn demangle(&self, name: &str) -> Option<String> {
         cwdemangle::demangle(name, &cwdemangle::DemangleOptions::default())
     }
@@ -178,21 +253,51 @@ impl ObjArch for ObjArchPpc {
     fn display_reloc(&self, flags: RelocationFlags) -> Cow<'static, str> {
         match flags {
             RelocationFlags::Elf { r_type } => match r_type {
-                elf::R_PPC_NONE => Cow::Borrowed("R_PPC_NONE"), // We use this for fake pool relocs
+                elf::R_PPC_NONE => Cow::Borrowed("R_PPC_NONE"), // Pseudo relocations
+                elf::R_PPC_ADDR32 => Cow::Borrowed("R_PPC_ADDR32"),
+                elf::R_PPC_ADDR24 => Cow::Borrowed("R_PPC_ADDR24"),
+                elf::R_PPC_ADDR16 => Cow::Borrowed("R_PPC_ADDR16"),
                 elf::R_PPC_ADDR16_LO => Cow::Borrowed("R_PPC_ADDR16_LO"),
                 elf::R_PPC_ADDR16_HI => Cow::Borrowed("R_PPC_ADDR16_HI"),
                 elf::R_PPC_ADDR16_HA => Cow::Borrowed("R_PPC_ADDR16_HA"),
-                elf::R_PPC_EMB_SDA21 => Cow::Borrowed("R_PPC_EMB_SDA21"),
-                elf::R_PPC_ADDR32 => Cow::Borrowed("R_PPC_ADDR32"),
-                elf::R_PPC_UADDR32 => Cow::Borrowed("R_PPC_UADDR32"),
+                elf::R_PPC_ADDR14 => Cow::Borrowed("R_PPC_ADDR14"),
+                elf::R_PPC_ADDR14_BRTAKEN => Cow::Borrowed("R_PPC_ADDR14_BRTAKEN"),
+                elf::R_PPC_ADDR14_BRNTAKEN => Cow::Borrowed("R_PPC_ADDR14_BRNTAKEN"),
                 elf::R_PPC_REL24 => Cow::Borrowed("R_PPC_REL24"),
                 elf::R_PPC_REL14 => Cow::Borrowed("R_PPC_REL14"),
+                elf::R_PPC_REL14_BRTAKEN => Cow::Borrowed("R_PPC_REL14_BRTAKEN"),
+                elf::R_PPC_REL14_BRNTAKEN => Cow::Borrowed("R_PPC_REL14_BRNTAKEN"),
+                elf::R_PPC_GOT16 => Cow::Borrowed("R_PPC_GOT16"),
+                elf::R_PPC_GOT16_LO => Cow::Borrowed("R_PPC_GOT16_LO"),
+                elf::R_PPC_GOT16_HI => Cow::Borrowed("R_PPC_GOT16_HI"),
+                elf::R_PPC_GOT16_HA => Cow::Borrowed("R_PPC_GOT16_HA"),
+                elf::R_PPC_PLTREL24 => Cow::Borrowed("R_PPC_PLTREL24"),
+                elf::R_PPC_LOCAL24PC => Cow::Borrowed("R_PPC_LOCAL24PC"),
+                elf::R_PPC_UADDR32 => Cow::Borrowed("R_PPC_UADDR32"),
+                elf::R_PPC_UADDR16 => Cow::Borrowed("R_PPC_UADDR16"),
+                elf::R_PPC_REL32 => Cow::Borrowed("R_PPC_REL32"),
+                elf::R_PPC_SDAREL16 => Cow::Borrowed("R_PPC_SDAREL16"),
+                elf::R_PPC_EMB_SDA21 => Cow::Borrowed("R_PPC_EMB_SDA21"),
+                elf::R_PPC_REL16 => Cow::Borrowed("R_PPC_REL16"),
+                elf::R_PPC_REL16_LO => Cow::Borrowed("R_PPC_REL16_LO"),
+                elf::R_PPC_REL16_HI => Cow::Borrowed("R_PPC_REL16_HI"),
+                elf::R_PPC_REL16_HA => Cow::Borrowed("R_PPC_REL16_HA"),
                 _ => Cow::Owned(format!("<{flags:?}>")),
             },
             _ => Cow::Owned(format!("<{flags:?}>")),
         }
     }
 
+    fn data_reloc_size(&self, flags: RelocationFlags) -> Option<usize> {
+        match flags {
+            RelocationFlags::Elf {
+                r_type: elf::R_PPC_ADDR32 | elf::R_PPC_UADDR32 | elf::R_PPC_REL32,
+            } => Some(4),
+            RelocationFlags::Elf { r_type: elf::R_PPC_ADDR16 | elf::R_PPC_UADDR16 } => Some(2),
+            _ => None,
+        }
+    }
+
     fn guess_data_type(&self, instruction: &ObjIns) -> Option<super::DataType> {
         if instruction.reloc.as_ref().is_some_and(|r| r.target.name.starts_with("@stringBase")) {
             return Some(DataType::String);
@@ -217,41 +322,208 @@ impl ObjArch for ObjArchPpc {
         ty.display_bytes::<BigEndian>(bytes)
     }
 
+    fn is_soft_arg(&self, arg: &ObjInsArg, config: &DiffObjConfig) -> bool {
+        config.ppc_cr_field_mismatch != PpcCrFieldMismatch::Mismatch
+            && matches!(arg, ObjInsArg::Arg(ObjInsArgValue::Opaque(s)) if is_cr_field(s))
+    }
+
+    fn instruction_hover(&self, _ins: &ObjIns, code: &[u8]) -> Vec<String> {
+        let Ok(code) = <[u8; 4]>::try_from(code) else {
+            return Vec::new();
+        };
+        let code = u32::from_be_bytes(code);
+        decode_rotate(code).into_iter().chain(psq_hover(code)).collect()
+    }
+
     fn ppc(&self) -> Option<&ObjArchPpc> { Some(self) }
 }
 
+/// A paired-single quantized load or store: `psq_l`, `psq_st` and their update and indexed
+/// forms.
+struct PsqAccess {
+    load: bool,
+    /// Only ps0 is transferred (W bit)
+    single: bool,
+    /// Index of the graphics quantization register (GQR) selecting the type and scale
+    gqr: u32,
+}
+
+fn decode_psq(code: u32) -> Option<PsqAccess> {
+    let (load, w, i) = match code >> 26 {
+        // psq_l, psq_lu
+        56 | 57 => (true, (code >> 15) & 1, (code >> 12) & 7),
+        // psq_st, psq_stu
+        60 | 61 => (false, (code >> 15) & 1, (code >> 12) & 7),
+        4 => match (code >> 1) & 0x3F {
+            // psq_lx, psq_lux
+            6 | 38 => (true, (code >> 10) & 1, (code >> 7) & 7),
+            // psq_stx, psq_stux
+            7 | 39 => (false, (code >> 10) & 1, (code >> 7) & 7),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(PsqAccess { load, single: w != 0, gqr: i })
+}
+
+/// Describes the load or store half of a GQR value as a type and scale.
+fn describe_gqr(value: u32, load: bool) -> String {
+    let (ty, scale) = if load { (value >> 16, value >> 24) } else { (value, value >> 8) };
+    // The scale is a 6-bit signed exponent: loads multiply by 2^-scale, stores by 2^scale
+    let scale = (((scale & 0x3F) << 2) as u8 as i8) >> 2;
+    let ty = match ty & 7 {
+        0 => return "f32".to_string(),
+        4 => "u8",
+        5 => "u16",
+        6 => "s8",
+        7 => "s16",
+        _ => return format!("reserved type {}", ty & 7),
+    };
+    match scale {
+        0 => ty.to_string(),
+        _ => format!("{ty}, scale {scale}"),
+    }
+}
+
+/// GQR values set up by the Dolphin/Revolution SDK (`OSInitFastCast`), used when the project
+/// doesn't supply its own. GQR0 is always used for unquantized floats.
+const COMMON_GQRS: [(u32, u32, &str); 5] = [
+    (0, 0x00000000, "f32"),
+    (2, 0x00040004, "u8 (OSInitFastCast)"),
+    (3, 0x00050005, "u16 (OSInitFastCast)"),
+    (4, 0x00060006, "s8 (OSInitFastCast)"),
+    (5, 0x00070007, "s16 (OSInitFastCast)"),
+];
+
+fn psq_hover(code: u32) -> Vec<String> {
+    let Some(access) = decode_psq(code) else {
+        return Vec::new();
+    };
+    let mut lines = vec![format!(
+        "{} GQR{}, {}",
+        if access.load { "Dequantizes with" } else { "Quantizes with" },
+        access.gqr,
+        if access.single { "ps0 only" } else { "ps0 and ps1" }
+    )];
+    if let Some((_, value, name)) = COMMON_GQRS.iter().find(|(i, _, _)| *i == access.gqr) {
+        lines.push(format!("Common setup: {name}, GQR{} = {value:#010x}", access.gqr));
+    } else {
+        lines.push(format!("GQR{} has no common setup", access.gqr));
+    }
+    lines
+}
+
+/// Mask from bit `mb` to bit `me` inclusive (big-endian bit numbering), wrapping if `mb > me`.
+fn rotate_mask(mb: u32, me: u32) -> u32 {
+    let begin = u32::MAX >> mb;
+    let end = u32::MAX << (31 - me);
+    if mb <= me {
+        begin & end
+    } else {
+        begin | end
+    }
+}
+
+/// Describes rlwinm, rlwimi and rlwnm as C-like expressions, regardless of the simplified
+/// mnemonic (slwi, clrlwi, extrwi, etc.) used to display them.
+fn decode_rotate(code: u32) -> Option<String> {
+    let rs = format!("r{}", (code >> 21) & 0x1F);
+    let ra = format!("r{}", (code >> 16) & 0x1F);
+    let sh = (code >> 11) & 0x1F;
+    let mask = rotate_mask((code >> 6) & 0x1F, (code >> 1) & 0x1F);
+    match code >> 26 {
+        // rlwimi
+        20 => {
+            let rotated = bitfield::rotate_and_mask(&rs, "", Some(sh), mask);
+            Some(format!("{ra} = ({ra} & ~{mask:#x}) | ({rotated})"))
+        }
+        // rlwinm
+        21 => Some(format!("{ra} = {}", bitfield::rotate_and_mask(&rs, "", Some(sh), mask))),
+        // rlwnm
+        23 => {
+            let rb = format!("r{sh}");
+            Some(format!("{ra} = {}", bitfield::rotate_and_mask(&rs, &rb, None, mask)))
+        }
+        _ => None,
+    }
+}
+
 impl ObjArchPpc {
     pub fn extab_for_symbol(&self, symbol: &ObjSymbol) -> Option<&ExceptionInfo> {
         symbol.original_index.and_then(|i| self.extab.as_ref()?.get(&i))
     }
 }
 
+/// Returns the bits of an instruction that are filled in by a relocation.
+fn reloc_mask(r_type: u32) -> u32 {
+    match r_type {
+        elf::R_PPC_EMB_SDA21 => 0x1FFFFF,
+        elf::R_PPC_REL24 | elf::R_PPC_PLTREL24 | elf::R_PPC_LOCAL24PC | elf::R_PPC_ADDR24 => {
+            0x3FFFFFC
+        }
+        elf::R_PPC_REL14
+        | elf::R_PPC_REL14_BRTAKEN
+        | elf::R_PPC_REL14_BRNTAKEN
+        | elf::R_PPC_ADDR14
+        | elf::R_PPC_ADDR14_BRTAKEN
+        | elf::R_PPC_ADDR14_BRNTAKEN => 0xFFFC,
+        elf::R_PPC_ADDR16
+        | elf::R_PPC_ADDR16_LO
+        | elf::R_PPC_ADDR16_HI
+        | elf::R_PPC_ADDR16_HA
+        | elf::R_PPC_GOT16
+        | elf::R_PPC_GOT16_LO
+        | elf::R_PPC_GOT16_HI
+        | elf::R_PPC_GOT16_HA
+        | elf::R_PPC_SDAREL16
+        | elf::R_PPC_REL16
+        | elf::R_PPC_REL16_LO
+        | elf::R_PPC_REL16_HI
+        | elf::R_PPC_REL16_HA => 0xFFFF,
+        _ => 0,
+    }
+}
+
 fn push_reloc(args: &mut Vec<ObjInsArg>, reloc: &ObjReloc) -> Result<()> {
-    match reloc.flags {
-        RelocationFlags::Elf { r_type } => match r_type {
-            elf::R_PPC_ADDR16_LO => {
-                args.push(ObjInsArg::Reloc);
-                args.push(ObjInsArg::PlainText("@l".into()));
-            }
-            elf::R_PPC_ADDR16_HI => {
-                args.push(ObjInsArg::Reloc);
-                args.push(ObjInsArg::PlainText("@h".into()));
-            }
-            elf::R_PPC_ADDR16_HA => {
-                args.push(ObjInsArg::Reloc);
-                args.push(ObjInsArg::PlainText("@ha".into()));
-            }
-            elf::R_PPC_EMB_SDA21 => {
-                args.push(ObjInsArg::Reloc);
-                args.push(ObjInsArg::PlainText("@sda21".into()));
-            }
-            elf::R_PPC_ADDR32 | elf::R_PPC_UADDR32 | elf::R_PPC_REL24 | elf::R_PPC_REL14 => {
-                args.push(ObjInsArg::Reloc);
-            }
-            _ => bail!("Unsupported ELF PPC relocation type {r_type}"),
-        },
-        flags => bail!("Unsupported PPC relocation kind: {flags:?}"),
+    let RelocationFlags::Elf { r_type } = reloc.flags else {
+        bail!("Unsupported PPC relocation kind: {:?}", reloc.flags);
+    };
+    let suffix = match r_type {
+        elf::R_PPC_ADDR16_LO => "@l",
+        elf::R_PPC_ADDR16_HI => "@h",
+        elf::R_PPC_ADDR16_HA => "@ha",
+        elf::R_PPC_EMB_SDA21 => "@sda21",
+        elf::R_PPC_SDAREL16 => "@sdarel",
+        elf::R_PPC_GOT16 => "@got",
+        elf::R_PPC_GOT16_LO => "@got@l",
+        elf::R_PPC_GOT16_HI => "@got@h",
+        elf::R_PPC_GOT16_HA => "@got@ha",
+        elf::R_PPC_PLTREL24 => "@plt",
+        // PC-relative halves, as emitted by GCC for -fPIC GOT pointer setup
+        elf::R_PPC_REL16 => "-.",
+        elf::R_PPC_REL16_LO => "-.@l",
+        elf::R_PPC_REL16_HI => "-.@h",
+        elf::R_PPC_REL16_HA => "-.@ha",
+        elf::R_PPC_ADDR32
+        | elf::R_PPC_UADDR32
+        | elf::R_PPC_ADDR24
+        | elf::R_PPC_ADDR16
+        | elf::R_PPC_UADDR16
+        | elf::R_PPC_ADDR14
+        | elf::R_PPC_ADDR14_BRTAKEN
+        | elf::R_PPC_ADDR14_BRNTAKEN
+        | elf::R_PPC_REL32
+        | elf::R_PPC_REL24
+        | elf::R_PPC_LOCAL24PC
+        | elf::R_PPC_REL14
+        | elf::R_PPC_REL14_BRTAKEN
+        | elf::R_PPC_REL14_BRNTAKEN => "",
+        _ => bail!("Unsupported ELF PPC relocation type {r_type}"),
     };
+    args.push(ObjInsArg::Reloc);
+    if !suffix.is_empty() {
+        args.push(ObjInsArg::PlainText(suffix.into()));
+    }
     Ok(())
 }
 
@@ -384,6 +656,12 @@ fn make_symbol_ref(symbol: &Symbol) -> Result<ExtabSymbolRef> {
     Ok(ExtabSymbolRef { original_index: symbol.index().0, name, demangled_name })
 }
 
+/// The number of a non-volatile GPR or FPR (r14-r31, f14-f31).
+fn ppc_nonvolatile(register: &str) -> Option<u32> {
+    let number = register.strip_prefix(['r', 'f'])?.parse::<u32>().ok()?;
+    (14..=31).contains(&number).then_some(number)
+}
+
 fn guess_data_type_from_load_store_inst_op(inst_op: Opcode) -> Option<DataType> {
     match inst_op {
         Opcode::Lbz | Opcode::Lbzu | Opcode::Lbzux | Opcode::Lbzx => Some(DataType::Int8),
@@ -490,6 +768,8 @@ fn make_fake_pool_reloc(offset: i16, cur_addr: u32, pool_reloc: &ObjReloc) -> Op
         address: cur_addr as u64,
         target: fake_target_symbol,
         addend: fake_addend,
+        original: None,
+        pseudo: true,
     })
 }
 
@@ -0,0 +1,1179 @@
+use std::{borrow::Cow, collections::BTreeMap};
+
+use anyhow::{bail, Result};
+use byteorder::LittleEndian;
+use object::{elf, File, Object, Relocation, RelocationFlags};
+
+use crate::{
+    arch::{DataType, ObjArch, ProcessCodeResult},
+    diff::DiffObjConfig,
+    obj::{ObjIns, ObjInsArg, ObjInsArgValue, ObjReloc, ObjSection},
+};
+
+pub struct ObjArchRiscv {
+    /// Register width, 32 or 64
+    xlen: u32,
+}
+
+impl ObjArchRiscv {
+    pub fn new(file: &File) -> Result<Self> {
+        Ok(Self { xlen: if file.is_64() { 64 } else { 32 } })
+    }
+}
+
+impl ObjArch for ObjArchRiscv {
+    fn process_code(
+        &self,
+        address: u64,
+        code: &[u8],
+        _section_index: usize,
+        relocations: &[ObjReloc],
+        line_info: &BTreeMap<u64, u32>,
+        config: &DiffObjConfig,
+    ) -> Result<ProcessCodeResult> {
+        let ins_count = code.len() / 4;
+        let mut ops = Vec::<u16>::with_capacity(ins_count);
+        let mut insts = Vec::<ObjIns>::with_capacity(ins_count);
+        let mut offset = 0;
+        while offset + 2 <= code.len() {
+            let cur_addr = address + offset as u64;
+            let half = u16::from_le_bytes([code[offset], code[offset + 1]]);
+            let size = if half & 3 == 3 { 4 } else { 2 };
+            if offset + size > code.len() {
+                break;
+            }
+            let reloc = relocations.iter().find(|r| r.address == cur_addr).cloned();
+
+            let decoded = if size == 4 {
+                let mut raw = u32::from_le_bytes(code[offset..offset + 4].try_into()?);
+                if let Some(RelocationFlags::Elf { r_type }) = reloc.as_ref().map(|r| r.flags) {
+                    // Zero out relocations
+                    raw &= !reloc_mask(r_type);
+                }
+                decode(raw, self.xlen)
+            } else {
+                let mut raw = half;
+                if let Some(RelocationFlags::Elf { r_type }) = reloc.as_ref().map(|r| r.flags) {
+                    raw &= !(reloc_mask(r_type) as u16);
+                }
+                decode_compressed(raw, self.xlen)
+            };
+            offset += size;
+
+            let line = line_info.range(..=cur_addr).last().map(|(_, &b)| b);
+            let Some(ins) = decoded else {
+                ops.push(u16::MAX);
+                insts.push(ObjIns {
+                    address: cur_addr,
+                    size: size as u8,
+                    op: u16::MAX,
+                    mnemonic: Cow::Borrowed("<invalid>"),
+                    args: vec![],
+                    reloc,
+                    branch_dest: None,
+                    line,
+                    formatted: "".to_string(),
+                    orig: None,
+                    raw_args: None,
+                });
+                continue;
+            };
+
+            let op = opcode_id(ins.mnemonic);
+            let orig = ins.to_string();
+            let simplified = simplify(&ins, reloc.is_some());
+            let formatted = simplified.to_string();
+
+            // The immediate replaced by the relocation is always the last one
+            let reloc_arg = reloc.as_ref().and_then(|_| {
+                simplified.operands.iter().rposition(|o| {
+                    matches!(
+                        o,
+                        Operand::Imm(_) | Operand::Upper(_) | Operand::Mem(..) | Operand::Branch(_)
+                    )
+                })
+            });
+
+            let mut args = vec![];
+            let mut branch_dest = None;
+            for (idx, operand) in simplified.operands.iter().enumerate() {
+                if idx > 0 {
+                    args.push(ObjInsArg::PlainText(config.separator().into()));
+                }
+                if reloc_arg == Some(idx) {
+                    push_reloc(&mut args, reloc.as_ref().unwrap())?;
+                    if let Operand::Mem(_, base) = operand {
+                        args.push(ObjInsArg::PlainText("(".into()));
+                        args.push(ObjInsArg::Arg(ObjInsArgValue::Opaque(
+                            GPR[*base as usize].into(),
+                        )));
+                        args.push(ObjInsArg::PlainText(")".into()));
+                    }
+                    continue;
+                }
+                match *operand {
+                    Operand::Gpr(r) => {
+                        args.push(ObjInsArg::Arg(ObjInsArgValue::Opaque(GPR[r as usize].into())))
+                    }
+                    Operand::Fpr(r) => {
+                        args.push(ObjInsArg::Arg(ObjInsArgValue::Opaque(FPR[r as usize].into())))
+                    }
+                    Operand::Imm(imm) => args.push(ObjInsArg::Arg(ObjInsArgValue::Signed(imm))),
+                    Operand::Upper(imm) | Operand::Shamt(imm) => {
+                        args.push(ObjInsArg::Arg(ObjInsArgValue::Unsigned(imm as u64)))
+                    }
+                    Operand::Mem(imm, base) => {
+                        args.push(ObjInsArg::Arg(ObjInsArgValue::Signed(imm)));
+                        args.push(ObjInsArg::PlainText("(".into()));
+                        args.push(ObjInsArg::Arg(ObjInsArgValue::Opaque(
+                            GPR[base as usize].into(),
+                        )));
+                        args.push(ObjInsArg::PlainText(")".into()));
+                    }
+                    Operand::Branch(rel) => {
+                        let dest = cur_addr.wrapping_add_signed(rel);
+                        args.push(ObjInsArg::BranchDest(dest));
+                        branch_dest = Some(dest);
+                    }
+                    Operand::Csr(csr) => args.push(ObjInsArg::Arg(ObjInsArgValue::Opaque(
+                        csr_name(csr).map_or_else(|| format!("{csr:#x}").into(), Cow::Borrowed),
+                    ))),
+                    Operand::Text(s) => args.push(ObjInsArg::Arg(ObjInsArgValue::Opaque(s.into()))),
+                }
+            }
+
+            if let Some(reloc) = &reloc {
+                if reloc_arg.is_none() {
+                    args.push(ObjInsArg::PlainText(Cow::Borrowed(" <unhandled relocation>")));
+                    log::warn!(
+                        "Unhandled RISC-V relocation {:?}: {} @ {:#X}",
+                        reloc.flags,
+                        orig,
+                        cur_addr
+                    );
+                }
+            }
+
+            ops.push(op);
+            insts.push(ObjIns {
+                address: cur_addr,
+                size: size as u8,
+                op,
+                mnemonic: simplified.full_mnemonic(),
+                args,
+                reloc,
+                branch_dest,
+                line,
+                formatted,
+                orig: Some(orig),
+                raw_args: None,
+            });
+        }
+        Ok(ProcessCodeResult { ops, insts })
+    }
+
+    fn implcit_addend(
+        &self,
+        _file: &File<'_>,
+        _section: &ObjSection,
+        address: u64,
+        reloc: &Relocation,
+    ) -> Result<i64> {
+        bail!("Unsupported RISC-V implicit relocation {:#x}:{:?}", address, reloc.flags())
+    }
+
+    fn demangle(&self, name: &str) -> Option<String> {
+        cpp_demangle::Symbol::new(name)
+            .ok()
+            .and_then(|s| s.demangle(&cpp_demangle::DemangleOptions::default()).ok())
+    }
+
+    fn ignore_reloc(&self, flags: RelocationFlags) -> bool {
+        // Linker relaxation and alignment hints, which share their address with the real
+        // relocation (if any)
+        matches!(flags, RelocationFlags::Elf {
+            r_type: elf::R_RISCV_RELAX | elf::R_RISCV_ALIGN | elf::R_RISCV_TPREL_ADD
+        })
+    }
+
+    fn display_reloc(&self, flags: RelocationFlags) -> Cow<'static, str> {
+        match flags {
+            RelocationFlags::Elf { r_type } => match reloc_name(r_type) {
+                Some(name) => Cow::Borrowed(name),
+                None => Cow::Owned(format!("<{flags:?}>")),
+            },
+            _ => Cow::Owned(format!("<{flags:?}>")),
+        }
+    }
+
+    fn data_reloc_size(&self, flags: RelocationFlags) -> Option<usize> {
+        match flags {
+            RelocationFlags::Elf { r_type: elf::R_RISCV_64 } => Some(8),
+            RelocationFlags::Elf { r_type: elf::R_RISCV_32 | elf::R_RISCV_32_PCREL } => Some(4),
+            _ => None,
+        }
+    }
+
+    fn guess_data_type(&self, instruction: &ObjIns) -> Option<DataType> {
+        match MNEMONICS.get(instruction.op as usize)?.trim_start_matches("c.") {
+            "lb" | "lbu" | "sb" => Some(DataType::Int8),
+            "lh" | "lhu" | "sh" => Some(DataType::Int16),
+            "lw" | "lwu" | "sw" | "lwsp" | "swsp" => Some(DataType::Int32),
+            "ld" | "sd" | "ldsp" | "sdsp" => Some(DataType::Int64),
+            "flw" | "fsw" | "flwsp" | "fswsp" => Some(DataType::Float),
+            "fld" | "fsd" | "fldsp" | "fsdsp" => Some(DataType::Double),
+            _ => None,
+        }
+    }
+
+    fn display_data_type(&self, ty: DataType, bytes: &[u8]) -> Option<String> {
+        ty.display_bytes::<LittleEndian>(bytes)
+    }
+}
+
+const R_RISCV_32_PCREL: u32 = 57;
+
+fn reloc_name(r_type: u32) -> Option<&'static str> {
+    Some(match r_type {
+        elf::R_RISCV_32 => "R_RISCV_32",
+        elf::R_RISCV_64 => "R_RISCV_64",
+        elf::R_RISCV_BRANCH => "R_RISCV_BRANCH",
+        elf::R_RISCV_JAL => "R_RISCV_JAL",
+        elf::R_RISCV_CALL => "R_RISCV_CALL",
+        elf::R_RISCV_CALL_PLT => "R_RISCV_CALL_PLT",
+        elf::R_RISCV_GOT_HI20 => "R_RISCV_GOT_HI20",
+        elf::R_RISCV_TLS_GOT_HI20 => "R_RISCV_TLS_GOT_HI20",
+        elf::R_RISCV_TLS_GD_HI20 => "R_RISCV_TLS_GD_HI20",
+        elf::R_RISCV_PCREL_HI20 => "R_RISCV_PCREL_HI20",
+        elf::R_RISCV_PCREL_LO12_I => "R_RISCV_PCREL_LO12_I",
+        elf::R_RISCV_PCREL_LO12_S => "R_RISCV_PCREL_LO12_S",
+        elf::R_RISCV_HI20 => "R_RISCV_HI20",
+        elf::R_RISCV_LO12_I => "R_RISCV_LO12_I",
+        elf::R_RISCV_LO12_S => "R_RISCV_LO12_S",
+        elf::R_RISCV_TPREL_HI20 => "R_RISCV_TPREL_HI20",
+        elf::R_RISCV_TPREL_LO12_I => "R_RISCV_TPREL_LO12_I",
+        elf::R_RISCV_TPREL_LO12_S => "R_RISCV_TPREL_LO12_S",
+        elf::R_RISCV_TPREL_ADD => "R_RISCV_TPREL_ADD",
+        elf::R_RISCV_ADD8 => "R_RISCV_ADD8",
+        elf::R_RISCV_ADD16 => "R_RISCV_ADD16",
+        elf::R_RISCV_ADD32 => "R_RISCV_ADD32",
+        elf::R_RISCV_ADD64 => "R_RISCV_ADD64",
+        elf::R_RISCV_SUB8 => "R_RISCV_SUB8",
+        elf::R_RISCV_SUB16 => "R_RISCV_SUB16",
+        elf::R_RISCV_SUB32 => "R_RISCV_SUB32",
+        elf::R_RISCV_SUB64 => "R_RISCV_SUB64",
+        elf::R_RISCV_ALIGN => "R_RISCV_ALIGN",
+        elf::R_RISCV_RVC_BRANCH => "R_RISCV_RVC_BRANCH",
+        elf::R_RISCV_RVC_JUMP => "R_RISCV_RVC_JUMP",
+        elf::R_RISCV_RVC_LUI => "R_RISCV_RVC_LUI",
+        elf::R_RISCV_RELAX => "R_RISCV_RELAX",
+        elf::R_RISCV_SUB6 => "R_RISCV_SUB6",
+        elf::R_RISCV_SET6 => "R_RISCV_SET6",
+        elf::R_RISCV_SET8 => "R_RISCV_SET8",
+        elf::R_RISCV_SET16 => "R_RISCV_SET16",
+        elf::R_RISCV_SET32 => "R_RISCV_SET32",
+        R_RISCV_32_PCREL => "R_RISCV_32_PCREL",
+        _ => return None,
+    })
+}
+
+/// The instruction bits written by a relocation.
+fn reloc_mask(r_type: u32) -> u32 {
+    match r_type {
+        elf::R_RISCV_BRANCH
+        | elf::R_RISCV_LO12_S
+        | elf::R_RISCV_PCREL_LO12_S
+        | elf::R_RISCV_TPREL_LO12_S => 0xFE000F80,
+        elf::R_RISCV_LO12_I | elf::R_RISCV_PCREL_LO12_I | elf::R_RISCV_TPREL_LO12_I => 0xFFF00000,
+        // R_RISCV_CALL applies to an auipc/jalr pair, but is placed on the auipc
+        elf::R_RISCV_JAL
+        | elf::R_RISCV_CALL
+        | elf::R_RISCV_CALL_PLT
+        | elf::R_RISCV_HI20
+        | elf::R_RISCV_PCREL_HI20
+        | elf::R_RISCV_GOT_HI20
+        | elf::R_RISCV_TLS_GOT_HI20
+        | elf::R_RISCV_TLS_GD_HI20
+        | elf::R_RISCV_TPREL_HI20 => 0xFFFFF000,
+        elf::R_RISCV_RVC_BRANCH => 0x1C7C,
+        elf::R_RISCV_RVC_JUMP => 0x1FFC,
+        elf::R_RISCV_RVC_LUI => 0x107C,
+        _ => 0,
+    }
+}
+
+fn push_reloc(args: &mut Vec<ObjInsArg>, reloc: &ObjReloc) -> Result<()> {
+    let RelocationFlags::Elf { r_type } = reloc.flags else {
+        bail!("Unsupported RISC-V relocation kind: {:?}", reloc.flags);
+    };
+    let prefix = match r_type {
+        elf::R_RISCV_HI20 | elf::R_RISCV_RVC_LUI => "%hi",
+        elf::R_RISCV_LO12_I | elf::R_RISCV_LO12_S => "%lo",
+        elf::R_RISCV_PCREL_HI20 => "%pcrel_hi",
+        elf::R_RISCV_PCREL_LO12_I | elf::R_RISCV_PCREL_LO12_S => "%pcrel_lo",
+        elf::R_RISCV_GOT_HI20 => "%got_pcrel_hi",
+        elf::R_RISCV_TLS_GOT_HI20 => "%tls_ie_pcrel_hi",
+        elf::R_RISCV_TLS_GD_HI20 => "%tls_gd_pcrel_hi",
+        elf::R_RISCV_TPREL_HI20 => "%tprel_hi",
+        elf::R_RISCV_TPREL_LO12_I | elf::R_RISCV_TPREL_LO12_S => "%tprel_lo",
+        elf::R_RISCV_BRANCH
+        | elf::R_RISCV_JAL
+        | elf::R_RISCV_CALL
+        | elf::R_RISCV_CALL_PLT
+        | elf::R_RISCV_RVC_BRANCH
+        | elf::R_RISCV_RVC_JUMP => "",
+        _ => bail!("Unsupported ELF RISC-V relocation type {r_type}"),
+    };
+    if prefix.is_empty() {
+        args.push(ObjInsArg::Reloc);
+    } else {
+        args.push(ObjInsArg::PlainText(format!("{prefix}(").into()));
+        args.push(ObjInsArg::Reloc);
+        args.push(ObjInsArg::PlainText(")".into()));
+    }
+    Ok(())
+}
+
+const GPR: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+const FPR: [&str; 32] = [
+    "ft0", "ft1", "ft2", "ft3", "ft4", "ft5", "ft6", "ft7", "fs0", "fs1", "fa0", "fa1", "fa2",
+    "fa3", "fa4", "fa5", "fa6", "fa7", "fs2", "fs3", "fs4", "fs5", "fs6", "fs7", "fs8", "fs9",
+    "fs10", "fs11", "ft8", "ft9", "ft10", "ft11",
+];
+
+const ROUNDING_MODES: [&str; 8] = ["rne", "rtz", "rdn", "rup", "rmm", "<rm5>", "<rm6>", "dyn"];
+
+/// Fence predecessor/successor sets, indexed by the IORW bits.
+const FENCE_SETS: [&str; 16] = [
+    "0", "w", "r", "rw", "o", "ow", "or", "orw", "i", "iw", "ir", "irw", "io", "iow", "ior", "iorw",
+];
+
+fn csr_name(csr: u32) -> Option<&'static str> {
+    Some(match csr {
+        0x001 => "fflags",
+        0x002 => "frm",
+        0x003 => "fcsr",
+        0xC00 => "cycle",
+        0xC01 => "time",
+        0xC02 => "instret",
+        0xC80 => "cycleh",
+        0xC81 => "timeh",
+        0xC82 => "instreth",
+        0x100 => "sstatus",
+        0x104 => "sie",
+        0x105 => "stvec",
+        0x140 => "sscratch",
+        0x141 => "sepc",
+        0x142 => "scause",
+        0x143 => "stval",
+        0x144 => "sip",
+        0x180 => "satp",
+        0x300 => "mstatus",
+        0x301 => "misa",
+        0x304 => "mie",
+        0x305 => "mtvec",
+        0x340 => "mscratch",
+        0x341 => "mepc",
+        0x342 => "mcause",
+        0x343 => "mtval",
+        0x344 => "mip",
+        0xF14 => "mhartid",
+        _ => return None,
+    })
+}
+
+/// Every mnemonic produced by the decoder, before simplification. The index is used as the
+/// instruction's opcode ID.
+const MNEMONICS: &[&str] = &[
+    // RV32I/RV64I
+    "lui",
+    "auipc",
+    "jal",
+    "jalr",
+    "beq",
+    "bne",
+    "blt",
+    "bge",
+    "bltu",
+    "bgeu",
+    "lb",
+    "lh",
+    "lw",
+    "ld",
+    "lbu",
+    "lhu",
+    "lwu",
+    "sb",
+    "sh",
+    "sw",
+    "sd",
+    "addi",
+    "slti",
+    "sltiu",
+    "xori",
+    "ori",
+    "andi",
+    "slli",
+    "srli",
+    "srai",
+    "addiw",
+    "slliw",
+    "srliw",
+    "sraiw",
+    "add",
+    "sub",
+    "sll",
+    "slt",
+    "sltu",
+    "xor",
+    "srl",
+    "sra",
+    "or",
+    "and",
+    "addw",
+    "subw",
+    "sllw",
+    "srlw",
+    "sraw",
+    "fence",
+    "fence.tso",
+    "fence.i",
+    "ecall",
+    "ebreak",
+    "sret",
+    "mret",
+    "wfi",
+    "sfence.vma",
+    "csrrw",
+    "csrrs",
+    "csrrc",
+    "csrrwi",
+    "csrrsi",
+    "csrrci",
+    // M
+    "mul",
+    "mulh",
+    "mulhsu",
+    "mulhu",
+    "div",
+    "divu",
+    "rem",
+    "remu",
+    "mulw",
+    "divw",
+    "divuw",
+    "remw",
+    "remuw",
+    // A
+    "lr.w",
+    "sc.w",
+    "amoswap.w",
+    "amoadd.w",
+    "amoxor.w",
+    "amoand.w",
+    "amoor.w",
+    "amomin.w",
+    "amomax.w",
+    "amominu.w",
+    "amomaxu.w",
+    "lr.d",
+    "sc.d",
+    "amoswap.d",
+    "amoadd.d",
+    "amoxor.d",
+    "amoand.d",
+    "amoor.d",
+    "amomin.d",
+    "amomax.d",
+    "amominu.d",
+    "amomaxu.d",
+    // F/D
+    "flw",
+    "fld",
+    "fsw",
+    "fsd",
+    "fmadd.s",
+    "fmadd.d",
+    "fmsub.s",
+    "fmsub.d",
+    "fnmsub.s",
+    "fnmsub.d",
+    "fnmadd.s",
+    "fnmadd.d",
+    "fadd.s",
+    "fadd.d",
+    "fsub.s",
+    "fsub.d",
+    "fmul.s",
+    "fmul.d",
+    "fdiv.s",
+    "fdiv.d",
+    "fsqrt.s",
+    "fsqrt.d",
+    "fsgnj.s",
+    "fsgnj.d",
+    "fsgnjn.s",
+    "fsgnjn.d",
+    "fsgnjx.s",
+    "fsgnjx.d",
+    "fmin.s",
+    "fmin.d",
+    "fmax.s",
+    "fmax.d",
+    "fcvt.s.d",
+    "fcvt.d.s",
+    "fle.s",
+    "fle.d",
+    "flt.s",
+    "flt.d",
+    "feq.s",
+    "feq.d",
+    "fcvt.w.s",
+    "fcvt.wu.s",
+    "fcvt.l.s",
+    "fcvt.lu.s",
+    "fcvt.w.d",
+    "fcvt.wu.d",
+    "fcvt.l.d",
+    "fcvt.lu.d",
+    "fcvt.s.w",
+    "fcvt.s.wu",
+    "fcvt.s.l",
+    "fcvt.s.lu",
+    "fcvt.d.w",
+    "fcvt.d.wu",
+    "fcvt.d.l",
+    "fcvt.d.lu",
+    "fmv.x.w",
+    "fmv.x.d",
+    "fclass.s",
+    "fclass.d",
+    "fmv.w.x",
+    "fmv.d.x",
+    // C
+    "c.addi4spn",
+    "c.fld",
+    "c.lw",
+    "c.flw",
+    "c.ld",
+    "c.fsd",
+    "c.sw",
+    "c.fsw",
+    "c.sd",
+    "c.nop",
+    "c.addi",
+    "c.jal",
+    "c.addiw",
+    "c.li",
+    "c.addi16sp",
+    "c.lui",
+    "c.srli",
+    "c.srai",
+    "c.andi",
+    "c.sub",
+    "c.xor",
+    "c.or",
+    "c.and",
+    "c.subw",
+    "c.addw",
+    "c.j",
+    "c.beqz",
+    "c.bnez",
+    "c.slli",
+    "c.fldsp",
+    "c.lwsp",
+    "c.flwsp",
+    "c.ldsp",
+    "c.jr",
+    "c.mv",
+    "c.ebreak",
+    "c.jalr",
+    "c.add",
+    "c.fsdsp",
+    "c.swsp",
+    "c.fswsp",
+    "c.sdsp",
+];
+
+fn opcode_id(mnemonic: &str) -> u16 {
+    MNEMONICS.iter().position(|&m| m == mnemonic).map_or(u16::MAX, |i| i as u16)
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Operand {
+    Gpr(u32),
+    Fpr(u32),
+    Imm(i64),
+    /// Upper immediate of lui/auipc, shown unshifted
+    Upper(u32),
+    Shamt(u32),
+    /// Offset and base register
+    Mem(i64, u32),
+    /// Offset from the instruction's address
+    Branch(i64),
+    Csr(u32),
+    Text(&'static str),
+}
+
+#[derive(Debug, Clone)]
+struct Instruction {
+    mnemonic: &'static str,
+    /// Atomic ordering suffix (`.aq`, `.rl` or `.aqrl`)
+    suffix: &'static str,
+    operands: Vec<Operand>,
+}
+
+impl Instruction {
+    fn new(mnemonic: &'static str, operands: Vec<Operand>) -> Self {
+        Self { mnemonic, suffix: "", operands }
+    }
+
+    fn full_mnemonic(&self) -> Cow<'static, str> {
+        if self.suffix.is_empty() {
+            Cow::Borrowed(self.mnemonic)
+        } else {
+            Cow::Owned(format!("{}{}", self.mnemonic, self.suffix))
+        }
+    }
+}
+
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.mnemonic, self.suffix)?;
+        for (i, operand) in self.operands.iter().enumerate() {
+            f.write_str(if i == 0 { " " } else { ", " })?;
+            match *operand {
+                Operand::Gpr(r) => f.write_str(GPR[r as usize])?,
+                Operand::Fpr(r) => f.write_str(FPR[r as usize])?,
+                Operand::Imm(imm) => write!(f, "{imm}")?,
+                Operand::Upper(imm) => write!(f, "{imm:#x}")?,
+                Operand::Shamt(imm) => write!(f, "{imm}")?,
+                Operand::Mem(imm, base) => write!(f, "{imm}({})", GPR[base as usize])?,
+                Operand::Branch(rel) => {
+                    write!(f, ". {} {}", if rel < 0 { '-' } else { '+' }, rel.unsigned_abs())?
+                }
+                Operand::Csr(csr) => match csr_name(csr) {
+                    Some(name) => f.write_str(name)?,
+                    None => write!(f, "{csr:#x}")?,
+                },
+                Operand::Text(s) => f.write_str(s)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sign-extends the low `bits` bits of `value`.
+#[inline]
+fn sext(value: u32, bits: u32) -> i64 {
+    let shift = 32 - bits;
+    ((value << shift) as i32 >> shift) as i64
+}
+
+fn rounding_mode(ops: &mut Vec<Operand>, rm: u32) {
+    if rm != 7 {
+        ops.push(Operand::Text(ROUNDING_MODES[rm as usize]));
+    }
+}
+
+/// Decodes a 32-bit instruction.
+fn decode(code: u32, xlen: u32) -> Option<Instruction> {
+    use Operand::*;
+    let rd = (code >> 7) & 0x1F;
+    let funct3 = (code >> 12) & 7;
+    let rs1 = (code >> 15) & 0x1F;
+    let rs2 = (code >> 20) & 0x1F;
+    let rs3 = code >> 27;
+    let funct7 = code >> 25;
+    let imm_i = sext(code >> 20, 12);
+    let imm_s = sext(((code >> 20) & 0xFE0) | ((code >> 7) & 0x1F), 12);
+    let imm_b = sext(
+        ((code >> 19) & 0x1000)
+            | ((code << 4) & 0x800)
+            | ((code >> 20) & 0x7E0)
+            | ((code >> 7) & 0x1E),
+        13,
+    );
+    let imm_j = sext(
+        ((code >> 11) & 0x100000)
+            | (code & 0xFF000)
+            | ((code >> 9) & 0x800)
+            | ((code >> 20) & 0x7FE),
+        21,
+    );
+    let rv64 = xlen == 64;
+    // Single or double precision, for F/D instructions
+    let fp = |names: [&'static str; 2]| names.get((funct7 & 3) as usize).copied();
+
+    let ins = match code & 0x7F {
+        0x37 => Instruction::new("lui", vec![Gpr(rd), Upper(code >> 12)]),
+        0x17 => Instruction::new("auipc", vec![Gpr(rd), Upper(code >> 12)]),
+        0x6F => Instruction::new("jal", vec![Gpr(rd), Branch(imm_j)]),
+        0x67 if funct3 == 0 => Instruction::new("jalr", vec![Gpr(rd), Mem(imm_i, rs1)]),
+        0x63 => {
+            let mnemonic = match funct3 {
+                0 => "beq",
+                1 => "bne",
+                4 => "blt",
+                5 => "bge",
+                6 => "bltu",
+                7 => "bgeu",
+                _ => return None,
+            };
+            Instruction::new(mnemonic, vec![Gpr(rs1), Gpr(rs2), Branch(imm_b)])
+        }
+        0x03 => {
+            let mnemonic = match funct3 {
+                0 => "lb",
+                1 => "lh",
+                2 => "lw",
+                3 if rv64 => "ld",
+                4 => "lbu",
+                5 => "lhu",
+                6 if rv64 => "lwu",
+                _ => return None,
+            };
+            Instruction::new(mnemonic, vec![Gpr(rd), Mem(imm_i, rs1)])
+        }
+        0x23 => {
+            let mnemonic = match funct3 {
+                0 => "sb",
+                1 => "sh",
+                2 => "sw",
+                3 if rv64 => "sd",
+                _ => return None,
+            };
+            Instruction::new(mnemonic, vec![Gpr(rs2), Mem(imm_s, rs1)])
+        }
+        0x13 => {
+            let shamt = (code >> 20) & if rv64 { 0x3F } else { 0x1F };
+            let shift_type = code >> if rv64 { 26 } else { 25 };
+            let srai_type = if rv64 { 0x10 } else { 0x20 };
+            match funct3 {
+                1 if shift_type == 0 => {
+                    Instruction::new("slli", vec![Gpr(rd), Gpr(rs1), Shamt(shamt)])
+                }
+                5 if shift_type == 0 => {
+                    Instruction::new("srli", vec![Gpr(rd), Gpr(rs1), Shamt(shamt)])
+                }
+                5 if shift_type == srai_type => {
+                    Instruction::new("srai", vec![Gpr(rd), Gpr(rs1), Shamt(shamt)])
+                }
+                1 | 5 => return None,
+                _ => {
+                    let mnemonic = match funct3 {
+                        0 => "addi",
+                        2 => "slti",
+                        3 => "sltiu",
+                        4 => "xori",
+                        6 => "ori",
+                        _ => "andi",
+                    };
+                    Instruction::new(mnemonic, vec![Gpr(rd), Gpr(rs1), Imm(imm_i)])
+                }
+            }
+        }
+        0x1B if rv64 => match (funct3, funct7) {
+            (0, _) => Instruction::new("addiw", vec![Gpr(rd), Gpr(rs1), Imm(imm_i)]),
+            (1, 0) => Instruction::new("slliw", vec![Gpr(rd), Gpr(rs1), Shamt(rs2)]),
+            (5, 0) => Instruction::new("srliw", vec![Gpr(rd), Gpr(rs1), Shamt(rs2)]),
+            (5, 0x20) => Instruction::new("sraiw", vec![Gpr(rd), Gpr(rs1), Shamt(rs2)]),
+            _ => return None,
+        },
+        0x33 => {
+            let mnemonic = match (funct7, funct3) {
+                (0, 0) => "add",
+                (0x20, 0) => "sub",
+                (0, 1) => "sll",
+                (0, 2) => "slt",
+                (0, 3) => "sltu",
+                (0, 4) => "xor",
+                (0, 5) => "srl",
+                (0x20, 5) => "sra",
+                (0, 6) => "or",
+                (0, 7) => "and",
+                (1, 0) => "mul",
+                (1, 1) => "mulh",
+                (1, 2) => "mulhsu",
+                (1, 3) => "mulhu",
+                (1, 4) => "div",
+                (1, 5) => "divu",
+                (1, 6) => "rem",
+                (1, 7) => "remu",
+                _ => return None,
+            };
+            Instruction::new(mnemonic, vec![Gpr(rd), Gpr(rs1), Gpr(rs2)])
+        }
+        0x3B if rv64 => {
+            let mnemonic = match (funct7, funct3) {
+                (0, 0) => "addw",
+                (0x20, 0) => "subw",
+                (0, 1) => "sllw",
+                (0, 5) => "srlw",
+                (0x20, 5) => "sraw",
+                (1, 0) => "mulw",
+                (1, 4) => "divw",
+                (1, 5) => "divuw",
+                (1, 6) => "remw",
+                (1, 7) => "remuw",
+                _ => return None,
+            };
+            Instruction::new(mnemonic, vec![Gpr(rd), Gpr(rs1), Gpr(rs2)])
+        }
+        0x0F => match funct3 {
+            0 if code >> 28 == 8 => Instruction::new("fence.tso", vec![]),
+            0 => {
+                let pred = (code >> 24) & 0xF;
+                let succ = (code >> 20) & 0xF;
+                if pred == 0xF && succ == 0xF {
+                    Instruction::new("fence", vec![])
+                } else {
+                    Instruction::new("fence", vec![
+                        Text(FENCE_SETS[pred as usize]),
+                        Text(FENCE_SETS[succ as usize]),
+                    ])
+                }
+            }
+            1 => Instruction::new("fence.i", vec![]),
+            _ => return None,
+        },
+        0x73 => {
+            let csr = code >> 20;
+            match funct3 {
+                0 if funct7 == 0x09 && rd == 0 => {
+                    Instruction::new("sfence.vma", vec![Gpr(rs1), Gpr(rs2)])
+                }
+                0 if rd == 0 && rs1 == 0 => {
+                    let mnemonic = match csr {
+                        0x000 => "ecall",
+                        0x001 => "ebreak",
+                        0x102 => "sret",
+                        0x302 => "mret",
+                        0x105 => "wfi",
+                        _ => return None,
+                    };
+                    Instruction::new(mnemonic, vec![])
+                }
+                1 => Instruction::new("csrrw", vec![Gpr(rd), Csr(csr), Gpr(rs1)]),
+                2 => Instruction::new("csrrs", vec![Gpr(rd), Csr(csr), Gpr(rs1)]),
+                3 => Instruction::new("csrrc", vec![Gpr(rd), Csr(csr), Gpr(rs1)]),
+                5 => Instruction::new("csrrwi", vec![Gpr(rd), Csr(csr), Shamt(rs1)]),
+                6 => Instruction::new("csrrsi", vec![Gpr(rd), Csr(csr), Shamt(rs1)]),
+                7 => Instruction::new("csrrci", vec![Gpr(rd), Csr(csr), Shamt(rs1)]),
+                _ => return None,
+            }
+        }
+        0x2F if funct3 == 2 || (funct3 == 3 && rv64) => {
+            let double = funct3 == 3;
+            let pick = |w: &'static str, d: &'static str| if double { d } else { w };
+            let mnemonic = match code >> 27 {
+                0x02 if rs2 == 0 => pick("lr.w", "lr.d"),
+                0x03 => pick("sc.w", "sc.d"),
+                0x01 => pick("amoswap.w", "amoswap.d"),
+                0x00 => pick("amoadd.w", "amoadd.d"),
+                0x04 => pick("amoxor.w", "amoxor.d"),
+                0x0C => pick("amoand.w", "amoand.d"),
+                0x08 => pick("amoor.w", "amoor.d"),
+                0x10 => pick("amomin.w", "amomin.d"),
+                0x14 => pick("amomax.w", "amomax.d"),
+                0x18 => pick("amominu.w", "amominu.d"),
+                0x1C => pick("amomaxu.w", "amomaxu.d"),
+                _ => return None,
+            };
+            let suffix = match (code >> 25) & 3 {
+                0 => "",
+                1 => ".rl",
+                2 => ".aq",
+                _ => ".aqrl",
+            };
+            let operands = if mnemonic.starts_with("lr") {
+                vec![Gpr(rd), Mem(0, rs1)]
+            } else {
+                vec![Gpr(rd), Gpr(rs2), Mem(0, rs1)]
+            };
+            Instruction { mnemonic, suffix, operands }
+        }
+        0x07 => match funct3 {
+            2 => Instruction::new("flw", vec![Fpr(rd), Mem(imm_i, rs1)]),
+            3 => Instruction::new("fld", vec![Fpr(rd), Mem(imm_i, rs1)]),
+            _ => return None,
+        },
+        0x27 => match funct3 {
+            2 => Instruction::new("fsw", vec![Fpr(rs2), Mem(imm_s, rs1)]),
+            3 => Instruction::new("fsd", vec![Fpr(rs2), Mem(imm_s, rs1)]),
+            _ => return None,
+        },
+        opcode @ (0x43 | 0x47 | 0x4B | 0x4F) => {
+            let mnemonic = match opcode {
+                0x43 => fp(["fmadd.s", "fmadd.d"]),
+                0x47 => fp(["fmsub.s", "fmsub.d"]),
+                0x4B => fp(["fnmsub.s", "fnmsub.d"]),
+                _ => fp(["fnmadd.s", "fnmadd.d"]),
+            }?;
+            let mut operands = vec![Fpr(rd), Fpr(rs1), Fpr(rs2), Fpr(rs3)];
+            rounding_mode(&mut operands, funct3);
+            Instruction::new(mnemonic, operands)
+        }
+        0x53 => {
+            let (mnemonic, mut operands) = match funct7 >> 2 {
+                0x00 => (fp(["fadd.s", "fadd.d"])?, vec![Fpr(rd), Fpr(rs1), Fpr(rs2)]),
+                0x01 => (fp(["fsub.s", "fsub.d"])?, vec![Fpr(rd), Fpr(rs1), Fpr(rs2)]),
+                0x02 => (fp(["fmul.s", "fmul.d"])?, vec![Fpr(rd), Fpr(rs1), Fpr(rs2)]),
+                0x03 => (fp(["fdiv.s", "fdiv.d"])?, vec![Fpr(rd), Fpr(rs1), Fpr(rs2)]),
+                0x0B if rs2 == 0 => (fp(["fsqrt.s", "fsqrt.d"])?, vec![Fpr(rd), Fpr(rs1)]),
+                0x04 => {
+                    let mnemonic = match funct3 {
+                        0 => fp(["fsgnj.s", "fsgnj.d"]),
+                        1 => fp(["fsgnjn.s", "fsgnjn.d"]),
+                        2 => fp(["fsgnjx.s", "fsgnjx.d"]),
+                        _ => None,
+                    }?;
+                    return Some(Instruction::new(mnemonic, vec![Fpr(rd), Fpr(rs1), Fpr(rs2)]));
+                }
+                0x05 => {
+                    let mnemonic = match funct3 {
+                        0 => fp(["fmin.s", "fmin.d"]),
+                        1 => fp(["fmax.s", "fmax.d"]),
+                        _ => None,
+                    }?;
+                    return Some(Instruction::new(mnemonic, vec![Fpr(rd), Fpr(rs1), Fpr(rs2)]));
+                }
+                0x08 => {
+                    let mnemonic = match (funct7, rs2) {
+                        (0x20, 1) => "fcvt.s.d",
+                        (0x21, 0) => "fcvt.d.s",
+                        _ => return None,
+                    };
+                    (mnemonic, vec![Fpr(rd), Fpr(rs1)])
+                }
+                0x14 => {
+                    let mnemonic = match funct3 {
+                        0 => fp(["fle.s", "fle.d"]),
+                        1 => fp(["flt.s", "flt.d"]),
+                        2 => fp(["feq.s", "feq.d"]),
+                        _ => None,
+                    }?;
+                    return Some(Instruction::new(mnemonic, vec![Gpr(rd), Fpr(rs1), Fpr(rs2)]));
+                }
+                0x18 => {
+                    let names = match funct7 & 3 {
+                        0 => ["fcvt.w.s", "fcvt.wu.s", "fcvt.l.s", "fcvt.lu.s"],
+                        1 => ["fcvt.w.d", "fcvt.wu.d", "fcvt.l.d", "fcvt.lu.d"],
+                        _ => return None,
+                    };
+                    (*names.get(rs2 as usize)?, vec![Gpr(rd), Fpr(rs1)])
+                }
+                0x1A => {
+                    let names = match funct7 & 3 {
+                        0 => ["fcvt.s.w", "fcvt.s.wu", "fcvt.s.l", "fcvt.s.lu"],
+                        1 => ["fcvt.d.w", "fcvt.d.wu", "fcvt.d.l", "fcvt.d.lu"],
+                        _ => return None,
+                    };
+                    (*names.get(rs2 as usize)?, vec![Fpr(rd), Gpr(rs1)])
+                }
+                0x1C if rs2 == 0 => {
+                    let mnemonic = match funct3 {
+                        0 => fp(["fmv.x.w", "fmv.x.d"]),
+                        1 => fp(["fclass.s", "fclass.d"]),
+                        _ => None,
+                    }?;
+                    return Some(Instruction::new(mnemonic, vec![Gpr(rd), Fpr(rs1)]));
+                }
+                0x1E if rs2 == 0 && funct3 == 0 => {
+                    return Some(Instruction::new(fp(["fmv.w.x", "fmv.d.x"])?, vec![
+                        Fpr(rd),
+                        Gpr(rs1),
+                    ]));
+                }
+                _ => return None,
+            };
+            rounding_mode(&mut operands, funct3);
+            Instruction::new(mnemonic, operands)
+        }
+        _ => return None,
+    };
+    Some(ins)
+}
+
+/// Decodes a 16-bit compressed (C extension) instruction, keeping its compressed form.
+fn decode_compressed(code: u16, xlen: u32) -> Option<Instruction> {
+    use Operand::*;
+    let c = code as u32;
+    let funct3 = c >> 13;
+    let rd = (c >> 7) & 0x1F;
+    let rs2 = (c >> 2) & 0x1F;
+    // Registers x8-x15 in the three-bit fields
+    let rd_p = 8 + ((c >> 2) & 7);
+    let rs1_p = 8 + ((c >> 7) & 7);
+    let imm6 = sext(((c >> 7) & 0x20) | ((c >> 2) & 0x1F), 6);
+    let shamt = ((c >> 7) & 0x20) | ((c >> 2) & 0x1F);
+    // Load/store offsets, scaled by the access size
+    let off_w = ((c >> 7) & 0x38) | ((c >> 4) & 4) | ((c << 1) & 0x40);
+    let off_d = ((c >> 7) & 0x38) | ((c << 1) & 0xC0);
+    let cj = sext(
+        ((c >> 1) & 0x800)
+            | ((c >> 7) & 0x10)
+            | ((c >> 1) & 0x300)
+            | ((c << 2) & 0x400)
+            | ((c >> 1) & 0x40)
+            | ((c << 1) & 0x80)
+            | ((c >> 2) & 0xE)
+            | ((c << 3) & 0x20),
+        12,
+    );
+    let cb = sext(
+        ((c >> 4) & 0x100)
+            | ((c >> 7) & 0x18)
+            | ((c << 1) & 0xC0)
+            | ((c >> 2) & 6)
+            | ((c << 3) & 0x20),
+        9,
+    );
+    let rv64 = xlen == 64;
+
+    let ins = match (c & 3, funct3) {
+        (0, 0) => {
+            let imm = ((c >> 7) & 0x30) | ((c >> 1) & 0x3C0) | ((c >> 4) & 4) | ((c >> 2) & 8);
+            if imm == 0 {
+                return None;
+            }
+            Instruction::new("c.addi4spn", vec![Gpr(rd_p), Gpr(2), Imm(imm as i64)])
+        }
+        (0, 1) => Instruction::new("c.fld", vec![Fpr(rd_p), Mem(off_d as i64, rs1_p)]),
+        (0, 2) => Instruction::new("c.lw", vec![Gpr(rd_p), Mem(off_w as i64, rs1_p)]),
+        (0, 3) if rv64 => Instruction::new("c.ld", vec![Gpr(rd_p), Mem(off_d as i64, rs1_p)]),
+        (0, 3) => Instruction::new("c.flw", vec![Fpr(rd_p), Mem(off_w as i64, rs1_p)]),
+        (0, 5) => Instruction::new("c.fsd", vec![Fpr(rd_p), Mem(off_d as i64, rs1_p)]),
+        (0, 6) => Instruction::new("c.sw", vec![Gpr(rd_p), Mem(off_w as i64, rs1_p)]),
+        (0, 7) if rv64 => Instruction::new("c.sd", vec![Gpr(rd_p), Mem(off_d as i64, rs1_p)]),
+        (0, 7) => Instruction::new("c.fsw", vec![Fpr(rd_p), Mem(off_w as i64, rs1_p)]),
+        (1, 0) if rd == 0 => Instruction::new("c.nop", vec![]),
+        (1, 0) => Instruction::new("c.addi", vec![Gpr(rd), Imm(imm6)]),
+        (1, 1) if rv64 => Instruction::new("c.addiw", vec![Gpr(rd), Imm(imm6)]),
+        (1, 1) => Instruction::new("c.jal", vec![Branch(cj)]),
+        (1, 2) => Instruction::new("c.li", vec![Gpr(rd), Imm(imm6)]),
+        (1, 3) if rd == 2 => {
+            let imm = sext(
+                ((c >> 3) & 0x200)
+                    | ((c >> 2) & 0x10)
+                    | ((c << 1) & 0x40)
+                    | ((c << 4) & 0x180)
+                    | ((c << 3) & 0x20),
+                10,
+            );
+            Instruction::new("c.addi16sp", vec![Gpr(2), Imm(imm)])
+        }
+        (1, 3) => Instruction::new("c.lui", vec![Gpr(rd), Upper(imm6 as u32 & 0xFFFFF)]),
+        (1, 4) => match (c >> 10) & 3 {
+            0 => Instruction::new("c.srli", vec![Gpr(rs1_p), Shamt(shamt)]),
+            1 => Instruction::new("c.srai", vec![Gpr(rs1_p), Shamt(shamt)]),
+            2 => Instruction::new("c.andi", vec![Gpr(rs1_p), Imm(imm6)]),
+            _ => {
+                let mnemonic = match ((c >> 12) & 1, (c >> 5) & 3) {
+                    (0, 0) => "c.sub",
+                    (0, 1) => "c.xor",
+                    (0, 2) => "c.or",
+                    (0, 3) => "c.and",
+                    (1, 0) if rv64 => "c.subw",
+                    (1, 1) if rv64 => "c.addw",
+                    _ => return None,
+                };
+                Instruction::new(mnemonic, vec![Gpr(rs1_p), Gpr(rd_p)])
+            }
+        },
+        (1, 5) => Instruction::new("c.j", vec![Branch(cj)]),
+        (1, 6) => Instruction::new("c.beqz", vec![Gpr(rs1_p), Branch(cb)]),
+        (1, 7) => Instruction::new("c.bnez", vec![Gpr(rs1_p), Branch(cb)]),
+        (2, 0) => Instruction::new("c.slli", vec![Gpr(rd), Shamt(shamt)]),
+        (2, 1) => {
+            let off = ((c >> 7) & 0x20) | ((c >> 2) & 0x18) | ((c << 4) & 0x1C0);
+            Instruction::new("c.fldsp", vec![Fpr(rd), Mem(off as i64, 2)])
+        }
+        (2, 2) => {
+            let off = ((c >> 7) & 0x20) | ((c >> 2) & 0x1C) | ((c << 4) & 0xC0);
+            Instruction::new("c.lwsp", vec![Gpr(rd), Mem(off as i64, 2)])
+        }
+        (2, 3) if rv64 => {
+            let off = ((c >> 7) & 0x20) | ((c >> 2) & 0x18) | ((c << 4) & 0x1C0);
+            Instruction::new("c.ldsp", vec![Gpr(rd), Mem(off as i64, 2)])
+        }
+        (2, 3) => {
+            let off = ((c >> 7) & 0x20) | ((c >> 2) & 0x1C) | ((c << 4) & 0xC0);
+            Instruction::new("c.flwsp", vec![Fpr(rd), Mem(off as i64, 2)])
+        }
+        (2, 4) => match ((c >> 12) & 1, rd, rs2) {
+            (0, 0, _) => return None,
+            (0, _, 0) => Instruction::new("c.jr", vec![Gpr(rd)]),
+            (0, _, _) => Instruction::new("c.mv", vec![Gpr(rd), Gpr(rs2)]),
+            (_, 0, 0) => Instruction::new("c.ebreak", vec![]),
+            (_, _, 0) => Instruction::new("c.jalr", vec![Gpr(rd)]),
+            _ => Instruction::new("c.add", vec![Gpr(rd), Gpr(rs2)]),
+        },
+        (2, 5) => {
+            let off = ((c >> 7) & 0x38) | ((c >> 1) & 0x1C0);
+            Instruction::new("c.fsdsp", vec![Fpr(rs2), Mem(off as i64, 2)])
+        }
+        (2, 6) => {
+            let off = ((c >> 7) & 0x3C) | ((c >> 1) & 0xC0);
+            Instruction::new("c.swsp", vec![Gpr(rs2), Mem(off as i64, 2)])
+        }
+        (2, 7) if rv64 => {
+            let off = ((c >> 7) & 0x38) | ((c >> 1) & 0x1C0);
+            Instruction::new("c.sdsp", vec![Gpr(rs2), Mem(off as i64, 2)])
+        }
+        (2, 7) => {
+            let off = ((c >> 7) & 0x3C) | ((c >> 1) & 0xC0);
+            Instruction::new("c.fswsp", vec![Fpr(rs2), Mem(off as i64, 2)])
+        }
+        _ => return None,
+    };
+    Some(ins)
+}
+
+/// Rewrites common idioms as their assembler pseudo-instructions (e.g. `addi a0, zero, 1` as
+/// `li a0, 1`). Immediates that are filled in by a relocation are left alone.
+fn simplify(ins: &Instruction, has_reloc: bool) -> Instruction {
+    use Operand::*;
+    let ops = ins.operands.as_slice();
+    let (mnemonic, operands) = match (ins.mnemonic, ops) {
+        ("addi", [Gpr(0), Gpr(0), Imm(0)]) => ("nop", vec![]),
+        ("addi", [rd, Gpr(0), imm]) if !has_reloc => ("li", vec![*rd, *imm]),
+        ("addi", [rd, rs, Imm(0)]) if !has_reloc => ("mv", vec![*rd, *rs]),
+        ("addiw", [rd, rs, Imm(0)]) if !has_reloc => ("sext.w", vec![*rd, *rs]),
+        ("xori", [rd, rs, Imm(-1)]) => ("not", vec![*rd, *rs]),
+        ("sub", [rd, Gpr(0), rs]) => ("neg", vec![*rd, *rs]),
+        ("subw", [rd, Gpr(0), rs]) => ("negw", vec![*rd, *rs]),
+        ("sltiu", [rd, rs, Imm(1)]) => ("seqz", vec![*rd, *rs]),
+        ("sltu", [rd, Gpr(0), rs]) => ("snez", vec![*rd, *rs]),
+        ("slt", [rd, rs, Gpr(0)]) => ("sltz", vec![*rd, *rs]),
+        ("slt", [rd, Gpr(0), rs]) => ("sgtz", vec![*rd, *rs]),
+        ("jal", [Gpr(0), dest]) => ("j", vec![*dest]),
+        ("jal", [Gpr(1), dest]) => ("jal", vec![*dest]),
+        ("jalr", [Gpr(0), Mem(0, 1)]) if !has_reloc => ("ret", vec![]),
+        ("jalr", [Gpr(0), Mem(0, rs)]) if !has_reloc => ("jr", vec![Gpr(*rs)]),
+        ("jalr", [Gpr(1), Mem(0, rs)]) if !has_reloc => ("jalr", vec![Gpr(*rs)]),
+        ("beq", [rs, Gpr(0), dest]) => ("beqz", vec![*rs, *dest]),
+        ("bne", [rs, Gpr(0), dest]) => ("bnez", vec![*rs, *dest]),
+        ("bge", [Gpr(0), rs, dest]) => ("blez", vec![*rs, *dest]),
+        ("bge", [rs, Gpr(0), dest]) => ("bgez", vec![*rs, *dest]),
+        ("blt", [rs, Gpr(0), dest]) => ("bltz", vec![*rs, *dest]),
+        ("blt", [Gpr(0), rs, dest]) => ("bgtz", vec![*rs, *dest]),
+        ("csrrs", [rd, csr, Gpr(0)]) => ("csrr", vec![*rd, *csr]),
+        ("csrrw", [Gpr(0), csr, rs]) => ("csrw", vec![*csr, *rs]),
+        ("csrrs", [Gpr(0), csr, rs]) => ("csrs", vec![*csr, *rs]),
+        ("csrrc", [Gpr(0), csr, rs]) => ("csrc", vec![*csr, *rs]),
+        ("fsgnj.s", [rd, rs, rt]) if rs == rt => ("fmv.s", vec![*rd, *rs]),
+        ("fsgnj.d", [rd, rs, rt]) if rs == rt => ("fmv.d", vec![*rd, *rs]),
+        ("fsgnjn.s", [rd, rs, rt]) if rs == rt => ("fneg.s", vec![*rd, *rs]),
+        ("fsgnjn.d", [rd, rs, rt]) if rs == rt => ("fneg.d", vec![*rd, *rs]),
+        ("fsgnjx.s", [rd, rs, rt]) if rs == rt => ("fabs.s", vec![*rd, *rs]),
+        ("fsgnjx.d", [rd, rs, rt]) if rs == rt => ("fabs.d", vec![*rd, *rs]),
+        _ => return ins.clone(),
+    };
+    Instruction { mnemonic, suffix: ins.suffix, operands }
+}
@@ -1,27 +1,53 @@
-use std::{borrow::Cow, collections::BTreeMap};
+use std::{borrow::Cow, collections::BTreeMap, ops::Range};
 
 use anyhow::{anyhow, bail, ensure, Result};
 use iced_x86::{
     Decoder, DecoderOptions, DecoratorKind, Formatter, FormatterOutput, FormatterTextKind,
-    GasFormatter, Instruction, IntelFormatter, MasmFormatter, NasmFormatter, NumberKind, OpKind,
-    PrefixKind, Register,
+    GasFormatter, Instruction, IntelFormatter, MasmFormatter, Mnemonic, NasmFormatter, NumberKind,
+    OpKind, PrefixKind, Register,
 };
-use object::{pe, Endian, Endianness, File, Object, Relocation, RelocationFlags};
+use object::{elf, pe, Endian, Endianness, File, Object, ObjectKind, Relocation, RelocationFlags};
 
 use crate::{
-    arch::{ObjArch, ProcessCodeResult},
+    arch::{immediate_arg, register_arg, ObjArch, ProcessCodeResult, StackFrame, PROLOGUE_LIMIT},
     diff::{DiffObjConfig, X86Formatter},
-    obj::{ObjIns, ObjInsArg, ObjInsArgValue, ObjReloc, ObjSection},
+    obj::{
+        dynamic::LINKED_RELOC_FLAGS, ObjIns, ObjInsArg, ObjInsArgValue, ObjReloc, ObjSection,
+        ObjSymbol, ObjSymbolKind,
+    },
 };
 
 pub struct ObjArchX86 {
     bits: u32,
     endianness: Endianness,
+    /// Linked images have no relocations, so targets are resolved from instruction operands.
+    linked: bool,
 }
 
+/// Mnemonics that compilers commonly use interchangeably, aligned as one opcode. The first
+/// mnemonic of each class is its id.
+const OPCODE_CLASSES: &[&[Mnemonic]] = &[
+    &[Mnemonic::Mov, Mnemonic::Movzx, Mnemonic::Movsx, Mnemonic::Movsxd],
+    &[Mnemonic::Add, Mnemonic::Sub, Mnemonic::Inc, Mnemonic::Dec, Mnemonic::Lea],
+    &[Mnemonic::Cmp, Mnemonic::Test],
+    &[Mnemonic::Shl, Mnemonic::Sal],
+    &[
+        Mnemonic::Movaps,
+        Mnemonic::Movups,
+        Mnemonic::Movapd,
+        Mnemonic::Movupd,
+        Mnemonic::Movdqa,
+        Mnemonic::Movdqu,
+    ],
+];
+
 impl ObjArchX86 {
     pub fn new(object: &File) -> Result<Self> {
-        Ok(Self { bits: if object.is_64() { 64 } else { 32 }, endianness: object.endianness() })
+        Ok(Self {
+            bits: if object.is_64() { 64 } else { 32 },
+            endianness: object.endianness(),
+            linked: object.kind() != ObjectKind::Relocatable,
+        })
     }
 }
 
@@ -58,10 +84,12 @@ impl ObjArch for ObjArchX86 {
                 line: None,
                 formatted: String::new(),
                 orig: None,
+                raw_args: None,
             },
             error: None,
             ins_operands: vec![],
         };
+        let function_range = address..address + code.len() as u64;
         let mut instruction = Instruction::default();
         while decoder.can_decode() {
             decoder.decode_out(&mut instruction);
@@ -70,7 +98,11 @@ impl ObjArch for ObjArchX86 {
             let op = instruction.mnemonic() as u16;
             let reloc = relocations
                 .iter()
-                .find(|r| r.address >= address && r.address < address + instruction.len() as u64);
+                .find(|r| r.address >= address && r.address < address + instruction.len() as u64)
+                .map(make_fake_section_reloc)
+                .or_else(|| {
+                    self.linked.then(|| make_linked_reloc(&instruction, &function_range)).flatten()
+                });
             let line = line_info.range(..=address).last().map(|(_, &b)| b);
             output.ins = ObjIns {
                 address,
@@ -78,11 +110,12 @@ impl ObjArch for ObjArchX86 {
                 op,
                 mnemonic: Cow::Borrowed("<invalid>"),
                 args: vec![],
-                reloc: reloc.cloned(),
+                reloc: reloc.clone(),
                 branch_dest: None,
                 line,
                 formatted: String::new(),
                 orig: None,
+                raw_args: None,
             };
             // Run the formatter, which will populate output.ins
             formatter.format(&instruction, &mut output);
@@ -152,6 +185,41 @@ impl ObjArch for ObjArchX86 {
         }
     }
 
+    fn opcode_class(&self, op: u16) -> u16 {
+        OPCODE_CLASSES
+            .iter()
+            .find(|class| class.iter().any(|&m| m as u16 == op))
+            .map_or(op, |class| class[0] as u16)
+    }
+
+    fn stack_frame(&self, insts: &[ObjIns]) -> Option<StackFrame> {
+        let mut frame = StackFrame::default();
+        for ins in insts.iter().take(PROLOGUE_LIMIT) {
+            let args = ins.iter_args().collect::<Vec<_>>();
+            let is_stack_pointer = |r: Option<&str>| matches!(r, Some("esp" | "rsp"));
+            match ins.op {
+                op if op == Mnemonic::Push as u16 && args.len() == 1 => {
+                    if let Some(register) = register_arg(args.first()) {
+                        frame.save(register);
+                    }
+                }
+                op if op == Mnemonic::Sub as u16
+                    && is_stack_pointer(register_arg(args.first())) =>
+                {
+                    frame.size = immediate_arg(args.get(1)).map(i64::unsigned_abs);
+                }
+                op if op == Mnemonic::Call as u16
+                    || op == Mnemonic::Ret as u16
+                    || op == Mnemonic::Jmp as u16 =>
+                {
+                    break
+                }
+                _ => {}
+            }
+        }
+        (!frame.is_empty()).then_some(frame)
+    }
+
     fn display_reloc(&self, flags: RelocationFlags) -> Cow<'static, str> {
         match flags {
             RelocationFlags::Coff { typ } => match typ {
@@ -162,6 +230,112 @@ impl ObjArch for ObjArchX86 {
             _ => Cow::Owned(format!("<{flags:?}>")),
         }
     }
+
+    fn data_reloc_size(&self, flags: RelocationFlags) -> Option<usize> {
+        if self.bits == 64 {
+            match flags {
+                RelocationFlags::Elf { r_type: elf::R_X86_64_64 | elf::R_X86_64_PC64 }
+                | RelocationFlags::Coff { typ: pe::IMAGE_REL_AMD64_ADDR64 } => Some(8),
+                RelocationFlags::Elf {
+                    r_type: elf::R_X86_64_32 | elf::R_X86_64_32S | elf::R_X86_64_PC32,
+                }
+                | RelocationFlags::Coff {
+                    typ:
+                        pe::IMAGE_REL_AMD64_ADDR32
+                        | pe::IMAGE_REL_AMD64_ADDR32NB
+                        | pe::IMAGE_REL_AMD64_REL32,
+                } => Some(4),
+                RelocationFlags::Elf { r_type: elf::R_X86_64_16 | elf::R_X86_64_PC16 } => Some(2),
+                _ => None,
+            }
+        } else {
+            match flags {
+                RelocationFlags::Elf { r_type: elf::R_386_32 | elf::R_386_PC32 }
+                | RelocationFlags::Coff {
+                    typ:
+                        pe::IMAGE_REL_I386_DIR32 | pe::IMAGE_REL_I386_DIR32NB | pe::IMAGE_REL_I386_REL32,
+                } => Some(4),
+                RelocationFlags::Elf { r_type: elf::R_386_16 | elf::R_386_PC16 } => Some(2),
+                _ => None,
+            }
+        }
+    }
+}
+
+// MSVC references string literals and switch jump tables through local symbols (`$SG1234`,
+// `$LN5@func`), but objects produced by other tools may use section-relative relocations instead
+// (`.rdata+0x34`). Similar to the PPC fake pool relocations, we replace the section target with a
+// placeholder symbol at the referenced address, which is resolved to the real symbol containing
+// that address once all of the object's symbols are available. This way both sides display
+// named targets.
+fn make_fake_section_reloc(reloc: &ObjReloc) -> ObjReloc {
+    if reloc.target.kind != ObjSymbolKind::Section {
+        return reloc.clone();
+    }
+    let Some(target_address) = reloc.target.address.checked_add_signed(reloc.addend) else {
+        return reloc.clone();
+    };
+    let fake_target_symbol = ObjSymbol {
+        name: "".to_string(),
+        demangled_name: None,
+        address: target_address,
+        section_address: 0,
+        size: 0,
+        size_known: false,
+        kind: Default::default(),
+        flags: Default::default(),
+        orig_section_index: reloc.target.orig_section_index,
+        virtual_address: None,
+        original_index: None,
+        bytes: vec![],
+    };
+    ObjReloc {
+        flags: reloc.flags,
+        address: reloc.address,
+        target: fake_target_symbol,
+        addend: 0,
+        original: None,
+        pseudo: reloc.pseudo,
+    }
+}
+
+// Linked images have no relocations, but the target of a call or a RIP-relative load is known
+// from the instruction itself. We create a relocation to a placeholder symbol at the target
+// address, which is resolved to the PLT stub, GOT slot or symbol containing that address once all
+// of the object's symbols are available. Branches within the function are left as-is.
+fn make_linked_reloc(instruction: &Instruction, function_range: &Range<u64>) -> Option<ObjReloc> {
+    let target_address = if instruction.is_ip_rel_memory_operand() {
+        instruction.ip_rel_memory_address()
+    } else if instruction.is_call_near()
+        || (instruction.is_jmp_near()
+            && !function_range.contains(&instruction.near_branch_target()))
+    {
+        instruction.near_branch_target()
+    } else {
+        return None;
+    };
+    let fake_target_symbol = ObjSymbol {
+        name: "".to_string(),
+        demangled_name: None,
+        address: target_address,
+        section_address: 0,
+        size: 0,
+        size_known: false,
+        kind: Default::default(),
+        flags: Default::default(),
+        orig_section_index: None,
+        virtual_address: None,
+        original_index: None,
+        bytes: vec![],
+    };
+    Some(ObjReloc {
+        flags: LINKED_RELOC_FLAGS,
+        address: instruction.ip(),
+        target: fake_target_symbol,
+        addend: 0,
+        original: None,
+        pseudo: false,
+    })
 }
 
 fn replace_arg(
@@ -263,9 +437,11 @@ impl FormatterOutput for InstructionFormatterOutput {
         match kind {
             FormatterTextKind::LabelAddress => {
                 if let Some(reloc) = self.ins.reloc.as_ref() {
-                    if matches!(reloc.flags, RelocationFlags::Coff {
-                        typ: pe::IMAGE_REL_I386_DIR32 | pe::IMAGE_REL_I386_REL32
-                    }) {
+                    if reloc.flags == LINKED_RELOC_FLAGS
+                        || matches!(reloc.flags, RelocationFlags::Coff {
+                            typ: pe::IMAGE_REL_I386_DIR32 | pe::IMAGE_REL_I386_REL32
+                        })
+                    {
                         self.ins.args.push(ObjInsArg::Reloc);
                         return;
                     } else if self.error.is_none() {
@@ -281,9 +457,11 @@ impl FormatterOutput for InstructionFormatterOutput {
             }
             FormatterTextKind::FunctionAddress => {
                 if let Some(reloc) = self.ins.reloc.as_ref() {
-                    if matches!(reloc.flags, RelocationFlags::Coff {
-                        typ: pe::IMAGE_REL_I386_REL32
-                    }) {
+                    if reloc.flags == LINKED_RELOC_FLAGS
+                        || matches!(reloc.flags, RelocationFlags::Coff {
+                            typ: pe::IMAGE_REL_I386_REL32
+                        })
+                    {
                         self.ins.args.push(ObjInsArg::Reloc);
                         return;
                     } else if self.error.is_none() {
@@ -215,6 +215,8 @@ impl From<ObjInsDiffKind> for DiffKind {
             ObjInsDiffKind::Replace => DiffKind::DiffReplace,
             ObjInsDiffKind::Delete => DiffKind::DiffDelete,
             ObjInsDiffKind::Insert => DiffKind::DiffInsert,
+            ObjInsDiffKind::Quirk => DiffKind::DiffQuirk,
+            ObjInsDiffKind::SoftMismatch => DiffKind::DiffSoftMismatch,
         }
     }
 }
@@ -1,5 +1,8 @@
 #[cfg(feature = "any-arch")]
 pub mod diff;
+#[cfg(feature = "python")]
+pub mod python;
 pub mod report;
+pub mod report_summary;
 #[cfg(feature = "wasm")]
 pub mod wasm;
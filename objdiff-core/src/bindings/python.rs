@@ -0,0 +1,138 @@
+//! Python bindings, built with [maturin](https://www.maturin.rs) as the `pyobjdiff` module.
+//!
+//! The API mirrors the wasm bindings: objects are passed as raw bytes, the diff configuration
+//! as a JSON string (see `DiffObjConfig`), and results are returned as protobuf or JSON.
+use prost::Message;
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyBytes};
+
+use crate::{
+    bindings::{
+        diff::DiffResult,
+        report::{ReportUnit, ReportUnitMetadata},
+    },
+    diff, obj,
+};
+
+fn parse_config(config: Option<&str>) -> PyResult<diff::DiffObjConfig> {
+    config.map(serde_json::from_str).transpose().to_py().map(Option::unwrap_or_default)
+}
+
+fn parse_object(
+    data: Option<&[u8]>,
+    config: &diff::DiffObjConfig,
+) -> PyResult<Option<obj::ObjInfo>> {
+    data.map(|data| obj::read::parse(data, config)).transpose().to_py()
+}
+
+fn parse_and_run_diff(
+    left: Option<&[u8]>,
+    right: Option<&[u8]>,
+    config: Option<&str>,
+) -> PyResult<DiffResult> {
+    let config = parse_config(config)?;
+    let target = parse_object(left, &config)?;
+    let base = parse_object(right, &config)?;
+    let result = diff::diff_objs(&config, target.as_ref(), base.as_ref(), None).to_py()?;
+    let left = target.as_ref().and_then(|o| result.left.as_ref().map(|d| (o, d)));
+    let right = base.as_ref().and_then(|o| result.right.as_ref().map(|d| (o, d)));
+    Ok(DiffResult::new(left, right))
+}
+
+/// Diffs two objects, returning a protobuf-encoded `objdiff.diff.DiffResult`.
+///
+/// Passing only one object loads it without diffing.
+#[pyfunction]
+#[pyo3(signature = (left, right, config=None))]
+fn run_diff_proto<'py>(
+    py: Python<'py>,
+    left: Option<&[u8]>,
+    right: Option<&[u8]>,
+    config: Option<&str>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let out = parse_and_run_diff(left, right, config)?;
+    Ok(PyBytes::new(py, &out.encode_to_vec()))
+}
+
+/// Diffs two objects, returning an `objdiff.diff.DiffResult` as JSON.
+///
+/// Passing only one object loads it without diffing.
+#[pyfunction]
+#[pyo3(signature = (left, right, config=None))]
+fn run_diff_json(
+    left: Option<&[u8]>,
+    right: Option<&[u8]>,
+    config: Option<&str>,
+) -> PyResult<String> {
+    let out = parse_and_run_diff(left, right, config)?;
+    serde_json::to_string(&out).to_py()
+}
+
+fn run_report_unit(
+    name: String,
+    left: Option<&[u8]>,
+    right: Option<&[u8]>,
+    complete: bool,
+) -> PyResult<ReportUnit> {
+    // Reports always relax relocation diffs, matching `objdiff-cli report generate`
+    let config = diff::DiffObjConfig { relax_reloc_diffs: true, ..Default::default() };
+    let target = parse_object(left, &config)?;
+    let base = parse_object(right, &config)?;
+    let result = diff::diff_objs(&config, target.as_ref(), base.as_ref(), None).to_py()?;
+    let metadata = ReportUnitMetadata { complete: Some(complete), ..Default::default() };
+    Ok(ReportUnit::from_diff(name, target.as_ref(), base.as_ref(), &result, metadata, None))
+}
+
+/// Generates a progress report for a single unit, returning a protobuf-encoded
+/// `objdiff.report.ReportUnit`.
+#[pyfunction]
+#[pyo3(signature = (name, left, right, complete=false))]
+fn report_unit_proto<'py>(
+    py: Python<'py>,
+    name: String,
+    left: Option<&[u8]>,
+    right: Option<&[u8]>,
+    complete: bool,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let unit = run_report_unit(name, left, right, complete)?;
+    Ok(PyBytes::new(py, &unit.encode_to_vec()))
+}
+
+/// Generates a progress report for a single unit, returning an `objdiff.report.ReportUnit`
+/// as JSON.
+#[pyfunction]
+#[pyo3(signature = (name, left, right, complete=false))]
+fn report_unit_json(
+    name: String,
+    left: Option<&[u8]>,
+    right: Option<&[u8]>,
+    complete: bool,
+) -> PyResult<String> {
+    let unit = run_report_unit(name, left, right, complete)?;
+    serde_json::to_string(&unit).to_py()
+}
+
+#[pymodule]
+#[pyo3(name = "pyobjdiff")]
+fn pyobjdiff(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add_function(wrap_pyfunction!(run_diff_proto, m)?)?;
+    m.add_function(wrap_pyfunction!(run_diff_json, m)?)?;
+    m.add_function(wrap_pyfunction!(report_unit_proto, m)?)?;
+    m.add_function(wrap_pyfunction!(report_unit_json, m)?)?;
+    Ok(())
+}
+
+#[inline]
+fn to_py_error(e: impl std::fmt::Display) -> PyErr { PyValueError::new_err(e.to_string()) }
+
+trait ToPyResult {
+    type Output;
+
+    fn to_py(self) -> PyResult<Self::Output>;
+}
+
+impl<T, E: std::fmt::Display> ToPyResult for Result<T, E> {
+    type Output = T;
+
+    fn to_py(self) -> PyResult<T> { self.map_err(to_py_error) }
+}
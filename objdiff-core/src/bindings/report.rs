@@ -1,16 +1,32 @@
 #![allow(clippy::needless_lifetimes)] // Generated serde code
-use std::ops::AddAssign;
+#[cfg(feature = "any-arch")]
+use std::collections::HashSet;
+use std::{cmp::Reverse, collections::BTreeMap, ops::AddAssign};
 
 use anyhow::{bail, Result};
 use prost::Message;
 use serde_json::error::Category;
 
+#[cfg(feature = "any-arch")]
+use crate::{
+    config::{
+        match_history::{MatchHistory, SECONDS_PER_WEEK},
+        SymbolReviewState,
+    },
+    diff::{DiffObjsResult, ObjInsDiff},
+    obj::{ObjInfo, ObjSectionKind, ObjSymbolFlags},
+};
+
 // Protobuf report types
 include!(concat!(env!("OUT_DIR"), "/objdiff.report.rs"));
 include!(concat!(env!("OUT_DIR"), "/objdiff.report.serde.rs"));
 
 pub const REPORT_VERSION: u32 = 2;
 
+/// Functions with fewer instructions than this aren't given an opcode signature, since short
+/// functions such as accessors and stubs are often identical by chance.
+pub const MIN_SIGNATURE_INSTRUCTIONS: usize = 8;
+
 impl Report {
     /// Attempts to parse the report as binary protobuf or JSON.
     pub fn parse(data: &[u8]) -> Result<Self> {
@@ -46,6 +62,42 @@ impl Report {
         }
     }
 
+    /// Sorts units and their functions by name, so that the output doesn't depend on the order
+    /// units were processed in. Functions sharing a name are ordered by address and size.
+    pub fn sort(&mut self) {
+        self.units.sort_by(|a, b| a.name.cmp(&b.name));
+        for unit in &mut self.units {
+            unit.functions.sort_by(|a, b| {
+                let address = |item: &ReportItem| item.metadata.as_ref()?.virtual_address;
+                a.name
+                    .cmp(&b.name)
+                    .then_with(|| address(a).cmp(&address(b)))
+                    .then_with(|| a.size.cmp(&b.size))
+            });
+        }
+    }
+
+    /// Removes fields that change whenever unrelated code moves, such as virtual addresses,
+    /// or with the time the report is generated, such as match times. This reduces churn when
+    /// reports are stored in version control.
+    pub fn strip_volatile(&mut self) {
+        let clear_recent = |measures: &mut Measures| {
+            measures.recently_matched_functions = 0;
+            measures.recently_matched_code = 0;
+        };
+        self.measures.iter_mut().for_each(clear_recent);
+        self.categories.iter_mut().filter_map(|c| c.measures.as_mut()).for_each(clear_recent);
+        for unit in &mut self.units {
+            unit.measures.iter_mut().for_each(clear_recent);
+            for item in unit.sections.iter_mut().chain(unit.functions.iter_mut()) {
+                if let Some(metadata) = &mut item.metadata {
+                    metadata.virtual_address = None;
+                    metadata.matched_at = None;
+                }
+            }
+        }
+    }
+
     /// Migrates the report to the latest version.
     /// Fails if the report version is newer than supported.
     pub fn migrate(&mut self) -> Result<()> {
@@ -221,10 +273,262 @@ impl Report {
                 units: sub_units,
                 version: self.version,
                 categories: sub_categories,
+                clones: vec![],
             }));
         }
         reports
     }
+
+    /// Finds the functions matching a symbol name, along with their units.
+    ///
+    /// Functions are matched by their exact name or demangled name. If there are no exact
+    /// matches, falls back to a case-insensitive substring match.
+    pub fn lookup_symbol(&self, query: &str) -> Vec<SymbolLookup<'_>> {
+        let functions = || {
+            self.units.iter().flat_map(|unit| {
+                unit.functions.iter().map(move |function| SymbolLookup { unit, function })
+            })
+        };
+        let exact = functions()
+            .filter(|lookup| {
+                lookup.function.name == query || lookup.demangled_name() == Some(query)
+            })
+            .collect::<Vec<_>>();
+        if !exact.is_empty() {
+            return exact;
+        }
+        let query = query.to_ascii_lowercase();
+        functions()
+            .filter(|lookup| {
+                lookup.function.name.to_ascii_lowercase().contains(&query)
+                    || lookup
+                        .demangled_name()
+                        .is_some_and(|name| name.to_ascii_lowercase().contains(&query))
+            })
+            .collect()
+    }
+}
+
+impl Report {
+    /// Groups functions of different units that share an opcode signature, so that likely
+    /// inlined or copy-pasted code can be listed. Groups with the most code come first.
+    pub fn find_clones(&self) -> Vec<ReportCloneGroup> {
+        let mut groups = BTreeMap::<u64, Vec<ReportCloneMember>>::new();
+        for unit in &self.units {
+            for function in &unit.functions {
+                let Some(metadata) = &function.metadata else {
+                    continue;
+                };
+                let Some(signature) = metadata.opcode_signature else {
+                    continue;
+                };
+                groups.entry(signature).or_default().push(ReportCloneMember {
+                    unit: unit.name.clone(),
+                    name: function.name.clone(),
+                    demangled_name: metadata.demangled_name.clone(),
+                    size: function.size,
+                    fuzzy_match_percent: function.fuzzy_match_percent,
+                });
+            }
+        }
+        let mut clones = groups
+            .into_iter()
+            .filter(|(_, members)| members.iter().any(|m| m.unit != members[0].unit))
+            .map(|(signature, mut members)| {
+                members.sort_by(|a, b| a.unit.cmp(&b.unit).then_with(|| a.name.cmp(&b.name)));
+                ReportCloneGroup { signature, members }
+            })
+            .collect::<Vec<_>>();
+        clones.sort_by_key(|group| Reverse(group.total_size()));
+        clones
+    }
+}
+
+impl ReportCloneGroup {
+    /// Combined size of the functions in bytes.
+    pub fn total_size(&self) -> u64 { self.members.iter().map(|m| m.size).sum() }
+}
+
+/// A function found by [`Report::lookup_symbol`].
+#[derive(Debug, Clone, Copy)]
+pub struct SymbolLookup<'a> {
+    pub unit: &'a ReportUnit,
+    pub function: &'a ReportItem,
+}
+
+impl SymbolLookup<'_> {
+    pub fn demangled_name(&self) -> Option<&str> {
+        self.function.metadata.as_ref().and_then(|m| m.demangled_name.as_deref())
+    }
+}
+
+#[cfg(feature = "any-arch")]
+impl ReportUnit {
+    /// Builds a unit report from the diff of a unit's target and base objects.
+    ///
+    /// If `existing_functions` is provided, global functions that were already reported by
+    /// another unit are skipped.
+    pub fn from_diff(
+        name: String,
+        target: Option<&ObjInfo>,
+        base: Option<&ObjInfo>,
+        result: &DiffObjsResult,
+        mut metadata: ReportUnitMetadata,
+        mut existing_functions: Option<&mut HashSet<String>>,
+    ) -> Self {
+        let split_meta = target.and_then(|o| o.split_meta.as_ref());
+        if metadata.module_name.is_none() {
+            metadata.module_name = split_meta.and_then(|m| m.module_name.clone());
+        }
+        if metadata.module_id.is_none() {
+            metadata.module_id = split_meta.and_then(|m| m.module_id);
+        }
+        let complete = metadata.complete.unwrap_or(false);
+        // Support cases where we don't have a target object, assume complete means 100% match
+        let default_match_percent = if complete { 100.0 } else { 0.0 };
+
+        let mut measures = Measures { total_units: 1, ..Default::default() };
+        let mut sections = vec![];
+        let mut functions = vec![];
+
+        let obj_and_diff =
+            target.zip(result.left.as_ref()).or_else(|| base.zip(result.right.as_ref()));
+        if let Some((obj, obj_diff)) = obj_and_diff {
+            for (section, section_diff) in obj.sections.iter().zip(&obj_diff.sections) {
+                let section_match_percent =
+                    section_diff.match_percent.unwrap_or(default_match_percent);
+                sections.push(ReportItem {
+                    name: section.name.clone(),
+                    fuzzy_match_percent: section_match_percent,
+                    size: section.size,
+                    metadata: Some(ReportItemMetadata {
+                        demangled_name: None,
+                        virtual_address: section.virtual_address,
+                        size_inferred: None,
+                        matched_at: None,
+                        opcode_signature: None,
+                    }),
+                });
+
+                match section.kind {
+                    ObjSectionKind::Data | ObjSectionKind::Bss => {
+                        measures.total_data += section.size;
+                        if section_match_percent == 100.0 {
+                            measures.matched_data += section.size;
+                        }
+                        continue;
+                    }
+                    ObjSectionKind::Code => (),
+                }
+
+                for (symbol, symbol_diff) in section.symbols.iter().zip(&section_diff.symbols) {
+                    if symbol.size == 0 || symbol.flags.0.contains(ObjSymbolFlags::Hidden) {
+                        measures.ignored_symbols += 1;
+                        continue;
+                    }
+                    if let Some(existing_functions) = &mut existing_functions {
+                        if (symbol.flags.0.contains(ObjSymbolFlags::Global)
+                            || symbol.flags.0.contains(ObjSymbolFlags::Weak))
+                            && !existing_functions.insert(symbol.name.clone())
+                        {
+                            continue;
+                        }
+                    }
+                    let match_percent = symbol_diff.match_percent.unwrap_or(default_match_percent);
+                    measures.fuzzy_match_percent += match_percent * symbol.size as f32;
+                    measures.total_code += symbol.size;
+                    if match_percent == 100.0 {
+                        measures.matched_code += symbol.size;
+                    }
+                    if !symbol.size_known {
+                        measures.size_inferred_functions += 1;
+                    }
+                    functions.push(ReportItem {
+                        name: symbol.name.clone(),
+                        size: symbol.size,
+                        fuzzy_match_percent: match_percent,
+                        metadata: Some(ReportItemMetadata {
+                            demangled_name: symbol.demangled_name.clone(),
+                            virtual_address: symbol.virtual_address,
+                            size_inferred: (!symbol.size_known).then_some(true),
+                            matched_at: None,
+                            opcode_signature: opcode_signature(obj, &symbol_diff.instructions),
+                        }),
+                    });
+                    if match_percent == 100.0 {
+                        measures.matched_functions += 1;
+                    }
+                    measures.total_functions += 1;
+                }
+            }
+        }
+        if complete {
+            measures.complete_code = measures.total_code;
+            measures.complete_data = measures.total_data;
+            measures.complete_units = 1;
+        }
+        measures.calc_fuzzy_match_percent();
+        measures.calc_matched_percent();
+        ReportUnit { name, measures: Some(measures), sections, functions, metadata: Some(metadata) }
+    }
+
+    /// Counts functions with a manual review state, keyed by function name.
+    pub fn apply_review_states(&mut self, review_states: &BTreeMap<String, SymbolReviewState>) {
+        let measures = self.measures.get_or_insert_with(Default::default);
+        for function in &self.functions {
+            match review_states.get(&function.name) {
+                Some(SymbolReviewState::Matching) => measures.reviewed_matching_functions += 1,
+                Some(SymbolReviewState::VerifiedEquivalent) => {
+                    measures.verified_equivalent_functions += 1
+                }
+                Some(SymbolReviewState::Unverified) | None => {}
+            }
+        }
+    }
+
+    /// Records fully matched functions in the match history, then fills in each function's
+    /// first match time and counts functions matched within the last week. Returns whether the
+    /// history was updated.
+    pub fn apply_match_history(&mut self, history: &mut MatchHistory, now: u64) -> bool {
+        let matched = self
+            .functions
+            .iter()
+            .filter(|f| f.fuzzy_match_percent == 100.0)
+            .map(|f| (f.name.as_str(), f.size));
+        let changed = history.record(&self.name, matched, now);
+        let measures = self.measures.get_or_insert_with(Default::default);
+        for function in &mut self.functions {
+            let Some(record) = history.get(&self.name, &function.name) else {
+                continue;
+            };
+            function.metadata.get_or_insert_with(Default::default).matched_at =
+                Some(record.matched_at);
+            if function.fuzzy_match_percent == 100.0
+                && now.saturating_sub(record.matched_at) < SECONDS_PER_WEEK
+            {
+                measures.recently_matched_functions += 1;
+                measures.recently_matched_code += function.size;
+            }
+        }
+        changed
+    }
+}
+
+/// FNV-1a hash of a function's instruction opcode classes, or `None` for short functions. The
+/// hash doesn't depend on the platform or Rust version, so signatures can be compared between
+/// reports.
+#[cfg(feature = "any-arch")]
+fn opcode_signature(obj: &ObjInfo, instructions: &[ObjInsDiff]) -> Option<u64> {
+    let mut hash = 0xcbf29ce484222325u64;
+    let mut count = 0;
+    for ins in instructions.iter().filter_map(|ins_diff| ins_diff.ins.as_ref()) {
+        for byte in obj.arch.opcode_class(ins.op).to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        count += 1;
+    }
+    (count >= MIN_SIGNATURE_INSTRUCTIONS).then_some(hash)
 }
 
 impl Measures {
@@ -267,6 +571,113 @@ impl Measures {
     }
 }
 
+impl Changes {
+    /// Compares two reports, listing the units, sections and functions that changed.
+    pub fn between(previous: &Report, current: &Report) -> Self {
+        let mut changes = Changes { from: previous.measures, to: current.measures, units: vec![] };
+        for prev_unit in &previous.units {
+            let curr_unit = current.units.iter().find(|u| u.name == prev_unit.name);
+            let sections = changed_items(prev_unit, curr_unit, |u| &u.sections);
+            let functions = changed_items(prev_unit, curr_unit, |u| &u.functions);
+
+            let prev_measures = prev_unit.measures;
+            let curr_measures = curr_unit.and_then(|u| u.measures);
+            if !functions.is_empty() || prev_measures != curr_measures {
+                changes.units.push(ChangeUnit {
+                    name: prev_unit.name.clone(),
+                    from: prev_measures,
+                    to: curr_measures,
+                    sections,
+                    functions,
+                    metadata: curr_unit
+                        .as_ref()
+                        .and_then(|u| u.metadata.clone())
+                        .or_else(|| prev_unit.metadata.clone()),
+                });
+            }
+        }
+        for curr_unit in &current.units {
+            if !previous.units.iter().any(|u| u.name == curr_unit.name) {
+                changes.units.push(ChangeUnit {
+                    name: curr_unit.name.clone(),
+                    from: None,
+                    to: curr_unit.measures,
+                    sections: new_items(&curr_unit.sections),
+                    functions: new_items(&curr_unit.functions),
+                    metadata: curr_unit.metadata.clone(),
+                });
+            }
+        }
+        changes
+    }
+}
+
+fn changed_items<F: Fn(&ReportUnit) -> &Vec<ReportItem>>(
+    prev_unit: &ReportUnit,
+    curr_unit: Option<&ReportUnit>,
+    getter: F,
+) -> Vec<ChangeItem> {
+    let prev_items = getter(prev_unit);
+    let mut items = vec![];
+    if let Some(curr_unit) = curr_unit {
+        let curr_items = getter(curr_unit);
+        for prev_func in prev_items {
+            let prev_func_info = ChangeItemInfo::from(prev_func);
+            let curr_func = curr_items.iter().find(|f| f.name == prev_func.name);
+            let curr_func_info = curr_func.map(ChangeItemInfo::from);
+            if let Some(curr_func_info) = curr_func_info {
+                if prev_func_info != curr_func_info {
+                    items.push(ChangeItem {
+                        name: prev_func.name.clone(),
+                        from: Some(prev_func_info),
+                        to: Some(curr_func_info),
+                        metadata: curr_func.as_ref().unwrap().metadata.clone(),
+                    });
+                }
+            } else {
+                items.push(ChangeItem {
+                    name: prev_func.name.clone(),
+                    from: Some(prev_func_info),
+                    to: None,
+                    metadata: prev_func.metadata.clone(),
+                });
+            }
+        }
+        for curr_func in curr_items {
+            if !prev_items.iter().any(|f| f.name == curr_func.name) {
+                items.push(ChangeItem {
+                    name: curr_func.name.clone(),
+                    from: None,
+                    to: Some(ChangeItemInfo::from(curr_func)),
+                    metadata: curr_func.metadata.clone(),
+                });
+            }
+        }
+    } else {
+        for prev_func in prev_items {
+            items.push(ChangeItem {
+                name: prev_func.name.clone(),
+                from: Some(ChangeItemInfo::from(prev_func)),
+                to: None,
+                metadata: prev_func.metadata.clone(),
+            });
+        }
+    }
+    items
+}
+
+fn new_items(items: &[ReportItem]) -> Vec<ChangeItem> {
+    items
+        .iter()
+        .map(|item| ChangeItem {
+            name: item.name.clone(),
+            from: None,
+            to: Some(ChangeItemInfo::from(item)),
+            metadata: item.metadata.clone(),
+        })
+        .collect()
+}
+
 impl From<&ReportItem> for ChangeItemInfo {
     fn from(value: &ReportItem) -> Self {
         Self { fuzzy_match_percent: value.fuzzy_match_percent, size: value.size }
@@ -286,6 +697,12 @@ impl AddAssign for Measures {
         self.complete_data += other.complete_data;
         self.total_units += other.total_units;
         self.complete_units += other.complete_units;
+        self.size_inferred_functions += other.size_inferred_functions;
+        self.ignored_symbols += other.ignored_symbols;
+        self.reviewed_matching_functions += other.reviewed_matching_functions;
+        self.verified_equivalent_functions += other.verified_equivalent_functions;
+        self.recently_matched_functions += other.recently_matched_functions;
+        self.recently_matched_code += other.recently_matched_code;
     }
 }
 
@@ -414,6 +831,9 @@ impl From<LegacyReportItem> for ReportItem {
             metadata: Some(ReportItemMetadata {
                 demangled_name: value.demangled_name,
                 virtual_address: value.address,
+                size_inferred: None,
+                matched_at: None,
+                opcode_signature: None,
             }),
         }
     }
@@ -0,0 +1,258 @@
+//! Human-readable progress summaries of a [`Report`], for READMEs and CI pages. The summary
+//! reads the same measures as the JSON and protobuf output. The printable variant also lists the
+//! functions of each unit, for progress snapshots kept in documents.
+
+use std::fmt::Write;
+
+use crate::bindings::report::{Measures, Report, ReportCloneGroup};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SummaryFormat {
+    /// GitHub-flavored markdown tables
+    Markdown,
+    /// A standalone HTML page
+    Html,
+    /// A standalone HTML page listing the functions of each unit, styled for printing or saving
+    /// as a PDF from a browser
+    Printable,
+}
+
+/// Columns after the name column.
+const COLUMNS: [&str; 5] = ["Fuzzy match", "Matched code", "Matched data", "Functions", "Linked"];
+
+fn size_cell(percent: f32, matched: u64, total: u64) -> String {
+    format!("{percent:.2}% ({matched} / {total} bytes)")
+}
+
+fn row_cells(name: &str, measures: &Measures) -> [String; 6] {
+    [
+        name.to_string(),
+        format!("{:.2}%", measures.fuzzy_match_percent),
+        size_cell(measures.matched_code_percent, measures.matched_code, measures.total_code),
+        size_cell(measures.matched_data_percent, measures.matched_data, measures.total_data),
+        format!(
+            "{:.2}% ({} / {})",
+            measures.matched_functions_percent,
+            measures.matched_functions,
+            measures.total_functions
+        ),
+        if measures.total_units > 0 {
+            format!(
+                "{:.2}% ({} / {} units)",
+                measures.complete_code_percent, measures.complete_units, measures.total_units
+            )
+        } else {
+            format!("{:.2}%", measures.complete_code_percent)
+        },
+    ]
+}
+
+/// A table of rows, each with a name and its measures. The first row of the overview is the
+/// total, which is emphasized.
+struct Table<'a> {
+    title: &'static str,
+    name_column: &'static str,
+    rows: Vec<(&'a str, Measures)>,
+}
+
+impl Report {
+    fn summary_tables(&self) -> Vec<Table<'_>> {
+        let mut overview = vec![("Total", self.measures.unwrap_or_default())];
+        for category in &self.categories {
+            let name = if category.name.is_empty() { &category.id } else { &category.name };
+            overview.push((name.as_str(), category.measures.unwrap_or_default()));
+        }
+        let units =
+            self.units.iter().map(|u| (u.name.as_str(), u.measures.unwrap_or_default())).collect();
+        vec![Table { title: "Progress", name_column: "Category", rows: overview }, Table {
+            title: "Units",
+            name_column: "Unit",
+            rows: units,
+        }]
+    }
+
+    /// Renders the overall, per-category and per-unit progress as tables.
+    pub fn summary(&self, format: SummaryFormat) -> String {
+        let tables = self.summary_tables();
+        match format {
+            SummaryFormat::Markdown => markdown_summary(&tables, &self.clones),
+            SummaryFormat::Html => html_summary(&tables, &self.clones),
+            SummaryFormat::Printable => printable_summary(self, &tables[0]),
+        }
+    }
+}
+
+fn markdown_escape(s: &str) -> String { s.replace('|', "\\|") }
+
+/// Columns of the duplicated code table.
+const CLONE_COLUMNS: [&str; 4] = ["Function", "Copies", "Units", "Total size"];
+
+fn clone_cells(group: &ReportCloneGroup) -> [String; 4] {
+    let first = &group.members[0];
+    let mut units = group.members.iter().map(|m| m.unit.as_str()).collect::<Vec<_>>();
+    units.dedup();
+    [
+        first.demangled_name.as_deref().unwrap_or(&first.name).to_string(),
+        group.members.len().to_string(),
+        units.join(", "),
+        format!("{} bytes", group.total_size()),
+    ]
+}
+
+fn markdown_summary(tables: &[Table], clones: &[ReportCloneGroup]) -> String {
+    let mut out = String::new();
+    for (i, table) in tables.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let _ = writeln!(out, "## {}\n", table.title);
+        let _ = writeln!(out, "| {} | {} |", table.name_column, COLUMNS.join(" | "));
+        let _ = writeln!(out, "|:---|{}", "---:|".repeat(COLUMNS.len()));
+        for (j, (name, measures)) in table.rows.iter().enumerate() {
+            let cells = row_cells(name, measures).map(|c| markdown_escape(&c));
+            if i == 0 && j == 0 {
+                let _ = writeln!(out, "| **{}** | {} |", cells[0], cells[1..].join(" | "));
+            } else {
+                let _ = writeln!(out, "| {} |", cells.join(" | "));
+            }
+        }
+    }
+    if !clones.is_empty() {
+        let _ = writeln!(out, "\n## Duplicated code\n");
+        let _ = writeln!(out, "| {} |", CLONE_COLUMNS.join(" | "));
+        let _ = writeln!(out, "|:---|---:|:---|---:|");
+        for group in clones {
+            let cells = clone_cells(group).map(|c| markdown_escape(&c));
+            let _ = writeln!(out, "| {} |", cells.join(" | "));
+        }
+    }
+    out
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+const HTML_STYLE: &str = "body{font-family:sans-serif;margin:2em}\
+table{border-collapse:collapse;margin-bottom:2em}\
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:right}\
+th:first-child,td:first-child{text-align:left}\
+tr.total{font-weight:bold}\
+progress{width:6em;margin-right:0.5em;vertical-align:middle}";
+
+fn html_header(out: &mut String, style: &str) {
+    out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str("<title>Progress report</title>\n");
+    let _ = writeln!(out, "<style>{style}</style>");
+    out.push_str("</head>\n<body>\n");
+}
+
+fn html_table(out: &mut String, table: &Table, emphasize_total: bool) {
+    let _ = writeln!(out, "<h2>{}</h2>\n<table>", table.title);
+    let _ = write!(out, "<tr><th>{}</th>", table.name_column);
+    for column in COLUMNS {
+        let _ = write!(out, "<th>{column}</th>");
+    }
+    out.push_str("</tr>\n");
+    for (j, (name, measures)) in table.rows.iter().enumerate() {
+        if emphasize_total && j == 0 {
+            out.push_str("<tr class=\"total\">");
+        } else {
+            out.push_str("<tr>");
+        }
+        for (k, cell) in row_cells(name, measures).iter().enumerate() {
+            // Show a bar for the fuzzy match percent
+            if k == 1 {
+                let _ = write!(
+                    out,
+                    "<td><progress max=\"100\" value=\"{:.2}\"></progress>{}</td>",
+                    measures.fuzzy_match_percent,
+                    html_escape(cell)
+                );
+            } else {
+                let _ = write!(out, "<td>{}</td>", html_escape(cell));
+            }
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+}
+
+fn html_summary(tables: &[Table], clones: &[ReportCloneGroup]) -> String {
+    let mut out = String::new();
+    html_header(&mut out, HTML_STYLE);
+    for (i, table) in tables.iter().enumerate() {
+        html_table(&mut out, table, i == 0);
+    }
+    if !clones.is_empty() {
+        out.push_str("<h2>Duplicated code</h2>\n<table>\n<tr>");
+        for column in CLONE_COLUMNS {
+            let _ = write!(out, "<th>{column}</th>");
+        }
+        out.push_str("</tr>\n");
+        for group in clones {
+            out.push_str("<tr>");
+            for cell in clone_cells(group) {
+                let _ = write!(out, "<td>{}</td>", html_escape(&cell));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Keeps each unit on one page where possible, and drops the margins the browser adds anyway.
+const PRINT_STYLE: &str = "@media print{body{margin:0}\
+section.unit{break-inside:avoid}\
+h2{break-after:avoid}}\
+section.unit h3{margin-bottom:0.25em}\
+p.unit-summary{margin-top:0;color:#555}\
+td.matched{color:#2a7d2a}";
+
+fn printable_summary(report: &Report, overview: &Table) -> String {
+    let mut out = String::new();
+    html_header(&mut out, &format!("{HTML_STYLE}{PRINT_STYLE}"));
+    html_table(&mut out, overview, true);
+    out.push_str("<h2>Units</h2>\n");
+    for unit in &report.units {
+        let measures = unit.measures.unwrap_or_default();
+        out.push_str("<section class=\"unit\">\n");
+        let _ = writeln!(out, "<h3>{}</h3>", html_escape(&unit.name));
+        let _ = writeln!(
+            out,
+            "<p class=\"unit-summary\">{:.2}% fuzzy match, {} / {} functions matched, {} / {} \
+             bytes of code matched</p>",
+            measures.fuzzy_match_percent,
+            measures.matched_functions,
+            measures.total_functions,
+            measures.matched_code,
+            measures.total_code
+        );
+        if unit.functions.is_empty() {
+            out.push_str("</section>\n");
+            continue;
+        }
+        out.push_str("<table>\n<tr><th>Function</th><th>Size</th><th>Match</th></tr>\n");
+        for function in &unit.functions {
+            let name = function
+                .metadata
+                .as_ref()
+                .and_then(|m| m.demangled_name.as_deref())
+                .unwrap_or(&function.name);
+            let class =
+                if function.fuzzy_match_percent >= 100.0 { " class=\"matched\"" } else { "" };
+            let _ = writeln!(
+                out,
+                "<tr><td>{}</td><td>{}</td><td{class}>{:.2}%</td></tr>",
+                html_escape(name),
+                function.size,
+                function.fuzzy_match_percent
+            );
+        }
+        out.push_str("</table>\n</section>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
@@ -1,7 +1,13 @@
 use prost::Message;
 use wasm_bindgen::prelude::*;
 
-use crate::{bindings::diff::DiffResult, diff, obj};
+use crate::{
+    bindings::{
+        diff::DiffResult,
+        report::{ReportUnit, ReportUnitMetadata},
+    },
+    diff, obj,
+};
 
 fn parse_object(
     data: Option<Box<[u8]>>,
@@ -52,6 +58,22 @@ pub fn run_diff_proto(
     Ok(out.encode_to_vec().into_boxed_slice())
 }
 
+#[wasm_bindgen]
+pub fn report_unit_proto(
+    name: String,
+    left: Option<Box<[u8]>>,
+    right: Option<Box<[u8]>>,
+    complete: bool,
+) -> Result<Box<[u8]>, JsError> {
+    let config = diff::DiffObjConfig { relax_reloc_diffs: true, ..Default::default() };
+    let target = parse_object(left, &config)?;
+    let base = parse_object(right, &config)?;
+    let result = diff::diff_objs(&config, target.as_ref(), base.as_ref(), None).to_js()?;
+    let metadata = ReportUnitMetadata { complete: Some(complete), ..Default::default() };
+    let unit = ReportUnit::from_diff(name, target.as_ref(), base.as_ref(), &result, metadata, None);
+    Ok(unit.encode_to_vec().into_boxed_slice())
+}
+
 #[wasm_bindgen(start)]
 fn start() -> Result<(), JsError> {
     console_error_panic_hook::set_once();
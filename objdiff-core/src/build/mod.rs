@@ -1,10 +1,19 @@
 pub mod watcher;
 
 use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    fs,
+    hash::{Hash, Hasher},
+    io::{BufRead, BufReader, Read},
     path::{Path, PathBuf},
-    process::Command,
+    process::{Child, Command, Stdio},
+    sync::{mpsc, Arc, Mutex},
+    thread::JoinHandle,
+    time::{Duration, SystemTime},
 };
 
+use crate::util::CancellationToken;
+
 pub struct BuildStatus {
     pub success: bool,
     pub cmdline: String,
@@ -23,6 +32,14 @@ impl Default for BuildStatus {
     }
 }
 
+/// Observes a running build process.
+pub struct BuildMonitor<'a> {
+    /// Called with each line of stdout or stderr as it's produced, without the line ending.
+    pub on_output: &'a mut dyn FnMut(&str),
+    /// Kills the build process and its children when cancelled.
+    pub cancel: &'a CancellationToken,
+}
+
 #[derive(Debug, Clone)]
 pub struct BuildConfig {
     pub project_dir: Option<PathBuf>,
@@ -32,7 +49,109 @@ pub struct BuildConfig {
     pub selected_wsl_distro: Option<String>,
 }
 
-pub fn run_make(config: &BuildConfig, arg: &Path) -> BuildStatus {
+/// Fingerprints of the unit inputs at the time each object was last built
+/// successfully. Used to skip running the build system when nothing has changed.
+#[derive(Debug, Clone, Default)]
+pub struct BuildCache(Arc<Mutex<HashMap<PathBuf, u64>>>);
+
+impl BuildCache {
+    /// Whether the object was built from inputs matching the fingerprint and still exists.
+    pub fn is_fresh(&self, project_dir: &Path, obj_path: &Path, fingerprint: u64) -> bool {
+        let Ok(entries) = self.0.lock() else { return false };
+        entries.get(obj_path) == Some(&fingerprint) && project_dir.join(obj_path).is_file()
+    }
+
+    pub fn insert(&self, obj_path: &Path, fingerprint: u64) {
+        if let Ok(mut entries) = self.0.lock() {
+            entries.insert(obj_path.to_path_buf(), fingerprint);
+        }
+    }
+
+    pub fn remove(&self, obj_path: &Path) {
+        if let Ok(mut entries) = self.0.lock() {
+            entries.remove(obj_path);
+        }
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.0.lock() {
+            entries.clear();
+        }
+    }
+}
+
+/// Computes a fingerprint of a unit's build inputs, based on their paths, sizes and modification
+/// times. The inputs are the unit's source file and the dependencies listed in the depfiles
+/// (`.d`) of its objects, if the build system emits them. Paths are relative to the project
+/// directory. Returns `None` if none of the unit's inputs are known.
+pub fn fingerprint_unit_inputs(
+    project_dir: &Path,
+    source_path: Option<&Path>,
+    obj_paths: &[&Path],
+) -> Option<u64> {
+    let mut inputs = source_path.map(Path::to_path_buf).into_iter().collect::<Vec<_>>();
+    for obj_path in obj_paths {
+        // Either `foo.o.d` or `foo.d`, depending on the build system
+        let obj_path = project_dir.join(obj_path);
+        let mut depfile = obj_path.clone().into_os_string();
+        depfile.push(".d");
+        let contents = fs::read_to_string(PathBuf::from(depfile))
+            .or_else(|_| fs::read_to_string(obj_path.with_extension("d")));
+        if let Ok(contents) = contents {
+            inputs.extend(parse_depfile(&contents));
+        }
+    }
+    if inputs.is_empty() {
+        return None;
+    }
+    inputs.sort_unstable();
+    inputs.dedup();
+    let mut hasher = DefaultHasher::new();
+    for input in &inputs {
+        input.hash(&mut hasher);
+        match fs::metadata(project_dir.join(input)) {
+            Ok(metadata) => {
+                metadata.len().hash(&mut hasher);
+                metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH).hash(&mut hasher);
+            }
+            // A missing input is part of the fingerprint too
+            Err(_) => u64::MAX.hash(&mut hasher),
+        }
+    }
+    Some(hasher.finish())
+}
+
+/// Parses the prerequisites of a Makefile-style depfile, as written by `-MMD` and similar
+/// compiler options.
+fn parse_depfile(contents: &str) -> Vec<PathBuf> {
+    let mut deps = Vec::new();
+    let contents = contents.replace("\\\r\n", " ").replace("\\\n", " ");
+    for line in contents.lines() {
+        // Skip the target(s), which may contain a drive letter on Windows
+        let Some(idx) = line.find(": ") else {
+            continue;
+        };
+        let mut dep = String::new();
+        let mut chars = line[idx + 1..].chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' if chars.peek() == Some(&' ') => dep.push(chars.next().unwrap()),
+                c if c.is_whitespace() => {
+                    if !dep.is_empty() {
+                        deps.push(PathBuf::from(std::mem::take(&mut dep)));
+                    }
+                }
+                c => dep.push(c),
+            }
+        }
+        if !dep.is_empty() {
+            deps.push(PathBuf::from(dep));
+        }
+    }
+    deps
+}
+
+pub fn run_make(config: &BuildConfig, arg: &Path, monitor: Option<BuildMonitor>) -> BuildStatus {
     let Some(cwd) = &config.project_dir else {
         return BuildStatus {
             success: false,
@@ -43,13 +162,13 @@ pub fn run_make(config: &BuildConfig, arg: &Path) -> BuildStatus {
     let make = config.custom_make.as_deref().unwrap_or("make");
     let make_args = config.custom_args.as_deref().unwrap_or(&[]);
     #[cfg(not(windows))]
-    let mut command = {
+    let command = {
         let mut command = Command::new(make);
         command.current_dir(cwd).args(make_args).arg(arg);
         command
     };
     #[cfg(windows)]
-    let mut command = {
+    let command = {
         use std::os::windows::process::CommandExt;
 
         use path_slash::PathExt;
@@ -59,16 +178,9 @@ pub fn run_make(config: &BuildConfig, arg: &Path) -> BuildStatus {
             Command::new(make)
         };
         if let Some(distro) = &config.selected_wsl_distro {
-            // Strip distro root prefix \\wsl.localhost\{distro}
-            let wsl_path_prefix = format!("\\\\wsl.localhost\\{}", distro);
-            let cwd = match cwd.strip_prefix(wsl_path_prefix) {
-                Ok(new_cwd) => format!("/{}", new_cwd.to_slash_lossy().as_ref()),
-                Err(_) => cwd.to_string_lossy().to_string(),
-            };
-
             command
                 .arg("--cd")
-                .arg(cwd)
+                .arg(wsl_path(cwd, distro))
                 .arg("-d")
                 .arg(distro)
                 .arg("--")
@@ -81,13 +193,153 @@ pub fn run_make(config: &BuildConfig, arg: &Path) -> BuildStatus {
         command.creation_flags(winapi::um::winbase::CREATE_NO_WINDOW);
         command
     };
+    run_command(command, monitor)
+}
+
+/// Runs a command line through the shell in the project directory, inside the selected WSL
+/// distro if any.
+pub fn run_shell_command(
+    config: &BuildConfig,
+    cmdline: &str,
+    monitor: Option<BuildMonitor>,
+) -> BuildStatus {
+    let Some(cwd) = &config.project_dir else {
+        return BuildStatus {
+            success: false,
+            stderr: "Missing project dir".to_string(),
+            ..Default::default()
+        };
+    };
+    #[cfg(not(windows))]
+    let command = {
+        let mut command = Command::new("sh");
+        command.current_dir(cwd).arg("-c").arg(cmdline);
+        command
+    };
+    #[cfg(windows)]
+    let command = {
+        use std::os::windows::process::CommandExt;
+        let mut command = if let Some(distro) = &config.selected_wsl_distro {
+            let mut command = Command::new("wsl");
+            command
+                .arg("--cd")
+                .arg(wsl_path(cwd, distro))
+                .arg("-d")
+                .arg(distro)
+                .arg("--")
+                .arg("sh")
+                .arg("-c")
+                .arg(cmdline);
+            command
+        } else {
+            let mut command = Command::new("cmd");
+            command.current_dir(cwd).arg("/C").raw_arg(cmdline);
+            command
+        };
+        command.creation_flags(winapi::um::winbase::CREATE_NO_WINDOW);
+        command
+    };
+    run_command(command, monitor)
+}
+
+/// Converts a path within a WSL distro's filesystem to a path inside the distro.
+#[cfg(windows)]
+fn wsl_path(path: &Path, distro: &str) -> String {
+    use path_slash::PathExt;
+    // Strip distro root prefix \\wsl.localhost\{distro}
+    let wsl_path_prefix = format!("\\\\wsl.localhost\\{}", distro);
+    match path.strip_prefix(wsl_path_prefix) {
+        Ok(path) => format!("/{}", path.to_slash_lossy().as_ref()),
+        Err(_) => path.to_string_lossy().to_string(),
+    }
+}
+
+/// Runs a compiler invocation directly, such as one taken from a `compile_commands.json` entry.
+pub fn run_compile_command(
+    directory: &Path,
+    arguments: &[String],
+    monitor: Option<BuildMonitor>,
+) -> BuildStatus {
+    let Some((program, args)) = arguments.split_first() else {
+        return BuildStatus {
+            success: false,
+            stderr: "Empty compile command".to_string(),
+            ..Default::default()
+        };
+    };
+    let mut command = Command::new(program);
+    command.current_dir(directory).args(args);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(winapi::um::winbase::CREATE_NO_WINDOW);
+    }
+    run_command(command, monitor)
+}
+
+/// Reads lines from a child process pipe on a separate thread, sending them with a flag
+/// indicating whether they came from stderr.
+fn spawn_reader(
+    reader: impl Read + Send + 'static,
+    is_stderr: bool,
+    tx: mpsc::Sender<(bool, String)>,
+) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let line = String::from_utf8_lossy(&buf).into_owned();
+                    if tx.send((is_stderr, line)).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Kills a build process along with any processes it spawned (e.g. compilers run by make).
+fn kill_process_tree(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        // The child was started in its own process group, so signal the whole group
+        let _ = Command::new("kill")
+            .args(["-KILL", "--", &format!("-{}", child.id())])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        let _ = Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &child.id().to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .creation_flags(winapi::um::winbase::CREATE_NO_WINDOW)
+            .status();
+    }
+    let _ = child.kill();
+}
+
+fn run_command(mut command: Command, mut monitor: Option<BuildMonitor>) -> BuildStatus {
     let mut cmdline = shell_escape::escape(command.get_program().to_string_lossy()).into_owned();
     for arg in command.get_args() {
         cmdline.push(' ');
         cmdline.push_str(shell_escape::escape(arg.to_string_lossy()).as_ref());
     }
-    let output = match command.output() {
-        Ok(output) => output,
+    command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = match command.spawn() {
+        Ok(child) => child,
         Err(e) => {
             return BuildStatus {
                 success: false,
@@ -97,10 +349,55 @@ pub fn run_make(config: &BuildConfig, arg: &Path) -> BuildStatus {
             };
         }
     };
-    // Try from_utf8 first to avoid copying the buffer if it's valid, then fall back to from_utf8_lossy
-    let stdout = String::from_utf8(output.stdout)
-        .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned());
-    let stderr = String::from_utf8(output.stderr)
-        .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned());
-    BuildStatus { success: output.status.success(), cmdline, stdout, stderr }
+
+    let (tx, rx) = mpsc::channel();
+    let mut readers = vec![];
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(spawn_reader(stdout, false, tx.clone()));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(spawn_reader(stderr, true, tx));
+    } else {
+        drop(tx);
+    }
+
+    // Collect output as it arrives until both pipes are closed, polling for cancellation
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+    let mut cancelled = false;
+    loop {
+        match rx.recv_timeout(Duration::from_millis(50)) {
+            Ok((is_stderr, line)) => {
+                if let Some(monitor) = &mut monitor {
+                    (monitor.on_output)(line.trim_end_matches(['\r', '\n']));
+                }
+                if is_stderr {
+                    stderr.push_str(&line);
+                } else {
+                    stdout.push_str(&line);
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+        if !cancelled && monitor.as_ref().is_some_and(|m| m.cancel.is_cancelled()) {
+            kill_process_tree(&mut child);
+            cancelled = true;
+        }
+    }
+    for reader in readers {
+        let _ = reader.join();
+    }
+
+    let success = match child.wait() {
+        Ok(status) => status.success() && !cancelled,
+        Err(e) => {
+            stderr.push_str(&e.to_string());
+            false
+        }
+    };
+    if cancelled {
+        stderr.push_str("Build cancelled\n");
+    }
+    BuildStatus { success, cmdline, stdout, stderr }
 }
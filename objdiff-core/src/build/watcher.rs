@@ -1,4 +1,5 @@
 use std::{
+    collections::HashSet,
     fs,
     path::{Path, PathBuf},
     sync::{
@@ -13,6 +14,8 @@ use globset::GlobSet;
 use notify::RecursiveMode;
 use notify_debouncer_full::{new_debouncer_opt, DebounceEventResult};
 
+use crate::config::match_history::STATE_DIR;
+
 pub type Watcher = notify_debouncer_full::Debouncer<
     notify::RecommendedWatcher,
     notify_debouncer_full::RecommendedCache,
@@ -25,15 +28,45 @@ pub struct WatcherState {
     pub patterns: GlobSet,
 }
 
+/// Default time to wait for changes to settle before notifying, in milliseconds.
+pub const DEFAULT_WATCH_DEBOUNCE_MS: u64 = 200;
+
+pub struct WatcherOptions {
+    /// Time to wait for changes to settle before notifying
+    pub debounce: Duration,
+    /// Paths relative to the project directory that never trigger a notification, even if they
+    /// match the watch patterns
+    pub excluded_paths: HashSet<PathBuf>,
+}
+
+impl Default for WatcherOptions {
+    fn default() -> Self {
+        Self {
+            debounce: Duration::from_millis(DEFAULT_WATCH_DEBOUNCE_MS),
+            excluded_paths: HashSet::new(),
+        }
+    }
+}
+
+impl WatcherOptions {
+    pub fn with_debounce_ms(debounce_ms: Option<u64>) -> Self {
+        Self {
+            debounce: Duration::from_millis(debounce_ms.unwrap_or(DEFAULT_WATCH_DEBOUNCE_MS)),
+            ..Default::default()
+        }
+    }
+}
+
 pub fn create_watcher(
     modified: Arc<AtomicBool>,
     project_dir: &Path,
     patterns: GlobSet,
+    options: WatcherOptions,
     waker: Waker,
 ) -> notify::Result<Watcher> {
     let base_dir = fs::canonicalize(project_dir)?;
     let base_dir_clone = base_dir.clone();
-    let timeout = Duration::from_millis(200);
+    let WatcherOptions { debounce: timeout, excluded_paths } = options;
     let config = notify::Config::default().with_poll_interval(Duration::from_secs(2));
     let mut debouncer = new_debouncer_opt(
         timeout,
@@ -54,6 +87,9 @@ pub fn create_watcher(
                         let Ok(path) = path.strip_prefix(&base_dir_clone) else {
                             continue;
                         };
+                        if path.starts_with(STATE_DIR) || excluded_paths.contains(path) {
+                            continue;
+                        }
                         if patterns.is_match(path) {
                             // log::info!("File modified: {}", path.display());
                             any_match = true;
@@ -0,0 +1,128 @@
+//! Lightweight per-unit claims, so contributors can mark the units they're working on. Each claim
+//! is a small file in a shared directory next to the project config, which can be committed or
+//! synced with the project so that others see who's working on what.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use filetime::FileTime;
+
+use crate::config::ProjectConfig;
+
+/// Default claims directory, relative to the project directory.
+pub const DEFAULT_CLAIMS_DIR: &str = ".objdiff-claims";
+/// Claim file extension. It isn't matched by the default watch patterns, so claiming a unit
+/// doesn't trigger a rebuild.
+pub const CLAIM_EXTENSION: &str = "claim";
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UnitClaim {
+    pub unit: String,
+    /// Name of the person who claimed the unit.
+    pub name: String,
+    /// Unix timestamp (seconds) when the unit was claimed.
+    pub claimed_at: u64,
+}
+
+/// The claims of a project, keyed by unit name.
+#[derive(Debug, Clone, Default)]
+pub struct Claims {
+    pub dir: PathBuf,
+    pub claims: BTreeMap<String, UnitClaim>,
+    /// Modification time of the directory when loaded, which changes when claims are added or
+    /// removed.
+    timestamp: Option<FileTime>,
+}
+
+/// The claims directory configured by the project, or [`DEFAULT_CLAIMS_DIR`].
+pub fn claims_dir(project_dir: &Path, config: Option<&ProjectConfig>) -> PathBuf {
+    match config.and_then(|c| c.claims_dir.as_deref()) {
+        Some(dir) => project_dir.join(dir),
+        None => project_dir.join(DEFAULT_CLAIMS_DIR),
+    }
+}
+
+/// Unit names may contain path separators, so anything other than `[A-Za-z0-9._-]` is
+/// percent-encoded.
+fn claim_file_name(unit: &str) -> String {
+    let mut out = String::with_capacity(unit.len() + CLAIM_EXTENSION.len() + 1);
+    for b in unit.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out.push('.');
+    out.push_str(CLAIM_EXTENSION);
+    out
+}
+
+fn dir_timestamp(dir: &Path) -> Option<FileTime> {
+    fs::metadata(dir).ok().map(|m| FileTime::from_last_modification_time(&m))
+}
+
+impl Claims {
+    /// An empty set of claims stored in `dir`.
+    pub fn new(dir: PathBuf) -> Self {
+        Self { timestamp: dir_timestamp(&dir), dir, claims: BTreeMap::new() }
+    }
+
+    /// Loads all claims in the directory. A missing directory has no claims, and files that
+    /// can't be parsed (e.g. while being synced) are skipped.
+    pub fn load(dir: PathBuf) -> Result<Self> {
+        let mut claims = Self::new(dir);
+        if claims.dir.is_dir() {
+            let entries = fs::read_dir(&claims.dir)
+                .with_context(|| format!("Failed to read {}", claims.dir.display()))?;
+            for entry in entries {
+                let path = entry?.path();
+                if !path.extension().is_some_and(|ext| ext == CLAIM_EXTENSION) {
+                    continue;
+                }
+                let Ok(data) = fs::read(&path) else {
+                    continue;
+                };
+                let Ok(claim) = serde_json::from_slice::<UnitClaim>(&data) else {
+                    continue;
+                };
+                claims.claims.insert(claim.unit.clone(), claim);
+            }
+        }
+        Ok(claims)
+    }
+
+    /// Whether claims were added or removed since they were loaded.
+    pub fn is_modified(&self) -> bool { dir_timestamp(&self.dir) != self.timestamp }
+
+    pub fn get(&self, unit: &str) -> Option<&UnitClaim> { self.claims.get(unit) }
+
+    /// Claims a unit, replacing any existing claim.
+    pub fn claim(&mut self, unit: &str, name: &str, now: u64) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let claim = UnitClaim { unit: unit.to_string(), name: name.to_string(), claimed_at: now };
+        let path = self.dir.join(claim_file_name(unit));
+        let data = serde_json::to_vec_pretty(&claim)?;
+        fs::write(&path, data).with_context(|| format!("Failed to write {}", path.display()))?;
+        self.claims.insert(claim.unit.clone(), claim);
+        self.timestamp = dir_timestamp(&self.dir);
+        Ok(())
+    }
+
+    /// Releases the claim on a unit, if any.
+    pub fn release(&mut self, unit: &str) -> Result<()> {
+        let path = self.dir.join(claim_file_name(unit));
+        if path.is_file() {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        self.claims.remove(unit);
+        self.timestamp = dir_timestamp(&self.dir);
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+//! Support for resolving unit base objects from a `compile_commands.json` compilation database,
+//! as generated by CMake (`CMAKE_EXPORT_COMPILE_COMMANDS`), ninja (`-t compdb`) or bear.
+
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+/// A raw entry in a compilation database.
+#[derive(Clone, serde::Deserialize)]
+struct CompileCommandEntry {
+    directory: PathBuf,
+    file: PathBuf,
+    #[serde(default)]
+    command: Option<String>,
+    #[serde(default)]
+    arguments: Option<Vec<String>>,
+    #[serde(default)]
+    output: Option<PathBuf>,
+}
+
+/// A compile command resolved for a unit: the command used to rebuild the base object and
+/// the path of the object it produces.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CompileCommand {
+    /// Working directory for the command.
+    pub directory: PathBuf,
+    /// Program and arguments.
+    pub arguments: Vec<String>,
+    /// Absolute path to the output object.
+    pub output: PathBuf,
+}
+
+/// A loaded compilation database.
+pub struct CompileCommands {
+    entries: Vec<CompileCommandEntry>,
+}
+
+impl CompileCommands {
+    pub fn load(path: &Path) -> Result<Self> {
+        let file =
+            File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        let entries = serde_json::from_reader(BufReader::new(file))
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Self { entries })
+    }
+
+    /// Finds the entry compiling `source` (relative to `project_dir`) and derives its output
+    /// object path from the `output` field or the compiler's `-o`/`/Fo` argument.
+    pub fn find(&self, project_dir: &Path, source: &Path) -> Result<CompileCommand> {
+        let source = normalize(&project_dir.join(source));
+        let entry = self
+            .entries
+            .iter()
+            .find(|e| normalize(&e.directory.join(&e.file)) == source)
+            .ok_or_else(|| anyhow!("No compile command found for {}", source.display()))?;
+        let arguments = match (&entry.arguments, &entry.command) {
+            (Some(arguments), _) => arguments.clone(),
+            (None, Some(command)) => split_command(command),
+            (None, None) => vec![],
+        };
+        let output = entry
+            .output
+            .clone()
+            .or_else(|| output_argument(&arguments))
+            .ok_or_else(|| anyhow!("Compile command for {} has no output", source.display()))?;
+        let output = normalize(&entry.directory.join(output));
+        Ok(CompileCommand { directory: entry.directory.clone(), arguments, output })
+    }
+}
+
+fn output_argument(arguments: &[String]) -> Option<PathBuf> {
+    let mut iter = arguments.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "-o" {
+            return iter.next().map(PathBuf::from);
+        } else if let Some(path) = arg.strip_prefix("-o") {
+            return Some(PathBuf::from(path));
+        } else if let Some(path) = arg.strip_prefix("/Fo").or_else(|| arg.strip_prefix("-Fo")) {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+/// Splits a shell command line into arguments, handling quotes and backslash escapes.
+/// Backslashes not followed by a quote, backslash or whitespace are kept as-is, so that
+/// Windows paths survive.
+fn split_command(command: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quote = None;
+    let mut chars = command.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', Some('\'')) => current.push(c),
+            ('\\', _) => {
+                match chars.peek() {
+                    Some(&next) if matches!(next, '"' | '\'' | '\\') || next.is_whitespace() => {
+                        current.push(next);
+                        chars.next();
+                    }
+                    _ => current.push(c),
+                }
+                in_arg = true;
+            }
+            ('"' | '\'', None) => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            (c, Some(q)) if c == q => quote = None,
+            (c, None) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            (c, _) => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+/// Lexically normalizes a path, resolving `.` and `..` components without touching the
+/// filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !out.pop() {
+                    out.push(component);
+                }
+            }
+            _ => out.push(component),
+        }
+    }
+    out
+}
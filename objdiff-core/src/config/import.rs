@@ -0,0 +1,185 @@
+//! Generating a project configuration from the project files of other decompilation tools:
+//! [decomp-toolkit](https://github.com/encounter/decomp-toolkit) (`config.yml`, with units
+//! listed in `splits.txt`) and [splat](https://github.com/ethteck/splat) (`splat.yaml`).
+//!
+//! Object paths follow the layout of each tool's project template, and can be adjusted in the
+//! generated config afterwards.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde_yaml::Value;
+
+use crate::config::{default_watch_patterns, ProjectConfig, ProjectObject, ProjectObjectMetadata};
+
+/// The parts of a decomp-toolkit `config.yml` needed to list its units.
+#[derive(serde::Deserialize)]
+struct DtkConfig {
+    splits: Option<PathBuf>,
+    #[serde(default)]
+    modules: Vec<DtkModuleConfig>,
+}
+
+#[derive(serde::Deserialize)]
+struct DtkModuleConfig {
+    object: PathBuf,
+    splits: Option<PathBuf>,
+}
+
+fn read_yaml<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    let data =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_yaml::from_str(&data).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Unit names from a decomp-toolkit `splits.txt`: every non-indented line ending its name with
+/// `:`, other than the `Sections:` header.
+fn dtk_split_units(data: &str) -> Vec<&str> {
+    data.lines()
+        .filter(|line| !line.starts_with(char::is_whitespace))
+        .filter_map(|line| line.split_once(':').map(|(name, _)| name.trim()))
+        .filter(|name| !name.is_empty() && *name != "Sections")
+        .collect()
+}
+
+fn strip_extension(name: &str) -> &str {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !ext.contains('/') => stem,
+        _ => name,
+    }
+}
+
+fn project_config(units: Vec<ProjectObject>) -> ProjectConfig {
+    ProjectConfig {
+        custom_make: Some("ninja".to_string()),
+        build_target: Some(false),
+        watch_patterns: Some(default_watch_patterns()),
+        units: Some(units),
+        ..Default::default()
+    }
+}
+
+/// Generates a project configuration from a decomp-toolkit `config.yml`. Paths in the config are
+/// relative to `project_dir`. The version is taken from the name of the directory containing
+/// `config.yml` (e.g. `config/GALE01/config.yml`), as in the decomp-toolkit project template.
+pub fn import_dtk(project_dir: &Path, config_path: &Path) -> Result<ProjectConfig> {
+    let config: DtkConfig = read_yaml(config_path)?;
+    let version = config_path
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow!("Failed to determine the version from {}", config_path.display()))?;
+    let build_dir = Path::new("build").join(version);
+
+    let mut modules = vec![("main".to_string(), config.splits, build_dir.clone())];
+    for module in config.modules {
+        let name = module
+            .object
+            .file_stem()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow!("Invalid module object path {}", module.object.display()))?
+            .to_string();
+        let module_dir = build_dir.join(&name);
+        modules.push((name, module.splits, module_dir));
+    }
+
+    let mut units = Vec::new();
+    for (module, splits, module_dir) in modules {
+        // Modules without splits have no units yet
+        let Some(splits) = splits else {
+            continue;
+        };
+        let splits_path = project_dir.join(&splits);
+        let data = fs::read_to_string(&splits_path)
+            .with_context(|| format!("Failed to read {}", splits_path.display()))?;
+        for unit in dtk_split_units(&data) {
+            let stem = strip_extension(unit);
+            let object_name = format!("{stem}.o");
+            units.push(ProjectObject {
+                name: Some(format!("{module}/{stem}")),
+                target_path: Some(module_dir.join("obj").join(&object_name)),
+                base_path: Some(build_dir.join("src").join(&object_name)),
+                metadata: Some(ProjectObjectMetadata {
+                    source_path: Some(format!("src/{unit}")),
+                    auto_generated: stem.starts_with("auto_").then_some(true),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+        }
+    }
+    Ok(project_config(units))
+}
+
+/// A subsegment of a splat segment, in either list (`[start, type, name]`) or map form.
+fn splat_subsegment(value: &Value) -> Option<(String, String, Option<String>)> {
+    let (start, ty, name) = match value {
+        Value::Sequence(seq) => (seq.first()?, seq.get(1)?, seq.get(2)),
+        Value::Mapping(map) => (map.get("start")?, map.get("type")?, map.get("name")),
+        _ => return None,
+    };
+    let start = match start {
+        Value::Number(n) => format!("{:X}", n.as_u64()?),
+        Value::String(s) => s.clone(),
+        _ => return None,
+    };
+    Some((start, ty.as_str()?.to_string(), name.and_then(Value::as_str).map(str::to_string)))
+}
+
+/// Generates a project configuration from a splat `splat.yaml`. Units are created for the
+/// `c`/`cpp` and `asm` subsegments of code segments. The target object is assembled from the
+/// split assembly and the base object is built from source; `asm` subsegments have no source,
+/// so they're marked as auto-generated.
+pub fn import_splat(config_path: &Path) -> Result<ProjectConfig> {
+    let config: Value = read_yaml(config_path)?;
+    let options = config.get("options");
+    let option = |key: &str, default: &str| {
+        options.and_then(|o| o.get(key)).and_then(Value::as_str).unwrap_or(default).to_string()
+    };
+    let build_dir = PathBuf::from(option("build_path", "build"));
+    let asm_dir = option("asm_path", "asm");
+    let src_dir = option("src_path", "src");
+    let segments = config
+        .get("segments")
+        .and_then(Value::as_sequence)
+        .ok_or_else(|| anyhow!("No segments found in {}", config_path.display()))?;
+
+    let mut units = Vec::new();
+    for segment in segments {
+        let Some(subsegments) = segment.get("subsegments").and_then(Value::as_sequence) else {
+            continue;
+        };
+        let dir = segment.get("dir").and_then(Value::as_str).map(|dir| format!("{dir}/"));
+        for subsegment in subsegments {
+            let Some((start, ty, name)) = splat_subsegment(subsegment) else {
+                continue;
+            };
+            let has_source = match ty.as_str() {
+                "c" | "cpp" => true,
+                "asm" | "hasm" => false,
+                _ => continue,
+            };
+            let name = format!("{}{}", dir.as_deref().unwrap_or_default(), name.unwrap_or(start));
+            if units.iter().any(|u: &ProjectObject| u.name.as_deref() == Some(name.as_str())) {
+                // Subsegments of the same file share its name
+                continue;
+            }
+            let source_path = has_source.then(|| format!("{src_dir}/{name}.{ty}"));
+            units.push(ProjectObject {
+                name: Some(name.clone()),
+                target_path: Some(build_dir.join(format!("{asm_dir}/{name}.s.o"))),
+                base_path: source_path.as_ref().map(|path| build_dir.join(format!("{path}.o"))),
+                metadata: Some(ProjectObjectMetadata {
+                    source_path,
+                    auto_generated: (!has_source).then_some(true),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+        }
+    }
+    Ok(project_config(units))
+}
@@ -0,0 +1,222 @@
+//! Bulk import and export of manual symbol mappings, for exchanging mappings with external
+//! tools such as BinDiff.
+
+use std::{collections::HashSet, fmt::Write, path::Path};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::config::SymbolMappings;
+
+/// File format for exported or imported symbol mappings.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MappingsFormat {
+    /// Rows of `target,base` symbol names, with an optional header row.
+    Csv,
+    /// An object of `"target": "base"` pairs, as used by `symbol_mappings` in the project
+    /// config, or an array of objects with `target` and `base` keys.
+    Json,
+}
+
+impl MappingsFormat {
+    /// Guesses the format from a file extension, defaulting to CSV.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Csv,
+        }
+    }
+}
+
+/// Column names recognized as the target (left) and base (right) symbol names in CSV headers.
+const TARGET_COLUMNS: &[&str] = &["target", "left", "primary", "primary_name", "name1"];
+const BASE_COLUMNS: &[&str] = &["base", "right", "secondary", "secondary_name", "name2"];
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Splits a CSV line into fields, handling quoted fields with `""` escapes.
+fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields.into_iter().map(|f| f.trim().to_string()).collect()
+}
+
+/// Serializes mappings in the given format.
+pub fn export_mappings(mappings: &SymbolMappings, format: MappingsFormat) -> Result<String> {
+    match format {
+        MappingsFormat::Csv => {
+            let mut out = String::from("target,base\n");
+            for (target, base) in mappings {
+                writeln!(out, "{},{}", csv_escape(target), csv_escape(base))?;
+            }
+            Ok(out)
+        }
+        MappingsFormat::Json => Ok(serde_json::to_string_pretty(mappings)?),
+    }
+}
+
+fn parse_csv(data: &str) -> Result<Vec<(String, String)>> {
+    let mut lines = data
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty() && !l.trim_start().starts_with('#'))
+        .peekable();
+    let mut columns = (0, 1);
+    if let Some((_, header)) = lines.peek() {
+        let fields =
+            csv_fields(header).into_iter().map(|f| f.to_ascii_lowercase()).collect::<Vec<_>>();
+        let target = fields.iter().position(|f| TARGET_COLUMNS.contains(&f.as_str()));
+        let base = fields.iter().position(|f| BASE_COLUMNS.contains(&f.as_str()));
+        if let (Some(target), Some(base)) = (target, base) {
+            columns = (target, base);
+            lines.next();
+        }
+    }
+    let mut result = vec![];
+    for (line_idx, line) in lines {
+        let fields = csv_fields(line);
+        let (Some(target), Some(base)) = (fields.get(columns.0), fields.get(columns.1)) else {
+            bail!(
+                "Line {}: expected at least {} columns",
+                line_idx + 1,
+                columns.0.max(columns.1) + 1
+            );
+        };
+        if target.is_empty() || base.is_empty() {
+            bail!("Line {}: empty symbol name", line_idx + 1);
+        }
+        result.push((target.clone(), base.clone()));
+    }
+    Ok(result)
+}
+
+fn parse_json(data: &str) -> Result<Vec<(String, String)>> {
+    let value: serde_json::Value = serde_json::from_str(data)?;
+    match value {
+        serde_json::Value::Object(map) => map
+            .into_iter()
+            .map(|(target, base)| match base {
+                serde_json::Value::String(base) => Ok((target, base)),
+                _ => Err(anyhow!("Mapping for {target} is not a string")),
+            })
+            .collect(),
+        serde_json::Value::Array(entries) => entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let field = |names: &[&str]| {
+                    names.iter().find_map(|name| entry.get(*name).and_then(|v| v.as_str()))
+                };
+                match (field(TARGET_COLUMNS), field(BASE_COLUMNS)) {
+                    (Some(target), Some(base)) => Ok((target.to_string(), base.to_string())),
+                    _ => Err(anyhow!("Entry {i} is missing a target or base name")),
+                }
+            })
+            .collect(),
+        _ => bail!("Expected an object or array of mappings"),
+    }
+}
+
+/// Parses `(target, base)` symbol name pairs.
+pub fn parse_mappings(data: &str, format: MappingsFormat) -> Result<Vec<(String, String)>> {
+    match format {
+        MappingsFormat::Csv => parse_csv(data),
+        MappingsFormat::Json => parse_json(data),
+    }
+    .context("Failed to parse symbol mappings")
+}
+
+/// The outcome of importing a single mapping on top of the existing ones.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum MappingImportStatus {
+    /// Neither symbol is currently mapped.
+    New,
+    /// The same mapping already exists.
+    Unchanged,
+    /// Either symbol is already mapped to a different symbol. Applying the import replaces
+    /// the existing mappings.
+    Conflict { existing_base: Option<String>, existing_target: Option<String> },
+    /// The target or base symbol doesn't exist in its object.
+    Missing { target: bool, base: bool },
+    /// The target or base symbol was already mapped earlier in the import.
+    Duplicate,
+}
+
+#[derive(Debug, Clone)]
+pub struct MappingImportEntry {
+    pub target: String,
+    pub base: String,
+    pub status: MappingImportStatus,
+    /// Whether the mapping will be applied. Defaults to `true` for new mappings only, so that
+    /// conflicts must be resolved explicitly.
+    pub apply: bool,
+}
+
+/// Validates imported mappings against the existing mappings and the symbols present in each
+/// object.
+pub fn validate_import(
+    existing: &SymbolMappings,
+    imported: Vec<(String, String)>,
+    target_exists: impl Fn(&str) -> bool,
+    base_exists: impl Fn(&str) -> bool,
+) -> Vec<MappingImportEntry> {
+    let mut seen_targets = HashSet::new();
+    let mut seen_bases = HashSet::new();
+    imported
+        .into_iter()
+        .map(|(target, base)| {
+            let first_target = seen_targets.insert(target.clone());
+            let first_base = seen_bases.insert(base.clone());
+            let status = if !first_target || !first_base {
+                MappingImportStatus::Duplicate
+            } else if !target_exists(&target) || !base_exists(&base) {
+                MappingImportStatus::Missing {
+                    target: !target_exists(&target),
+                    base: !base_exists(&base),
+                }
+            } else if existing.get_by_left(&target) == Some(&base) {
+                MappingImportStatus::Unchanged
+            } else {
+                let existing_base = existing.get_by_left(&target).cloned();
+                let existing_target = existing.get_by_right(&base).cloned();
+                if existing_base.is_some() || existing_target.is_some() {
+                    MappingImportStatus::Conflict { existing_base, existing_target }
+                } else {
+                    MappingImportStatus::New
+                }
+            };
+            let apply = status == MappingImportStatus::New;
+            MappingImportEntry { target, base, status, apply }
+        })
+        .collect()
+}
+
+/// Applies the entries marked with [`MappingImportEntry::apply`], replacing any conflicting
+/// mappings. Returns the number of mappings applied.
+pub fn apply_import(mappings: &mut SymbolMappings, entries: &[MappingImportEntry]) -> usize {
+    let mut count = 0;
+    for entry in entries.iter().filter(|e| e.apply) {
+        mappings.insert(entry.target.clone(), entry.base.clone());
+        count += 1;
+    }
+    count
+}
@@ -0,0 +1,121 @@
+//! Tracks when each function first became fully matched, for progress statistics over time.
+//! The history is stored in a project-local state file and updated whenever a unit is diffed
+//! or a report is generated.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+
+/// Directory for state files written by objdiff, relative to the project directory.
+/// Ignored by the file watcher.
+pub const STATE_DIR: &str = ".objdiff";
+/// Match history file name within [`STATE_DIR`].
+pub const MATCH_HISTORY_FILE: &str = "match_history.json";
+
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+pub const SECONDS_PER_WEEK: u64 = 7 * SECONDS_PER_DAY;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MatchRecord {
+    /// Unix timestamp (seconds) when the function first reached 100%.
+    pub matched_at: u64,
+    /// Size of the function in bytes at that time.
+    pub size: u64,
+}
+
+/// Match records keyed by unit name, then function name.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct MatchHistory {
+    pub units: BTreeMap<String, BTreeMap<String, MatchRecord>>,
+}
+
+/// Aggregate statistics over a [`MatchHistory`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MatchStats {
+    /// Functions with a recorded match time.
+    pub total_functions: usize,
+    /// Functions first matched within the last 7 days.
+    pub week_functions: usize,
+    /// Size in bytes of functions first matched within the last 7 days.
+    pub week_code: u64,
+    /// Average size in bytes matched per day, from the first recorded match until now.
+    pub average_code_per_day: f64,
+}
+
+pub fn match_history_path(project_dir: &Path) -> PathBuf {
+    project_dir.join(STATE_DIR).join(MATCH_HISTORY_FILE)
+}
+
+/// Current Unix timestamp in seconds.
+pub fn unix_now() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()) }
+
+impl MatchHistory {
+    /// Loads the history for a project, or an empty history if none has been recorded.
+    pub fn load(project_dir: &Path) -> Result<Self> {
+        let path = match_history_path(project_dir);
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let data = fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_slice(&data).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, project_dir: &Path) -> Result<()> {
+        let path = match_history_path(project_dir);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let data = serde_json::to_vec_pretty(self)?;
+        fs::write(&path, data).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Records fully matched `(name, size)` functions of a unit that don't have a match time
+    /// yet. Returns whether any records were added.
+    pub fn record<'a>(
+        &mut self,
+        unit: &str,
+        matched_functions: impl IntoIterator<Item = (&'a str, u64)>,
+        now: u64,
+    ) -> bool {
+        let mut records = self.units.remove(unit).unwrap_or_default();
+        let mut changed = false;
+        for (name, size) in matched_functions {
+            if !records.contains_key(name) {
+                records.insert(name.to_string(), MatchRecord { matched_at: now, size });
+                changed = true;
+            }
+        }
+        if !records.is_empty() {
+            self.units.insert(unit.to_string(), records);
+        }
+        changed
+    }
+
+    pub fn get(&self, unit: &str, function: &str) -> Option<&MatchRecord> {
+        self.units.get(unit)?.get(function)
+    }
+
+    pub fn stats(&self, now: u64) -> MatchStats {
+        let mut stats = MatchStats::default();
+        let mut first = now;
+        let mut total_code = 0;
+        for record in self.units.values().flat_map(|r| r.values()) {
+            stats.total_functions += 1;
+            total_code += record.size;
+            first = first.min(record.matched_at);
+            if now.saturating_sub(record.matched_at) < SECONDS_PER_WEEK {
+                stats.week_functions += 1;
+                stats.week_code += record.size;
+            }
+        }
+        let days = (now.saturating_sub(first) as f64 / SECONDS_PER_DAY as f64).max(1.0);
+        stats.average_code_per_day = total_code as f64 / days;
+        stats
+    }
+}
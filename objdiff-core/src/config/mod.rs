@@ -1,14 +1,26 @@
 use std::{
+    collections::{BTreeMap, BTreeSet},
     fs,
     fs::File,
     io::{BufReader, BufWriter, Read},
     path::{Path, PathBuf},
 };
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, ensure, Context, Result};
 use bimap::BiBTreeMap;
 use filetime::FileTime;
-use globset::{Glob, GlobSet, GlobSetBuilder};
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+
+use crate::config::compile_commands::{CompileCommand, CompileCommands};
+
+pub mod claims;
+pub mod compile_commands;
+pub mod import;
+pub mod mappings;
+pub mod match_history;
+#[cfg(feature = "any-arch")]
+pub mod session_file;
+pub mod usage_stats;
 
 #[derive(Default, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ProjectConfig {
@@ -32,8 +44,56 @@ pub struct ProjectConfig {
     pub units: Option<Vec<ProjectObject>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub progress_categories: Option<Vec<ProjectProgressCategory>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codegen_quirks: Option<Vec<CodegenQuirk>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub versions: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compile_commands: Option<PathBuf>,
+    /// Values of the PowerPC graphics quantization registers GQR0-GQR7 as set up at runtime,
+    /// used to annotate paired-single loads and stores.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ppc_gqrs: Option<Vec<u32>>,
+    /// Directory for unit claim files, relative to the project directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claims_dir: Option<PathBuf>,
+    /// Symbol name patterns never offered as candidates when manually mapping a symbol, in any
+    /// unit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mapping_exclude: Option<Vec<Glob>>,
+    /// Time to wait for changes to watched files to settle before rebuilding, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watch_debounce_ms: Option<u64>,
+    /// Whether changes to watched files rebuild the selected unit automatically.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rebuild_on_changes: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watch_scope: Option<WatchScope>,
+    /// Rewrites of relocation target names applied when loading objects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reloc_renames: Option<Vec<RelocRename>>,
+    /// Only list, match and report global symbols.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub global_symbols_only: Option<bool>,
 }
 
+/// Which changes to watched files rebuild the selected unit.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchScope {
+    /// Any watched file
+    #[default]
+    All,
+    /// Watched files other than the source files of other units, which can't affect the
+    /// selected unit
+    SelectedUnit,
+}
+
+/// Placeholder substituted with the active version name in unit and directory paths.
+pub const VERSION_PLACEHOLDER: &str = "{version}";
+
 impl ProjectConfig {
     #[inline]
     pub fn units(&self) -> &[ProjectObject] { self.units.as_deref().unwrap_or_default() }
@@ -52,6 +112,131 @@ impl ProjectConfig {
     pub fn progress_categories_mut(&mut self) -> &mut Vec<ProjectProgressCategory> {
         self.progress_categories.get_or_insert_with(Vec::new)
     }
+
+    #[inline]
+    pub fn versions(&self) -> &[String] { self.versions.as_deref().unwrap_or_default() }
+
+    /// Returns a copy of this config with all paths resolved for the given version.
+    ///
+    /// When no version is specified, the first configured version is used. Any occurrences of
+    /// `{version}` in `target_dir`, `base_dir` and unit paths are replaced with the version name,
+    /// and per-unit version overrides are applied. Configs without versions are returned as-is.
+    pub fn for_version(&self, version: Option<&str>) -> Result<ProjectConfig> {
+        let versions = self.versions();
+        let version = match version {
+            Some(version) => {
+                ensure!(
+                    versions.iter().any(|v| v == version),
+                    "Unknown version {version} (available: {})",
+                    versions.join(", ")
+                );
+                version
+            }
+            None => match versions.first() {
+                Some(version) => version.as_str(),
+                None => return Ok(self.clone()),
+            },
+        };
+        let mut config = self.clone();
+        let subst = |path: &mut Option<PathBuf>| {
+            if let Some(p) = path {
+                *p = substitute_version(p, version);
+            }
+        };
+        subst(&mut config.target_dir);
+        subst(&mut config.base_dir);
+        for unit in config.units_mut() {
+            subst(&mut unit.path);
+            subst(&mut unit.target_path);
+            subst(&mut unit.base_path);
+            if let Some(overrides) = unit.versions.as_ref().and_then(|v| v.get(version)) {
+                if let Some(target_path) = &overrides.target_path {
+                    unit.target_path = Some(target_path.clone());
+                }
+                if let Some(base_path) = &overrides.base_path {
+                    unit.base_path = Some(base_path.clone());
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    /// Merges an included config fragment into this config.
+    ///
+    /// Options already set in this config take precedence over the fragment. Units,
+    /// progress categories and codegen quirks are appended in order. Defining the same unit
+    /// in more than one file is an error, while a progress category with a duplicate ID keeps
+    /// the first definition.
+    fn merge_include(&mut self, other: ProjectConfig, path: &Path) -> Result<()> {
+        let ProjectConfig {
+            min_version,
+            custom_make,
+            custom_args,
+            target_dir,
+            base_dir,
+            build_base,
+            build_target,
+            watch_patterns,
+            units,
+            progress_categories,
+            include: _,
+            codegen_quirks,
+            versions,
+            compile_commands,
+            ppc_gqrs,
+            claims_dir,
+            mapping_exclude,
+            watch_debounce_ms,
+            rebuild_on_changes,
+            watch_scope,
+            reloc_renames,
+            global_symbols_only,
+        } = other;
+        self.min_version = self.min_version.take().or(min_version);
+        self.custom_make = self.custom_make.take().or(custom_make);
+        self.custom_args = self.custom_args.take().or(custom_args);
+        self.target_dir = self.target_dir.take().or(target_dir);
+        self.base_dir = self.base_dir.take().or(base_dir);
+        self.build_base = self.build_base.or(build_base);
+        self.build_target = self.build_target.or(build_target);
+        self.watch_patterns = self.watch_patterns.take().or(watch_patterns);
+        self.versions = self.versions.take().or(versions);
+        self.compile_commands = self.compile_commands.take().or(compile_commands);
+        self.ppc_gqrs = self.ppc_gqrs.take().or(ppc_gqrs);
+        self.claims_dir = self.claims_dir.take().or(claims_dir);
+        self.mapping_exclude = self.mapping_exclude.take().or(mapping_exclude);
+        self.watch_debounce_ms = self.watch_debounce_ms.or(watch_debounce_ms);
+        self.rebuild_on_changes = self.rebuild_on_changes.or(rebuild_on_changes);
+        self.watch_scope = self.watch_scope.or(watch_scope);
+        self.global_symbols_only = self.global_symbols_only.or(global_symbols_only);
+        if let Some(units) = units {
+            let existing = self.units_mut();
+            for unit in units {
+                ensure!(
+                    !existing.iter().any(|u| u.name() == unit.name()),
+                    "Unit {} in {} is already defined",
+                    unit.name(),
+                    path.display()
+                );
+                existing.push(unit);
+            }
+        }
+        if let Some(categories) = progress_categories {
+            let existing = self.progress_categories_mut();
+            for category in categories {
+                if !existing.iter().any(|c| c.id == category.id) {
+                    existing.push(category);
+                }
+            }
+        }
+        if let Some(quirks) = codegen_quirks {
+            self.codegen_quirks.get_or_insert_with(Vec::new).extend(quirks);
+        }
+        if let Some(renames) = reloc_renames {
+            self.reloc_renames.get_or_insert_with(Vec::new).extend(renames);
+        }
+        Ok(())
+    }
 }
 
 #[derive(Default, Clone, serde::Serialize, serde::Deserialize)]
@@ -76,6 +261,26 @@ pub struct ProjectObject {
     pub metadata: Option<ProjectObjectMetadata>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub symbol_mappings: Option<SymbolMappings>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub versions: Option<BTreeMap<String, ProjectObjectVersion>>,
+    /// Source file whose `compile_commands.json` entry builds the base object.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compile_command: Option<PathBuf>,
+    /// The compile command resolved from `compile_command` when the config was loaded.
+    #[serde(skip)]
+    pub resolved_compile_command: Option<CompileCommand>,
+    /// Function list exported from Ghidra or IDA, used to symbolize the target object.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_import: Option<SymbolImport>,
+}
+
+/// Per-version overrides for a unit's object paths.
+#[derive(Default, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ProjectObjectVersion {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_path: Option<PathBuf>,
 }
 
 pub type SymbolMappings = BiBTreeMap<String, String>;
@@ -92,6 +297,57 @@ pub struct ProjectObjectMetadata {
     pub progress_categories: Option<Vec<String>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub auto_generated: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_matching: Option<SymbolMatching>,
+    /// Manual review states, keyed by target symbol name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review_states: Option<BTreeMap<String, SymbolReviewState>>,
+    /// Path to a markdown file with notes about the unit, from the project root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes_path: Option<String>,
+    /// Target symbol names to watch, reporting match percent changes after each rebuild.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watched_symbols: Option<BTreeSet<String>>,
+    /// Compiler flags the unit is built with, for reference.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiler_flags: Option<String>,
+    /// Compiler flags of individual functions built with different flags than the unit (e.g.
+    /// with `#pragma`), keyed by target symbol name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_compiler_flags: Option<BTreeMap<String, String>>,
+    /// If set, only symbols matching these name patterns are offered as candidates when
+    /// manually mapping a symbol.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mapping_include: Option<Vec<Glob>>,
+    /// Symbol name patterns never offered as candidates when manually mapping a symbol.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mapping_exclude: Option<Vec<Glob>>,
+}
+
+/// How symbols are paired between the target and base objects.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolMatching {
+    /// Match symbols by name
+    #[default]
+    Name,
+    /// Match symbols by address within sections of the same name, for objects with
+    /// identical layout but differing names (e.g. stripped vs named)
+    Address,
+}
+
+/// A manually assigned review state for a symbol, independent of its match percent.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolReviewState {
+    /// Not reviewed
+    #[default]
+    Unverified,
+    /// Reviewed and confirmed to match
+    Matching,
+    /// Differs from the target, but reviewed and confirmed to be functionally equivalent
+    VerifiedEquivalent,
 }
 
 #[derive(Default, Clone, serde::Serialize, serde::Deserialize)]
@@ -126,12 +382,18 @@ impl ProjectObject {
         } else if let Some(path) = &self.target_path {
             self.target_path = Some(project_dir.join(path));
         }
-        if let (Some(base_obj_dir), Some(path), None) = (base_obj_dir, &self.path, &self.base_path)
+        if let (None, Some(command)) = (&self.base_path, &self.resolved_compile_command) {
+            self.base_path = Some(command.output.clone());
+        } else if let (Some(base_obj_dir), Some(path), None) =
+            (base_obj_dir, &self.path, &self.base_path)
         {
             self.base_path = Some(base_obj_dir.join(path));
         } else if let Some(path) = &self.base_path {
             self.base_path = Some(project_dir.join(path));
         }
+        if let Some(import) = &mut self.symbol_import {
+            import.path = project_dir.join(&import.path);
+        }
     }
 
     pub fn complete(&self) -> Option<bool> {
@@ -144,6 +406,26 @@ impl ProjectObject {
         self.metadata.as_ref().and_then(|m| m.reverse_fn_order).or(self.reverse_fn_order)
     }
 
+    pub fn symbol_matching(&self) -> Option<SymbolMatching> {
+        self.metadata.as_ref().and_then(|m| m.symbol_matching)
+    }
+
+    pub fn review_states(&self) -> Option<&BTreeMap<String, SymbolReviewState>> {
+        self.metadata.as_ref().and_then(|m| m.review_states.as_ref())
+    }
+
+    pub fn watched_symbols(&self) -> Option<&BTreeSet<String>> {
+        self.metadata.as_ref().and_then(|m| m.watched_symbols.as_ref())
+    }
+
+    pub fn compiler_flags(&self) -> Option<&String> {
+        self.metadata.as_ref().and_then(|m| m.compiler_flags.as_ref())
+    }
+
+    pub fn symbol_compiler_flags(&self) -> Option<&BTreeMap<String, String>> {
+        self.metadata.as_ref().and_then(|m| m.symbol_compiler_flags.as_ref())
+    }
+
     pub fn hidden(&self) -> bool {
         self.metadata.as_ref().and_then(|m| m.auto_generated).unwrap_or(false)
     }
@@ -151,6 +433,79 @@ impl ProjectObject {
     pub fn source_path(&self) -> Option<&String> {
         self.metadata.as_ref().and_then(|m| m.source_path.as_ref())
     }
+
+    pub fn notes_path(&self) -> Option<&String> {
+        self.metadata.as_ref().and_then(|m| m.notes_path.as_ref())
+    }
+
+    pub fn mapping_include(&self) -> Option<&Vec<Glob>> {
+        self.metadata.as_ref().and_then(|m| m.mapping_include.as_ref())
+    }
+
+    pub fn mapping_exclude(&self) -> Option<&Vec<Glob>> {
+        self.metadata.as_ref().and_then(|m| m.mapping_exclude.as_ref())
+    }
+}
+
+/// A pair of instruction sequences that a compiler may emit interchangeably.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, serde::Deserialize, serde::Serialize)]
+pub struct CodegenQuirk {
+    #[serde(default)]
+    pub name: String,
+    /// Only applies to units whose scratch compiler ID contains this (e.g. `mwcc`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiler: Option<String>,
+    /// Mnemonics of one sequence
+    #[serde(default)]
+    pub left: Vec<String>,
+    /// Mnemonics of the equivalent sequence
+    #[serde(default)]
+    pub right: Vec<String>,
+}
+
+/// Renames relocation targets that a linker or toolchain rewrites systematically, such as
+/// `__imp_` import prefixes or small data base symbols, so that they compare as equal.
+///
+/// `from` matches the whole target name and may contain a single `*`, which matches any text.
+/// The matched text is substituted for a `*` in `to`.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, serde::Deserialize, serde::Serialize)]
+pub struct RelocRename {
+    pub from: String,
+    pub to: String,
+}
+
+impl RelocRename {
+    /// The renamed target, if `name` matches the rule.
+    pub fn apply(&self, name: &str) -> Option<String> {
+        let Some((prefix, suffix)) = self.from.split_once('*') else {
+            return (name == self.from).then(|| self.to.clone());
+        };
+        let inner = name.strip_prefix(prefix)?.strip_suffix(suffix)?;
+        Some(self.to.replacen('*', inner, 1))
+    }
+}
+
+/// A function list (address, name and optionally size) exported from a disassembler, for
+/// target objects or binaries without symbols. See [`crate::obj::symbol_import`] for the
+/// supported formats.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, serde::Deserialize, serde::Serialize)]
+pub struct SymbolImport {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub conflicts: SymbolImportConflicts,
+}
+
+/// What to do when an imported symbol starts at the address of a symbol already in the object.
+#[derive(
+    Debug, Default, Copy, Clone, Eq, PartialEq, Hash, serde::Deserialize, serde::Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolImportConflicts {
+    /// Keep the object's symbol.
+    #[default]
+    Keep,
+    /// Rename the object's symbol to the imported name.
+    Replace,
 }
 
 #[derive(Default, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
@@ -176,6 +531,15 @@ pub const DEFAULT_WATCH_PATTERNS: &[&str] = &[
     "*.inc", "*.py", "*.yml", "*.txt", "*.json",
 ];
 
+fn substitute_version(path: &Path, version: &str) -> PathBuf {
+    match path.to_str() {
+        Some(s) if s.contains(VERSION_PLACEHOLDER) => {
+            PathBuf::from(s.replace(VERSION_PLACEHOLDER, version))
+        }
+        _ => path.to_path_buf(),
+    }
+}
+
 pub fn default_watch_patterns() -> Vec<Glob> {
     DEFAULT_WATCH_PATTERNS.iter().map(|s| Glob::new(s).unwrap()).collect()
 }
@@ -184,6 +548,20 @@ pub fn default_watch_patterns() -> Vec<Glob> {
 pub struct ProjectConfigInfo {
     pub path: PathBuf,
     pub timestamp: Option<FileTime>,
+    /// Config fragments merged in via `include`, in load order.
+    pub includes: Vec<ProjectConfigInfo>,
+}
+
+impl ProjectConfigInfo {
+    /// Whether the config file or any of its included fragments changed on disk.
+    pub fn is_modified(&self) -> bool {
+        let modified = |info: &ProjectConfigInfo| {
+            let Some(last_ts) = info.timestamp else { return false };
+            fs::metadata(&info.path)
+                .is_ok_and(|m| FileTime::from_last_modification_time(&m) != last_ts)
+        };
+        modified(self) || self.includes.iter().any(modified)
+    }
 }
 
 pub fn try_project_config(dir: &Path) -> Option<(Result<ProjectConfig>, ProjectConfigInfo)> {
@@ -203,28 +581,96 @@ pub fn try_project_config(dir: &Path) -> Option<(Result<ProjectConfig>, ProjectC
                 true => read_json_config(&mut reader),
                 false => read_yml_config(&mut reader),
             };
-            if let Ok(config) = &result {
+            let mut info =
+                ProjectConfigInfo { path: config_path, timestamp: Some(ts), includes: vec![] };
+            if let Ok(config) = &mut result {
                 // Validate min_version if present
                 if let Err(e) = validate_min_version(config) {
                     result = Err(e);
+                } else if let Err(e) = resolve_includes(config, &mut vec![], &mut info) {
+                    result = Err(e);
+                } else if let Err(e) = resolve_compile_commands(config, dir) {
+                    result = Err(e);
                 }
             }
-            return Some((result, ProjectConfigInfo { path: config_path, timestamp: Some(ts) }));
+            return Some((result, info));
         }
     }
     None
 }
 
+/// Saves the project config. For configs that use includes, the loaded config has the
+/// fragments merged into it, so only its units are written back, each to the file that
+/// defines it. Units no longer in the project are removed from their file, and units not
+/// defined in any file are added to the root config.
 pub fn save_project_config(
     config: &ProjectConfig,
     info: &ProjectConfigInfo,
 ) -> Result<ProjectConfigInfo> {
+    if info.includes.is_empty() {
+        let timestamp = write_project_config(config, info)?;
+        return Ok(ProjectConfigInfo {
+            path: info.path.clone(),
+            timestamp: Some(timestamp),
+            includes: vec![],
+        });
+    }
+    let mut new_info = info.clone();
+    let mut saved = BTreeSet::new();
+    save_units(config, &mut new_info, &mut saved)?;
+    for include in &mut new_info.includes {
+        save_units(config, include, &mut saved)?;
+    }
+    let new_units =
+        config.units().iter().filter(|u| !saved.contains(u.name())).cloned().collect::<Vec<_>>();
+    if !new_units.is_empty() {
+        let mut root_config = read_project_config(&new_info.path)?;
+        root_config.units_mut().extend(new_units);
+        new_info.timestamp = Some(write_project_config(&root_config, &new_info)?);
+    }
+    Ok(new_info)
+}
+
+/// Writes the units defined in a single config file back to it, if any of them changed or
+/// were removed from the project.
+fn save_units(
+    config: &ProjectConfig,
+    info: &mut ProjectConfigInfo,
+    saved: &mut BTreeSet<String>,
+) -> Result<()> {
+    let mut file_config = read_project_config(&info.path)?;
+    let units = file_config.units_mut();
+    let count = units.len();
+    units.retain(|unit| config.units().iter().any(|u| u.name() == unit.name()));
+    let mut changed = units.len() != count;
+    for unit in units {
+        let Some(updated) = config.units().iter().find(|u| u.name() == unit.name()) else {
+            continue;
+        };
+        saved.insert(unit.name().to_string());
+        if serde_json::to_value(&*unit)? != serde_json::to_value(updated)? {
+            *unit = updated.clone();
+            changed = true;
+        }
+    }
+    if changed {
+        info.timestamp = Some(write_project_config(&file_config, info)?);
+    }
+    Ok(())
+}
+
+/// Writes a single config file, failing if it changed on disk since it was read. Returns the
+/// new modification time.
+fn write_project_config(config: &ProjectConfig, info: &ProjectConfigInfo) -> Result<FileTime> {
     if let Some(last_ts) = info.timestamp {
         // Check if the file has changed since we last read it
         if let Ok(metadata) = fs::metadata(&info.path) {
             let ts = FileTime::from_last_modification_time(&metadata);
             if ts != last_ts {
-                return Err(anyhow!("Config file has changed since last read"));
+                return Err(anyhow!(
+                    "Config file {} has changed since last read",
+                    info.path.display()
+                ));
             }
         }
     }
@@ -240,8 +686,18 @@ pub fn save_project_config(
     }?;
     let file = writer.into_inner().context("Failed to flush file")?;
     let metadata = file.metadata().context("Failed to get file metadata")?;
-    let ts = FileTime::from_last_modification_time(&metadata);
-    Ok(ProjectConfigInfo { path: info.path.clone(), timestamp: Some(ts) })
+    Ok(FileTime::from_last_modification_time(&metadata))
+}
+
+/// Reads a single config file as is, without resolving its includes.
+fn read_project_config(path: &Path) -> Result<ProjectConfig> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut reader = BufReader::new(file);
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yml" | "yaml") => read_yml_config(&mut reader),
+        _ => read_json_config(&mut reader),
+    }
+    .with_context(|| format!("Failed to parse {}", path.display()))
 }
 
 fn validate_min_version(config: &ProjectConfig) -> Result<()> {
@@ -264,6 +720,143 @@ fn read_json_config<R: Read>(reader: &mut R) -> Result<ProjectConfig> {
     Ok(serde_json::from_reader(reader)?)
 }
 
+/// Loads the files listed in `include` (recursively) and merges them into `config`.
+///
+/// Include paths are relative to the directory of the file containing them and may be glob
+/// patterns. Files matched by a single pattern are merged in lexical order. A file included
+/// more than once is only merged the first time, and an include cycle is an error.
+fn resolve_includes(
+    config: &mut ProjectConfig,
+    stack: &mut Vec<PathBuf>,
+    info: &mut ProjectConfigInfo,
+) -> Result<()> {
+    let config_path = match stack.last() {
+        Some(path) => path.clone(),
+        None => fs::canonicalize(&info.path).unwrap_or_else(|_| info.path.clone()),
+    };
+    let Some(patterns) = config.include.clone() else { return Ok(()) };
+    let base_dir = config_path.parent().unwrap_or(Path::new("")).to_path_buf();
+    if stack.is_empty() {
+        stack.push(config_path);
+    }
+    for pattern in &patterns {
+        for path in expand_include(&base_dir, pattern)? {
+            let path = fs::canonicalize(&path)
+                .with_context(|| format!("Failed to resolve include {}", path.display()))?;
+            if let Some(pos) = stack.iter().position(|p| p == &path) {
+                let cycle = stack[pos..]
+                    .iter()
+                    .chain(std::iter::once(&path))
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                bail!("Config include cycle detected: {cycle}");
+            }
+            if info.includes.iter().any(|i| i.path == path) {
+                continue;
+            }
+            let file = File::open(&path)
+                .with_context(|| format!("Failed to open include {}", path.display()))?;
+            let ts = file.metadata().ok().map(|m| FileTime::from_last_modification_time(&m));
+            let mut reader = BufReader::new(file);
+            let mut fragment = match path.extension().and_then(|ext| ext.to_str()) {
+                Some("yml" | "yaml") => read_yml_config(&mut reader),
+                _ => read_json_config(&mut reader),
+            }
+            .with_context(|| format!("Failed to parse include {}", path.display()))?;
+            validate_min_version(&fragment)?;
+            info.includes.push(ProjectConfigInfo {
+                path: path.clone(),
+                timestamp: ts,
+                includes: vec![],
+            });
+            stack.push(path.clone());
+            resolve_includes(&mut fragment, stack, info)?;
+            stack.pop();
+            config.merge_include(fragment, &path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Resolves units referencing a `compile_commands.json` entry to their base object path and
+/// rebuild command. A unit without a matching entry is an error.
+fn resolve_compile_commands(config: &mut ProjectConfig, project_dir: &Path) -> Result<()> {
+    let Some(path) = &config.compile_commands else { return Ok(()) };
+    if !config.units().iter().any(|u| u.compile_command.is_some()) {
+        return Ok(());
+    }
+    let project_dir = fs::canonicalize(project_dir).unwrap_or_else(|_| project_dir.to_path_buf());
+    let commands = CompileCommands::load(&project_dir.join(path))?;
+    for unit in config.units_mut() {
+        if let Some(source) = &unit.compile_command {
+            let command = commands
+                .find(&project_dir, source)
+                .with_context(|| format!("Failed to resolve compile command of {}", unit.name()))?;
+            unit.resolved_compile_command = Some(command);
+        }
+    }
+    Ok(())
+}
+
+fn is_glob_pattern(s: &str) -> bool { s.contains(['*', '?', '[', '{']) }
+
+/// Expands an include pattern relative to `base_dir` into a sorted list of files.
+fn expand_include(base_dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    if !is_glob_pattern(pattern) {
+        let path = base_dir.join(pattern);
+        ensure!(path.is_file(), "Included config {} not found", path.display());
+        return Ok(vec![path]);
+    }
+    let matcher = GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .with_context(|| format!("Invalid include pattern {pattern:?}"))?
+        .compile_matcher();
+    // Only walk the directories that the pattern can match
+    let components = pattern.split('/').collect::<Vec<_>>();
+    let prefix_len = components.iter().take_while(|c| !is_glob_pattern(c)).count();
+    let max_depth = if components[prefix_len..].iter().any(|c| c.contains("**")) {
+        None
+    } else {
+        Some(components.len() - prefix_len)
+    };
+    let mut files = vec![];
+    let dir = components[..prefix_len].iter().fold(base_dir.to_path_buf(), |p, c| p.join(c));
+    collect_files(&dir, max_depth, &mut files);
+    let mut paths = files
+        .into_iter()
+        .filter(|path| {
+            path.strip_prefix(base_dir).is_ok_and(|relative| {
+                let relative = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                matcher.is_match(relative)
+            })
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    Ok(paths)
+}
+
+fn collect_files(dir: &Path, max_depth: Option<usize>, out: &mut Vec<PathBuf>) {
+    if max_depth == Some(0) {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else { continue };
+        if file_type.is_dir() {
+            collect_files(&path, max_depth.map(|d| d - 1), out);
+        } else if file_type.is_file() {
+            out.push(path);
+        }
+    }
+}
+
 pub fn build_globset(vec: &[Glob]) -> std::result::Result<GlobSet, globset::Error> {
     let mut builder = GlobSetBuilder::new();
     for glob in vec {
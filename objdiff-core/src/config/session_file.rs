@@ -0,0 +1,243 @@
+//! Self-contained diff sessions, bundling a unit's objects with the configuration used to diff
+//! them, so that a diff can be reviewed without the project checkout.
+//!
+//! A session file starts with [`SESSION_MAGIC`], followed by the length of a JSON
+//! [`SessionHeader`] as a little-endian `u32`, the header itself, the raw target and base
+//! objects in that order, and the encoded diff result, if any.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, ensure, Context, Result};
+
+use crate::{
+    config::SymbolMappings,
+    diff::{DiffObjConfig, ObjDiff},
+    obj::ObjInfo,
+};
+
+pub const SESSION_MAGIC: [u8; 8] = *b"OBJDSESS";
+pub const SESSION_VERSION: u32 = 1;
+const MAX_HEADER_SIZE: usize = 64 * 1024 * 1024;
+/// File extension for session files.
+pub const SESSION_EXTENSION: &str = "objdiff-session";
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct SessionHeader {
+    pub version: u32,
+    /// Version of objdiff that exported the session
+    pub objdiff_version: String,
+    /// Unit name
+    pub name: String,
+    pub diff_obj_config: DiffObjConfig,
+    #[serde(default)]
+    pub symbol_mappings: SymbolMappings,
+    #[serde(default)]
+    pub target: Option<SessionObject>,
+    #[serde(default)]
+    pub base: Option<SessionObject>,
+    /// Match percent of each target symbol when the session was exported, to tell whether the
+    /// diff computed on import differs (e.g. with another objdiff version)
+    #[serde(default)]
+    pub match_percents: BTreeMap<String, f32>,
+    /// Size of the protobuf-encoded diff result stored after the objects
+    #[serde(default)]
+    pub diff_result_size: Option<u64>,
+}
+
+impl SessionHeader {
+    /// Collects the match percent of each symbol of a diffed object, for
+    /// [`match_percents`](Self::match_percents).
+    pub fn collect_match_percents(obj: &ObjInfo, diff: &ObjDiff) -> BTreeMap<String, f32> {
+        let mut result = BTreeMap::new();
+        for (section, section_diff) in obj.sections.iter().zip(&diff.sections) {
+            for (symbol, symbol_diff) in section.symbols.iter().zip(&section_diff.symbols) {
+                if let Some(match_percent) = symbol_diff.match_percent {
+                    result.insert(symbol.name.clone(), match_percent);
+                }
+            }
+        }
+        result
+    }
+
+    /// Returns the names of symbols whose match percent differs from the exported one.
+    pub fn changed_symbols<'a>(
+        &'a self,
+        match_percents: &'a BTreeMap<String, f32>,
+    ) -> impl Iterator<Item = &'a str> + 'a {
+        self.match_percents.iter().filter_map(move |(name, &percent)| {
+            match match_percents.get(name) {
+                Some(&current) if (current - percent).abs() < 0.01 => None,
+                _ => Some(name.as_str()),
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SessionObject {
+    /// Original file name of the object
+    pub file_name: String,
+    pub size: u64,
+}
+
+/// A session header with its objects' contents.
+#[derive(Debug, Clone, Default)]
+pub struct DiffSession {
+    pub header: SessionHeader,
+    pub target: Option<Vec<u8>>,
+    pub base: Option<Vec<u8>>,
+    /// The diff result computed by the exporting objdiff, as an encoded
+    /// [`DiffResult`](crate::bindings::diff::DiffResult)
+    pub diff_result: Option<Vec<u8>>,
+}
+
+impl DiffSession {
+    /// Creates a session by reading the objects from disk.
+    pub fn from_paths(
+        mut header: SessionHeader,
+        target_path: Option<&Path>,
+        base_path: Option<&Path>,
+    ) -> Result<Self> {
+        let read = |path: Option<&Path>| -> Result<Option<(SessionObject, Vec<u8>)>> {
+            let Some(path) = path else {
+                return Ok(None);
+            };
+            let data =
+                fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+            let file_name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "object.o".to_string());
+            Ok(Some((SessionObject { file_name, size: data.len() as u64 }, data)))
+        };
+        let (target, target_data) = read(target_path)?.unzip();
+        let (base, base_data) = read(base_path)?.unzip();
+        header.version = SESSION_VERSION;
+        header.target = target;
+        header.base = base;
+        Ok(Self { header, target: target_data, base: base_data, diff_result: None })
+    }
+
+    /// Stores the diff result of the session's objects.
+    #[cfg(feature = "bindings")]
+    pub fn set_diff_result(&mut self, result: &crate::bindings::diff::DiffResult) {
+        self.diff_result = Some(prost::Message::encode_to_vec(result));
+    }
+
+    /// Decodes the diff result stored with the session, if any.
+    #[cfg(feature = "bindings")]
+    pub fn decode_diff_result(&self) -> Result<Option<crate::bindings::diff::DiffResult>> {
+        let Some(data) = &self.diff_result else {
+            return Ok(None);
+        };
+        let result =
+            prost::Message::decode(data.as_slice()).context("Failed to decode diff result")?;
+        Ok(Some(result))
+    }
+
+    pub fn write<W: Write>(&self, mut w: W) -> Result<()> {
+        let mut header = self.header.clone();
+        header.diff_result_size = self.diff_result.as_ref().map(|data| data.len() as u64);
+        let header = serde_json::to_vec(&header)?;
+        w.write_all(&SESSION_MAGIC)?;
+        w.write_all(&u32::try_from(header.len())?.to_le_bytes())?;
+        w.write_all(&header)?;
+        for data in [&self.target, &self.base, &self.diff_result].into_iter().flatten() {
+            w.write_all(data)?;
+        }
+        Ok(())
+    }
+
+    pub fn read<R: Read>(mut r: R) -> Result<Self> {
+        let mut magic = [0u8; 8];
+        r.read_exact(&mut magic)?;
+        ensure!(magic == SESSION_MAGIC, "Not an objdiff session file");
+        let mut len = [0u8; 4];
+        r.read_exact(&mut len)?;
+        let len = u32::from_le_bytes(len) as usize;
+        ensure!(len <= MAX_HEADER_SIZE, "Session header too large");
+        let mut header = vec![0u8; len];
+        r.read_exact(&mut header)?;
+        let header: SessionHeader =
+            serde_json::from_slice(&header).context("Failed to parse session header")?;
+        if header.version > SESSION_VERSION {
+            bail!(
+                "Session version {} is not supported, exported with objdiff {}",
+                header.version,
+                header.objdiff_version
+            );
+        }
+        let mut read_data = |size: u64, name: &str| -> Result<Vec<u8>> {
+            // Don't trust the header's size for the allocation
+            let mut data = Vec::new();
+            r.by_ref()
+                .take(size)
+                .read_to_end(&mut data)
+                .with_context(|| format!("Failed to read {name}"))?;
+            ensure!(data.len() as u64 == size, "{name} is truncated");
+            Ok(data)
+        };
+        let target = header
+            .target
+            .as_ref()
+            .map(|o| read_data(o.size, &format!("object {}", o.file_name)))
+            .transpose()?;
+        let base = header
+            .base
+            .as_ref()
+            .map(|o| read_data(o.size, &format!("object {}", o.file_name)))
+            .transpose()?;
+        let diff_result =
+            header.diff_result_size.map(|size| read_data(size, "diff result")).transpose()?;
+        Ok(Self { header, target, base, diff_result })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let file = fs::File::create(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        let mut w = BufWriter::new(file);
+        self.write(&mut w)?;
+        w.flush()?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let file =
+            fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        Self::read(BufReader::new(file))
+            .with_context(|| format!("Failed to load session {}", path.display()))
+    }
+
+    /// Writes the objects to `dir`, returning the target and base object paths.
+    pub fn extract(&self, dir: &Path) -> Result<(Option<PathBuf>, Option<PathBuf>)> {
+        let mut paths = [None, None];
+        for (i, (side, object, data)) in
+            [("target", &self.header.target, &self.target), ("base", &self.header.base, &self.base)]
+                .into_iter()
+                .enumerate()
+        {
+            let (Some(object), Some(data)) = (object, data) else {
+                continue;
+            };
+            let side_dir = dir.join(side);
+            fs::create_dir_all(&side_dir)
+                .with_context(|| format!("Failed to create {}", side_dir.display()))?;
+            // Only keep the file name, the header may come from an untrusted source
+            let file_name = Path::new(&object.file_name)
+                .file_name()
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from(format!("{side}.o")));
+            let path = side_dir.join(file_name);
+            fs::write(&path, data)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            paths[i] = Some(path);
+        }
+        let [target, base] = paths;
+        Ok((target, base))
+    }
+}
@@ -0,0 +1,145 @@
+//! Opt-in usage statistics, for project leads planning work across a team: builds run and time
+//! spent per unit, by day. Statistics are stored in a project-local state file and are never
+//! sent anywhere; they can be exported to JSON along with the functions matched per day from
+//! the [`MatchHistory`].
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+use crate::config::match_history::{MatchHistory, SECONDS_PER_DAY, STATE_DIR};
+
+/// Usage statistics file name within [`STATE_DIR`].
+pub const USAGE_STATS_FILE: &str = "usage_stats.json";
+
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+pub struct UsageRecord {
+    /// Number of builds run.
+    pub builds: u64,
+    /// Time spent with the unit selected, in seconds.
+    pub seconds: u64,
+}
+
+/// Usage records keyed by day (days since the Unix epoch, UTC), then unit name.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct UsageStats {
+    pub days: BTreeMap<u64, BTreeMap<String, UsageRecord>>,
+}
+
+/// Exported statistics for a single day.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct DayUsageReport {
+    /// Date in `YYYY-MM-DD` form (UTC).
+    pub date: String,
+    pub builds: u64,
+    pub seconds: u64,
+    /// Functions that first reached 100% on this day.
+    pub functions_matched: u64,
+}
+
+/// Exported statistics for a single unit, over all days.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct UnitUsageReport {
+    pub name: String,
+    pub builds: u64,
+    pub seconds: u64,
+    pub functions_matched: u64,
+}
+
+/// Usage statistics in the exported JSON form.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct UsageReport {
+    /// Unix timestamp (seconds) of the export.
+    pub exported_at: u64,
+    pub days: Vec<DayUsageReport>,
+    pub units: Vec<UnitUsageReport>,
+}
+
+pub fn usage_stats_path(project_dir: &Path) -> PathBuf {
+    project_dir.join(STATE_DIR).join(USAGE_STATS_FILE)
+}
+
+/// Formats a day number (days since the Unix epoch) as a `YYYY-MM-DD` date.
+pub fn format_day(day: u64) -> String {
+    // Civil date from days, see https://howardhinnant.github.io/date_algorithms.html
+    let z = day as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    format!("{y:04}-{m:02}-{d:02}")
+}
+
+impl UsageStats {
+    /// Loads the statistics for a project, or empty statistics if none have been recorded.
+    pub fn load(project_dir: &Path) -> Result<Self> {
+        let path = usage_stats_path(project_dir);
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let data = fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_slice(&data).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, project_dir: &Path) -> Result<()> {
+        let path = usage_stats_path(project_dir);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let data = serde_json::to_vec_pretty(self)?;
+        fs::write(&path, data).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    fn record_mut(&mut self, unit: &str, now: u64) -> &mut UsageRecord {
+        self.days.entry(now / SECONDS_PER_DAY).or_default().entry(unit.to_string()).or_default()
+    }
+
+    pub fn record_build(&mut self, unit: &str, now: u64) { self.record_mut(unit, now).builds += 1; }
+
+    pub fn record_time(&mut self, unit: &str, seconds: u64, now: u64) {
+        self.record_mut(unit, now).seconds += seconds;
+    }
+
+    /// Combines the statistics with the functions matched per day and unit from the project's
+    /// match history.
+    pub fn report(&self, history: &MatchHistory, now: u64) -> UsageReport {
+        let mut days = BTreeMap::<u64, DayUsageReport>::new();
+        let mut units = BTreeMap::<&str, UnitUsageReport>::new();
+        for (&day, records) in &self.days {
+            let day_report = days.entry(day).or_default();
+            for (unit, record) in records {
+                day_report.builds += record.builds;
+                day_report.seconds += record.seconds;
+                let unit_report = units.entry(unit).or_default();
+                unit_report.builds += record.builds;
+                unit_report.seconds += record.seconds;
+            }
+        }
+        for (unit, records) in &history.units {
+            for record in records.values() {
+                days.entry(record.matched_at / SECONDS_PER_DAY).or_default().functions_matched += 1;
+                units.entry(unit).or_default().functions_matched += 1;
+            }
+        }
+        UsageReport {
+            exported_at: now,
+            days: days
+                .into_iter()
+                .map(|(day, report)| DayUsageReport { date: format_day(day), ..report })
+                .collect(),
+            units: units
+                .into_iter()
+                .map(|(name, report)| UnitUsageReport { name: name.to_string(), ..report })
+                .collect(),
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use std::{cmp::max, collections::BTreeMap};
+use std::{
+    cmp::max,
+    collections::{BTreeMap, HashMap},
+    ops::Range,
+};
 
 use anyhow::{anyhow, Result};
 use similar::{capture_diff_slices_deadline, Algorithm};
@@ -6,21 +10,39 @@ use similar::{capture_diff_slices_deadline, Algorithm};
 use crate::{
     arch::ProcessCodeResult,
     diff::{
-        DiffObjConfig, ObjInsArgDiff, ObjInsBranchFrom, ObjInsBranchTo, ObjInsDiff, ObjInsDiffKind,
-        ObjSymbolDiff,
+        quirks::apply_codegen_quirks, AlignmentAnchor, DiffObjConfig, ObjInsArgDiff,
+        ObjInsBranchFrom, ObjInsBranchTo, ObjInsDiff, ObjInsDiffKind, ObjSymbolDiff,
+        PpcCrFieldMismatch, SizeMismatchFix,
+    },
+    obj::{
+        dynamic::LINKED_RELOC_FLAGS, ObjInfo, ObjIns, ObjInsArg, ObjReloc, ObjSection, ObjSymbol,
+        ObjSymbolFlags, ObjSymbolKind, SymbolRef,
     },
-    obj::{ObjInfo, ObjInsArg, ObjReloc, ObjSection, ObjSymbol, ObjSymbolFlags, SymbolRef},
 };
 
 pub fn process_code_symbol(
     obj: &ObjInfo,
     symbol_ref: SymbolRef,
     config: &DiffObjConfig,
+) -> Result<ProcessCodeResult> {
+    let (_, symbol) = obj.section_symbol(symbol_ref);
+    process_code_range(obj, symbol_ref, symbol.size, config)
+}
+
+/// Processes the first `size` bytes of a code symbol, which may differ from the symbol's own
+/// size when a size mismatch is being worked around.
+pub fn process_code_range(
+    obj: &ObjInfo,
+    symbol_ref: SymbolRef,
+    size: u64,
+    config: &DiffObjConfig,
 ) -> Result<ProcessCodeResult> {
     let (section, symbol) = obj.section_symbol(symbol_ref);
     let section = section.ok_or_else(|| anyhow!("Code symbol section not found"))?;
-    let code = &section.data
-        [symbol.section_address as usize..(symbol.section_address + symbol.size) as usize];
+    let code = section
+        .data
+        .get(symbol.section_address as usize..(symbol.section_address + size) as usize)
+        .ok_or_else(|| anyhow!("Code symbol {} extends past its section", symbol.name))?;
     let mut res = obj.arch.process_code(
         symbol.address,
         code,
@@ -31,7 +53,42 @@ pub fn process_code_symbol(
     )?;
 
     for inst in res.insts.iter_mut() {
+        if inst.reloc.is_none() && !obj.dynamic_imports.is_empty() {
+            // Branches to a PLT stub call the imported function
+            if let Some(name) =
+                inst.branch_dest.and_then(|dest| obj.dynamic_imports.plt_target(dest))
+            {
+                inst.reloc = Some(import_reloc(inst.address, name));
+                inst.branch_dest = None;
+                for arg in &mut inst.args {
+                    if matches!(arg, ObjInsArg::BranchDest(_)) {
+                        *arg = ObjInsArg::Reloc;
+                    }
+                }
+            }
+        }
         if let Some(reloc) = &mut inst.reloc {
+            if reloc.flags == LINKED_RELOC_FLAGS && reloc.target.orig_section_index.is_none() {
+                // Placeholder target created from an address in a linked image
+                let address = reloc.target.address;
+                if let Some(name) = obj
+                    .dynamic_imports
+                    .got_target(address)
+                    .or_else(|| obj.dynamic_imports.plt_target(address))
+                {
+                    *reloc = import_reloc(reloc.address, name);
+                    continue;
+                }
+                reloc.target.orig_section_index = obj
+                    .sections
+                    .iter()
+                    .find(|s| (s.address..s.address + s.size).contains(&address))
+                    .map(|s| s.orig_index);
+                if reloc.target.orig_section_index.is_none() {
+                    reloc.target.name = format!("{address:#x}");
+                    continue;
+                }
+            }
             if reloc.target.size == 0 && reloc.target.name.is_empty() {
                 // Fake target symbol we added as a placeholder. We need to find the real one.
                 if let Some(real_target) =
@@ -39,6 +96,16 @@ pub fn process_code_symbol(
                 {
                     reloc.addend = (reloc.target.address - real_target.address) as i64;
                     reloc.target = real_target;
+                } else if let Some(section) = reloc
+                    .target
+                    .orig_section_index
+                    .and_then(|idx| obj.sections.iter().find(|s| s.orig_index == idx))
+                {
+                    // No symbol covers the address, fall back to a section-relative target
+                    reloc.addend = (reloc.target.address - section.address) as i64;
+                    reloc.target.name.clone_from(&section.name);
+                    reloc.target.address = section.address;
+                    reloc.target.kind = ObjSymbolKind::Section;
                 }
             }
         }
@@ -47,7 +114,85 @@ pub fn process_code_symbol(
     Ok(res)
 }
 
-pub fn no_diff_code(out: &ProcessCodeResult, symbol_ref: SymbolRef) -> Result<ObjSymbolDiff> {
+/// A relocation to an imported symbol, resolved through the PLT or GOT of a linked image.
+fn import_reloc(address: u64, name: &str) -> ObjReloc {
+    ObjReloc {
+        flags: LINKED_RELOC_FLAGS,
+        address,
+        target: ObjSymbol {
+            name: name.to_string(),
+            demangled_name: None,
+            address: 0,
+            section_address: 0,
+            size: 0,
+            size_known: false,
+            kind: Default::default(),
+            flags: Default::default(),
+            orig_section_index: None,
+            virtual_address: None,
+            original_index: None,
+            bytes: vec![],
+        },
+        addend: 0,
+        original: None,
+        pseudo: false,
+    }
+}
+
+/// The size of a symbol if it extended up to the next symbol or the end of its section.
+pub fn inferred_symbol_size(obj: &ObjInfo, symbol_ref: SymbolRef) -> u64 {
+    let (section, symbol) = obj.section_symbol(symbol_ref);
+    let Some(section) = section else {
+        return symbol.size;
+    };
+    let end = section
+        .symbols
+        .iter()
+        .map(|s| s.address)
+        .filter(|&address| address > symbol.address)
+        .min()
+        .unwrap_or(section.address + section.size);
+    end - symbol.address
+}
+
+/// The sizes to diff a pair of code symbols with. Without a fix, or when the sizes already
+/// match, these are the symbols' own sizes.
+pub fn fixed_code_sizes(
+    left_obj: &ObjInfo,
+    left_symbol_ref: SymbolRef,
+    right_obj: &ObjInfo,
+    right_symbol_ref: SymbolRef,
+    fix: Option<SizeMismatchFix>,
+) -> (u64, u64) {
+    let left_size = left_obj.section_symbol(left_symbol_ref).1.size;
+    let right_size = right_obj.section_symbol(right_symbol_ref).1.size;
+    if left_size == right_size {
+        return (left_size, right_size);
+    }
+    match fix {
+        None => (left_size, right_size),
+        Some(SizeMismatchFix::Overlap) => {
+            let size = left_size.min(right_size);
+            (size, size)
+        }
+        Some(SizeMismatchFix::Extend) => {
+            // The shorter side grows up to the longer side's size, but never into the next symbol
+            if left_size < right_size {
+                let inferred = inferred_symbol_size(left_obj, left_symbol_ref);
+                (inferred.clamp(left_size, right_size), right_size)
+            } else {
+                let inferred = inferred_symbol_size(right_obj, right_symbol_ref);
+                (left_size, inferred.clamp(right_size, left_size))
+            }
+        }
+    }
+}
+
+pub fn no_diff_code(
+    obj: &ObjInfo,
+    out: &ProcessCodeResult,
+    symbol_ref: SymbolRef,
+) -> Result<ObjSymbolDiff> {
     let mut diff = Vec::<ObjInsDiff>::new();
     for i in &out.insts {
         diff.push(ObjInsDiff {
@@ -57,7 +202,17 @@ pub fn no_diff_code(out: &ProcessCodeResult, symbol_ref: SymbolRef) -> Result<Ob
         });
     }
     resolve_branches(&mut diff);
-    Ok(ObjSymbolDiff { symbol_ref, target_symbol: None, instructions: diff, match_percent: None })
+    Ok(ObjSymbolDiff {
+        symbol_ref,
+        target_symbol: None,
+        instructions: diff,
+        match_percent: None,
+        opcode_match_percent: None,
+        ambiguous_match: false,
+        too_different: false,
+        stack_frame: obj.arch.stack_frame(&out.insts),
+        quirk_count: 0,
+    })
 }
 
 pub fn diff_code(
@@ -71,7 +226,28 @@ pub fn diff_code(
 ) -> Result<(ObjSymbolDiff, ObjSymbolDiff)> {
     let mut left_diff = Vec::<ObjInsDiff>::new();
     let mut right_diff = Vec::<ObjInsDiff>::new();
-    diff_instructions(&mut left_diff, &mut right_diff, left_out, right_out)?;
+    let (_, left_symbol) = left_obj.section_symbol(left_symbol_ref);
+    let (_, right_symbol) = right_obj.section_symbol(right_symbol_ref);
+    let anchors = config
+        .symbol_mappings
+        .alignment_anchors
+        .get(&left_symbol.name)
+        .map(|anchors| {
+            anchor_indices(left_out, right_out, left_symbol.address, right_symbol.address, anchors)
+        })
+        .unwrap_or_default();
+    let too_different = diff_instructions(
+        &mut left_diff,
+        &mut right_diff,
+        left_obj,
+        right_obj,
+        left_out,
+        right_out,
+        &anchors,
+        config,
+    )?;
+
+    let opcode_percent = opcode_match_percent(&left_diff, &right_diff);
 
     resolve_branches(&mut left_diff);
     resolve_branches(&mut right_diff);
@@ -84,6 +260,18 @@ pub fn diff_code(
         left.arg_diff = result.left_args_diff;
         right.arg_diff = result.right_args_diff;
     }
+    let quirk_count = if config.ignore_codegen_quirks {
+        apply_codegen_quirks(config, left_obj.architecture, &mut left_diff, &mut right_diff)
+    } else {
+        0
+    };
+    // Quirk rows were differing rows, but count as matching
+    diff_state.diff_count -= quirk_count;
+    if config.ignore_pseudo_reloc_diffs {
+        // After quirks, which may already cover these rows
+        diff_state.diff_count -=
+            left_diff.iter().zip(&right_diff).filter(|(l, r)| pseudo_reloc_mismatch(l, r)).count();
+    }
 
     let total = left_out.insts.len().max(right_out.insts.len());
     let percent = if diff_state.diff_count >= total {
@@ -98,42 +286,243 @@ pub fn diff_code(
             target_symbol: Some(right_symbol_ref),
             instructions: left_diff,
             match_percent: Some(percent),
+            opcode_match_percent: Some(opcode_percent),
+            ambiguous_match: false,
+            too_different,
+            stack_frame: left_obj.arch.stack_frame(&left_out.insts),
+            quirk_count,
         },
         ObjSymbolDiff {
             symbol_ref: right_symbol_ref,
             target_symbol: Some(left_symbol_ref),
             instructions: right_diff,
             match_percent: Some(percent),
+            opcode_match_percent: Some(opcode_percent),
+            ambiguous_match: false,
+            too_different,
+            stack_frame: right_obj.arch.stack_frame(&right_out.insts),
+            quirk_count,
         },
     ))
 }
 
+/// Percentage of aligned rows where both sides have the same opcode. Arguments aren't compared,
+/// so this is available before (and much cheaper than) the full instruction comparison.
+fn opcode_match_percent(left_diff: &[ObjInsDiff], right_diff: &[ObjInsDiff]) -> f32 {
+    let total = left_diff.len().max(right_diff.len());
+    if total == 0 {
+        return 100.0;
+    }
+    let matching = left_diff
+        .iter()
+        .zip(right_diff)
+        .filter(|(l, r)| match (&l.ins, &r.ins) {
+            (Some(l), Some(r)) => l.op == r.op,
+            _ => false,
+        })
+        .count();
+    (matching as f32 / total as f32) * 100.0
+}
+
+/// Whether a row is an argument mismatch only in relocations, at least one of which is a pseudo
+/// relocation inferred by flow analysis. See [`DiffObjConfig::ignore_pseudo_reloc_diffs`].
+pub fn pseudo_reloc_mismatch(left: &ObjInsDiff, right: &ObjInsDiff) -> bool {
+    if left.kind != ObjInsDiffKind::ArgMismatch {
+        return false;
+    }
+    let (Some(left_ins), Some(right_ins)) = (&left.ins, &right.ins) else {
+        return false;
+    };
+    if !left_ins.reloc.as_ref().is_some_and(|r| r.pseudo)
+        && !right_ins.reloc.as_ref().is_some_and(|r| r.pseudo)
+    {
+        return false;
+    }
+    let only_relocs = |ins: &ObjIns, arg_diff: &[Option<ObjInsArgDiff>]| {
+        ins.iter_args()
+            .zip(arg_diff)
+            .all(|(arg, diff)| diff.is_none() || matches!(arg, ObjInsArg::Reloc))
+    };
+    only_relocs(left_ins, &left.arg_diff) && only_relocs(right_ins, &right.arg_diff)
+}
+
+/// Row counts of a function diff by kind, with the totals the match percent is computed from.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct InsDiffStats {
+    pub rows: usize,
+    pub equal: usize,
+    pub op_mismatch: usize,
+    pub arg_mismatch: usize,
+    pub replace: usize,
+    pub insert: usize,
+    pub delete: usize,
+    pub quirk: usize,
+    pub soft_mismatch: usize,
+    /// Argument mismatches only in pseudo relocations, also included in `arg_mismatch`
+    pub pseudo_reloc: usize,
+    /// Instruction count of the larger side, the denominator of the match percent
+    pub total: usize,
+    /// Rows counted against the match percent
+    pub diff_count: usize,
+    /// Size of the instructions in rows counted as matching
+    pub matched_bytes: u64,
+    /// Code size of the larger side
+    pub total_bytes: u64,
+}
+
+impl InsDiffStats {
+    pub fn new(left: &[ObjInsDiff], right: &[ObjInsDiff], config: &DiffObjConfig) -> Self {
+        let mut stats = Self { rows: left.len().max(right.len()), ..Default::default() };
+        let side_totals = |rows: &[ObjInsDiff]| {
+            rows.iter()
+                .filter_map(|row| row.ins.as_ref())
+                .fold((0, 0), |(count, bytes), ins| (count + 1, bytes + ins.size as u64))
+        };
+        let (left_count, left_bytes) = side_totals(left);
+        let (right_count, right_bytes) = side_totals(right);
+        stats.total = left_count.max(right_count);
+        stats.total_bytes = left_bytes.max(right_bytes);
+        for (l, r) in left.iter().zip(right) {
+            let counted = match l.kind {
+                ObjInsDiffKind::None => {
+                    stats.equal += 1;
+                    false
+                }
+                ObjInsDiffKind::OpMismatch => {
+                    stats.op_mismatch += 1;
+                    true
+                }
+                ObjInsDiffKind::ArgMismatch => {
+                    stats.arg_mismatch += 1;
+                    if pseudo_reloc_mismatch(l, r) {
+                        stats.pseudo_reloc += 1;
+                        !config.ignore_pseudo_reloc_diffs
+                    } else {
+                        true
+                    }
+                }
+                ObjInsDiffKind::Replace => {
+                    stats.replace += 1;
+                    true
+                }
+                ObjInsDiffKind::Delete => {
+                    stats.delete += 1;
+                    true
+                }
+                ObjInsDiffKind::Insert => {
+                    stats.insert += 1;
+                    true
+                }
+                ObjInsDiffKind::Quirk => {
+                    stats.quirk += 1;
+                    false
+                }
+                ObjInsDiffKind::SoftMismatch => {
+                    stats.soft_mismatch += 1;
+                    config.ppc_cr_field_mismatch != PpcCrFieldMismatch::Ignore
+                }
+            };
+            if counted {
+                stats.diff_count += 1;
+            } else if let Some(ins) = l.ins.as_ref().or(r.ins.as_ref()) {
+                stats.matched_bytes += ins.size as u64;
+            }
+        }
+        stats
+    }
+}
+
+/// Functions whose instruction counts multiply to more than this are aligned with a bounded
+/// edit distance, since unbounded alignment can take seconds for huge, very different functions.
+const LARGE_ALIGNMENT_CELLS: usize = 1 << 24;
+
+/// A run of aligned instructions. Equal runs have the same length on both sides; otherwise
+/// one side is empty (a deletion or insertion), or both are non-empty and paired by position.
+type AlignOp = (Range<usize>, Range<usize>);
+
+/// Resolves alignment anchors to pairs of instruction indices, in increasing order. Anchors that
+/// don't point at an instruction on both sides, or that cross an earlier anchor, are ignored.
+fn anchor_indices(
+    left_code: &ProcessCodeResult,
+    right_code: &ProcessCodeResult,
+    left_address: u64,
+    right_address: u64,
+    anchors: &[AlignmentAnchor],
+) -> Vec<(usize, usize)> {
+    let index = |code: &ProcessCodeResult, address: u64| {
+        code.insts.iter().position(|ins| ins.address == address)
+    };
+    let mut anchors = anchors.to_vec();
+    anchors.sort();
+    let mut out = Vec::<(usize, usize)>::with_capacity(anchors.len());
+    for anchor in anchors {
+        let (Some(left), Some(right)) = (
+            index(left_code, left_address + anchor.left),
+            index(right_code, right_address + anchor.right),
+        ) else {
+            continue;
+        };
+        if out
+            .last()
+            .map_or(true, |&(prev_left, prev_right)| left > prev_left && right > prev_right)
+        {
+            out.push((left, right));
+        }
+    }
+    out
+}
+
+/// Aligns the instructions and appends the resulting rows. Returns `true` if the functions were
+/// too different to align within [`DiffObjConfig::max_alignment_edits`], in which case the
+/// instructions are paired by position.
+///
+/// `anchors` are pairs of instruction indices that must be aligned to each other. The
+/// instructions between consecutive anchors are aligned independently.
+#[expect(clippy::too_many_arguments)]
 fn diff_instructions(
     left_diff: &mut Vec<ObjInsDiff>,
     right_diff: &mut Vec<ObjInsDiff>,
+    left_obj: &ObjInfo,
+    right_obj: &ObjInfo,
     left_code: &ProcessCodeResult,
     right_code: &ProcessCodeResult,
-) -> Result<()> {
-    let ops =
-        capture_diff_slices_deadline(Algorithm::Patience, &left_code.ops, &right_code.ops, None);
-    if ops.is_empty() {
-        left_diff.extend(
-            left_code
-                .insts
-                .iter()
-                .map(|i| ObjInsDiff { ins: Some(i.clone()), ..Default::default() }),
-        );
-        right_diff.extend(
-            right_code
-                .insts
-                .iter()
-                .map(|i| ObjInsDiff { ins: Some(i.clone()), ..Default::default() }),
+    anchors: &[(usize, usize)],
+    config: &DiffObjConfig,
+) -> Result<bool> {
+    // Align by opcode class, so that trivially equivalent instructions are paired
+    let left_ops =
+        left_code.ops.iter().map(|&op| left_obj.arch.opcode_class(op)).collect::<Vec<_>>();
+    let right_ops =
+        right_code.ops.iter().map(|&op| right_obj.arch.opcode_class(op)).collect::<Vec<_>>();
+    let max_edits = config.max_alignment_edits as usize;
+
+    let mut ops = Vec::<AlignOp>::new();
+    let mut too_different = false;
+    let (mut left_start, mut right_start) = (0, 0);
+    for (left_end, right_end) in anchors.iter().copied().chain([(left_ops.len(), right_ops.len())])
+    {
+        let segment = align_ops(
+            &left_ops[left_start..left_end],
+            &right_ops[right_start..right_end],
+            max_edits,
         );
-        return Ok(());
+        too_different |= segment.is_none();
+        let segment =
+            segment.unwrap_or_else(|| vec![(0..left_end - left_start, 0..right_end - right_start)]);
+        ops.extend(segment.into_iter().map(|(left_range, right_range)| {
+            (
+                left_range.start + left_start..left_range.end + left_start,
+                right_range.start + right_start..right_range.end + right_start,
+            )
+        }));
+        if left_end < left_ops.len() {
+            // The anchored instructions themselves
+            ops.push((left_end..left_end + 1, right_end..right_end + 1));
+        }
+        (left_start, right_start) = (left_end + 1, right_end + 1);
     }
 
-    for op in ops {
-        let (_tag, left_range, right_range) = op.as_tag_tuple();
+    for (left_range, right_range) in ops {
         let len = max(left_range.len(), right_range.len());
         left_diff.extend(
             left_code.insts[left_range.clone()]
@@ -153,7 +542,141 @@ fn diff_instructions(
         }
     }
 
-    Ok(())
+    Ok(too_different)
+}
+
+/// Aligns two sequences of opcode classes, or returns `None` if they differ by more than
+/// `max_edits` insertions and deletions.
+fn align_ops(left_ops: &[u16], right_ops: &[u16], max_edits: usize) -> Option<Vec<AlignOp>> {
+    if max_edits == 0 || left_ops.len().saturating_mul(right_ops.len()) <= LARGE_ALIGNMENT_CELLS {
+        Some(
+            capture_diff_slices_deadline(Algorithm::Patience, left_ops, right_ops, None)
+                .into_iter()
+                .map(|op| {
+                    let (_tag, left_range, right_range) = op.as_tag_tuple();
+                    (left_range, right_range)
+                })
+                .collect(),
+        )
+    } else if min_edit_distance(left_ops, right_ops) > max_edits {
+        // Exit early without attempting the alignment
+        None
+    } else {
+        bounded_diff(left_ops, right_ops, max_edits)
+    }
+}
+
+/// A lower bound on the number of insertions and deletions needed to align the sequences,
+/// from the number of opcodes they have in common regardless of order.
+fn min_edit_distance(left: &[u16], right: &[u16]) -> usize {
+    let mut counts = HashMap::<u16, (usize, usize)>::new();
+    for &op in left {
+        counts.entry(op).or_default().0 += 1;
+    }
+    for &op in right {
+        counts.entry(op).or_default().1 += 1;
+    }
+    let common = counts.values().map(|&(l, r)| l.min(r)).sum::<usize>();
+    left.len() + right.len() - common * 2
+}
+
+/// Myers' O(ND) diff, giving up if more than `max_edits` insertions and deletions are needed.
+/// Memory use is O(max_edits²) rather than O(N·M).
+fn bounded_diff(left: &[u16], right: &[u16], max_edits: usize) -> Option<Vec<AlignOp>> {
+    // Strip the common prefix and suffix, which are aligned trivially
+    let prefix = left.iter().zip(right).take_while(|(l, r)| l == r).count();
+    let suffix = left[prefix..]
+        .iter()
+        .rev()
+        .zip(right[prefix..].iter().rev())
+        .take_while(|(l, r)| l == r)
+        .count();
+    let a = &left[prefix..left.len() - suffix];
+    let b = &right[prefix..right.len() - suffix];
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max_d = max_edits.min(a.len() + b.len()) as isize;
+
+    // `v[k + offset]` is the furthest x reached on diagonal k. Before each round d, the
+    // diagonals -d-1..=d+1 are saved for backtracking.
+    let offset = max_d + 1;
+    let mut v = vec![0isize; (2 * max_d + 3) as usize];
+    let mut trace = Vec::<Vec<isize>>::new();
+    let mut end = None;
+    'outer: for d in 0..=max_d {
+        trace.push(v[(offset - d - 1) as usize..=(offset + d + 1) as usize].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let idx = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+                v[idx + 1]
+            } else {
+                v[idx - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx] = x;
+            if x >= n && y >= m {
+                end = Some(d);
+                break 'outer;
+            }
+        }
+    }
+    let end = end?;
+
+    // Backtrack from (n, m), collecting single steps in reverse
+    #[derive(Copy, Clone, Eq, PartialEq)]
+    enum Step {
+        Equal,
+        Delete,
+        Insert,
+    }
+    let mut steps = Vec::with_capacity((n + m) as usize);
+    let (mut x, mut y) = (n, m);
+    for d in (1..=end).rev() {
+        let saved = &trace[d as usize];
+        let get = |k: isize| saved[(k + d + 1) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && get(k - 1) < get(k + 1)) { k + 1 } else { k - 1 };
+        let prev_x = get(prev_k);
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            steps.push(Step::Equal);
+            x -= 1;
+            y -= 1;
+        }
+        steps.push(if prev_k == k + 1 { Step::Insert } else { Step::Delete });
+        x = prev_x;
+        y = prev_y;
+    }
+    steps.extend((0..x).map(|_| Step::Equal));
+    steps.reverse();
+
+    // Group consecutive steps of the same kind into runs
+    let mut ops = Vec::<AlignOp>::new();
+    if prefix > 0 {
+        ops.push((0..prefix, 0..prefix));
+    }
+    let (mut li, mut ri) = (prefix, prefix);
+    let mut i = 0;
+    while i < steps.len() {
+        let step = steps[i];
+        let len = steps[i..].iter().take_while(|&&s| s == step).count();
+        let (l_len, r_len) = match step {
+            Step::Equal => (len, len),
+            Step::Delete => (len, 0),
+            Step::Insert => (0, len),
+        };
+        ops.push((li..li + l_len, ri..ri + r_len));
+        li += l_len;
+        ri += r_len;
+        i += len;
+    }
+    if suffix > 0 {
+        ops.push((li..li + suffix, ri..ri + suffix));
+    }
+    Some(ops)
 }
 
 fn resolve_branches(vec: &mut [ObjInsDiff]) {
@@ -221,6 +744,10 @@ fn reloc_eq(
     let (Some(left), Some(right)) = (left_reloc, right_reloc) else {
         return false;
     };
+    if left.flags == LINKED_RELOC_FLAGS || right.flags == LINKED_RELOC_FLAGS {
+        // Relocations of a linked image don't carry a type; compare the targets only
+        return config.relax_reloc_diffs || left.target.name == right.target.name;
+    }
     if left.flags != right.flags {
         return false;
     }
@@ -292,10 +819,8 @@ fn arg_eq(
 #[derive(Default)]
 struct InsDiffState {
     diff_count: usize,
-    left_arg_idx: usize,
-    right_arg_idx: usize,
-    left_args_idx: BTreeMap<String, usize>,
-    right_args_idx: BTreeMap<String, usize>,
+    arg_idx: usize,
+    args_idx: BTreeMap<(String, String), usize>,
 }
 
 #[derive(Default)]
@@ -318,12 +843,42 @@ fn compare_ins(
         // Count only non-PlainText args
         let left_args_count = left_ins.iter_args().count();
         let right_args_count = right_ins.iter_args().count();
-        if left_args_count != right_args_count || left_ins.op != right_ins.op {
+        if left_ins.op != right_ins.op {
             // Totally different op
             result.kind = ObjInsDiffKind::Replace;
             state.diff_count += 1;
             return Ok(result);
         }
+        if left_ins.mnemonic != right_ins.mnemonic
+            && (left_ins.raw_args.is_some() || right_ins.raw_args.is_some())
+        {
+            // A pseudo-instruction against another form of the same raw instruction, e.g. MIPS
+            // `beqz` against `beq`. The displayed arguments don't line up, so compare those of
+            // the raw forms, without highlighting individual arguments.
+            let (left_args, right_args) = (left_ins.iter_raw_args(), right_ins.iter_raw_args());
+            if left_args.clone().count() != right_args.clone().count()
+                || !left_args
+                    .zip(right_args)
+                    .all(|(a, b)| arg_eq(config, left_obj, right_obj, a, b, left, right))
+            {
+                result.kind = ObjInsDiffKind::ArgMismatch;
+                state.diff_count += 1;
+            }
+            return Ok(result);
+        }
+        if left_args_count != right_args_count {
+            // Simplified forms may omit soft arguments, such as cr0 on PPC
+            if soft_mismatch(config, left_obj, right_obj, left, right) {
+                result.kind = ObjInsDiffKind::SoftMismatch;
+                if config.ppc_cr_field_mismatch != PpcCrFieldMismatch::Ignore {
+                    state.diff_count += 1;
+                }
+            } else {
+                result.kind = ObjInsDiffKind::Replace;
+                state.diff_count += 1;
+            }
+            return Ok(result);
+        }
         if left_ins.mnemonic != right_ins.mnemonic {
             // Same op but different mnemonic, still cmp args
             result.kind = ObjInsDiffKind::OpMismatch;
@@ -347,14 +902,6 @@ fn compare_ins(
                         .map_or_else(|| "<unknown>".to_string(), |r| r.target.name.clone()),
                     ObjInsArg::BranchDest(arg) => arg.to_string(),
                 };
-                let a_diff = if let Some(idx) = state.left_args_idx.get(&a_str) {
-                    ObjInsArgDiff { idx: *idx }
-                } else {
-                    let idx = state.left_arg_idx;
-                    state.left_args_idx.insert(a_str, idx);
-                    state.left_arg_idx += 1;
-                    ObjInsArgDiff { idx }
-                };
                 let b_str = match b {
                     ObjInsArg::PlainText(arg) => arg.to_string(),
                     ObjInsArg::Arg(arg) => arg.to_string(),
@@ -364,16 +911,27 @@ fn compare_ins(
                         .map_or_else(|| "<unknown>".to_string(), |r| r.target.name.clone()),
                     ObjInsArg::BranchDest(arg) => arg.to_string(),
                 };
-                let b_diff = if let Some(idx) = state.right_args_idx.get(&b_str) {
-                    ObjInsArgDiff { idx: *idx }
+                // Each distinct pair of differing operands gets the same index on both sides,
+                // so that counterparts can be colored and highlighted together
+                let key = (a_str, b_str);
+                let idx = if let Some(idx) = state.args_idx.get(&key) {
+                    *idx
                 } else {
-                    let idx = state.right_arg_idx;
-                    state.right_args_idx.insert(b_str, idx);
-                    state.right_arg_idx += 1;
-                    ObjInsArgDiff { idx }
+                    let idx = state.arg_idx;
+                    state.args_idx.insert(key, idx);
+                    state.arg_idx += 1;
+                    idx
                 };
-                result.left_args_diff.push(Some(a_diff));
-                result.right_args_diff.push(Some(b_diff));
+                result.left_args_diff.push(Some(ObjInsArgDiff { idx }));
+                result.right_args_diff.push(Some(ObjInsArgDiff { idx }));
+            }
+        }
+        if result.kind != ObjInsDiffKind::None
+            && soft_mismatch(config, left_obj, right_obj, left, right)
+        {
+            result.kind = ObjInsDiffKind::SoftMismatch;
+            if config.ppc_cr_field_mismatch == PpcCrFieldMismatch::Ignore {
+                state.diff_count -= 1;
             }
         }
     } else if left.ins.is_some() {
@@ -386,6 +944,34 @@ fn compare_ins(
     Ok(result)
 }
 
+/// Whether two instructions with the same opcode are equal apart from arguments the arch
+/// considers interchangeable, such as PPC condition register fields.
+fn soft_mismatch(
+    config: &DiffObjConfig,
+    left_obj: &ObjInfo,
+    right_obj: &ObjInfo,
+    left: &ObjInsDiff,
+    right: &ObjInsDiff,
+) -> bool {
+    let (Some(left_ins), Some(right_ins)) = (&left.ins, &right.ins) else {
+        return false;
+    };
+    if left_ins.mnemonic != right_ins.mnemonic {
+        return false;
+    }
+    let left_args =
+        left_ins.iter_args().filter(|a| !left_obj.arch.is_soft_arg(a, config)).collect::<Vec<_>>();
+    let right_args = right_ins
+        .iter_args()
+        .filter(|a| !right_obj.arch.is_soft_arg(a, config))
+        .collect::<Vec<_>>();
+    left_args.len() == right_args.len()
+        && left_args
+            .iter()
+            .zip(&right_args)
+            .all(|(a, b)| arg_eq(config, left_obj, right_obj, a, b, left, right))
+}
+
 fn find_symbol_matching_fake_symbol_in_sections(
     fake_symbol: &ObjSymbol,
     sections: &[ObjSection],
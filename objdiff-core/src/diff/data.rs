@@ -1,11 +1,15 @@
-use std::cmp::{max, min, Ordering};
+use std::{
+    cmp::{max, min, Ordering},
+    collections::{BTreeMap, HashMap, HashSet},
+    ops::Range,
+};
 
 use anyhow::{anyhow, Result};
 use similar::{capture_diff_slices_deadline, get_diff_ratio, Algorithm};
 
 use crate::{
-    diff::{ObjDataDiff, ObjDataDiffKind, ObjSectionDiff, ObjSymbolDiff},
-    obj::{ObjInfo, ObjSection, SymbolRef},
+    diff::{DiffObjConfig, ObjDataDiff, ObjDataDiffKind, ObjSectionDiff, ObjSymbolDiff},
+    obj::{ObjInfo, ObjSection, ObjSymbolKind, SymbolRef},
 };
 
 pub fn diff_bss_symbol(
@@ -23,35 +27,214 @@ pub fn diff_bss_symbol(
             target_symbol: Some(right_symbol_ref),
             instructions: vec![],
             match_percent: Some(percent),
+            opcode_match_percent: None,
+            ambiguous_match: false,
+            too_different: false,
+            stack_frame: None,
+            quirk_count: 0,
         },
         ObjSymbolDiff {
             symbol_ref: right_symbol_ref,
             target_symbol: Some(left_symbol_ref),
             instructions: vec![],
             match_percent: Some(percent),
+            opcode_match_percent: None,
+            ambiguous_match: false,
+            too_different: false,
+            stack_frame: None,
+            quirk_count: 0,
         },
     ))
 }
 
 pub fn no_diff_symbol(_obj: &ObjInfo, symbol_ref: SymbolRef) -> ObjSymbolDiff {
-    ObjSymbolDiff { symbol_ref, target_symbol: None, instructions: vec![], match_percent: None }
+    ObjSymbolDiff {
+        symbol_ref,
+        target_symbol: None,
+        instructions: vec![],
+        match_percent: None,
+        opcode_match_percent: None,
+        ambiguous_match: false,
+        too_different: false,
+        stack_frame: None,
+        quirk_count: 0,
+    }
+}
+
+/// Maximum alignment assumed for padding when the section alignment is unknown.
+const DEFAULT_MAX_PADDING_ALIGN: u64 = 32;
+
+/// Byte ranges of zeroed alignment padding between the symbols of a data section: gaps that are
+/// shorter than the alignment of the following symbol, limited by the section alignment.
+pub fn data_padding_ranges(section: &ObjSection) -> Vec<Range<usize>> {
+    let max_align = if section.align > 1 { section.align } else { DEFAULT_MAX_PADDING_ALIGN };
+    let mut symbols = section
+        .symbols
+        .iter()
+        .filter(|s| s.size > 0)
+        .map(|s| (s.section_address, s.section_address + s.size))
+        .collect::<Vec<_>>();
+    symbols.sort_unstable();
+    let mut ranges = Vec::new();
+    let mut end = None;
+    for (start, symbol_end) in symbols {
+        if let Some(end) = end.filter(|&end| start > end) {
+            let align =
+                if start == 0 { max_align } else { (1 << start.trailing_zeros()).min(max_align) };
+            let range = end as usize..start as usize;
+            if start - end < align
+                && section.data.get(range.clone()).is_some_and(|d| d.iter().all(|&b| b == 0))
+            {
+                ranges.push(range);
+            }
+        }
+        end = Some(end.map_or(symbol_end, |end: u64| end.max(symbol_end)));
+    }
+    ranges
+}
+
+fn strip_padding(data: &[u8], padding: &[Range<usize>]) -> Vec<u8> {
+    data.iter()
+        .enumerate()
+        .filter(|(i, _)| !padding.iter().any(|p| p.contains(i)))
+        .map(|(_, &b)| b)
+        .collect()
+}
+
+fn split_data_diff(
+    diff: &ObjDataDiff,
+    range: Range<usize>,
+    pos: usize,
+    padding: &[Range<usize>],
+) -> ObjDataDiff {
+    let has_data = !diff.data.is_empty();
+    ObjDataDiff {
+        data: if has_data { diff.data[range.clone()].to_vec() } else { vec![] },
+        kind: diff.kind,
+        len: range.len(),
+        symbol: diff.symbol.clone(),
+        padding: has_data && padding.iter().any(|p| p.contains(&(pos + range.start))),
+    }
+}
+
+/// Splits the paired data diff rows at the padding boundaries of either side, and marks the
+/// padding bytes of each side.
+fn mark_padding(
+    left_diff: Vec<ObjDataDiff>,
+    right_diff: Vec<ObjDataDiff>,
+    left_padding: &[Range<usize>],
+    right_padding: &[Range<usize>],
+) -> (Vec<ObjDataDiff>, Vec<ObjDataDiff>) {
+    let mut left_out = Vec::with_capacity(left_diff.len());
+    let mut right_out = Vec::with_capacity(right_diff.len());
+    let (mut left_pos, mut right_pos) = (0, 0);
+    for (left, right) in left_diff.into_iter().zip(right_diff) {
+        let mut splits = vec![0, left.len];
+        for (diff, pos, padding) in
+            [(&left, left_pos, left_padding), (&right, right_pos, right_padding)]
+        {
+            if diff.data.is_empty() {
+                continue;
+            }
+            for boundary in padding.iter().flat_map(|p| [p.start, p.end]) {
+                if boundary > pos && boundary < pos + diff.len {
+                    splits.push(boundary - pos);
+                }
+            }
+        }
+        splits.sort_unstable();
+        splits.dedup();
+        for range in splits.windows(2).map(|w| w[0]..w[1]) {
+            left_out.push(split_data_diff(&left, range.clone(), left_pos, left_padding));
+            right_out.push(split_data_diff(&right, range, right_pos, right_padding));
+        }
+        left_pos += left.data.len();
+        right_pos += right.data.len();
+    }
+    (left_out, right_out)
+}
+
+/// The byte ranges of values of known size within `range` of a section, relative to the start
+/// of `range`: relocated words, and data symbols the size of a scalar. Relocations take
+/// precedence over a symbol containing them.
+fn known_words(obj: &ObjInfo, section: &ObjSection, range: Range<u64>) -> Vec<Range<usize>> {
+    let mut words = Vec::<Range<usize>>::new();
+    let mut push = |word: Range<usize>| {
+        if !words.iter().any(|w| w.start < word.end && word.start < w.end) {
+            words.push(word);
+        }
+    };
+    for reloc in section.relocations.iter().filter(|r| range.contains(&r.address)) {
+        if let Some(size) = obj.arch.data_reloc_size(reloc.flags) {
+            let start = (reloc.address - range.start) as usize;
+            push(start..start + size);
+        }
+    }
+    for symbol in &section.symbols {
+        if matches!(symbol.kind, ObjSymbolKind::Object | ObjSymbolKind::Unknown)
+            && matches!(symbol.size, 2 | 4 | 8)
+            && symbol.section_address >= range.start
+            && symbol.section_address + symbol.size <= range.end
+        {
+            let start = (symbol.section_address - range.start) as usize;
+            push(start..start + symbol.size as usize);
+        }
+    }
+    words
+}
+
+/// Reverses the bytes of each of `words` in `data`, for comparing objects of differing
+/// endianness. Bytes of unknown size, such as strings and arrays, keep their order.
+fn swap_words(data: &[u8], words: &[Range<usize>]) -> Vec<u8> {
+    let mut out = data.to_vec();
+    for word in words.iter().filter(|w| w.end <= data.len()) {
+        out[word.clone()].reverse();
+    }
+    out
 }
 
 /// Compare the data sections of two object files.
+///
+/// If the objects differ in endianness, values of known size on the right side are compared in
+/// the byte order of the left side, while each side's data is still displayed as-is.
 pub fn diff_data_section(
-    left: &ObjSection,
-    right: &ObjSection,
+    left_obj: &ObjInfo,
+    right_obj: &ObjInfo,
+    left_section_idx: usize,
+    right_section_idx: usize,
     left_section_diff: &ObjSectionDiff,
     right_section_diff: &ObjSectionDiff,
+    config: &DiffObjConfig,
 ) -> Result<(ObjSectionDiff, ObjSectionDiff)> {
+    let left = &left_obj.sections[left_section_idx];
+    let right = &right_obj.sections[right_section_idx];
     let left_max =
         left.symbols.iter().map(|s| s.section_address + s.size).max().unwrap_or(0).min(left.size);
     let right_max =
         right.symbols.iter().map(|s| s.section_address + s.size).max().unwrap_or(0).min(right.size);
     let left_data = &left.data[..left_max as usize];
-    let right_data = &right.data[..right_max as usize];
+    let right_normalized;
+    let right_data = if left_obj.endianness != right_obj.endianness {
+        let words = known_words(right_obj, right, 0..right_max);
+        right_normalized = swap_words(&right.data[..right_max as usize], &words);
+        right_normalized.as_slice()
+    } else {
+        &right.data[..right_max as usize]
+    };
     let ops = capture_diff_slices_deadline(Algorithm::Patience, left_data, right_data, None);
-    let match_percent = get_diff_ratio(&ops, left_data.len(), right_data.len()) * 100.0;
+    let (left_padding, right_padding) = if config.ignore_data_padding {
+        (data_padding_ranges(left), data_padding_ranges(right))
+    } else {
+        Default::default()
+    };
+    let match_percent = if left_padding.is_empty() && right_padding.is_empty() {
+        get_diff_ratio(&ops, left_data.len(), right_data.len()) * 100.0
+    } else {
+        let left_data = strip_padding(left_data, &left_padding);
+        let right_data = strip_padding(right_data, &right_padding);
+        let ops = capture_diff_slices_deadline(Algorithm::Patience, &left_data, &right_data, None);
+        get_diff_ratio(&ops, left_data.len(), right_data.len()) * 100.0
+    };
 
     let mut left_diff = Vec::<ObjDataDiff>::new();
     let mut right_diff = Vec::<ObjDataDiff>::new();
@@ -121,6 +304,11 @@ pub fn diff_data_section(
         }
     }
 
+    if !left_padding.is_empty() || !right_padding.is_empty() {
+        (left_diff, right_diff) =
+            mark_padding(left_diff, right_diff, &left_padding, &right_padding);
+    }
+
     let (mut left_section_diff, mut right_section_diff) =
         diff_generic_section(left, right, left_section_diff, right_section_diff)?;
     left_section_diff.data_diff = left_diff;
@@ -135,6 +323,128 @@ pub fn diff_data_section(
     Ok((left_section_diff, right_section_diff))
 }
 
+/// Compare mergeable string sections (SHF_MERGE | SHF_STRINGS) of two object files.
+///
+/// The toolchain may deduplicate (and reorder) strings in these sections, so they are compared
+/// as sets of strings rather than raw bytes. Strings that aren't referenced by any relocation
+/// are considered dead and don't count as differences, unless the section has no references
+/// at all. Both sides are displayed in address order, aligned where the sequences of strings
+/// agree. Strings that were only moved aren't highlighted.
+pub fn diff_string_section(
+    left_obj: &ObjInfo,
+    right_obj: &ObjInfo,
+    left_section_idx: usize,
+    right_section_idx: usize,
+    left_section_diff: &ObjSectionDiff,
+    right_section_diff: &ObjSectionDiff,
+) -> Result<(ObjSectionDiff, ObjSectionDiff)> {
+    let left = &left_obj.sections[left_section_idx];
+    let right = &right_obj.sections[right_section_idx];
+    let left_strings = split_strings(left_obj, left);
+    let right_strings = split_strings(right_obj, right);
+
+    // Match each string once against any string with the same content on the other side
+    let mut right_unmatched = HashMap::<&[u8], usize>::new();
+    for s in &right_strings {
+        *right_unmatched.entry(s.data).or_default() += 1;
+    }
+    let mut matched_len = 0;
+    for s in left_strings.iter().filter(|s| s.live) {
+        if let Some(count) = right_unmatched.get_mut(s.data).filter(|count| **count > 0) {
+            *count -= 1;
+            matched_len += s.data.len();
+        }
+    }
+    let left_contents = left_strings.iter().map(|s| s.data).collect::<Vec<_>>();
+    let right_contents = right_strings.iter().map(|s| s.data).collect::<Vec<_>>();
+    let left_set = left_contents.iter().copied().collect::<HashSet<_>>();
+    let right_set = right_contents.iter().copied().collect::<HashSet<_>>();
+    // Strings present on both sides were moved or duplicated by merging, not changed
+    let row_kind = |s: &SectionString, other: &HashSet<&[u8]>, kind| {
+        if s.live && !other.contains(s.data) {
+            kind
+        } else {
+            ObjDataDiffKind::None
+        }
+    };
+
+    let mut left_diff = Vec::<ObjDataDiff>::new();
+    let mut right_diff = Vec::<ObjDataDiff>::new();
+    let ops =
+        capture_diff_slices_deadline(Algorithm::Patience, &left_contents, &right_contents, None);
+    for op in ops {
+        let (tag, left_range, right_range) = op.as_tag_tuple();
+        if tag == similar::DiffTag::Equal {
+            for s in &left_strings[left_range] {
+                let len = s.data.len();
+                left_diff.push(ObjDataDiff { data: s.data.to_vec(), len, ..Default::default() });
+                right_diff.push(ObjDataDiff { data: s.data.to_vec(), len, ..Default::default() });
+            }
+            continue;
+        }
+        for s in &left_strings[left_range] {
+            let len = s.data.len();
+            let kind = row_kind(s, &right_set, ObjDataDiffKind::Delete);
+            left_diff.push(ObjDataDiff { data: s.data.to_vec(), kind, len, ..Default::default() });
+            right_diff.push(ObjDataDiff { data: vec![], kind, len, ..Default::default() });
+        }
+        for s in &right_strings[right_range] {
+            let len = s.data.len();
+            let kind = row_kind(s, &left_set, ObjDataDiffKind::Insert);
+            left_diff.push(ObjDataDiff { data: vec![], kind, len, ..Default::default() });
+            right_diff.push(ObjDataDiff { data: s.data.to_vec(), kind, len, ..Default::default() });
+        }
+    }
+
+    let left_live_len = left_strings.iter().filter(|s| s.live).map(|s| s.data.len()).sum::<usize>();
+    let right_live_len =
+        right_strings.iter().filter(|s| s.live).map(|s| s.data.len()).sum::<usize>();
+    let match_percent = if left_live_len + right_live_len == 0 {
+        100.0
+    } else {
+        (matched_len * 2) as f32 / (left_live_len + right_live_len) as f32 * 100.0
+    };
+
+    let (mut left_section_diff, mut right_section_diff) =
+        diff_generic_section(left, right, left_section_diff, right_section_diff)?;
+    left_section_diff.data_diff = left_diff;
+    right_section_diff.data_diff = right_diff;
+    left_section_diff.match_percent = Some(match_percent.min(100.0));
+    right_section_diff.match_percent = Some(match_percent.min(100.0));
+    Ok((left_section_diff, right_section_diff))
+}
+
+struct SectionString<'a> {
+    /// String data, including the NUL terminator (if any)
+    data: &'a [u8],
+    /// Whether the string is referenced by a relocation (or the section has no references)
+    live: bool,
+}
+
+/// Splits a mergeable string section into strings, counting relocation references to each.
+fn split_strings<'a>(obj: &ObjInfo, section: &'a ObjSection) -> Vec<SectionString<'a>> {
+    let mut ref_counts = BTreeMap::<usize, usize>::new();
+    for reloc in obj.sections.iter().flat_map(|s| &s.relocations) {
+        if reloc.target.orig_section_index != Some(section.orig_index) {
+            continue;
+        }
+        let offset = reloc.target.section_address as i64 + reloc.addend;
+        if let Ok(offset) = usize::try_from(offset) {
+            *ref_counts.entry(offset).or_default() += 1;
+        }
+    }
+    let has_refs = !ref_counts.is_empty();
+    let mut strings = Vec::new();
+    let mut offset = 0;
+    for data in section.data.split_inclusive(|&b| b == 0) {
+        let end = offset + data.len();
+        let refs = ref_counts.range(offset..end).map(|(_, c)| c).sum::<usize>();
+        strings.push(SectionString { data, live: !has_refs || refs > 0 });
+        offset = end;
+    }
+    strings
+}
+
 pub fn diff_data_symbol(
     left_obj: &ObjInfo,
     right_obj: &ObjInfo,
@@ -151,6 +461,18 @@ pub fn diff_data_symbol(
         ..(left_symbol.section_address + left_symbol.size) as usize];
     let right_data = &right_section.data[right_symbol.section_address as usize
         ..(right_symbol.section_address + right_symbol.size) as usize];
+    let right_normalized;
+    let right_data = if left_obj.endianness != right_obj.endianness {
+        let words = known_words(
+            right_obj,
+            right_section,
+            right_symbol.section_address..right_symbol.section_address + right_symbol.size,
+        );
+        right_normalized = swap_words(right_data, &words);
+        right_normalized.as_slice()
+    } else {
+        right_data
+    };
 
     let ops = capture_diff_slices_deadline(Algorithm::Patience, left_data, right_data, None);
     let match_percent = get_diff_ratio(&ops, left_data.len(), right_data.len()) * 100.0;
@@ -161,12 +483,22 @@ pub fn diff_data_symbol(
             target_symbol: Some(right_symbol_ref),
             instructions: vec![],
             match_percent: Some(match_percent),
+            opcode_match_percent: None,
+            ambiguous_match: false,
+            too_different: false,
+            stack_frame: None,
+            quirk_count: 0,
         },
         ObjSymbolDiff {
             symbol_ref: right_symbol_ref,
             target_symbol: Some(left_symbol_ref),
             instructions: vec![],
             match_percent: Some(match_percent),
+            opcode_match_percent: None,
+            ambiguous_match: false,
+            too_different: false,
+            stack_frame: None,
+            quirk_count: 0,
         },
     ))
 }
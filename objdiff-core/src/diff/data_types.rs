@@ -0,0 +1,111 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use crate::{
+    arch::DataType,
+    diff::ObjDiff,
+    obj::{ObjInfo, ObjSectionKind, ObjSymbol, ObjSymbolKind, SymbolRef},
+};
+
+/// How a data symbol is accessed from code, as inferred from the instructions referencing it.
+#[derive(Debug, Clone, Default)]
+pub struct DataTypeSummary {
+    /// Number of references inferred as each type.
+    pub counts: BTreeMap<DataType, usize>,
+    /// Number of references whose type couldn't be inferred.
+    pub unknown: usize,
+}
+
+impl DataTypeSummary {
+    pub fn is_empty(&self) -> bool { self.counts.is_empty() && self.unknown == 0 }
+
+    /// Whether the symbol is accessed as more than one type, which often points to a
+    /// wrongly-typed variable.
+    pub fn is_mixed(&self) -> bool { self.counts.len() > 1 }
+
+    /// Types ordered by reference count, most common first.
+    pub fn sorted(&self) -> Vec<(DataType, usize)> {
+        let mut types = self.counts.iter().map(|(&ty, &count)| (ty, count)).collect::<Vec<_>>();
+        types.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        types
+    }
+}
+
+impl fmt::Display for DataTypeSummary {
+    /// Formats the summary as e.g. `f32 x3, u16 x1`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (ty, count) in self.sorted() {
+            if !first {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} x{}", ty.short_name(), count)?;
+            first = false;
+        }
+        if self.unknown > 0 {
+            if !first {
+                write!(f, ", ")?;
+            }
+            write!(f, "unknown x{}", self.unknown)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether a relocation target (plus addend) refers to `symbol`, either directly or through a
+/// section symbol of the same section.
+fn references_symbol(target: &ObjSymbol, addend: i64, symbol: &ObjSymbol) -> bool {
+    if target.kind == ObjSymbolKind::Section {
+        let address = target.address.wrapping_add_signed(addend);
+        target.orig_section_index == symbol.orig_section_index
+            && address >= symbol.address
+            && address < symbol.address + symbol.size.max(1)
+    } else {
+        target.name == symbol.name && target.address == symbol.address
+    }
+}
+
+/// Summarizes how each data symbol is accessed by every instruction in the object that
+/// references it, using the architecture's data type guess for each instruction.
+pub fn data_type_summaries(obj: &ObjInfo, diff: &ObjDiff) -> BTreeMap<SymbolRef, DataTypeSummary> {
+    let data_sections = obj
+        .sections
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| matches!(s.kind, ObjSectionKind::Data | ObjSectionKind::Bss))
+        .map(|(idx, s)| (s.orig_index, idx))
+        .collect::<HashMap<_, _>>();
+    let mut summaries = BTreeMap::<SymbolRef, DataTypeSummary>::new();
+    for (section, section_diff) in obj.sections.iter().zip(&diff.sections) {
+        if section.kind != ObjSectionKind::Code {
+            continue;
+        }
+        for symbol_diff in &section_diff.symbols {
+            for ins in symbol_diff.instructions.iter().filter_map(|i| i.ins.as_ref()) {
+                let Some(reloc) = &ins.reloc else {
+                    continue;
+                };
+                let Some(&section_idx) =
+                    reloc.target.orig_section_index.and_then(|i| data_sections.get(&i))
+                else {
+                    continue;
+                };
+                let ty = obj.arch.guess_data_type(ins);
+                for (symbol_idx, symbol) in obj.sections[section_idx].symbols.iter().enumerate() {
+                    if !references_symbol(&reloc.target, reloc.addend, symbol) {
+                        continue;
+                    }
+                    let summary =
+                        summaries.entry(SymbolRef { section_idx, symbol_idx }).or_default();
+                    match ty {
+                        Some(ty) => *summary.counts.entry(ty).or_default() += 1,
+                        None => summary.unknown += 1,
+                    }
+                }
+            }
+        }
+    }
+    summaries
+}
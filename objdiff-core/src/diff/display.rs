@@ -135,3 +135,80 @@ impl From<DiffText<'_>> for HighlightKind {
         }
     }
 }
+
+/// Case-insensitive text search over the rows of a function diff. Rows are matched against
+/// their displayed text (mnemonic, operands and relocation targets), as well as the mangled
+/// names of relocation targets shown on hover.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffTextSearch {
+    query: String,
+}
+
+impl DiffTextSearch {
+    /// Returns `None` for an empty query.
+    pub fn new(query: &str) -> Option<Self> {
+        let query = query.trim();
+        (!query.is_empty()).then(|| Self { query: query.to_lowercase() })
+    }
+
+    fn contains(&self, text: &str) -> bool { text.to_lowercase().contains(&self.query) }
+
+    /// Whether a single segment of a row matches, for highlighting.
+    pub fn matches_text(&self, text: DiffText) -> bool {
+        match text {
+            DiffText::Opcode(mnemonic, _) => self.contains(mnemonic),
+            DiffText::Argument(arg, _) => self.contains(&arg.to_string()),
+            DiffText::BranchDest(dest, _) => self.contains(&format!("{dest:x}")),
+            DiffText::Symbol(sym, _) => {
+                self.contains(&sym.name)
+                    || sym.demangled_name.as_deref().is_some_and(|name| self.contains(name))
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether an instruction row matches. The query may span several segments
+    /// (e.g. `lwz r3`).
+    pub fn matches_row(&self, ins_diff: &ObjInsDiff, base_addr: u64) -> bool {
+        let mut line = String::new();
+        let mut matched = false;
+        display_diff(ins_diff, base_addr, |text| {
+            matched = matched || self.matches_text(text);
+            match text {
+                DiffText::Basic(s) => line.push_str(s),
+                DiffText::Opcode(mnemonic, _) => line.push_str(mnemonic),
+                DiffText::Argument(arg, _) => line.push_str(&arg.to_string()),
+                DiffText::BranchDest(dest, _) => line.push_str(&format!("{dest:x}")),
+                DiffText::Symbol(sym, _) => {
+                    line.push_str(sym.demangled_name.as_deref().unwrap_or(&sym.name))
+                }
+                DiffText::Spacing(_) if !line.is_empty() => line.push(' '),
+                _ => {}
+            }
+            Ok::<_, ()>(())
+        })
+        .ok();
+        matched || self.contains(&line)
+    }
+
+    /// Returns the rows matching in any of the given columns, each a function's instruction
+    /// diffs and base address. Columns of a function diff share row indices.
+    pub fn find_rows<'a>(
+        &self,
+        columns: impl IntoIterator<Item = (&'a [ObjInsDiff], u64)>,
+    ) -> Vec<usize> {
+        let mut rows = Vec::new();
+        for (instructions, base_addr) in columns {
+            rows.extend(
+                instructions
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, ins_diff)| self.matches_row(ins_diff, base_addr))
+                    .map(|(row, _)| row),
+            );
+        }
+        rows.sort_unstable();
+        rows.dedup();
+        rows
+    }
+}
@@ -0,0 +1,178 @@
+use std::{collections::HashMap, ops::Range};
+
+use similar::{capture_diff_slices_deadline, Algorithm, DiffTag};
+
+use crate::obj::{ObjInfo, ObjSection};
+
+/// Section name prefixes of static initializer and finalizer arrays. Numbered variants (e.g.
+/// `.init_array.00100`) hold prioritized entries and are compared separately.
+const INITIALIZER_SECTIONS: [&str; 5] =
+    [".ctors", ".dtors", ".init_array", ".fini_array", ".preinit_array"];
+
+/// Whether the section is an array of initializer or finalizer function pointers.
+pub fn is_initializer_section(name: &str) -> bool {
+    INITIALIZER_SECTIONS.iter().any(|prefix| {
+        name.strip_prefix(prefix).is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    }) || name.starts_with(".CRT$XC")
+        || name.starts_with(".CRT$XI")
+}
+
+/// A single entry of an initializer array, resolved from its relocation.
+#[derive(Debug, Clone)]
+pub struct InitializerEntry {
+    /// Offset of the entry from the start of the section.
+    pub offset: u64,
+    /// The function called, with any addend.
+    pub name: String,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum InitializerDiffKind {
+    #[default]
+    None,
+    /// Present on both sides, but in a different position.
+    Reordered,
+    /// Only present in the target object.
+    Missing,
+    /// Only present in the base object.
+    Extra,
+}
+
+/// A row in a side-by-side diff of an initializer array.
+#[derive(Debug, Clone, Default)]
+pub struct InitializerDiffRow {
+    pub left: Option<InitializerEntry>,
+    pub right: Option<InitializerEntry>,
+    pub kind: InitializerDiffKind,
+}
+
+/// Comparison of an initializer array matched by name between both objects.
+#[derive(Debug, Clone)]
+pub struct InitializerArrayDiff {
+    pub name: String,
+    pub rows: Vec<InitializerDiffRow>,
+}
+
+impl InitializerArrayDiff {
+    pub fn has_differences(&self) -> bool {
+        self.rows.iter().any(|row| row.kind != InitializerDiffKind::None)
+    }
+
+    /// Number of rows of the given kind.
+    pub fn count(&self, kind: InitializerDiffKind) -> usize {
+        self.rows.iter().filter(|row| row.kind == kind).count()
+    }
+}
+
+fn initializer_entries(section: &ObjSection) -> Vec<InitializerEntry> {
+    let mut relocations = section.relocations.iter().collect::<Vec<_>>();
+    relocations.sort_by_key(|r| r.address);
+    relocations
+        .into_iter()
+        .map(|reloc| {
+            let name = reloc.target.demangled_name.as_deref().unwrap_or(&reloc.target.name);
+            let name = match reloc.addend {
+                0 => name.to_string(),
+                addend if addend > 0 => format!("{name}+{addend:#x}"),
+                addend => format!("{name}-{:#x}", -addend),
+            };
+            InitializerEntry { offset: reloc.address - section.address, name }
+        })
+        .collect()
+}
+
+fn initializer_sections(obj: &ObjInfo) -> impl Iterator<Item = &ObjSection> {
+    obj.sections.iter().filter(|s| is_initializer_section(&s.name))
+}
+
+/// Counts the entries of `entries` within the given ranges by name.
+fn count_names(
+    entries: &[InitializerEntry],
+    ranges: impl Iterator<Item = Range<usize>>,
+) -> HashMap<&str, usize> {
+    let mut counts = HashMap::new();
+    for range in ranges {
+        for entry in &entries[range] {
+            *counts.entry(entry.name.as_str()).or_default() += 1;
+        }
+    }
+    counts
+}
+
+fn diff_entries(left: &[InitializerEntry], right: &[InitializerEntry]) -> Vec<InitializerDiffRow> {
+    let left_names = left.iter().map(|e| e.name.as_str()).collect::<Vec<_>>();
+    let right_names = right.iter().map(|e| e.name.as_str()).collect::<Vec<_>>();
+    let ops = capture_diff_slices_deadline(Algorithm::Patience, &left_names, &right_names, None);
+    let ops = ops.iter().map(|op| op.as_tag_tuple()).collect::<Vec<_>>();
+
+    // Entries outside of the common sequence that are present on the other side were moved
+    let unmatched = || ops.iter().filter(|(tag, _, _)| *tag != DiffTag::Equal);
+    let mut left_unmatched = count_names(left, unmatched().map(|(_, l, _)| l.clone()));
+    let mut right_unmatched = count_names(right, unmatched().map(|(_, _, r)| r.clone()));
+
+    let mut rows = Vec::with_capacity(left.len().max(right.len()));
+    for (tag, left_range, right_range) in ops {
+        if tag == DiffTag::Equal {
+            for (l, r) in left_range.zip(right_range) {
+                rows.push(InitializerDiffRow {
+                    left: Some(left[l].clone()),
+                    right: Some(right[r].clone()),
+                    kind: InitializerDiffKind::None,
+                });
+            }
+            continue;
+        }
+        for entry in &left[left_range] {
+            let kind = match right_unmatched.get_mut(entry.name.as_str()) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    InitializerDiffKind::Reordered
+                }
+                _ => InitializerDiffKind::Missing,
+            };
+            rows.push(InitializerDiffRow { left: Some(entry.clone()), right: None, kind });
+        }
+        for entry in &right[right_range] {
+            let kind = match left_unmatched.get_mut(entry.name.as_str()) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    InitializerDiffKind::Reordered
+                }
+                _ => InitializerDiffKind::Extra,
+            };
+            rows.push(InitializerDiffRow { left: None, right: Some(entry.clone()), kind });
+        }
+    }
+    rows
+}
+
+/// Compares the sequence of functions in each initializer array (`.ctors`, `.init_array`, etc.)
+/// between both objects. Arrays are paired by section name; the order of these entries decides
+/// static initialization order, which isn't visible in the data diff.
+pub fn diff_initializer_arrays(
+    left: Option<&ObjInfo>,
+    right: Option<&ObjInfo>,
+) -> Vec<InitializerArrayDiff> {
+    let mut names = Vec::<&str>::new();
+    for obj in left.into_iter().chain(right) {
+        for section in initializer_sections(obj) {
+            if !names.contains(&section.name.as_str()) {
+                names.push(&section.name);
+            }
+        }
+    }
+    let entries = |obj: Option<&ObjInfo>, name: &str| {
+        obj.into_iter()
+            .flat_map(initializer_sections)
+            .filter(|s| s.name == name)
+            .flat_map(initializer_entries)
+            .collect::<Vec<_>>()
+    };
+    names
+        .into_iter()
+        .map(|name| InitializerArrayDiff {
+            name: name.to_string(),
+            rows: diff_entries(&entries(left, name), &entries(right, name)),
+        })
+        .collect()
+}
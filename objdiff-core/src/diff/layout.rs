@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use crate::{
+    diff::ObjDiff,
+    obj::{ObjInfo, ObjSectionKind, ObjSymbolKind, SymbolRef},
+};
+
+/// A symbol placed within a section's address space.
+#[derive(Debug, Clone)]
+pub struct LayoutSymbol {
+    pub symbol_ref: SymbolRef,
+    /// Offset from the start of the section.
+    pub offset: u64,
+    pub size: u64,
+    pub match_percent: Option<f32>,
+    /// Index of the matched symbol within the other side's [`SectionLayout::symbols`].
+    pub target: Option<usize>,
+}
+
+/// The symbol layout of a section on one side of the diff.
+#[derive(Debug, Clone)]
+pub struct SectionLayout {
+    pub section_idx: usize,
+    pub size: u64,
+    /// Symbols in address order.
+    pub symbols: Vec<LayoutSymbol>,
+}
+
+/// Layout comparison of a section matched by name between both objects.
+#[derive(Debug, Clone)]
+pub struct SectionLayoutDiff {
+    pub name: String,
+    pub kind: ObjSectionKind,
+    pub left: Option<SectionLayout>,
+    pub right: Option<SectionLayout>,
+}
+
+impl SectionLayoutDiff {
+    /// Size of the right section minus the size of the left section.
+    pub fn size_delta(&self) -> i64 {
+        let left = self.left.as_ref().map_or(0, |l| l.size);
+        let right = self.right.as_ref().map_or(0, |r| r.size);
+        right as i64 - left as i64
+    }
+
+    /// Whether matched symbols appear in a different order on the right side.
+    pub fn reordered(&self) -> bool {
+        let Some(left) = &self.left else {
+            return false;
+        };
+        let targets = left.symbols.iter().filter_map(|s| s.target).collect::<Vec<_>>();
+        targets.windows(2).any(|w| w[0] > w[1])
+    }
+
+    /// Number of matched symbols placed at a different offset on the right side.
+    pub fn moved_symbols(&self) -> usize {
+        let (Some(left), Some(right)) = (&self.left, &self.right) else {
+            return 0;
+        };
+        left.symbols
+            .iter()
+            .filter(|s| s.target.is_some_and(|t| right.symbols[t].offset != s.offset))
+            .count()
+    }
+}
+
+fn section_layout(obj: &ObjInfo, diff: &ObjDiff, section_idx: usize) -> SectionLayout {
+    let section = &obj.sections[section_idx];
+    let mut symbols = section
+        .symbols
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.kind != ObjSymbolKind::Section && s.size > 0)
+        .map(|(symbol_idx, s)| {
+            let symbol_ref = SymbolRef { section_idx, symbol_idx };
+            LayoutSymbol {
+                symbol_ref,
+                offset: s.section_address,
+                size: s.size,
+                match_percent: diff.symbol_diff(symbol_ref).match_percent,
+                target: None,
+            }
+        })
+        .collect::<Vec<_>>();
+    symbols.sort_by_key(|s| (s.offset, s.symbol_ref.symbol_idx));
+    SectionLayout { section_idx, size: section.size, symbols }
+}
+
+/// Links each left symbol to its matched symbol in the right layout, and vice versa.
+fn link_layouts(
+    left: &mut SectionLayout,
+    left_diff: &ObjDiff,
+    right: &mut SectionLayout,
+    right_diff: &ObjDiff,
+) {
+    let right_indices =
+        right.symbols.iter().enumerate().map(|(i, s)| (s.symbol_ref, i)).collect::<HashMap<_, _>>();
+    for (left_idx, symbol) in left.symbols.iter_mut().enumerate() {
+        let Some(target_ref) = left_diff.symbol_diff(symbol.symbol_ref).target_symbol else {
+            continue;
+        };
+        if let Some(&right_idx) = right_indices.get(&target_ref) {
+            symbol.target = Some(right_idx);
+            if right_diff.symbol_diff(target_ref).target_symbol == Some(symbol.symbol_ref) {
+                right.symbols[right_idx].target = Some(left_idx);
+            }
+        }
+    }
+}
+
+/// Compares the symbol layout of each section between both objects. Sections are paired by
+/// name and kind; sections present on only one side are included with the other side empty.
+pub fn diff_section_layouts(
+    left: Option<(&ObjInfo, &ObjDiff)>,
+    right: Option<(&ObjInfo, &ObjDiff)>,
+) -> Vec<SectionLayoutDiff> {
+    let mut result = Vec::new();
+    let mut right_used = vec![false; right.map_or(0, |(obj, _)| obj.sections.len())];
+    if let Some((left_obj, left_diff)) = left {
+        for (section_idx, section) in left_obj.sections.iter().enumerate() {
+            let mut left_layout = section_layout(left_obj, left_diff, section_idx);
+            let mut right_layout = None;
+            if let Some((right_obj, right_diff)) = right {
+                let right_idx = right_obj
+                    .sections
+                    .iter()
+                    .enumerate()
+                    .find(|&(i, s)| {
+                        !right_used[i] && s.kind == section.kind && s.name == section.name
+                    })
+                    .map(|(i, _)| i);
+                if let Some(right_idx) = right_idx {
+                    right_used[right_idx] = true;
+                    let mut layout = section_layout(right_obj, right_diff, right_idx);
+                    link_layouts(&mut left_layout, left_diff, &mut layout, right_diff);
+                    right_layout = Some(layout);
+                }
+            }
+            result.push(SectionLayoutDiff {
+                name: section.name.clone(),
+                kind: section.kind,
+                left: Some(left_layout),
+                right: right_layout,
+            });
+        }
+    }
+    if let Some((right_obj, right_diff)) = right {
+        for (section_idx, section) in right_obj.sections.iter().enumerate() {
+            if right_used[section_idx] {
+                continue;
+            }
+            result.push(SectionLayoutDiff {
+                name: section.name.clone(),
+                kind: section.kind,
+                left: None,
+                right: Some(section_layout(right_obj, right_diff, section_idx)),
+            });
+        }
+    }
+    result
+}
@@ -1,22 +1,38 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use anyhow::Result;
+use globset::{Glob, GlobSet};
 
 use crate::{
-    config::SymbolMappings,
+    arch::StackFrame,
+    config::{build_globset, CodegenQuirk, RelocRename, SymbolMappings, SymbolMatching},
     diff::{
-        code::{diff_code, no_diff_code, process_code_symbol},
+        code::{
+            diff_code, fixed_code_sizes, no_diff_code, process_code_range, process_code_symbol,
+        },
         data::{
             diff_bss_section, diff_bss_symbol, diff_data_section, diff_data_symbol,
-            diff_generic_section, no_diff_symbol,
+            diff_generic_section, diff_string_section, no_diff_symbol,
         },
+        data_types::{data_type_summaries, DataTypeSummary},
+    },
+    obj::{
+        ObjInfo, ObjIns, ObjSection, ObjSectionKind, ObjSymbol, ObjSymbolFlags, SymbolRef,
+        SECTION_COMMON,
     },
-    obj::{ObjInfo, ObjIns, ObjSection, ObjSectionKind, ObjSymbol, SymbolRef, SECTION_COMMON},
+    util::{CancellationToken, Stopwatch, Timings},
 };
 
 pub mod code;
 pub mod data;
+pub mod data_types;
 pub mod display;
+pub mod initializers;
+pub mod layout;
+pub mod order;
+pub mod properties;
+pub mod quirks;
+pub mod unwind;
 
 #[derive(
     Debug,
@@ -68,6 +84,30 @@ pub enum MipsAbi {
     N64,
 }
 
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    Default,
+    Eq,
+    PartialEq,
+    serde::Deserialize,
+    serde::Serialize,
+    strum::VariantArray,
+    strum::EnumMessage,
+)]
+#[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
+pub enum MipsFpRegisterName {
+    #[default]
+    #[strum(
+        message = "$fp (default)",
+        detailed_message = "Name register 30 as the frame pointer."
+    )]
+    Fp,
+    #[strum(message = "$s8", detailed_message = "Name register 30 as saved register 8.")]
+    S8,
+}
+
 #[derive(
     Debug,
     Copy,
@@ -151,25 +191,104 @@ pub enum ArmR9Usage {
     Tr,
 }
 
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    Default,
+    Eq,
+    PartialEq,
+    serde::Deserialize,
+    serde::Serialize,
+    strum::VariantArray,
+    strum::EnumMessage,
+)]
+#[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
+pub enum PpcCrFieldMismatch {
+    #[default]
+    #[strum(
+        message = "Mismatch (default)",
+        detailed_message = "Instructions using a different condition register field differ."
+    )]
+    Mismatch,
+    #[strum(
+        message = "Soft mismatch",
+        detailed_message = "Highlight instructions differing only in condition register field \
+        separately, but count them as mismatched."
+    )]
+    Soft,
+    #[strum(
+        message = "Ignore",
+        detailed_message = "Highlight instructions differing only in condition register field \
+        separately, and count them as matched."
+    )]
+    Ignore,
+}
+
 #[inline]
 const fn default_true() -> bool { true }
 
+pub const DEFAULT_MAX_ALIGNMENT_EDITS: u32 = 1000;
+
 #[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 #[cfg_attr(feature = "wasm", derive(tsify_next::Tsify))]
 #[cfg_attr(feature = "wasm", tsify(from_wasm_abi))]
 #[serde(default)]
 pub struct DiffObjConfig {
     pub relax_reloc_diffs: bool,
+    /// Count instructions that differ only in pseudo relocations, inferred by flow analysis
+    /// rather than read from the object, as matching. They're still shown as mismatched
+    pub ignore_pseudo_reloc_diffs: bool,
     #[serde(default = "default_true")]
     pub space_between_args: bool,
     pub combine_data_sections: bool,
     #[serde(default)]
     pub symbol_mappings: MappingConfig,
+    pub ignore_codegen_quirks: bool,
+    pub symbol_matching: SymbolMatching,
+    /// Project-specific quirk patterns, in addition to the built-in ones
+    pub codegen_quirks: Vec<CodegenQuirk>,
+    /// decomp.me compiler ID of the unit (its scratch `compiler`), used to select codegen quirks
+    pub compiler: Option<String>,
+    /// Project-specific rewrites of relocation target names, applied when loading objects
+    pub reloc_renames: Vec<RelocRename>,
+    /// Maximum number of inserted or deleted instructions when aligning large functions.
+    /// Functions that differ by more are paired by position instead. 0 disables the limit.
+    pub max_alignment_edits: u32,
+    /// Strip ELF symbol versions (`memcpy@GLIBC_2.2.5`, `foo@@VERS_1`) from symbol names
+    #[serde(default = "default_true")]
+    pub strip_symbol_versions: bool,
+    /// Hide assembler-local labels (`.L*`, `$x`) from symbol lists and matching, resolving
+    /// relocations against them to the enclosing symbol instead
+    #[serde(default = "default_true")]
+    pub filter_local_labels: bool,
+    /// Hide local symbols from symbol lists, matching and reports. Relocations against them
+    /// still resolve to their names
+    pub global_symbols_only: bool,
+    /// Exclude zeroed alignment padding between data symbols from the data match percent
+    pub ignore_data_padding: bool,
+    /// Pair symbols whose mangled names differ but demangle to the same signature
+    #[serde(default = "default_true")]
+    pub demangled_name_matching: bool,
     // x86
     pub x86_formatter: X86Formatter,
     // MIPS
     pub mips_abi: MipsAbi,
     pub mips_instr_category: MipsInstrCategory,
+    /// Display pseudo-instructions such as `move`, `beqz` and `b` instead of their raw forms
+    #[serde(default = "default_true")]
+    pub mips_pseudo_instructions: bool,
+    /// How to name register 30, which is either the frame pointer or a saved register
+    pub mips_fp_register_name: MipsFpRegisterName,
+    // PowerPC
+    /// How to treat instructions that differ only in condition register field (e.g. cr0 vs cr7)
+    pub ppc_cr_field_mismatch: PpcCrFieldMismatch,
+    /// Annotate paired-single loads and stores with the type and scale of their GQR, using
+    /// the project's [`ppc_gqrs`](Self::ppc_gqrs)
+    #[serde(default = "default_true")]
+    pub ppc_gqr_annotations: bool,
+    /// Project-supplied values of GQR0-GQR7, as set up at runtime
+    pub ppc_gqrs: Vec<u32>,
     // ARM
     pub arm_arch_version: ArmArchVersion,
     pub arm_unified_syntax: bool,
@@ -184,12 +303,29 @@ impl Default for DiffObjConfig {
     fn default() -> Self {
         Self {
             relax_reloc_diffs: false,
+            ignore_pseudo_reloc_diffs: false,
             space_between_args: true,
             combine_data_sections: false,
             symbol_mappings: Default::default(),
+            ignore_codegen_quirks: false,
+            symbol_matching: Default::default(),
+            codegen_quirks: vec![],
+            compiler: None,
+            reloc_renames: vec![],
+            max_alignment_edits: DEFAULT_MAX_ALIGNMENT_EDITS,
+            strip_symbol_versions: true,
+            filter_local_labels: true,
+            global_symbols_only: false,
+            ignore_data_padding: false,
+            demangled_name_matching: true,
             x86_formatter: Default::default(),
             mips_abi: Default::default(),
             mips_instr_category: Default::default(),
+            mips_pseudo_instructions: true,
+            mips_fp_register_name: Default::default(),
+            ppc_cr_field_mismatch: Default::default(),
+            ppc_gqr_annotations: true,
+            ppc_gqrs: vec![],
             arm_arch_version: Default::default(),
             arm_unified_syntax: true,
             arm_av_registers: false,
@@ -234,6 +370,20 @@ pub struct ObjSymbolDiff {
     pub target_symbol: Option<SymbolRef>,
     pub instructions: Vec<ObjInsDiff>,
     pub match_percent: Option<f32>,
+    /// Percentage of aligned instructions with the same opcode, ignoring their arguments. A
+    /// rough measure of how close a mismatched function's structure is.
+    pub opcode_match_percent: Option<f32>,
+    /// Whether the symbol was matched by name while other symbols shared that name,
+    /// so the pairing was made by section and ordinal
+    pub ambiguous_match: bool,
+    /// Whether the instructions differ by more than [`DiffObjConfig::max_alignment_edits`],
+    /// so they were paired by position instead of aligned
+    pub too_different: bool,
+    /// The stack frame set up by the function's prologue, if the arch can parse it
+    pub stack_frame: Option<StackFrame>,
+    /// Rows differing only by a known codegen quirk, which count as matching in the match
+    /// percent
+    pub quirk_count: usize,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -258,6 +408,11 @@ pub enum ObjInsDiffKind {
     Replace,
     Delete,
     Insert,
+    /// Differs only by a known benign codegen quirk
+    Quirk,
+    /// Differs only in arguments that don't change behavior, such as the PPC condition
+    /// register field. See [`ObjArch::is_soft_arg`](crate::arch::ObjArch::is_soft_arg).
+    SoftMismatch,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -266,6 +421,8 @@ pub struct ObjDataDiff {
     pub kind: ObjDataDiffKind,
     pub len: usize,
     pub symbol: String,
+    /// Alignment padding between symbols, see [`DiffObjConfig::ignore_data_padding`]
+    pub padding: bool,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
@@ -279,7 +436,7 @@ pub enum ObjDataDiffKind {
 
 #[derive(Debug, Copy, Clone)]
 pub struct ObjInsArgDiff {
-    /// Incrementing index for coloring
+    /// Incrementing index for coloring, shared by both sides of a differing operand pair
     pub idx: usize,
 }
 
@@ -308,6 +465,8 @@ pub struct ObjDiff {
     /// If `selecting_left` or `selecting_right` is set, this is the list of symbols
     /// that are being mapped to the other object.
     pub mapping_symbols: Vec<ObjSymbolDiff>,
+    /// How each data symbol is accessed from code, see [`data_types::data_type_summaries`].
+    pub data_types: BTreeMap<SymbolRef, DataTypeSummary>,
 }
 
 impl ObjDiff {
@@ -316,6 +475,7 @@ impl ObjDiff {
             sections: Vec::with_capacity(obj.sections.len()),
             common: Vec::with_capacity(obj.common.len()),
             mapping_symbols: vec![],
+            data_types: BTreeMap::new(),
         };
         for (section_idx, section) in obj.sections.iter().enumerate() {
             let mut symbols = Vec::with_capacity(section.symbols.len());
@@ -325,6 +485,11 @@ impl ObjDiff {
                     target_symbol: None,
                     instructions: vec![],
                     match_percent: None,
+                    opcode_match_percent: None,
+                    ambiguous_match: false,
+                    too_different: false,
+                    stack_frame: None,
+                    quirk_count: 0,
                 });
             }
             result.sections.push(ObjSectionDiff {
@@ -334,6 +499,7 @@ impl ObjDiff {
                     kind: ObjDataDiffKind::None,
                     len: section.data.len(),
                     symbol: section.name.clone(),
+                    padding: false,
                 }],
                 match_percent: None,
             });
@@ -344,6 +510,11 @@ impl ObjDiff {
                 target_symbol: None,
                 instructions: vec![],
                 match_percent: None,
+                opcode_match_percent: None,
+                ambiguous_match: false,
+                too_different: false,
+                stack_frame: None,
+                quirk_count: 0,
             });
         }
         result
@@ -383,6 +554,7 @@ pub struct DiffObjsResult {
     pub left: Option<ObjDiff>,
     pub right: Option<ObjDiff>,
     pub prev: Option<ObjDiff>,
+    pub timings: Timings,
 }
 
 pub fn diff_objs(
@@ -391,26 +563,73 @@ pub fn diff_objs(
     right: Option<&ObjInfo>,
     prev: Option<&ObjInfo>,
 ) -> Result<DiffObjsResult> {
-    let symbol_matches = matching_symbols(left, right, prev, &config.symbol_mappings)?;
-    let section_matches = matching_sections(left, right)?;
+    diff_objs_with_progress(config, left, right, prev, &CancellationToken::default(), |_, _| {})
+}
+
+/// Like [`diff_objs`], but checks `cancel` before diffing each symbol, stopping early with a
+/// [`Cancelled`](crate::util::Cancelled) error. `progress` is called with the number of
+/// processed and total symbol matches.
+pub fn diff_objs_with_progress(
+    config: &DiffObjConfig,
+    left: Option<&ObjInfo>,
+    right: Option<&ObjInfo>,
+    prev: Option<&ObjInfo>,
+    cancel: &CancellationToken,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<DiffObjsResult> {
+    let mut timings = Timings::default();
+    let (symbol_matches, section_matches) = timings.time("Symbol matching", || {
+        let symbol_matches = matching_symbols(
+            left,
+            right,
+            prev,
+            &config.symbol_mappings,
+            config.symbol_matching,
+            config.demangled_name_matching,
+            config.global_symbols_only,
+        )?;
+        Ok::<_, anyhow::Error>((symbol_matches, matching_sections(left, right)?))
+    })?;
     let mut left = left.map(|p| (p, ObjDiff::new_from_obj(p)));
     let mut right = right.map(|p| (p, ObjDiff::new_from_obj(p)));
     let mut prev = prev.map(|p| (p, ObjDiff::new_from_obj(p)));
 
-    for symbol_match in symbol_matches {
+    let total = symbol_matches.len();
+    for (idx, symbol_match) in symbol_matches.into_iter().enumerate() {
+        cancel.check()?;
+        progress(idx, total);
+        let start = Stopwatch::start();
+        let stage = match symbol_match.section_kind {
+            ObjSectionKind::Code => "Code diff",
+            ObjSectionKind::Data | ObjSectionKind::Bss => "Data diff",
+        };
         match symbol_match {
             SymbolMatch {
                 left: Some(left_symbol_ref),
                 right: Some(right_symbol_ref),
                 prev: prev_symbol_ref,
                 section_kind,
+                ambiguous,
             } => {
                 let (left_obj, left_out) = left.as_mut().unwrap();
                 let (right_obj, right_out) = right.as_mut().unwrap();
                 match section_kind {
                     ObjSectionKind::Code => {
-                        let left_code = process_code_symbol(left_obj, left_symbol_ref, config)?;
-                        let right_code = process_code_symbol(right_obj, right_symbol_ref, config)?;
+                        let (left_size, right_size) = fixed_code_sizes(
+                            left_obj,
+                            left_symbol_ref,
+                            right_obj,
+                            right_symbol_ref,
+                            config
+                                .symbol_mappings
+                                .size_fixes
+                                .get(&left_obj.section_symbol(left_symbol_ref).1.name)
+                                .copied(),
+                        );
+                        let left_code =
+                            process_code_range(left_obj, left_symbol_ref, left_size, config)?;
+                        let right_code =
+                            process_code_range(right_obj, right_symbol_ref, right_size, config)?;
                         let (left_diff, right_diff) = diff_code(
                             left_obj,
                             right_obj,
@@ -459,14 +678,18 @@ pub fn diff_objs(
                         *right_out.symbol_diff_mut(right_symbol_ref) = right_diff;
                     }
                 }
+                if ambiguous {
+                    left_out.symbol_diff_mut(left_symbol_ref).ambiguous_match = true;
+                    right_out.symbol_diff_mut(right_symbol_ref).ambiguous_match = true;
+                }
             }
-            SymbolMatch { left: Some(left_symbol_ref), right: None, prev: _, section_kind } => {
+            SymbolMatch { left: Some(left_symbol_ref), right: None, section_kind, .. } => {
                 let (left_obj, left_out) = left.as_mut().unwrap();
                 match section_kind {
                     ObjSectionKind::Code => {
                         let code = process_code_symbol(left_obj, left_symbol_ref, config)?;
                         *left_out.symbol_diff_mut(left_symbol_ref) =
-                            no_diff_code(&code, left_symbol_ref)?;
+                            no_diff_code(left_obj, &code, left_symbol_ref)?;
                     }
                     ObjSectionKind::Data | ObjSectionKind::Bss => {
                         *left_out.symbol_diff_mut(left_symbol_ref) =
@@ -474,13 +697,13 @@ pub fn diff_objs(
                     }
                 }
             }
-            SymbolMatch { left: None, right: Some(right_symbol_ref), prev: _, section_kind } => {
+            SymbolMatch { left: None, right: Some(right_symbol_ref), section_kind, .. } => {
                 let (right_obj, right_out) = right.as_mut().unwrap();
                 match section_kind {
                     ObjSectionKind::Code => {
                         let code = process_code_symbol(right_obj, right_symbol_ref, config)?;
                         *right_out.symbol_diff_mut(right_symbol_ref) =
-                            no_diff_code(&code, right_symbol_ref)?;
+                            no_diff_code(right_obj, &code, right_symbol_ref)?;
                     }
                     ObjSectionKind::Data | ObjSectionKind::Bss => {
                         *right_out.symbol_diff_mut(right_symbol_ref) =
@@ -492,9 +715,14 @@ pub fn diff_objs(
                 // Should not happen
             }
         }
+        timings.add(stage, start.elapsed());
     }
 
+    progress(total, total);
+
+    let start = Stopwatch::start();
     for section_match in section_matches {
+        cancel.check()?;
         if let SectionMatch {
             left: Some(left_section_idx),
             right: Some(right_section_idx),
@@ -521,12 +749,27 @@ pub fn diff_objs(
                 ObjSectionKind::Data => {
                     let left_section_diff = left_out.section_diff(left_section_idx);
                     let right_section_diff = right_out.section_diff(right_section_idx);
-                    let (left_diff, right_diff) = diff_data_section(
-                        left_section,
-                        right_section,
-                        left_section_diff,
-                        right_section_diff,
-                    )?;
+                    let (left_diff, right_diff) =
+                        if left_section.merge_strings || right_section.merge_strings {
+                            diff_string_section(
+                                left_obj,
+                                right_obj,
+                                left_section_idx,
+                                right_section_idx,
+                                left_section_diff,
+                                right_section_diff,
+                            )?
+                        } else {
+                            diff_data_section(
+                                left_obj,
+                                right_obj,
+                                left_section_idx,
+                                right_section_idx,
+                                left_section_diff,
+                                right_section_diff,
+                                config,
+                            )?
+                        };
                     left_out.section_diff_mut(left_section_idx).merge(left_diff);
                     right_out.section_diff_mut(right_section_idx).merge(right_diff);
                 }
@@ -546,6 +789,15 @@ pub fn diff_objs(
         }
     }
 
+    timings.add("Section diff", start.elapsed());
+
+    let start = Stopwatch::start();
+    for (obj, out) in [left.as_mut(), right.as_mut()].into_iter().flatten() {
+        out.data_types = data_type_summaries(obj, out);
+    }
+    timings.add("Data types", start.elapsed());
+
+    let start = Stopwatch::start();
     if let (Some((right_obj, right_out)), Some((left_obj, left_out))) =
         (right.as_mut(), left.as_mut())
     {
@@ -555,12 +807,19 @@ pub fn diff_objs(
         if let Some(left_name) = &config.symbol_mappings.selecting_right {
             generate_mapping_symbols(left_obj, left_name, right_obj, right_out, config)?;
         }
+        if config.symbol_mappings.selecting_left.is_some()
+            || config.symbol_mappings.selecting_right.is_some()
+        {
+            timings.add("Mapping candidates", start.elapsed());
+        }
     }
+    timings.log();
 
     Ok(DiffObjsResult {
         left: left.map(|(_, o)| o),
         right: right.map(|(_, o)| o),
         prev: prev.map(|(_, o)| o),
+        timings,
     })
 }
 
@@ -585,10 +844,15 @@ fn generate_mapping_symbols(
         ObjSectionKind::Code => Some(process_code_symbol(base_obj, base_symbol_ref, config)?),
         _ => None,
     };
+    let include = build_globset(&config.symbol_mappings.candidate_include)?;
+    let exclude = build_globset(&config.symbol_mappings.candidate_exclude)?;
     for (target_section_index, target_section) in
         target_obj.sections.iter().enumerate().filter(|(_, s)| s.kind == base_section.kind)
     {
-        for (target_symbol_index, _target_symbol) in target_section.symbols.iter().enumerate() {
+        for (target_symbol_index, target_symbol) in target_section.symbols.iter().enumerate() {
+            if !is_mapping_candidate(target_symbol, &include, &exclude) {
+                continue;
+            }
             let target_symbol_ref =
                 SymbolRef { section_idx: target_section_index, symbol_idx: target_symbol_index };
             match base_section.kind {
@@ -621,12 +885,21 @@ fn generate_mapping_symbols(
     Ok(())
 }
 
+/// Whether a symbol passes the configured candidate patterns, by either its name or its
+/// demangled name.
+fn is_mapping_candidate(symbol: &ObjSymbol, include: &GlobSet, exclude: &GlobSet) -> bool {
+    let names = || std::iter::once(&symbol.name).chain(symbol.demangled_name.as_ref());
+    (include.is_empty() || names().any(|n| include.is_match(n)))
+        && !names().any(|n| exclude.is_match(n))
+}
+
 #[derive(Copy, Clone, Eq, PartialEq)]
 struct SymbolMatch {
     left: Option<SymbolRef>,
     right: Option<SymbolRef>,
     prev: Option<SymbolRef>,
     section_kind: ObjSectionKind,
+    ambiguous: bool,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -644,8 +917,49 @@ pub struct MappingConfig {
     pub selecting_left: Option<String>,
     /// The left object symbol name that we're selecting a right symbol for
     pub selecting_right: Option<String>,
+    /// If not empty, only symbols matching these name patterns are candidates while selecting
+    #[serde(default)]
+    pub candidate_include: Vec<Glob>,
+    /// Symbol name patterns that are never candidates while selecting
+    #[serde(default)]
+    pub candidate_exclude: Vec<Glob>,
+    /// Manual instruction alignment constraints, keyed by left symbol name
+    #[serde(default)]
+    pub alignment_anchors: AlignmentAnchors,
+    /// How functions whose size differs between both sides are diffed, keyed by left symbol
+    /// name
+    #[serde(default)]
+    pub size_fixes: SizeMismatchFixes,
 }
 
+/// A manual alignment constraint within a function: the left instruction at offset `left` is
+/// shown on the same row as the right instruction at offset `right`. Offsets are relative to the
+/// start of each symbol.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Deserialize, serde::Serialize,
+)]
+pub struct AlignmentAnchor {
+    pub left: u64,
+    pub right: u64,
+}
+
+/// Alignment anchors of each function, keyed by left symbol name.
+pub type AlignmentAnchors = BTreeMap<String, Vec<AlignmentAnchor>>;
+
+/// A way to diff a function whose size differs between both sides, instead of showing the
+/// extra instructions as inserted or deleted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeMismatchFix {
+    /// Only diff the range both sides have in common.
+    Overlap,
+    /// Extend the shorter side towards the longer side's size, up to the next symbol.
+    Extend,
+}
+
+/// Size mismatch fixes of each function, keyed by left symbol name.
+pub type SizeMismatchFixes = BTreeMap<String, SizeMismatchFix>;
+
 fn symbol_ref_by_name(obj: &ObjInfo, name: &str) -> Option<SymbolRef> {
     for (section_idx, section) in obj.sections.iter().enumerate() {
         for (symbol_idx, symbol) in section.symbols.iter().enumerate() {
@@ -709,6 +1023,7 @@ fn apply_symbol_mappings(
             right: Some(right_symbol),
             prev: None, // TODO
             section_kind: left_section.kind,
+            ambiguous: false,
         });
         left_used.insert(left_symbol);
         right_used.insert(right_symbol);
@@ -716,17 +1031,153 @@ fn apply_symbol_mappings(
     Ok(())
 }
 
+/// Whether the symbol name looks compiler-generated for a literal or anonymous data object
+/// (e.g. `@stringBase0`, `@123`, `.LC0`, `.str.1`). These names depend on the order in which
+/// the compiler emitted them, so they often differ between otherwise matching objects.
+pub(crate) fn is_literal_symbol_name(name: &str) -> bool {
+    fn numbered(rest: &str) -> bool { !rest.is_empty() && rest.bytes().all(|b| b.is_ascii_digit()) }
+    name.starts_with("@stringBase")
+        || name.strip_prefix('@').is_some_and(numbered)
+        || name.strip_prefix(".LC").or_else(|| name.strip_prefix("$LC")).is_some_and(numbered)
+        || matches!(name, ".str" | ".L.str")
+        || name.strip_prefix(".str.").or_else(|| name.strip_prefix(".L.str.")).is_some_and(numbered)
+}
+
+/// The relocations within a symbol, relative to its start. Targets that are literals
+/// themselves are compared by shape only, since their names aren't stable either.
+fn literal_relocations<'a>(
+    section: &'a ObjSection,
+    symbol: &'a ObjSymbol,
+) -> impl Iterator<Item = (u64, object::RelocationFlags, Option<&'a str>, i64)> + 'a {
+    section
+        .relocations
+        .iter()
+        .filter(move |r| r.address >= symbol.address && r.address < symbol.address + symbol.size)
+        .map(move |r| {
+            let target =
+                (!is_literal_symbol_name(&r.target.name)).then_some(r.target.name.as_str());
+            (r.address - symbol.address, r.flags, target, r.addend)
+        })
+}
+
+/// Pairs compiler-generated literal symbols by content (bytes and relocations) before falling
+/// back to name matching. Symbols that also share a name are paired first, then symbols within
+/// sections of the same name, in symbol order.
+fn match_literal_symbols(
+    left: &SymbolIndex,
+    right: &ObjInfo,
+    prev: Option<&SymbolIndex>,
+    left_used: &mut HashSet<SymbolRef>,
+    right_used: &mut HashSet<SymbolRef>,
+    matches: &mut Vec<SymbolMatch>,
+) {
+    let literals = |obj: &ObjInfo, used: &HashSet<SymbolRef>| {
+        let mut literals = Vec::new();
+        for (section_idx, section) in obj.sections.iter().enumerate() {
+            if section.kind != ObjSectionKind::Data {
+                continue;
+            }
+            for (symbol_idx, symbol) in section.symbols.iter().enumerate() {
+                let symbol_ref = SymbolRef { section_idx, symbol_idx };
+                if !used.contains(&symbol_ref)
+                    && !symbol.bytes.is_empty()
+                    && is_literal_symbol_name(&symbol.name)
+                {
+                    literals.push(symbol_ref);
+                }
+            }
+        }
+        literals
+    };
+    let left_literals = literals(left.obj, left_used);
+    if left_literals.is_empty() {
+        return;
+    }
+    let mut right_by_bytes = HashMap::<&[u8], Vec<SymbolRef>>::new();
+    for symbol_ref in literals(right, right_used) {
+        let (_, symbol) = right.section_symbol(symbol_ref);
+        right_by_bytes.entry(symbol.bytes.as_slice()).or_default().push(symbol_ref);
+    }
+    if right_by_bytes.is_empty() {
+        return;
+    }
+    for pass in 0..3 {
+        for &left_ref in &left_literals {
+            if left_used.contains(&left_ref) {
+                continue;
+            }
+            let (Some(left_section), left_symbol) = left.obj.section_symbol(left_ref) else {
+                continue;
+            };
+            let Some(candidates) = right_by_bytes.get(left_symbol.bytes.as_slice()) else {
+                continue;
+            };
+            let right_ref = candidates.iter().copied().find(|right_ref| {
+                if right_used.contains(right_ref) {
+                    return false;
+                }
+                let right_section = &right.sections[right_ref.section_idx];
+                let right_symbol = &right_section.symbols[right_ref.symbol_idx];
+                let preferred = match pass {
+                    0 => right_symbol.name == left_symbol.name,
+                    1 => right_section.name == left_section.name,
+                    _ => true,
+                };
+                preferred
+                    && literal_relocations(left_section, left_symbol)
+                        .eq(literal_relocations(right_section, right_symbol))
+            });
+            let Some(right_ref) = right_ref else {
+                continue;
+            };
+            matches.push(SymbolMatch {
+                left: Some(left_ref),
+                right: Some(right_ref),
+                prev: find_symbol(prev, left, left_ref, None),
+                section_kind: ObjSectionKind::Data,
+                ambiguous: false,
+            });
+            left_used.insert(left_ref);
+            right_used.insert(right_ref);
+        }
+    }
+}
+
 /// Find matching symbols between each object.
 fn matching_symbols(
     left: Option<&ObjInfo>,
     right: Option<&ObjInfo>,
     prev: Option<&ObjInfo>,
     mappings: &MappingConfig,
+    matching: SymbolMatching,
+    demangled_name_matching: bool,
+    global_symbols_only: bool,
 ) -> Result<Vec<SymbolMatch>> {
+    let left_index = left.map(|obj| SymbolIndex::new(obj, demangled_name_matching));
+    let right_index = right.map(|obj| SymbolIndex::new(obj, demangled_name_matching));
+    let prev_index = prev.map(|obj| SymbolIndex::new(obj, demangled_name_matching));
+    let find_match = |obj: Option<&SymbolIndex>,
+                      in_obj: &SymbolIndex,
+                      in_ref: SymbolRef,
+                      used: Option<&HashSet<SymbolRef>>| {
+        // Local symbols are only paired by mappings and literal matching
+        if global_symbols_only
+            && in_obj.obj.section_symbol(in_ref).1.flags.0.contains(ObjSymbolFlags::Local)
+        {
+            return None;
+        }
+        match matching {
+            SymbolMatching::Name => find_symbol(obj, in_obj, in_ref, used),
+            SymbolMatching::Address => {
+                find_symbol_by_address(obj.map(|i| i.obj), in_obj.obj, in_ref, used)
+            }
+        }
+    };
     let mut matches = Vec::new();
     let mut left_used = HashSet::new();
     let mut right_used = HashSet::new();
-    if let Some(left) = left {
+    if let Some(left_index) = &left_index {
+        let left = left_index.obj;
         if let Some(right) = right {
             apply_symbol_mappings(
                 left,
@@ -736,6 +1187,16 @@ fn matching_symbols(
                 &mut right_used,
                 &mut matches,
             )?;
+            if matching == SymbolMatching::Name {
+                match_literal_symbols(
+                    left_index,
+                    right,
+                    prev_index.as_ref(),
+                    &mut left_used,
+                    &mut right_used,
+                    &mut matches,
+                );
+            }
         }
         for (section_idx, section) in left.sections.iter().enumerate() {
             for (symbol_idx, symbol) in section.symbols.iter().enumerate() {
@@ -743,11 +1204,19 @@ fn matching_symbols(
                 if left_used.contains(&symbol_ref) {
                     continue;
                 }
+                let right_match =
+                    find_match(right_index.as_ref(), left_index, symbol_ref, Some(&right_used));
                 let symbol_match = SymbolMatch {
                     left: Some(symbol_ref),
-                    right: find_symbol(right, symbol, section, Some(&right_used)),
-                    prev: find_symbol(prev, symbol, section, None),
+                    right: right_match,
+                    prev: find_match(prev_index.as_ref(), left_index, symbol_ref, None),
                     section_kind: section.kind,
+                    ambiguous: matching == SymbolMatching::Name
+                        && right_match.is_some()
+                        && (left_index.is_duplicate_name(section.kind, &symbol.name)
+                            || right_index.as_ref().is_some_and(|right| {
+                                right.is_duplicate_name(section.kind, &symbol.name)
+                            })),
                 };
                 matches.push(symbol_match);
                 if let Some(right) = symbol_match.right {
@@ -765,6 +1234,7 @@ fn matching_symbols(
                 right: find_common_symbol(right, symbol),
                 prev: find_common_symbol(prev, symbol),
                 section_kind: ObjSectionKind::Bss,
+                ambiguous: false,
             };
             matches.push(symbol_match);
             if let Some(right) = symbol_match.right {
@@ -772,9 +1242,10 @@ fn matching_symbols(
             }
         }
     }
-    if let Some(right) = right {
+    if let Some(right_index) = &right_index {
+        let right = right_index.obj;
         for (section_idx, section) in right.sections.iter().enumerate() {
-            for (symbol_idx, symbol) in section.symbols.iter().enumerate() {
+            for symbol_idx in 0..section.symbols.len() {
                 let symbol_ref = SymbolRef { section_idx, symbol_idx };
                 if right_used.contains(&symbol_ref) {
                     continue;
@@ -782,8 +1253,9 @@ fn matching_symbols(
                 matches.push(SymbolMatch {
                     left: None,
                     right: Some(symbol_ref),
-                    prev: find_symbol(prev, symbol, section, None),
+                    prev: find_match(prev_index.as_ref(), right_index, symbol_ref, None),
                     section_kind: section.kind,
+                    ambiguous: false,
                 });
             }
         }
@@ -797,6 +1269,7 @@ fn matching_symbols(
                 right: Some(symbol_ref),
                 prev: find_common_symbol(prev, symbol),
                 section_kind: ObjSectionKind::Bss,
+                ambiguous: false,
             });
         }
     }
@@ -817,22 +1290,134 @@ where
     })
 }
 
-fn find_symbol(
+/// Finds an unmatched symbol at the same address within a section of the same name.
+fn find_symbol_by_address(
     obj: Option<&ObjInfo>,
-    in_symbol: &ObjSymbol,
-    in_section: &ObjSection,
+    in_obj: &ObjInfo,
+    in_ref: SymbolRef,
     used: Option<&HashSet<SymbolRef>>,
 ) -> Option<SymbolRef> {
     let obj = obj?;
-    // Try to find an exact name match
-    for (section_idx, section) in obj.sections.iter().enumerate() {
-        if section.kind != in_section.kind {
-            continue;
+    let (in_section, in_symbol) = in_obj.section_symbol(in_ref);
+    let in_section = in_section?;
+    let (section_idx, section) = obj
+        .sections
+        .iter()
+        .enumerate()
+        .find(|(_, s)| s.kind == in_section.kind && s.name == in_section.name)?;
+    let (symbol_idx, _) = unmatched_symbols(section, section_idx, used)
+        .find(|(_, symbol)| symbol.section_address == in_symbol.section_address)?;
+    Some(SymbolRef { section_idx, symbol_idx })
+}
+
+/// The section symbols of an object indexed by name, so that matching a symbol doesn't scan
+/// every symbol of the other object.
+struct SymbolIndex<'a> {
+    obj: &'a ObjInfo,
+    /// Symbols by name, in section and symbol table order
+    by_name: HashMap<&'a str, Vec<SymbolRef>>,
+    /// Symbols by demangled name without whitespace, if demangled name matching is enabled
+    by_demangled_name: HashMap<String, Vec<SymbolRef>>,
+}
+
+impl<'a> SymbolIndex<'a> {
+    fn new(obj: &'a ObjInfo, demangled_name_matching: bool) -> Self {
+        let mut by_name = HashMap::<&str, Vec<SymbolRef>>::new();
+        let mut by_demangled_name = HashMap::<String, Vec<SymbolRef>>::new();
+        for (section_idx, section) in obj.sections.iter().enumerate() {
+            for (symbol_idx, symbol) in section.symbols.iter().enumerate() {
+                let symbol_ref = SymbolRef { section_idx, symbol_idx };
+                by_name.entry(&symbol.name).or_default().push(symbol_ref);
+                if let Some(demangled) =
+                    symbol.demangled_name.as_deref().filter(|_| demangled_name_matching)
+                {
+                    by_demangled_name.entry(demangled_key(demangled)).or_default().push(symbol_ref);
+                }
+            }
         }
-        if let Some((symbol_idx, _)) = unmatched_symbols(section, section_idx, used)
-            .find(|(_, symbol)| symbol.name == in_symbol.name)
-        {
-            return Some(SymbolRef { section_idx, symbol_idx });
+        Self { obj, by_name, by_demangled_name }
+    }
+
+    fn named(&self, name: &str) -> &[SymbolRef] {
+        self.by_name.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the symbols named `name` within sections of the given name and kind, in section
+    /// and symbol table order.
+    fn same_name_symbols<'s>(
+        &'s self,
+        section_name: &'s str,
+        section_kind: ObjSectionKind,
+        name: &str,
+    ) -> impl Iterator<Item = SymbolRef> + 's {
+        self.named(name).iter().copied().filter(move |r| {
+            let section = &self.obj.sections[r.section_idx];
+            section.kind == section_kind && section.name == section_name
+        })
+    }
+
+    /// Whether more than one symbol in sections of the given kind is named `name`.
+    fn is_duplicate_name(&self, section_kind: ObjSectionKind, name: &str) -> bool {
+        self.named(name)
+            .iter()
+            .filter(|r| self.obj.sections[r.section_idx].kind == section_kind)
+            .nth(1)
+            .is_some()
+    }
+}
+
+/// Normalizes a demangled name, ignoring whitespace that differs between demanglers (e.g.
+/// `foo(int *)` and `foo(int*)`).
+fn demangled_key(name: &str) -> String { name.chars().filter(|c| !c.is_whitespace()).collect() }
+
+fn find_symbol(
+    index: Option<&SymbolIndex>,
+    in_index: &SymbolIndex,
+    in_ref: SymbolRef,
+    used: Option<&HashSet<SymbolRef>>,
+) -> Option<SymbolRef> {
+    let index = index?;
+    let obj = index.obj;
+    let (in_section, in_symbol) = in_index.obj.section_symbol(in_ref);
+    let in_section = in_section?;
+    let is_used = |symbol_ref: &SymbolRef| used.is_some_and(|u| u.contains(symbol_ref));
+    // Local symbols may share a name (e.g. multiple `@stringBase0` or `text$0`). Pair the
+    // n-th such symbol within sections of the same name with the n-th on the other side,
+    // falling back to the first unmatched one, so that matching doesn't depend on which
+    // section happens to come first.
+    if in_index.is_duplicate_name(in_section.kind, &in_symbol.name)
+        || index.is_duplicate_name(in_section.kind, &in_symbol.name)
+    {
+        let ordinal = in_index
+            .same_name_symbols(&in_section.name, in_section.kind, &in_symbol.name)
+            .position(|r| r == in_ref);
+        let candidates = index
+            .same_name_symbols(&in_section.name, in_section.kind, &in_symbol.name)
+            .collect::<Vec<_>>();
+        if let Some(&symbol_ref) = ordinal.and_then(|i| candidates.get(i)) {
+            if !is_used(&symbol_ref) {
+                return Some(symbol_ref);
+            }
+        }
+        if let Some(&symbol_ref) = candidates.iter().find(|r| !is_used(r)) {
+            return Some(symbol_ref);
+        }
+    }
+    let unmatched_of_kind = |symbol_ref: &&SymbolRef| {
+        obj.sections[symbol_ref.section_idx].kind == in_section.kind && !is_used(symbol_ref)
+    };
+    // Try to find an exact name match
+    if let Some(&symbol_ref) = index.named(&in_symbol.name).iter().find(unmatched_of_kind) {
+        return Some(symbol_ref);
+    }
+    // Match symbols mangled differently (e.g. by another compiler) with the same signature
+    if let Some(refs) = in_symbol
+        .demangled_name
+        .as_deref()
+        .and_then(|demangled| index.by_demangled_name.get(&demangled_key(demangled)))
+    {
+        if let Some(&symbol_ref) = refs.iter().find(unmatched_of_kind) {
+            return Some(symbol_ref);
         }
     }
     // Match compiler-generated symbols against each other (e.g. @251 -> @60)
@@ -0,0 +1,94 @@
+use std::fmt::Write;
+
+use crate::{
+    diff::ObjDiff,
+    obj::{ObjInfo, ObjSectionKind, ObjSymbolKind, SymbolRef},
+};
+
+/// Suggested symbol order for a single section, taken from the target object.
+#[derive(Debug, Clone)]
+pub struct SectionOrder {
+    pub name: String,
+    pub kind: ObjSectionKind,
+    /// Symbol names in target address order.
+    pub symbols: Vec<String>,
+    /// Whether every function in the section matches.
+    pub all_functions_match: bool,
+    /// Whether the base object places the matched symbols in a different order.
+    pub reordered: bool,
+}
+
+/// A linker order suggestion derived from a diff result.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolOrderSuggestion {
+    pub sections: Vec<SectionOrder>,
+}
+
+impl SymbolOrderSuggestion {
+    /// Renders the suggestion as a linker order file: one symbol name per line, in target
+    /// order, as read by e.g. lld's `--symbol-ordering-file`. Other linkers may need the list
+    /// adapted to their own format.
+    pub fn to_order_file(&self) -> String {
+        let mut out = String::new();
+        for section in &self.sections {
+            for symbol in &section.symbols {
+                writeln!(out, "{symbol}").unwrap();
+            }
+        }
+        out
+    }
+}
+
+/// Builds a symbol order suggestion using the target object's layout. Symbols that have no
+/// counterpart in the base object are still included, so the resulting file describes the
+/// full target order.
+pub fn suggest_symbol_order(
+    target: &ObjInfo,
+    target_diff: &ObjDiff,
+    base: Option<&ObjInfo>,
+) -> SymbolOrderSuggestion {
+    let mut sections = Vec::new();
+    for (section_idx, section) in target.sections.iter().enumerate() {
+        if !matches!(section.kind, ObjSectionKind::Code | ObjSectionKind::Data) {
+            continue;
+        }
+        let mut symbol_indices = section
+            .symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.kind != ObjSymbolKind::Section && !s.name.is_empty())
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        if symbol_indices.is_empty() {
+            continue;
+        }
+        symbol_indices.sort_by_key(|&i| (section.symbols[i].address, i));
+
+        let mut all_functions_match = true;
+        let mut base_addresses = Vec::new();
+        for &symbol_idx in &symbol_indices {
+            let symbol_diff = target_diff.symbol_diff(SymbolRef { section_idx, symbol_idx });
+            if section.symbols[symbol_idx].kind == ObjSymbolKind::Function
+                && symbol_diff.match_percent != Some(100.0)
+            {
+                all_functions_match = false;
+            }
+            if let (Some(base), Some(target_ref)) = (base, symbol_diff.target_symbol) {
+                let (base_section, base_symbol) = base.section_symbol(target_ref);
+                if base_section.is_some_and(|s| s.name == section.name) {
+                    base_addresses.push(base_symbol.address);
+                }
+            }
+        }
+        let reordered = base_addresses.windows(2).any(|w| w[0] > w[1]);
+
+        sections.push(SectionOrder {
+            name: section.name.clone(),
+            kind: section.kind,
+            symbols: symbol_indices.iter().map(|&i| section.symbols[i].name.clone()).collect(),
+            all_functions_match,
+            reordered,
+        });
+    }
+    SymbolOrderSuggestion { sections }
+}
@@ -0,0 +1,427 @@
+//! Descriptions of the user-facing [`DiffObjConfig`] options, shared by the GUI settings and
+//! `objdiff-cli diff --config`.
+
+use std::fmt;
+
+use anyhow::{anyhow, bail, Result};
+use strum::{EnumMessage, VariantArray};
+
+use crate::diff::{
+    ArmArchVersion, ArmR9Usage, DiffObjConfig, MipsAbi, MipsFpRegisterName, MipsInstrCategory,
+    PpcCrFieldMismatch, X86Formatter,
+};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, strum::VariantArray)]
+pub enum ConfigProperty {
+    RelaxRelocDiffs,
+    IgnorePseudoRelocDiffs,
+    SpaceBetweenArgs,
+    CombineDataSections,
+    IgnoreCodegenQuirks,
+    MaxAlignmentEdits,
+    StripSymbolVersions,
+    FilterLocalLabels,
+    GlobalSymbolsOnly,
+    IgnoreDataPadding,
+    DemangledNameMatching,
+    X86Formatter,
+    MipsAbi,
+    MipsInstrCategory,
+    MipsPseudoInstructions,
+    MipsFpRegisterName,
+    PpcCrFieldMismatch,
+    PpcGqrAnnotations,
+    ArmArchVersion,
+    ArmUnifiedSyntax,
+    ArmAvRegisters,
+    ArmR9Usage,
+    ArmSlUsage,
+    ArmFpUsage,
+    ArmIpUsage,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ConfigPropertyKind {
+    Boolean,
+    Number,
+    Choice,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ConfigPropertyValue {
+    Boolean(bool),
+    Number(u32),
+    /// Index into [`ConfigProperty::choices`]
+    Choice(usize),
+}
+
+/// A possible value of a [`ConfigPropertyKind::Choice`] property.
+#[derive(Debug, Clone)]
+pub struct ConfigChoice {
+    /// Value as written in configuration files and on the command line
+    pub value: String,
+    pub name: &'static str,
+    pub description: Option<&'static str>,
+}
+
+fn choices<T: VariantArray + EnumMessage + fmt::Debug>() -> Vec<ConfigChoice> {
+    T::VARIANTS
+        .iter()
+        .map(|v| ConfigChoice {
+            value: format!("{v:?}"),
+            name: v.get_message().unwrap_or_default(),
+            description: v.get_detailed_message(),
+        })
+        .collect()
+}
+
+fn choice_index<T: VariantArray + PartialEq>(value: &T) -> usize {
+    T::VARIANTS.iter().position(|v| v == value).unwrap_or_default()
+}
+
+fn set_choice<T: VariantArray + Copy>(target: &mut T, index: usize) -> Result<()> {
+    *target = *T::VARIANTS.get(index).ok_or_else(|| anyhow!("Invalid choice {index}"))?;
+    Ok(())
+}
+
+impl ConfigProperty {
+    pub const ALL: &'static [ConfigProperty] = Self::VARIANTS;
+
+    /// Field name in [`DiffObjConfig`], used as the key in configuration files.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::RelaxRelocDiffs => "relax_reloc_diffs",
+            Self::IgnorePseudoRelocDiffs => "ignore_pseudo_reloc_diffs",
+            Self::SpaceBetweenArgs => "space_between_args",
+            Self::CombineDataSections => "combine_data_sections",
+            Self::IgnoreCodegenQuirks => "ignore_codegen_quirks",
+            Self::MaxAlignmentEdits => "max_alignment_edits",
+            Self::StripSymbolVersions => "strip_symbol_versions",
+            Self::FilterLocalLabels => "filter_local_labels",
+            Self::GlobalSymbolsOnly => "global_symbols_only",
+            Self::IgnoreDataPadding => "ignore_data_padding",
+            Self::DemangledNameMatching => "demangled_name_matching",
+            Self::X86Formatter => "x86_formatter",
+            Self::MipsAbi => "mips_abi",
+            Self::MipsInstrCategory => "mips_instr_category",
+            Self::MipsPseudoInstructions => "mips_pseudo_instructions",
+            Self::MipsFpRegisterName => "mips_fp_register_name",
+            Self::PpcCrFieldMismatch => "ppc_cr_field_mismatch",
+            Self::PpcGqrAnnotations => "ppc_gqr_annotations",
+            Self::ArmArchVersion => "arm_arch_version",
+            Self::ArmUnifiedSyntax => "arm_unified_syntax",
+            Self::ArmAvRegisters => "arm_av_registers",
+            Self::ArmR9Usage => "arm_r9_usage",
+            Self::ArmSlUsage => "arm_sl_usage",
+            Self::ArmFpUsage => "arm_fp_usage",
+            Self::ArmIpUsage => "arm_ip_usage",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> { Self::ALL.iter().copied().find(|p| p.id() == id) }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::RelaxRelocDiffs => "Relax relocation diffs",
+            Self::IgnorePseudoRelocDiffs => "Ignore pseudo relocation diffs",
+            Self::SpaceBetweenArgs => "Space between args",
+            Self::CombineDataSections => "Combine data sections",
+            Self::IgnoreCodegenQuirks => "Ignore known codegen quirks",
+            Self::MaxAlignmentEdits => "Max alignment edits",
+            Self::StripSymbolVersions => "Strip symbol versions",
+            Self::FilterLocalLabels => "Hide assembler-local labels",
+            Self::GlobalSymbolsOnly => "Global symbols only",
+            Self::IgnoreDataPadding => "Ignore data padding",
+            Self::DemangledNameMatching => "Match symbols by demangled name",
+            Self::X86Formatter => "Format",
+            Self::MipsAbi => "ABI",
+            Self::MipsInstrCategory => "Instruction category",
+            Self::MipsPseudoInstructions => "Pseudo-instructions",
+            Self::MipsFpRegisterName => "Display R30 as",
+            Self::PpcCrFieldMismatch => "Condition register field differences",
+            Self::PpcGqrAnnotations => "Annotate quantized loads and stores",
+            Self::ArmArchVersion => "Architecture version",
+            Self::ArmUnifiedSyntax => "Unified syntax",
+            Self::ArmAvRegisters => "Use A/V registers",
+            Self::ArmR9Usage => "Display R9 as",
+            Self::ArmSlUsage => "Display R10 as SL",
+            Self::ArmFpUsage => "Display R11 as FP",
+            Self::ArmIpUsage => "Display R12 as IP",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::RelaxRelocDiffs => {
+                "Ignores differences in relocation targets. (Address, name, etc)"
+            }
+            Self::IgnorePseudoRelocDiffs => {
+                "Counts instructions that differ only in pseudo relocations as matching. These \
+                are inferred by flow analysis rather than read from the object (e.g. PowerPC \
+                accesses through a pooled data pointer, shown as R_PPC_NONE), and the inference \
+                can be wrong.\nThey're still shown as mismatched."
+            }
+            Self::SpaceBetweenArgs => "Adds a space after the commas separating arguments.",
+            Self::CombineDataSections => "Combines data sections with equal names.",
+            Self::IgnoreCodegenQuirks => {
+                "Highlights known benign instruction sequence differences and counts them \
+                 separately. They still count against the match percent."
+            }
+            Self::MaxAlignmentEdits => {
+                "Large functions that need more inserted or deleted instructions than this to \
+                align are paired by position instead.\n0 always aligns, which can be slow for \
+                huge functions."
+            }
+            Self::StripSymbolVersions => {
+                "Removes ELF symbol versions (e.g. memcpy@GLIBC_2.2.5) from symbol names, so \
+                versioned and unversioned symbols match."
+            }
+            Self::FilterLocalLabels => {
+                "Hides assembler-local labels (.L*, $L*, and ARM mapping symbols such as $x) \
+                from symbol lists and matching.\nRelocations against them are resolved to the \
+                enclosing symbol."
+            }
+            Self::GlobalSymbolsOnly => {
+                "Hides local symbols from symbol lists, matching and report statistics, for \
+                objects with many compiler-local symbols.\nRelocations against them still show \
+                their names, and they're listed with \"Show hidden symbols\"."
+            }
+            Self::IgnoreDataPadding => {
+                "Excludes zeroed alignment padding between data symbols from the data match \
+                percent, so sections differing only in alignment match.\nPadding is hatched in \
+                the data view."
+            }
+            Self::DemangledNameMatching => {
+                "Pairs symbols whose mangled names differ but demangle to the same signature, \
+                e.g. when comparing objects from different compilers.\nOnly used when no symbol \
+                with the same name exists."
+            }
+            Self::X86Formatter => "Assembly syntax used to display x86 instructions.",
+            Self::MipsAbi => {
+                "ABI used to name registers (e.g. $t0-$t3 in O32 are $a4-$a7 in N32 and N64). \
+                Auto detects it from the object's header flags."
+            }
+            Self::MipsInstrCategory => {
+                "Instruction set extension to decode. Auto detects it from the object's header \
+                flags."
+            }
+            Self::MipsPseudoInstructions => {
+                "Display pseudo-instructions such as move, beqz and b instead of their raw \
+                forms.\nInstructions are always compared by their raw encoding."
+            }
+            Self::MipsFpRegisterName => {
+                "How to name register 30, to match the project's assembly dumps."
+            }
+            Self::PpcCrFieldMismatch => {
+                "How to treat instructions that differ only in condition register field \
+                (e.g. cr0 vs cr7)."
+            }
+            Self::PpcGqrAnnotations => {
+                "Annotates psq_l and psq_st with the type and scale of their GQR, when the \
+                project config supplies GQR values (ppc_gqrs)."
+            }
+            Self::ArmArchVersion => {
+                "Architecture version to disassemble for. Auto uses the object's build \
+                attributes."
+            }
+            Self::ArmUnifiedSyntax => "Disassemble as unified assembly language (UAL).",
+            Self::ArmAvRegisters => "Display R0-R3 as A1-A4 and R4-R11 as V1-V8.",
+            Self::ArmR9Usage => "How the project uses R9, which determines how it is displayed.",
+            Self::ArmSlUsage => "Used for explicit stack limits.",
+            Self::ArmFpUsage => "Used for frame pointers.",
+            Self::ArmIpUsage => "Used for interworking and long branches.",
+        }
+    }
+
+    /// Heading the property is listed under.
+    pub fn group(self) -> &'static str {
+        match self {
+            Self::RelaxRelocDiffs
+            | Self::IgnorePseudoRelocDiffs
+            | Self::SpaceBetweenArgs
+            | Self::CombineDataSections
+            | Self::IgnoreCodegenQuirks
+            | Self::MaxAlignmentEdits
+            | Self::StripSymbolVersions
+            | Self::FilterLocalLabels
+            | Self::GlobalSymbolsOnly
+            | Self::IgnoreDataPadding
+            | Self::DemangledNameMatching => "General",
+            Self::X86Formatter => "x86",
+            Self::MipsAbi
+            | Self::MipsInstrCategory
+            | Self::MipsPseudoInstructions
+            | Self::MipsFpRegisterName => "MIPS",
+            Self::PpcCrFieldMismatch | Self::PpcGqrAnnotations => "PowerPC",
+            Self::ArmArchVersion
+            | Self::ArmUnifiedSyntax
+            | Self::ArmAvRegisters
+            | Self::ArmR9Usage
+            | Self::ArmSlUsage
+            | Self::ArmFpUsage
+            | Self::ArmIpUsage => "ARM",
+        }
+    }
+
+    pub fn kind(self) -> ConfigPropertyKind {
+        match self {
+            Self::MaxAlignmentEdits => ConfigPropertyKind::Number,
+            Self::X86Formatter
+            | Self::MipsAbi
+            | Self::MipsInstrCategory
+            | Self::MipsFpRegisterName
+            | Self::PpcCrFieldMismatch
+            | Self::ArmArchVersion
+            | Self::ArmR9Usage => ConfigPropertyKind::Choice,
+            _ => ConfigPropertyKind::Boolean,
+        }
+    }
+
+    /// Possible values of a [`ConfigPropertyKind::Choice`] property, empty otherwise.
+    pub fn choices(self) -> Vec<ConfigChoice> {
+        match self {
+            Self::X86Formatter => choices::<X86Formatter>(),
+            Self::MipsAbi => choices::<MipsAbi>(),
+            Self::MipsInstrCategory => choices::<MipsInstrCategory>(),
+            Self::MipsFpRegisterName => choices::<MipsFpRegisterName>(),
+            Self::PpcCrFieldMismatch => choices::<PpcCrFieldMismatch>(),
+            Self::ArmArchVersion => choices::<ArmArchVersion>(),
+            Self::ArmR9Usage => choices::<ArmR9Usage>(),
+            _ => vec![],
+        }
+    }
+
+    /// Parses a value as written on the command line (e.g. `true`, `500` or `Gas`).
+    pub fn parse_value(self, value: &str) -> Result<ConfigPropertyValue> {
+        match self.kind() {
+            ConfigPropertyKind::Boolean => match value.to_ascii_lowercase().as_str() {
+                "true" | "1" | "yes" | "on" => Ok(ConfigPropertyValue::Boolean(true)),
+                "false" | "0" | "no" | "off" => Ok(ConfigPropertyValue::Boolean(false)),
+                _ => bail!("Invalid value for {}: {value} (expected true or false)", self.id()),
+            },
+            ConfigPropertyKind::Number => {
+                value.parse().map(ConfigPropertyValue::Number).map_err(|_| {
+                    anyhow!("Invalid value for {}: {value} (expected a number)", self.id())
+                })
+            }
+            ConfigPropertyKind::Choice => {
+                let choices = self.choices();
+                choices
+                    .iter()
+                    .position(|c| c.value.eq_ignore_ascii_case(value))
+                    .map(ConfigPropertyValue::Choice)
+                    .ok_or_else(|| {
+                        let values = choices.iter().map(|c| c.value.as_str()).collect::<Vec<_>>();
+                        anyhow!(
+                            "Invalid value for {}: {value} (expected one of {})",
+                            self.id(),
+                            values.join(", ")
+                        )
+                    })
+            }
+        }
+    }
+
+    /// Formats a value the way [`Self::parse_value`] accepts it.
+    pub fn display_value(self, value: ConfigPropertyValue) -> String {
+        match value {
+            ConfigPropertyValue::Boolean(v) => v.to_string(),
+            ConfigPropertyValue::Number(v) => v.to_string(),
+            ConfigPropertyValue::Choice(i) => {
+                self.choices().get(i).map(|c| c.value.clone()).unwrap_or_default()
+            }
+        }
+    }
+}
+
+impl DiffObjConfig {
+    pub fn property_value(&self, property: ConfigProperty) -> ConfigPropertyValue {
+        use ConfigPropertyValue::{Boolean, Choice, Number};
+        match property {
+            ConfigProperty::RelaxRelocDiffs => Boolean(self.relax_reloc_diffs),
+            ConfigProperty::IgnorePseudoRelocDiffs => Boolean(self.ignore_pseudo_reloc_diffs),
+            ConfigProperty::SpaceBetweenArgs => Boolean(self.space_between_args),
+            ConfigProperty::CombineDataSections => Boolean(self.combine_data_sections),
+            ConfigProperty::IgnoreCodegenQuirks => Boolean(self.ignore_codegen_quirks),
+            ConfigProperty::MaxAlignmentEdits => Number(self.max_alignment_edits),
+            ConfigProperty::StripSymbolVersions => Boolean(self.strip_symbol_versions),
+            ConfigProperty::FilterLocalLabels => Boolean(self.filter_local_labels),
+            ConfigProperty::GlobalSymbolsOnly => Boolean(self.global_symbols_only),
+            ConfigProperty::IgnoreDataPadding => Boolean(self.ignore_data_padding),
+            ConfigProperty::DemangledNameMatching => Boolean(self.demangled_name_matching),
+            ConfigProperty::X86Formatter => Choice(choice_index(&self.x86_formatter)),
+            ConfigProperty::MipsAbi => Choice(choice_index(&self.mips_abi)),
+            ConfigProperty::MipsInstrCategory => Choice(choice_index(&self.mips_instr_category)),
+            ConfigProperty::MipsPseudoInstructions => Boolean(self.mips_pseudo_instructions),
+            ConfigProperty::MipsFpRegisterName => Choice(choice_index(&self.mips_fp_register_name)),
+            ConfigProperty::PpcCrFieldMismatch => Choice(choice_index(&self.ppc_cr_field_mismatch)),
+            ConfigProperty::PpcGqrAnnotations => Boolean(self.ppc_gqr_annotations),
+            ConfigProperty::ArmArchVersion => Choice(choice_index(&self.arm_arch_version)),
+            ConfigProperty::ArmUnifiedSyntax => Boolean(self.arm_unified_syntax),
+            ConfigProperty::ArmAvRegisters => Boolean(self.arm_av_registers),
+            ConfigProperty::ArmR9Usage => Choice(choice_index(&self.arm_r9_usage)),
+            ConfigProperty::ArmSlUsage => Boolean(self.arm_sl_usage),
+            ConfigProperty::ArmFpUsage => Boolean(self.arm_fp_usage),
+            ConfigProperty::ArmIpUsage => Boolean(self.arm_ip_usage),
+        }
+    }
+
+    pub fn set_property_value(
+        &mut self,
+        property: ConfigProperty,
+        value: ConfigPropertyValue,
+    ) -> Result<()> {
+        use ConfigPropertyValue::{Boolean, Choice, Number};
+        match (property, value) {
+            (ConfigProperty::RelaxRelocDiffs, Boolean(v)) => self.relax_reloc_diffs = v,
+            (ConfigProperty::IgnorePseudoRelocDiffs, Boolean(v)) => {
+                self.ignore_pseudo_reloc_diffs = v
+            }
+            (ConfigProperty::SpaceBetweenArgs, Boolean(v)) => self.space_between_args = v,
+            (ConfigProperty::CombineDataSections, Boolean(v)) => self.combine_data_sections = v,
+            (ConfigProperty::IgnoreCodegenQuirks, Boolean(v)) => self.ignore_codegen_quirks = v,
+            (ConfigProperty::MaxAlignmentEdits, Number(v)) => self.max_alignment_edits = v,
+            (ConfigProperty::StripSymbolVersions, Boolean(v)) => self.strip_symbol_versions = v,
+            (ConfigProperty::FilterLocalLabels, Boolean(v)) => self.filter_local_labels = v,
+            (ConfigProperty::GlobalSymbolsOnly, Boolean(v)) => self.global_symbols_only = v,
+            (ConfigProperty::IgnoreDataPadding, Boolean(v)) => self.ignore_data_padding = v,
+            (ConfigProperty::DemangledNameMatching, Boolean(v)) => self.demangled_name_matching = v,
+            (ConfigProperty::X86Formatter, Choice(i)) => set_choice(&mut self.x86_formatter, i)?,
+            (ConfigProperty::MipsAbi, Choice(i)) => set_choice(&mut self.mips_abi, i)?,
+            (ConfigProperty::MipsInstrCategory, Choice(i)) => {
+                set_choice(&mut self.mips_instr_category, i)?
+            }
+            (ConfigProperty::MipsPseudoInstructions, Boolean(v)) => {
+                self.mips_pseudo_instructions = v
+            }
+            (ConfigProperty::MipsFpRegisterName, Choice(i)) => {
+                set_choice(&mut self.mips_fp_register_name, i)?
+            }
+            (ConfigProperty::PpcCrFieldMismatch, Choice(i)) => {
+                set_choice(&mut self.ppc_cr_field_mismatch, i)?
+            }
+            (ConfigProperty::PpcGqrAnnotations, Boolean(v)) => self.ppc_gqr_annotations = v,
+            (ConfigProperty::ArmArchVersion, Choice(i)) => {
+                set_choice(&mut self.arm_arch_version, i)?
+            }
+            (ConfigProperty::ArmUnifiedSyntax, Boolean(v)) => self.arm_unified_syntax = v,
+            (ConfigProperty::ArmAvRegisters, Boolean(v)) => self.arm_av_registers = v,
+            (ConfigProperty::ArmR9Usage, Choice(i)) => set_choice(&mut self.arm_r9_usage, i)?,
+            (ConfigProperty::ArmSlUsage, Boolean(v)) => self.arm_sl_usage = v,
+            (ConfigProperty::ArmFpUsage, Boolean(v)) => self.arm_fp_usage = v,
+            (ConfigProperty::ArmIpUsage, Boolean(v)) => self.arm_ip_usage = v,
+            (property, value) => bail!("Invalid value for {}: {value:?}", property.id()),
+        }
+        Ok(())
+    }
+
+    /// Applies a `key=value` option, as passed to `objdiff-cli diff --config`.
+    pub fn set_property_str(&mut self, option: &str) -> Result<()> {
+        let (key, value) = option
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Invalid option {option}, expected key=value"))?;
+        let property = ConfigProperty::from_id(key.trim())
+            .ok_or_else(|| anyhow!("Unknown option {}", key.trim()))?;
+        self.set_property_value(property, property.parse_value(value.trim())?)
+    }
+}
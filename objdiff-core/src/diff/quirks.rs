@@ -0,0 +1,306 @@
+use std::{collections::BTreeMap, fmt};
+
+use object::Architecture;
+
+use crate::{
+    config::CodegenQuirk,
+    diff::{DiffObjConfig, ObjInsDiff, ObjInsDiffKind},
+    obj::{ObjIns, ObjInsArg, ObjInsArgValue, ObjReloc},
+};
+
+/// A known benign difference in instruction selection between compiler versions or flags.
+pub struct BuiltinQuirk {
+    pub arches: &'static [Architecture],
+    pub compiler: &'static str,
+    pub name: &'static str,
+    pub left: &'static [&'static str],
+    pub right: &'static [&'static str],
+}
+
+pub const BUILTIN_QUIRKS: &[BuiltinQuirk] = &[
+    BuiltinQuirk {
+        arches: &[Architecture::PowerPc],
+        compiler: "mwcc",
+        name: "Constant materialization via lis/ori vs xoris/ori",
+        left: &["lis", "ori"],
+        right: &["xoris", "ori"],
+    },
+    BuiltinQuirk {
+        arches: &[Architecture::PowerPc],
+        compiler: "mwcc",
+        name: "Constant materialization via lis/addi vs lis/ori",
+        left: &["lis", "addi"],
+        right: &["lis", "ori"],
+    },
+    BuiltinQuirk {
+        arches: &[Architecture::I386, Architecture::X86_64],
+        compiler: "msvc",
+        name: "Register zeroing via xor vs mov",
+        left: &["xor"],
+        right: &["mov"],
+    },
+];
+
+/// Whether a quirk for `quirk_compiler` applies to the unit's compiler, a decomp.me compiler ID
+/// such as `mwcc_242_81`. Quirks apply to every compiler if either is unknown.
+fn compiler_matches(quirk_compiler: Option<&str>, compiler: Option<&str>) -> bool {
+    match (quirk_compiler, compiler) {
+        (Some(quirk_compiler), Some(compiler)) => compiler.contains(quirk_compiler),
+        _ => true,
+    }
+}
+
+/// Marks instruction sequences that match a known codegen quirk as [`ObjInsDiffKind::Quirk`].
+///
+/// Built-in quirks only apply to their architectures, and quirks naming a compiler only apply
+/// to units built with it (see [`DiffObjConfig::compiler`]).
+///
+/// Quirk patterns are symmetric and only apply to runs of rows that all differ. Beyond the
+/// mnemonics, both sequences must write the same destination (first argument of the final
+/// instruction) and compute the same value into it, so that a quirk never hides a real
+/// difference. Sequences of instructions the evaluator doesn't know (e.g. from project quirks)
+/// must instead have identical operands, immediates included.
+///
+/// Returns the number of rows that were marked.
+pub fn apply_codegen_quirks(
+    config: &DiffObjConfig,
+    arch: Architecture,
+    left_diff: &mut [ObjInsDiff],
+    right_diff: &mut [ObjInsDiff],
+) -> usize {
+    let compiler = config.compiler.as_deref();
+    let patterns = BUILTIN_QUIRKS
+        .iter()
+        .filter(|q| q.arches.contains(&arch) && compiler_matches(Some(q.compiler), compiler))
+        .map(|q| (q.left.to_vec(), q.right.to_vec()))
+        .chain(
+            config
+                .codegen_quirks
+                .iter()
+                .filter(|q| compiler_matches(q.compiler.as_deref(), compiler))
+                .map(|q: &CodegenQuirk| {
+                    (
+                        q.left.iter().map(String::as_str).collect(),
+                        q.right.iter().map(String::as_str).collect(),
+                    )
+                }),
+        )
+        .filter(|(a, b): &(Vec<&str>, Vec<&str>)| !a.is_empty() && !b.is_empty())
+        .collect::<Vec<_>>();
+    let mut count = 0;
+    let mut i = 0;
+    while i < left_diff.len() {
+        if matches!(left_diff[i].kind, ObjInsDiffKind::None | ObjInsDiffKind::SoftMismatch) {
+            i += 1;
+            continue;
+        }
+        let matched = patterns.iter().find_map(|(a, b)| {
+            match_window(arch, left_diff, right_diff, i, a, b)
+                .or_else(|| match_window(arch, left_diff, right_diff, i, b, a))
+        });
+        let Some(len) = matched else {
+            i += 1;
+            continue;
+        };
+        for row in i..i + len {
+            for diff in [&mut left_diff[row], &mut right_diff[row]] {
+                diff.kind = ObjInsDiffKind::Quirk;
+                diff.arg_diff.clear();
+            }
+        }
+        count += len;
+        i += len;
+    }
+    count
+}
+
+fn match_window(
+    arch: Architecture,
+    left_diff: &[ObjInsDiff],
+    right_diff: &[ObjInsDiff],
+    start: usize,
+    left_pattern: &[&str],
+    right_pattern: &[&str],
+) -> Option<usize> {
+    // The aligner may have paired the sequences as replacements, or split them into
+    // deletions and insertions, so try every window length between the two.
+    let min_len = left_pattern.len().max(right_pattern.len());
+    let max_len = left_pattern.len() + right_pattern.len();
+    for len in min_len..=max_len {
+        let end = start + len;
+        if end > left_diff.len() || end > right_diff.len() {
+            break;
+        }
+        let rows = start..end;
+        if left_diff[rows.clone()]
+            .iter()
+            .any(|d| matches!(d.kind, ObjInsDiffKind::None | ObjInsDiffKind::SoftMismatch))
+        {
+            break;
+        }
+        let mut left_ins = left_diff[rows.clone()].iter().filter_map(|d| d.ins.as_ref());
+        let mut right_ins = right_diff[rows].iter().filter_map(|d| d.ins.as_ref());
+        if !left_ins.clone().map(|i| i.mnemonic.as_ref()).eq(left_pattern.iter().copied())
+            || !right_ins.clone().map(|i| i.mnemonic.as_ref()).eq(right_pattern.iter().copied())
+        {
+            continue;
+        }
+        let equivalent = match (evaluate(arch, left_ins.clone()), evaluate(arch, right_ins.clone()))
+        {
+            (Some(left_value), Some(right_value)) => left_value == right_value,
+            _ => operands(left_ins.clone()) == operands(right_ins.clone()),
+        };
+        if !equivalent {
+            continue;
+        }
+        let left_dest = left_ins.next_back().and_then(|i| i.iter_args().next());
+        let right_dest = right_ins.next_back().and_then(|i| i.iter_args().next());
+        match (left_dest, right_dest) {
+            (Some(l), Some(r)) if !l.loose_eq(r) => continue,
+            _ => return Some(len),
+        }
+    }
+    None
+}
+
+/// All operands used by a sequence, in order of appearance.
+fn operands<'a>(ins: impl Iterator<Item = &'a ObjIns>) -> Vec<String> {
+    let mut out = Vec::new();
+    for ins in ins {
+        for arg in ins.iter_args() {
+            out.push(match arg {
+                ObjInsArg::PlainText(arg) => arg.to_string(),
+                ObjInsArg::Arg(arg) => arg.to_string(),
+                ObjInsArg::Reloc => ins.reloc.as_ref().map_or_else(String::new, reloc_operand),
+                ObjInsArg::BranchDest(dest) => format!("{dest:#x}"),
+            });
+        }
+    }
+    out.sort();
+    out
+}
+
+fn reloc_operand(reloc: &ObjReloc) -> String {
+    format!("{:?}({}+{:#x})", reloc.flags, reloc.target.name, reloc.addend)
+}
+
+/// A value computed by a quirk sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Const(u64),
+    /// A value that isn't constant within the sequence, such as a register's value before it or
+    /// a relocation target, with the operations applied to it
+    Expr(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Const(v) => write!(f, "{v:#x}"),
+            Value::Expr(e) => f.write_str(e),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Op {
+    Add,
+    Or,
+    Xor,
+}
+
+impl Value {
+    fn apply(&self, op: Op, rhs: &Value, mask: u64) -> Value {
+        match (self, rhs) {
+            (Value::Const(a), Value::Const(b)) => Value::Const(
+                match op {
+                    Op::Add => a.wrapping_add(*b),
+                    Op::Or => a | b,
+                    Op::Xor => a ^ b,
+                } & mask,
+            ),
+            (a, b) => {
+                let op = match op {
+                    Op::Add => '+',
+                    Op::Or => '|',
+                    Op::Xor => '^',
+                };
+                Value::Expr(format!("({a} {op} {b})"))
+            }
+        }
+    }
+
+    fn shifted(&self, mask: u64) -> Value {
+        match self {
+            Value::Const(v) => Value::Const((v << 16) & mask),
+            Value::Expr(e) => Value::Expr(format!("({e} << 16)")),
+        }
+    }
+}
+
+/// An operand of an instruction being evaluated: the register it names, if any, and its value.
+struct Operand<'a> {
+    reg: Option<&'a str>,
+    value: Value,
+}
+
+/// Evaluates a sequence of simple integer instructions, returning the value written to its
+/// destination by the last instruction. Returns `None` if an instruction isn't supported.
+fn evaluate<'a>(arch: Architecture, ins: impl Iterator<Item = &'a ObjIns>) -> Option<Value> {
+    let mask = match arch {
+        Architecture::X86_64 => u64::MAX,
+        _ => u32::MAX as u64,
+    };
+    let mut regs = BTreeMap::<&str, Value>::new();
+    let mut result = None;
+    for ins in ins {
+        let args = ins
+            .iter_args()
+            .map(|arg| match arg {
+                ObjInsArg::Arg(ObjInsArgValue::Opaque(reg)) => Some(Operand {
+                    reg: Some(reg.as_ref()),
+                    value: regs
+                        .get(reg.as_ref())
+                        .cloned()
+                        .unwrap_or_else(|| Value::Expr(reg.to_string())),
+                }),
+                ObjInsArg::Arg(ObjInsArgValue::Signed(v)) => {
+                    Some(Operand { reg: None, value: Value::Const(*v as u64 & mask) })
+                }
+                ObjInsArg::Arg(ObjInsArgValue::Unsigned(v)) => {
+                    Some(Operand { reg: None, value: Value::Const(v & mask) })
+                }
+                ObjInsArg::Reloc => Some(Operand {
+                    reg: None,
+                    value: Value::Expr(reloc_operand(ins.reloc.as_ref()?)),
+                }),
+                ObjInsArg::PlainText(_) | ObjInsArg::BranchDest(_) => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        // On PPC, r0 as the base of an addition reads as zero
+        let base = |op: &Operand| match op.reg {
+            Some("r0") => Value::Const(0),
+            _ => op.value.clone(),
+        };
+        let (dest, value) = match (ins.mnemonic.as_ref(), args.as_slice()) {
+            // PPC
+            ("li", [d, imm]) => (d, imm.value.clone()),
+            ("lis", [d, imm]) => (d, imm.value.shifted(mask)),
+            ("addi", [d, a, imm]) => (d, base(a).apply(Op::Add, &imm.value, mask)),
+            ("addis", [d, a, imm]) => (d, base(a).apply(Op::Add, &imm.value.shifted(mask), mask)),
+            ("ori", [d, s, imm]) => (d, s.value.apply(Op::Or, &imm.value, mask)),
+            ("oris", [d, s, imm]) => (d, s.value.apply(Op::Or, &imm.value.shifted(mask), mask)),
+            ("xori", [d, s, imm]) => (d, s.value.apply(Op::Xor, &imm.value, mask)),
+            ("xoris", [d, s, imm]) => (d, s.value.apply(Op::Xor, &imm.value.shifted(mask), mask)),
+            ("mr", [d, s]) => (d, s.value.clone()),
+            // x86 (Intel syntax)
+            ("mov", [d, s]) => (d, s.value.clone()),
+            ("xor", [d, s]) if d.reg.is_some() && d.reg == s.reg => (d, Value::Const(0)),
+            ("xor", [d, s]) => (d, d.value.apply(Op::Xor, &s.value, mask)),
+            _ => return None,
+        };
+        regs.insert(dest.reg?, value.clone());
+        result = Some(value);
+    }
+    result
+}
@@ -0,0 +1,63 @@
+use similar::{capture_diff_slices_deadline, Algorithm, DiffTag};
+
+use crate::{
+    diff::ObjInsDiffKind,
+    obj::{seh::SehUnwindInfo, unwind::UnwindInfo},
+};
+
+/// A row in a side-by-side diff of decoded unwind information.
+#[derive(Debug, Clone, Default)]
+pub struct UnwindDiffRow {
+    pub left: Option<String>,
+    pub right: Option<String>,
+    pub kind: ObjInsDiffKind,
+}
+
+/// Diffs the decoded CFI programs and LSDA tables of two functions line by line.
+pub fn diff_unwind_info(
+    left: Option<&UnwindInfo>,
+    right: Option<&UnwindInfo>,
+) -> Vec<UnwindDiffRow> {
+    let left_lines = left.map(UnwindInfo::lines).unwrap_or_default();
+    let right_lines = right.map(UnwindInfo::lines).unwrap_or_default();
+    diff_unwind_lines(&left_lines, &right_lines)
+}
+
+/// Diffs the decoded `.pdata`/`.xdata` unwind information of two functions line by line.
+pub fn diff_seh_unwind_info(
+    left: Option<&SehUnwindInfo>,
+    right: Option<&SehUnwindInfo>,
+) -> Vec<UnwindDiffRow> {
+    let left_lines = left.map(SehUnwindInfo::lines).unwrap_or_default();
+    let right_lines = right.map(SehUnwindInfo::lines).unwrap_or_default();
+    diff_unwind_lines(&left_lines, &right_lines)
+}
+
+fn diff_unwind_lines(left_lines: &[String], right_lines: &[String]) -> Vec<UnwindDiffRow> {
+    let ops = capture_diff_slices_deadline(Algorithm::Patience, left_lines, right_lines, None);
+    let mut rows = Vec::with_capacity(left_lines.len().max(right_lines.len()));
+    for op in ops {
+        let (tag, left_range, right_range) = op.as_tag_tuple();
+        let kind = match tag {
+            DiffTag::Equal => ObjInsDiffKind::None,
+            DiffTag::Delete => ObjInsDiffKind::Delete,
+            DiffTag::Insert => ObjInsDiffKind::Insert,
+            DiffTag::Replace => ObjInsDiffKind::Replace,
+        };
+        let len = left_range.len().max(right_range.len());
+        for i in 0..len {
+            rows.push(UnwindDiffRow {
+                left: left_lines
+                    .get(left_range.start + i)
+                    .filter(|_| i < left_range.len())
+                    .cloned(),
+                right: right_lines
+                    .get(right_range.start + i)
+                    .filter(|_| i < right_range.len())
+                    .cloned(),
+                kind,
+            });
+        }
+    }
+    rows
+}
@@ -1,4 +1,4 @@
-use std::{sync::mpsc::Receiver, task::Waker};
+use std::task::Waker;
 
 use anyhow::{Context, Result};
 use self_update::{
@@ -6,7 +6,10 @@ use self_update::{
     update::{Release, ReleaseUpdate},
 };
 
-use crate::jobs::{start_job, update_status, Job, JobContext, JobResult, JobState};
+use crate::{
+    jobs::{start_job, update_status, Job, JobContext, JobResult, JobState},
+    util::CancellationToken,
+};
 
 pub struct CheckUpdateConfig {
     pub build_updater: fn() -> Result<Box<dyn ReleaseUpdate>>,
@@ -21,7 +24,7 @@ pub struct CheckUpdateResult {
 
 fn run_check_update(
     context: &JobContext,
-    cancel: Receiver<()>,
+    cancel: CancellationToken,
     config: CheckUpdateConfig,
 ) -> Result<Box<CheckUpdateResult>> {
     update_status(context, "Fetching latest release".to_string(), 0, 1, &cancel)?;
@@ -0,0 +1,53 @@
+use std::task::Waker;
+
+use anyhow::Result;
+
+use crate::{
+    build::{run_shell_command, BuildConfig, BuildMonitor},
+    jobs::{start_job, update_status, Job, JobContext, JobResult, JobState},
+    util::CancellationToken,
+};
+
+#[derive(Debug, Clone)]
+pub struct CommandConfig {
+    pub build_config: BuildConfig,
+    pub cmdline: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CommandResult {
+    pub cmdline: String,
+    pub success: bool,
+    /// Interleaved stdout and stderr lines, in the order they were produced
+    pub output: Vec<String>,
+}
+
+fn run_shell_command_job(
+    context: &JobContext,
+    cancel: CancellationToken,
+    config: CommandConfig,
+) -> Result<Box<CommandResult>> {
+    update_status(context, config.cmdline.clone(), 0, 1, &cancel)?;
+    let mut output = vec![];
+    let on_output = &mut |line: &str| {
+        context.push_output(line);
+        output.push(line.to_string());
+    };
+    let monitor = Some(BuildMonitor { on_output, cancel: &cancel });
+    let status = run_shell_command(&config.build_config, &config.cmdline, monitor);
+    if output.is_empty() {
+        // Errors starting the process aren't reported through the monitor
+        output.extend(status.stderr.lines().map(str::to_string));
+    } else if cancel.is_cancelled() {
+        output.push("Cancelled".to_string());
+    }
+    Ok(Box::new(CommandResult { cmdline: config.cmdline, success: status.success, output }))
+}
+
+/// Runs a command line in the project directory, streaming its output to the job status.
+pub fn start_command(waker: Waker, config: CommandConfig) -> JobState {
+    start_job(waker, "Run command", Job::Command, move |context, cancel| {
+        run_shell_command_job(&context, cancel, config)
+            .map(|result| JobResult::Command(Some(result)))
+    })
+}
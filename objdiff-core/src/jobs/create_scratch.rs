@@ -1,10 +1,11 @@
-use std::{fs, path::PathBuf, sync::mpsc::Receiver, task::Waker};
+use std::{fs, path::PathBuf, task::Waker};
 
 use anyhow::{anyhow, bail, Context, Result};
 
 use crate::{
-    build::{run_make, BuildConfig, BuildStatus},
+    build::{run_make, BuildConfig, BuildMonitor, BuildStatus},
     jobs::{start_job, update_status, Job, JobContext, JobResult, JobState},
+    util::CancellationToken,
 };
 
 #[derive(Debug, Clone)]
@@ -37,7 +38,7 @@ const API_HOST: &str = "https://decomp.me";
 
 fn run_create_scratch(
     status: &JobContext,
-    cancel: Receiver<()>,
+    cancel: CancellationToken,
     config: CreateScratchConfig,
 ) -> Result<Box<CreateScratchResult>> {
     let project_dir =
@@ -47,7 +48,9 @@ fn run_create_scratch(
     if let Some(context_path) = &config.context_path {
         if config.build_context {
             update_status(status, "Building context".to_string(), 0, 2, &cancel)?;
-            match run_make(&config.build_config, context_path) {
+            let on_output = &mut |line: &str| status.push_output(line);
+            let monitor = Some(BuildMonitor { on_output, cancel: &cancel });
+            match run_make(&config.build_config, context_path, monitor) {
                 BuildStatus { success: true, .. } => {}
                 BuildStatus { success: false, stdout, stderr, .. } => {
                     bail!("Failed to build context:\n{stdout}\n{stderr}")
@@ -0,0 +1,49 @@
+use std::{path::PathBuf, task::Waker};
+
+use anyhow::Result;
+
+use crate::{
+    diff::DiffObjConfig,
+    jobs::{start_job, update_status, Job, JobContext, JobResult, JobState},
+    obj::{read, ObjSymbolKind},
+    util::CancellationToken,
+};
+
+pub struct LoadSymbolsConfig {
+    pub path: PathBuf,
+    pub diff_obj_config: DiffObjConfig,
+}
+
+pub struct LoadSymbolsResult {
+    pub path: PathBuf,
+    /// Function names paired with their demangled names, or the load error.
+    pub symbols: Result<Vec<(String, Option<String>)>, String>,
+}
+
+fn run_load_symbols(
+    context: &JobContext,
+    cancel: CancellationToken,
+    config: LoadSymbolsConfig,
+) -> Result<Box<LoadSymbolsResult>> {
+    update_status(context, format!("Loading {}", config.path.display()), 0, 1, &cancel)?;
+    let symbols = read::read(&config.path, &config.diff_obj_config)
+        .map(|obj| {
+            obj.sections
+                .iter()
+                .flat_map(|s| &s.symbols)
+                .filter(|s| s.kind == ObjSymbolKind::Function)
+                .map(|s| (s.name.clone(), s.demangled_name.clone()))
+                .collect()
+        })
+        .map_err(|e| format!("{e:#}"));
+    update_status(context, "Complete".to_string(), 1, 1, &cancel)?;
+    Ok(Box::new(LoadSymbolsResult { path: config.path, symbols }))
+}
+
+/// Reads an object to list its functions, e.g. to pick a symbol from another unit.
+pub fn start_load_symbols(waker: Waker, config: LoadSymbolsConfig) -> JobState {
+    start_job(waker, "Load symbols", Job::LoadSymbols, move |context, cancel| {
+        run_load_symbols(&context, cancel, config)
+            .map(|result| JobResult::LoadSymbols(Some(result)))
+    })
+}
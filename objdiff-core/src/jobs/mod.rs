@@ -1,23 +1,35 @@
 use std::{
+    collections::VecDeque,
     sync::{
         atomic::{AtomicUsize, Ordering},
-        mpsc::{Receiver, Sender, TryRecvError},
-        Arc, RwLock,
+        Arc, Condvar, Mutex, RwLock,
     },
     task::Waker,
     thread::JoinHandle,
+    time::Duration,
 };
 
 use anyhow::Result;
 
-use crate::jobs::{
-    check_update::CheckUpdateResult, create_scratch::CreateScratchResult, objdiff::ObjDiffResult,
-    update::UpdateResult,
+use crate::{
+    jobs::{
+        check_update::CheckUpdateResult,
+        command::CommandResult,
+        create_scratch::CreateScratchResult,
+        load_symbols::LoadSymbolsResult,
+        objdiff::{LoadPreview, ObjDiffResult},
+        symbol_index::SymbolIndexResult,
+        update::UpdateResult,
+    },
+    util::CancellationToken,
 };
 
 pub mod check_update;
+pub mod command;
 pub mod create_scratch;
+pub mod load_symbols;
 pub mod objdiff;
+pub mod symbol_index;
 pub mod update;
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
@@ -26,9 +38,39 @@ pub enum Job {
     CheckUpdate,
     Update,
     CreateScratch,
+    Command,
+    SymbolIndex,
+    LoadSymbols,
+}
+
+/// Scheduling priority of a job. When the number of running jobs is limited, waiting jobs
+/// start in priority order, then in the order they were queued.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub enum JobPriority {
+    /// Report generation and other batch work
+    Report,
+    /// Indexing, update checks and commands that run in the background
+    Background,
+    /// Jobs the user is waiting on, such as rebuilding the selected unit
+    Interactive,
+}
+
+impl Job {
+    pub fn priority(self) -> JobPriority {
+        match self {
+            Job::ObjDiff | Job::Update | Job::CreateScratch | Job::LoadSymbols => {
+                JobPriority::Interactive
+            }
+            Job::CheckUpdate | Job::Command | Job::SymbolIndex => JobPriority::Background,
+        }
+    }
 }
+
 pub static JOB_ID: AtomicUsize = AtomicUsize::new(0);
 
+/// Maximum number of lines kept in [`JobStatus::output`].
+pub const MAX_OUTPUT_LINES: usize = 500;
+
 #[derive(Default)]
 pub struct JobQueue {
     pub jobs: Vec<JobState>,
@@ -36,6 +78,13 @@ pub struct JobQueue {
 }
 
 impl JobQueue {
+    /// Limits the number of jobs running at once, or 0 for no limit. Jobs started past the
+    /// limit wait for a running job to finish.
+    pub fn set_max_concurrent(&mut self, max: usize) { SCHEDULER.set_max_concurrent(max); }
+
+    /// The maximum number of jobs running at once, or 0 for no limit.
+    pub fn max_concurrent(&self) -> usize { SCHEDULER.max_concurrent() }
+
     /// Adds a job to the queue.
     #[inline]
     pub fn push(&mut self, state: JobState) { self.jobs.push(state); }
@@ -128,6 +177,8 @@ impl JobQueue {
                             progress_percent: 0.0,
                             progress_items: None,
                             status: String::new(),
+                            output: VecDeque::new(),
+                            load_preview: None,
                             error: Some(err),
                         }));
                     }
@@ -139,18 +190,127 @@ impl JobQueue {
     }
 }
 
+struct SchedulerState {
+    running: usize,
+    max_concurrent: usize,
+    /// Waiting jobs by priority and ID.
+    waiting: Vec<(JobPriority, usize)>,
+}
+
+impl SchedulerState {
+    /// Whether the job is the next to run and a slot is free.
+    fn can_start(&self, priority: JobPriority, id: usize) -> bool {
+        if self.max_concurrent != 0 && self.running >= self.max_concurrent {
+            return false;
+        }
+        let next = self.waiting.iter().max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+        next == Some(&(priority, id))
+    }
+}
+
+/// Limits the number of running jobs, shared by all [`JobQueue`]s.
+struct JobScheduler {
+    state: Mutex<SchedulerState>,
+    condvar: Condvar,
+}
+
+static SCHEDULER: JobScheduler = JobScheduler {
+    state: Mutex::new(SchedulerState { running: 0, max_concurrent: 0, waiting: Vec::new() }),
+    condvar: Condvar::new(),
+};
+
+/// Holds a job's slot in the [`JobScheduler`] until dropped.
+struct JobPermit;
+
+impl Drop for JobPermit {
+    fn drop(&mut self) {
+        if let Ok(mut state) = SCHEDULER.state.lock() {
+            state.running -= 1;
+        }
+        SCHEDULER.condvar.notify_all();
+    }
+}
+
+impl JobScheduler {
+    fn set_max_concurrent(&self, max: usize) {
+        if let Ok(mut state) = self.state.lock() {
+            if state.max_concurrent == max {
+                return;
+            }
+            state.max_concurrent = max;
+        }
+        self.condvar.notify_all();
+    }
+
+    fn max_concurrent(&self) -> usize { self.state.lock().map_or(0, |s| s.max_concurrent) }
+
+    /// Blocks until the job may run, or returns an error if it's cancelled while waiting.
+    fn acquire(
+        &self,
+        priority: JobPriority,
+        id: usize,
+        cancel: &CancellationToken,
+    ) -> Result<JobPermit> {
+        let mut state =
+            self.state.lock().map_err(|_| anyhow::Error::msg("Failed to lock job scheduler"))?;
+        state.waiting.push((priority, id));
+        loop {
+            if cancel.is_cancelled() || state.can_start(priority, id) {
+                state.waiting.retain(|&w| w != (priority, id));
+                // Another job may be able to start in this one's place
+                self.condvar.notify_all();
+                cancel.check()?;
+                state.running += 1;
+                return Ok(JobPermit);
+            }
+            // Wake periodically to observe cancellation
+            state = self
+                .condvar
+                .wait_timeout(state, Duration::from_millis(100))
+                .map_err(|_| anyhow::Error::msg("Failed to lock job scheduler"))?
+                .0;
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct JobContext {
     pub status: Arc<RwLock<JobStatus>>,
     pub waker: Waker,
 }
 
+impl JobContext {
+    /// Appends a line of live build output, discarding the oldest lines past
+    /// [`MAX_OUTPUT_LINES`].
+    pub fn push_output(&self, line: &str) {
+        if let Ok(mut w) = self.status.write() {
+            if w.output.len() >= MAX_OUTPUT_LINES {
+                w.output.pop_front();
+            }
+            w.output.push_back(line.to_string());
+        }
+        self.waker.wake_by_ref();
+    }
+
+    pub fn clear_output(&self) {
+        if let Ok(mut w) = self.status.write() {
+            w.output.clear();
+        }
+    }
+}
+
 pub struct JobState {
     pub id: usize,
     pub kind: Job,
     pub handle: Option<JoinHandle<JobResult>>,
     pub context: JobContext,
-    pub cancel: Sender<()>,
+    pub cancel: CancellationToken,
+}
+
+impl Drop for JobState {
+    /// Cancels the job when it's dropped, so an abandoned job stops running and releases its
+    /// scheduler slot.
+    fn drop(&mut self) { self.cancel.cancel(); }
 }
 
 #[derive(Default)]
@@ -159,6 +319,10 @@ pub struct JobStatus {
     pub progress_percent: f32,
     pub progress_items: Option<[u32; 2]>,
     pub status: String,
+    /// Most recent output of the running build process.
+    pub output: VecDeque<String>,
+    /// Partially loaded objects, shown while a large object is being parsed.
+    pub load_preview: Option<LoadPreview>,
     pub error: Option<anyhow::Error>,
 }
 
@@ -168,43 +332,52 @@ pub enum JobResult {
     CheckUpdate(Option<Box<CheckUpdateResult>>),
     Update(Box<UpdateResult>),
     CreateScratch(Option<Box<CreateScratchResult>>),
-}
-
-fn should_cancel(rx: &Receiver<()>) -> bool {
-    match rx.try_recv() {
-        Ok(_) | Err(TryRecvError::Disconnected) => true,
-        Err(_) => false,
-    }
+    Command(Option<Box<CommandResult>>),
+    SymbolIndex(Option<Box<SymbolIndexResult>>),
+    LoadSymbols(Option<Box<LoadSymbolsResult>>),
 }
 
 fn start_job(
     waker: Waker,
     title: &str,
     kind: Job,
-    run: impl FnOnce(JobContext, Receiver<()>) -> Result<JobResult> + Send + 'static,
+    run: impl FnOnce(JobContext, CancellationToken) -> Result<JobResult> + Send + 'static,
 ) -> JobState {
     let status = Arc::new(RwLock::new(JobStatus {
         title: title.to_string(),
         progress_percent: 0.0,
         progress_items: None,
-        status: String::new(),
+        status: "Waiting…".to_string(),
+        output: VecDeque::new(),
+        load_preview: None,
         error: None,
     }));
     let context = JobContext { status: status.clone(), waker: waker.clone() };
     let context_inner = JobContext { status: status.clone(), waker };
-    let (tx, rx) = std::sync::mpsc::channel();
-    let handle = std::thread::spawn(move || match run(context_inner, rx) {
-        Ok(state) => state,
-        Err(e) => {
+    let cancel = CancellationToken::new();
+    let cancel_inner = cancel.clone();
+    let id = JOB_ID.fetch_add(1, Ordering::Relaxed);
+    let handle = std::thread::spawn(move || {
+        let result = SCHEDULER.acquire(kind.priority(), id, &cancel_inner).and_then(|permit| {
             if let Ok(mut w) = status.write() {
-                w.error = Some(e);
+                w.status.clear();
+            }
+            let result = run(context_inner, cancel_inner);
+            drop(permit);
+            result
+        });
+        match result {
+            Ok(state) => state,
+            Err(e) => {
+                if let Ok(mut w) = status.write() {
+                    w.error = Some(e);
+                }
+                JobResult::None
             }
-            JobResult::None
         }
     });
-    let id = JOB_ID.fetch_add(1, Ordering::Relaxed);
     // log::info!("Started job {}", id); TODO
-    JobState { id, kind, handle: Some(handle), context, cancel: tx }
+    JobState { id, kind, handle: Some(handle), context, cancel }
 }
 
 fn update_status(
@@ -212,15 +385,15 @@ fn update_status(
     str: String,
     count: u32,
     total: u32,
-    cancel: &Receiver<()>,
+    cancel: &CancellationToken,
 ) -> Result<()> {
     let mut w =
         context.status.write().map_err(|_| anyhow::Error::msg("Failed to lock job status"))?;
     w.progress_items = Some([count, total]);
     w.progress_percent = count as f32 / total as f32;
-    if should_cancel(cancel) {
+    if cancel.is_cancelled() {
         w.status = "Cancelled".to_string();
-        return Err(anyhow::Error::msg("Cancelled"));
+        return cancel.check();
     } else {
         w.status = str;
     }
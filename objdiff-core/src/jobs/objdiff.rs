@@ -1,14 +1,31 @@
-use std::{path::PathBuf, sync::mpsc::Receiver, task::Waker};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    task::{Wake, Waker},
+};
 
 use anyhow::{anyhow, Error, Result};
+use globset::Glob;
 use time::OffsetDateTime;
 
 use crate::{
-    build::{run_make, BuildConfig, BuildStatus},
-    config::SymbolMappings,
-    diff::{diff_objs, DiffObjConfig, MappingConfig, ObjDiff},
+    build::{
+        fingerprint_unit_inputs, run_compile_command, run_make, BuildCache, BuildConfig,
+        BuildMonitor, BuildStatus,
+    },
+    config::{compile_commands::CompileCommand, SymbolImport, SymbolMappings},
+    diff::{
+        diff_objs_with_progress, AlignmentAnchors, DiffObjConfig, MappingConfig, ObjDiff,
+        SizeMismatchFixes,
+    },
     jobs::{start_job, update_status, Job, JobContext, JobResult, JobState},
-    obj::{read, ObjInfo},
+    obj::{
+        cache::ObjCache,
+        read::{self, LoadProgress},
+        symbol_import::import_symbols,
+        ObjInfo, ObjSectionKind,
+    },
+    util::{CancellationToken, Stopwatch, Timings},
 };
 
 pub struct ObjDiffConfig {
@@ -17,10 +34,31 @@ pub struct ObjDiffConfig {
     pub build_target: bool,
     pub target_path: Option<PathBuf>,
     pub base_path: Option<PathBuf>,
+    /// Compiler invocation used to rebuild the base object instead of the build system.
+    pub base_build_command: Option<CompileCommand>,
     pub diff_obj_config: DiffObjConfig,
     pub symbol_mappings: SymbolMappings,
     pub selecting_left: Option<String>,
     pub selecting_right: Option<String>,
+    /// Symbol name patterns limiting the candidates offered while selecting a mapping.
+    pub mapping_include: Vec<Glob>,
+    pub mapping_exclude: Vec<Glob>,
+    /// Manual instruction alignment constraints, keyed by left symbol name.
+    pub alignment_anchors: AlignmentAnchors,
+    /// How functions whose size differs between both sides are diffed, keyed by left symbol
+    /// name.
+    pub size_fixes: SizeMismatchFixes,
+    /// If set, objects whose watched inputs are unchanged since their last build are not rebuilt.
+    pub build_cache: Option<BuildCache>,
+    /// Source file of the unit, relative to the project directory. Fingerprinted along with the
+    /// objects' dependencies for the build cache.
+    pub source_path: Option<PathBuf>,
+    /// Always run the build system, ignoring the build cache.
+    pub force_build: bool,
+    /// If set, unchanged objects are taken from the cache instead of being read again.
+    pub obj_cache: Option<ObjCache>,
+    /// Function list used to symbolize the target object after it's read.
+    pub target_symbol_import: Option<SymbolImport>,
 }
 
 pub struct ObjDiffResult {
@@ -29,11 +67,85 @@ pub struct ObjDiffResult {
     pub first_obj: Option<(ObjInfo, ObjDiff)>,
     pub second_obj: Option<(ObjInfo, ObjDiff)>,
     pub time: OffsetDateTime,
+    /// The configuration the objects were read and diffed with
+    pub diff_obj_config: DiffObjConfig,
+    /// Time spent loading and diffing the objects, by stage
+    pub timings: Timings,
+}
+
+/// Partial results of the objects being loaded by a build job.
+#[derive(Debug, Clone, Default)]
+pub struct LoadPreview {
+    pub target: Option<ObjPreview>,
+    pub base: Option<ObjPreview>,
+}
+
+/// An object whose sections are known, with their symbols filled in as they're parsed.
+#[derive(Debug, Clone, Default)]
+pub struct ObjPreview {
+    pub sections: Vec<SectionPreview>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SectionPreview {
+    pub name: String,
+    pub kind: ObjSectionKind,
+    pub size: u64,
+    /// Display names of the section's symbols, or `None` if they're not parsed yet
+    pub symbols: Option<Vec<String>>,
+}
+
+/// Reads an object, publishing its sections and symbols to the job status as they're parsed.
+fn load_obj(
+    context: &JobContext,
+    path: &Path,
+    config: &DiffObjConfig,
+    cancel: &CancellationToken,
+    is_base: bool,
+) -> Result<ObjInfo> {
+    let mut on_progress = |progress: LoadProgress<'_>| {
+        let Ok(mut w) = context.status.write() else {
+            return;
+        };
+        let preview = w.load_preview.get_or_insert_with(Default::default);
+        let obj = if is_base { &mut preview.base } else { &mut preview.target };
+        match progress {
+            LoadProgress::Sections(sections) => {
+                *obj = Some(ObjPreview {
+                    sections: sections
+                        .iter()
+                        .map(|section| SectionPreview {
+                            name: section.name.clone(),
+                            kind: section.kind,
+                            size: section.size,
+                            symbols: None,
+                        })
+                        .collect(),
+                });
+            }
+            LoadProgress::Symbols(index, section) => {
+                if let Some(preview) = obj.as_mut().and_then(|obj| obj.sections.get_mut(index)) {
+                    preview.symbols = Some(
+                        section
+                            .symbols
+                            .iter()
+                            .map(|symbol| {
+                                symbol.demangled_name.clone().unwrap_or_else(|| symbol.name.clone())
+                            })
+                            .collect(),
+                    );
+                }
+            }
+        }
+        drop(w);
+        context.waker.wake_by_ref();
+    };
+    read::read_with_progress(path, config, cancel, &mut on_progress)
 }
 
 fn run_build(
     context: &JobContext,
-    cancel: Receiver<()>,
+    cancel: CancellationToken,
     mut config: ObjDiffConfig,
 ) -> Result<Box<ObjDiffResult>> {
     // Use the per-object symbol mappings, we don't set mappings globally
@@ -41,6 +153,10 @@ fn run_build(
         mappings: config.symbol_mappings,
         selecting_left: config.selecting_left,
         selecting_right: config.selecting_right,
+        candidate_include: config.mapping_include,
+        candidate_exclude: config.mapping_exclude,
+        alignment_anchors: config.alignment_anchors,
+        size_fixes: config.size_fixes,
     };
 
     let mut target_path_rel = None;
@@ -61,13 +177,18 @@ fn run_build(
             })?);
         }
         if let Some(base_path) = &config.base_path {
-            base_path_rel = Some(base_path.strip_prefix(project_dir).map_err(|_| {
-                anyhow!(
-                    "Base path '{}' doesn't begin with '{}'",
-                    base_path.display(),
-                    project_dir.display()
-                )
-            })?);
+            base_path_rel = Some(match base_path.strip_prefix(project_dir) {
+                Ok(path) => path,
+                // Compile commands may write objects outside the project directory
+                Err(_) if config.base_build_command.is_some() => base_path.as_path(),
+                Err(_) => {
+                    return Err(anyhow!(
+                        "Base path '{}' doesn't begin with '{}'",
+                        base_path.display(),
+                        project_dir.display()
+                    ))
+                }
+            });
         };
     }
 
@@ -85,6 +206,47 @@ fn run_build(
         total += 1;
     }
 
+    // Fingerprint the unit's inputs so that up-to-date objects can skip the build
+    let obj_paths = [
+        target_path_rel.filter(|_| config.build_target),
+        base_path_rel.filter(|_| config.build_base),
+    ];
+    let obj_paths = obj_paths.into_iter().flatten().collect::<Vec<_>>();
+    let input_fingerprint = || {
+        let project_dir = config.build_config.project_dir.as_ref()?;
+        fingerprint_unit_inputs(project_dir, config.source_path.as_deref(), &obj_paths)
+    };
+    let fingerprint = match &config.build_cache {
+        Some(_) if !config.force_build && !obj_paths.is_empty() => input_fingerprint(),
+        _ => None,
+    };
+    let mut built = Vec::new();
+    let mut build = |path_rel: &Path, command: Option<&CompileCommand>| -> BuildStatus {
+        if let (Some(cache), Some(fingerprint), Some(project_dir)) =
+            (&config.build_cache, fingerprint, &config.build_config.project_dir)
+        {
+            if cache.is_fresh(project_dir, path_rel, fingerprint) {
+                return BuildStatus {
+                    stdout: format!("Inputs unchanged, skipped building {}", path_rel.display()),
+                    ..Default::default()
+                };
+            }
+        }
+        context.clear_output();
+        let on_output = &mut |line: &str| context.push_output(line);
+        let monitor = Some(BuildMonitor { on_output, cancel: &cancel });
+        let status = match command {
+            Some(command) => run_compile_command(&command.directory, &command.arguments, monitor),
+            None => run_make(&config.build_config, path_rel, monitor),
+        };
+        if status.success {
+            built.push(path_rel.to_path_buf());
+        } else if let Some(cache) = &config.build_cache {
+            cache.remove(path_rel);
+        }
+        status
+    };
+
     let mut step_idx = 0;
     let mut first_status = match target_path_rel {
         Some(target_path_rel) if config.build_target => {
@@ -96,7 +258,7 @@ fn run_build(
                 &cancel,
             )?;
             step_idx += 1;
-            run_make(&config.build_config, target_path_rel)
+            build(target_path_rel, None)
         }
         _ => BuildStatus::default(),
     };
@@ -111,12 +273,25 @@ fn run_build(
                 &cancel,
             )?;
             step_idx += 1;
-            run_make(&config.build_config, base_path_rel)
+            build(base_path_rel, config.base_build_command.as_ref())
         }
         _ => BuildStatus::default(),
     };
 
+    // Record the inputs the objects were built from. The build may have updated the
+    // dependency files, so the fingerprint is taken again afterwards.
+    if let Some(cache) = &config.build_cache {
+        if !built.is_empty() {
+            if let Some(fingerprint) = input_fingerprint() {
+                for path_rel in &built {
+                    cache.insert(path_rel, fingerprint);
+                }
+            }
+        }
+    }
+
     let time = OffsetDateTime::now_utc();
+    let mut timings = Timings::default();
 
     let first_obj = match &config.target_path {
         Some(target_path) if first_status.success => {
@@ -128,8 +303,29 @@ fn run_build(
                 &cancel,
             )?;
             step_idx += 1;
-            match read::read(target_path, &config.diff_obj_config) {
-                Ok(obj) => Some(obj),
+            let start = Stopwatch::start();
+            // A symbolized object may be cached, so always read it again to pick up changes to
+            // the function list
+            let cached = config
+                .obj_cache
+                .as_ref()
+                .filter(|_| config.target_symbol_import.is_none())
+                .and_then(|c| c.take(target_path, &config.diff_obj_config));
+            match cached
+                .map_or_else(
+                    || load_obj(context, target_path, &config.diff_obj_config, &cancel, false),
+                    Ok,
+                )
+                .and_then(|mut obj| {
+                    if let Some(import) = &config.target_symbol_import {
+                        import_symbols(&mut obj, import)?;
+                    }
+                    Ok(obj)
+                }) {
+                Ok(obj) => {
+                    timings.add("Load target", start.elapsed());
+                    Some(obj)
+                }
                 Err(e) => {
                     first_status = BuildStatus {
                         success: false,
@@ -158,8 +354,17 @@ fn run_build(
                 &cancel,
             )?;
             step_idx += 1;
-            match read::read(base_path, &config.diff_obj_config) {
-                Ok(obj) => Some(obj),
+            let start = Stopwatch::start();
+            let cached =
+                config.obj_cache.as_ref().and_then(|c| c.take(base_path, &config.diff_obj_config));
+            match cached.map_or_else(
+                || load_obj(context, base_path, &config.diff_obj_config, &cancel, true),
+                Ok,
+            ) {
+                Ok(obj) => {
+                    timings.add("Load base", start.elapsed());
+                    Some(obj)
+                }
                 Err(e) => {
                     second_status = BuildStatus {
                         success: false,
@@ -179,8 +384,23 @@ fn run_build(
     };
 
     update_status(context, "Performing diff".to_string(), step_idx, total, &cancel)?;
+    let diff_step = step_idx as f32;
     step_idx += 1;
-    let result = diff_objs(&config.diff_obj_config, first_obj.as_ref(), second_obj.as_ref(), None)?;
+    let result = diff_objs_with_progress(
+        &config.diff_obj_config,
+        first_obj.as_ref(),
+        second_obj.as_ref(),
+        None,
+        &cancel,
+        |count, symbols| {
+            if let Ok(mut w) = context.status.write() {
+                w.progress_percent =
+                    (diff_step + count as f32 / symbols.max(1) as f32) / total as f32;
+            }
+        },
+    )?;
+
+    timings.extend(&result.timings);
 
     update_status(context, "Complete".to_string(), step_idx, total, &cancel)?;
     Ok(Box::new(ObjDiffResult {
@@ -189,6 +409,8 @@ fn run_build(
         first_obj: first_obj.and_then(|o| result.left.map(|d| (o, d))),
         second_obj: second_obj.and_then(|o| result.right.map(|d| (o, d))),
         time,
+        diff_obj_config: config.diff_obj_config,
+        timings,
     }))
 }
 
@@ -197,3 +419,24 @@ pub fn start_build(waker: Waker, config: ObjDiffConfig) -> JobState {
         run_build(&context, cancel, config).map(|result| JobResult::ObjDiff(Some(result)))
     })
 }
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+/// Runs a build job and waits for it to finish, for frontends without an event loop.
+pub fn run_build_blocking(config: ObjDiffConfig) -> Result<Box<ObjDiffResult>> {
+    let mut job = start_build(Waker::from(Arc::new(NoopWaker)), config);
+    let handle = job.handle.take().ok_or_else(|| anyhow!("Job not started"))?;
+    match handle.join() {
+        Ok(JobResult::ObjDiff(Some(result))) => Ok(result),
+        Ok(_) => {
+            let mut status =
+                job.context.status.write().map_err(|_| anyhow!("Failed to lock job status"))?;
+            Err(status.error.take().unwrap_or_else(|| anyhow!("Build failed")))
+        }
+        Err(_) => Err(anyhow!("Build job panicked")),
+    }
+}
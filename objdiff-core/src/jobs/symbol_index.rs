@@ -0,0 +1,79 @@
+use std::task::Waker;
+
+use anyhow::{Context, Result};
+
+use crate::{
+    bindings::report::{Report, ReportUnit, ReportUnitMetadata},
+    config::ProjectObject,
+    diff::{diff_objs, DiffObjConfig},
+    jobs::{start_job, update_status, Job, JobContext, JobResult, JobState},
+    obj::read,
+    util::CancellationToken,
+};
+
+pub struct SymbolIndexConfig {
+    /// Units to index, with resolved paths
+    pub objects: Vec<ProjectObject>,
+    pub diff_obj_config: DiffObjConfig,
+}
+
+/// A project-wide index of functions and their match percents, in the same format as a
+/// progress report.
+pub struct SymbolIndexResult {
+    pub report: Report,
+    /// Units that failed to load, with the error
+    pub errors: Vec<(String, String)>,
+}
+
+fn index_object(object: &ProjectObject, config: &DiffObjConfig) -> Result<Option<ReportUnit>> {
+    if object.target_path.is_none() && object.base_path.is_none() {
+        return Ok(None);
+    }
+    let config = DiffObjConfig {
+        symbol_matching: object.symbol_matching().unwrap_or(config.symbol_matching),
+        ..config.clone()
+    };
+    let read_obj = |path: &std::path::Path| {
+        read::read(path, &config).with_context(|| format!("Failed to open {}", path.display()))
+    };
+    let target = object.target_path.as_deref().map(read_obj).transpose()?;
+    let base = object.base_path.as_deref().map(read_obj).transpose()?;
+    let result = diff_objs(&config, target.as_ref(), base.as_ref(), None)?;
+    let metadata = ReportUnitMetadata { complete: object.complete(), ..Default::default() };
+    Ok(Some(ReportUnit::from_diff(
+        object.name().to_string(),
+        target.as_ref(),
+        base.as_ref(),
+        &result,
+        metadata,
+        None,
+    )))
+}
+
+fn run_symbol_index(
+    context: &JobContext,
+    cancel: CancellationToken,
+    config: SymbolIndexConfig,
+) -> Result<Box<SymbolIndexResult>> {
+    let total = config.objects.len() as u32;
+    let mut units = vec![];
+    let mut errors = vec![];
+    for (i, object) in config.objects.iter().enumerate() {
+        update_status(context, format!("Indexing {}", object.name()), i as u32, total, &cancel)?;
+        match index_object(object, &config.diff_obj_config) {
+            Ok(Some(unit)) => units.push(unit),
+            Ok(None) => {}
+            Err(e) => errors.push((object.name().to_string(), format!("{e:#}"))),
+        }
+    }
+    update_status(context, "Complete".to_string(), total, total, &cancel)?;
+    Ok(Box::new(SymbolIndexResult { report: Report { units, ..Default::default() }, errors }))
+}
+
+/// Diffs every unit of the project to find which unit each function belongs to.
+pub fn start_symbol_index(waker: Waker, config: SymbolIndexConfig) -> JobState {
+    start_job(waker, "Index symbols", Job::SymbolIndex, move |context, cancel| {
+        run_symbol_index(&context, cancel, config)
+            .map(|result| JobResult::SymbolIndex(Some(result)))
+    })
+}
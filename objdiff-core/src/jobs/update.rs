@@ -2,7 +2,6 @@ use std::{
     env::{current_dir, current_exe},
     fs::File,
     path::PathBuf,
-    sync::mpsc::Receiver,
     task::Waker,
 };
 
@@ -10,7 +9,10 @@ use anyhow::{Context, Result};
 pub use self_update; // Re-export self_update crate
 use self_update::update::ReleaseUpdate;
 
-use crate::jobs::{start_job, update_status, Job, JobContext, JobResult, JobState};
+use crate::{
+    jobs::{start_job, update_status, Job, JobContext, JobResult, JobState},
+    util::CancellationToken,
+};
 
 pub struct UpdateConfig {
     pub build_updater: fn() -> Result<Box<dyn ReleaseUpdate>>,
@@ -23,7 +25,7 @@ pub struct UpdateResult {
 
 fn run_update(
     status: &JobContext,
-    cancel: Receiver<()>,
+    cancel: CancellationToken,
     config: UpdateConfig,
 ) -> Result<Box<UpdateResult>> {
     update_status(status, "Fetching latest release".to_string(), 0, 3, &cancel)?;
@@ -12,5 +12,9 @@ pub mod diff;
 pub mod jobs;
 #[cfg(feature = "any-arch")]
 pub mod obj;
+#[cfg(feature = "build")]
+pub mod session;
+#[cfg(feature = "test-util")]
+pub mod test_util;
 #[cfg(feature = "any-arch")]
 pub mod util;
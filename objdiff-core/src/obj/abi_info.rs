@@ -0,0 +1,270 @@
+//! ABI metadata sections: MIPS `.reginfo` and `.MIPS.abiflags`, and PowerPC
+//! `.PPC.EMB.apuinfo`.
+//!
+//! These sections record the register usage, ISA level, floating-point ABI and processor
+//! extensions an object was built for. Toolchains fill them in differently, and a mismatch can
+//! make objects incompatible at link time even when the code matches.
+
+use object::{Endianness, File, Object, ObjectSection};
+
+/// A decoded ABI metadata section.
+#[derive(Debug, Clone)]
+pub struct AbiSection {
+    pub name: String,
+    pub fields: Vec<AbiField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiField {
+    pub name: String,
+    pub value: String,
+}
+
+impl AbiField {
+    fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self { name: name.into(), value: value.into() }
+    }
+}
+
+/// A field of an ABI metadata section compared between both objects.
+#[derive(Debug, Clone)]
+pub struct AbiFieldDiff {
+    pub section: String,
+    pub field: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+impl AbiFieldDiff {
+    #[inline]
+    pub fn is_match(&self) -> bool { self.left == self.right }
+}
+
+fn read_u32(data: &[u8], offset: usize, endian: Endianness) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?.try_into().ok()?;
+    Some(match endian {
+        Endianness::Little => u32::from_le_bytes(bytes),
+        Endianness::Big => u32::from_be_bytes(bytes),
+    })
+}
+
+fn read_u16(data: &[u8], offset: usize, endian: Endianness) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?.try_into().ok()?;
+    Some(match endian {
+        Endianness::Little => u16::from_le_bytes(bytes),
+        Endianness::Big => u16::from_be_bytes(bytes),
+    })
+}
+
+/// `Elf32_RegInfo`: the general and coprocessor registers used, and the initial `$gp` value.
+fn parse_reginfo(data: &[u8], endian: Endianness) -> Option<Vec<AbiField>> {
+    let mut fields =
+        vec![AbiField::new("gprmask", format!("{:#010x}", read_u32(data, 0, endian)?))];
+    for i in 0..4 {
+        let mask = read_u32(data, 4 + i * 4, endian)?;
+        fields.push(AbiField::new(format!("cprmask[{i}]"), format!("{mask:#010x}")));
+    }
+    fields.push(AbiField::new("gp_value", format!("{:#x}", read_u32(data, 20, endian)?)));
+    Some(fields)
+}
+
+fn mips_register_size(size: u8) -> String {
+    match size {
+        0 => "none".to_string(),
+        1 => "32-bit".to_string(),
+        2 => "64-bit".to_string(),
+        3 => "128-bit".to_string(),
+        _ => format!("unknown ({size})"),
+    }
+}
+
+fn mips_fp_abi(fp_abi: u8) -> String {
+    match fp_abi {
+        0 => "any".to_string(),
+        1 => "double-precision (-mdouble-float)".to_string(),
+        2 => "single-precision (-msingle-float)".to_string(),
+        3 => "soft-float".to_string(),
+        4 => "64-bit (-mips32r2 -mfp64, deprecated)".to_string(),
+        5 => "-mfpxx".to_string(),
+        6 => "-mgp32 -mfp64".to_string(),
+        7 => "-mgp32 -mfp64 -mno-odd-spreg".to_string(),
+        _ => format!("unknown ({fp_abi})"),
+    }
+}
+
+fn mips_isa_ext(ext: u32) -> String {
+    match ext {
+        0 => "none".to_string(),
+        1 => "RMI XLR".to_string(),
+        2 => "Cavium Networks Octeon2".to_string(),
+        3 => "Cavium Networks OcteonP".to_string(),
+        4 => "Loongson 3A".to_string(),
+        5 => "Cavium Networks Octeon".to_string(),
+        6 => "Toshiba R5900".to_string(),
+        7 => "MIPS R4650".to_string(),
+        8 => "LSI R4010".to_string(),
+        9 => "NEC VR4100".to_string(),
+        10 => "Toshiba R3900".to_string(),
+        11 => "MIPS R10000".to_string(),
+        12 => "Broadcom SB-1".to_string(),
+        13 => "NEC VR4111/VR4181".to_string(),
+        14 => "NEC VR4120".to_string(),
+        15 => "NEC VR5400".to_string(),
+        16 => "NEC VR5500".to_string(),
+        17 => "ST Microelectronics Loongson 2E".to_string(),
+        18 => "ST Microelectronics Loongson 2F".to_string(),
+        19 => "Cavium Networks Octeon3".to_string(),
+        _ => format!("unknown ({ext})"),
+    }
+}
+
+const MIPS_ASES: [(u32, &str); 13] = [
+    (0x1, "DSP"),
+    (0x2, "DSPR2"),
+    (0x4, "EVA"),
+    (0x8, "MCU"),
+    (0x10, "MDMX"),
+    (0x20, "MIPS-3D"),
+    (0x40, "MT"),
+    (0x80, "SmartMIPS"),
+    (0x100, "VZ"),
+    (0x200, "MSA"),
+    (0x400, "MIPS16"),
+    (0x800, "microMIPS"),
+    (0x1000, "XPA"),
+];
+
+fn mips_ases(ases: u32) -> String {
+    let known = MIPS_ASES.iter().fold(0, |acc, (bit, _)| acc | bit);
+    let mut names = MIPS_ASES
+        .iter()
+        .filter(|(bit, _)| ases & bit != 0)
+        .map(|(_, name)| name.to_string())
+        .collect::<Vec<_>>();
+    if ases & !known != 0 {
+        names.push(format!("{:#x}", ases & !known));
+    }
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+/// `Elf_MIPS_ABIFlags_v0`: the ISA level and extensions, register sizes and floating-point ABI.
+fn parse_abiflags(data: &[u8], endian: Endianness) -> Option<Vec<AbiField>> {
+    let version = read_u16(data, 0, endian)?;
+    let &[isa_level, isa_rev, gpr_size, cpr1_size, cpr2_size, fp_abi] = data.get(2..8)? else {
+        return None;
+    };
+    let isa = match isa_rev {
+        0 => format!("MIPS{isa_level}"),
+        rev => format!("MIPS{isa_level}r{rev}"),
+    };
+    let flags1 = read_u32(data, 16, endian)?;
+    Some(vec![
+        AbiField::new("version", version.to_string()),
+        AbiField::new("isa", isa),
+        AbiField::new("isa_ext", mips_isa_ext(read_u32(data, 8, endian)?)),
+        AbiField::new("ases", mips_ases(read_u32(data, 12, endian)?)),
+        AbiField::new("gpr_size", mips_register_size(gpr_size)),
+        AbiField::new("cpr1_size", mips_register_size(cpr1_size)),
+        AbiField::new("cpr2_size", mips_register_size(cpr2_size)),
+        AbiField::new("fp_abi", mips_fp_abi(fp_abi)),
+        AbiField::new(
+            "flags1",
+            if flags1 & 1 != 0 {
+                format!("{flags1:#x} (odd-spreg)")
+            } else {
+                format!("{flags1:#x}")
+            },
+        ),
+        AbiField::new("flags2", format!("{:#x}", read_u32(data, 20, endian)?)),
+    ])
+}
+
+fn apu_name(id: u16) -> Option<&'static str> {
+    Some(match id {
+        0x40 => "ISEL",
+        0x41 => "PMR",
+        0x42 => "RFMCI",
+        0x43 => "CACHELCK",
+        0x100 => "SPE",
+        0x101 => "EFS",
+        0x102 => "BRLOCK",
+        0x104 => "VLE",
+        _ => return None,
+    })
+}
+
+/// An `APUinfo` note listing the auxiliary processing units used, each as an ID and version.
+fn parse_apuinfo(data: &[u8], endian: Endianness) -> Option<Vec<AbiField>> {
+    let name_size = read_u32(data, 0, endian)? as usize;
+    let desc_size = read_u32(data, 4, endian)? as usize;
+    let desc_start = 12 + name_size.next_multiple_of(4);
+    let desc = data.get(desc_start..desc_start + desc_size)?;
+    let mut fields = vec![];
+    for offset in (0..desc.len() / 4).map(|i| i * 4) {
+        let value = read_u32(desc, offset, endian)?;
+        let id = (value >> 16) as u16;
+        let name = apu_name(id).map_or_else(|| format!("APU {id:#x}"), str::to_string);
+        fields.push(AbiField::new(name, format!("version {}", value & 0xFFFF)));
+    }
+    Some(fields)
+}
+
+/// Decodes the ABI metadata sections of an object. Sections that can't be decoded are
+/// skipped.
+pub fn parse_abi_sections(obj_file: &File) -> Vec<AbiSection> {
+    let endian = obj_file.endianness();
+    let mut sections = vec![];
+    for section in obj_file.sections() {
+        let Ok(name) = section.name() else {
+            continue;
+        };
+        let parse: fn(&[u8], Endianness) -> Option<Vec<AbiField>> = match name {
+            ".reginfo" => parse_reginfo,
+            ".MIPS.abiflags" => parse_abiflags,
+            ".PPC.EMB.apuinfo" => parse_apuinfo,
+            _ => continue,
+        };
+        let Some(fields) = section.data().ok().and_then(|data| parse(data, endian)) else {
+            log::warn!("Failed to decode {name}");
+            continue;
+        };
+        sections.push(AbiSection { name: name.to_string(), fields });
+    }
+    sections
+}
+
+/// Compares the ABI metadata sections of both objects field by field. Sections and fields only
+/// present in one object are included with the other side missing.
+pub fn diff_abi_sections(left: &[AbiSection], right: &[AbiSection]) -> Vec<AbiFieldDiff> {
+    let mut diffs = Vec::<AbiFieldDiff>::new();
+    for (sections, is_left) in [(left, true), (right, false)] {
+        for section in sections {
+            for field in &section.fields {
+                let existing =
+                    diffs.iter_mut().find(|d| d.section == section.name && d.field == field.name);
+                let diff = match existing {
+                    Some(diff) => diff,
+                    None => {
+                        diffs.push(AbiFieldDiff {
+                            section: section.name.clone(),
+                            field: field.name.clone(),
+                            left: None,
+                            right: None,
+                        });
+                        diffs.last_mut().unwrap()
+                    }
+                };
+                if is_left {
+                    diff.left = Some(field.value.clone());
+                } else {
+                    diff.right = Some(field.value.clone());
+                }
+            }
+        }
+    }
+    diffs
+}
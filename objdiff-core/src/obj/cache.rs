@@ -0,0 +1,247 @@
+use std::{
+    fs,
+    mem::{size_of, size_of_val},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use filetime::FileTime;
+
+use crate::{
+    diff::{
+        data_types::DataTypeSummary, DiffObjConfig, ObjDiff, ObjInsArgDiff, ObjInsDiff,
+        ObjSymbolDiff,
+    },
+    obj::{ObjInfo, ObjIns, ObjInsArg, ObjReloc, ObjSection, ObjSymbol, SymbolRef},
+};
+
+/// Default memory budget for loaded objects and diff results, in bytes.
+pub const DEFAULT_MEMORY_LIMIT: usize = 1024 * 1024 * 1024;
+
+/// Approximate heap and inline size of a value, in bytes. Used to keep memory usage of loaded
+/// objects under a budget, so it doesn't need to be exact.
+pub trait MemoryUsage {
+    fn memory_usage(&self) -> usize;
+}
+
+impl MemoryUsage for ObjSymbol {
+    fn memory_usage(&self) -> usize {
+        size_of::<Self>()
+            + self.name.capacity()
+            + self.demangled_name.as_ref().map_or(0, String::capacity)
+            + self.bytes.capacity()
+    }
+}
+
+impl MemoryUsage for ObjReloc {
+    fn memory_usage(&self) -> usize {
+        // The target symbol is stored inline
+        self.target.memory_usage() - size_of::<ObjSymbol>()
+            + size_of::<Self>()
+            + self.original.as_ref().map_or(0, |o| o.name.capacity())
+    }
+}
+
+impl MemoryUsage for ObjSection {
+    fn memory_usage(&self) -> usize {
+        size_of::<Self>()
+            + self.name.capacity()
+            + self.data.capacity()
+            + self.symbols.iter().map(MemoryUsage::memory_usage).sum::<usize>()
+            + self.relocations.iter().map(MemoryUsage::memory_usage).sum::<usize>()
+            // B-tree nodes add roughly a pointer per entry
+            + self.line_info.len() * (size_of::<(u64, u32)>() + size_of::<usize>())
+    }
+}
+
+impl MemoryUsage for ObjInfo {
+    fn memory_usage(&self) -> usize {
+        size_of::<Self>()
+            + self.sections.iter().map(MemoryUsage::memory_usage).sum::<usize>()
+            + self.common.iter().map(MemoryUsage::memory_usage).sum::<usize>()
+            + self
+                .diagnostics
+                .iter()
+                .map(|d| d.name.capacity() + d.reason.capacity())
+                .sum::<usize>()
+    }
+}
+
+impl MemoryUsage for ObjIns {
+    fn memory_usage(&self) -> usize {
+        size_of::<Self>()
+            + self.args.capacity() * size_of::<ObjInsArg>()
+            + self.reloc.as_ref().map_or(0, |r| r.memory_usage() - size_of::<ObjReloc>())
+            + self.formatted.capacity()
+            + self.orig.as_ref().map_or(0, String::capacity)
+            + self.raw_args.as_ref().map_or(0, |a| a.capacity() * size_of::<ObjInsArg>())
+    }
+}
+
+impl MemoryUsage for ObjInsDiff {
+    fn memory_usage(&self) -> usize {
+        size_of::<Self>()
+            + self.ins.as_ref().map_or(0, |ins| ins.memory_usage() - size_of::<ObjIns>())
+            + self.branch_from.as_ref().map_or(0, |b| b.ins_idx.capacity() * size_of::<usize>())
+            + self.arg_diff.capacity() * size_of::<Option<ObjInsArgDiff>>()
+    }
+}
+
+impl MemoryUsage for ObjSymbolDiff {
+    fn memory_usage(&self) -> usize {
+        size_of::<Self>() + self.instructions.iter().map(MemoryUsage::memory_usage).sum::<usize>()
+    }
+}
+
+impl MemoryUsage for ObjDiff {
+    fn memory_usage(&self) -> usize {
+        let sections = self.sections.iter().map(|section| {
+            section.symbols.iter().map(MemoryUsage::memory_usage).sum::<usize>()
+                + section
+                    .data_diff
+                    .iter()
+                    .map(|d| size_of_val(d) + d.data.capacity() + d.symbol.capacity())
+                    .sum::<usize>()
+        });
+        size_of::<Self>()
+            + sections.sum::<usize>()
+            + self.common.iter().map(MemoryUsage::memory_usage).sum::<usize>()
+            + self.mapping_symbols.iter().map(MemoryUsage::memory_usage).sum::<usize>()
+            + self.data_types.len() * (size_of::<SymbolRef>() + size_of::<DataTypeSummary>())
+    }
+}
+
+/// A loaded object held by [`ObjCache`].
+struct CacheEntry {
+    unit: String,
+    path: PathBuf,
+    timestamp: FileTime,
+    /// The configuration the object was read with
+    config: DiffObjConfig,
+    obj: ObjInfo,
+    size: usize,
+}
+
+/// Memory used by a cached object, as reported by [`ObjCache::usage`].
+#[derive(Debug, Clone)]
+pub struct CachedObjUsage {
+    pub unit: String,
+    pub path: PathBuf,
+    pub size: usize,
+}
+
+#[derive(Default)]
+struct ObjCacheInner {
+    /// Least recently used first
+    entries: Vec<CacheEntry>,
+    limit: Option<usize>,
+    /// Memory used by objects and diff results outside the cache
+    in_use: usize,
+}
+
+impl ObjCacheInner {
+    fn limit(&self) -> usize { self.limit.unwrap_or(DEFAULT_MEMORY_LIMIT) }
+
+    fn cached(&self) -> usize { self.entries.iter().map(|e| e.size).sum() }
+
+    /// Drops the least recently used objects until memory usage fits the limit.
+    fn evict(&mut self) {
+        let limit = self.limit();
+        let mut total = self.in_use + self.cached();
+        while total > limit && !self.entries.is_empty() {
+            let entry = self.entries.remove(0);
+            log::debug!("Evicting {} from the object cache ({} bytes)", entry.unit, entry.size);
+            total -= entry.size;
+        }
+    }
+}
+
+/// Objects that were loaded recently, so that switching back to a unit doesn't need to read
+/// and parse its objects again. Objects are reused only if the file is unchanged and the
+/// configuration they were read with matches.
+///
+/// The cache is bounded by a memory limit shared with the objects in use: when it's
+/// exceeded, the least recently used objects are evicted.
+#[derive(Clone, Default)]
+pub struct ObjCache(Arc<Mutex<ObjCacheInner>>);
+
+/// Settings that affect reading an object. Symbol mappings only affect the diff.
+fn read_config(config: &DiffObjConfig) -> DiffObjConfig {
+    DiffObjConfig { symbol_mappings: Default::default(), ..config.clone() }
+}
+
+impl ObjCache {
+    /// Sets the memory limit in bytes, evicting objects if it's exceeded.
+    pub fn set_limit(&self, limit: usize) {
+        if let Ok(mut inner) = self.0.lock() {
+            inner.limit = Some(limit);
+            inner.evict();
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.0.lock().map(|inner| inner.limit()).unwrap_or(DEFAULT_MEMORY_LIMIT)
+    }
+
+    /// Sets the memory used by objects and diff results outside the cache, which counts
+    /// towards the limit.
+    pub fn set_in_use(&self, size: usize) {
+        if let Ok(mut inner) = self.0.lock() {
+            inner.in_use = size;
+            inner.evict();
+        }
+    }
+
+    /// Removes and returns the cached object for the path, if the file hasn't changed since
+    /// it was read with the same configuration.
+    pub fn take(&self, path: &Path, config: &DiffObjConfig) -> Option<ObjInfo> {
+        let entry = {
+            let mut inner = self.0.lock().ok()?;
+            let idx = inner.entries.iter().position(|e| e.path == path)?;
+            inner.entries.remove(idx)
+        };
+        let timestamp = FileTime::from_last_modification_time(&fs::metadata(path).ok()?);
+        (entry.timestamp == timestamp && entry.config == read_config(config)).then_some(entry.obj)
+    }
+
+    /// Adds an object that's no longer in use, as the most recently used. Objects that weren't
+    /// read from a file are dropped.
+    pub fn insert(&self, unit: &str, obj: ObjInfo, config: &DiffObjConfig) {
+        let (Some(path), Some(timestamp)) = (obj.path.clone(), obj.timestamp) else {
+            return;
+        };
+        let Ok(mut inner) = self.0.lock() else {
+            return;
+        };
+        inner.entries.retain(|e| e.path != path);
+        let size = obj.memory_usage();
+        inner.entries.push(CacheEntry {
+            unit: unit.to_string(),
+            path,
+            timestamp,
+            config: read_config(config),
+            obj,
+            size,
+        });
+        inner.evict();
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut inner) = self.0.lock() {
+            inner.entries.clear();
+        }
+    }
+
+    /// Memory used by each cached object, most recently used first.
+    pub fn usage(&self) -> Vec<CachedObjUsage> {
+        let Ok(inner) = self.0.lock() else {
+            return vec![];
+        };
+        inner
+            .entries
+            .iter()
+            .rev()
+            .map(|e| CachedObjUsage { unit: e.unit.clone(), path: e.path.clone(), size: e.size })
+            .collect()
+    }
+}
@@ -0,0 +1,194 @@
+//! PLT and GOT resolution for linked ELF executables and shared libraries.
+//!
+//! Linked images call imported functions through PLT stubs and load imported addresses from
+//! GOT slots, neither of which exist in relocatable objects. Resolving them to the imported
+//! symbol lets calls and loads in a linked image match the relocations of the original object.
+
+use std::collections::BTreeMap;
+
+use flagset::Flags;
+use object::{
+    elf, Architecture, BinaryFormat, File, Object, ObjectKind, ObjectSection, ObjectSymbol,
+    ObjectSymbolTable, RelocationEncoding, RelocationFlags, RelocationKind, RelocationTarget,
+};
+
+use crate::obj::{ObjSection, ObjSymbol, ObjSymbolFlagSet, ObjSymbolFlags, ObjSymbolKind};
+
+/// Flags of relocations synthesized for linked images, which have none. The target was
+/// resolved from an address, so only the target symbol is meaningful when comparing.
+pub const LINKED_RELOC_FLAGS: RelocationFlags = RelocationFlags::Generic {
+    kind: RelocationKind::Unknown,
+    encoding: RelocationEncoding::Generic,
+    size: 0,
+};
+
+/// A PLT stub, jumping to an imported function.
+#[derive(Debug, Clone)]
+pub struct PltStub {
+    pub address: u64,
+    pub size: u64,
+    /// The imported symbol name
+    pub target: String,
+}
+
+/// Imported symbols of a linked ELF image, resolved through its PLT and GOT.
+#[derive(Debug, Clone, Default)]
+pub struct DynamicImports {
+    /// PLT stubs, keyed by address
+    pub plt: BTreeMap<u64, PltStub>,
+    /// Imported symbol names, keyed by GOT slot address
+    pub got: BTreeMap<u64, String>,
+}
+
+impl DynamicImports {
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.plt.is_empty() && self.got.is_empty() }
+
+    /// The function imported by the PLT stub containing `address`.
+    pub fn plt_target(&self, address: u64) -> Option<&str> {
+        let (_, stub) = self.plt.range(..=address).next_back()?;
+        (address < stub.address + stub.size).then_some(stub.target.as_str())
+    }
+
+    /// The symbol imported through the GOT slot at `address`.
+    pub fn got_target(&self, address: u64) -> Option<&str> {
+        self.got.get(&address).map(String::as_str)
+    }
+}
+
+enum DynamicRelocKind {
+    JumpSlot,
+    GlobDat,
+}
+
+fn dynamic_reloc_kind(arch: Architecture, flags: RelocationFlags) -> Option<DynamicRelocKind> {
+    let RelocationFlags::Elf { r_type } = flags else {
+        return None;
+    };
+    let (jump_slot, glob_dat) = match arch {
+        Architecture::X86_64 => (elf::R_X86_64_JUMP_SLOT, Some(elf::R_X86_64_GLOB_DAT)),
+        Architecture::I386 => (elf::R_386_JMP_SLOT, Some(elf::R_386_GLOB_DAT)),
+        Architecture::Aarch64 => (elf::R_AARCH64_JUMP_SLOT, Some(elf::R_AARCH64_GLOB_DAT)),
+        Architecture::Arm => (elf::R_ARM_JUMP_SLOT, Some(elf::R_ARM_GLOB_DAT)),
+        Architecture::PowerPc => (elf::R_PPC_JMP_SLOT, Some(elf::R_PPC_GLOB_DAT)),
+        // MIPS resolves global symbols through the GOT without dynamic relocations
+        Architecture::Mips => (elf::R_MIPS_JUMP_SLOT, None),
+        _ => return None,
+    };
+    if r_type == jump_slot {
+        Some(DynamicRelocKind::JumpSlot)
+    } else if Some(r_type) == glob_dat {
+        Some(DynamicRelocKind::GlobDat)
+    } else {
+        None
+    }
+}
+
+/// The PLT section and its layout: header size and entry size. Entries are in the same order
+/// as the jump slot relocations.
+fn plt_layout<'data, 'file>(
+    obj_file: &'file File<'data>,
+) -> Option<(object::Section<'data, 'file>, u64, u64)> {
+    match obj_file.architecture() {
+        Architecture::X86_64 | Architecture::I386 => {
+            // With IBT, calls go through .plt.sec, which has no header
+            if let Some(section) = obj_file.section_by_name(".plt.sec") {
+                return Some((section, 0, 16));
+            }
+            Some((obj_file.section_by_name(".plt")?, 16, 16))
+        }
+        Architecture::Aarch64 => Some((obj_file.section_by_name(".plt")?, 32, 16)),
+        Architecture::Arm => Some((obj_file.section_by_name(".plt")?, 20, 12)),
+        _ => None,
+    }
+}
+
+/// Parses the PLT stubs and GOT slots of a linked ELF image. Relocatable objects and other
+/// formats have none.
+pub fn parse_dynamic_imports(obj_file: &File<'_>) -> DynamicImports {
+    let mut result = DynamicImports::default();
+    if obj_file.format() != BinaryFormat::Elf
+        || !matches!(obj_file.kind(), ObjectKind::Executable | ObjectKind::Dynamic)
+    {
+        return result;
+    }
+    let (Some(relocations), Some(dynamic_symbols)) =
+        (obj_file.dynamic_relocations(), obj_file.dynamic_symbol_table())
+    else {
+        return result;
+    };
+    let arch = obj_file.architecture();
+    let mut jump_slots = Vec::new();
+    for (address, reloc) in relocations {
+        let Some(kind) = dynamic_reloc_kind(arch, reloc.flags()) else {
+            continue;
+        };
+        let RelocationTarget::Symbol(index) = reloc.target() else {
+            continue;
+        };
+        let Some(name) = dynamic_symbols
+            .symbol_by_index(index)
+            .ok()
+            .and_then(|s| s.name().ok())
+            .filter(|name| !name.is_empty())
+        else {
+            continue;
+        };
+        // Imports may be versioned (e.g. `puts@GLIBC_2.2.5`)
+        let name = name.split_once('@').map_or(name, |(name, _)| name).to_string();
+        if matches!(kind, DynamicRelocKind::JumpSlot) {
+            jump_slots.push(name.clone());
+        }
+        result.got.insert(address, name);
+    }
+
+    if let Some((section, header_size, entry_size)) = plt_layout(obj_file) {
+        let count = section.size().saturating_sub(header_size) / entry_size;
+        if count >= jump_slots.len() as u64 {
+            for (i, target) in jump_slots.into_iter().enumerate() {
+                let address = section.address() + header_size + i as u64 * entry_size;
+                result.plt.insert(address, PltStub { address, size: entry_size, target });
+            }
+        } else {
+            log::warn!(
+                "PLT section {} has room for {count} entries, but there are {} jump slots",
+                section.name().unwrap_or("?"),
+                jump_slots.len()
+            );
+        }
+    }
+    result
+}
+
+/// Adds `name@plt` symbols for the PLT stubs within the section, replacing the placeholder
+/// symbol of an otherwise empty section.
+pub fn add_plt_symbols(section: &mut ObjSection, imports: &DynamicImports) {
+    let end = section.address + section.size;
+    let stubs = imports.plt.range(section.address..end).map(|(_, stub)| stub).collect::<Vec<_>>();
+    if stubs.is_empty() {
+        return;
+    }
+    section.symbols.retain(|s| s.original_index.is_some());
+    for stub in stubs {
+        let section_address = stub.address - section.address;
+        let bytes = section
+            .data
+            .get(section_address as usize..(section_address + stub.size) as usize)
+            .unwrap_or_default();
+        section.symbols.push(ObjSymbol {
+            name: format!("{}@plt", stub.target),
+            demangled_name: None,
+            address: stub.address,
+            section_address,
+            size: stub.size,
+            size_known: true,
+            kind: ObjSymbolKind::Function,
+            flags: ObjSymbolFlagSet(ObjSymbolFlags::none() | ObjSymbolFlags::Local),
+            orig_section_index: Some(section.orig_index),
+            virtual_address: None,
+            original_index: None,
+            bytes: bytes.to_vec(),
+        });
+    }
+    section.symbols.sort_by_key(|s| s.address);
+}
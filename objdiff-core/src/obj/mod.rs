@@ -1,12 +1,25 @@
+pub mod abi_info;
+pub mod cache;
+pub mod dynamic;
 pub mod read;
+#[cfg(feature = "ppc")]
+pub mod rel;
+pub mod search;
+pub mod seh;
 pub mod split_meta;
+pub mod symbol_import;
+pub mod unwind;
 
 use std::{borrow::Cow, collections::BTreeMap, fmt, path::PathBuf};
 
+use abi_info::AbiSection;
+use dynamic::DynamicImports;
 use filetime::FileTime;
 use flagset::{flags, FlagSet};
 use object::RelocationFlags;
+use seh::SehUnwindInfo;
 use split_meta::SplitMeta;
+use unwind::UnwindInfo;
 
 use crate::{arch::ObjArch, util::ReallySigned};
 
@@ -37,6 +50,8 @@ pub struct ObjSection {
     pub kind: ObjSectionKind,
     pub address: u64,
     pub size: u64,
+    /// Alignment in bytes, or 1 if unknown
+    pub align: u64,
     pub data: Vec<u8>,
     pub orig_index: usize,
     pub symbols: Vec<ObjSymbol>,
@@ -44,6 +59,8 @@ pub struct ObjSection {
     pub virtual_address: Option<u64>,
     /// Line number info (.line or .debug_line section)
     pub line_info: BTreeMap<u64, u32>,
+    /// Mergeable string section (SHF_MERGE | SHF_STRINGS), which the toolchain may deduplicate
+    pub merge_strings: bool,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -113,6 +130,8 @@ pub struct ObjIns {
     pub formatted: String,
     /// Original (unsimplified) instruction
     pub orig: Option<String>,
+    /// Arguments of the raw instruction, if this is a pseudo-instruction whose arguments differ
+    pub raw_args: Option<Vec<ObjInsArg>>,
 }
 
 impl ObjIns {
@@ -121,6 +140,13 @@ impl ObjIns {
     pub fn iter_args(&self) -> impl DoubleEndedIterator<Item = &ObjInsArg> {
         self.args.iter().filter(|a| !a.is_plain_text())
     }
+
+    /// Iterate over non-PlainText arguments of the raw instruction, if this is a
+    /// pseudo-instruction with different arguments, or of the instruction itself.
+    #[inline]
+    pub fn iter_raw_args(&self) -> impl DoubleEndedIterator<Item = &ObjInsArg> + Clone {
+        self.raw_args.as_ref().unwrap_or(&self.args).iter().filter(|a| !a.is_plain_text())
+    }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
@@ -150,15 +176,64 @@ pub struct ObjSymbol {
     pub bytes: Vec<u8>,
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LoadDiagnosticKind {
+    Section,
+    Symbol,
+    Relocation,
+}
+
+/// An item that was skipped or adjusted while loading an object, and why.
+#[derive(Debug, Clone)]
+pub struct LoadDiagnostic {
+    pub kind: LoadDiagnosticKind,
+    /// Name of the affected section, symbol or relocation location
+    pub name: String,
+    pub reason: String,
+}
+
+impl LoadDiagnostic {
+    pub fn new(
+        kind: LoadDiagnosticKind,
+        name: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self { kind, name: name.into(), reason: reason.into() }
+    }
+}
+
+impl fmt::Display for LoadDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            LoadDiagnosticKind::Section => "section",
+            LoadDiagnosticKind::Symbol => "symbol",
+            LoadDiagnosticKind::Relocation => "relocation",
+        };
+        write!(f, "{} {}: {}", kind, self.name, self.reason)
+    }
+}
+
 pub struct ObjInfo {
     pub arch: Box<dyn ObjArch>,
     pub path: Option<PathBuf>,
     pub timestamp: Option<FileTime>,
+    pub endianness: object::Endianness,
+    pub architecture: object::Architecture,
     pub sections: Vec<ObjSection>,
     /// Common BSS symbols
     pub common: Vec<ObjSymbol>,
     /// Split object metadata (.note.split section)
     pub split_meta: Option<SplitMeta>,
+    /// GCC-style unwind information (.eh_frame), keyed by original symbol index
+    pub unwind_info: BTreeMap<usize, UnwindInfo>,
+    /// Windows ARM/ARM64 unwind information (.pdata/.xdata), keyed by original symbol index
+    pub seh_unwind_info: BTreeMap<usize, SehUnwindInfo>,
+    /// Sections, symbols and relocations that were skipped or adjusted while loading
+    pub diagnostics: Vec<LoadDiagnostic>,
+    /// PLT stubs and GOT slots of a linked ELF image
+    pub dynamic_imports: DynamicImports,
+    /// Decoded ABI metadata sections (MIPS `.reginfo`, `.MIPS.abiflags`, PPC `.PPC.EMB.apuinfo`)
+    pub abi_sections: Vec<AbiSection>,
 }
 
 #[derive(Debug, Clone)]
@@ -167,6 +242,19 @@ pub struct ObjReloc {
     pub address: u64,
     pub target: ObjSymbol,
     pub addend: i64,
+    /// The target as encoded in the object, if it was resolved to a different symbol
+    pub original: Option<ObjRelocOrigin>,
+    /// Whether the relocation isn't in the object, but was inferred by flow analysis, such as
+    /// PowerPC loads through a pointer to pooled data
+    pub pseudo: bool,
+}
+
+/// The target of a relocation as encoded by the object producer, e.g. `.data+0x104`, before
+/// it was canonicalized to the enclosing symbol (`sym+0x4`).
+#[derive(Debug, Clone)]
+pub struct ObjRelocOrigin {
+    pub name: String,
+    pub addend: i64,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
@@ -178,6 +266,16 @@ pub struct SymbolRef {
 pub const SECTION_COMMON: usize = usize::MAX - 1;
 
 impl ObjInfo {
+    /// Returns the decoded `.eh_frame` unwind information for a function, if any.
+    pub fn unwind_info_for_symbol(&self, symbol: &ObjSymbol) -> Option<&UnwindInfo> {
+        symbol.original_index.and_then(|i| self.unwind_info.get(&i))
+    }
+
+    /// Returns the decoded `.pdata`/`.xdata` unwind information for a function, if any.
+    pub fn seh_unwind_info_for_symbol(&self, symbol: &ObjSymbol) -> Option<&SehUnwindInfo> {
+        symbol.original_index.and_then(|i| self.seh_unwind_info.get(&i))
+    }
+
     pub fn section_symbol(&self, symbol_ref: SymbolRef) -> (Option<&ObjSection>, &ObjSymbol) {
         if symbol_ref.section_idx == SECTION_COMMON {
             let symbol = &self.common[symbol_ref.symbol_idx];
@@ -187,4 +285,23 @@ impl ObjInfo {
         let symbol = &section.symbols[symbol_ref.symbol_idx];
         (Some(section), symbol)
     }
+
+    /// Returns the number of padding bytes between the end of a symbol and the start of the
+    /// next symbol (or the end of the section), such as inter-function alignment padding.
+    pub fn symbol_padding(&self, symbol_ref: SymbolRef) -> Option<u64> {
+        let (section, symbol) = self.section_symbol(symbol_ref);
+        let section = section?;
+        if symbol.size == 0 {
+            return None;
+        }
+        let end = symbol.address + symbol.size;
+        let next = section
+            .symbols
+            .iter()
+            .filter(|s| s.size > 0 && s.address >= end)
+            .map(|s| s.address)
+            .min()
+            .unwrap_or(section.address + section.size);
+        Some(next.saturating_sub(end))
+    }
 }
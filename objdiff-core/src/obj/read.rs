@@ -19,19 +19,24 @@ use object::{
 
 use crate::{
     arch::{new_arch, ObjArch},
-    diff::DiffObjConfig,
+    config::RelocRename,
+    diff::{is_literal_symbol_name, DiffObjConfig},
     obj::{
+        abi_info::parse_abi_sections,
+        dynamic::{add_plt_symbols, parse_dynamic_imports},
         split_meta::{SplitMeta, SPLITMETA_SECTION},
-        ObjInfo, ObjReloc, ObjSection, ObjSectionKind, ObjSymbol, ObjSymbolFlagSet, ObjSymbolFlags,
-        ObjSymbolKind,
+        LoadDiagnostic, LoadDiagnosticKind, ObjInfo, ObjReloc, ObjRelocOrigin, ObjSection,
+        ObjSectionKind, ObjSymbol, ObjSymbolFlagSet, ObjSymbolFlags, ObjSymbolKind,
     },
-    util::{read_u16, read_u32},
+    util::{read_u16, read_u32, CancellationToken, Stopwatch},
 };
 
 fn to_obj_section_kind(kind: SectionKind) -> Option<ObjSectionKind> {
     match kind {
         SectionKind::Text => Some(ObjSectionKind::Code),
-        SectionKind::Data | SectionKind::ReadOnlyData => Some(ObjSectionKind::Data),
+        SectionKind::Data | SectionKind::ReadOnlyData | SectionKind::ReadOnlyString => {
+            Some(ObjSectionKind::Data)
+        }
         SectionKind::UninitializedData => Some(ObjSectionKind::Bss),
         _ => None,
     }
@@ -115,13 +120,31 @@ fn to_obj_symbol(
     })
 }
 
-fn filter_sections(obj_file: &File<'_>, split_meta: Option<&SplitMeta>) -> Result<Vec<ObjSection>> {
+fn filter_sections(
+    obj_file: &File<'_>,
+    split_meta: Option<&SplitMeta>,
+    diagnostics: &mut Vec<LoadDiagnostic>,
+) -> Result<Vec<ObjSection>> {
     let mut result = Vec::<ObjSection>::new();
     for section in obj_file.sections() {
         if section.size() == 0 {
+            if let Ok(name) = section.name() {
+                if !name.is_empty() {
+                    diagnostics.push(LoadDiagnostic::new(
+                        LoadDiagnosticKind::Section,
+                        name,
+                        "Empty section",
+                    ));
+                }
+            }
             continue;
         }
         let Some(kind) = to_obj_section_kind(section.kind()) else {
+            diagnostics.push(LoadDiagnostic::new(
+                LoadDiagnosticKind::Section,
+                section.name().unwrap_or("?"),
+                format!("Unsupported section kind {:?}", section.kind()),
+            ));
             continue;
         };
         let name = section.name().context("Failed to process section name")?;
@@ -142,12 +165,14 @@ fn filter_sections(obj_file: &File<'_>, split_meta: Option<&SplitMeta>) -> Resul
             kind,
             address: section.address(),
             size: section.size(),
+            align: section.align().max(1),
             data: data.to_vec(),
             orig_index: section.index().0,
             symbols: Vec::new(),
             relocations: Vec::new(),
             virtual_address,
             line_info: Default::default(),
+            merge_strings: section.kind() == SectionKind::ReadOnlyString,
         });
     }
     result.sort_by(|a, b| a.name.cmp(&b.name));
@@ -161,6 +186,7 @@ fn symbols_by_section(
     section_symbols: &[Symbol<'_, '_>],
     split_meta: Option<&SplitMeta>,
     name_counts: &mut HashMap<String, u32>,
+    diagnostics: &mut Vec<LoadDiagnostic>,
 ) -> Result<Vec<ObjSymbol>> {
     let mut result = Vec::<ObjSymbol>::new();
     for symbol in section_symbols {
@@ -171,10 +197,27 @@ fn symbols_by_section(
             // TODO strip local syms in diff?
             let name = symbol.name().context("Failed to process symbol name")?;
             if symbol.size() == 0 || name.starts_with("lbl_") {
+                diagnostics.push(LoadDiagnostic::new(
+                    LoadDiagnosticKind::Symbol,
+                    name,
+                    if symbol.size() == 0 {
+                        "Local code label with zero size"
+                    } else {
+                        "Local code label (lbl_ prefix)"
+                    },
+                ));
                 continue;
             }
         }
-        result.push(to_obj_symbol(arch, obj_file, symbol, split_meta)?);
+        let obj_symbol = to_obj_symbol(arch, obj_file, symbol, split_meta)?;
+        if obj_symbol.flags.0.contains(ObjSymbolFlags::Hidden) {
+            diagnostics.push(LoadDiagnostic::new(
+                LoadDiagnosticKind::Symbol,
+                &obj_symbol.name,
+                "Hidden visibility, only shown with \"Show hidden symbols\"",
+            ));
+        }
+        result.push(obj_symbol);
     }
     result.sort_by(|a, b| a.address.cmp(&b.address).then(a.size.cmp(&b.size)));
     let mut iter = result.iter_mut().peekable();
@@ -185,6 +228,11 @@ fn symbols_by_section(
             } else {
                 symbol.size = (section.address + section.size) - symbol.address;
             }
+            diagnostics.push(LoadDiagnostic::new(
+                LoadDiagnosticKind::Symbol,
+                &symbol.name,
+                format!("Size unknown, inferred as {:#x}", symbol.size),
+            ));
             // Set symbol kind if we ended up with a non-zero size
             if symbol.kind == ObjSymbolKind::Unknown && symbol.size > 0 {
                 symbol.kind = match section.kind {
@@ -235,6 +283,109 @@ fn common_symbols(
         .collect::<Result<Vec<ObjSymbol>>>()
 }
 
+/// Hides the local symbols of a section, so that only global symbols are listed, matched and
+/// reported. Relocations keep their own copies of target symbols, so they're unaffected.
+/// Returns the number of symbols hidden.
+fn hide_local_symbols(section: &mut ObjSection, diagnostics: &mut Vec<LoadDiagnostic>) {
+    let mut count = 0;
+    for symbol in &mut section.symbols {
+        if symbol.flags.0.contains(ObjSymbolFlags::Local)
+            && !symbol.flags.0.contains(ObjSymbolFlags::Hidden)
+        {
+            symbol.flags = ObjSymbolFlagSet(symbol.flags.0 | ObjSymbolFlags::Hidden);
+            count += 1;
+        }
+    }
+    if count > 0 {
+        diagnostics.push(LoadDiagnostic::new(
+            LoadDiagnosticKind::Symbol,
+            &section.name,
+            format!("{count} local symbols hidden"),
+        ));
+    }
+}
+
+/// Whether the symbol is an assembler-local label, such as GNU as `.L` labels, `$L` labels or
+/// ARM/AArch64 mapping symbols (`$a`, `$t`, `$d`, `$x`). Literal pool labels (`.LC0`,
+/// `.L.str.1`) are kept, since they're matched by content when diffing.
+fn is_local_label(symbol: &Symbol<'_, '_>) -> bool {
+    if !symbol.is_local() || symbol.kind() == SymbolKind::Section {
+        return false;
+    }
+    let name = symbol.name().unwrap_or_default();
+    if is_literal_symbol_name(name) {
+        return false;
+    }
+    if name.starts_with(".L") || name.starts_with("$L") {
+        return true;
+    }
+    matches!(name.split('.').next(), Some("$a" | "$t" | "$d" | "$x"))
+}
+
+/// Strips an ELF symbol version (`memcpy@GLIBC_2.2.5`, `foo@@VERS_1`) from a symbol name.
+fn strip_symbol_version(name: &str) -> Option<&str> {
+    let (base, version) = name.split_once('@')?;
+    let version = version.strip_prefix('@').unwrap_or(version);
+    if base.is_empty()
+        || version.is_empty()
+        || !version.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+    {
+        return None;
+    }
+    Some(base)
+}
+
+fn strip_symbol_versions(
+    arch: &dyn ObjArch,
+    sections: &mut [ObjSection],
+    common: &mut [ObjSymbol],
+) {
+    let symbols = sections
+        .iter_mut()
+        .flat_map(|s| s.symbols.iter_mut().chain(s.relocations.iter_mut().map(|r| &mut r.target)))
+        .chain(common.iter_mut());
+    for symbol in symbols {
+        if let Some(name) = strip_symbol_version(&symbol.name) {
+            symbol.name = name.to_string();
+            symbol.demangled_name = arch.demangle(&symbol.name);
+        }
+    }
+}
+
+/// Applies the first matching rename rule to each relocation target, recording how many targets
+/// each rule renamed.
+fn rename_reloc_targets(
+    arch: &dyn ObjArch,
+    sections: &mut [ObjSection],
+    rules: &[RelocRename],
+    diagnostics: &mut Vec<LoadDiagnostic>,
+) {
+    let mut hits = vec![0usize; rules.len()];
+    for reloc in sections.iter_mut().flat_map(|s| s.relocations.iter_mut()) {
+        let Some((index, name)) = rules
+            .iter()
+            .enumerate()
+            .find_map(|(i, rule)| Some((i, rule.apply(&reloc.target.name)?)))
+        else {
+            continue;
+        };
+        hits[index] += 1;
+        if name != reloc.target.name {
+            reloc.target.demangled_name = arch.demangle(&name);
+            reloc.target.name = name;
+        }
+    }
+    for (rule, count) in rules.iter().zip(hits) {
+        if count > 0 {
+            diagnostics.push(LoadDiagnostic::new(
+                LoadDiagnosticKind::Relocation,
+                &rule.from,
+                format!("{count} targets renamed to {}", rule.to),
+            ));
+        }
+    }
+}
+
 const LOW_PRIORITY_SYMBOLS: &[&str] =
     &["__gnu_compiled_c", "__gnu_compiled_cplusplus", "gcc2_compiled."];
 
@@ -312,10 +463,15 @@ fn relocations_by_section(
     section: &ObjSection,
     section_symbols: &[Vec<Symbol<'_, '_>>],
     split_meta: Option<&SplitMeta>,
+    config: &DiffObjConfig,
+    diagnostics: &mut Vec<LoadDiagnostic>,
 ) -> Result<Vec<ObjReloc>> {
     let obj_section = obj_file.section_by_index(SectionIndex(section.orig_index))?;
     let mut relocations = Vec::<ObjReloc>::new();
     for (address, reloc) in obj_section.relocations() {
+        if arch.ignore_reloc(reloc.flags()) {
+            continue;
+        }
         let symbol = match reloc.target() {
             RelocationTarget::Symbol(idx) => {
                 if idx.0 == u32::MAX as usize {
@@ -328,12 +484,22 @@ fn relocations_by_section(
                         address,
                         idx.0
                     );
+                    diagnostics.push(LoadDiagnostic::new(
+                        LoadDiagnosticKind::Relocation,
+                        format!("{}:{:#x}", section.name, address),
+                        format!("Target symbol {} not found", idx.0),
+                    ));
                     continue;
                 };
                 symbol
             }
             RelocationTarget::Absolute => {
                 log::warn!("Ignoring absolute relocation @ {}:{:#x}", section.name, address);
+                diagnostics.push(LoadDiagnostic::new(
+                    LoadDiagnosticKind::Relocation,
+                    format!("{}:{:#x}", section.name, address),
+                    "Absolute relocation target",
+                ));
                 continue;
             }
             _ => bail!("Unhandled relocation target: {:?}", reloc.target()),
@@ -344,8 +510,33 @@ fn relocations_by_section(
         } else {
             reloc.addend()
         };
+        let mut original = None;
         let target = match symbol.kind() {
+            _ if config.filter_local_labels && is_local_label(&symbol) => {
+                // Local labels are hidden, so resolve the target like a section relocation
+                let section_index = symbol
+                    .section_index()
+                    .ok_or_else(|| anyhow!("Local label {symbol:?} has no section index"))?;
+                let target_address = symbol.address() as i64 + addend;
+                ensure!(target_address >= 0, "Negative local label reloc target: {target_address}");
+                original = Some(ObjRelocOrigin { name: symbol_name(&symbol)?, addend });
+                let section = obj_file.section_by_index(section_index)?;
+                let symbol = find_section_symbol(
+                    arch,
+                    obj_file,
+                    &section,
+                    &section_symbols[section_index.0],
+                    target_address as u64,
+                    split_meta,
+                )?;
+                // Adjust addend to be relative to the selected symbol
+                addend = target_address - symbol.address as i64;
+                symbol
+            }
             SymbolKind::Text | SymbolKind::Data | SymbolKind::Label | SymbolKind::Unknown => {
+                // Kept as encoded, even if the addend points outside of the symbol: one past
+                // the end (`arr+sizeof(arr)`), negative offsets (`sym-4`) and split HI16/LO16
+                // pairs are all legitimate and must resolve against the same symbol.
                 to_obj_symbol(arch, obj_file, &symbol, split_meta)?
             }
             SymbolKind::Section => {
@@ -362,18 +553,32 @@ fn relocations_by_section(
                     addend as u64,
                     split_meta,
                 )?;
+                original = Some(ObjRelocOrigin { name: section.name()?.to_string(), addend });
                 // Adjust addend to be relative to the selected symbol
-                addend = (symbol.address - section.address()) as i64;
+                addend -= (symbol.address - section.address()) as i64;
                 symbol
             }
             kind => bail!("Unhandled relocation symbol type {kind:?}"),
         };
-        relocations.push(ObjReloc { flags, address, target, addend });
+        if original.as_ref().is_some_and(|o| o.name == target.name && o.addend == addend) {
+            // Resolved to the same target, e.g. a section with no symbols
+            original = None;
+        }
+        relocations.push(ObjReloc { flags, address, target, addend, original, pseudo: false });
     }
     Ok(relocations)
 }
 
-fn line_info(obj_file: &File<'_>, sections: &mut [ObjSection], obj_data: &[u8]) -> Result<()> {
+fn symbol_name(symbol: &Symbol<'_, '_>) -> Result<String> {
+    Ok(symbol.name().context("Failed to process symbol name")?.to_string())
+}
+
+fn line_info(
+    obj_file: &File<'_>,
+    sections: &mut [ObjSection],
+    obj_data: &[u8],
+    obj_path: Option<&Path>,
+) -> Result<()> {
     // DWARF 1.1
     if let Some(section) = obj_file.section_by_name(".line") {
         let data = section.uncompressed_data()?;
@@ -412,51 +617,9 @@ fn line_info(obj_file: &File<'_>, sections: &mut [ObjSection], obj_data: &[u8])
 
     // DWARF 2+
     #[cfg(feature = "dwarf")]
-    {
-        let dwarf_cow = gimli::DwarfSections::load(|id| {
-            Ok::<_, gimli::Error>(
-                obj_file
-                    .section_by_name(id.name())
-                    .and_then(|section| section.uncompressed_data().ok())
-                    .unwrap_or(std::borrow::Cow::Borrowed(&[][..])),
-            )
-        })?;
-        let endian = match obj_file.endianness() {
-            object::Endianness::Little => gimli::RunTimeEndian::Little,
-            object::Endianness::Big => gimli::RunTimeEndian::Big,
-        };
-        let dwarf = dwarf_cow.borrow(|section| gimli::EndianSlice::new(section, endian));
-        let mut iter = dwarf.units();
-        if let Some(header) = iter.next()? {
-            let unit = dwarf.unit(header)?;
-            if let Some(program) = unit.line_program.clone() {
-                let mut text_sections =
-                    obj_file.sections().filter(|s| s.kind() == SectionKind::Text);
-                let section_index = text_sections.next().map(|s| s.index().0);
-                let mut lines = section_index
-                    .and_then(|index| sections.iter_mut().find(|s| s.orig_index == index))
-                    .map(|s| &mut s.line_info);
-
-                let mut rows = program.rows();
-                while let Some((_header, row)) = rows.next_row()? {
-                    if let (Some(line), Some(lines)) = (row.line(), &mut lines) {
-                        lines.insert(row.address(), line.get() as u32);
-                    }
-                    if row.end_sequence() {
-                        // The next row is the start of a new sequence, which means we must
-                        // advance to the next .text section.
-                        let section_index = text_sections.next().map(|s| s.index().0);
-                        lines = section_index
-                            .and_then(|index| sections.iter_mut().find(|s| s.orig_index == index))
-                            .map(|s| &mut s.line_info);
-                    }
-                }
-            }
-        }
-        if iter.next()?.is_some() {
-            log::warn!("Multiple units found in DWARF data, only processing the first");
-        }
-    }
+    line_info_dwarf(obj_file, sections, obj_path)?;
+    #[cfg(not(feature = "dwarf"))]
+    let _ = obj_path;
 
     // COFF
     if let File::Coff(coff) = obj_file {
@@ -466,6 +629,192 @@ fn line_info(obj_file: &File<'_>, sections: &mut [ObjSection], obj_data: &[u8])
     Ok(())
 }
 
+#[cfg(feature = "dwarf")]
+type DwarfReader<'a> = gimli::EndianSlice<'a, gimli::RunTimeEndian>;
+
+#[cfg(feature = "dwarf")]
+fn dwarf_endian(obj_file: &File<'_>) -> gimli::RunTimeEndian {
+    match obj_file.endianness() {
+        object::Endianness::Little => gimli::RunTimeEndian::Little,
+        object::Endianness::Big => gimli::RunTimeEndian::Big,
+    }
+}
+
+/// Loads the DWARF sections of an object, or the `.dwo` variants of a split DWARF object.
+#[cfg(feature = "dwarf")]
+fn load_dwarf_sections<'data>(
+    obj_file: &File<'data>,
+    dwo: bool,
+) -> Result<gimli::DwarfSections<std::borrow::Cow<'data, [u8]>>> {
+    Ok(gimli::DwarfSections::load(|id| {
+        let name = if dwo { id.dwo_name() } else { Some(id.name()) };
+        Ok::<_, gimli::Error>(
+            name.and_then(|name| obj_file.section_by_name(name))
+                .and_then(|section| section.uncompressed_data().ok())
+                .unwrap_or(std::borrow::Cow::Borrowed(&[][..])),
+        )
+    })?)
+}
+
+#[cfg(feature = "dwarf")]
+fn line_info_dwarf(
+    obj_file: &File<'_>,
+    sections: &mut [ObjSection],
+    obj_path: Option<&Path>,
+) -> Result<()> {
+    let dwarf_cow = load_dwarf_sections(obj_file, false)?;
+    let endian = dwarf_endian(obj_file);
+    let dwarf = dwarf_cow.borrow(|section| gimli::EndianSlice::new(section, endian));
+    let mut iter = dwarf.units();
+    if let Some(header) = iter.next()? {
+        let unit = dwarf.unit(header)?;
+        // With split DWARF (-gsplit-dwarf), the line table stays with the skeleton unit in the
+        // object, since its rows need relocated addresses. The table in the .dwo or .dwp file
+        // only names files for type units, so it's a fallback for skeletons without one.
+        if let Some(program) = unit.line_program.clone() {
+            apply_line_program(obj_file, sections, program)?;
+        } else if let Some(dwo_id) = unit.dwo_id {
+            match split_dwarf_line_info(obj_file, sections, &dwarf, &unit, dwo_id, obj_path) {
+                Ok(true) => {}
+                Ok(false) => log::warn!("Split DWARF object for unit {:#x} not found", dwo_id.0),
+                Err(e) => log::warn!("Failed to load split DWARF: {e:#}"),
+            }
+        }
+    }
+    if iter.next()?.is_some() {
+        log::warn!("Multiple units found in DWARF data, only processing the first");
+    }
+    Ok(())
+}
+
+#[cfg(feature = "dwarf")]
+fn apply_line_program<R: gimli::Reader>(
+    obj_file: &File<'_>,
+    sections: &mut [ObjSection],
+    program: gimli::IncompleteLineProgram<R>,
+) -> Result<()> {
+    let mut text_sections = obj_file.sections().filter(|s| s.kind() == SectionKind::Text);
+    let section_index = text_sections.next().map(|s| s.index().0);
+    let mut lines = section_index
+        .and_then(|index| sections.iter_mut().find(|s| s.orig_index == index))
+        .map(|s| &mut s.line_info);
+
+    let mut rows = program.rows();
+    while let Some((_header, row)) = rows.next_row()? {
+        if let (Some(line), Some(lines)) = (row.line(), &mut lines) {
+            lines.insert(row.address(), line.get() as u32);
+        }
+        if row.end_sequence() {
+            // The next row is the start of a new sequence, which means we must
+            // advance to the next .text section.
+            let section_index = text_sections.next().map(|s| s.index().0);
+            lines = section_index
+                .and_then(|index| sections.iter_mut().find(|s| s.orig_index == index))
+                .map(|s| &mut s.line_info);
+        }
+    }
+    Ok(())
+}
+
+/// Candidate locations for the split DWARF file of a skeleton unit: the unit's `DW_AT_dwo_name`
+/// resolved against its compilation directory and the object's directory, then a `.dwo` or
+/// `.dwp` file next to the object.
+#[cfg(feature = "dwarf")]
+fn split_dwarf_paths(
+    dwarf: &gimli::Dwarf<DwarfReader<'_>>,
+    unit: &gimli::Unit<DwarfReader<'_>>,
+    obj_path: Option<&Path>,
+) -> Result<Vec<std::path::PathBuf>> {
+    let mut paths = vec![];
+    if let Some(attr) = unit.dwo_name()? {
+        let name = dwarf.attr_string(unit, attr)?;
+        let name = Path::new(name.to_string_lossy().as_ref()).to_path_buf();
+        if let Some(comp_dir) = &unit.comp_dir {
+            paths.push(Path::new(comp_dir.to_string_lossy().as_ref()).join(&name));
+        }
+        if let Some(dir) = obj_path.and_then(Path::parent) {
+            paths.push(dir.join(&name));
+            if let Some(file_name) = name.file_name() {
+                paths.push(dir.join(file_name));
+            }
+        }
+    }
+    if let Some(obj_path) = obj_path {
+        paths.push(obj_path.with_extension("dwo"));
+        let mut dwp = obj_path.as_os_str().to_owned();
+        dwp.push(".dwp");
+        paths.push(dwp.into());
+    }
+    Ok(paths)
+}
+
+/// Loads line info for a skeleton unit without a line table from its split DWARF file. Returns
+/// `false` if no matching `.dwo` or `.dwp` file was found.
+#[cfg(feature = "dwarf")]
+fn split_dwarf_line_info(
+    obj_file: &File<'_>,
+    sections: &mut [ObjSection],
+    dwarf: &gimli::Dwarf<DwarfReader<'_>>,
+    unit: &gimli::Unit<DwarfReader<'_>>,
+    dwo_id: gimli::DwoId,
+    obj_path: Option<&Path>,
+) -> Result<bool> {
+    let endian = dwarf_endian(obj_file);
+    for path in split_dwarf_paths(dwarf, unit, obj_path)? {
+        let Ok(file) = fs::File::open(&path) else {
+            continue;
+        };
+        let data = unsafe { memmap2::Mmap::map(&file) }?;
+        let split_file =
+            File::parse(&*data).with_context(|| format!("Failed to parse {}", path.display()))?;
+        // Declared here so that the borrowed DWARF data outlives the branches below
+        let package_sections;
+        let split_sections;
+        let split_dwarf = if path.extension().is_some_and(|ext| ext == "dwp") {
+            package_sections = gimli::DwarfPackageSections::load(|id| {
+                Ok::<_, gimli::Error>(
+                    id.dwo_name()
+                        .and_then(|name| split_file.section_by_name(name))
+                        .and_then(|section| section.uncompressed_data().ok())
+                        .unwrap_or(std::borrow::Cow::Borrowed(&[][..])),
+                )
+            })?;
+            let package = package_sections.borrow(
+                |section| gimli::EndianSlice::new(section, endian),
+                gimli::EndianSlice::new(&[], endian),
+            )?;
+            match package.find_cu(dwo_id, dwarf)? {
+                Some(split_dwarf) => split_dwarf,
+                None => continue,
+            }
+        } else {
+            split_sections = load_dwarf_sections(&split_file, true)?;
+            let mut split_dwarf =
+                split_sections.borrow(|section| gimli::EndianSlice::new(section, endian));
+            // Addresses and ranges are resolved through the skeleton's sections
+            split_dwarf.make_dwo(dwarf);
+            split_dwarf
+        };
+        let mut units = split_dwarf.units();
+        while let Some(header) = units.next()? {
+            let mut split_unit = split_dwarf.unit(header)?;
+            if split_unit.dwo_id != Some(dwo_id) {
+                continue;
+            }
+            split_unit.copy_relocated_attributes(unit);
+            let Some(program) = split_unit.line_program.clone() else {
+                return Ok(true);
+            };
+            if program.clone().rows().next_row()?.is_none() {
+                log::warn!("Split DWARF line table for unit {:#x} has no rows", dwo_id.0);
+            }
+            apply_line_program(obj_file, sections, program)?;
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 fn line_info_coff(coff: &CoffFile, sections: &mut [ObjSection], obj_data: &[u8]) -> Result<()> {
     let symbol_table = coff.coff_header().symbols(obj_data)?;
 
@@ -614,6 +963,8 @@ fn combine_sections(section: ObjSection, combine: ObjSection) -> Result<ObjSecti
             address: (reloc.address as i64 + address_change).try_into()?,
             target: reloc.target, // TODO: Should be updated?
             addend: reloc.addend,
+            original: reloc.original,
+            pseudo: reloc.pseudo,
         });
     }
 
@@ -628,12 +979,14 @@ fn combine_sections(section: ObjSection, combine: ObjSection) -> Result<ObjSecti
         kind: section.kind,
         address: section.address,
         size: section.size + combine.size,
+        align: section.align.max(combine.align),
         data,
         orig_index: section.orig_index,
         symbols,
         relocations,
         virtual_address: section.virtual_address,
         line_info,
+        merge_strings: section.merge_strings && combine.merge_strings,
     })
 }
 
@@ -677,41 +1030,137 @@ fn combine_data_sections(sections: &mut Vec<ObjSection>) -> Result<()> {
     Ok(())
 }
 
+/// Progress reported by [`read_with_progress`] as each phase of parsing completes.
+pub enum LoadProgress<'a> {
+    /// The sections were located. Their symbols aren't parsed yet.
+    Sections(&'a [ObjSection]),
+    /// The symbols of the section at the given index were parsed.
+    Symbols(usize, &'a ObjSection),
+}
+
 pub fn read(obj_path: &Path, config: &DiffObjConfig) -> Result<ObjInfo> {
+    read_with_cancel(obj_path, config, &CancellationToken::default())
+}
+
+/// Like [`read`], but stops early with a [`Cancelled`](crate::util::Cancelled) error once
+/// `cancel` is triggered.
+pub fn read_with_cancel(
+    obj_path: &Path,
+    config: &DiffObjConfig,
+    cancel: &CancellationToken,
+) -> Result<ObjInfo> {
+    read_with_progress(obj_path, config, cancel, &mut |_| {})
+}
+
+/// Like [`read_with_cancel`], but calls `on_progress` with the partially loaded object so
+/// that large objects can be displayed before parsing completes.
+pub fn read_with_progress(
+    obj_path: &Path,
+    config: &DiffObjConfig,
+    cancel: &CancellationToken,
+    on_progress: &mut dyn FnMut(LoadProgress<'_>),
+) -> Result<ObjInfo> {
     let (data, timestamp) = {
         let file = fs::File::open(obj_path)?;
         let timestamp = FileTime::from_last_modification_time(&file.metadata()?);
         (unsafe { memmap2::Mmap::map(&file) }?, timestamp)
     };
-    let mut obj = parse(&data, config)?;
+    let start = Stopwatch::start();
+    let mut obj = parse_inner(&data, config, cancel, Some(obj_path), on_progress)?;
+    log::debug!("Loaded {}: {:.2}ms", obj_path.display(), start.elapsed().as_secs_f64() * 1000.0);
     obj.path = Some(obj_path.to_owned());
     obj.timestamp = Some(timestamp);
     Ok(obj)
 }
 
 pub fn parse(data: &[u8], config: &DiffObjConfig) -> Result<ObjInfo> {
+    parse_with_cancel(data, config, &CancellationToken::default())
+}
+
+/// Like [`parse`], but stops early with a [`Cancelled`](crate::util::Cancelled) error once
+/// `cancel` is triggered.
+pub fn parse_with_cancel(
+    data: &[u8],
+    config: &DiffObjConfig,
+    cancel: &CancellationToken,
+) -> Result<ObjInfo> {
+    parse_inner(data, config, cancel, None, &mut |_| {})
+}
+
+/// `obj_path` is used to locate split DWARF files, if any.
+fn parse_inner(
+    data: &[u8],
+    config: &DiffObjConfig,
+    cancel: &CancellationToken,
+    obj_path: Option<&Path>,
+    on_progress: &mut dyn FnMut(LoadProgress<'_>),
+) -> Result<ObjInfo> {
+    #[cfg(feature = "ppc")]
+    if object::FileKind::parse(data).is_err() {
+        if let Some(kind) = super::rel::detect(data) {
+            let mut obj = super::rel::parse(data, kind, cancel)?;
+            on_progress(LoadProgress::Sections(&obj.sections));
+            for (index, section) in obj.sections.iter_mut().enumerate() {
+                if config.global_symbols_only {
+                    hide_local_symbols(section, &mut obj.diagnostics);
+                }
+                on_progress(LoadProgress::Symbols(index, section));
+            }
+            if config.combine_data_sections {
+                combine_data_sections(&mut obj.sections)?;
+            }
+            if !config.reloc_renames.is_empty() {
+                rename_reloc_targets(
+                    obj.arch.as_ref(),
+                    &mut obj.sections,
+                    &config.reloc_renames,
+                    &mut obj.diagnostics,
+                );
+            }
+            return Ok(obj);
+        }
+    }
     let obj_file = File::parse(data)?;
     let arch = new_arch(&obj_file)?;
     let split_meta = split_meta(&obj_file)?;
 
-    // Create sorted symbol list for each section
-    let mut section_symbols = Vec::with_capacity(obj_file.sections().count());
-    for section in obj_file.sections() {
-        let mut symbols = obj_file
-            .symbols()
-            .filter(|s| s.section_index() == Some(section.index()))
-            .collect::<Vec<_>>();
+    // Create sorted symbol list for each section. Symbols are bucketed in a single pass,
+    // since objects may contain a large number of both.
+    let section_count = obj_file.sections().map(|s| s.index().0 + 1).max().unwrap_or_default();
+    let mut section_symbols = Vec::<Vec<Symbol>>::new();
+    section_symbols.resize_with(section_count, Vec::new);
+    let mut hidden_labels = vec![0usize; section_count];
+    for symbol in obj_file.symbols() {
+        let Some(section_index) = symbol.section_index() else {
+            continue;
+        };
+        if let Some(symbols) = section_symbols.get_mut(section_index.0) {
+            symbols.push(symbol);
+        }
+    }
+    cancel.check()?;
+    for (section_index, symbols) in section_symbols.iter_mut().enumerate() {
         symbols.sort_by_key(|s| s.address());
-        let section_index = section.index().0;
-        if section_index >= section_symbols.len() {
-            section_symbols.resize_with(section_index + 1, Vec::new);
+        if config.filter_local_labels {
+            let count = symbols.len();
+            symbols.retain(|s| !is_local_label(s));
+            hidden_labels[section_index] = count - symbols.len();
         }
-        section_symbols[section_index] = symbols;
     }
 
-    let mut sections = filter_sections(&obj_file, split_meta.as_ref())?;
+    let mut diagnostics = vec![];
+    let mut sections = filter_sections(&obj_file, split_meta.as_ref(), &mut diagnostics)?;
+    on_progress(LoadProgress::Sections(&sections));
     let mut section_name_counts: HashMap<String, u32> = HashMap::new();
-    for section in &mut sections {
+    for (index, section) in sections.iter_mut().enumerate() {
+        cancel.check()?;
+        if hidden_labels[section.orig_index] > 0 {
+            diagnostics.push(LoadDiagnostic::new(
+                LoadDiagnosticKind::Symbol,
+                &section.name,
+                format!("{} assembler-local labels hidden", hidden_labels[section.orig_index]),
+            ));
+        }
         section.symbols = symbols_by_section(
             arch.as_ref(),
             &obj_file,
@@ -719,6 +1168,7 @@ pub fn parse(data: &[u8], config: &DiffObjConfig) -> Result<ObjInfo> {
             &section_symbols[section.orig_index],
             split_meta.as_ref(),
             &mut section_name_counts,
+            &mut diagnostics,
         )?;
         section.relocations = relocations_by_section(
             arch.as_ref(),
@@ -726,14 +1176,74 @@ pub fn parse(data: &[u8], config: &DiffObjConfig) -> Result<ObjInfo> {
             section,
             &section_symbols,
             split_meta.as_ref(),
+            config,
+            &mut diagnostics,
         )?;
+        if config.global_symbols_only {
+            hide_local_symbols(section, &mut diagnostics);
+        }
+        on_progress(LoadProgress::Symbols(index, section));
+    }
+    for symbol in obj_file.symbols() {
+        let Some(section_index) = symbol.section_index() else {
+            continue;
+        };
+        if matches!(symbol.kind(), SymbolKind::Section | SymbolKind::File)
+            || sections.iter().any(|s| s.orig_index == section_index.0)
+        {
+            continue;
+        }
+        let section_name =
+            obj_file.section_by_index(section_index).and_then(|s| s.name()).unwrap_or("?");
+        diagnostics.push(LoadDiagnostic::new(
+            LoadDiagnosticKind::Symbol,
+            symbol.name().unwrap_or("?"),
+            format!("Located in skipped section {section_name}"),
+        ));
+    }
+    let dynamic_imports = parse_dynamic_imports(&obj_file);
+    let abi_sections = parse_abi_sections(&obj_file);
+    for section in sections.iter_mut().filter(|s| s.kind == ObjSectionKind::Code) {
+        add_plt_symbols(section, &dynamic_imports);
     }
     if config.combine_data_sections {
         combine_data_sections(&mut sections)?;
     }
-    line_info(&obj_file, &mut sections, data)?;
-    let common = common_symbols(arch.as_ref(), &obj_file, split_meta.as_ref())?;
-    Ok(ObjInfo { arch, path: None, timestamp: None, sections, common, split_meta })
+    line_info(&obj_file, &mut sections, data, obj_path)?;
+    let mut common = common_symbols(arch.as_ref(), &obj_file, split_meta.as_ref())?;
+    if config.strip_symbol_versions && obj_file.format() == BinaryFormat::Elf {
+        strip_symbol_versions(arch.as_ref(), &mut sections, &mut common);
+    }
+    if !config.reloc_renames.is_empty() {
+        rename_reloc_targets(arch.as_ref(), &mut sections, &config.reloc_renames, &mut diagnostics);
+    }
+    #[cfg(feature = "dwarf")]
+    let unwind_info = super::unwind::parse_unwind_info(&obj_file, &sections).unwrap_or_else(|e| {
+        log::warn!("Failed to parse unwind info: {e:#}");
+        Default::default()
+    });
+    #[cfg(not(feature = "dwarf"))]
+    let unwind_info = Default::default();
+    let seh_unwind_info = super::seh::parse_seh_unwind_info(&obj_file, &sections, &mut diagnostics)
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to parse .pdata unwind info: {e:#}");
+            Default::default()
+        });
+    Ok(ObjInfo {
+        arch,
+        path: None,
+        timestamp: None,
+        endianness: obj_file.endianness(),
+        architecture: obj_file.architecture(),
+        sections,
+        common,
+        split_meta,
+        unwind_info,
+        seh_unwind_info,
+        diagnostics,
+        dynamic_imports,
+        abi_sections,
+    })
 }
 
 pub fn has_function(obj_path: &Path, symbol_name: &str) -> Result<bool> {
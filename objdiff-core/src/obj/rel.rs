@@ -0,0 +1,567 @@
+//! Loader for GameCube/Wii relocatable modules (REL) and Wii dynamic modules (RSO), so that
+//! functions inside a module can be diffed without extracting objects first.
+//!
+//! Modules have no symbol table beyond RSO exports, so symbols are generated at relocation
+//! targets and named after their module-relative location (e.g. `fn_1_1A4`). References to
+//! the main executable are named after their absolute address (e.g. `fn_80012345`), matching
+//! the names generated by decomp-toolkit.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{bail, ensure, Context, Result};
+use object::{elf, Architecture, Endianness, RelocationFlags};
+
+use crate::{
+    arch::{ppc::ObjArchPpc, ObjArch},
+    obj::{
+        LoadDiagnostic, LoadDiagnosticKind, ObjInfo, ObjReloc, ObjSection, ObjSectionKind,
+        ObjSymbol, ObjSymbolFlagSet, ObjSymbolFlags, ObjSymbolKind,
+    },
+    util::CancellationToken,
+};
+
+const R_DOLPHIN_NOP: u8 = 201;
+const R_DOLPHIN_SECTION: u8 = 202;
+const R_DOLPHIN_END: u8 = 203;
+const R_DOLPHIN_MRKREF: u8 = 204;
+
+const REL_HEADER_SIZE: u32 = 0x40;
+const RSO_HEADER_SIZE: u32 = 0x58;
+const MAX_SECTIONS: u32 = 64;
+
+/// Conventional section layout of modules built with the CodeWarrior toolchain.
+const SECTION_NAMES: [(&str, ObjSectionKind); 6] = [
+    (".text", ObjSectionKind::Code),
+    (".ctors", ObjSectionKind::Data),
+    (".dtors", ObjSectionKind::Data),
+    (".rodata", ObjSectionKind::Data),
+    (".data", ObjSectionKind::Data),
+    (".bss", ObjSectionKind::Bss),
+];
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ModuleKind {
+    Rel,
+    Rso,
+}
+
+struct SectionInfo {
+    index: usize,
+    offset: u32,
+    size: u32,
+    kind: ObjSectionKind,
+}
+
+enum RelocTarget {
+    /// Offset within a section of this module
+    Internal { section: usize, offset: u32 },
+    /// Absolute address in the main executable
+    Executable(u32),
+    /// Offset within a section of another REL module
+    Module { module_id: u32, section: usize, offset: u32 },
+    /// Named symbol imported by an RSO module
+    Import(String),
+}
+
+struct RawReloc {
+    section: usize,
+    offset: u32,
+    r_type: u32,
+    target: RelocTarget,
+}
+
+struct Module {
+    id: u32,
+    sections: Vec<SectionInfo>,
+    relocations: Vec<RawReloc>,
+    /// Named symbols (prolog, epilog, exports) keyed by section and offset
+    named: BTreeMap<(usize, u32), String>,
+    diagnostics: Vec<LoadDiagnostic>,
+}
+
+fn read_u32(data: &[u8], offset: u32) -> Result<u32> {
+    let offset = offset as usize;
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_be_bytes(b.try_into().unwrap()))
+        .with_context(|| format!("Offset {offset:#x} out of bounds"))
+}
+
+fn read_u16(data: &[u8], offset: u32) -> Result<u16> {
+    let offset = offset as usize;
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_be_bytes(b.try_into().unwrap()))
+        .with_context(|| format!("Offset {offset:#x} out of bounds"))
+}
+
+fn read_u8(data: &[u8], offset: u32) -> Result<u8> {
+    data.get(offset as usize).copied().with_context(|| format!("Offset {offset:#x} out of bounds"))
+}
+
+fn read_string(data: &[u8], offset: u32) -> Result<String> {
+    let bytes = data.get(offset as usize..).context("String offset out of bounds")?;
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    Ok(String::from_utf8_lossy(&bytes[..end]).into_owned())
+}
+
+/// Checks whether the data looks like a REL or RSO module. Modules have no magic number, so
+/// this only checks that the header is plausible; callers should try other formats first.
+pub fn detect(data: &[u8]) -> Option<ModuleKind> {
+    let len = data.len() as u32;
+    let section_table_fits = |count: u32, offset: u32, header_size: u32| {
+        (1..=MAX_SECTIONS).contains(&count)
+            && offset >= header_size
+            && offset.checked_add(count * 8).is_some_and(|end| end <= len)
+    };
+    // REL: module ID, next, prev, section count, section table offset, ..., version
+    if let (Ok(id), Ok(0), Ok(0), Ok(count), Ok(offset), Ok(version)) = (
+        read_u32(data, 0x0),
+        read_u32(data, 0x4),
+        read_u32(data, 0x8),
+        read_u32(data, 0xC),
+        read_u32(data, 0x10),
+        read_u32(data, 0x1C),
+    ) {
+        if id != 0
+            && (1..=3).contains(&version)
+            && section_table_fits(count, offset, REL_HEADER_SIZE)
+        {
+            return Some(ModuleKind::Rel);
+        }
+    }
+    // RSO: next, prev, section count, section table offset, ..., version
+    if let (Ok(0), Ok(0), Ok(count), Ok(offset), Ok(version)) = (
+        read_u32(data, 0x0),
+        read_u32(data, 0x4),
+        read_u32(data, 0x8),
+        read_u32(data, 0xC),
+        read_u32(data, 0x18),
+    ) {
+        if version == 1 && section_table_fits(count, offset, RSO_HEADER_SIZE) {
+            return Some(ModuleKind::Rso);
+        }
+    }
+    None
+}
+
+fn section_name(index: usize, kind: ObjSectionKind) -> String {
+    match index.checked_sub(1).and_then(|i| SECTION_NAMES.get(i)) {
+        Some((name, expected)) if *expected == kind => name.to_string(),
+        _ => format!(".section{index}"),
+    }
+}
+
+fn read_sections(data: &[u8], count: u32, table_offset: u32) -> Result<Vec<SectionInfo>> {
+    let mut sections = vec![];
+    for index in 0..count {
+        let entry = table_offset + index * 8;
+        let offset = read_u32(data, entry)?;
+        let size = read_u32(data, entry + 4)?;
+        if size == 0 {
+            continue;
+        }
+        let kind = if offset == 0 {
+            ObjSectionKind::Bss
+        } else if offset & 1 != 0 {
+            ObjSectionKind::Code
+        } else {
+            ObjSectionKind::Data
+        };
+        let offset = offset & !1;
+        ensure!(
+            kind == ObjSectionKind::Bss
+                || offset.checked_add(size).is_some_and(|end| end as usize <= data.len()),
+            "Section {index} out of bounds"
+        );
+        sections.push(SectionInfo { index: index as usize, offset, size, kind });
+    }
+    Ok(sections)
+}
+
+/// Adds the prolog, epilog and unresolved functions referenced by the header.
+fn add_entry_points(
+    data: &[u8],
+    named: &mut BTreeMap<(usize, u32), String>,
+    section_offset: u32,
+    function_offset: u32,
+) -> Result<()> {
+    for (i, name) in ["_prolog", "_epilog", "_unresolved"].into_iter().enumerate() {
+        let section = read_u8(data, section_offset + i as u32)? as usize;
+        let offset = read_u32(data, function_offset + i as u32 * 4)?;
+        if section != 0 {
+            named.insert((section, offset), name.to_string());
+        }
+    }
+    Ok(())
+}
+
+fn read_rel(data: &[u8]) -> Result<Module> {
+    let id = read_u32(data, 0x0)?;
+    let sections = read_sections(data, read_u32(data, 0xC)?, read_u32(data, 0x10)?)?;
+    let imp_offset = read_u32(data, 0x28)?;
+    let imp_size = read_u32(data, 0x2C)?;
+    let mut named = BTreeMap::new();
+    add_entry_points(data, &mut named, 0x30, 0x34)?;
+
+    let mut relocations = vec![];
+    let mut diagnostics = vec![];
+    for imp in 0..imp_size / 8 {
+        let module_id = read_u32(data, imp_offset + imp * 8)?;
+        let mut entry = read_u32(data, imp_offset + imp * 8 + 4)?;
+        let mut section = None;
+        let mut position = 0u32;
+        loop {
+            let delta = read_u16(data, entry)?;
+            let r_type = read_u8(data, entry + 2)?;
+            let target_section = read_u8(data, entry + 3)? as usize;
+            let addend = read_u32(data, entry + 4)?;
+            entry += 8;
+            position = position.wrapping_add(delta as u32);
+            match r_type {
+                R_DOLPHIN_END => break,
+                R_DOLPHIN_SECTION => {
+                    section = Some(target_section);
+                    position = 0;
+                }
+                R_DOLPHIN_NOP | R_DOLPHIN_MRKREF => {}
+                _ if r_type as u32 == elf::R_PPC_NONE => {}
+                _ => {
+                    let Some(section) = section else {
+                        bail!("Relocation before R_DOLPHIN_SECTION in import {imp}");
+                    };
+                    let target = if module_id == id {
+                        RelocTarget::Internal { section: target_section, offset: addend }
+                    } else if module_id == 0 {
+                        RelocTarget::Executable(addend)
+                    } else {
+                        RelocTarget::Module { module_id, section: target_section, offset: addend }
+                    };
+                    relocations.push(RawReloc {
+                        section,
+                        offset: position,
+                        r_type: r_type as u32,
+                        target,
+                    });
+                }
+            }
+        }
+    }
+    if relocations.is_empty() && imp_size > 0 {
+        diagnostics.push(LoadDiagnostic::new(
+            LoadDiagnosticKind::Relocation,
+            "imports",
+            "No relocations found in import table",
+        ));
+    }
+    Ok(Module { id, sections, relocations, named, diagnostics })
+}
+
+fn read_rso(data: &[u8]) -> Result<Module> {
+    let sections = read_sections(data, read_u32(data, 0x8)?, read_u32(data, 0xC)?)?;
+    let mut named = BTreeMap::new();
+    add_entry_points(data, &mut named, 0x20, 0x24)?;
+
+    // Exports: name offset, section offset, section index, hash
+    let export_offset = read_u32(data, 0x40)?;
+    let export_size = read_u32(data, 0x44)?;
+    let export_names = read_u32(data, 0x48)?;
+    for i in 0..export_size / 16 {
+        let entry = export_offset + i * 16;
+        let name = read_string(data, export_names + read_u32(data, entry)?)?;
+        let offset = read_u32(data, entry + 4)?;
+        let section = read_u32(data, entry + 8)? as usize;
+        named.insert((section, offset), name);
+    }
+
+    // Imports: name offset, value, relocation offset
+    let import_offset = read_u32(data, 0x4C)?;
+    let import_size = read_u32(data, 0x50)?;
+    let import_names = read_u32(data, 0x54)?;
+    let mut imports = vec![];
+    for i in 0..import_size / 12 {
+        imports.push(read_string(data, import_names + read_u32(data, import_offset + i * 12)?)?);
+    }
+
+    // Relocations: module offset, symbol index and type, symbol offset. Internal relocations
+    // target a section of this module, external relocations target an import.
+    let mut relocations = vec![];
+    let mut diagnostics = vec![];
+    for (table, external) in [(0x30, false), (0x38, true)] {
+        let offset = read_u32(data, table)?;
+        let size = read_u32(data, table + 4)?;
+        for i in 0..size / 12 {
+            let entry = offset + i * 12;
+            let module_offset = read_u32(data, entry)?;
+            let id_and_type = read_u32(data, entry + 4)?;
+            let sym_offset = read_u32(data, entry + 8)?;
+            let Some(section) =
+                sections.iter().find(|s| (s.offset..s.offset + s.size).contains(&module_offset))
+            else {
+                diagnostics.push(LoadDiagnostic::new(
+                    LoadDiagnosticKind::Relocation,
+                    format!("{module_offset:#x}"),
+                    "Not within any section",
+                ));
+                continue;
+            };
+            let id = (id_and_type >> 8) as usize;
+            let target = if external {
+                let Some(name) = imports.get(id) else {
+                    diagnostics.push(LoadDiagnostic::new(
+                        LoadDiagnosticKind::Relocation,
+                        format!("{module_offset:#x}"),
+                        format!("Invalid import index {id}"),
+                    ));
+                    continue;
+                };
+                RelocTarget::Import(name.clone())
+            } else {
+                RelocTarget::Internal { section: id, offset: sym_offset }
+            };
+            relocations.push(RawReloc {
+                section: section.index,
+                offset: module_offset - section.offset,
+                r_type: id_and_type & 0xFF,
+                target,
+            });
+        }
+    }
+    Ok(Module { id: 0, sections, relocations, named, diagnostics })
+}
+
+fn new_symbol(
+    name: String,
+    arch: &dyn ObjArch,
+    address: u64,
+    size: u64,
+    kind: ObjSymbolKind,
+    flags: ObjSymbolFlags,
+    section: Option<usize>,
+) -> ObjSymbol {
+    ObjSymbol {
+        demangled_name: arch.demangle(&name),
+        name,
+        address,
+        section_address: address,
+        size,
+        size_known: false,
+        kind,
+        flags: ObjSymbolFlagSet(flags.into()),
+        orig_section_index: section,
+        virtual_address: None,
+        original_index: None,
+        bytes: vec![],
+    }
+}
+
+fn symbol_kind(kind: ObjSectionKind) -> ObjSymbolKind {
+    match kind {
+        ObjSectionKind::Code => ObjSymbolKind::Function,
+        ObjSectionKind::Data | ObjSectionKind::Bss => ObjSymbolKind::Object,
+    }
+}
+
+fn is_branch(r_type: u32) -> bool {
+    matches!(r_type, elf::R_PPC_REL24 | elf::R_PPC_ADDR24 | elf::R_PPC_REL14 | elf::R_PPC_ADDR14)
+}
+
+/// Whether the instruction before `offset` in a code section ends a function (`blr`) or is
+/// padding.
+fn follows_function_end(data: &[u8], section: &SectionInfo, offset: u32) -> bool {
+    offset >= 4
+        && offset <= section.size
+        && matches!(read_u32(data, section.offset + offset - 4), Ok(0x4E800020 | 0))
+}
+
+/// Parses a REL or RSO module. Sections are placed at address 0, so symbol and relocation
+/// addresses are module-section-relative, like in relocatable ELF objects.
+pub fn parse(data: &[u8], kind: ModuleKind, cancel: &CancellationToken) -> Result<ObjInfo> {
+    let module = match kind {
+        ModuleKind::Rel => read_rel(data).context("Failed to parse REL module")?,
+        ModuleKind::Rso => read_rso(data).context("Failed to parse RSO module")?,
+    };
+    let arch: Box<dyn ObjArch> = Box::new(ObjArchPpc { extab: None });
+    let mut diagnostics = module.diagnostics;
+
+    // Symbol start offsets in each section: the section start, named symbols and internal
+    // relocation targets that aren't within an existing function. Branch targets are function
+    // entry points, while other references into code (jump tables, `fn+offset`) are resolved
+    // relative to the containing function, unless they follow the end of one.
+    let mut starts = BTreeMap::<usize, BTreeSet<u32>>::new();
+    for section in &module.sections {
+        starts.entry(section.index).or_default().insert(0);
+    }
+    for &(section, offset) in module.named.keys() {
+        starts.entry(section).or_default().insert(offset);
+    }
+    for reloc in &module.relocations {
+        let RelocTarget::Internal { section, offset } = reloc.target else {
+            continue;
+        };
+        let Some(info) = module.sections.iter().find(|s| s.index == section) else {
+            continue;
+        };
+        if info.kind != ObjSectionKind::Code
+            || is_branch(reloc.r_type)
+            || follows_function_end(data, info, offset)
+        {
+            starts.entry(section).or_default().insert(offset);
+        }
+    }
+
+    let mut sections = Vec::with_capacity(module.sections.len());
+    for info in &module.sections {
+        cancel.check()?;
+        let name = section_name(info.index, info.kind);
+        let section_data = match info.kind {
+            ObjSectionKind::Bss => vec![],
+            _ => data[info.offset as usize..(info.offset + info.size) as usize].to_vec(),
+        };
+        let offsets = starts
+            .get(&info.index)
+            .map(|s| s.range(..info.size).copied().collect::<Vec<_>>())
+            .unwrap_or_default();
+        let mut symbols = Vec::with_capacity(offsets.len());
+        for (i, &offset) in offsets.iter().enumerate() {
+            let end = offsets.get(i + 1).copied().unwrap_or(info.size);
+            let (symbol_name, flags) = match module.named.get(&(info.index, offset)) {
+                Some(name) => (name.clone(), ObjSymbolFlags::Global),
+                None => {
+                    let prefix = if info.kind == ObjSectionKind::Code { "fn" } else { "lbl" };
+                    (format!("{prefix}_{}_{offset:X}", info.index), ObjSymbolFlags::Local)
+                }
+            };
+            let mut symbol = new_symbol(
+                symbol_name,
+                arch.as_ref(),
+                offset as u64,
+                (end - offset) as u64,
+                symbol_kind(info.kind),
+                flags,
+                Some(info.index),
+            );
+            if let Some(bytes) = section_data.get(offset as usize..end as usize) {
+                symbol.bytes = bytes.to_vec();
+            }
+            symbols.push(symbol);
+        }
+        sections.push(ObjSection {
+            name,
+            kind: info.kind,
+            address: 0,
+            size: info.size as u64,
+            align: 1,
+            data: section_data,
+            orig_index: info.index,
+            symbols,
+            relocations: vec![],
+            virtual_address: None,
+            line_info: Default::default(),
+            merge_strings: false,
+        });
+    }
+
+    cancel.check()?;
+    for reloc in &module.relocations {
+        let Some(section_idx) = sections.iter().position(|s| s.orig_index == reloc.section) else {
+            diagnostics.push(LoadDiagnostic::new(
+                LoadDiagnosticKind::Relocation,
+                format!("{}:{:#x}", reloc.section, reloc.offset),
+                "Located in unknown section",
+            ));
+            continue;
+        };
+        let (target, addend) = match &reloc.target {
+            RelocTarget::Internal { section, offset } => {
+                let target = sections
+                    .iter()
+                    .find(|s| s.orig_index == *section)
+                    .and_then(|s| s.symbols.iter().rev().find(|sym| sym.address <= *offset as u64));
+                match target {
+                    Some(target) => (target.clone(), *offset as i64 - target.address as i64),
+                    None => {
+                        diagnostics.push(LoadDiagnostic::new(
+                            LoadDiagnosticKind::Relocation,
+                            format!("{}:{:#x}", sections[section_idx].name, reloc.offset),
+                            format!("Target {section}:{offset:#x} is outside of the module"),
+                        ));
+                        continue;
+                    }
+                }
+            }
+            RelocTarget::Executable(address) => {
+                let (prefix, kind) = if is_branch(reloc.r_type) {
+                    ("fn", ObjSymbolKind::Function)
+                } else {
+                    ("lbl", ObjSymbolKind::Unknown)
+                };
+                let name = format!("{prefix}_{address:08X}");
+                let symbol = new_symbol(
+                    name,
+                    arch.as_ref(),
+                    *address as u64,
+                    0,
+                    kind,
+                    ObjSymbolFlags::Global,
+                    None,
+                );
+                (symbol, 0)
+            }
+            RelocTarget::Module { module_id, section, offset } => {
+                let prefix = if is_branch(reloc.r_type) { "fn" } else { "lbl" };
+                let name = format!("{prefix}_{module_id}_{section}_{offset:X}");
+                let symbol = new_symbol(
+                    name,
+                    arch.as_ref(),
+                    *offset as u64,
+                    0,
+                    ObjSymbolKind::Unknown,
+                    ObjSymbolFlags::Global,
+                    None,
+                );
+                (symbol, 0)
+            }
+            RelocTarget::Import(name) => {
+                let symbol = new_symbol(
+                    name.clone(),
+                    arch.as_ref(),
+                    0,
+                    0,
+                    ObjSymbolKind::Unknown,
+                    ObjSymbolFlags::Global,
+                    None,
+                );
+                (symbol, 0)
+            }
+        };
+        sections[section_idx].relocations.push(ObjReloc {
+            flags: RelocationFlags::Elf { r_type: reloc.r_type },
+            address: reloc.offset as u64,
+            target,
+            addend,
+            original: None,
+            pseudo: false,
+        });
+    }
+    for section in &mut sections {
+        section.relocations.sort_by_key(|r| r.address);
+    }
+    if module.id != 0 {
+        log::debug!("Loaded REL module {} with {} sections", module.id, sections.len());
+    }
+
+    Ok(ObjInfo {
+        arch,
+        path: None,
+        timestamp: None,
+        endianness: Endianness::Big,
+        architecture: Architecture::PowerPc,
+        sections,
+        common: vec![],
+        split_meta: None,
+        unwind_info: Default::default(),
+        seh_unwind_info: Default::default(),
+        diagnostics,
+        dynamic_imports: Default::default(),
+        abi_sections: vec![],
+    })
+}
@@ -0,0 +1,126 @@
+use anyhow::{bail, ensure, Result};
+
+use crate::obj::{ObjInfo, SymbolRef};
+
+/// A byte pattern to search for. `None` entries match any byte.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BytePattern(pub Vec<Option<u8>>);
+
+impl BytePattern {
+    /// Parses a search query.
+    ///
+    /// Queries starting with `#` are immediate values (e.g. `#0x80001234` or `#-1`), encoded
+    /// in the object's byte order using the smallest of 2, 4 or 8 bytes that fits. Otherwise,
+    /// the query is a sequence of hex bytes, optionally separated by whitespace, where `??`
+    /// matches any byte (e.g. `3c 60 ?? ?? 38 63`).
+    pub fn parse(query: &str, endianness: object::Endianness) -> Result<Self> {
+        let query = query.trim();
+        if let Some(value) = query.strip_prefix('#') {
+            return Self::parse_immediate(value.trim(), endianness);
+        }
+        let digits = query.chars().filter(|c| !c.is_whitespace()).collect::<Vec<_>>();
+        ensure!(!digits.is_empty(), "Empty search pattern");
+        ensure!(digits.len() % 2 == 0, "Hex pattern must have an even number of digits");
+        let mut bytes = Vec::with_capacity(digits.len() / 2);
+        for pair in digits.chunks(2) {
+            match pair {
+                ['?', '?'] => bytes.push(None),
+                [hi, lo] => match (hi.to_digit(16), lo.to_digit(16)) {
+                    (Some(hi), Some(lo)) => bytes.push(Some((hi << 4 | lo) as u8)),
+                    _ => bail!("Invalid hex byte: {hi}{lo}"),
+                },
+                _ => unreachable!(),
+            }
+        }
+        ensure!(bytes.iter().any(Option::is_some), "Pattern must contain at least one byte");
+        Ok(Self(bytes))
+    }
+
+    fn parse_immediate(value: &str, endianness: object::Endianness) -> Result<Self> {
+        let (negative, value) = match value.strip_prefix('-') {
+            Some(value) => (true, value),
+            None => (false, value),
+        };
+        let magnitude =
+            if let Some(hex) = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+                u64::from_str_radix(hex, 16)
+            } else {
+                value.parse::<u64>()
+            };
+        let Ok(magnitude) = magnitude else { bail!("Invalid immediate value: {value}") };
+        let value = if negative { (magnitude as i64).wrapping_neg() } else { magnitude as i64 };
+        let size = if i16::try_from(value).is_ok() || u16::try_from(value).is_ok() {
+            2
+        } else if i32::try_from(value).is_ok() || u32::try_from(value).is_ok() {
+            4
+        } else {
+            8
+        };
+        let bytes = match endianness {
+            object::Endianness::Little => value.to_le_bytes()[..size].to_vec(),
+            object::Endianness::Big => value.to_be_bytes()[8 - size..].to_vec(),
+        };
+        Ok(Self(bytes.into_iter().map(Some).collect()))
+    }
+
+    #[inline]
+    fn matches(&self, data: &[u8]) -> bool {
+        self.0.iter().zip(data).all(|(p, b)| p.map_or(true, |p| p == *b))
+    }
+}
+
+/// A single match of a [`BytePattern`] within an object.
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub section_idx: usize,
+    /// Address of the match within the section.
+    pub address: u64,
+    /// The symbol containing the match, if any.
+    pub symbol: Option<SymbolRef>,
+    /// Offset of the match from the start of the containing symbol.
+    pub symbol_offset: u64,
+}
+
+/// Searches all sections of an object for a byte pattern, returning hits in address order.
+pub fn search_bytes(obj: &ObjInfo, pattern: &BytePattern) -> Vec<SearchHit> {
+    let mut hits = Vec::new();
+    let len = pattern.0.len();
+    // Anchor the scan on the first non-wildcard byte to skip most candidate positions
+    let Some((anchor_idx, anchor)) =
+        pattern.0.iter().enumerate().find_map(|(i, b)| b.map(|b| (i, b)))
+    else {
+        return hits;
+    };
+    for (section_idx, section) in obj.sections.iter().enumerate() {
+        let data = &section.data;
+        if data.len() < len {
+            continue;
+        }
+        let mut symbols = section
+            .symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.size > 0)
+            .map(|(i, s)| (s.address, s.address + s.size, i))
+            .collect::<Vec<_>>();
+        symbols.sort_unstable();
+        for start in 0..=data.len() - len {
+            if data[start + anchor_idx] != anchor || !pattern.matches(&data[start..start + len]) {
+                continue;
+            }
+            let address = section.address + start as u64;
+            let symbol = symbols
+                .iter()
+                .rev()
+                .skip_while(|(begin, _, _)| *begin > address)
+                .find(|(_, end, _)| address < *end);
+            hits.push(SearchHit {
+                section_idx,
+                address,
+                symbol: symbol.map(|&(_, _, symbol_idx)| SymbolRef { section_idx, symbol_idx }),
+                symbol_offset: symbol.map_or(0, |(begin, _, _)| address - begin),
+            });
+        }
+    }
+    hits
+}
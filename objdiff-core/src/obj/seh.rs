@@ -0,0 +1,486 @@
+//! Windows ARM and ARM64 structured exception handling unwind data (`.pdata` and `.xdata`).
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+use object::{
+    Architecture, BinaryFormat, File, Object, ObjectSection, ObjectSymbol, Relocation,
+    RelocationTarget, SectionIndex,
+};
+
+use crate::obj::{LoadDiagnostic, LoadDiagnosticKind, ObjSection, ObjSymbolKind};
+
+/// The unwind code format, which differs between ARM (Thumb-2) and ARM64 (including ARM64EC).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SehArch {
+    Arm,
+    Arm64,
+}
+
+/// An epilog scope from an `.xdata` record.
+#[derive(Debug, Clone)]
+pub struct SehEpilog {
+    /// Offset of the epilog relative to the start of the range, or `None` for a single epilog
+    /// at the end of the range
+    pub offset: Option<u64>,
+    /// Condition code under which the epilog executes (ARM only)
+    pub condition: Option<u8>,
+    pub codes: Vec<String>,
+}
+
+/// A decoded `.xdata` record.
+#[derive(Debug, Clone)]
+pub struct SehXdata {
+    pub section_name: String,
+    pub offset: u64,
+    pub version: u8,
+    /// The range is a fragment of a function without a prolog (ARM only)
+    pub fragment: bool,
+    pub prolog: Vec<String>,
+    pub epilogs: Vec<SehEpilog>,
+    /// Exception handler symbol name
+    pub handler: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum SehUnwindData {
+    /// Unwind data packed into the `.pdata` entry itself
+    Packed { fragment: bool, fields: String },
+    /// Unwind data in a separate `.xdata` record
+    Xdata(SehXdata),
+}
+
+/// A `.pdata` entry covering a range of a function. Functions split into several chunks
+/// (e.g. separated cold paths or very large functions) have one entry per chunk.
+#[derive(Debug, Clone)]
+pub struct SehUnwindEntry {
+    /// Offset of the entry within its `.pdata` section
+    pub pdata_offset: u64,
+    /// Start of the range, relative to the function
+    pub start: u64,
+    pub length: u64,
+    pub data: SehUnwindData,
+}
+
+/// Windows unwind information for a single function.
+#[derive(Debug, Clone)]
+pub struct SehUnwindInfo {
+    pub arch: SehArch,
+    /// Entries sorted by start offset
+    pub entries: Vec<SehUnwindEntry>,
+}
+
+impl SehUnwindInfo {
+    /// Renders the unwind information as lines of text, used for display and diffing. Offsets
+    /// are relative to the function so that they line up between objects.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![];
+        for entry in &self.entries {
+            let kind = if entry.start == 0 { "function" } else { "chunk" };
+            lines.push(format!("{kind} +{:#x}..+{:#x}", entry.start, entry.start + entry.length));
+            match &entry.data {
+                SehUnwindData::Packed { fragment, fields } => {
+                    let fragment = if *fragment { " fragment" } else { "" };
+                    lines.push(format!("  packed{fragment}: {fields}"));
+                }
+                SehUnwindData::Xdata(xdata) => {
+                    let fragment = if xdata.fragment { ", fragment" } else { "" };
+                    lines.push(format!("  xdata: version {}{fragment}", xdata.version));
+                    lines.push("  prolog:".to_string());
+                    lines.extend(xdata.prolog.iter().map(|c| format!("    {c}")));
+                    for epilog in &xdata.epilogs {
+                        let location = match epilog.offset {
+                            Some(offset) => format!("+{offset:#x}"),
+                            None => "end".to_string(),
+                        };
+                        match epilog.condition {
+                            Some(condition) if condition != 0xe => lines.push(format!(
+                                "  epilog at {location} (condition {condition:#x}):"
+                            )),
+                            _ => lines.push(format!("  epilog at {location}:")),
+                        }
+                        lines.extend(epilog.codes.iter().map(|c| format!("    {c}")));
+                    }
+                    if let Some(handler) = &xdata.handler {
+                        lines.push(format!("  handler: {handler}"));
+                    }
+                }
+            }
+        }
+        lines
+    }
+}
+
+/// Parses the `.pdata` sections of a COFF ARM or ARM64 object and associates each entry with
+/// the function containing its range.
+///
+/// Returns a map of original symbol index to unwind information. Entries that can't be
+/// associated with a function are reported in `diagnostics`.
+pub fn parse_seh_unwind_info(
+    obj_file: &File<'_>,
+    sections: &[ObjSection],
+    diagnostics: &mut Vec<LoadDiagnostic>,
+) -> Result<BTreeMap<usize, SehUnwindInfo>> {
+    let mut result = BTreeMap::<usize, SehUnwindInfo>::new();
+    if obj_file.format() != BinaryFormat::Coff {
+        return Ok(result);
+    }
+    let arch = match obj_file.architecture() {
+        Architecture::Arm => SehArch::Arm,
+        // Also covers ARM64EC, which uses the ARM64 unwind format
+        Architecture::Aarch64 => SehArch::Arm64,
+        _ => return Ok(result),
+    };
+    for pdata_section in obj_file.sections().filter(|s| s.name().is_ok_and(|n| n == ".pdata")) {
+        let data = pdata_section.uncompressed_data()?;
+        let relocations = pdata_section.relocations().collect::<BTreeMap<u64, Relocation>>();
+        for pdata_offset in (0..data.len() as u64 / 8).map(|i| i * 8) {
+            let Some((_, section_index, mut begin)) =
+                resolve_relocation(obj_file, &data, pdata_offset, &relocations)
+            else {
+                diagnostics.push(LoadDiagnostic::new(
+                    LoadDiagnosticKind::Section,
+                    ".pdata",
+                    format!("Failed to resolve function for entry at {pdata_offset:#x}"),
+                ));
+                continue;
+            };
+            if arch == SehArch::Arm {
+                // Clear the Thumb bit
+                begin &= !1;
+            }
+            let Some(symbol) = sections
+                .iter()
+                .filter(|s| s.orig_index == section_index)
+                .flat_map(|s| &s.symbols)
+                .filter(|s| s.kind == ObjSymbolKind::Function)
+                .find(|s| begin >= s.section_address && begin < s.section_address + s.size.max(1))
+            else {
+                let section_name = obj_file
+                    .section_by_index(SectionIndex(section_index))
+                    .and_then(|s| s.name())
+                    .unwrap_or("?");
+                diagnostics.push(LoadDiagnostic::new(
+                    LoadDiagnosticKind::Section,
+                    ".pdata",
+                    format!(
+                        "Entry at {pdata_offset:#x} covers {section_name}+{begin:#x}, which is \
+                         outside any function symbol"
+                    ),
+                ));
+                continue;
+            };
+            let Some(symbol_index) = symbol.original_index else {
+                continue;
+            };
+
+            let word = read_u32(&data, pdata_offset + 4)?;
+            let (length, unwind_data) = if word & 3 == 0 {
+                let xdata = resolve_relocation(obj_file, &data, pdata_offset + 4, &relocations);
+                let Some((_, xdata_section_index, xdata_offset)) = xdata else {
+                    diagnostics.push(LoadDiagnostic::new(
+                        LoadDiagnosticKind::Symbol,
+                        &symbol.name,
+                        format!("Failed to resolve .xdata for .pdata entry at {pdata_offset:#x}"),
+                    ));
+                    continue;
+                };
+                match parse_xdata(obj_file, arch, xdata_section_index, xdata_offset) {
+                    Ok((length, xdata)) => (length, SehUnwindData::Xdata(xdata)),
+                    Err(e) => {
+                        diagnostics.push(LoadDiagnostic::new(
+                            LoadDiagnosticKind::Symbol,
+                            &symbol.name,
+                            format!("Failed to parse .xdata: {e:#}"),
+                        ));
+                        continue;
+                    }
+                }
+            } else {
+                let (length, fields) = decode_packed(arch, word);
+                (length, SehUnwindData::Packed { fragment: word & 3 == 2, fields })
+            };
+            result
+                .entry(symbol_index)
+                .or_insert_with(|| SehUnwindInfo { arch, entries: vec![] })
+                .entries
+                .push(SehUnwindEntry {
+                    pdata_offset,
+                    start: begin - symbol.section_address,
+                    length,
+                    data: unwind_data,
+                });
+        }
+    }
+    for info in result.values_mut() {
+        info.entries.sort_by_key(|e| e.start);
+    }
+    Ok(result)
+}
+
+/// Resolves a relocation to its target symbol name, section index and offset within that
+/// section. COFF relocations store their addend in the relocated data.
+fn resolve_relocation(
+    obj_file: &File<'_>,
+    data: &[u8],
+    address: u64,
+    relocations: &BTreeMap<u64, Relocation>,
+) -> Option<(String, usize, u64)> {
+    let reloc = relocations.get(&address)?;
+    let RelocationTarget::Symbol(idx) = reloc.target() else {
+        return None;
+    };
+    let symbol = obj_file.symbol_by_index(idx).ok()?;
+    let addend = if reloc.has_implicit_addend() {
+        read_u32(data, address).ok()? as i32 as i64
+    } else {
+        reloc.addend()
+    };
+    let section_index = symbol.section_index()?;
+    let section = obj_file.section_by_index(section_index).ok()?;
+    let offset = symbol.address().checked_sub(section.address())?;
+    let name = symbol.name().unwrap_or("?").to_string();
+    Some((name, section_index.0, offset.checked_add_signed(addend)?))
+}
+
+fn read_u32(data: &[u8], offset: u64) -> Result<u32> {
+    data.get(offset as usize..offset as usize + 4)
+        .and_then(|b| b.try_into().ok())
+        .map(u32::from_le_bytes)
+        .ok_or_else(|| anyhow!("Offset {offset:#x} out of bounds"))
+}
+
+/// Decodes packed unwind data from the second word of a `.pdata` entry, returning the length
+/// of the range and the decoded fields.
+fn decode_packed(arch: SehArch, word: u32) -> (u64, String) {
+    match arch {
+        SehArch::Arm64 => (
+            ((word >> 2) & 0x7ff) as u64 * 4,
+            format!(
+                "RegF={} RegI={} H={} CR={} FrameSize={}",
+                (word >> 13) & 7,
+                (word >> 16) & 0xf,
+                (word >> 20) & 1,
+                (word >> 21) & 3,
+                (word >> 23) * 16
+            ),
+        ),
+        SehArch::Arm => (
+            ((word >> 2) & 0x7ff) as u64 * 2,
+            format!(
+                "Ret={} H={} Reg={} R={} L={} C={} StackAdjust={}",
+                (word >> 13) & 3,
+                (word >> 15) & 1,
+                (word >> 16) & 7,
+                (word >> 19) & 1,
+                (word >> 20) & 1,
+                (word >> 21) & 1,
+                word >> 22
+            ),
+        ),
+    }
+}
+
+/// Parses an `.xdata` record, returning the length of the range it covers and the record.
+fn parse_xdata(
+    obj_file: &File<'_>,
+    arch: SehArch,
+    section_index: usize,
+    offset: u64,
+) -> Result<(u64, SehXdata)> {
+    let section = obj_file.section_by_index(SectionIndex(section_index))?;
+    let section_name = section.name()?.to_string();
+    let section_data = section.uncompressed_data()?;
+    let data = section_data
+        .get(offset as usize..)
+        .ok_or_else(|| anyhow!("Offset {offset:#x} out of bounds"))?;
+    let header = read_u32(data, 0)?;
+    let (length, fragment, mut epilog_count, mut code_words) = match arch {
+        SehArch::Arm64 => {
+            ((header & 0x3ffff) as u64 * 4, false, (header >> 22) & 0x1f, (header >> 27) & 0x1f)
+        }
+        SehArch::Arm => (
+            (header & 0x3ffff) as u64 * 2,
+            header & (1 << 22) != 0,
+            (header >> 23) & 0x1f,
+            header >> 28,
+        ),
+    };
+    let version = ((header >> 18) & 3) as u8;
+    let has_handler = header & (1 << 20) != 0;
+    let single_epilog = header & (1 << 21) != 0;
+    let mut pos = 4;
+    if epilog_count == 0 && code_words == 0 {
+        let extended = read_u32(data, pos)?;
+        epilog_count = extended & 0xffff;
+        code_words = (extended >> 16) & 0xff;
+        pos += 4;
+    }
+
+    // (offset, condition, first unwind code index)
+    let mut scopes = vec![];
+    if single_epilog {
+        scopes.push((None, None, epilog_count as usize));
+    } else {
+        for _ in 0..epilog_count {
+            let scope = read_u32(data, pos)?;
+            pos += 4;
+            scopes.push(match arch {
+                SehArch::Arm64 => {
+                    (Some((scope & 0x3ffff) as u64 * 4), None, (scope >> 22) as usize)
+                }
+                SehArch::Arm => (
+                    Some((scope & 0x3ffff) as u64 * 2),
+                    Some(((scope >> 20) & 0xf) as u8),
+                    (scope >> 24) as usize,
+                ),
+            });
+        }
+    }
+    let codes_len = code_words as u64 * 4;
+    let codes = data
+        .get(pos as usize..(pos + codes_len) as usize)
+        .ok_or_else(|| anyhow!("Unwind codes out of bounds"))?;
+    pos += codes_len;
+
+    let prolog = decode_codes(arch, codes, 0);
+    let epilogs = scopes
+        .into_iter()
+        .map(|(offset, condition, index)| SehEpilog {
+            offset,
+            condition,
+            codes: decode_codes(arch, codes, index),
+        })
+        .collect();
+    let handler = if has_handler {
+        let relocations = section.relocations().collect::<BTreeMap<u64, Relocation>>();
+        let (name, _, _) = resolve_relocation(obj_file, &section_data, offset + pos, &relocations)
+            .ok_or_else(|| anyhow!("Failed to resolve exception handler"))?;
+        Some(name)
+    } else {
+        None
+    };
+    Ok((length, SehXdata { section_name, offset, version, fragment, prolog, epilogs, handler }))
+}
+
+/// Decodes unwind codes starting at `index` until an end code.
+fn decode_codes(arch: SehArch, codes: &[u8], mut index: usize) -> Vec<String> {
+    let mut out = vec![];
+    while let Some(&op) = codes.get(index) {
+        let (text, len) = match arch {
+            SehArch::Arm64 => decode_arm64_code(&codes[index..]),
+            SehArch::Arm => decode_arm_code(&codes[index..]),
+        };
+        out.push(text);
+        index += len;
+        let end = match arch {
+            SehArch::Arm64 => matches!(op, 0xe4 | 0xe5),
+            SehArch::Arm => matches!(op, 0xfd..=0xff),
+        };
+        if end {
+            break;
+        }
+    }
+    out
+}
+
+/// Decodes a single ARM64 unwind code, returning its text and length in bytes.
+fn decode_arm64_code(codes: &[u8]) -> (String, usize) {
+    let b = |i: usize| codes.get(i).copied().unwrap_or(0) as u32;
+    let op = b(0);
+    // Register and offset fields of the two byte save codes
+    let x3 = ((op & 1) << 2) | (b(1) >> 6);
+    let x4 = ((op & 3) << 2) | (b(1) >> 6);
+    let z6 = b(1) & 0x3f;
+    match op {
+        0x00..=0x1f => (format!("alloc_s #{}", (op & 0x1f) * 16), 1),
+        0x20..=0x3f => (format!("save_r19r20_x [sp, #-{}]!", (op & 0x1f) * 8), 1),
+        0x40..=0x7f => (format!("save_fplr [sp, #{}]", (op & 0x3f) * 8), 1),
+        0x80..=0xbf => (format!("save_fplr_x [sp, #-{}]!", ((op & 0x3f) + 1) * 8), 1),
+        0xc0..=0xc7 => (format!("alloc_m #{}", (((op & 7) << 8) | b(1)) * 16), 2),
+        0xc8..=0xcb => (format!("save_regp x{}, x{}, [sp, #{}]", 19 + x4, 20 + x4, z6 * 8), 2),
+        0xcc..=0xcf => {
+            (format!("save_regp_x x{}, x{}, [sp, #-{}]!", 19 + x4, 20 + x4, (z6 + 1) * 8), 2)
+        }
+        0xd0..=0xd3 => (format!("save_reg x{}, [sp, #{}]", 19 + x4, z6 * 8), 2),
+        0xd4..=0xd5 => {
+            let x = ((op & 1) << 3) | (b(1) >> 5);
+            (format!("save_reg_x x{}, [sp, #-{}]!", 19 + x, ((b(1) & 0x1f) + 1) * 8), 2)
+        }
+        0xd6..=0xd7 => (format!("save_lrpair x{}, lr, [sp, #{}]", 19 + 2 * x3, z6 * 8), 2),
+        0xd8..=0xd9 => (format!("save_fregp d{}, d{}, [sp, #{}]", 8 + x3, 9 + x3, z6 * 8), 2),
+        0xda..=0xdb => {
+            (format!("save_fregp_x d{}, d{}, [sp, #-{}]!", 8 + x3, 9 + x3, (z6 + 1) * 8), 2)
+        }
+        0xdc..=0xdd => (format!("save_freg d{}, [sp, #{}]", 8 + x3, z6 * 8), 2),
+        0xde => {
+            (format!("save_freg_x d{}, [sp, #-{}]!", 8 + (b(1) >> 5), ((b(1) & 0x1f) + 1) * 8), 2)
+        }
+        0xdf => (format!("alloc_z #{}", b(1)), 2),
+        0xe0 => (format!("alloc_l #{}", ((b(1) << 16) | (b(2) << 8) | b(3)) * 16), 4),
+        0xe1 => ("set_fp".to_string(), 1),
+        0xe2 => (format!("add_fp #{}", b(1) * 8), 2),
+        0xe3 => ("nop".to_string(), 1),
+        0xe4 => ("end".to_string(), 1),
+        0xe5 => ("end_c".to_string(), 1),
+        0xe6 => ("save_next".to_string(), 1),
+        0xe7 => (format!("save_any_reg {:#04x} {:#04x}", b(1), b(2)), 3),
+        0xe8 => ("MSFT_OP_TRAP_FRAME".to_string(), 1),
+        0xe9 => ("MSFT_OP_MACHINE_FRAME".to_string(), 1),
+        0xea => ("MSFT_OP_CONTEXT".to_string(), 1),
+        0xeb => ("MSFT_OP_EC_CONTEXT".to_string(), 1),
+        0xec => ("MSFT_OP_CLEAR_UNWOUND_TO_CALL".to_string(), 1),
+        0xfc => ("pac_sign_lr".to_string(), 1),
+        _ => (format!("unknown {op:#04x}"), 1),
+    }
+}
+
+/// Decodes a single ARM (Thumb-2) unwind code, returning its text and length in bytes.
+fn decode_arm_code(codes: &[u8]) -> (String, usize) {
+    let b = |i: usize| codes.get(i).copied().unwrap_or(0) as u32;
+    let op = b(0);
+    match op {
+        0x00..=0x7f => (format!("add sp, sp, #{}", op * 4), 1),
+        0x80..=0xbf => {
+            let mask = ((op & 0x1f) << 8) | b(1);
+            (format!("pop.w {}", register_list(mask, op & 0x20 != 0)), 2)
+        }
+        0xc0..=0xcf => (format!("mov sp, r{}", op & 0xf), 1),
+        0xd0..=0xd7 => (format!("pop {}", register_range(4 + (op & 3), op & 4 != 0)), 1),
+        0xd8..=0xdf => (format!("pop.w {}", register_range(8 + (op & 3), op & 4 != 0)), 1),
+        0xe0..=0xe7 => (format!("vpop {{d8-d{}}}", 8 + (op & 7)), 1),
+        0xe8..=0xeb => (format!("addw sp, sp, #{}", (((op & 3) << 8) | b(1)) * 4), 2),
+        0xec..=0xed => (format!("pop {}", register_list(b(1), op & 1 != 0)), 2),
+        0xee if b(1) < 0x10 => (format!("MSFT_OP {}", b(1)), 2),
+        0xef if b(1) < 0x10 => (format!("ldr lr, [sp], #{}", b(1) * 4), 2),
+        0xf5 => (format!("vpop {{d{}-d{}}}", b(1) >> 4, b(1) & 0xf), 2),
+        0xf6 => (format!("vpop {{d{}-d{}}}", 16 + (b(1) >> 4), 16 + (b(1) & 0xf)), 2),
+        0xf7 => (format!("add sp, sp, #{}", ((b(1) << 8) | b(2)) * 4), 3),
+        0xf8 => (format!("add sp, sp, #{}", ((b(1) << 16) | (b(2) << 8) | b(3)) * 4), 4),
+        0xf9 => (format!("add.w sp, sp, #{}", ((b(1) << 8) | b(2)) * 4), 3),
+        0xfa => (format!("add.w sp, sp, #{}", ((b(1) << 16) | (b(2) << 8) | b(3)) * 4), 4),
+        0xfb => ("nop".to_string(), 1),
+        0xfc => ("nop.w".to_string(), 1),
+        0xfd => ("end + nop".to_string(), 1),
+        0xfe => ("end + nop.w".to_string(), 1),
+        0xff => ("end".to_string(), 1),
+        _ => (format!("unknown {op:#04x}"), 2),
+    }
+}
+
+fn register_list(mask: u32, lr: bool) -> String {
+    let mut registers =
+        (0..13).filter(|i| mask & (1 << i) != 0).map(|i| format!("r{i}")).collect::<Vec<_>>();
+    if lr {
+        registers.push("lr".to_string());
+    }
+    format!("{{{}}}", registers.join(", "))
+}
+
+fn register_range(last: u32, lr: bool) -> String {
+    if lr {
+        format!("{{r4-r{last}, lr}}")
+    } else {
+        format!("{{r4-r{last}}}")
+    }
+}
@@ -0,0 +1,313 @@
+//! Symbolization of target objects and binaries without symbols, from function lists exported
+//! by Ghidra or IDA.
+//!
+//! CSV exports, such as Ghidra's "Export to CSV" from the Functions window, are read by their
+//! header row: the address column is one of `Address`, `Location`, `Entry Point`, `Start` or
+//! `EA`, the name column `Name` or `Function Name`, and the optional size column `Size`,
+//! `Function Size` or `Length`. Without a recognized header, the columns are read as address,
+//! name and size. Tab-separated files are also accepted.
+//!
+//! JSON exports are an array of objects with the same keys (lowercase, with `_` instead of
+//! spaces), or an object with such an array under `functions`.
+//!
+//! Addresses are hexadecimal, with or without a `0x` or `segment:` prefix (`ram:80003100`).
+//! Sizes are decimal unless prefixed with `0x`.
+
+use std::{fs, mem::take, path::Path};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::Value;
+
+use crate::{
+    config::{SymbolImport, SymbolImportConflicts},
+    obj::{
+        LoadDiagnostic, LoadDiagnosticKind, ObjInfo, ObjSection, ObjSectionKind, ObjSymbol,
+        ObjSymbolFlagSet, ObjSymbolFlags, ObjSymbolKind,
+    },
+};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ImportedSymbol {
+    pub address: u64,
+    pub name: String,
+    pub size: Option<u64>,
+}
+
+const ADDRESS_COLUMNS: [&str; 6] =
+    ["address", "location", "entry point", "entry_point", "start", "ea"];
+const NAME_COLUMNS: [&str; 3] = ["name", "function name", "function_name"];
+const SIZE_COLUMNS: [&str; 4] = ["size", "function size", "function_size", "length"];
+
+fn parse_address(value: &str) -> Option<u64> {
+    let value = value.rsplit(':').next()?.trim();
+    let value = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")).unwrap_or(value);
+    u64::from_str_radix(value, 16).ok()
+}
+
+fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// Splits a CSV line into fields. Quoted fields may contain the delimiter and doubled quotes.
+fn split_csv_line(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => fields.push(take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields.into_iter().map(|f| f.trim().to_string()).collect()
+}
+
+fn parse_csv(data: &str) -> Result<Vec<ImportedSymbol>> {
+    let mut lines = data
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .peekable();
+    let Some(&(_, first)) = lines.peek() else {
+        return Ok(vec![]);
+    };
+    let delimiter = if first.contains('\t') { '\t' } else { ',' };
+    let header = split_csv_line(first, delimiter);
+    let column = |names: &[&str]| {
+        header.iter().position(|field| names.contains(&field.to_ascii_lowercase().as_str()))
+    };
+    let (address_col, name_col, size_col) = match (column(&ADDRESS_COLUMNS), column(&NAME_COLUMNS))
+    {
+        (Some(address), Some(name)) => {
+            lines.next();
+            (address, name, column(&SIZE_COLUMNS))
+        }
+        _ => (0, 1, Some(2)),
+    };
+    let mut symbols = vec![];
+    for (idx, line) in lines {
+        let fields = split_csv_line(line, delimiter);
+        let field = |col: usize| fields.get(col).map(String::as_str).unwrap_or_default();
+        let Some(address) = parse_address(field(address_col)) else {
+            bail!("Line {}: invalid address {:?}", idx + 1, field(address_col));
+        };
+        let name = field(name_col);
+        if name.is_empty() {
+            bail!("Line {}: missing name", idx + 1);
+        }
+        let size = match size_col.map(field).filter(|s| !s.is_empty()) {
+            Some(size) => Some(
+                parse_size(size)
+                    .ok_or_else(|| anyhow!("Line {}: invalid size {:?}", idx + 1, size))?,
+            ),
+            None => None,
+        };
+        symbols.push(ImportedSymbol {
+            address,
+            name: name.to_string(),
+            size: size.filter(|&s| s != 0),
+        });
+    }
+    Ok(symbols)
+}
+
+fn json_field<'a>(entry: &'a Value, names: &[&str]) -> Option<&'a Value> {
+    names.iter().find_map(|name| entry.get(name.replace(' ', "_")))
+}
+
+fn parse_json(data: &str) -> Result<Vec<ImportedSymbol>> {
+    let value: Value = serde_json::from_str(data)?;
+    let entries = match &value {
+        Value::Array(entries) => entries,
+        Value::Object(object) => object
+            .get("functions")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("Expected a \"functions\" array"))?,
+        _ => bail!("Expected an array of functions"),
+    };
+    let mut symbols = vec![];
+    for (idx, entry) in entries.iter().enumerate() {
+        let address = match json_field(entry, &ADDRESS_COLUMNS) {
+            Some(Value::Number(n)) => n.as_u64(),
+            Some(Value::String(s)) => parse_address(s),
+            _ => None,
+        }
+        .ok_or_else(|| anyhow!("Entry {idx}: missing or invalid address"))?;
+        let name = json_field(entry, &NAME_COLUMNS)
+            .and_then(Value::as_str)
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| anyhow!("Entry {idx}: missing name"))?;
+        let size = match json_field(entry, &SIZE_COLUMNS) {
+            None | Some(Value::Null) => None,
+            Some(Value::Number(n)) => n.as_u64(),
+            Some(Value::String(s)) => parse_size(s),
+            Some(_) => bail!("Entry {idx}: invalid size"),
+        };
+        symbols.push(ImportedSymbol {
+            address,
+            name: name.to_string(),
+            size: size.filter(|&s| s != 0),
+        });
+    }
+    Ok(symbols)
+}
+
+/// Reads a function list exported from Ghidra or IDA, as CSV or JSON.
+pub fn read_symbol_import(path: &Path) -> Result<Vec<ImportedSymbol>> {
+    let data =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let is_json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+        || data.trim_start().starts_with(['[', '{']);
+    let symbols = if is_json { parse_json(&data) } else { parse_csv(&data) };
+    symbols.with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// The dummy symbol covering a section without symbols.
+fn is_placeholder(section: &ObjSection, symbol: &ObjSymbol) -> bool {
+    symbol.original_index.is_none() && symbol.name.starts_with(&format!("[{}", section.name))
+}
+
+/// The section containing an address, preferring code sections when sections overlap (e.g. in
+/// relocatable objects, where every section starts at 0).
+fn find_section(sections: &[ObjSection], address: u64) -> Option<usize> {
+    let contains = |s: &ObjSection| s.address <= address && address < s.address + s.size;
+    sections
+        .iter()
+        .position(|s| s.kind == ObjSectionKind::Code && contains(s))
+        .or_else(|| sections.iter().position(contains))
+}
+
+/// Adds the imported symbols to the object. An imported symbol at the address of an existing
+/// symbol with a different name is a conflict, resolved according to `conflicts`; imported
+/// symbols inside existing symbols or outside of any section are skipped. Returns the number of
+/// symbols added or renamed. Conflicts and skipped symbols are recorded in the object's load
+/// diagnostics.
+pub fn apply_symbol_import(
+    obj: &mut ObjInfo,
+    symbols: &[ImportedSymbol],
+    conflicts: SymbolImportConflicts,
+) -> usize {
+    let ObjInfo { arch, sections, diagnostics, .. } = obj;
+    let mut count = 0;
+    let mut modified = vec![false; sections.len()];
+    for import in symbols {
+        let Some(section_idx) = find_section(sections, import.address) else {
+            diagnostics.push(LoadDiagnostic::new(
+                LoadDiagnosticKind::Symbol,
+                &import.name,
+                format!("Imported address {:#x} is outside of any section", import.address),
+            ));
+            continue;
+        };
+        let section = &mut sections[section_idx];
+        if let Some(existing) = section
+            .symbols
+            .iter()
+            .position(|s| s.address == import.address && !is_placeholder(section, s))
+        {
+            let existing = &mut section.symbols[existing];
+            if existing.name == import.name {
+                continue;
+            }
+            match conflicts {
+                SymbolImportConflicts::Keep => diagnostics.push(LoadDiagnostic::new(
+                    LoadDiagnosticKind::Symbol,
+                    &existing.name,
+                    format!("Kept instead of imported name {}", import.name),
+                )),
+                SymbolImportConflicts::Replace => {
+                    diagnostics.push(LoadDiagnostic::new(
+                        LoadDiagnosticKind::Symbol,
+                        &existing.name,
+                        format!("Renamed to imported name {}", import.name),
+                    ));
+                    existing.name = import.name.clone();
+                    existing.demangled_name = arch.demangle(&import.name);
+                    count += 1;
+                }
+            }
+            continue;
+        }
+        if let Some(outer) = section.symbols.iter().find(|s| {
+            !is_placeholder(section, s)
+                && s.size_known
+                && s.address < import.address
+                && import.address < s.address + s.size
+        }) {
+            diagnostics.push(LoadDiagnostic::new(
+                LoadDiagnosticKind::Symbol,
+                &import.name,
+                format!("Imported address {:#x} is inside {}, skipped", import.address, outer.name),
+            ));
+            continue;
+        }
+        section.symbols.push(ObjSymbol {
+            name: import.name.clone(),
+            demangled_name: arch.demangle(&import.name),
+            address: import.address,
+            section_address: import.address - section.address,
+            size: import.size.unwrap_or(0),
+            size_known: import.size.is_some(),
+            kind: match section.kind {
+                ObjSectionKind::Code => ObjSymbolKind::Function,
+                ObjSectionKind::Data | ObjSectionKind::Bss => ObjSymbolKind::Object,
+            },
+            flags: ObjSymbolFlagSet(ObjSymbolFlags::Global.into()),
+            orig_section_index: Some(section.orig_index),
+            virtual_address: None,
+            original_index: None,
+            bytes: vec![],
+        });
+        modified[section_idx] = true;
+        count += 1;
+    }
+
+    for (section, _) in sections.iter_mut().zip(modified).filter(|(_, modified)| *modified) {
+        let symbols = take(&mut section.symbols);
+        section.symbols = symbols.into_iter().filter(|s| !is_placeholder(section, s)).collect();
+        section.symbols.sort_by(|a, b| a.address.cmp(&b.address).then(a.size.cmp(&b.size)));
+        let section_end = section.address + section.size;
+        for idx in 0..section.symbols.len() {
+            let next_address =
+                section.symbols.get(idx + 1).map_or(section_end, |next| next.address);
+            let symbol = &mut section.symbols[idx];
+            if symbol.original_index.is_some() {
+                continue;
+            }
+            if symbol.size == 0 {
+                symbol.size = next_address - symbol.address;
+                diagnostics.push(LoadDiagnostic::new(
+                    LoadDiagnosticKind::Symbol,
+                    &symbol.name,
+                    format!("Imported without size, inferred as {:#x}", symbol.size),
+                ));
+            }
+            let start = symbol.section_address as usize;
+            symbol.bytes = section
+                .data
+                .get(start..start + symbol.size as usize)
+                .map(<[u8]>::to_vec)
+                .unwrap_or_default();
+        }
+    }
+    count
+}
+
+/// Reads the configured function list and adds its symbols to the object.
+pub fn import_symbols(obj: &mut ObjInfo, import: &SymbolImport) -> Result<()> {
+    let symbols = read_symbol_import(&import.path)?;
+    let count = apply_symbol_import(obj, &symbols, import.conflicts);
+    log::info!("Imported {count} of {} symbols from {}", symbols.len(), import.path.display());
+    Ok(())
+}
@@ -0,0 +1,330 @@
+//! GCC-style unwind information (`.eh_frame` and `.gcc_except_table`).
+
+/// A call site entry from an LSDA call-site table.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LsdaCallSite {
+    pub start: u64,
+    pub length: u64,
+    pub landing_pad: u64,
+    pub action: u64,
+}
+
+/// A decoded language-specific data area (`.gcc_except_table`) entry.
+#[derive(Debug, Clone)]
+pub struct LsdaInfo {
+    pub section_name: String,
+    pub offset: u64,
+    pub call_sites: Vec<LsdaCallSite>,
+}
+
+/// Unwind information for a single function, decoded from its FDE and CIE.
+#[derive(Debug, Clone)]
+pub struct UnwindInfo {
+    /// Offset of the FDE within `.eh_frame`
+    pub fde_offset: u64,
+    pub code_alignment_factor: u64,
+    pub data_alignment_factor: i64,
+    pub return_address_register: u16,
+    /// Decoded CIE initial instructions
+    pub cie_instructions: Vec<String>,
+    /// Decoded FDE instructions
+    pub instructions: Vec<String>,
+    pub lsda: Option<LsdaInfo>,
+}
+
+impl UnwindInfo {
+    /// Renders the unwind information as lines of text, used for display and diffing.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "CIE: code_align={} data_align={} ra=r{}",
+            self.code_alignment_factor, self.data_alignment_factor, self.return_address_register
+        )];
+        lines.extend(self.cie_instructions.iter().map(|i| format!("  {i}")));
+        lines.push("FDE:".to_string());
+        lines.extend(self.instructions.iter().map(|i| format!("  {i}")));
+        if let Some(lsda) = &self.lsda {
+            lines.push(format!("LSDA: {}+{:#x}", lsda.section_name, lsda.offset));
+            for site in &lsda.call_sites {
+                lines.push(format!(
+                    "  call site {:#x}..{:#x} -> landing pad {:#x}, action {}",
+                    site.start,
+                    site.start + site.length,
+                    site.landing_pad,
+                    site.action
+                ));
+            }
+        }
+        lines
+    }
+}
+
+#[cfg(feature = "dwarf")]
+mod parse {
+    use std::collections::BTreeMap;
+
+    use anyhow::{anyhow, bail, Result};
+    use gimli::{CallFrameInstruction, Reader, UnwindSection};
+    use object::{
+        Endian, File, Object, ObjectSection, ObjectSymbol, Relocation, RelocationTarget,
+        SectionIndex,
+    };
+
+    use super::{LsdaCallSite, LsdaInfo, UnwindInfo};
+    use crate::obj::{ObjSection, ObjSymbolKind};
+
+    type Slice<'a> = gimli::EndianSlice<'a, gimli::RunTimeEndian>;
+
+    /// Parses `.eh_frame` and associates each FDE with the function it covers.
+    ///
+    /// Returns a map of original symbol index to unwind information.
+    pub fn parse_unwind_info(
+        obj_file: &File<'_>,
+        sections: &[ObjSection],
+    ) -> Result<BTreeMap<usize, UnwindInfo>> {
+        let mut result = BTreeMap::new();
+        let Some(eh_frame_section) = obj_file.section_by_name(".eh_frame") else {
+            return Ok(result);
+        };
+        let data = eh_frame_section.uncompressed_data()?;
+        let endian = match obj_file.endianness() {
+            object::Endianness::Little => gimli::RunTimeEndian::Little,
+            object::Endianness::Big => gimli::RunTimeEndian::Big,
+        };
+        let address_size = if obj_file.is_64() { 8 } else { 4 };
+        let mut eh_frame = gimli::EhFrame::new(&data, endian);
+        eh_frame.set_address_size(address_size);
+        // Object files are unrelocated, so all section bases are zero
+        let bases = gimli::BaseAddresses::default().set_eh_frame(0).set_text(0);
+        let relocations = eh_frame_section.relocations().collect::<BTreeMap<u64, Relocation>>();
+
+        let mut entries = eh_frame.entries(&bases);
+        while let Some(entry) = entries.next()? {
+            let gimli::CieOrFde::Fde(partial) = entry else {
+                continue;
+            };
+            let fde = match partial
+                .parse(|section, bases, offset| section.cie_from_offset(bases, offset))
+            {
+                Ok(fde) => fde,
+                Err(e) => {
+                    log::warn!("Failed to parse FDE: {e}");
+                    continue;
+                }
+            };
+
+            // The PC begin field follows the length and CIE pointer fields
+            let fde_offset = fde.offset() as u64;
+            let fde_end = fde_offset + 4 + fde.entry_len() as u64;
+            let pc_begin = fde_offset + 8;
+            let Some((section_index, function_offset)) = relocations
+                .get(&pc_begin)
+                .and_then(|reloc| resolve_relocation(obj_file, &data, pc_begin, reloc))
+            else {
+                log::warn!("Failed to resolve function for FDE at {:#x}", fde_offset);
+                continue;
+            };
+            let Some(symbol_index) = sections
+                .iter()
+                .filter(|s| s.orig_index == section_index)
+                .flat_map(|s| &s.symbols)
+                .find(|s| s.kind == ObjSymbolKind::Function && s.section_address == function_offset)
+                .and_then(|s| s.original_index)
+            else {
+                continue;
+            };
+
+            let cie = fde.cie();
+            let code_alignment_factor = cie.code_alignment_factor();
+            let data_alignment_factor = cie.data_alignment_factor();
+            let cie_instructions = decode_instructions(
+                cie.instructions(&eh_frame, &bases),
+                code_alignment_factor,
+                data_alignment_factor,
+            )?;
+            let instructions = decode_instructions(
+                fde.instructions(&eh_frame, &bases),
+                code_alignment_factor,
+                data_alignment_factor,
+            )?;
+
+            // The LSDA pointer lives in the FDE augmentation data
+            let lsda = relocations.range(pc_begin + 4..fde_end).find_map(|(&address, reloc)| {
+                let (section_index, offset) = resolve_relocation(obj_file, &data, address, reloc)?;
+                let section = obj_file.section_by_index(SectionIndex(section_index)).ok()?;
+                let section_name = section.name().ok()?;
+                if !section_name.starts_with(".gcc_except_table") {
+                    return None;
+                }
+                let section_data = section.uncompressed_data().ok()?;
+                match parse_lsda(&section_data, offset, endian, address_size) {
+                    Ok(call_sites) => Some(LsdaInfo {
+                        section_name: section_name.to_string(),
+                        offset,
+                        call_sites,
+                    }),
+                    Err(e) => {
+                        log::warn!("Failed to parse LSDA at {section_name}+{offset:#x}: {e}");
+                        None
+                    }
+                }
+            });
+
+            result.insert(symbol_index, UnwindInfo {
+                fde_offset,
+                code_alignment_factor,
+                data_alignment_factor,
+                return_address_register: cie.return_address_register().0,
+                cie_instructions,
+                instructions,
+                lsda,
+            });
+        }
+        Ok(result)
+    }
+
+    /// Resolves a relocation in `.eh_frame` to a section index and offset within that section.
+    fn resolve_relocation(
+        obj_file: &File<'_>,
+        data: &[u8],
+        address: u64,
+        reloc: &Relocation,
+    ) -> Option<(usize, u64)> {
+        let RelocationTarget::Symbol(idx) = reloc.target() else {
+            return None;
+        };
+        let symbol = obj_file.symbol_by_index(idx).ok()?;
+        let addend = if reloc.has_implicit_addend() {
+            let bytes = data.get(address as usize..address as usize + 4)?;
+            obj_file.endianness().read_i32_bytes(bytes.try_into().ok()?) as i64
+        } else {
+            reloc.addend()
+        };
+        let section_index = symbol.section_index()?;
+        let section = obj_file.section_by_index(section_index).ok()?;
+        let offset = symbol.address().checked_sub(section.address())?;
+        Some((section_index.0, offset.checked_add_signed(addend)?))
+    }
+
+    fn decode_instructions(
+        mut iter: gimli::CallFrameInstructionIter<'_, Slice<'_>>,
+        code_alignment_factor: u64,
+        data_alignment_factor: i64,
+    ) -> Result<Vec<String>> {
+        let mut out = vec![];
+        while let Some(ins) = iter.next()? {
+            if matches!(ins, CallFrameInstruction::Nop) {
+                continue;
+            }
+            out.push(format_instruction(&ins, code_alignment_factor, data_alignment_factor));
+        }
+        Ok(out)
+    }
+
+    fn format_instruction(
+        ins: &CallFrameInstruction<usize>,
+        code_align: u64,
+        data_align: i64,
+    ) -> String {
+        match ins {
+            CallFrameInstruction::SetLoc { address } => format!("DW_CFA_set_loc: {address:#x}"),
+            CallFrameInstruction::AdvanceLoc { delta } => {
+                format!("DW_CFA_advance_loc: {}", *delta as u64 * code_align)
+            }
+            CallFrameInstruction::DefCfa { register, offset } => {
+                format!("DW_CFA_def_cfa: r{} ofs {}", register.0, offset)
+            }
+            CallFrameInstruction::DefCfaSf { register, factored_offset } => {
+                format!("DW_CFA_def_cfa_sf: r{} ofs {}", register.0, factored_offset * data_align)
+            }
+            CallFrameInstruction::DefCfaRegister { register } => {
+                format!("DW_CFA_def_cfa_register: r{}", register.0)
+            }
+            CallFrameInstruction::DefCfaOffset { offset } => {
+                format!("DW_CFA_def_cfa_offset: {offset}")
+            }
+            CallFrameInstruction::DefCfaOffsetSf { factored_offset } => {
+                format!("DW_CFA_def_cfa_offset_sf: {}", factored_offset * data_align)
+            }
+            CallFrameInstruction::Offset { register, factored_offset } => format!(
+                "DW_CFA_offset: r{} at cfa{:+}",
+                register.0,
+                *factored_offset as i64 * data_align
+            ),
+            CallFrameInstruction::OffsetExtendedSf { register, factored_offset } => format!(
+                "DW_CFA_offset_extended_sf: r{} at cfa{:+}",
+                register.0,
+                factored_offset * data_align
+            ),
+            CallFrameInstruction::Restore { register } => {
+                format!("DW_CFA_restore: r{}", register.0)
+            }
+            CallFrameInstruction::Undefined { register } => {
+                format!("DW_CFA_undefined: r{}", register.0)
+            }
+            CallFrameInstruction::SameValue { register } => {
+                format!("DW_CFA_same_value: r{}", register.0)
+            }
+            CallFrameInstruction::Register { dest_register, src_register } => {
+                format!("DW_CFA_register: r{} in r{}", dest_register.0, src_register.0)
+            }
+            CallFrameInstruction::RememberState => "DW_CFA_remember_state".to_string(),
+            CallFrameInstruction::RestoreState => "DW_CFA_restore_state".to_string(),
+            CallFrameInstruction::ArgsSize { size } => format!("DW_CFA_GNU_args_size: {size}"),
+            other => format!("{other:?}"),
+        }
+    }
+
+    /// Parses the call-site table of an LSDA.
+    fn parse_lsda(
+        data: &[u8],
+        offset: u64,
+        endian: gimli::RunTimeEndian,
+        address_size: u8,
+    ) -> Result<Vec<LsdaCallSite>> {
+        let data = data.get(offset as usize..).ok_or_else(|| anyhow!("Offset out of bounds"))?;
+        let mut reader = gimli::EndianSlice::new(data, endian);
+        let lpstart_encoding = gimli::DwEhPe(reader.read_u8()?);
+        if lpstart_encoding != gimli::DW_EH_PE_omit {
+            read_encoded(&mut reader, lpstart_encoding, address_size)?;
+        }
+        let ttype_encoding = gimli::DwEhPe(reader.read_u8()?);
+        if ttype_encoding != gimli::DW_EH_PE_omit {
+            reader.read_uleb128()?;
+        }
+        let call_site_encoding = gimli::DwEhPe(reader.read_u8()?);
+        let table_len = reader.read_uleb128()?;
+        let mut table = reader.split(table_len as usize)?;
+        let mut call_sites = vec![];
+        while !table.is_empty() {
+            call_sites.push(LsdaCallSite {
+                start: read_encoded(&mut table, call_site_encoding, address_size)?,
+                length: read_encoded(&mut table, call_site_encoding, address_size)?,
+                landing_pad: read_encoded(&mut table, call_site_encoding, address_size)?,
+                action: table.read_uleb128()?,
+            });
+        }
+        Ok(call_sites)
+    }
+
+    fn read_encoded(
+        reader: &mut Slice<'_>,
+        encoding: gimli::DwEhPe,
+        address_size: u8,
+    ) -> Result<u64> {
+        Ok(match gimli::DwEhPe(encoding.0 & 0x0f) {
+            gimli::DW_EH_PE_absptr => reader.read_address(address_size)?,
+            gimli::DW_EH_PE_uleb128 => reader.read_uleb128()?,
+            gimli::DW_EH_PE_udata2 => reader.read_u16()? as u64,
+            gimli::DW_EH_PE_udata4 => reader.read_u32()? as u64,
+            gimli::DW_EH_PE_udata8 => reader.read_u64()?,
+            gimli::DW_EH_PE_sleb128 => reader.read_sleb128()? as u64,
+            gimli::DW_EH_PE_sdata2 => reader.read_i16()? as u64,
+            gimli::DW_EH_PE_sdata4 => reader.read_i32()? as u64,
+            gimli::DW_EH_PE_sdata8 => reader.read_i64()? as u64,
+            _ => bail!("Unsupported pointer encoding {encoding}"),
+        })
+    }
+}
+
+#[cfg(feature = "dwarf")]
+pub use parse::parse_unwind_info;
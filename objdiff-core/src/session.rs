@@ -0,0 +1,327 @@
+//! Orchestration of a single diff: the project configuration, the selected unit, its build and
+//! diff results, and rebuilding when watched files change.
+//!
+//! This currently drives the CLI's interactive diff. The GUI keeps its own state for the project
+//! and unit list, and starts builds through its [`JobQueue`] directly. Every frontend derives
+//! its build configuration with [`DiffInputs::objdiff_config`], so project and unit settings
+//! are applied the same way everywhere.
+//!
+//! A [`Session`] is driven by its owner's event loop. After opening a project and selecting a
+//! unit (or setting the objects directly), [`Session::rebuild`] starts a background build, and
+//! the waker passed to [`Session::new`] is woken whenever there's progress. The owner then calls
+//! [`Session::poll`] to collect what happened and reads the latest results with
+//! [`Session::get_diff`]. Changes to watched files are rebuilt automatically.
+
+use std::{
+    mem,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::Waker,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::{
+    build::{
+        watcher::{create_watcher, Watcher, WatcherOptions},
+        BuildConfig,
+    },
+    config::{
+        build_globset, default_watch_patterns, try_project_config, ProjectConfig, ProjectObject,
+    },
+    diff::DiffObjConfig,
+    jobs::{
+        objdiff::{start_build, ObjDiffConfig, ObjDiffResult},
+        Job, JobQueue, JobResult,
+    },
+    obj::cache::ObjCache,
+};
+
+/// Something that happened since the last [`Session::poll`].
+#[derive(Debug)]
+pub enum SessionEvent {
+    /// Watched files changed, and a rebuild was started.
+    FilesChanged,
+    /// A build finished, and its results are available from [`Session::get_diff`]. The build
+    /// itself may have failed; see [`ObjDiffResult::first_status`].
+    DiffUpdated,
+    /// A build couldn't be run, e.g. because an object failed to load.
+    BuildFailed(anyhow::Error),
+}
+
+/// What to build and diff, and the settings to diff it with.
+pub struct DiffInputs<'a> {
+    /// Directory the build system runs in
+    pub project_dir: Option<&'a Path>,
+    pub project: Option<&'a ProjectConfig>,
+    /// The selected unit, with its paths resolved
+    pub unit: Option<&'a ProjectObject>,
+    pub target_path: Option<PathBuf>,
+    pub base_path: Option<PathBuf>,
+    /// The frontend's diff settings. Project and unit settings take precedence, and the unit's
+    /// symbol mappings are combined with these.
+    pub diff_obj_config: &'a DiffObjConfig,
+}
+
+impl DiffInputs<'_> {
+    /// The build configuration combining the project, unit and diff settings. Frontend state
+    /// such as the build and object caches is left unset.
+    pub fn objdiff_config(&self) -> ObjDiffConfig {
+        let project = self.project;
+        let unit = self.unit;
+        let diff_obj_config = self.diff_obj_config;
+        let mut symbol_mappings = unit.and_then(|u| u.symbol_mappings.clone()).unwrap_or_default();
+        for (left, right) in diff_obj_config.symbol_mappings.mappings.iter() {
+            symbol_mappings.insert(left.clone(), right.clone());
+        }
+        ObjDiffConfig {
+            build_config: BuildConfig {
+                project_dir: self.project_dir.map(Path::to_path_buf),
+                custom_make: project.and_then(|c| c.custom_make.clone()),
+                custom_args: project.and_then(|c| c.custom_args.clone()),
+                selected_wsl_distro: None,
+            },
+            // When diffing an object against itself, there's nothing to build
+            build_base: self.base_path != self.target_path
+                && project.is_some_and(|c| c.build_base.unwrap_or(true)),
+            build_target: project.is_some_and(|c| c.build_target.unwrap_or(false)),
+            target_path: self.target_path.clone(),
+            base_path: self.base_path.clone(),
+            // Only if the base object is the unit's own
+            base_build_command: unit
+                .filter(|u| u.base_path == self.base_path)
+                .and_then(|u| u.resolved_compile_command.clone()),
+            diff_obj_config: DiffObjConfig {
+                codegen_quirks: project
+                    .and_then(|c| c.codegen_quirks.clone())
+                    .unwrap_or_else(|| diff_obj_config.codegen_quirks.clone()),
+                compiler: unit
+                    .and_then(|u| u.scratch.as_ref())
+                    .and_then(|s| s.compiler.clone())
+                    .or_else(|| diff_obj_config.compiler.clone()),
+                ppc_gqrs: project
+                    .and_then(|c| c.ppc_gqrs.clone())
+                    .unwrap_or_else(|| diff_obj_config.ppc_gqrs.clone()),
+                reloc_renames: project
+                    .and_then(|c| c.reloc_renames.clone())
+                    .unwrap_or_else(|| diff_obj_config.reloc_renames.clone()),
+                global_symbols_only: project
+                    .and_then(|c| c.global_symbols_only)
+                    .unwrap_or(diff_obj_config.global_symbols_only),
+                symbol_matching: unit
+                    .and_then(|u| u.symbol_matching())
+                    .unwrap_or(diff_obj_config.symbol_matching),
+                ..diff_obj_config.clone()
+            },
+            symbol_mappings,
+            selecting_left: diff_obj_config.symbol_mappings.selecting_left.clone(),
+            selecting_right: diff_obj_config.symbol_mappings.selecting_right.clone(),
+            mapping_include: unit.and_then(|u| u.mapping_include()).cloned().unwrap_or_default(),
+            mapping_exclude: project
+                .and_then(|c| c.mapping_exclude.as_deref())
+                .unwrap_or_default()
+                .iter()
+                .chain(unit.and_then(|u| u.mapping_exclude()).into_iter().flatten())
+                .cloned()
+                .collect(),
+            alignment_anchors: diff_obj_config.symbol_mappings.alignment_anchors.clone(),
+            size_fixes: diff_obj_config.symbol_mappings.size_fixes.clone(),
+            build_cache: None,
+            source_path: unit.and_then(|u| u.source_path()).map(PathBuf::from),
+            force_build: false,
+            obj_cache: None,
+            // Only if the target object is the unit's own
+            target_symbol_import: unit
+                .filter(|u| u.target_path == self.target_path)
+                .and_then(|u| u.symbol_import.clone()),
+        }
+    }
+}
+
+pub struct Session {
+    waker: Waker,
+    jobs: JobQueue,
+    project_dir: Option<PathBuf>,
+    project_config: Option<ProjectConfig>,
+    /// The selected unit, with its paths resolved
+    unit: Option<ProjectObject>,
+    target_path: Option<PathBuf>,
+    base_path: Option<PathBuf>,
+    diff_obj_config: DiffObjConfig,
+    result: Option<Box<ObjDiffResult>>,
+    watcher: Option<Watcher>,
+    modified: Arc<AtomicBool>,
+    obj_cache: ObjCache,
+    /// A rebuild was requested while a build was running
+    queued_rebuild: bool,
+}
+
+impl Session {
+    /// Creates an empty session. `waker` is woken from background threads whenever
+    /// [`poll`](Self::poll) has something to report.
+    pub fn new(waker: Waker) -> Self {
+        Self {
+            waker,
+            jobs: JobQueue::default(),
+            project_dir: None,
+            project_config: None,
+            unit: None,
+            target_path: None,
+            base_path: None,
+            diff_obj_config: DiffObjConfig::default(),
+            result: None,
+            watcher: None,
+            modified: Arc::default(),
+            obj_cache: ObjCache::default(),
+            queued_rebuild: false,
+        }
+    }
+
+    /// Loads the project configuration in `project_dir` for the given version, and watches the
+    /// project for changes unless the project disables automatic rebuilds. Any selected unit
+    /// and results are cleared.
+    pub fn open_project(&mut self, project_dir: &Path, version: Option<&str>) -> Result<()> {
+        let Some((config, info)) = try_project_config(project_dir) else {
+            bail!("Project config not found in {}", project_dir.display());
+        };
+        let config = config
+            .with_context(|| format!("Reading project config {}", info.path.display()))?
+            .for_version(version)?;
+        self.watcher = None;
+        self.project_dir = Some(project_dir.to_path_buf());
+        self.project_config = Some(config);
+        self.unit = None;
+        self.target_path = None;
+        self.base_path = None;
+        self.result = None;
+        if self.rebuild_on_changes() {
+            self.start_watcher()?;
+        }
+        Ok(())
+    }
+
+    fn rebuild_on_changes(&self) -> bool {
+        self.project_config.as_ref().and_then(|c| c.rebuild_on_changes).unwrap_or(true)
+    }
+
+    fn start_watcher(&mut self) -> Result<()> {
+        let (Some(project_dir), Some(config)) = (&self.project_dir, &self.project_config) else {
+            return Ok(());
+        };
+        let patterns = config.watch_patterns.clone().unwrap_or_else(default_watch_patterns);
+        self.watcher = Some(create_watcher(
+            self.modified.clone(),
+            project_dir,
+            build_globset(&patterns)?,
+            WatcherOptions::with_debounce_ms(config.watch_debounce_ms),
+            self.waker.clone(),
+        )?);
+        Ok(())
+    }
+
+    /// Selects a unit of the open project by name. Call [`rebuild`](Self::rebuild) to build it.
+    pub fn select_unit(&mut self, name: &str) -> Result<()> {
+        let (Some(project_dir), Some(config)) = (&self.project_dir, &self.project_config) else {
+            bail!("No project open");
+        };
+        let mut unit = config
+            .units()
+            .iter()
+            .find(|unit| unit.name() == name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unit not found: {name}"))?;
+        unit.resolve_paths(project_dir, config.target_dir.as_deref(), config.base_dir.as_deref());
+        self.target_path = unit.target_path.clone();
+        self.base_path = unit.base_path.clone();
+        self.unit = Some(unit);
+        self.result = None;
+        Ok(())
+    }
+
+    /// Diffs the given objects instead of the selected unit's. Without an open project, the
+    /// objects are only read, never built. If both paths are the same, the object is diffed
+    /// against itself and isn't rebuilt.
+    pub fn set_objects(&mut self, target_path: Option<PathBuf>, base_path: Option<PathBuf>) {
+        self.target_path = target_path;
+        self.base_path = base_path;
+        self.result = None;
+    }
+
+    /// Replaces the diff configuration, taking effect on the next [`rebuild`](Self::rebuild).
+    /// Project settings such as codegen quirks and the unit's symbol mappings are applied on top
+    /// of it.
+    pub fn set_diff_config(&mut self, config: DiffObjConfig) { self.diff_obj_config = config; }
+
+    pub fn diff_config_mut(&mut self) -> &mut DiffObjConfig { &mut self.diff_obj_config }
+
+    /// Starts building and diffing the objects. If a build is already running, another one
+    /// starts once it finishes.
+    pub fn rebuild(&mut self) {
+        if self.target_path.is_none() && self.base_path.is_none() {
+            return;
+        }
+        if self.jobs.is_running(Job::ObjDiff) {
+            self.queued_rebuild = true;
+            return;
+        }
+        self.queued_rebuild = false;
+        let config = self.objdiff_config();
+        self.jobs.push(start_build(self.waker.clone(), config));
+    }
+
+    /// The build configuration for the current objects, combining the project, unit and diff
+    /// settings.
+    fn objdiff_config(&self) -> ObjDiffConfig {
+        let mut config = DiffInputs {
+            project_dir: self.project_dir.as_deref(),
+            project: self.project_config.as_ref(),
+            unit: self.unit.as_ref(),
+            target_path: self.target_path.clone(),
+            base_path: self.base_path.clone(),
+            diff_obj_config: &self.diff_obj_config,
+        }
+        .objdiff_config();
+        config.obj_cache = Some(self.obj_cache.clone());
+        config
+    }
+
+    /// Collects finished builds and file changes since the last call. Call this after the
+    /// session's waker is woken.
+    pub fn poll(&mut self) -> Vec<SessionEvent> {
+        let mut events = vec![];
+        self.jobs.collect_results();
+        for result in mem::take(&mut self.jobs.results) {
+            if let JobResult::ObjDiff(Some(result)) = result {
+                self.result = Some(result);
+                events.push(SessionEvent::DiffUpdated);
+            }
+        }
+        for job in &self.jobs.jobs {
+            if job.kind != Job::ObjDiff {
+                continue;
+            }
+            if let Ok(mut status) = job.context.status.write() {
+                if let Some(err) = status.error.take() {
+                    events.push(SessionEvent::BuildFailed(err));
+                }
+            }
+        }
+        self.jobs.clear_finished();
+        if self.modified.swap(false, Ordering::Relaxed) {
+            events.push(SessionEvent::FilesChanged);
+            self.rebuild();
+        } else if self.queued_rebuild {
+            self.rebuild();
+        }
+        events
+    }
+
+    /// The results of the last finished build.
+    pub fn get_diff(&self) -> Option<&ObjDiffResult> { self.result.as_deref() }
+
+    /// Running jobs, for progress display.
+    pub fn jobs(&self) -> &JobQueue { &self.jobs }
+}
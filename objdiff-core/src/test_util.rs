@@ -0,0 +1,239 @@
+//! Helpers for regression testing arch backends and the diff engine against real object files.
+//!
+//! Tests load small relocatable objects checked in as fixtures, render their disassembly or
+//! diff results to text, and compare the text against an expected file stored alongside the
+//! fixture with [`assert_expected`]. Set `OBJDIFF_UPDATE_EXPECTED=1` to write the expected
+//! files instead, after an intentional change in output.
+//!
+//! The rendered text uses the same display rows as the GUI and CLI, so it reflects what users
+//! see, but omits colors and uses mangled symbol names to stay stable across demanglers.
+
+use std::{
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::{
+    diff::{
+        diff_objs,
+        display::{display_diff, DiffText},
+        DiffObjConfig, ObjDiff, ObjInsDiff, ObjInsDiffKind,
+    },
+    obj::{read, ObjInfo, ObjSectionKind, SymbolRef},
+};
+
+/// Environment variable that makes [`assert_expected`] write expected files.
+pub const UPDATE_EXPECTED_ENV: &str = "OBJDIFF_UPDATE_EXPECTED";
+
+/// A diffed target object, and optionally the base object it was compared against.
+pub struct FixtureDiff {
+    pub target: ObjInfo,
+    pub target_diff: ObjDiff,
+    pub base: Option<(ObjInfo, ObjDiff)>,
+}
+
+/// Loads an object file fixture.
+pub fn read_fixture(path: impl AsRef<Path>, config: &DiffObjConfig) -> Result<ObjInfo> {
+    let path = path.as_ref();
+    read::read(path, config).with_context(|| format!("Failed to read fixture {}", path.display()))
+}
+
+/// Loads and diffs a target fixture against a base fixture. Without a base, the target is
+/// diffed against nothing, which still produces instruction rows for display.
+pub fn diff_fixtures(
+    target: impl AsRef<Path>,
+    base: Option<&Path>,
+    config: &DiffObjConfig,
+) -> Result<FixtureDiff> {
+    let target = read_fixture(target, config)?;
+    let base = base.map(|path| read_fixture(path, config)).transpose()?;
+    let result = diff_objs(config, Some(&target), base.as_ref(), None)?;
+    let target_diff = result.left.ok_or_else(|| anyhow!("Missing target diff"))?;
+    let base = match (base, result.right) {
+        (Some(base), Some(base_diff)) => Some((base, base_diff)),
+        _ => None,
+    };
+    Ok(FixtureDiff { target, target_diff, base })
+}
+
+/// Finds a symbol by its mangled name.
+pub fn find_symbol(obj: &ObjInfo, name: &str) -> Option<SymbolRef> {
+    obj.sections.iter().enumerate().find_map(|(section_idx, section)| {
+        section
+            .symbols
+            .iter()
+            .position(|symbol| symbol.name == name)
+            .map(|symbol_idx| SymbolRef { section_idx, symbol_idx })
+    })
+}
+
+/// Renders a function's instruction rows, one per line, each prefixed with its diff marker.
+pub fn display_function(obj: &ObjInfo, diff: &ObjDiff, name: &str) -> Result<String> {
+    let symbol_ref =
+        find_symbol(obj, name).ok_or_else(|| anyhow!("Function {name} not found in fixture"))?;
+    let (_, symbol) = obj.section_symbol(symbol_ref);
+    let mut out = String::new();
+    for ins_diff in &diff.symbol_diff(symbol_ref).instructions {
+        let row = display_row(ins_diff, symbol.address);
+        if row.is_empty() {
+            out.push('\n');
+        } else {
+            writeln!(out, "{} {row}", diff_marker(ins_diff.kind)).unwrap();
+        }
+    }
+    Ok(out)
+}
+
+/// Renders a function's diff side by side, target on the left and base on the right, preceded
+/// by its match percent.
+pub fn display_function_diff(fixture: &FixtureDiff, name: &str) -> Result<String> {
+    let (base, base_diff) =
+        fixture.base.as_ref().ok_or_else(|| anyhow!("Fixture diff has no base object"))?;
+    let target_ref = find_symbol(&fixture.target, name)
+        .ok_or_else(|| anyhow!("Function {name} not found in target"))?;
+    let target_symbol_diff = fixture.target_diff.symbol_diff(target_ref);
+    let base_ref = target_symbol_diff
+        .target_symbol
+        .ok_or_else(|| anyhow!("Function {name} has no match in base"))?;
+    let (_, target_symbol) = fixture.target.section_symbol(target_ref);
+    let (_, base_symbol) = base.section_symbol(base_ref);
+    let left = target_symbol_diff
+        .instructions
+        .iter()
+        .map(|ins_diff| display_row(ins_diff, target_symbol.address))
+        .collect::<Vec<_>>();
+    let right = base_diff
+        .symbol_diff(base_ref)
+        .instructions
+        .iter()
+        .map(|ins_diff| (ins_diff.kind, display_row(ins_diff, base_symbol.address)))
+        .collect::<Vec<_>>();
+    let width = left.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+    let mut out = String::new();
+    writeln!(out, "{name}: {}", format_percent(target_symbol_diff.match_percent)).unwrap();
+    for (left, (kind, right)) in left.iter().zip(&right) {
+        let line = format!("{left:width$} {} {right}", diff_marker(*kind));
+        writeln!(out, "{}", line.trim_end()).unwrap();
+    }
+    Ok(out)
+}
+
+/// Summarizes the match percent of every symbol in the target, in section order, to assert
+/// whole-object diff results.
+pub fn match_summary(fixture: &FixtureDiff) -> String {
+    let mut out = String::new();
+    for (section, section_diff) in fixture.target.sections.iter().zip(&fixture.target_diff.sections)
+    {
+        writeln!(out, "[{}]", section.name).unwrap();
+        for (symbol, symbol_diff) in section.symbols.iter().zip(&section_diff.symbols) {
+            let percent = match section.kind {
+                ObjSectionKind::Code | ObjSectionKind::Data => {
+                    format_percent(symbol_diff.match_percent)
+                }
+                ObjSectionKind::Bss => "-".to_string(),
+            };
+            writeln!(out, "{}: {percent}", symbol.name).unwrap();
+        }
+    }
+    out
+}
+
+/// Asserts that `actual` equals the contents of the expected file, or writes it if
+/// [`UPDATE_EXPECTED_ENV`] is set. Line endings are normalized before comparing.
+#[track_caller]
+pub fn assert_expected(actual: &str, expected_path: impl AsRef<Path>) {
+    let expected_path = expected_path.as_ref();
+    if std::env::var_os(UPDATE_EXPECTED_ENV).is_some_and(|v| v != "0") {
+        if let Some(parent) = expected_path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(expected_path, actual).unwrap_or_else(|e| {
+            panic!("Failed to write {}: {e}", expected_path.display());
+        });
+        return;
+    }
+    let expected = fs::read_to_string(expected_path).unwrap_or_else(|e| {
+        panic!(
+            "Failed to read {}: {e}\nRun with {UPDATE_EXPECTED_ENV}=1 to create it.",
+            expected_path.display()
+        )
+    });
+    let expected = expected.replace("\r\n", "\n");
+    if expected != actual {
+        panic!(
+            "Output differs from {}\nRun with {UPDATE_EXPECTED_ENV}=1 to update it.\n{}",
+            expected_path.display(),
+            line_diff(&expected, actual)
+        );
+    }
+}
+
+/// Resolves a fixture path relative to a crate's `tests/fixtures` directory. Pass
+/// `env!("CARGO_MANIFEST_DIR")` as `manifest_dir`.
+pub fn fixture_path(manifest_dir: &str, name: &str) -> PathBuf {
+    Path::new(manifest_dir).join("tests").join("fixtures").join(name)
+}
+
+fn display_row(ins_diff: &ObjInsDiff, base_addr: u64) -> String {
+    let mut row = String::new();
+    display_diff(ins_diff, base_addr, |text| {
+        let (text, pad_to) = match text {
+            DiffText::Basic(s) | DiffText::BasicColor(s, _) => (s.to_string(), 0),
+            DiffText::Line(_) | DiffText::Eol => return Ok(()),
+            DiffText::Address(addr) => (format!("{addr:x}:"), 5),
+            DiffText::Opcode(mnemonic, _) => (mnemonic.to_string(), 8),
+            DiffText::Argument(arg, _) => (arg.to_string(), 0),
+            DiffText::BranchDest(dest, _) => (format!("{dest:x}"), 0),
+            DiffText::Symbol(sym, _) => (sym.name.clone(), 0),
+            DiffText::Spacing(n) => (" ".repeat(n), 0),
+        };
+        write!(row, "{text:pad_to$}")
+    })
+    .unwrap();
+    row.trim_end().to_string()
+}
+
+fn diff_marker(kind: ObjInsDiffKind) -> char {
+    match kind {
+        ObjInsDiffKind::None => ' ',
+        ObjInsDiffKind::Delete => '<',
+        ObjInsDiffKind::Insert => '>',
+        ObjInsDiffKind::Quirk => '~',
+        ObjInsDiffKind::SoftMismatch => '≈',
+        ObjInsDiffKind::OpMismatch | ObjInsDiffKind::ArgMismatch | ObjInsDiffKind::Replace => '|',
+    }
+}
+
+fn format_percent(percent: Option<f32>) -> String {
+    match percent {
+        Some(percent) => format!("{percent:.2}%"),
+        None => "-".to_string(),
+    }
+}
+
+/// A minimal line diff for assertion failures: lines only in the expected output are prefixed
+/// with `-`, lines only in the actual output with `+`.
+fn line_diff(expected: &str, actual: &str) -> String {
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+    let mut out = String::new();
+    for op in similar::capture_diff_slices(similar::Algorithm::Myers, &expected, &actual) {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        if tag == similar::DiffTag::Equal {
+            for line in &expected[old_range] {
+                writeln!(out, " {line}").unwrap();
+            }
+            continue;
+        }
+        for line in &expected[old_range] {
+            writeln!(out, "-{line}").unwrap();
+        }
+        for line in &actual[new_range] {
+            writeln!(out, "+{line}").unwrap();
+        }
+    }
+    out
+}
@@ -1,6 +1,11 @@
 use std::{
-    fmt::{LowerHex, UpperHex},
+    fmt::{Display, LowerHex, UpperHex},
     io::Read,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 
 use anyhow::Result;
@@ -36,3 +41,94 @@ pub fn read_u32<R: Read>(obj_file: &object::File, reader: &mut R) -> Result<u32>
 pub fn read_u16<R: Read>(obj_file: &object::File, reader: &mut R) -> Result<u16> {
     Ok(obj_file.endianness().read_u16(reader.read_u16::<NativeEndian>()?))
 }
+
+/// A cheaply cloneable flag used to cancel long-running operations, such as loading or diffing
+/// objects, from another thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self { Self::default() }
+
+    /// Requests cancellation. All clones of this token observe the request.
+    pub fn cancel(&self) { self.0.store(true, Ordering::Relaxed); }
+
+    pub fn is_cancelled(&self) -> bool { self.0.load(Ordering::Relaxed) }
+
+    /// Returns a [`Cancelled`] error if cancellation was requested.
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(Cancelled.into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The error returned by an operation that was cancelled via [`CancellationToken`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { f.write_str("Cancelled") }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Measures the time since it was started. `Instant` isn't available on
+/// `wasm32-unknown-unknown`, where no time is measured.
+#[derive(Debug, Copy, Clone)]
+pub struct Stopwatch(#[cfg(not(target_arch = "wasm32"))] std::time::Instant);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Stopwatch {
+    pub fn start() -> Self { Self(std::time::Instant::now()) }
+
+    pub fn elapsed(&self) -> Duration { self.0.elapsed() }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Stopwatch {
+    pub fn start() -> Self { Self() }
+
+    pub fn elapsed(&self) -> Duration { Duration::ZERO }
+}
+
+/// Time spent in each stage of loading and diffing objects, in the order the stages first ran.
+#[derive(Debug, Clone, Default)]
+pub struct Timings {
+    pub stages: Vec<(&'static str, Duration)>,
+}
+
+impl Timings {
+    /// Adds to the time spent in a stage.
+    pub fn add(&mut self, stage: &'static str, duration: Duration) {
+        match self.stages.iter_mut().find(|(name, _)| *name == stage) {
+            Some((_, total)) => *total += duration,
+            None => self.stages.push((stage, duration)),
+        }
+    }
+
+    /// Runs `f` and adds the time it took to a stage.
+    pub fn time<R>(&mut self, stage: &'static str, f: impl FnOnce() -> R) -> R {
+        let start = Stopwatch::start();
+        let result = f();
+        self.add(stage, start.elapsed());
+        result
+    }
+
+    pub fn extend(&mut self, other: &Timings) {
+        for &(stage, duration) in &other.stages {
+            self.add(stage, duration);
+        }
+    }
+
+    pub fn total(&self) -> Duration { self.stages.iter().map(|(_, duration)| *duration).sum() }
+
+    /// Logs each stage at debug level.
+    pub fn log(&self) {
+        for (stage, duration) in &self.stages {
+            log::debug!("{stage}: {:.2}ms", duration.as_secs_f64() * 1000.0);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Diffs a small object per arch with the `test_util` helpers.
+//!
+//! Each fixture directory holds `target.s` and `base.s`, assembled with e.g.
+//! `llvm-mc -filetype=obj -triple=powerpc-unknown-eabi -o target.o target.s`. Both define a
+//! function `same`, identical on both sides, and a function `changed`, which differs by one
+//! immediate.
+
+#[cfg(any(
+    feature = "x86",
+    feature = "ppc",
+    feature = "mips",
+    feature = "arm",
+    feature = "arm64",
+    feature = "riscv"
+))]
+use objdiff_core::{diff::DiffObjConfig, test_util::*};
+
+#[cfg(any(
+    feature = "x86",
+    feature = "ppc",
+    feature = "mips",
+    feature = "arm",
+    feature = "arm64",
+    feature = "riscv"
+))]
+fn diff_arch(arch: &str) -> FixtureDiff {
+    let dir = fixture_path(env!("CARGO_MANIFEST_DIR"), arch);
+    diff_fixtures(dir.join("target.o"), Some(&dir.join("base.o")), &DiffObjConfig::default())
+        .unwrap()
+}
+
+/// Checks the match percents of both functions, that `same` renders each of `mnemonics`, and
+/// that the differing instruction of `changed` is marked.
+#[cfg(any(
+    feature = "x86",
+    feature = "ppc",
+    feature = "mips",
+    feature = "arm",
+    feature = "arm64",
+    feature = "riscv"
+))]
+fn check_arch(arch: &str, mnemonics: &[&str]) {
+    let fixture = diff_arch(arch);
+
+    let summary = match_summary(&fixture);
+    assert!(summary.contains("same: 100.00%\n"), "{summary}");
+    assert!(summary.contains("changed: ") && !summary.contains("changed: 100.00%"), "{summary}");
+
+    let same = display_function(&fixture.target, &fixture.target_diff, "same").unwrap();
+    for mnemonic in mnemonics {
+        assert!(same.contains(mnemonic), "{mnemonic} not found in:\n{same}");
+    }
+    assert!(same.lines().all(|line| line.is_empty() || line.starts_with(' ')), "{same}");
+
+    let changed = display_function_diff(&fixture, "changed").unwrap();
+    assert!(changed.lines().any(|line| line.contains(" | ")), "{changed}");
+}
+
+#[test]
+#[cfg(feature = "x86")]
+fn x86_64() {
+    check_arch("x86_64", &["mov", "add", "ret"]);
+    let fixture = diff_arch("x86_64");
+    assert!(find_symbol(&fixture.target, "changed").is_some());
+    assert!(find_symbol(&fixture.target, "missing").is_none());
+    let dir = fixture_path(env!("CARGO_MANIFEST_DIR"), "x86_64");
+    assert_expected(&display_function_diff(&fixture, "changed").unwrap(), dir.join("changed.txt"));
+    assert_expected(&match_summary(&fixture), dir.join("summary.txt"));
+}
+
+#[test]
+#[cfg(feature = "ppc")]
+fn ppc() { check_arch("ppc", &["add", "blr"]); }
+
+#[test]
+#[cfg(feature = "mips")]
+fn mips() { check_arch("mips", &["jr", "addu"]); }
+
+#[test]
+#[cfg(feature = "arm")]
+fn arm() { check_arch("arm", &["add", "bx"]); }
+
+#[test]
+#[cfg(feature = "arm64")]
+fn arm64() { check_arch("arm64", &["add", "ret"]); }
+
+#[test]
+#[cfg(feature = "riscv")]
+fn riscv() { check_arch("riscv", &["add"]); }
+
+/// Pseudo-instructions are compared by the arguments of their raw form, so `beqz` against `beq`
+/// and `move` against `or` differ in an argument instead of being replaced.
+#[test]
+#[cfg(feature = "mips")]
+fn mips_pseudo_instructions() {
+    use objdiff_core::diff::ObjInsDiffKind;
+
+    let dir = fixture_path(env!("CARGO_MANIFEST_DIR"), "mips_pseudo");
+    let fixture =
+        diff_fixtures(dir.join("target.o"), Some(&dir.join("base.o")), &DiffObjConfig::default())
+            .unwrap();
+    let symbol_ref = find_symbol(&fixture.target, "pseudo").unwrap();
+    let kinds = fixture
+        .target_diff
+        .symbol_diff(symbol_ref)
+        .instructions
+        .iter()
+        .map(|ins_diff| ins_diff.kind)
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, [
+        ObjInsDiffKind::ArgMismatch,
+        ObjInsDiffKind::ArgMismatch,
+        ObjInsDiffKind::None,
+        ObjInsDiffKind::None,
+    ]);
+}
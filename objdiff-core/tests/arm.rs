@@ -0,0 +1,69 @@
+//! Checks that the same source assembled little- and big-endian disassembles identically,
+//! including the implicit addends of branch and literal pool relocations. The fixtures in
+//! `fixtures/arm_endian` are assembled from `code.s` with
+//! `llvm-mc -filetype=obj -triple=armv5te -o little.o code.s` and
+//! `llvm-mc -filetype=obj -triple=armebv5te -o big.o code.s`.
+
+use std::path::Path;
+
+use objdiff_core::{
+    diff::{
+        diff_objs,
+        display::{display_diff, DiffText},
+        DiffObjConfig, ObjDiff,
+    },
+    obj::{read, ObjInfo, SymbolRef},
+};
+
+fn display_function(obj: &ObjInfo, diff: &ObjDiff, name: &str) -> String {
+    let symbol_ref = obj
+        .sections
+        .iter()
+        .enumerate()
+        .find_map(|(section_idx, section)| {
+            let symbol_idx = section.symbols.iter().position(|s| s.name == name)?;
+            Some(SymbolRef { section_idx, symbol_idx })
+        })
+        .unwrap();
+    let mut text = String::new();
+    for ins_diff in &diff.symbol_diff(symbol_ref).instructions {
+        display_diff(ins_diff, 0, |t| {
+            match t {
+                DiffText::Basic(s) | DiffText::BasicColor(s, _) => text.push_str(s),
+                DiffText::Address(addr) => text.push_str(&format!("{addr:x}: ")),
+                DiffText::Opcode(mnemonic, _) => text.push_str(mnemonic),
+                DiffText::Argument(arg, _) => text.push_str(&arg.to_string()),
+                DiffText::BranchDest(dest, _) => text.push_str(&format!("{dest:x}")),
+                DiffText::Symbol(sym, _) => text.push_str(&sym.name),
+                DiffText::Spacing(n) => text.push_str(&" ".repeat(n)),
+                DiffText::Eol => text.push('\n'),
+                _ => {}
+            }
+            Ok::<_, ()>(())
+        })
+        .unwrap();
+    }
+    text
+}
+
+#[test]
+fn big_endian() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/arm_endian");
+    let config = DiffObjConfig::default();
+    let little = read::read(&dir.join("little.o"), &config).unwrap();
+    let big = read::read(&dir.join("big.o"), &config).unwrap();
+    let result = diff_objs(&config, Some(&little), Some(&big), None).unwrap();
+    let (little_diff, big_diff) = (result.left.unwrap(), result.right.unwrap());
+    for name in ["calls", "thumb_calls"] {
+        let text = display_function(&little, &little_diff, name);
+        assert_eq!(text, display_function(&big, &big_diff, name));
+        assert!(text.contains("bl") && text.contains("callee"), "{text}");
+    }
+    for (section, section_diff) in little.sections.iter().zip(&little_diff.sections) {
+        for (symbol, symbol_diff) in section.symbols.iter().zip(&section_diff.symbols) {
+            if let Some(percent) = symbol_diff.match_percent {
+                assert_eq!(percent, 100.0, "{}", symbol.name);
+            }
+        }
+    }
+}
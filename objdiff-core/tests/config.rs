@@ -0,0 +1,58 @@
+//! Checks saving project configs that are split into fragments with `include`.
+
+use std::{fs, path::PathBuf};
+
+use objdiff_core::config::{save_project_config, try_project_config};
+
+/// A scratch project directory, removed when dropped.
+struct ProjectDir(PathBuf);
+
+impl ProjectDir {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("objdiff-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("units")).unwrap();
+        Self(dir)
+    }
+}
+
+impl Drop for ProjectDir {
+    fn drop(&mut self) { let _ = fs::remove_dir_all(&self.0); }
+}
+
+fn unit_names(path: PathBuf) -> Vec<String> {
+    let config: serde_json::Value = serde_json::from_slice(&fs::read(path).unwrap()).unwrap();
+    config["units"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|unit| unit["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn save_after_removing_unit() {
+    let dir = ProjectDir::new("config-include");
+    fs::write(
+        dir.0.join("objdiff.json"),
+        r#"{ "include": ["units/*.json"], "units": [{ "name": "main" }] }"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.0.join("units/game.json"),
+        r#"{ "units": [{ "name": "game/a" }, { "name": "game/b" }] }"#,
+    )
+    .unwrap();
+
+    let (config, info) = try_project_config(&dir.0).unwrap();
+    let mut config = config.unwrap();
+    assert_eq!(config.units().len(), 3);
+    config.units_mut().retain(|unit| unit.name() != "game/b");
+    save_project_config(&config, &info).unwrap();
+
+    assert_eq!(unit_names(dir.0.join("objdiff.json")), ["main"]);
+    assert_eq!(unit_names(dir.0.join("units/game.json")), ["game/a"]);
+    let (config, _) = try_project_config(&dir.0).unwrap();
+    let names = config.unwrap().units().iter().map(|u| u.name().to_string()).collect::<Vec<_>>();
+    assert_eq!(names, ["main", "game/a"]);
+}
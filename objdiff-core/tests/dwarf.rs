@@ -0,0 +1,25 @@
+//! Line info from DWARF debug info.
+//!
+//! `split_dwarf/split.o` and `split.dwo` are built with
+//! `gcc -O0 -g -gsplit-dwarf -fdebug-prefix-map=$PWD=. -c split.c -o split.o`.
+
+use objdiff_core::{diff::DiffObjConfig, obj::ObjSectionKind, test_util::*};
+
+/// The rows come from the skeleton unit's line table in `split.o`, not the table in `split.dwo`.
+#[test]
+fn split_dwarf_line_info() {
+    let dir = fixture_path(env!("CARGO_MANIFEST_DIR"), "split_dwarf");
+    let obj = read_fixture(dir.join("split.o"), &DiffObjConfig::default()).unwrap();
+    let text = obj.sections.iter().find(|s| s.kind == ObjSectionKind::Code).unwrap();
+    let lines = text.line_info.iter().map(|(&addr, &line)| (addr, line)).collect::<Vec<_>>();
+    assert_eq!(lines, [
+        (0x0, 1),
+        (0xa, 2),
+        (0x12, 3),
+        (0x14, 5),
+        (0x1f, 6),
+        (0x31, 7),
+        (0x34, 8),
+        (0x36, 8),
+    ]);
+}
@@ -0,0 +1,20 @@
+//! Checks data diffing between a big-endian `target.o` (PowerPC) and a little-endian `base.o`
+//! (x86-64) in `fixtures/endian`, assembled with `llvm-mc` from the `.s` files next to them.
+
+use objdiff_core::{diff::DiffObjConfig, test_util::*};
+
+#[test]
+fn data_values() {
+    let dir = fixture_path(env!("CARGO_MANIFEST_DIR"), "endian");
+    let fixture =
+        diff_fixtures(dir.join("target.o"), Some(&dir.join("base.o")), &DiffObjConfig::default())
+            .unwrap();
+    let match_percent = |name: &str| {
+        let symbol_ref = find_symbol(&fixture.target, name).unwrap();
+        fixture.target_diff.symbol_diff(symbol_ref).match_percent
+    };
+
+    assert_eq!(match_percent("same"), Some(100.0));
+    // Both are stored as the bytes 00 00 00 01, but hold different values
+    assert_ne!(match_percent("swapped"), Some(100.0));
+}
@@ -0,0 +1,15 @@
+	.arm
+	.text
+	.globl	same
+	.type	same, %function
+same:
+	add	r0, r0, r1
+	bx	lr
+	.size	same, .-same
+
+	.globl	changed
+	.type	changed, %function
+changed:
+	add	r0, r0, #1
+	bx	lr
+	.size	changed, .-changed
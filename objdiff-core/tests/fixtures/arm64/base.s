@@ -0,0 +1,14 @@
+	.text
+	.globl	same
+	.type	same, %function
+same:
+	add	w0, w0, w1
+	ret
+	.size	same, .-same
+
+	.globl	changed
+	.type	changed, %function
+changed:
+	add	w0, w0, #2
+	ret
+	.size	changed, .-changed
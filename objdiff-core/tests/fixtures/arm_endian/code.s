@@ -0,0 +1,21 @@
+	.arm
+	.text
+	.globl	calls
+	.type	calls, %function
+calls:
+	push	{r4, lr}
+	bl	callee
+	ldr	r0, =value+4
+	add	r0, r0, #1
+	pop	{r4, pc}
+	.size	calls, .-calls
+
+	.thumb
+	.globl	thumb_calls
+	.type	thumb_calls, %function
+	.thumb_func
+thumb_calls:
+	push	{r4, lr}
+	bl	callee
+	pop	{r4, pc}
+	.size	thumb_calls, .-thumb_calls
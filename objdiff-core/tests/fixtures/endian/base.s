@@ -0,0 +1,13 @@
+	.data
+	.globl	same
+	.type	same, @object
+same:
+	.long	1
+	.size	same, 4
+
+	# Stored as 00 00 00 01, the same bytes as the big-endian 1
+	.globl	swapped
+	.type	swapped, @object
+swapped:
+	.long	0x01000000
+	.size	swapped, 4
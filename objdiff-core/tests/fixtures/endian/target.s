@@ -0,0 +1,12 @@
+	.data
+	.globl	same
+	.type	same, @object
+same:
+	.long	1
+	.size	same, 4
+
+	.globl	swapped
+	.type	swapped, @object
+swapped:
+	.long	1
+	.size	swapped, 4
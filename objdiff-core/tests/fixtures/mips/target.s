@@ -0,0 +1,15 @@
+	.set	noreorder
+	.text
+	.globl	same
+	.type	same, @function
+same:
+	jr	$ra
+	addu	$v0, $a0, $a1
+	.size	same, .-same
+
+	.globl	changed
+	.type	changed, @function
+changed:
+	jr	$ra
+	addiu	$v0, $a0, 1
+	.size	changed, .-changed
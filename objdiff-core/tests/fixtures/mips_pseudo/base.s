@@ -0,0 +1,10 @@
+	.set	noreorder
+	.text
+	.globl	pseudo
+	.type	pseudo, @function
+pseudo:
+	beq	$a0, $a1, 1f
+	or	$v0, $a1, $a2
+	jr	$ra
+1:	nop
+	.size	pseudo, .-pseudo
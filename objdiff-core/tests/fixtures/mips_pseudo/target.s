@@ -0,0 +1,10 @@
+	.set	noreorder
+	.text
+	.globl	pseudo
+	.type	pseudo, @function
+pseudo:
+	beqz	$a0, 1f
+	move	$v0, $a1
+	jr	$ra
+1:	nop
+	.size	pseudo, .-pseudo
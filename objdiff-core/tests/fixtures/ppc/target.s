@@ -0,0 +1,14 @@
+	.text
+	.globl	same
+	.type	same, @function
+same:
+	add	3, 3, 4
+	blr
+	.size	same, .-same
+
+	.globl	changed
+	.type	changed, @function
+changed:
+	addi	3, 3, 1
+	blr
+	.size	changed, .-changed
@@ -0,0 +1,18 @@
+	.text
+	.globl	relocs
+	.type	relocs, @function
+relocs:
+	bl	callee@plt
+	lis	3, value@ha
+	addi	3, 3, value@l
+	lwz	4, value@got(30)
+	addis	30, 30, (_GLOBAL_OFFSET_TABLE_ - .)@ha
+	blr
+	.size	relocs, .-relocs
+
+	.data
+	.globl	offsets
+	.type	offsets, @object
+offsets:
+	.long	value - .
+	.size	offsets, .-offsets
@@ -0,0 +1,16 @@
+	.text
+	.globl	const_same
+	.type	const_same, @function
+const_same:
+	lis	3, 0x1235
+	addi	3, 3, -0x1000
+	blr
+	.size	const_same, .-const_same
+
+	.globl	const_other
+	.type	const_other, @function
+const_other:
+	lis	3, 0x1235
+	addi	3, 3, -0x1000
+	blr
+	.size	const_other, .-const_other
@@ -0,0 +1,15 @@
+	.intel_syntax noprefix
+	.text
+	.globl	zero_same
+	.type	zero_same, @function
+zero_same:
+	mov	eax, 0
+	ret
+	.size	zero_same, .-zero_same
+
+	.globl	zero_other
+	.type	zero_other, @function
+zero_other:
+	mov	eax, ebx
+	ret
+	.size	zero_other, .-zero_other
@@ -0,0 +1,14 @@
+	.text
+	.globl	same
+	.type	same, @function
+same:
+	add	a0, a0, a1
+	ret
+	.size	same, .-same
+
+	.globl	changed
+	.type	changed, @function
+changed:
+	addi	a0, a0, 2
+	ret
+	.size	changed, .-changed
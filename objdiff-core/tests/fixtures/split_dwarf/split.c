@@ -0,0 +1,8 @@
+int add(int a, int b) {
+    return a + b;
+}
+
+int twice(int a) {
+    int b = add(a, a);
+    return b;
+}
@@ -0,0 +1,17 @@
+	.intel_syntax noprefix
+	.text
+	.globl	same
+	.type	same, @function
+same:
+	mov	eax, edi
+	add	eax, esi
+	ret
+	.size	same, .-same
+
+	.globl	changed
+	.type	changed, @function
+changed:
+	mov	eax, edi
+	add	eax, 2
+	ret
+	.size	changed, .-changed
@@ -0,0 +1,68 @@
+//! Checks PPC relocation handling against `fixtures/ppc_relocs/target.o`, assembled from the
+//! `target.s` next to it with
+//! `llvm-mc -filetype=obj -triple=powerpc-unknown-linux-gnu -o target.o target.s`.
+
+use std::path::Path;
+
+use objdiff_core::{
+    diff::{
+        diff_objs,
+        display::{display_diff, DiffText},
+        DiffObjConfig,
+    },
+    obj::{read, SymbolRef},
+};
+
+#[test]
+fn relocations() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/ppc_relocs/target.o");
+    let config = DiffObjConfig::default();
+    let obj = read::read(&path, &config).unwrap();
+    let mut names = obj
+        .sections
+        .iter()
+        .flat_map(|s| &s.relocations)
+        .map(|r| obj.arch.display_reloc(r.flags).into_owned())
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, [
+        "R_PPC_ADDR16_HA",
+        "R_PPC_ADDR16_LO",
+        "R_PPC_GOT16",
+        "R_PPC_PLTREL24",
+        "R_PPC_REL16_HA",
+        "R_PPC_REL32",
+    ]);
+
+    let diff = diff_objs(&config, Some(&obj), None, None).unwrap().left.unwrap();
+    let symbol_ref = obj
+        .sections
+        .iter()
+        .enumerate()
+        .find_map(|(section_idx, section)| {
+            let symbol_idx = section.symbols.iter().position(|s| s.name == "relocs")?;
+            Some(SymbolRef { section_idx, symbol_idx })
+        })
+        .unwrap();
+    let mut text = String::new();
+    for ins_diff in &diff.symbol_diff(symbol_ref).instructions {
+        display_diff(ins_diff, 0, |t| {
+            match t {
+                DiffText::Basic(s) | DiffText::BasicColor(s, _) => text.push_str(s),
+                DiffText::Opcode(mnemonic, _) => text.push_str(mnemonic),
+                DiffText::Argument(arg, _) => text.push_str(&arg.to_string()),
+                DiffText::Symbol(sym, _) => text.push_str(&sym.name),
+                DiffText::Spacing(n) => text.push_str(&" ".repeat(n)),
+                DiffText::Eol => text.push('\n'),
+                _ => {}
+            }
+            Ok::<_, ()>(())
+        })
+        .unwrap();
+    }
+    for expected in
+        ["callee@plt", "value@ha", "value@l", "value@got", "_GLOBAL_OFFSET_TABLE_+0x2-.@ha"]
+    {
+        assert!(text.contains(expected), "{expected} not found in:\n{text}");
+    }
+}
@@ -0,0 +1,58 @@
+//! Checks that codegen quirks only hide sequences computing the same value.
+//!
+//! Each fixture directory holds `target.s` and `base.s`, assembled with e.g.
+//! `llvm-mc -filetype=obj -triple=x86_64-unknown-linux-gnu -o target.o target.s`. Functions
+//! named `*_same` differ only by a quirk, functions named `*_other` use the quirk's mnemonics
+//! but compute a different value.
+
+#[cfg(any(feature = "x86", feature = "ppc"))]
+use objdiff_core::{
+    diff::{DiffObjConfig, ObjInsDiffKind},
+    test_util::*,
+};
+
+#[cfg(any(feature = "x86", feature = "ppc"))]
+fn diff_quirks(name: &str) -> FixtureDiff {
+    let dir = fixture_path(env!("CARGO_MANIFEST_DIR"), name);
+    let config = DiffObjConfig { ignore_codegen_quirks: true, ..Default::default() };
+    diff_fixtures(dir.join("target.o"), Some(&dir.join("base.o")), &config).unwrap()
+}
+
+/// The diff kinds of a function's rows and its match percent.
+#[cfg(any(feature = "x86", feature = "ppc"))]
+fn symbol_kinds(fixture: &FixtureDiff, name: &str) -> (Vec<ObjInsDiffKind>, Option<f32>) {
+    let symbol_diff = fixture.target_diff.symbol_diff(find_symbol(&fixture.target, name).unwrap());
+    let kinds = symbol_diff.instructions.iter().map(|ins_diff| ins_diff.kind).collect();
+    (kinds, symbol_diff.match_percent)
+}
+
+#[test]
+#[cfg(feature = "x86")]
+fn x86_register_zeroing() {
+    let fixture = diff_quirks("quirks_x86_64");
+
+    let (kinds, percent) = symbol_kinds(&fixture, "zero_same");
+    assert!(kinds.contains(&ObjInsDiffKind::Quirk), "{kinds:?}");
+    assert_eq!(percent, Some(100.0));
+
+    // `xor eax, ebx` doesn't zero the register, so it isn't equivalent to `mov eax, ebx`
+    let (kinds, percent) = symbol_kinds(&fixture, "zero_other");
+    assert!(!kinds.contains(&ObjInsDiffKind::Quirk), "{kinds:?}");
+    assert_ne!(percent, Some(100.0));
+}
+
+#[test]
+#[cfg(feature = "ppc")]
+fn ppc_constant_materialization() {
+    let fixture = diff_quirks("quirks_ppc");
+
+    // lis/addi and lis/ori both build 0x1234f000
+    let (kinds, percent) = symbol_kinds(&fixture, "const_same");
+    assert!(kinds.contains(&ObjInsDiffKind::Quirk), "{kinds:?}");
+    assert_eq!(percent, Some(100.0));
+
+    // 0x1234f000 against 0x1234f001
+    let (kinds, percent) = symbol_kinds(&fixture, "const_other");
+    assert!(!kinds.contains(&ObjInsDiffKind::Quirk), "{kinds:?}");
+    assert_ne!(percent, Some(100.0));
+}
@@ -1,47 +1,87 @@
 use std::{
+    collections::{BTreeMap, BTreeSet},
     default::Default,
     fs,
     path::{Path, PathBuf},
     rc::Rc,
     sync::{
         atomic::{AtomicBool, Ordering},
+        mpsc::Receiver,
         Arc, Mutex, RwLock,
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 
+use anyhow::{anyhow, Context, Result};
 use filetime::FileTime;
 use globset::Glob;
 use objdiff_core::{
-    build::watcher::{create_watcher, Watcher},
+    build::{
+        watcher::{create_watcher, Watcher, WatcherOptions, DEFAULT_WATCH_DEBOUNCE_MS},
+        BuildCache,
+    },
     config::{
-        build_globset, default_watch_patterns, save_project_config, ProjectConfig,
-        ProjectConfigInfo, ProjectObject, ScratchConfig, SymbolMappings, DEFAULT_WATCH_PATTERNS,
+        build_globset,
+        claims::{claims_dir, Claims},
+        compile_commands::CompileCommand,
+        default_watch_patterns,
+        mappings::{apply_import, MappingImportEntry},
+        match_history::{unix_now, MatchHistory, MatchStats},
+        save_project_config,
+        session_file::{DiffSession, SessionHeader, SESSION_EXTENSION},
+        usage_stats::UsageStats,
+        ProjectConfig, ProjectConfigInfo, ProjectObject, ScratchConfig, SymbolImport,
+        SymbolMappings, SymbolMatching, SymbolReviewState, WatchScope, DEFAULT_WATCH_PATTERNS,
+    },
+    diff::{
+        properties::ConfigProperty, AlignmentAnchor, AlignmentAnchors, DiffObjConfig,
+        SizeMismatchFix, SizeMismatchFixes,
+    },
+    jobs::{objdiff::ObjDiffResult, Job, JobQueue, JobResult},
+    obj::{
+        cache::{ObjCache, DEFAULT_MEMORY_LIMIT},
+        ObjSectionKind, ObjSymbolKind,
     },
-    diff::DiffObjConfig,
-    jobs::{Job, JobQueue, JobResult},
 };
 use time::UtcOffset;
 
 use crate::{
     app_config::{deserialize_config, AppConfigVersion},
     config::{load_project_config, ProjectObjectNode},
+    deep_link::{listen as listen_for_links, open_deep_link, DeepLink},
+    hotkeys,
     jobs::{create_objdiff_config, egui_waker, start_build},
     views::{
         appearance::{appearance_window, Appearance},
+        command_palette::{
+            command_palette_window, AppWindow, Command, CommandPaletteState, DiffOption,
+        },
         config::{
             arch_config_window, config_ui, project_window, ConfigViewState, CONFIG_DISABLED_TEXT,
         },
+        cross_diff::{cross_diff_window, CrossDiffViewState},
         data_diff::data_diff_ui,
         debug::debug_window,
         demangle::{demangle_window, DemangleViewState},
         extab_diff::extab_diff_ui,
+        file::FileDialogResult,
         frame_history::FrameHistory,
-        function_diff::function_diff_ui,
+        function_diff::{focus_function_search, function_diff_ui},
         graphics::{graphics_window, GraphicsConfig, GraphicsViewState},
         jobs::{jobs_menu_ui, jobs_window},
+        load_details::load_details_window,
+        load_progress::load_progress_ui,
+        mappings::{mappings_window, MappingsViewState},
+        notes::{notes_panel, NotesViewState},
+        recent_projects::{recent_projects_menu_ui, start_screen_ui},
+        report_changes::{report_changes_window, ReportChangesViewState},
         rlwinm::{rlwinm_decode_window, RlwinmDecodeViewState},
+        search::{search_window, SearchViewState},
+        session::session_panel,
         symbol_diff::{symbol_diff_ui, DiffViewAction, DiffViewNavigation, DiffViewState, View},
+        symbol_lookup::{symbol_lookup_window, SymbolLookupViewState},
+        terminal::{terminal_panel, TerminalViewState},
+        watch::watch_notification_window,
     },
 };
 
@@ -50,18 +90,33 @@ pub struct ViewState {
     pub config_state: ConfigViewState,
     pub demangle_state: DemangleViewState,
     pub rlwinm_decode_state: RlwinmDecodeViewState,
+    pub search_state: SearchViewState,
     pub diff_state: DiffViewState,
     pub graphics_state: GraphicsViewState,
     pub frame_history: FrameHistory,
+    pub command_palette_state: CommandPaletteState,
+    pub cross_diff_state: CrossDiffViewState,
+    pub mappings_state: MappingsViewState,
+    pub notes_state: NotesViewState,
+    pub terminal_state: TerminalViewState,
+    pub symbol_lookup_state: SymbolLookupViewState,
+    pub report_changes_state: ReportChangesViewState,
     pub show_appearance_config: bool,
     pub show_demangle: bool,
     pub show_rlwinm_decode: bool,
+    pub show_search: bool,
+    pub show_symbol_lookup: bool,
+    pub show_report_changes: bool,
+    pub show_load_details: bool,
     pub show_project_config: bool,
     pub show_arch_config: bool,
     pub show_debug: bool,
     pub show_graphics: bool,
     pub show_jobs: bool,
+    pub show_mappings: bool,
     pub show_side_panel: bool,
+    pub show_notes: bool,
+    pub show_terminal: bool,
 }
 
 impl Default for ViewState {
@@ -71,18 +126,33 @@ impl Default for ViewState {
             config_state: Default::default(),
             demangle_state: Default::default(),
             rlwinm_decode_state: Default::default(),
+            search_state: Default::default(),
             diff_state: Default::default(),
             graphics_state: Default::default(),
             frame_history: Default::default(),
+            command_palette_state: Default::default(),
+            cross_diff_state: Default::default(),
+            mappings_state: Default::default(),
+            notes_state: Default::default(),
+            terminal_state: Default::default(),
+            symbol_lookup_state: Default::default(),
+            report_changes_state: Default::default(),
             show_appearance_config: false,
             show_demangle: false,
             show_rlwinm_decode: false,
+            show_search: false,
+            show_symbol_lookup: false,
+            show_report_changes: false,
+            show_load_details: false,
             show_project_config: false,
             show_arch_config: false,
             show_debug: false,
             show_graphics: false,
             show_jobs: false,
+            show_mappings: false,
             show_side_panel: true,
+            show_notes: true,
+            show_terminal: false,
         }
     }
 }
@@ -98,7 +168,31 @@ pub struct ObjectConfig {
     pub scratch: Option<ScratchConfig>,
     pub source_path: Option<String>,
     #[serde(default)]
+    pub notes_path: Option<String>,
+    #[serde(default)]
     pub symbol_mappings: SymbolMappings,
+    #[serde(default)]
+    pub symbol_matching: Option<SymbolMatching>,
+    #[serde(default)]
+    pub review_states: BTreeMap<String, SymbolReviewState>,
+    #[serde(default)]
+    pub watched_symbols: BTreeSet<String>,
+    #[serde(default)]
+    pub compiler_flags: Option<String>,
+    #[serde(default)]
+    pub symbol_compiler_flags: BTreeMap<String, String>,
+    #[serde(default)]
+    pub mapping_include: Vec<Glob>,
+    #[serde(default)]
+    pub mapping_exclude: Vec<Glob>,
+    #[serde(skip)]
+    pub base_build_command: Option<CompileCommand>,
+    #[serde(default)]
+    pub symbol_import: Option<SymbolImport>,
+    /// A temporary pairing of symbols from arbitrary objects, created with "Diff with…".
+    /// Objects are diffed as-is, without invoking the build system.
+    #[serde(default)]
+    pub cross_unit: bool,
 }
 
 impl From<&ProjectObject> for ObjectConfig {
@@ -111,7 +205,30 @@ impl From<&ProjectObject> for ObjectConfig {
             complete: object.complete(),
             scratch: object.scratch.clone(),
             source_path: object.source_path().cloned(),
+            notes_path: object.notes_path().cloned(),
             symbol_mappings: object.symbol_mappings.clone().unwrap_or_default(),
+            symbol_matching: object.symbol_matching(),
+            review_states: object.review_states().cloned().unwrap_or_default(),
+            watched_symbols: object.watched_symbols().cloned().unwrap_or_default(),
+            compiler_flags: object.compiler_flags().cloned(),
+            symbol_compiler_flags: object.symbol_compiler_flags().cloned().unwrap_or_default(),
+            mapping_include: object.mapping_include().cloned().unwrap_or_default(),
+            mapping_exclude: object.mapping_exclude().cloned().unwrap_or_default(),
+            base_build_command: object.resolved_compile_command.clone(),
+            symbol_import: object.symbol_import.clone(),
+            cross_unit: false,
+        }
+    }
+}
+
+impl ObjectConfig {
+    /// Returns the compiler flags in effect for a target symbol, and whether they're specific to
+    /// the symbol rather than the unit.
+    pub fn compiler_flags_for(&self, symbol_name: &str) -> Option<(&str, bool)> {
+        if let Some(flags) = self.symbol_compiler_flags.get(symbol_name) {
+            Some((flags, true))
+        } else {
+            self.compiler_flags.as_deref().map(|flags| (flags, false))
         }
     }
 }
@@ -119,6 +236,29 @@ impl From<&ProjectObject> for ObjectConfig {
 #[inline]
 fn bool_true() -> bool { true }
 
+/// Number of unpinned projects kept in the recent projects list.
+const MAX_RECENT_PROJECTS: usize = 10;
+
+fn default_memory_limit_mb() -> u64 { (DEFAULT_MEMORY_LIMIT >> 20) as u64 }
+
+/// Default limit of jobs running at once, so rebuilds in watch mode don't starve the
+/// interactive diff.
+const DEFAULT_MAX_CONCURRENT_JOBS: usize = 4;
+
+fn default_max_concurrent_jobs() -> usize { DEFAULT_MAX_CONCURRENT_JOBS }
+
+fn default_watch_debounce_ms() -> u64 { DEFAULT_WATCH_DEBOUNCE_MS }
+
+/// Builds shorter than this don't send a notification, since the result is seen right away.
+const DEFAULT_BUILD_NOTIFICATION_MIN_SECS: u64 = 30;
+
+fn default_build_notification_min_secs() -> u64 { DEFAULT_BUILD_NOTIFICATION_MIN_SECS }
+
+/// The user name from the environment, used as the default name for unit claims.
+fn default_claim_name() -> String {
+    std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default()
+}
+
 pub struct AppState {
     pub config: AppConfig,
     pub objects: Vec<ProjectObject>,
@@ -128,6 +268,11 @@ pub struct AppState {
     pub obj_change: bool,
     pub queue_build: bool,
     pub queue_reload: bool,
+    /// Ignore the build cache for the next build
+    pub force_build: bool,
+    pub build_cache: BuildCache,
+    /// Recently loaded objects, reused when switching back to a unit
+    pub obj_cache: ObjCache,
     pub current_project_config: Option<ProjectConfig>,
     pub project_config_info: Option<ProjectConfigInfo>,
     pub last_mod_check: Instant,
@@ -136,6 +281,19 @@ pub struct AppState {
     /// The left object symbol name that we're selecting a right symbol for
     pub selecting_right: Option<String>,
     pub config_error: Option<String>,
+    /// Loaded on first use for the current project
+    pub match_history: Option<MatchHistory>,
+    /// The diff session being viewed, if any. Its objects are diffed as-is, with the
+    /// configuration they were exported with.
+    pub session: Option<SessionHeader>,
+    /// Temporary directory holding the objects of the open session, removed when it's closed
+    pub session_dir: Option<tempfile::TempDir>,
+    /// Loaded on first use for the current project, if usage statistics are enabled
+    pub usage_stats: Option<UsageStats>,
+    /// Time spent on the selected unit that hasn't been recorded yet, and the last update
+    usage_time: Option<(Duration, Instant)>,
+    /// Loaded on first use for the current project, and reloaded when claims change on disk
+    pub claims: Option<Claims>,
 }
 
 impl Default for AppState {
@@ -149,12 +307,21 @@ impl Default for AppState {
             obj_change: false,
             queue_build: false,
             queue_reload: false,
+            force_build: false,
+            build_cache: Default::default(),
+            obj_cache: Default::default(),
             current_project_config: None,
             project_config_info: None,
             last_mod_check: Instant::now(),
             selecting_left: None,
             selecting_right: None,
             config_error: None,
+            match_history: None,
+            session: None,
+            session_dir: None,
+            usage_stats: None,
+            usage_time: None,
+            claims: None,
         }
     }
 }
@@ -189,10 +356,56 @@ pub struct AppConfig {
     pub auto_update_check: bool,
     #[serde(default = "default_watch_patterns")]
     pub watch_patterns: Vec<Glob>,
+    /// Time to wait for changes to watched files to settle before rebuilding, in milliseconds.
+    #[serde(default = "default_watch_debounce_ms")]
+    pub watch_debounce_ms: u64,
+    #[serde(default)]
+    pub watch_scope: WatchScope,
+    /// Recently opened projects, most recent first. Pinned projects aren't included.
     #[serde(default)]
     pub recent_projects: Vec<PathBuf>,
+    /// Projects that are always listed first, in the order they were pinned.
+    #[serde(default)]
+    pub pinned_projects: Vec<PathBuf>,
     #[serde(default)]
     pub diff_obj_config: DiffObjConfig,
+    #[serde(default)]
+    pub selected_version: Option<String>,
+    /// Instruction bookmarks, keyed by object name and symbol name, as offsets into the symbol
+    #[serde(default)]
+    pub bookmarks: BTreeMap<String, BTreeMap<String, BTreeSet<u64>>>,
+    /// Manual function diff alignment anchors, keyed by object name and left symbol name
+    #[serde(default)]
+    pub alignment_anchors: BTreeMap<String, AlignmentAnchors>,
+    /// How functions with mismatched sizes are diffed, keyed by object name and left symbol name
+    #[serde(default)]
+    pub size_fixes: BTreeMap<String, SizeMismatchFixes>,
+    /// Memory budget for loaded objects and diff results, in MiB. Recently loaded objects are
+    /// cached until it's exceeded.
+    #[serde(default = "default_memory_limit_mb")]
+    pub memory_limit_mb: u64,
+    /// Record builds and time spent per unit in the project directory. Nothing is sent anywhere.
+    #[serde(default)]
+    pub usage_stats: bool,
+    /// Maximum number of jobs running at once, or 0 for no limit.
+    #[serde(default = "default_max_concurrent_jobs")]
+    pub max_concurrent_jobs: usize,
+    /// Name written to unit claims.
+    #[serde(default = "default_claim_name")]
+    pub claim_name: String,
+    /// Send a desktop notification when a build & diff finishes while objdiff isn't focused.
+    #[serde(default)]
+    pub build_notifications: bool,
+    /// Play a sound along with build notifications.
+    #[serde(default)]
+    pub build_notification_sound: bool,
+    /// Only notify for builds taking at least this long, in seconds.
+    #[serde(default = "default_build_notification_min_secs")]
+    pub build_notification_min_secs: u64,
+    /// Command used to open source files, with `{file}` and `{line}` placeholders. Empty opens
+    /// them with the default application.
+    #[serde(default)]
+    pub editor_command: String,
 }
 
 impl Default for AppConfig {
@@ -211,24 +424,46 @@ impl Default for AppConfig {
             rebuild_on_changes: true,
             auto_update_check: true,
             watch_patterns: DEFAULT_WATCH_PATTERNS.iter().map(|s| Glob::new(s).unwrap()).collect(),
+            watch_debounce_ms: DEFAULT_WATCH_DEBOUNCE_MS,
+            watch_scope: WatchScope::default(),
             recent_projects: vec![],
+            pinned_projects: vec![],
             diff_obj_config: Default::default(),
+            selected_version: None,
+            bookmarks: BTreeMap::new(),
+            alignment_anchors: BTreeMap::new(),
+            size_fixes: BTreeMap::new(),
+            memory_limit_mb: default_memory_limit_mb(),
+            usage_stats: false,
+            max_concurrent_jobs: DEFAULT_MAX_CONCURRENT_JOBS,
+            claim_name: default_claim_name(),
+            build_notifications: false,
+            build_notification_sound: false,
+            build_notification_min_secs: DEFAULT_BUILD_NOTIFICATION_MIN_SECS,
+            editor_command: String::new(),
         }
     }
 }
 
 impl AppState {
     pub fn set_project_dir(&mut self, path: PathBuf) {
-        self.config.recent_projects.retain(|p| p != &path);
-        if self.config.recent_projects.len() > 9 {
-            self.config.recent_projects.truncate(9);
+        self.save_usage_time();
+        if !self.config.pinned_projects.contains(&path) {
+            self.config.recent_projects.retain(|p| p != &path);
+            self.config.recent_projects.truncate(MAX_RECENT_PROJECTS - 1);
+            self.config.recent_projects.insert(0, path.clone());
         }
-        self.config.recent_projects.insert(0, path.clone());
         self.config.project_dir = Some(path);
         self.config.target_obj_dir = None;
         self.config.base_obj_dir = None;
         self.config.selected_obj = None;
+        self.config.selected_version = None;
         self.config.build_target = false;
+        self.build_cache.clear();
+        self.obj_cache.clear();
+        self.match_history = None;
+        self.usage_stats = None;
+        self.claims = None;
         self.objects.clear();
         self.object_nodes.clear();
         self.watcher_change = true;
@@ -241,6 +476,28 @@ impl AppState {
         self.selecting_right = None;
     }
 
+    /// Pinned projects followed by recent projects, with whether each is pinned.
+    pub fn recent_projects(&self) -> impl Iterator<Item = (&Path, bool)> {
+        let pinned = self.config.pinned_projects.iter().map(|p| (p.as_path(), true));
+        pinned.chain(self.config.recent_projects.iter().map(|p| (p.as_path(), false)))
+    }
+
+    pub fn set_project_pinned(&mut self, path: &Path, pinned: bool) {
+        self.config.pinned_projects.retain(|p| p != path);
+        self.config.recent_projects.retain(|p| p != path);
+        if pinned {
+            self.config.pinned_projects.push(path.to_path_buf());
+        } else {
+            self.config.recent_projects.insert(0, path.to_path_buf());
+            self.config.recent_projects.truncate(MAX_RECENT_PROJECTS);
+        }
+    }
+
+    pub fn remove_recent_project(&mut self, path: &Path) {
+        self.config.pinned_projects.retain(|p| p != path);
+        self.config.recent_projects.retain(|p| p != path);
+    }
+
     pub fn set_target_obj_dir(&mut self, path: PathBuf) {
         self.config.target_obj_dir = Some(path);
         self.config.selected_obj = None;
@@ -270,8 +527,17 @@ impl AppState {
                 unit_changed = false;
             }
         }
+        if unit_changed {
+            self.save_usage_time();
+        }
         self.config.selected_obj = Some(config);
         if unit_changed {
+            if self.config.watch_scope == WatchScope::SelectedUnit {
+                // The excluded source files depend on the selected unit
+                self.watcher_change = true;
+            }
+            self.session = None;
+            self.session_dir = None;
             self.obj_change = true;
             self.queue_build = false;
             self.selecting_left = None;
@@ -282,7 +548,10 @@ impl AppState {
     }
 
     pub fn clear_selected_obj(&mut self) {
+        self.save_usage_time();
         self.config.selected_obj = None;
+        self.session = None;
+        self.session_dir = None;
         self.obj_change = true;
         self.queue_build = false;
         self.selecting_left = None;
@@ -326,12 +595,377 @@ impl AppState {
         self.save_config();
     }
 
+    /// Maps `left` to `right`, replacing the mapping of `previous` if it was edited.
+    pub fn replace_symbol_mapping(&mut self, previous: Option<&str>, left: String, right: String) {
+        let Some(object) = self.config.selected_obj.as_mut() else {
+            log::warn!("No selected object");
+            return;
+        };
+        if let Some(previous) = previous {
+            object.symbol_mappings.remove_by_left(previous);
+        }
+        self.set_symbol_mapping(left, right);
+    }
+
+    pub fn remove_symbol_mapping(&mut self, left: &str) {
+        let Some(object) = self.config.selected_obj.as_mut() else {
+            log::warn!("No selected object");
+            return;
+        };
+        object.symbol_mappings.remove_by_left(left);
+        self.queue_reload = true;
+        self.save_config();
+    }
+
+    pub fn set_review_state(&mut self, symbol_name: String, review_state: SymbolReviewState) {
+        let Some(object) = self.config.selected_obj.as_mut() else {
+            log::warn!("No selected object");
+            return;
+        };
+        if review_state == SymbolReviewState::Unverified {
+            object.review_states.remove(&symbol_name);
+        } else {
+            object.review_states.insert(symbol_name, review_state);
+        }
+        self.save_config();
+    }
+
+    pub fn set_watched(&mut self, symbol_name: String, watched: bool) {
+        let Some(object) = self.config.selected_obj.as_mut() else {
+            log::warn!("No selected object");
+            return;
+        };
+        if watched {
+            object.watched_symbols.insert(symbol_name);
+        } else {
+            object.watched_symbols.remove(&symbol_name);
+        }
+        self.save_config();
+    }
+
+    pub fn set_memory_limit(&mut self, limit_mb: u64) {
+        self.config.memory_limit_mb = limit_mb;
+        self.obj_cache.set_limit((limit_mb << 20) as usize);
+    }
+
+    pub fn toggle_bookmark(&mut self, symbol_name: String, offset: u64) {
+        let Some(object) = self.config.selected_obj.as_ref() else {
+            log::warn!("No selected object");
+            return;
+        };
+        let object_bookmarks = self.config.bookmarks.entry(object.name.clone()).or_default();
+        let symbol_bookmarks = object_bookmarks.entry(symbol_name.clone()).or_default();
+        if !symbol_bookmarks.remove(&offset) {
+            symbol_bookmarks.insert(offset);
+        }
+        if symbol_bookmarks.is_empty() {
+            object_bookmarks.remove(&symbol_name);
+        }
+        if object_bookmarks.is_empty() {
+            self.config.bookmarks.remove(&object.name);
+        }
+    }
+
+    /// Anchors two instructions of a function to the same row, replacing anchors on either
+    /// instruction and anchors that cross it.
+    pub fn add_alignment_anchor(&mut self, symbol_name: String, anchor: AlignmentAnchor) {
+        let Some(object) = self.config.selected_obj.as_ref() else {
+            log::warn!("No selected object");
+            return;
+        };
+        let anchors = self
+            .config
+            .alignment_anchors
+            .entry(object.name.clone())
+            .or_default()
+            .entry(symbol_name)
+            .or_default();
+        anchors.retain(|a| {
+            (a.left < anchor.left && a.right < anchor.right)
+                || (a.left > anchor.left && a.right > anchor.right)
+        });
+        anchors.push(anchor);
+        anchors.sort();
+        self.queue_reload = true;
+    }
+
+    /// Removes the anchor on an instruction of a function diff column, given as an offset into
+    /// the symbol. All of the function's anchors are removed if `offset` is `None`.
+    pub fn remove_alignment_anchor(
+        &mut self,
+        symbol_name: &str,
+        column: usize,
+        offset: Option<u64>,
+    ) {
+        let Some(object) = self.config.selected_obj.as_ref() else {
+            log::warn!("No selected object");
+            return;
+        };
+        let Some(object_anchors) = self.config.alignment_anchors.get_mut(&object.name) else {
+            return;
+        };
+        if let Some(offset) = offset {
+            if let Some(anchors) = object_anchors.get_mut(symbol_name) {
+                anchors.retain(|a| if column == 0 { a.left != offset } else { a.right != offset });
+                if anchors.is_empty() {
+                    object_anchors.remove(symbol_name);
+                }
+            }
+        } else {
+            object_anchors.remove(symbol_name);
+        }
+        if object_anchors.is_empty() {
+            self.config.alignment_anchors.remove(&object.name);
+        }
+        self.queue_reload = true;
+    }
+
+    /// Sets how a function whose size differs between both sides is diffed, or diffs it as-is
+    /// if `fix` is `None`.
+    pub fn set_size_fix(&mut self, symbol_name: &str, fix: Option<SizeMismatchFix>) {
+        let Some(object) = self.config.selected_obj.as_ref() else {
+            log::warn!("No selected object");
+            return;
+        };
+        if let Some(fix) = fix {
+            self.config
+                .size_fixes
+                .entry(object.name.clone())
+                .or_default()
+                .insert(symbol_name.to_string(), fix);
+        } else if let Some(object_fixes) = self.config.size_fixes.get_mut(&object.name) {
+            object_fixes.remove(symbol_name);
+            if object_fixes.is_empty() {
+                self.config.size_fixes.remove(&object.name);
+            }
+        }
+        self.queue_reload = true;
+    }
+
+    pub fn import_symbol_mappings(&mut self, entries: &[MappingImportEntry]) -> usize {
+        let Some(object) = self.config.selected_obj.as_mut() else {
+            log::warn!("No selected object");
+            return 0;
+        };
+        let count = apply_import(&mut object.symbol_mappings, entries);
+        self.selecting_left = None;
+        self.selecting_right = None;
+        self.queue_reload = true;
+        self.save_config();
+        count
+    }
+
+    fn load_match_history(&mut self) -> Option<&mut MatchHistory> {
+        let project_dir = self.config.project_dir.as_deref()?;
+        Some(self.match_history.get_or_insert_with(|| {
+            MatchHistory::load(project_dir).unwrap_or_else(|e| {
+                log::warn!("Failed to load match history: {e:#}");
+                MatchHistory::default()
+            })
+        }))
+    }
+
+    /// Records functions that are fully matched in the target object in the project's match
+    /// history.
+    pub fn record_match_history(&mut self, build: &ObjDiffResult) {
+        let Some((obj, diff)) = &build.first_obj else {
+            return;
+        };
+        let Some(unit_name) =
+            self.config.selected_obj.as_ref().filter(|o| !o.cross_unit).map(|o| o.name.clone())
+        else {
+            return;
+        };
+        let Some(project_dir) = self.config.project_dir.clone() else {
+            return;
+        };
+        let Some(history) = self.load_match_history() else {
+            return;
+        };
+        let matched = obj
+            .sections
+            .iter()
+            .zip(&diff.sections)
+            .filter(|(section, _)| section.kind == ObjSectionKind::Code)
+            .flat_map(|(section, section_diff)| section.symbols.iter().zip(&section_diff.symbols))
+            .filter(|(symbol, symbol_diff)| {
+                symbol.kind == ObjSymbolKind::Function
+                    && symbol.size > 0
+                    && symbol_diff.match_percent == Some(100.0)
+            })
+            .map(|(symbol, _)| (symbol.name.as_str(), symbol.size));
+        if history.record(&unit_name, matched, unix_now()) {
+            if let Err(e) = history.save(&project_dir) {
+                log::error!("Failed to save match history: {e:#}");
+            }
+        }
+    }
+
+    fn load_usage_stats(&mut self) -> Option<&mut UsageStats> {
+        if !self.config.usage_stats {
+            return None;
+        }
+        let project_dir = self.config.project_dir.as_deref()?;
+        Some(self.usage_stats.get_or_insert_with(|| {
+            UsageStats::load(project_dir).unwrap_or_else(|e| {
+                log::warn!("Failed to load usage statistics: {e:#}");
+                UsageStats::default()
+            })
+        }))
+    }
+
+    fn usage_unit(&self) -> Option<String> {
+        self.config.selected_obj.as_ref().filter(|o| !o.cross_unit).map(|o| o.name.clone())
+    }
+
+    fn save_usage_stats(&self) {
+        let (Some(stats), Some(project_dir)) = (&self.usage_stats, &self.config.project_dir) else {
+            return;
+        };
+        if let Err(e) = stats.save(project_dir) {
+            log::error!("Failed to save usage statistics: {e:#}");
+        }
+    }
+
+    /// Moves whole seconds of the pending time spent on the selected unit into the usage
+    /// statistics. Returns whether any time was recorded.
+    fn flush_usage_time(&mut self) -> bool {
+        let Some(unit) = self.usage_unit() else {
+            return false;
+        };
+        let Some((pending, _)) = &mut self.usage_time else {
+            return false;
+        };
+        let seconds = pending.as_secs();
+        if seconds == 0 {
+            return false;
+        }
+        *pending -= Duration::from_secs(seconds);
+        let Some(stats) = self.load_usage_stats() else {
+            return false;
+        };
+        stats.record_time(&unit, seconds, unix_now());
+        true
+    }
+
+    /// Records the time spent on the selected unit, before it's deselected.
+    pub fn save_usage_time(&mut self) {
+        if self.flush_usage_time() {
+            self.save_usage_stats();
+        }
+        self.usage_time = None;
+    }
+
+    /// Accumulates the time spent on the selected unit, called every frame. Gaps between
+    /// frames longer than [`USAGE_IDLE_TIMEOUT`] are considered idle time.
+    pub fn update_usage_time(&mut self) {
+        if !self.config.usage_stats || self.usage_unit().is_none() {
+            self.usage_time = None;
+            return;
+        }
+        let now = Instant::now();
+        let (pending, last) = self.usage_time.get_or_insert((Duration::ZERO, now));
+        let elapsed = now.duration_since(*last);
+        if elapsed < USAGE_IDLE_TIMEOUT {
+            *pending += elapsed;
+        }
+        *last = now;
+        if *pending >= USAGE_SAVE_INTERVAL && self.flush_usage_time() {
+            self.save_usage_stats();
+        }
+    }
+
+    /// Records a build of the selected unit in the usage statistics, if enabled. Reloads
+    /// without a build aren't counted.
+    pub fn record_usage_build(&mut self, build: &ObjDiffResult) {
+        if build.first_status.cmdline.is_empty() && build.second_status.cmdline.is_empty() {
+            return;
+        }
+        let Some(unit) = self.usage_unit() else {
+            return;
+        };
+        let Some(stats) = self.load_usage_stats() else {
+            return;
+        };
+        stats.record_build(&unit, unix_now());
+        self.flush_usage_time();
+        self.save_usage_stats();
+    }
+
+    /// Writes the project's usage statistics, with the functions matched per day from the
+    /// match history, to a JSON file.
+    pub fn export_usage_stats(&mut self, path: &Path) -> Result<()> {
+        if self.flush_usage_time() {
+            self.save_usage_stats();
+        }
+        let project_dir =
+            self.config.project_dir.clone().ok_or_else(|| anyhow!("No project loaded"))?;
+        let stats = match &self.usage_stats {
+            Some(stats) => stats.clone(),
+            None => UsageStats::load(&project_dir)?,
+        };
+        self.load_match_history();
+        let history = self.match_history.clone().unwrap_or_default();
+        let report = stats.report(&history, unix_now());
+        let data = serde_json::to_vec_pretty(&report)?;
+        fs::write(path, data).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn load_claims(&mut self) -> Option<&mut Claims> {
+        let project_dir = self.config.project_dir.as_deref()?;
+        let dir = claims_dir(project_dir, self.current_project_config.as_ref());
+        Some(self.claims.get_or_insert_with(|| {
+            Claims::load(dir.clone()).unwrap_or_else(|e| {
+                log::warn!("Failed to load claims: {e:#}");
+                Claims::new(dir)
+            })
+        }))
+    }
+
+    /// Claims or releases a unit under the configured claim name.
+    pub fn set_unit_claimed(&mut self, unit: &str, claimed: bool) {
+        let name = self.config.claim_name.trim().to_string();
+        let Some(claims) = self.load_claims() else {
+            return;
+        };
+        let result =
+            if claimed { claims.claim(unit, &name, unix_now()) } else { claims.release(unit) };
+        if let Err(e) = result {
+            log::error!("Failed to update claim for {unit}: {e:#}");
+        }
+    }
+
+    pub fn match_stats(&mut self) -> Option<MatchStats> {
+        self.load_match_history().map(|history| history.stats(unix_now()))
+    }
+
     pub fn clear_selection(&mut self) {
         self.selecting_left = None;
         self.selecting_right = None;
         self.queue_reload = true;
     }
 
+    /// Opens an exported diff session, extracting its objects to a new temporary directory.
+    pub fn open_session(&mut self, path: &Path) -> Result<()> {
+        let session = DiffSession::load(path)?;
+        let dir = tempfile::Builder::new()
+            .prefix("objdiff-session-")
+            .tempdir()
+            .context("Failed to create session directory")?;
+        let (target_path, base_path) = session.extract(dir.path())?;
+        self.set_selected_obj(ObjectConfig {
+            name: session.header.name.clone(),
+            target_path,
+            base_path,
+            symbol_mappings: session.header.symbol_mappings.clone(),
+            cross_unit: true,
+            ..Default::default()
+        });
+        self.session = Some(session.header);
+        self.session_dir = Some(dir);
+        Ok(())
+    }
+
     pub fn clear_mappings(&mut self) {
         self.selecting_left = None;
         self.selecting_right = None;
@@ -357,20 +991,12 @@ impl AppState {
             if let Some(existing) = config.units.as_mut().and_then(|v| {
                 v.iter_mut().find(|u| u.name.as_ref().is_some_and(|n| n == &object.name))
             }) {
-                existing.symbol_mappings = if object.symbol_mappings.is_empty() {
-                    None
-                } else {
-                    Some(object.symbol_mappings.clone())
-                };
+                update_project_object(existing, object);
             }
             if let Some(existing) =
                 self.objects.iter_mut().find(|u| u.name.as_ref().is_some_and(|n| n == &object.name))
             {
-                existing.symbol_mappings = if object.symbol_mappings.is_empty() {
-                    None
-                } else {
-                    Some(object.symbol_mappings.clone())
-                };
+                update_project_object(existing, object);
             }
         }
         // Save the updated project config
@@ -384,6 +1010,26 @@ impl AppState {
     }
 }
 
+/// Writes state edited in the GUI back to the project config's unit.
+pub fn update_project_object(existing: &mut ProjectObject, object: &ObjectConfig) {
+    existing.symbol_mappings =
+        if object.symbol_mappings.is_empty() { None } else { Some(object.symbol_mappings.clone()) };
+    let review_states =
+        if object.review_states.is_empty() { None } else { Some(object.review_states.clone()) };
+    let watched_symbols =
+        if object.watched_symbols.is_empty() { None } else { Some(object.watched_symbols.clone()) };
+    if review_states.is_some() || watched_symbols.is_some() || existing.metadata.is_some() {
+        let metadata = existing.metadata.get_or_insert_with(Default::default);
+        metadata.review_states = review_states;
+        metadata.watched_symbols = watched_symbols;
+    }
+}
+
+/// Time without any UI updates after which the user is considered idle.
+const USAGE_IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// How often the time spent on the selected unit is written to the usage statistics.
+const USAGE_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
 pub type AppStateRef = Arc<RwLock<AppState>>;
 
 #[derive(Default)]
@@ -396,6 +1042,10 @@ pub struct App {
     app_path: Option<PathBuf>,
     relaunch_path: Rc<Mutex<Option<PathBuf>>>,
     should_relaunch: bool,
+    /// Links forwarded by other instances
+    deep_links: Option<Receiver<DeepLink>>,
+    /// A link waiting for its project to load
+    pending_deep_link: Option<DeepLink>,
 }
 
 pub const APPEARANCE_KEY: &str = "appearance";
@@ -403,6 +1053,7 @@ pub const CONFIG_KEY: &str = "app_config";
 
 impl App {
     /// Called once before the first frame.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         cc: &eframe::CreationContext<'_>,
         utc_offset: UtcOffset,
@@ -410,6 +1061,8 @@ impl App {
         app_path: Option<PathBuf>,
         graphics_config: GraphicsConfig,
         graphics_config_path: Option<PathBuf>,
+        project_dir: Option<PathBuf>,
+        deep_link: Option<DeepLink>,
     ) -> Self {
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
@@ -420,6 +1073,7 @@ impl App {
             }
             if let Some(config) = deserialize_config(storage) {
                 let mut state = AppState { config, ..Default::default() };
+                state.set_memory_limit(state.config.memory_limit_mb);
                 if state.config.project_dir.is_some() {
                     state.config_change = true;
                     state.watcher_change = true;
@@ -431,10 +1085,23 @@ impl App {
                 app.state = Arc::new(RwLock::new(state));
             }
         }
+        if let Some(project_dir) = project_dir {
+            if let Ok(mut state) = app.state.write() {
+                state.set_project_dir(project_dir);
+            }
+        }
+        if let Ok(state) = app.state.read() {
+            app.view_state.jobs.set_max_concurrent(state.config.max_concurrent_jobs);
+        }
         app.appearance.init_fonts(&cc.egui_ctx);
         app.appearance.utc_offset = utc_offset;
         app.app_path = app_path;
         app.relaunch_path = relaunch_path;
+        app.pending_deep_link = deep_link;
+        match listen_for_links(cc.egui_ctx.clone()) {
+            Ok(receiver) => app.deep_links = Some(receiver),
+            Err(e) => log::warn!("Failed to listen for forwarded links: {e:#}"),
+        }
         #[cfg(feature = "wgpu")]
         if let Some(wgpu_render_state) = &cc.wgpu_render_state {
             use eframe::egui_wgpu::wgpu::Backend;
@@ -465,7 +1132,15 @@ impl App {
     fn pre_update(&mut self, ctx: &egui::Context) {
         self.appearance.pre_update(ctx);
 
-        let ViewState { jobs, diff_state, config_state, .. } = &mut self.view_state;
+        let ViewState {
+            jobs,
+            diff_state,
+            config_state,
+            terminal_state,
+            symbol_lookup_state,
+            cross_diff_state,
+            ..
+        } = &mut self.view_state;
 
         jobs.collect_results();
         jobs.results.retain(|result| match result {
@@ -480,6 +1155,9 @@ impl App {
         });
         diff_state.pre_update(jobs, &self.state);
         config_state.pre_update(jobs, &self.state);
+        terminal_state.pre_update(jobs);
+        symbol_lookup_state.pre_update(jobs);
+        cross_diff_state.pre_update(jobs);
         debug_assert!(jobs.results.is_empty());
     }
 
@@ -490,15 +1168,29 @@ impl App {
 
         self.appearance.post_update(ctx);
 
-        let ViewState { jobs, diff_state, config_state, graphics_state, .. } = &mut self.view_state;
+        let ViewState {
+            jobs,
+            diff_state,
+            config_state,
+            graphics_state,
+            terminal_state,
+            symbol_lookup_state,
+            cross_diff_state,
+            ..
+        } = &mut self.view_state;
         config_state.post_update(ctx, jobs, &self.state);
         diff_state.post_update(action, ctx, jobs, &self.state);
+        terminal_state.post_update(ctx, jobs, &self.state);
+        symbol_lookup_state.post_update(ctx, jobs, &self.state);
+        cross_diff_state.post_update(ctx, jobs, &self.state);
 
         let Ok(mut state) = self.state.write() else {
             return;
         };
         let state = &mut *state;
 
+        state.update_usage_time();
+
         let mut mod_check = false;
         if state.last_mod_check.elapsed().as_millis() >= 500 {
             state.last_mod_check = Instant::now();
@@ -507,12 +1199,13 @@ impl App {
 
         if mod_check {
             if let Some(info) = &state.project_config_info {
-                if let Some(last_ts) = info.timestamp {
-                    if file_modified(&info.path, last_ts) {
-                        state.config_change = true;
-                    }
+                if info.is_modified() {
+                    state.config_change = true;
                 }
             }
+            if state.claims.as_ref().is_some_and(|c| c.is_modified()) {
+                state.claims = None;
+            }
         }
 
         if state.config_change {
@@ -526,15 +1219,29 @@ impl App {
             }
         }
 
+        if let Some(link) = self.deep_links.as_ref().and_then(|r| r.try_recv().ok()) {
+            self.pending_deep_link = Some(link);
+        }
+        if let Some(link) = self.pending_deep_link.take() {
+            self.pending_deep_link = open_deep_link(state, diff_state, link);
+        }
+
         if state.watcher_change {
             drop(self.watcher.take());
 
             if let Some(project_dir) = &state.config.project_dir {
+                let options = watcher_options(state);
                 match build_globset(&state.config.watch_patterns)
                     .map_err(anyhow::Error::new)
                     .and_then(|globset| {
-                        create_watcher(self.modified.clone(), project_dir, globset, egui_waker(ctx))
-                            .map_err(anyhow::Error::new)
+                        create_watcher(
+                            self.modified.clone(),
+                            project_dir,
+                            globset,
+                            options,
+                            egui_waker(ctx),
+                        )
+                        .map_err(anyhow::Error::new)
                     }) {
                     Ok(watcher) => self.watcher = Some(watcher),
                     Err(e) => log::error!("Failed to create watcher: {e}"),
@@ -544,7 +1251,10 @@ impl App {
         }
 
         if state.obj_change {
+            // Keep any navigation queued for the new object (e.g. from "Diff with…")
+            let post_build_nav = diff_state.post_build_nav.take();
             *diff_state = Default::default();
+            diff_state.post_build_nav = post_build_nav;
             if state.config.selected_obj.is_some() {
                 state.queue_build = true;
             }
@@ -583,6 +1293,7 @@ impl App {
             start_build(ctx, jobs, create_objdiff_config(state));
             state.queue_build = false;
             state.queue_reload = false;
+            state.force_build = false;
         } else if state.queue_reload && !jobs.is_running(Job::ObjDiff) {
             let mut diff_config = create_objdiff_config(state);
             // Don't build, just reload the current files
@@ -620,24 +1331,46 @@ impl eframe::App for App {
             config_state,
             demangle_state,
             rlwinm_decode_state,
+            search_state,
             diff_state,
             graphics_state,
             frame_history,
+            command_palette_state,
+            cross_diff_state,
+            mappings_state,
+            notes_state,
+            terminal_state,
+            symbol_lookup_state,
+            report_changes_state,
             show_appearance_config,
             show_demangle,
             show_rlwinm_decode,
+            show_search,
+            show_symbol_lookup,
+            show_report_changes,
+            show_load_details,
             show_project_config,
             show_arch_config,
             show_debug,
             show_graphics,
             show_jobs,
+            show_mappings,
             show_side_panel,
+            show_notes,
+            show_terminal,
         } = view_state;
 
         frame_history.on_new_frame(ctx.input(|i| i.time), frame.info().cpu_usage);
 
         let side_panel_available = diff_state.current_view == View::SymbolDiff;
 
+        let notes_path = state.read().ok().and_then(|state| {
+            let project_dir = state.config.project_dir.as_ref()?;
+            let notes_path = state.config.selected_obj.as_ref()?.notes_path.as_ref()?;
+            Some(project_dir.join(notes_path))
+        });
+        notes_state.update(notes_path);
+
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
                 if ui
@@ -661,26 +1394,23 @@ impl eframe::App for App {
                         *show_project_config = !*show_project_config;
                         ui.close_menu();
                     }
-                    let recent_projects = if let Ok(guard) = state.read() {
-                        guard.config.recent_projects.clone()
-                    } else {
-                        vec![]
-                    };
-                    if recent_projects.is_empty() {
-                        ui.add_enabled(false, egui::Button::new("Recent projects…"));
-                    } else {
-                        ui.menu_button("Recent Projects…", |ui| {
-                            if ui.button("Clear").clicked() {
-                                state.write().unwrap().config.recent_projects.clear();
-                            };
-                            ui.separator();
-                            for path in recent_projects {
-                                if ui.button(format!("{}", path.display())).clicked() {
-                                    state.write().unwrap().set_project_dir(path);
-                                    ui.close_menu();
-                                }
-                            }
-                        });
+                    recent_projects_menu_ui(ui, state);
+                    if ui
+                        .button("Open Session…")
+                        .on_hover_text("Open a diff session exported by objdiff")
+                        .clicked()
+                    {
+                        config_state.file_dialog_state.queue(
+                            || {
+                                Box::pin(
+                                    rfd::AsyncFileDialog::new()
+                                        .add_filter("objdiff session", &[SESSION_EXTENSION])
+                                        .pick_file(),
+                                )
+                            },
+                            FileDialogResult::OpenSession,
+                        );
+                        ui.close_menu();
                     }
                     if ui.button("Appearance…").clicked() {
                         *show_appearance_config = !*show_appearance_config;
@@ -703,9 +1433,29 @@ impl eframe::App for App {
                         *show_rlwinm_decode = !*show_rlwinm_decode;
                         ui.close_menu();
                     }
+                    if ui.button("Byte Search…").clicked() {
+                        *show_search = !*show_search;
+                        ui.close_menu();
+                    }
+                    if ui.button("Symbol Lookup…").clicked() {
+                        *show_symbol_lookup = !*show_symbol_lookup;
+                        ui.close_menu();
+                    }
+                    if ui.button("Report Changes…").clicked() {
+                        *show_report_changes = !*show_report_changes;
+                        ui.close_menu();
+                    }
+                    if ui.button("Load Details…").clicked() {
+                        *show_load_details = !*show_load_details;
+                        ui.close_menu();
+                    }
+                    if ui.button("Terminal").clicked() {
+                        *show_terminal = !*show_terminal;
+                        ui.close_menu();
+                    }
                 });
                 ui.menu_button("Diff Options", |ui| {
-                    if ui.button("Arch Settings…").clicked() {
+                    if ui.button("Diff Settings…").clicked() {
                         *show_arch_config = !*show_arch_config;
                         ui.close_menu();
                     }
@@ -728,50 +1478,108 @@ impl eframe::App for App {
                         &mut diff_state.symbol_state.show_hidden_symbols,
                         "Show hidden symbols",
                     );
-                    if ui
-                        .checkbox(
-                            &mut state.config.diff_obj_config.relax_reloc_diffs,
-                            "Relax relocation diffs",
-                        )
-                        .on_hover_text(
-                            "Ignores differences in relocation targets. (Address, name, etc)",
-                        )
-                        .changed()
-                    {
-                        state.queue_reload = true;
+                    ui.checkbox(
+                        &mut diff_state.symbol_state.short_symbol_names,
+                        "Short symbol names",
+                    )
+                    .on_hover_text(
+                        "Show unqualified names without parameters in symbol lists.\n\
+                        Full names are still shown on hover and in the diff header, and used \
+                        for searches.",
+                    );
+                    ui.checkbox(
+                        &mut diff_state.symbol_state.show_opcode_percent,
+                        "Show opcode match percent",
+                    )
+                    .on_hover_text(
+                        "Show the percentage of aligned instructions with the same opcode next \
+                        to mismatched functions, ignoring arguments.\nUseful for finding \
+                        functions that only differ in registers or immediates.",
+                    );
+                    for option in DiffOption::ALL {
+                        let response = ui
+                            .checkbox(
+                                option.value_mut(&mut state.config.diff_obj_config),
+                                option.label(),
+                            )
+                            .on_hover_text(option.description());
+                        if response.changed() {
+                            state.queue_reload = true;
+                        }
                     }
+                    let unit_symbol_matching =
+                        state.config.selected_obj.as_ref().and_then(|obj| obj.symbol_matching);
+                    let mut match_by_address = unit_symbol_matching
+                        .unwrap_or(state.config.diff_obj_config.symbol_matching)
+                        == SymbolMatching::Address;
                     if ui
-                        .checkbox(
-                            &mut state.config.diff_obj_config.space_between_args,
-                            "Space between args",
+                        .add_enabled(
+                            unit_symbol_matching.is_none(),
+                            egui::Checkbox::new(&mut match_by_address, "Match symbols by address"),
                         )
-                        .changed()
-                    {
-                        state.queue_reload = true;
-                    }
-                    if ui
-                        .checkbox(
-                            &mut state.config.diff_obj_config.combine_data_sections,
-                            "Combine data sections",
+                        .on_hover_text(
+                            "Pairs symbols at the same address within sections of the same name, \
+                            instead of by name.",
                         )
-                        .on_hover_text("Combines data sections with equal names.")
+                        .on_disabled_hover_text(CONFIG_DISABLED_TEXT)
                         .changed()
                     {
+                        state.config.diff_obj_config.symbol_matching = if match_by_address {
+                            SymbolMatching::Address
+                        } else {
+                            SymbolMatching::Name
+                        };
                         state.queue_reload = true;
                     }
+                    ui.horizontal(|ui| {
+                        ui.label(ConfigProperty::MaxAlignmentEdits.name());
+                        let response = ui
+                            .add(
+                                egui::DragValue::new(
+                                    &mut state.config.diff_obj_config.max_alignment_edits,
+                                )
+                                .range(0..=100_000)
+                                .speed(10),
+                            )
+                            .on_hover_text(ConfigProperty::MaxAlignmentEdits.description());
+                        if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                            state.queue_reload = true;
+                        }
+                    });
                     if ui.button("Clear custom symbol mappings").clicked() {
                         state.clear_mappings();
                         diff_state.post_build_nav = Some(DiffViewNavigation::symbol_diff());
                         state.queue_reload = true;
                     }
+                    if ui.button("Symbol mappings…").clicked() {
+                        *show_mappings = !*show_mappings;
+                        ui.close_menu();
+                    }
                 });
                 ui.separator();
+                if notes_state.has_notes() {
+                    if ui
+                        .selectable_label(*show_notes, "📝 Notes")
+                        .on_hover_text("Toggle notes for the selected unit")
+                        .clicked()
+                    {
+                        *show_notes = !*show_notes;
+                    }
+                    ui.separator();
+                }
                 if jobs_menu_ui(ui, jobs, appearance) {
                     *show_jobs = !*show_jobs;
                 }
             });
         });
 
+        // Ctrl+F searches within the function when it's shown, otherwise it filters objects
+        if diff_state.current_view == View::FunctionDiff
+            && hotkeys::consume_function_search_shortcut(ctx)
+        {
+            focus_function_search(ctx);
+        }
+
         if side_panel_available {
             egui::SidePanel::left("side_panel").show_animated(ctx, *show_side_panel, |ui| {
                 egui::ScrollArea::both().show(ui, |ui| {
@@ -780,8 +1588,13 @@ impl eframe::App for App {
             });
         }
 
+        session_panel(ctx, state, diff_state, appearance);
+        notes_panel(ctx, *show_notes, notes_state, appearance);
+        terminal_panel(ctx, show_terminal, terminal_state, jobs, state, appearance);
+
         let mut action = None;
         egui::CentralPanel::default().show(ctx, |ui| {
+            let start = Instant::now();
             let build_success = matches!(&diff_state.build, Some(b) if b.first_status.success && b.second_status.success);
             action = if diff_state.current_view == View::FunctionDiff && build_success {
                 function_diff_ui(ui, diff_state, appearance)
@@ -789,26 +1602,115 @@ impl eframe::App for App {
                 data_diff_ui(ui, diff_state, appearance)
             } else if diff_state.current_view == View::ExtabDiff && build_success {
                 extab_diff_ui(ui, diff_state, appearance)
+            } else if diff_state.build.is_none() && diff_state.build_running {
+                load_progress_ui(ui, jobs, appearance);
+                None
+            } else if diff_state.build.is_none()
+                && state.read().is_ok_and(|state| state.config.project_dir.is_none())
+            {
+                start_screen_ui(ui, state, show_project_config, appearance);
+                None
             } else {
                 symbol_diff_ui(ui, diff_state, appearance)
             };
+            diff_state.display_time = start.elapsed();
         });
 
+        let command = if let Ok(state) = state.read() {
+            command_palette_window(ctx, command_palette_state, &state, appearance)
+        } else {
+            None
+        };
+        if let Some(command) = command {
+            let mut state = state.write().unwrap();
+            match command {
+                Command::Build => state.queue_build = true,
+                Command::ForceBuild => {
+                    state.queue_build = true;
+                    state.force_build = true;
+                }
+                Command::SelectUnit(name) => {
+                    if let Some(object) = state.objects.iter().find(|o| o.name() == name) {
+                        let config = ObjectConfig::from(object);
+                        state.set_selected_obj(config);
+                    }
+                }
+                Command::ToggleDiffOption(option) => {
+                    let value = option.value_mut(&mut state.config.diff_obj_config);
+                    *value = !*value;
+                    state.queue_reload = true;
+                }
+                Command::ToggleRebuildOnChanges => {
+                    state.config.rebuild_on_changes = !state.config.rebuild_on_changes;
+                    state.watcher_change = true;
+                }
+                Command::ToggleWindow(window) => {
+                    let show = match window {
+                        AppWindow::Project => &mut *show_project_config,
+                        AppWindow::Appearance => &mut *show_appearance_config,
+                        AppWindow::Graphics => &mut *show_graphics,
+                        AppWindow::Demangle => &mut *show_demangle,
+                        AppWindow::RlwinmDecoder => &mut *show_rlwinm_decode,
+                        AppWindow::ByteSearch => &mut *show_search,
+                        AppWindow::SymbolLookup => &mut *show_symbol_lookup,
+                        AppWindow::ReportChanges => &mut *show_report_changes,
+                        AppWindow::LoadDetails => &mut *show_load_details,
+                        AppWindow::ArchSettings => &mut *show_arch_config,
+                        AppWindow::Jobs => &mut *show_jobs,
+                        AppWindow::Terminal => &mut *show_terminal,
+                    };
+                    *show = !*show;
+                }
+                Command::ToggleSidePanel => *show_side_panel = !*show_side_panel,
+                Command::ClearSymbolMappings => {
+                    state.clear_mappings();
+                    diff_state.post_build_nav = Some(DiffViewNavigation::symbol_diff());
+                }
+            }
+        }
+
         project_window(ctx, state, show_project_config, config_state, appearance);
         appearance_window(ctx, show_appearance_config, appearance);
         demangle_window(ctx, show_demangle, demangle_state, appearance);
         rlwinm_decode_window(ctx, show_rlwinm_decode, rlwinm_decode_state, appearance);
-        arch_config_window(ctx, state, show_arch_config, appearance);
-        debug_window(ctx, show_debug, frame_history, appearance);
+        search_window(ctx, show_search, search_state, diff_state.build.as_deref(), appearance);
+        load_details_window(ctx, show_load_details, diff_state.build.as_deref(), appearance);
+        watch_notification_window(ctx, &mut diff_state.watch_state, appearance);
+        if let Some(DiffViewAction::DiffWith(source)) = &action {
+            cross_diff_state.open(source.clone());
+        }
+        cross_diff_window(ctx, cross_diff_state, state, diff_state, appearance);
+        symbol_lookup_window(
+            ctx,
+            show_symbol_lookup,
+            symbol_lookup_state,
+            state,
+            diff_state,
+            appearance,
+        );
+        report_changes_window(ctx, show_report_changes, report_changes_state, appearance);
+        mappings_window(
+            ctx,
+            show_mappings,
+            mappings_state,
+            state,
+            diff_state.build.as_deref(),
+            appearance,
+        );
+        arch_config_window(ctx, state, show_arch_config, config_state, appearance);
+        debug_window(ctx, show_debug, frame_history, state, diff_state, appearance);
         graphics_window(ctx, show_graphics, frame_history, graphics_state, appearance);
-        jobs_window(ctx, show_jobs, jobs, appearance);
+        jobs_window(ctx, show_jobs, jobs, state, appearance);
 
         self.post_update(ctx, action);
     }
 
     /// Called by the framework to save state before shutdown.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        if let Ok(state) = self.state.read() {
+        if let Ok(mut state) = self.state.write() {
+            if state.flush_usage_time() {
+                state.save_usage_stats();
+            }
             eframe::set_value(storage, CONFIG_KEY, &state.config);
         }
         eframe::set_value(storage, APPEARANCE_KEY, &self.appearance);
@@ -816,6 +1718,24 @@ impl eframe::App for App {
 }
 
 #[inline]
+fn watcher_options(state: &AppState) -> WatcherOptions {
+    let mut options = WatcherOptions {
+        debounce: Duration::from_millis(state.config.watch_debounce_ms),
+        ..Default::default()
+    };
+    if state.config.watch_scope == WatchScope::SelectedUnit {
+        let selected = state.config.selected_obj.as_ref().map(|obj| obj.name.as_str());
+        options.excluded_paths = state
+            .objects
+            .iter()
+            .filter(|unit| Some(unit.name()) != selected)
+            .filter_map(|unit| unit.source_path())
+            .map(PathBuf::from)
+            .collect();
+    }
+    options
+}
+
 fn file_modified(path: &Path, last_ts: FileTime) -> bool {
     if let Ok(metadata) = fs::metadata(path) {
         FileTime::from_last_modification_time(&metadata) != last_ts
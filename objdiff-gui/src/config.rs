@@ -92,18 +92,39 @@ pub fn load_project_config(state: &mut AppState) -> Result<()> {
     };
     if let Some((result, info)) = try_project_config(project_dir) {
         let project_config = result?;
+        // Fall back to the default version if the selected one no longer exists
+        if state
+            .config
+            .selected_version
+            .as_ref()
+            .is_some_and(|v| !project_config.versions().contains(v))
+        {
+            state.config.selected_version = None;
+        }
+        let versioned_config =
+            project_config.for_version(state.config.selected_version.as_deref())?;
         state.config.custom_make = project_config.custom_make.clone();
         state.config.custom_args = project_config.custom_args.clone();
         state.config.target_obj_dir =
-            project_config.target_dir.as_deref().map(|p| project_dir.join(p));
-        state.config.base_obj_dir = project_config.base_dir.as_deref().map(|p| project_dir.join(p));
+            versioned_config.target_dir.as_deref().map(|p| project_dir.join(p));
+        state.config.base_obj_dir =
+            versioned_config.base_dir.as_deref().map(|p| project_dir.join(p));
         state.config.build_base = project_config.build_base.unwrap_or(true);
         state.config.build_target = project_config.build_target.unwrap_or(false);
         state.config.watch_patterns = project_config.watch_patterns.clone().unwrap_or_else(|| {
             DEFAULT_WATCH_PATTERNS.iter().map(|s| Glob::new(s).unwrap()).collect()
         });
+        if let Some(debounce_ms) = project_config.watch_debounce_ms {
+            state.config.watch_debounce_ms = debounce_ms;
+        }
+        if let Some(rebuild_on_changes) = project_config.rebuild_on_changes {
+            state.config.rebuild_on_changes = rebuild_on_changes;
+        }
+        if let Some(watch_scope) = project_config.watch_scope {
+            state.config.watch_scope = watch_scope;
+        }
         state.watcher_change = true;
-        state.objects = project_config.units.clone().unwrap_or_default();
+        state.objects = versioned_config.units.unwrap_or_default();
         state.object_nodes = build_nodes(
             &mut state.objects,
             project_dir,
@@ -112,10 +133,14 @@ pub fn load_project_config(state: &mut AppState) -> Result<()> {
         );
         state.current_project_config = Some(project_config);
         state.project_config_info = Some(info);
+        // The claims directory may have changed
+        state.claims = None;
 
         // Reload selected object
         if let Some(selected_obj) = &state.config.selected_obj {
-            if let Some(obj) = state.objects.iter().find(|o| o.name() == selected_obj.name) {
+            if selected_obj.cross_unit {
+                // Temporary "Diff with…" pairings aren't part of the project config
+            } else if let Some(obj) = state.objects.iter().find(|o| o.name() == selected_obj.name) {
                 let config = ObjectConfig::from(obj);
                 state.set_selected_obj(config);
             } else {
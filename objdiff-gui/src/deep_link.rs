@@ -0,0 +1,336 @@
+//! `objdiff://` links to a unit, and optionally a symbol within it.
+//!
+//! Links have the form `objdiff://<unit>/<symbol>?project=<name>`, with the unit and symbol
+//! names percent-encoded (including any `/` in the unit name). The project is identified by its
+//! directory name, since teammates rarely have it at the same path; it's looked up among the
+//! open and recent projects. Data symbols add `view=data`.
+//!
+//! A link passed on the command line is forwarded to an already running instance if there is
+//! one. Each instance listens on a localhost port, written to `deep_link.port` in the storage
+//! directory, for links to open.
+
+use std::{
+    fmt, fs,
+    io::{BufRead, BufReader, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::mpsc::{channel, Receiver},
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+
+use crate::{
+    app::{AppState, ObjectConfig},
+    views::symbol_diff::{DiffViewNavigation, DiffViewState, SymbolRefByName, View},
+    APP_NAME,
+};
+
+pub const SCHEME: &str = "objdiff";
+
+/// File in the storage directory holding the port the running instance listens on.
+const PORT_FILE: &str = "deep_link.port";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeepLink {
+    /// Directory name of the project
+    pub project: Option<String>,
+    pub unit: String,
+    pub symbol: Option<String>,
+    /// [`View::FunctionDiff`] or [`View::DataDiff`], used if a symbol is given
+    pub view: View,
+}
+
+impl DeepLink {
+    pub fn new(project_dir: Option<&Path>, unit: &str) -> Self {
+        Self {
+            project: project_dir.and_then(project_name),
+            unit: unit.to_string(),
+            symbol: None,
+            view: View::FunctionDiff,
+        }
+    }
+
+    pub fn with_symbol(mut self, symbol: &str, view: View) -> Self {
+        self.symbol = Some(symbol.to_string());
+        self.view = view;
+        self
+    }
+}
+
+fn project_name(project_dir: &Path) -> Option<String> {
+    Some(project_dir.file_name()?.to_string_lossy().into_owned())
+}
+
+/// Percent-encodes everything but unreserved characters.
+fn encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+fn decode(s: &str) -> Result<String> {
+    let mut out = Vec::with_capacity(s.len());
+    let mut bytes = s.bytes();
+    while let Some(b) = bytes.next() {
+        if b == b'%' {
+            let hex = [bytes.next(), bytes.next()];
+            let byte = match hex {
+                [Some(hi), Some(lo)] => std::str::from_utf8(&[hi, lo])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+                _ => None,
+            };
+            out.push(byte.ok_or_else(|| anyhow!("Invalid percent-encoding in {s:?}"))?);
+        } else {
+            out.push(b);
+        }
+    }
+    String::from_utf8(out).with_context(|| format!("Invalid UTF-8 in {s:?}"))
+}
+
+impl fmt::Display for DeepLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{SCHEME}://{}", encode(&self.unit))?;
+        if let Some(symbol) = &self.symbol {
+            write!(f, "/{}", encode(symbol))?;
+        }
+        let mut separator = '?';
+        if let Some(project) = &self.project {
+            write!(f, "{separator}project={}", encode(project))?;
+            separator = '&';
+        }
+        if self.symbol.is_some() && self.view == View::DataDiff {
+            write!(f, "{separator}view=data")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DeepLink {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let rest = s
+            .strip_prefix(SCHEME)
+            .and_then(|s| s.strip_prefix("://"))
+            .ok_or_else(|| anyhow!("Not an {SCHEME}:// link: {s}"))?;
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let path = path.trim_end_matches('/');
+        let (unit, symbol) = match path.split_once('/') {
+            Some((unit, symbol)) => (unit, Some(symbol)),
+            None => (path, None),
+        };
+        let unit = decode(unit)?;
+        ensure!(!unit.is_empty(), "Link is missing a unit: {s}");
+        let symbol = symbol.map(decode).transpose()?.filter(|symbol| !symbol.is_empty());
+        let mut link = DeepLink { project: None, unit, symbol, view: View::FunctionDiff };
+        for param in query.split('&').filter(|param| !param.is_empty()) {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            match key {
+                "project" => link.project = Some(decode(value)?),
+                "view" => {
+                    link.view = match value {
+                        "function" => View::FunctionDiff,
+                        "data" => View::DataDiff,
+                        _ => bail!("Unknown view in link: {value}"),
+                    }
+                }
+                _ => log::warn!("Ignoring unknown link parameter {key}"),
+            }
+        }
+        Ok(link)
+    }
+}
+
+/// The link passed on the command line, if any.
+pub fn link_arg() -> Option<DeepLink> {
+    let arg = std::env::args().skip(1).find(|arg| arg.starts_with(&format!("{SCHEME}:")))?;
+    match arg.parse() {
+        Ok(link) => Some(link),
+        Err(e) => {
+            log::error!("Failed to parse link: {e:#}");
+            None
+        }
+    }
+}
+
+fn port_file() -> Option<PathBuf> { Some(eframe::storage_dir(APP_NAME)?.join(PORT_FILE)) }
+
+/// Sends a link to the running instance. Returns `false` if there's none to send it to.
+pub fn forward_to_running_instance(link: &DeepLink) -> bool {
+    let Some(port) = port_file()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|port| port.trim().parse::<u16>().ok())
+    else {
+        return false;
+    };
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let result = TcpStream::connect_timeout(&addr, Duration::from_millis(500))
+        .and_then(|mut stream| writeln!(stream, "{link}"));
+    match result {
+        Ok(()) => {
+            log::info!("Opened {link} in the running instance");
+            true
+        }
+        // Most likely a stale port file from an instance that's no longer running
+        Err(_) => false,
+    }
+}
+
+/// Listens for links forwarded by [`forward_to_running_instance`], focusing the window when
+/// one arrives.
+pub fn listen(ctx: egui::Context) -> Result<Receiver<DeepLink>> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let port = listener.local_addr()?.port();
+    let path = port_file().ok_or_else(|| anyhow!("No storage directory"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, port.to_string())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    let (sender, receiver) = channel();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let mut line = String::new();
+            if BufReader::new(stream).read_line(&mut line).is_err() {
+                continue;
+            }
+            match line.trim().parse::<DeepLink>() {
+                Ok(link) => {
+                    if sender.send(link).is_err() {
+                        break;
+                    }
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+                    ctx.request_repaint();
+                }
+                Err(e) => log::warn!("Ignoring forwarded link: {e:#}"),
+            }
+        }
+    });
+    Ok(receiver)
+}
+
+/// Opens the link's project, unit and symbol. If the project has to be loaded first, the link
+/// is returned to be opened again on a later frame.
+pub fn open_deep_link(
+    state: &mut AppState,
+    diff_state: &mut DiffViewState,
+    link: DeepLink,
+) -> Option<DeepLink> {
+    if let Some(project) = &link.project {
+        let current = state.config.project_dir.as_deref().and_then(project_name);
+        if current.as_ref() != Some(project) {
+            let Some(path) = state
+                .recent_projects()
+                .find(|(path, _)| project_name(path).as_ref() == Some(project))
+                .map(|(path, _)| path.to_path_buf())
+            else {
+                log::error!("Project {project} not found in recent projects; open it once first");
+                return None;
+            };
+            state.set_project_dir(path);
+            return Some(link);
+        }
+    }
+    if state.config_change {
+        return Some(link);
+    }
+    let Some(object) = state.objects.iter().find(|o| o.name() == link.unit) else {
+        log::error!("Unit {} not found in the project", link.unit);
+        return None;
+    };
+    let config = ObjectConfig::from(object);
+    let nav = match link.symbol {
+        Some(symbol_name) => {
+            let symbol_ref = SymbolRefByName { symbol_name, section_name: None };
+            DiffViewNavigation {
+                view: Some(link.view),
+                left_symbol: Some(symbol_ref.clone()),
+                right_symbol: Some(symbol_ref),
+            }
+        }
+        None => DiffViewNavigation::symbol_diff(),
+    };
+    if state.config.selected_obj.as_ref() == Some(&config) && diff_state.build.is_some() {
+        // Already loaded, navigate directly
+        if let Some(view) = nav.view {
+            diff_state.current_view = view;
+        }
+        diff_state.symbol_state.left_symbol = nav.left_symbol;
+        diff_state.symbol_state.right_symbol = nav.right_symbol;
+        return None;
+    }
+    state.set_selected_obj(config);
+    diff_state.post_build_nav = Some(nav);
+    None
+}
+
+/// Registers objdiff as the handler for `objdiff://` links, launching the current executable.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn register_handler() -> Result<()> {
+    use std::process::Command;
+    let exe = std::env::current_exe()?;
+    let data_dir = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))
+        .ok_or_else(|| anyhow!("Neither XDG_DATA_HOME nor HOME is set"))?;
+    let dir = data_dir.join("applications");
+    fs::create_dir_all(&dir)?;
+    let desktop_file = format!("{APP_NAME}-link.desktop");
+    let path = dir.join(&desktop_file);
+    fs::write(
+        &path,
+        format!(
+            "[Desktop Entry]\nType=Application\nName={APP_NAME}\nExec=\"{}\" %u\nNoDisplay=true\n\
+             MimeType=x-scheme-handler/{SCHEME};\n",
+            exe.display()
+        ),
+    )
+    .with_context(|| format!("Failed to write {}", path.display()))?;
+    let status = Command::new("xdg-mime")
+        .args(["default", &desktop_file, &format!("x-scheme-handler/{SCHEME}")])
+        .status()
+        .context("Failed to run xdg-mime")?;
+    ensure!(status.success(), "xdg-mime failed: {status}");
+    // Not available everywhere, and only needed by some desktops
+    let _ = Command::new("update-desktop-database").arg(&dir).status();
+    Ok(())
+}
+
+/// Registers objdiff as the handler for `objdiff://` links, launching the current executable.
+#[cfg(windows)]
+pub fn register_handler() -> Result<()> {
+    use std::{os::windows::process::CommandExt, process::Command};
+    let exe = std::env::current_exe()?;
+    let key = format!(r"HKCU\Software\Classes\{SCHEME}");
+    let command = format!("\"{}\" \"%1\"", exe.display());
+    for args in [
+        vec![key.clone(), "/ve".into(), "/d".into(), format!("URL:{APP_NAME} link")],
+        vec![key.clone(), "/v".into(), "URL Protocol".into(), "/d".into(), String::new()],
+        vec![format!(r"{key}\shell\open\command"), "/ve".into(), "/d".into(), command],
+    ] {
+        let status = Command::new("reg")
+            .arg("add")
+            .args(args)
+            .arg("/f")
+            .creation_flags(winapi::um::winbase::CREATE_NO_WINDOW)
+            .status()
+            .context("Failed to run reg")?;
+        ensure!(status.success(), "reg add failed: {status}");
+    }
+    Ok(())
+}
+
+/// macOS only routes links to applications that declare the scheme in their bundle.
+#[cfg(not(any(windows, all(unix, not(target_os = "macos")))))]
+pub fn register_handler() -> Result<()> {
+    bail!("Registering a link handler isn't supported on this platform")
+}
@@ -0,0 +1,76 @@
+//! Opening source files in an external editor, at a given line.
+//!
+//! The editor is configured as a command template, split into arguments like a shell would
+//! (whitespace separated, with `"` or `'` quoting). `{file}` is replaced with the path of the
+//! source file and `{line}` with the line number, or 1 if it's unknown.
+
+use std::{path::Path, process::Command};
+
+use anyhow::{anyhow, bail, Context, Result};
+use objdiff_core::obj::{ObjSection, ObjSymbol};
+
+/// Command templates for common editors, as (name, template).
+pub const EDITOR_PRESETS: &[(&str, &str)] = &[
+    ("Visual Studio Code", "code --goto {file}:{line}"),
+    ("CLion", "clion --line {line} {file}"),
+    ("Sublime Text", "subl {file}:{line}"),
+    ("Zed", "zed {file}:{line}"),
+    ("gVim", "gvim +{line} {file}"),
+    ("Vim (in a terminal)", "x-terminal-emulator -e vim +{line} {file}"),
+    ("Emacs", "emacsclient -n +{line} {file}"),
+];
+
+/// Splits a command template into arguments.
+fn split_args(template: &str) -> Result<Vec<String>> {
+    let mut args = vec![];
+    let mut arg = None::<String>;
+    let mut quote = None;
+    for c in template.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => arg.get_or_insert_with(String::new).push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                arg.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => args.extend(arg.take()),
+            (None, c) => arg.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        bail!("Unterminated quote in editor command");
+    }
+    args.extend(arg);
+    Ok(args)
+}
+
+/// Opens a file in the editor given by the command template, or with the default application
+/// if the template is empty.
+pub fn open_in_editor(template: &str, path: &Path, line: Option<u32>) -> Result<()> {
+    if template.trim().is_empty() {
+        return open::that_detached(path)
+            .with_context(|| format!("Failed to open {}", path.display()));
+    }
+    let file = path.to_string_lossy();
+    let line = line.unwrap_or(1).to_string();
+    let mut args = split_args(template)?
+        .into_iter()
+        .map(|arg| arg.replace("{file}", &file).replace("{line}", &line));
+    let program = args.next().ok_or_else(|| anyhow!("Empty editor command"))?;
+    log::info!("Opening {}:{line} with {program}", path.display());
+    // Not waited on: terminal editors keep running until they're closed
+    Command::new(&program)
+        .args(args)
+        .spawn()
+        .with_context(|| format!("Failed to run editor command {program}"))?;
+    Ok(())
+}
+
+/// The source line a symbol starts at, from the section's line number info.
+pub fn symbol_line(section: &ObjSection, symbol: &ObjSymbol) -> Option<u32> {
+    section
+        .line_info
+        .range(symbol.address..symbol.address + symbol.size.max(1))
+        .next()
+        .map(|(_, &line)| line)
+}
@@ -89,6 +89,12 @@ pub fn consume_object_filter_shortcut(ctx: &Context) -> bool {
     ctx.input_mut(|i| i.consume_shortcut(&OBJECT_FILTER_SHORTCUT))
 }
 
+const FUNCTION_SEARCH_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::CTRL, Key::F);
+
+pub fn consume_function_search_shortcut(ctx: &Context) -> bool {
+    ctx.input_mut(|i| i.consume_shortcut(&FUNCTION_SEARCH_SHORTCUT))
+}
+
 const SYMBOL_FILTER_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::CTRL, Key::S);
 
 pub fn consume_symbol_filter_shortcut(ctx: &Context) -> bool {
@@ -106,3 +112,22 @@ const CHANGE_BASE_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::
 pub fn consume_change_base_shortcut(ctx: &Context) -> bool {
     ctx.input_mut(|i| i.consume_shortcut(&CHANGE_BASE_SHORTCUT))
 }
+
+const COMMAND_PALETTE_SHORTCUT: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::CTRL.plus(Modifiers::SHIFT), Key::P);
+
+pub fn consume_command_palette_shortcut(ctx: &Context) -> bool {
+    ctx.input_mut(|i| i.consume_shortcut(&COMMAND_PALETTE_SHORTCUT))
+}
+
+const NEXT_BOOKMARK_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::F2);
+
+pub fn consume_next_bookmark_shortcut(ctx: &Context) -> bool {
+    ctx.input_mut(|i| i.consume_shortcut(&NEXT_BOOKMARK_SHORTCUT))
+}
+
+const PREV_BOOKMARK_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::SHIFT, Key::F2);
+
+pub fn consume_prev_bookmark_shortcut(ctx: &Context) -> bool {
+    ctx.input_mut(|i| i.consume_shortcut(&PREV_BOOKMARK_SHORTCUT))
+}
@@ -7,12 +7,14 @@ use anyhow::{bail, Result};
 use jobs::create_scratch;
 use objdiff_core::{
     build::BuildConfig,
+    diff::{DiffObjConfig, MappingConfig},
     jobs,
     jobs::{check_update::CheckUpdateConfig, objdiff, update::UpdateConfig, Job, JobQueue},
+    session::DiffInputs,
 };
 
 use crate::{
-    app::{AppConfig, AppState},
+    app::{update_project_object, AppConfig, AppState},
     update::{build_updater, BIN_NAME_NEW, BIN_NAME_OLD},
 };
 
@@ -70,7 +72,14 @@ fn create_scratch_config(
         build_context: scratch_config.build_ctx.unwrap_or(false),
         compiler: scratch_config.compiler.clone().unwrap_or_default(),
         platform: scratch_config.platform.clone().unwrap_or_default(),
-        compiler_flags: scratch_config.c_flags.clone().unwrap_or_default(),
+        // A function's flag override beats the scratch flags, which beat the unit's flags
+        compiler_flags: match selected_obj.compiler_flags_for(&function_name) {
+            Some((flags, true)) => Some(flags.to_string()),
+            unit_flags => {
+                scratch_config.c_flags.clone().or_else(|| unit_flags.map(|(f, _)| f.to_string()))
+            }
+        }
+        .unwrap_or_default(),
         function_name,
         target_obj: target_path.to_path_buf(),
         preset_id: scratch_config.preset_id,
@@ -89,33 +98,52 @@ impl From<&AppConfig> for BuildConfig {
 }
 
 pub fn create_objdiff_config(state: &AppState) -> objdiff::ObjDiffConfig {
-    objdiff::ObjDiffConfig {
-        build_config: BuildConfig::from(&state.config),
-        build_base: state.config.build_base,
-        build_target: state.config.build_target,
-        target_path: state
-            .config
-            .selected_obj
-            .as_ref()
-            .and_then(|obj| obj.target_path.as_ref())
-            .cloned(),
-        base_path: state
-            .config
-            .selected_obj
-            .as_ref()
-            .and_then(|obj| obj.base_path.as_ref())
-            .cloned(),
-        diff_obj_config: state.config.diff_obj_config.clone(),
-        symbol_mappings: state
-            .config
-            .selected_obj
-            .as_ref()
-            .map(|obj| &obj.symbol_mappings)
-            .cloned()
-            .unwrap_or_default(),
-        selecting_left: state.selecting_left.clone(),
-        selecting_right: state.selecting_right.clone(),
+    let selected_obj = state.config.selected_obj.as_ref();
+    let cross_unit = selected_obj.is_some_and(|obj| obj.cross_unit);
+    // The project's unit, with the state edited in the GUI since it was last saved
+    let unit = selected_obj.filter(|obj| !obj.cross_unit).and_then(|obj| {
+        let mut unit = state.objects.iter().find(|u| u.name() == obj.name)?.clone();
+        update_project_object(&mut unit, obj);
+        Some(unit)
+    });
+    let diff_obj_config = DiffObjConfig {
+        symbol_mappings: MappingConfig {
+            mappings: selected_obj.map(|obj| obj.symbol_mappings.clone()).unwrap_or_default(),
+            selecting_left: state.selecting_left.clone(),
+            selecting_right: state.selecting_right.clone(),
+            alignment_anchors: selected_obj
+                .and_then(|obj| state.config.alignment_anchors.get(&obj.name))
+                .cloned()
+                .unwrap_or_default(),
+            size_fixes: selected_obj
+                .and_then(|obj| state.config.size_fixes.get(&obj.name))
+                .cloned()
+                .unwrap_or_default(),
+            ..Default::default()
+        },
+        ..state.config.diff_obj_config.clone()
+    };
+    let mut config = DiffInputs {
+        project_dir: state.config.project_dir.as_deref(),
+        project: state.current_project_config.as_ref(),
+        unit: unit.as_ref(),
+        target_path: selected_obj.and_then(|obj| obj.target_path.clone()),
+        base_path: selected_obj.and_then(|obj| obj.base_path.clone()),
+        diff_obj_config: &diff_obj_config,
+    }
+    .objdiff_config();
+    // Build settings can be changed in the GUI
+    config.build_config = BuildConfig::from(&state.config);
+    config.build_base = state.config.build_base && !cross_unit;
+    config.build_target = state.config.build_target && !cross_unit;
+    config.build_cache = Some(state.build_cache.clone());
+    config.force_build = state.force_build;
+    config.obj_cache = Some(state.obj_cache.clone());
+    if let Some(session) = &state.session {
+        // Diff the session's objects exactly as they were diffed when exported
+        config.diff_obj_config = session.diff_obj_config.clone();
     }
+    config
 }
 
 pub fn start_build(ctx: &egui::Context, jobs: &mut JobQueue, config: objdiff::ObjDiffConfig) {
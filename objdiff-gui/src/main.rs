@@ -3,9 +3,14 @@
 mod app;
 mod app_config;
 mod config;
+mod deep_link;
+mod editor;
 mod fonts;
 mod hotkeys;
 mod jobs;
+mod notify;
+#[cfg(feature = "wgpu")]
+mod screenshot;
 mod update;
 mod views;
 
@@ -21,7 +26,10 @@ use cfg_if::cfg_if;
 use time::UtcOffset;
 use tracing_subscriber::EnvFilter;
 
-use crate::views::graphics::{load_graphics_config, GraphicsBackend, GraphicsConfig};
+use crate::{
+    deep_link::DeepLink,
+    views::graphics::{load_graphics_config, GraphicsBackend, GraphicsConfig},
+};
 
 fn load_icon() -> Result<egui::IconData> {
     use bytes::Buf;
@@ -37,6 +45,19 @@ fn load_icon() -> Result<egui::IconData> {
 
 const APP_NAME: &str = "objdiff";
 
+/// The project directory passed with `--project <dir>`, used when opening a recent project
+/// in a new window.
+#[cfg(not(target_arch = "wasm32"))]
+fn project_arg() -> Option<PathBuf> {
+    let mut args = std::env::args_os().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--project" {
+            return args.next().map(PathBuf::from);
+        }
+    }
+    None
+}
+
 // When compiling natively:
 #[cfg(not(target_arch = "wasm32"))]
 fn main() -> ExitCode {
@@ -52,12 +73,29 @@ fn main() -> ExitCode {
         )
         .init();
 
+    #[cfg(feature = "wgpu")]
+    if let Some(args) = screenshot::ScreenshotArgs::from_args(std::env::args().skip(1)) {
+        return match args.and_then(|args| screenshot::run(&args)) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                log::error!("Failed to take screenshot: {e:?}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
     // Because localtime_r is unsound in multithreaded apps,
     // we must call this before initializing eframe.
     // https://github.com/time-rs/time/issues/293
     let utc_offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
 
+    let deep_link = deep_link::link_arg();
+    if deep_link.as_ref().is_some_and(deep_link::forward_to_running_instance) {
+        return ExitCode::SUCCESS;
+    }
+
     let app_path = std::env::current_exe().ok();
+    let project_dir = project_arg();
     let exec_path: Rc<Mutex<Option<PathBuf>>> = Rc::new(Mutex::new(None));
     let mut native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_app_id(APP_NAME),
@@ -122,6 +160,8 @@ fn main() -> ExitCode {
         app_path.clone(),
         graphics_config.clone(),
         graphics_config_path.clone(),
+        project_dir.clone(),
+        deep_link.clone(),
     ) {
         eframe_error = Some(e);
     }
@@ -148,6 +188,8 @@ fn main() -> ExitCode {
                 app_path.clone(),
                 graphics_config.clone(),
                 graphics_config_path.clone(),
+                project_dir.clone(),
+                deep_link.clone(),
             ) {
                 eframe_error = Some(e);
             } else {
@@ -170,6 +212,8 @@ fn main() -> ExitCode {
             app_path,
             graphics_config,
             graphics_config_path,
+            project_dir,
+            deep_link,
         ) {
             eframe_error = Some(e);
         } else {
@@ -206,6 +250,7 @@ fn main() -> ExitCode {
     ExitCode::SUCCESS
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run_eframe(
     native_options: eframe::NativeOptions,
     utc_offset: UtcOffset,
@@ -213,6 +258,8 @@ fn run_eframe(
     app_path: Option<PathBuf>,
     graphics_config: GraphicsConfig,
     graphics_config_path: Option<PathBuf>,
+    project_dir: Option<PathBuf>,
+    deep_link: Option<DeepLink>,
 ) -> Result<(), eframe::Error> {
     eframe::run_native(
         APP_NAME,
@@ -225,6 +272,8 @@ fn run_eframe(
                 app_path,
                 graphics_config,
                 graphics_config_path,
+                project_dir,
+                deep_link,
             )))
         }),
     )
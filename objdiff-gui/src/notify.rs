@@ -0,0 +1,79 @@
+//! Desktop notifications, sent through the platform's own tools so that no extra
+//! dependencies are needed. Failures are logged and otherwise ignored.
+
+use std::process::Command;
+
+/// Shows a desktop notification, optionally with a sound. The command runs on a background
+/// thread so that a slow notification daemon doesn't stall the UI.
+pub fn send_notification(title: &str, body: &str, sound: bool) {
+    let commands = notification_commands(title, body, sound);
+    std::thread::spawn(move || {
+        for mut command in commands {
+            match command.status() {
+                Ok(status) if !status.success() => {
+                    log::warn!("Notification command {:?} failed: {status}", command.get_program())
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Failed to run notification command {:?}: {e}",
+                        command.get_program()
+                    )
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+#[cfg(target_os = "macos")]
+fn notification_commands(title: &str, body: &str, sound: bool) -> Vec<Command> {
+    // Passed as arguments rather than interpolated, so quotes in the text don't need escaping
+    let script = if sound {
+        "on run argv\ndisplay notification (item 2 of argv) with title (item 1 of argv) sound name \
+         \"Glass\"\nend run"
+    } else {
+        "on run argv\ndisplay notification (item 2 of argv) with title (item 1 of argv)\nend run"
+    };
+    let mut command = Command::new("osascript");
+    command.arg("-e").arg(script).arg(title).arg(body);
+    vec![command]
+}
+
+#[cfg(windows)]
+fn notification_commands(title: &str, body: &str, sound: bool) -> Vec<Command> {
+    use std::os::windows::process::CommandExt;
+    // Passed through the environment rather than interpolated into the script
+    let mut script = String::from(
+        "Add-Type -AssemblyName System.Windows.Forms; \
+         $icon = New-Object System.Windows.Forms.NotifyIcon; \
+         $icon.Icon = [System.Drawing.SystemIcons]::Information; \
+         $icon.Visible = $true; \
+         $icon.ShowBalloonTip(5000, $env:OBJDIFF_NOTIFY_TITLE, $env:OBJDIFF_NOTIFY_BODY, 'Info'); ",
+    );
+    if sound {
+        script.push_str("[System.Media.SystemSounds]::Asterisk.Play(); ");
+    }
+    // The balloon disappears along with its icon, so keep it around until it times out
+    script.push_str("Start-Sleep -Seconds 6; $icon.Dispose()");
+    let mut command = Command::new("powershell");
+    command
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .env("OBJDIFF_NOTIFY_TITLE", title)
+        .env("OBJDIFF_NOTIFY_BODY", body)
+        .creation_flags(winapi::um::winbase::CREATE_NO_WINDOW);
+    vec![command]
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn notification_commands(title: &str, body: &str, sound: bool) -> Vec<Command> {
+    let mut notify = Command::new("notify-send");
+    notify.args(["--app-name", "objdiff", title, body]);
+    let mut commands = vec![notify];
+    if sound {
+        // From libcanberra, available on most desktops
+        let mut play = Command::new("canberra-gtk-play");
+        play.args(["--id", "complete", "--description", "objdiff build finished"]);
+        commands.push(play);
+    }
+    commands
+}
@@ -0,0 +1,334 @@
+//! Headless rendering of the function diff view to a PNG, for documentation and bug reports.
+//!
+//! `objdiff --screenshot <unit>:<symbol> --out diff.png [--project <dir>] [--size <w>x<h>]
+//! [--scale <factor>] [--light]`
+//!
+//! The view is laid out at a fixed size with the default appearance and rendered offscreen
+//! with wgpu, so the output doesn't depend on the window size or saved settings.
+
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use eframe::egui_wgpu::{wgpu, Renderer, ScreenDescriptor};
+use objdiff_core::{
+    config::try_project_config,
+    diff::DiffObjConfig,
+    jobs::objdiff::{run_build_blocking, ObjDiffResult},
+    obj::{ObjInfo, SymbolRef},
+    session::DiffInputs,
+};
+
+use crate::views::{
+    appearance::Appearance,
+    function_diff::function_diff_ui,
+    symbol_diff::{DiffViewState, SymbolRefByName, View},
+};
+
+const DEFAULT_SIZE: [u32; 2] = [1600, 900];
+/// Frames to run before capturing, so that fonts are loaded and layout has settled.
+const WARMUP_FRAMES: usize = 3;
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+pub struct ScreenshotArgs {
+    pub unit: String,
+    pub symbol: String,
+    pub out: PathBuf,
+    pub project: Option<PathBuf>,
+    /// Output size in pixels
+    pub size: [u32; 2],
+    pub scale: f32,
+    pub light: bool,
+}
+
+impl ScreenshotArgs {
+    /// Parses screenshot mode arguments. Returns `None` if `--screenshot` wasn't passed.
+    pub fn from_args(args: impl Iterator<Item = String>) -> Option<Result<Self>> {
+        let args = args.collect::<Vec<_>>();
+        if !args.iter().any(|a| a == "--screenshot") {
+            return None;
+        }
+        Some(Self::parse(&args))
+    }
+
+    fn parse(args: &[String]) -> Result<Self> {
+        let mut target = None;
+        let mut out = None;
+        let mut project = None;
+        let mut size = DEFAULT_SIZE;
+        let mut scale = 1.0;
+        let mut light = false;
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let mut value = || iter.next().ok_or_else(|| anyhow!("Missing value for {arg}"));
+            match arg.as_str() {
+                "--screenshot" => target = Some(value()?.clone()),
+                "--out" => out = Some(PathBuf::from(value()?)),
+                "--project" => project = Some(PathBuf::from(value()?)),
+                "--size" => {
+                    let value = value()?;
+                    let (w, h) = value
+                        .split_once('x')
+                        .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                        .ok_or_else(|| anyhow!("Invalid size {value}, expected <w>x<h>"))?;
+                    ensure!(w > 0 && h > 0, "Invalid size {value}");
+                    size = [w, h];
+                }
+                "--scale" => {
+                    scale = value()?.parse().context("Invalid scale")?;
+                    ensure!(scale > 0.0, "Invalid scale {scale}");
+                }
+                "--light" => light = true,
+                _ => bail!("Unknown argument {arg}"),
+            }
+        }
+        let target = target.ok_or_else(|| anyhow!("Missing --screenshot <unit>:<symbol>"))?;
+        let Some((unit, symbol)) = target.split_once(':') else {
+            bail!("Invalid screenshot target {target}, expected <unit>:<symbol>");
+        };
+        let out = out.ok_or_else(|| anyhow!("Missing --out <path>"))?;
+        Ok(Self {
+            unit: unit.to_string(),
+            symbol: symbol.to_string(),
+            out,
+            project,
+            size,
+            scale,
+            light,
+        })
+    }
+}
+
+/// Loads and diffs a project unit with the same settings as a build, without building it.
+fn load_unit(args: &ScreenshotArgs) -> Result<Box<ObjDiffResult>> {
+    let project_dir = match &args.project {
+        Some(dir) => dir.clone(),
+        None => std::env::current_dir().context("Failed to get the current directory")?,
+    };
+    let Some((config, info)) = try_project_config(&project_dir) else {
+        bail!("Project config not found in {}", project_dir.display());
+    };
+    let config = config
+        .with_context(|| format!("Reading project config {}", info.path.display()))?
+        .for_version(None)?;
+    let mut unit = config
+        .units()
+        .iter()
+        .find(|u| u.name() == args.unit)
+        .cloned()
+        .ok_or_else(|| anyhow!("Unit not found: {}", args.unit))?;
+    unit.resolve_paths(&project_dir, config.target_dir.as_deref(), config.base_dir.as_deref());
+    let mut diff_config = DiffInputs {
+        project_dir: Some(&project_dir),
+        project: Some(&config),
+        unit: Some(&unit),
+        target_path: unit.target_path.clone(),
+        base_path: unit.base_path.clone(),
+        diff_obj_config: &DiffObjConfig::default(),
+    }
+    .objdiff_config();
+    // Render the objects as they are, without building
+    diff_config.build_base = false;
+    diff_config.build_target = false;
+    run_build_blocking(diff_config)
+}
+
+fn find_symbol_ref(obj: &ObjInfo, name: &str) -> Option<SymbolRef> {
+    obj.sections.iter().enumerate().find_map(|(section_idx, section)| {
+        section
+            .symbols
+            .iter()
+            .position(|s| s.name == name || s.demangled_name.as_deref() == Some(name))
+            .map(|symbol_idx| SymbolRef { section_idx, symbol_idx })
+    })
+}
+
+fn symbol_ref_by_name(obj: &ObjInfo, symbol_ref: SymbolRef) -> SymbolRefByName {
+    let (section, symbol) = obj.section_symbol(symbol_ref);
+    SymbolRefByName::new(symbol, section)
+}
+
+/// Selects the symbol on both sides, starting from whichever side contains it.
+fn select_symbol(state: &mut DiffViewState, name: &str) -> Result<()> {
+    let Some(result) = &state.build else { bail!("No diff result") };
+    let sides = [(&result.first_obj, &result.second_obj), (&result.second_obj, &result.first_obj)];
+    for (column, (this, other)) in sides.into_iter().enumerate() {
+        let Some((obj, diff)) = this else { continue };
+        let Some(symbol_ref) = find_symbol_ref(obj, name) else { continue };
+        let selected = Some(symbol_ref_by_name(obj, symbol_ref));
+        let matched = diff
+            .symbol_diff(symbol_ref)
+            .target_symbol
+            .zip(other.as_ref())
+            .map(|(target_ref, (other_obj, _))| symbol_ref_by_name(other_obj, target_ref));
+        let (left, right) = if column == 0 { (selected, matched) } else { (matched, selected) };
+        state.symbol_state.left_symbol = left;
+        state.symbol_state.right_symbol = right;
+        return Ok(());
+    }
+    bail!("Symbol not found: {name}")
+}
+
+/// Runs the UI for a few frames at a fixed size and renders the last one to RGBA pixels.
+fn render(
+    args: &ScreenshotArgs,
+    mut run_ui: impl FnMut(&egui::Context, &Appearance),
+) -> Result<Vec<u8>> {
+    let [width, height] = args.size;
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+    let adapter =
+        pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+            .ok_or_else(|| anyhow!("No suitable graphics adapter found"))?;
+    let (device, queue) =
+        pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default(), None))
+            .context("Failed to create graphics device")?;
+    let mut renderer = Renderer::new(&device, FORMAT, None, 1, false);
+
+    let ctx = egui::Context::default();
+    let mut appearance = Appearance::default();
+    if args.light {
+        appearance.theme = egui::Theme::Light;
+    }
+    appearance.init_fonts(&ctx);
+    ctx.style_mut(|style| style.animation_time = 0.0);
+
+    let mut raw_input = egui::RawInput {
+        screen_rect: Some(egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::vec2(width as f32, height as f32) / args.scale,
+        )),
+        ..Default::default()
+    };
+    raw_input.viewports.entry(egui::ViewportId::ROOT).or_default().native_pixels_per_point =
+        Some(args.scale);
+
+    // Textures (e.g. the font atlas) are created across frames, so apply every delta
+    let mut output = None;
+    for _ in 0..WARMUP_FRAMES {
+        appearance.pre_update(&ctx);
+        let full_output = ctx.run(raw_input.clone(), |ctx| run_ui(ctx, &appearance));
+        for (id, delta) in &full_output.textures_delta.set {
+            renderer.update_texture(&device, &queue, *id, delta);
+        }
+        for id in &full_output.textures_delta.free {
+            renderer.free_texture(id);
+        }
+        appearance.post_update(&ctx);
+        output = Some(full_output);
+    }
+    let output = output.unwrap();
+    let clipped = ctx.tessellate(output.shapes, output.pixels_per_point);
+    let screen = ScreenDescriptor {
+        size_in_pixels: [width, height],
+        pixels_per_point: output.pixels_per_point,
+    };
+
+    let size = wgpu::Extent3d { width, height, depth_or_array_layers: 1 };
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("screenshot"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    // Rows of the readback buffer must be aligned
+    let bytes_per_row = (width * 4).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("screenshot"),
+        size: bytes_per_row as u64 * height as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    let command_buffers = renderer.update_buffers(&device, &queue, &mut encoder, &clipped, &screen);
+    let [r, g, b, a] = ctx.style().visuals.panel_fill.to_normalized_gamma_f32();
+    {
+        let mut pass = encoder
+            .begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("screenshot"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            r: r as f64,
+                            g: g as f64,
+                            b: b as f64,
+                            a: a as f64,
+                        }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            })
+            .forget_lifetime();
+        renderer.render(&mut pass, &clipped, &screen);
+    }
+    encoder.copy_texture_to_buffer(
+        wgpu::ImageCopyTexture {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::ImageCopyBuffer {
+            buffer: &buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: Some(height),
+            },
+        },
+        size,
+    );
+    queue.submit(command_buffers.into_iter().chain([encoder.finish()]));
+
+    let slice = buffer.slice(..);
+    let (tx, rx) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = tx.send(result);
+    });
+    device.poll(wgpu::Maintain::Wait);
+    rx.recv()?.context("Failed to read back screenshot")?;
+    let mapped = slice.get_mapped_range();
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+    for row in mapped.chunks(bytes_per_row as usize) {
+        pixels.extend_from_slice(&row[..(width * 4) as usize]);
+    }
+    Ok(pixels)
+}
+
+fn write_png(args: &ScreenshotArgs, pixels: &[u8]) -> Result<()> {
+    let file = File::create(&args.out)
+        .with_context(|| format!("Failed to create {}", args.out.display()))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), args.size[0], args.size[1]);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(pixels)?;
+    Ok(())
+}
+
+pub fn run(args: &ScreenshotArgs) -> Result<()> {
+    let result = load_unit(args)?;
+    let mut state = DiffViewState {
+        build: Some(result),
+        current_view: View::FunctionDiff,
+        object_name: args.unit.clone(),
+        ..Default::default()
+    };
+    select_symbol(&mut state, &args.symbol)?;
+    let pixels = render(args, |ctx, appearance| {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let _ = function_diff_ui(ui, &state, appearance);
+        });
+    })?;
+    write_png(args, &pixels)?;
+    log::info!("Wrote {}", args.out.display());
+    Ok(())
+}
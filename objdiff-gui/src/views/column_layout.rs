@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use egui::{Align, Layout, Sense, Vec2};
 use egui_extras::{Column, Size, StripBuilder, TableBuilder, TableRow};
 
@@ -28,18 +30,20 @@ pub fn render_header(
     ui.separator();
 }
 
+/// Renders a table of rows, returning the range of rows that were visible.
 pub fn render_table(
     ui: &mut egui::Ui,
     available_width: f32,
     num_columns: usize,
     row_height: f32,
     total_rows: usize,
+    scroll_to_row: Option<usize>,
     mut add_contents: impl FnMut(&mut TableRow, usize),
-) {
+) -> Range<usize> {
     ui.style_mut().interaction.selectable_labels = false;
     let column_width = available_width / num_columns as f32;
     let available_height = ui.available_height();
-    let table = TableBuilder::new(ui)
+    let mut table = TableBuilder::new(ui)
         .striped(false)
         .cell_layout(Layout::left_to_right(Align::Min))
         .columns(Column::exact(column_width).clip(true), num_columns)
@@ -47,14 +51,22 @@ pub fn render_table(
         .auto_shrink([false, false])
         .min_scrolled_height(available_height)
         .sense(Sense::click());
+    if let Some(row) = scroll_to_row {
+        table = table.scroll_to_row(row, Some(Align::Center));
+    }
+    let mut visible_rows: Option<Range<usize>> = None;
     table.body(|body| {
         body.rows(row_height, total_rows, |mut row| {
+            let index = row.index();
+            let range = visible_rows.get_or_insert(index..index);
+            range.end = index + 1;
             row.set_hovered(false); // Disable hover effect
             for i in 0..num_columns {
                 add_contents(&mut row, i);
             }
         });
     });
+    visible_rows.unwrap_or_default()
 }
 
 pub fn render_strips(
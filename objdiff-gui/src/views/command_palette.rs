@@ -0,0 +1,267 @@
+use egui::{Align2, Key, Modifiers, RichText, ScrollArea, TextEdit, TextStyle};
+use objdiff_core::diff::{properties::ConfigProperty, DiffObjConfig};
+
+use crate::{app::AppState, hotkeys, views::appearance::Appearance};
+
+/// A diff option that can be toggled from the menu bar or the command palette.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DiffOption {
+    RelaxRelocDiffs,
+    SpaceBetweenArgs,
+    CombineDataSections,
+    IgnoreCodegenQuirks,
+}
+
+impl DiffOption {
+    pub const ALL: [DiffOption; 4] = [
+        DiffOption::RelaxRelocDiffs,
+        DiffOption::SpaceBetweenArgs,
+        DiffOption::CombineDataSections,
+        DiffOption::IgnoreCodegenQuirks,
+    ];
+
+    pub fn property(self) -> ConfigProperty {
+        match self {
+            DiffOption::RelaxRelocDiffs => ConfigProperty::RelaxRelocDiffs,
+            DiffOption::SpaceBetweenArgs => ConfigProperty::SpaceBetweenArgs,
+            DiffOption::CombineDataSections => ConfigProperty::CombineDataSections,
+            DiffOption::IgnoreCodegenQuirks => ConfigProperty::IgnoreCodegenQuirks,
+        }
+    }
+
+    pub fn label(self) -> &'static str { self.property().name() }
+
+    pub fn description(self) -> &'static str { self.property().description() }
+
+    pub fn value(self, config: &DiffObjConfig) -> bool {
+        match self {
+            DiffOption::RelaxRelocDiffs => config.relax_reloc_diffs,
+            DiffOption::SpaceBetweenArgs => config.space_between_args,
+            DiffOption::CombineDataSections => config.combine_data_sections,
+            DiffOption::IgnoreCodegenQuirks => config.ignore_codegen_quirks,
+        }
+    }
+
+    pub fn value_mut(self, config: &mut DiffObjConfig) -> &mut bool {
+        match self {
+            DiffOption::RelaxRelocDiffs => &mut config.relax_reloc_diffs,
+            DiffOption::SpaceBetweenArgs => &mut config.space_between_args,
+            DiffOption::CombineDataSections => &mut config.combine_data_sections,
+            DiffOption::IgnoreCodegenQuirks => &mut config.ignore_codegen_quirks,
+        }
+    }
+}
+
+/// A toggleable window.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum AppWindow {
+    Project,
+    Appearance,
+    Graphics,
+    Demangle,
+    RlwinmDecoder,
+    ByteSearch,
+    SymbolLookup,
+    ReportChanges,
+    LoadDetails,
+    ArchSettings,
+    Jobs,
+    Terminal,
+}
+
+impl AppWindow {
+    pub const ALL: [AppWindow; 12] = [
+        AppWindow::Project,
+        AppWindow::Appearance,
+        AppWindow::Graphics,
+        AppWindow::Demangle,
+        AppWindow::RlwinmDecoder,
+        AppWindow::ByteSearch,
+        AppWindow::SymbolLookup,
+        AppWindow::ReportChanges,
+        AppWindow::LoadDetails,
+        AppWindow::ArchSettings,
+        AppWindow::Jobs,
+        AppWindow::Terminal,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AppWindow::Project => "Project settings",
+            AppWindow::Appearance => "Appearance",
+            AppWindow::Graphics => "Graphics",
+            AppWindow::Demangle => "Demangle",
+            AppWindow::RlwinmDecoder => "Rlwinm decoder",
+            AppWindow::ByteSearch => "Byte search",
+            AppWindow::SymbolLookup => "Symbol lookup",
+            AppWindow::ReportChanges => "Report changes",
+            AppWindow::LoadDetails => "Load details",
+            AppWindow::ArchSettings => "Diff settings",
+            AppWindow::Jobs => "Jobs",
+            AppWindow::Terminal => "Terminal",
+        }
+    }
+}
+
+/// An action that can be run from the command palette.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Command {
+    Build,
+    ForceBuild,
+    SelectUnit(String),
+    ToggleDiffOption(DiffOption),
+    ToggleRebuildOnChanges,
+    ToggleWindow(AppWindow),
+    ToggleSidePanel,
+    ClearSymbolMappings,
+}
+
+impl Command {
+    fn label(&self, state: &AppState) -> String {
+        let on_off = |v: bool| if v { "on" } else { "off" };
+        match self {
+            Command::Build => "Build".to_string(),
+            Command::ForceBuild => "Force rebuild".to_string(),
+            Command::SelectUnit(name) => format!("Open unit: {name}"),
+            Command::ToggleDiffOption(option) => {
+                let value = option.value(&state.config.diff_obj_config);
+                format!("Toggle option: {} ({})", option.label(), on_off(value))
+            }
+            Command::ToggleRebuildOnChanges => format!(
+                "Toggle option: Rebuild on changes ({})",
+                on_off(state.config.rebuild_on_changes)
+            ),
+            Command::ToggleWindow(window) => format!("Show window: {}", window.label()),
+            Command::ToggleSidePanel => "Toggle side panel".to_string(),
+            Command::ClearSymbolMappings => "Clear custom symbol mappings".to_string(),
+        }
+    }
+}
+
+/// All commands available for the current state.
+fn all_commands(state: &AppState) -> Vec<Command> {
+    let mut commands = Vec::new();
+    if state.config.selected_obj.is_some() {
+        commands.push(Command::Build);
+        commands.push(Command::ForceBuild);
+    }
+    commands.extend(DiffOption::ALL.iter().map(|&o| Command::ToggleDiffOption(o)));
+    commands.push(Command::ToggleRebuildOnChanges);
+    commands.extend(AppWindow::ALL.iter().map(|&w| Command::ToggleWindow(w)));
+    commands.push(Command::ToggleSidePanel);
+    commands.push(Command::ClearSymbolMappings);
+    commands.extend(
+        state
+            .objects
+            .iter()
+            .filter(|o| !o.hidden())
+            .map(|o| Command::SelectUnit(o.name().to_string())),
+    );
+    commands
+}
+
+/// Scores a fuzzy (subsequence) match of the query against the text. Higher is better.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let mut score = 0;
+    let mut text_chars = text.chars().flat_map(char::to_lowercase).enumerate();
+    let mut last_match = None;
+    for q in query.chars().flat_map(char::to_lowercase).filter(|c| !c.is_whitespace()) {
+        let (idx, _) = text_chars.by_ref().find(|(_, c)| *c == q)?;
+        score += match last_match {
+            // Reward consecutive matches
+            Some(last) if idx == last + 1 => 5,
+            _ => 1,
+        };
+        last_match = Some(idx);
+    }
+    // Prefer shorter candidates
+    Some(score * 100 - text.len() as i32)
+}
+
+#[derive(Default)]
+pub struct CommandPaletteState {
+    pub open: bool,
+    query: String,
+    selected: usize,
+}
+
+/// Shows the command palette, returning the command chosen by the user.
+#[must_use]
+pub fn command_palette_window(
+    ctx: &egui::Context,
+    palette: &mut CommandPaletteState,
+    state: &AppState,
+    appearance: &Appearance,
+) -> Option<Command> {
+    if hotkeys::consume_command_palette_shortcut(ctx) {
+        palette.open = !palette.open;
+        palette.query.clear();
+        palette.selected = 0;
+    }
+    if !palette.open {
+        return None;
+    }
+    let mut matches = all_commands(state)
+        .into_iter()
+        .map(|c| (c.label(state), c))
+        .filter_map(|(label, c)| fuzzy_score(&palette.query, &label).map(|s| (s, label, c)))
+        .collect::<Vec<_>>();
+    if !palette.query.is_empty() {
+        matches.sort_by(|a, b| b.0.cmp(&a.0));
+    }
+    let mut result = None;
+    let mut close = false;
+    egui::Window::new("Command Palette")
+        .title_bar(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_TOP, [0.0, 40.0])
+        .fixed_size([480.0, 320.0])
+        .show(ctx, |ui| {
+            let response = ui.add(
+                TextEdit::singleline(&mut palette.query)
+                    .hint_text("Type a command")
+                    .desired_width(f32::INFINITY),
+            );
+            response.request_focus();
+            if response.changed() {
+                palette.selected = 0;
+            }
+            ui.input_mut(|i| {
+                if i.consume_key(Modifiers::NONE, Key::ArrowDown) {
+                    palette.selected = (palette.selected + 1).min(matches.len().saturating_sub(1));
+                }
+                if i.consume_key(Modifiers::NONE, Key::ArrowUp) {
+                    palette.selected = palette.selected.saturating_sub(1);
+                }
+                if i.consume_key(Modifiers::NONE, Key::Escape) {
+                    close = true;
+                }
+                if i.consume_key(Modifiers::NONE, Key::Enter) {
+                    result = matches.get(palette.selected).map(|(_, _, c)| c.clone());
+                }
+            });
+            ui.separator();
+            ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                ui.style_mut().override_text_style = Some(TextStyle::Monospace);
+                for (i, (_, label, command)) in matches.iter().enumerate() {
+                    let selected = i == palette.selected;
+                    let text = RichText::new(label).color(if selected {
+                        appearance.highlight_color
+                    } else {
+                        appearance.text_color
+                    });
+                    let response = ui.selectable_label(selected, text);
+                    if selected {
+                        response.scroll_to_me(None);
+                    }
+                    if response.clicked() {
+                        result = Some(command.clone());
+                    }
+                }
+            });
+        });
+    if result.is_some() || close {
+        palette.open = false;
+    }
+    result
+}
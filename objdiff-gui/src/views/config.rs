@@ -13,15 +13,23 @@ use egui::{
 };
 use globset::Glob;
 use objdiff_core::{
-    config::{ProjectObject, DEFAULT_WATCH_PATTERNS},
-    diff::{ArmArchVersion, ArmR9Usage, MipsAbi, MipsInstrCategory, X86Formatter},
+    config::{
+        claims::{Claims, UnitClaim},
+        match_history::unix_now,
+        ProjectObject, WatchScope, DEFAULT_WATCH_PATTERNS,
+    },
+    diff::{
+        properties::{ConfigProperty, ConfigPropertyValue},
+        DiffObjConfig,
+    },
     jobs::{check_update::CheckUpdateResult, Job, JobQueue, JobResult},
 };
-use strum::{EnumMessage, VariantArray};
 
 use crate::{
     app::{AppConfig, AppState, AppStateRef, ObjectConfig},
     config::ProjectObjectNode,
+    deep_link::{register_handler, DeepLink},
+    editor::EDITOR_PRESETS,
     hotkeys,
     jobs::{start_check_update, start_update},
     update::RELEASE_URL,
@@ -42,6 +50,7 @@ pub struct ConfigViewState {
     pub queue_build: bool,
     pub watch_pattern_text: String,
     pub object_search: String,
+    pub settings_search: String,
     pub filter_diffable: bool,
     pub filter_incomplete: bool,
     pub show_hidden: bool,
@@ -66,11 +75,32 @@ impl ConfigViewState {
 
         // Check async file dialog results
         match self.file_dialog_state.poll() {
-            FileDialogResult::None => {}
+            FileDialogResult::None
+            | FileDialogResult::ImportMappings(_)
+            | FileDialogResult::ExportMappings(_)
+            | FileDialogResult::PreviousReport(_)
+            | FileDialogResult::CurrentReport(_)
+            | FileDialogResult::ExportPrintableReport(_)
+            | FileDialogResult::ExportSymbolOrder(_)
+            | FileDialogResult::ExportSession(_) => {}
+            FileDialogResult::ExportUsageStats(path) => {
+                let mut guard = state.write().unwrap();
+                if let Err(e) = guard.export_usage_stats(&path) {
+                    log::error!("Failed to export usage statistics: {e:#}");
+                    guard.config_error = Some(format!("{e:#}"));
+                }
+            }
             FileDialogResult::ProjectDir(path) => {
                 let mut guard = state.write().unwrap();
                 guard.set_project_dir(path.to_path_buf());
             }
+            FileDialogResult::OpenSession(path) => {
+                let mut guard = state.write().unwrap();
+                if let Err(e) = guard.open_session(&path) {
+                    log::error!("Failed to open session: {e:#}");
+                    guard.config_error = Some(format!("{e:#}"));
+                }
+            }
             FileDialogResult::TargetDir(path) => {
                 let mut guard = state.write().unwrap();
                 guard.set_target_obj_dir(path.to_path_buf());
@@ -166,13 +196,26 @@ pub fn config_ui(
     appearance: &Appearance,
 ) {
     let mut state_guard = state.write().unwrap();
+    let match_stats = state_guard.match_stats().filter(|stats| stats.total_functions > 0);
+    state_guard.load_claims();
     let AppState {
         config:
             AppConfig {
-                project_dir, target_obj_dir, base_obj_dir, selected_obj, auto_update_check, ..
+                project_dir,
+                target_obj_dir,
+                base_obj_dir,
+                selected_obj,
+                auto_update_check,
+                selected_version,
+                usage_stats,
+                claim_name,
+                ..
             },
         objects,
         object_nodes,
+        current_project_config,
+        config_change,
+        claims,
         ..
     } = &mut *state_guard;
 
@@ -220,11 +263,70 @@ pub fn config_ui(
             *show_config_window = true;
         }
     });
+    if let Some(stats) = match_stats {
+        ui.label(format!(
+            "Matched this week: {} functions ({} bytes)",
+            stats.week_functions, stats.week_code
+        ))
+        .on_hover_text(format!(
+            "{:.0} bytes matched per day on average\n{} functions matched in total",
+            stats.average_code_per_day, stats.total_functions
+        ));
+    }
+    if project_dir.is_some() {
+        ui.horizontal(|ui| {
+            ui.checkbox(usage_stats, "Record usage statistics").on_hover_text(
+                "Record builds and time spent per unit in .objdiff/usage_stats.json in the \
+                project directory. Nothing is sent anywhere.",
+            );
+            if ui
+                .button("Export…")
+                .on_hover_text("Export usage statistics and functions matched per day to JSON")
+                .clicked()
+            {
+                config_state.file_dialog_state.queue(
+                    || {
+                        Box::pin(
+                            rfd::AsyncFileDialog::new()
+                                .set_file_name("usage_stats.json")
+                                .add_filter("JSON", &["json"])
+                                .save_file(),
+                        )
+                    },
+                    FileDialogResult::ExportUsageStats,
+                );
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Claim name:");
+            egui::TextEdit::singleline(claim_name)
+                .desired_width(120.0)
+                .ui(ui)
+                .on_hover_text("Your name, shown to others on units you claim");
+        });
+    }
+
+    if let Some(versions) =
+        current_project_config.as_ref().map(|c| c.versions()).filter(|v| !v.is_empty())
+    {
+        let current = selected_version.clone().unwrap_or_else(|| versions[0].clone());
+        egui::ComboBox::from_label("Version").selected_text(&current).show_ui(ui, |ui| {
+            for version in versions {
+                if ui.selectable_label(*version == current, version).clicked()
+                    && *version != current
+                {
+                    *selected_version = Some(version.clone());
+                    *config_change = true;
+                }
+            }
+        });
+    }
 
     let selected_index = selected_obj.as_ref().and_then(|selected_obj| {
         objects.iter().position(|obj| obj.name.as_ref() == Some(&selected_obj.name))
     });
     let mut new_selected_index = selected_index;
+    let mut claims_ui = ClaimsUi { claims: claims.as_ref(), name: claim_name.trim(), action: None };
     if objects.is_empty() {
         if let (Some(_base_dir), Some(target_dir)) = (base_obj_dir, target_obj_dir) {
             if ui.button("Select object").clicked() {
@@ -330,10 +432,12 @@ pub fn config_ui(
                     &node,
                     appearance,
                     node_open,
+                    &mut claims_ui,
                 );
             }
         });
     }
+    let claim_action = claims_ui.action;
     if new_selected_index != selected_index {
         if let Some(idx) = new_selected_index {
             // Will set obj_changed, which will trigger a rebuild
@@ -341,13 +445,53 @@ pub fn config_ui(
             state_guard.set_selected_obj(config);
         }
     }
+    if let Some((unit, claimed)) = claim_action {
+        state_guard.set_unit_claimed(&unit, claimed);
+    }
     if state_guard.config.selected_obj.is_some()
-        && ui.add_enabled(!config_state.build_running, egui::Button::new("Build")).clicked()
+        && ui
+            .add_enabled(!config_state.build_running, egui::Button::new("Build"))
+            .on_hover_text_at_pointer("Shift+click to force a rebuild")
+            .clicked()
     {
         config_state.queue_build = true;
+        if ui.input(|i| i.modifiers.shift) {
+            state_guard.force_build = true;
+        }
     }
 }
 
+/// Unit claims shown in the object list, and the claim change requested from a unit's context
+/// menu.
+struct ClaimsUi<'a> {
+    claims: Option<&'a Claims>,
+    /// The user's claim name
+    name: &'a str,
+    /// Unit name and whether to claim or release it
+    action: Option<(String, bool)>,
+}
+
+fn format_claim_age(seconds: u64) -> String {
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} minutes ago", seconds / 60),
+        3600..=86399 => format!("{} hours ago", seconds / 3600),
+        _ => format!("{} days ago", seconds / 86400),
+    }
+}
+
+fn claim_badge_ui(ui: &mut egui::Ui, claim: &UnitClaim, own: bool, appearance: &Appearance) {
+    let color = if own { appearance.insert_color } else { appearance.replace_color };
+    ui.label(RichText::new(format!("⚑ {}", claim.name)).small().color(color)).on_hover_text(
+        format!(
+            "Claimed by {} {}",
+            claim.name,
+            format_claim_age(unix_now().saturating_sub(claim.claimed_at))
+        ),
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
 fn display_unit(
     ui: &mut egui::Ui,
     selected_obj: &mut Option<usize>,
@@ -356,6 +500,7 @@ fn display_unit(
     units: &[ProjectObject],
     index: usize,
     appearance: &Appearance,
+    claims_ui: &mut ClaimsUi,
 ) {
     let object = &units[index];
     let selected = *selected_obj == Some(index);
@@ -370,18 +515,31 @@ fn display_unit(
     } else {
         appearance.text_color
     };
-    let response = SelectableLabel::new(
-        selected,
-        RichText::new(name)
-            .font(FontId {
-                size: appearance.ui_font.size,
-                family: appearance.code_font.family.clone(),
-            })
-            .color(color),
-    )
-    .ui(ui);
-    if get_source_path(project_dir, object).is_some() {
-        response.context_menu(|ui| object_context_ui(ui, object, project_dir));
+    let claim = claims_ui.claims.and_then(|c| c.get(object.name()));
+    let own_claim = claim.is_some_and(|c| c.name == claims_ui.name);
+    let response = ui
+        .horizontal(|ui| {
+            let response = SelectableLabel::new(
+                selected,
+                RichText::new(name)
+                    .font(FontId {
+                        size: appearance.ui_font.size,
+                        family: appearance.code_font.family.clone(),
+                    })
+                    .color(color),
+            )
+            .ui(ui);
+            if let Some(claim) = claim {
+                claim_badge_ui(ui, claim, own_claim, appearance);
+            }
+            response
+        })
+        .inner;
+    if project_dir.is_some() || claims_ui.claims.is_some() {
+        response.context_menu(|ui| {
+            object_context_ui(ui, object, project_dir);
+            claim_context_ui(ui, object, claim, own_claim, claims_ui);
+        });
     }
     if response.clicked() {
         *selected_obj = Some(index);
@@ -406,6 +564,44 @@ fn object_context_ui(ui: &mut egui::Ui, object: &ProjectObject, project_dir: Opt
             ui.close_menu();
         }
     }
+    if project_dir.is_some()
+        && ui
+            .button("Copy link")
+            .on_hover_text("Copy an objdiff:// link that opens this unit")
+            .clicked()
+    {
+        let link = DeepLink::new(project_dir, object.name());
+        ui.output_mut(|output| output.copied_text = link.to_string());
+        ui.close_menu();
+    }
+}
+
+fn claim_context_ui(
+    ui: &mut egui::Ui,
+    object: &ProjectObject,
+    claim: Option<&UnitClaim>,
+    own_claim: bool,
+    claims_ui: &mut ClaimsUi,
+) {
+    if claims_ui.claims.is_none() {
+        return;
+    }
+    if own_claim {
+        if ui.button("Release claim").clicked() {
+            claims_ui.action = Some((object.name().to_string(), false));
+            ui.close_menu();
+        }
+        return;
+    }
+    let label = if claim.is_some() { "Take over claim" } else { "Claim" };
+    let response = ui
+        .add_enabled(!claims_ui.name.is_empty(), egui::Button::new(label))
+        .on_hover_text("Mark this unit as being worked on by you")
+        .on_disabled_hover_text("Set a claim name under Project first");
+    if response.clicked() {
+        claims_ui.action = Some((object.name().to_string(), true));
+        ui.close_menu();
+    }
 }
 
 #[derive(Default, Copy, Clone, PartialEq, Eq, Debug)]
@@ -417,6 +613,7 @@ enum NodeOpen {
     Object,
 }
 
+#[allow(clippy::too_many_arguments)]
 fn display_node(
     ui: &mut egui::Ui,
     selected_obj: &mut Option<usize>,
@@ -425,10 +622,11 @@ fn display_node(
     node: &ProjectObjectNode,
     appearance: &Appearance,
     node_open: NodeOpen,
+    claims_ui: &mut ClaimsUi,
 ) {
     match node {
         ProjectObjectNode::Unit(name, idx) => {
-            display_unit(ui, selected_obj, project_dir, name, units, *idx, appearance);
+            display_unit(ui, selected_obj, project_dir, name, units, *idx, appearance, claims_ui);
         }
         ProjectObjectNode::Dir(name, children) => {
             let contains_obj = selected_obj.map(|idx| contains_node(node, idx));
@@ -454,7 +652,16 @@ fn display_node(
             .open(open)
             .show(ui, |ui| {
                 for node in children {
-                    display_node(ui, selected_obj, project_dir, units, node, appearance, node_open);
+                    display_node(
+                        ui,
+                        selected_obj,
+                        project_dir,
+                        units,
+                        node,
+                        appearance,
+                        node_open,
+                        claims_ui,
+                    );
                 }
             });
         }
@@ -808,6 +1015,43 @@ fn split_obj_config_ui(
     if response.changed() {
         state.watcher_change = true;
     };
+    ui.horizontal(|ui| {
+        ui.label("Debounce:");
+        if ui
+            .add(
+                egui::DragValue::new(&mut state.config.watch_debounce_ms)
+                    .range(0..=10000)
+                    .speed(10)
+                    .suffix(" ms"),
+            )
+            .on_hover_text("Time to wait for changes to settle before rebuilding")
+            .changed()
+        {
+            state.watcher_change = true;
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label("Rebuild for:");
+        let previous = state.config.watch_scope;
+        egui::ComboBox::from_id_salt("watch_scope")
+            .selected_text(watch_scope_label(state.config.watch_scope))
+            .show_ui(ui, |ui| {
+                for scope in [WatchScope::All, WatchScope::SelectedUnit] {
+                    ui.selectable_value(
+                        &mut state.config.watch_scope,
+                        scope,
+                        watch_scope_label(scope),
+                    );
+                }
+            })
+            .response
+            .on_hover_text(
+                "Whether changes to the source files of other units rebuild the selected unit",
+            );
+        if state.config.watch_scope != previous {
+            state.watcher_change = true;
+        }
+    });
 
     ui.horizontal(|ui| {
         ui.label(RichText::new("File patterns").color(appearance.text_color));
@@ -860,135 +1104,200 @@ fn split_obj_config_ui(
             }
         }
     });
+
+    subheading(ui, "Notifications", appearance);
+    ui.checkbox(&mut state.config.build_notifications, "Notify when a build finishes")
+        .on_hover_text(
+            "Send a desktop notification with the result of a build & diff that finishes while \
+            objdiff isn't focused",
+        );
+    ui.add_enabled_ui(state.config.build_notifications, |ui| {
+        ui.checkbox(&mut state.config.build_notification_sound, "Play a sound");
+        ui.horizontal(|ui| {
+            ui.label("Only for builds longer than:");
+            ui.add(
+                egui::DragValue::new(&mut state.config.build_notification_min_secs)
+                    .range(0..=3600)
+                    .suffix(" s"),
+            );
+        });
+    });
+
+    subheading(ui, "Editor", appearance);
+    ui.horizontal(|ui| {
+        ui.label("Open source files with:");
+        egui::ComboBox::from_id_salt("editor_preset")
+            .selected_text(
+                EDITOR_PRESETS
+                    .iter()
+                    .find(|(_, template)| *template == state.config.editor_command)
+                    .map_or(
+                        if state.config.editor_command.is_empty() {
+                            "Default application"
+                        } else {
+                            "Custom"
+                        },
+                        |(name, _)| *name,
+                    ),
+            )
+            .show_ui(ui, |ui| {
+                ui.selectable_value(
+                    &mut state.config.editor_command,
+                    String::new(),
+                    "Default application",
+                );
+                for (name, template) in EDITOR_PRESETS {
+                    ui.selectable_value(
+                        &mut state.config.editor_command,
+                        template.to_string(),
+                        *name,
+                    );
+                }
+            });
+    });
+    ui.add(
+        egui::TextEdit::singleline(&mut state.config.editor_command)
+            .hint_text("Default application")
+            .font(egui::TextStyle::Monospace),
+    )
+    .on_hover_text(
+        "Command used to open source files. {file} is replaced with the source file and {line} \
+        with the line of the function, from the base object's line info.",
+    );
+
+    subheading(ui, "Links", appearance);
+    if ui
+        .button("Register objdiff:// links")
+        .on_hover_text(
+            "Open objdiff:// links copied from the unit and symbol context menus with this copy \
+            of objdiff",
+        )
+        .clicked()
+    {
+        match register_handler() {
+            Ok(()) => log::info!("Registered objdiff:// link handler"),
+            Err(e) => log::error!("Failed to register link handler: {e:#}"),
+        }
+    }
+}
+
+fn watch_scope_label(scope: WatchScope) -> &'static str {
+    match scope {
+        WatchScope::All => "Any change",
+        WatchScope::SelectedUnit => "Selected unit only",
+    }
 }
 
 pub fn arch_config_window(
     ctx: &egui::Context,
     state: &AppStateRef,
     show: &mut bool,
+    config_state: &mut ConfigViewState,
     appearance: &Appearance,
 ) {
     let mut state_guard = state.write().unwrap();
-    egui::Window::new("Arch Settings").open(show).show(ctx, |ui| {
-        arch_config_ui(ui, &mut state_guard, appearance);
+    egui::Window::new("Diff Settings").open(show).show(ctx, |ui| {
+        arch_config_ui(ui, &mut state_guard, &mut config_state.settings_search, appearance);
     });
 }
 
-fn arch_config_ui(ui: &mut egui::Ui, state: &mut AppState, _appearance: &Appearance) {
-    ui.heading("x86");
-    egui::ComboBox::new("x86_formatter", "Format")
-        .selected_text(state.config.diff_obj_config.x86_formatter.get_message().unwrap())
-        .show_ui(ui, |ui| {
-            for &formatter in X86Formatter::VARIANTS {
-                if ui
-                    .selectable_label(
-                        state.config.diff_obj_config.x86_formatter == formatter,
-                        formatter.get_message().unwrap(),
-                    )
-                    .clicked()
-                {
-                    state.config.diff_obj_config.x86_formatter = formatter;
-                    state.queue_reload = true;
-                }
-            }
-        });
+fn property_matches(property: ConfigProperty, query: &str) -> bool {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return true;
+    }
+    [property.id(), property.name(), property.description(), property.group()]
+        .iter()
+        .any(|s| s.to_lowercase().contains(&query))
+        || property.choices().iter().any(|c| c.name.to_lowercase().contains(&query))
+}
+
+fn arch_config_ui(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    search: &mut String,
+    appearance: &Appearance,
+) {
+    egui::TextEdit::singleline(search)
+        .hint_text("Search settings…")
+        .desired_width(f32::INFINITY)
+        .ui(ui);
     ui.separator();
-    ui.heading("MIPS");
-    egui::ComboBox::new("mips_abi", "ABI")
-        .selected_text(state.config.diff_obj_config.mips_abi.get_message().unwrap())
-        .show_ui(ui, |ui| {
-            for &abi in MipsAbi::VARIANTS {
-                if ui
-                    .selectable_label(
-                        state.config.diff_obj_config.mips_abi == abi,
-                        abi.get_message().unwrap(),
-                    )
-                    .clicked()
-                {
-                    state.config.diff_obj_config.mips_abi = abi;
-                    state.queue_reload = true;
-                }
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        let mut current_group = None;
+        for &property in ConfigProperty::ALL {
+            if !property_matches(property, search) {
+                continue;
             }
-        });
-    egui::ComboBox::new("mips_instr_category", "Instruction Category")
-        .selected_text(state.config.diff_obj_config.mips_instr_category.get_message().unwrap())
-        .show_ui(ui, |ui| {
-            for &category in MipsInstrCategory::VARIANTS {
-                if ui
-                    .selectable_label(
-                        state.config.diff_obj_config.mips_instr_category == category,
-                        category.get_message().unwrap(),
-                    )
-                    .clicked()
-                {
-                    state.config.diff_obj_config.mips_instr_category = category;
-                    state.queue_reload = true;
+            if current_group != Some(property.group()) {
+                if current_group.is_some() {
+                    ui.separator();
                 }
+                ui.heading(property.group());
+                current_group = Some(property.group());
             }
-        });
-    ui.separator();
-    ui.heading("ARM");
-    egui::ComboBox::new("arm_arch_version", "Architecture Version")
-        .selected_text(state.config.diff_obj_config.arm_arch_version.get_message().unwrap())
-        .show_ui(ui, |ui| {
-            for &version in ArmArchVersion::VARIANTS {
-                if ui
-                    .selectable_label(
-                        state.config.diff_obj_config.arm_arch_version == version,
-                        version.get_message().unwrap(),
-                    )
-                    .clicked()
-                {
-                    state.config.diff_obj_config.arm_arch_version = version;
-                    state.queue_reload = true;
-                }
+            if property_ui(ui, &mut state.config.diff_obj_config, property) {
+                state.queue_reload = true;
             }
-        });
-    let response = ui
-        .checkbox(&mut state.config.diff_obj_config.arm_unified_syntax, "Unified syntax")
-        .on_hover_text("Disassemble as unified assembly language (UAL).");
-    if response.changed() {
-        state.queue_reload = true;
-    }
-    let response = ui
-        .checkbox(&mut state.config.diff_obj_config.arm_av_registers, "Use A/V registers")
-        .on_hover_text("Display R0-R3 as A1-A4 and R4-R11 as V1-V8");
-    if response.changed() {
-        state.queue_reload = true;
-    }
-    egui::ComboBox::new("arm_r9_usage", "Display R9 as")
-        .selected_text(state.config.diff_obj_config.arm_r9_usage.get_message().unwrap())
-        .show_ui(ui, |ui| {
-            for &usage in ArmR9Usage::VARIANTS {
-                if ui
-                    .selectable_label(
-                        state.config.diff_obj_config.arm_r9_usage == usage,
-                        usage.get_message().unwrap(),
-                    )
-                    .on_hover_text(usage.get_detailed_message().unwrap())
-                    .clicked()
-                {
-                    state.config.diff_obj_config.arm_r9_usage = usage;
-                    state.queue_reload = true;
-                }
+        }
+        if current_group.is_none() {
+            ui.colored_label(appearance.deemphasized_text_color, "No matching settings");
+        }
+    });
+}
+
+/// Displays the widget for a single option. Returns whether the diff should be reloaded.
+fn property_ui(ui: &mut egui::Ui, config: &mut DiffObjConfig, property: ConfigProperty) -> bool {
+    let mut new_value = None;
+    let mut reload = false;
+    match config.property_value(property) {
+        ConfigPropertyValue::Boolean(mut value) => {
+            if ui
+                .checkbox(&mut value, property.name())
+                .on_hover_text(property.description())
+                .changed()
+            {
+                new_value = Some(ConfigPropertyValue::Boolean(value));
+                reload = true;
             }
-        });
-    let response = ui
-        .checkbox(&mut state.config.diff_obj_config.arm_sl_usage, "Display R10 as SL")
-        .on_hover_text("Used for explicit stack limits.");
-    if response.changed() {
-        state.queue_reload = true;
-    }
-    let response = ui
-        .checkbox(&mut state.config.diff_obj_config.arm_fp_usage, "Display R11 as FP")
-        .on_hover_text("Used for frame pointers.");
-    if response.changed() {
-        state.queue_reload = true;
+        }
+        ConfigPropertyValue::Number(mut value) => {
+            ui.horizontal(|ui| {
+                ui.label(property.name());
+                let response = ui
+                    .add(egui::DragValue::new(&mut value).speed(10))
+                    .on_hover_text(property.description());
+                if response.changed() {
+                    new_value = Some(ConfigPropertyValue::Number(value));
+                }
+                reload = response.drag_stopped() || (response.changed() && !response.dragged());
+            });
+        }
+        ConfigPropertyValue::Choice(selected) => {
+            let choices = property.choices();
+            egui::ComboBox::new(property.id(), property.name())
+                .selected_text(choices.get(selected).map(|c| c.name).unwrap_or_default())
+                .show_ui(ui, |ui| {
+                    for (idx, choice) in choices.iter().enumerate() {
+                        let mut response = ui.selectable_label(selected == idx, choice.name);
+                        if let Some(description) = choice.description {
+                            response = response.on_hover_text(description);
+                        }
+                        if response.clicked() {
+                            new_value = Some(ConfigPropertyValue::Choice(idx));
+                            reload = true;
+                        }
+                    }
+                })
+                .response
+                .on_hover_text(property.description());
+        }
     }
-    let response = ui
-        .checkbox(&mut state.config.diff_obj_config.arm_ip_usage, "Display R12 as IP")
-        .on_hover_text("Used for interworking and long branches.");
-    if response.changed() {
-        state.queue_reload = true;
+    if let Some(value) = new_value {
+        if let Err(e) = config.set_property_value(property, value) {
+            log::error!("Failed to set {}: {e}", property.id());
+            return false;
+        }
     }
+    reload
 }
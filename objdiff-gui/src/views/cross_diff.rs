@@ -0,0 +1,212 @@
+use std::{mem::take, path::PathBuf};
+
+use egui::{RichText, ScrollArea, SelectableLabel, TextEdit, TextStyle};
+use objdiff_core::{
+    config::SymbolMappings,
+    jobs::{
+        load_symbols::{start_load_symbols, LoadSymbolsConfig, LoadSymbolsResult},
+        Job, JobQueue, JobResult,
+    },
+};
+
+use crate::{
+    app::{AppStateRef, ObjectConfig},
+    jobs::egui_waker,
+    views::{
+        appearance::Appearance,
+        symbol_diff::{DiffViewNavigation, DiffViewState, SymbolRefByName, View},
+    },
+};
+
+/// The symbol a "Diff with…" action was started from.
+#[derive(Clone)]
+pub struct CrossDiffSource {
+    pub symbol_name: String,
+    pub display_name: String,
+    pub path: PathBuf,
+}
+
+#[derive(Default)]
+pub struct CrossDiffViewState {
+    source: Option<CrossDiffSource>,
+    unit_filter: String,
+    symbol_filter: String,
+    selected_unit: Option<String>,
+    use_base: bool,
+    /// Diff against another symbol in the source object (e.g. an inlined copy)
+    same_object: bool,
+    loaded: Option<Box<LoadSymbolsResult>>,
+    /// Object to load symbols from once the current frame is done
+    queue_load: Option<PathBuf>,
+}
+
+impl CrossDiffViewState {
+    pub fn open(&mut self, source: CrossDiffSource) {
+        self.source = Some(source);
+        self.symbol_filter.clear();
+    }
+
+    pub fn pre_update(&mut self, jobs: &mut JobQueue) {
+        jobs.results.retain_mut(|result| {
+            if let JobResult::LoadSymbols(result) = result {
+                if let Some(result) = take(result) {
+                    self.loaded = Some(result);
+                }
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    pub fn post_update(&mut self, ctx: &egui::Context, jobs: &mut JobQueue, state: &AppStateRef) {
+        let Some(path) = take(&mut self.queue_load) else {
+            return;
+        };
+        let Ok(state) = state.read() else {
+            return;
+        };
+        let config =
+            LoadSymbolsConfig { path, diff_obj_config: state.config.diff_obj_config.clone() };
+        jobs.push_once(Job::LoadSymbols, || start_load_symbols(egui_waker(ctx), config));
+    }
+}
+
+/// Lets the user pick a symbol from any unit, or from the source object itself, to diff against
+/// the source symbol. The pair is opened as a temporary object outside the configured unit
+/// pairing.
+pub fn cross_diff_window(
+    ctx: &egui::Context,
+    state: &mut CrossDiffViewState,
+    app_state: &AppStateRef,
+    diff_state: &mut DiffViewState,
+    appearance: &Appearance,
+) {
+    let mut open = state.source.is_some();
+    let mut selected = None;
+    egui::Window::new("Diff with…").open(&mut open).show(ctx, |ui| {
+        let Some(source) = &state.source else {
+            return;
+        };
+        let Ok(app_state) = app_state.read() else {
+            return;
+        };
+        ui.horizontal(|ui| {
+            ui.label("Left:");
+            ui.label(
+                RichText::new(&source.display_name)
+                    .color(appearance.highlight_color)
+                    .text_style(TextStyle::Monospace),
+            );
+        });
+        ui.separator();
+
+        ui.checkbox(&mut state.same_object, "Same object")
+            .on_hover_text("Diff against another symbol in the same object file");
+        let path = if state.same_object {
+            source.path.clone()
+        } else {
+            ui.horizontal(|ui| {
+                TextEdit::singleline(&mut state.unit_filter).hint_text("Filter units").show(ui);
+                ui.radio_value(&mut state.use_base, false, "Target");
+                ui.radio_value(&mut state.use_base, true, "Base");
+            });
+            let unit_filter = state.unit_filter.to_ascii_lowercase();
+            ScrollArea::vertical().id_salt("cross_diff_units").max_height(160.0).show(ui, |ui| {
+                for object in &app_state.objects {
+                    let name = object.name();
+                    if !unit_filter.is_empty() && !name.to_ascii_lowercase().contains(&unit_filter)
+                    {
+                        continue;
+                    }
+                    let is_selected = state.selected_unit.as_deref() == Some(name);
+                    if ui.add(SelectableLabel::new(is_selected, name)).clicked() {
+                        state.selected_unit = Some(name.to_string());
+                    }
+                }
+            });
+            ui.separator();
+
+            let path =
+                state
+                    .selected_unit
+                    .as_deref()
+                    .and_then(|name| app_state.objects.iter().find(|o| o.name() == name))
+                    .and_then(|o| {
+                        if state.use_base {
+                            o.base_path.clone()
+                        } else {
+                            o.target_path.clone()
+                        }
+                    });
+            let Some(path) = path else {
+                ui.label("Select a unit with a target or base object.");
+                return;
+            };
+            path
+        };
+        let loaded = match &state.loaded {
+            Some(loaded) if loaded.path == path => loaded,
+            _ => {
+                ui.label("Loading symbols…");
+                state.queue_load = Some(path);
+                return;
+            }
+        };
+        let symbols = match &loaded.symbols {
+            Ok(symbols) => symbols,
+            Err(e) => {
+                ui.colored_label(appearance.delete_color, e);
+                return;
+            }
+        };
+
+        TextEdit::singleline(&mut state.symbol_filter).hint_text("Filter symbols").show(ui);
+        let symbol_filter = state.symbol_filter.to_ascii_lowercase();
+        ScrollArea::vertical().id_salt("cross_diff_symbols").max_height(240.0).show(ui, |ui| {
+            ui.style_mut().override_text_style = Some(TextStyle::Monospace);
+            for (name, demangled) in symbols {
+                if path == source.path && *name == source.symbol_name {
+                    continue;
+                }
+                let display = demangled.as_deref().unwrap_or(name);
+                if !symbol_filter.is_empty()
+                    && !display.to_ascii_lowercase().contains(&symbol_filter)
+                    && !name.to_ascii_lowercase().contains(&symbol_filter)
+                {
+                    continue;
+                }
+                if ui.add(SelectableLabel::new(false, display)).clicked() {
+                    selected =
+                        Some((source.clone(), path.clone(), name.clone(), display.to_string()));
+                }
+            }
+        });
+    });
+    if !open {
+        state.source = None;
+    }
+
+    let Some((source, path, symbol_name, display_name)) = selected else {
+        return;
+    };
+    state.source = None;
+    let Ok(mut app_state) = app_state.write() else {
+        return;
+    };
+    let mut symbol_mappings = SymbolMappings::new();
+    symbol_mappings.insert(source.symbol_name.clone(), symbol_name.clone());
+    app_state.set_selected_obj(ObjectConfig {
+        name: format!("{} ↔ {}", source.display_name, display_name),
+        target_path: Some(source.path),
+        base_path: Some(path),
+        symbol_mappings,
+        cross_unit: true,
+        ..Default::default()
+    });
+    diff_state.post_build_nav = Some(DiffViewNavigation {
+        view: Some(View::FunctionDiff),
+        left_symbol: Some(SymbolRefByName { symbol_name: source.symbol_name, section_name: None }),
+        right_symbol: Some(SymbolRefByName { symbol_name, section_name: None }),
+    });
+}
@@ -1,9 +1,13 @@
-use std::{cmp::min, default::Default, mem::take};
+use std::{cmp::min, default::Default, mem::take, ops::Range};
 
-use egui::{text::LayoutJob, Id, Label, RichText, Sense, Widget};
+use egui::{
+    pos2,
+    text::{CCursor, LayoutJob},
+    CollapsingHeader, Id, Label, Painter, Rect, RichText, Sense, Stroke, Widget,
+};
 use objdiff_core::{
     diff::{ObjDataDiff, ObjDataDiffKind, ObjDiff},
-    obj::ObjInfo,
+    obj::{ObjInfo, SymbolRef},
 };
 use time::format_description;
 
@@ -12,7 +16,7 @@ use crate::{
     views::{
         appearance::Appearance,
         column_layout::{render_header, render_table},
-        symbol_diff::{DiffViewAction, DiffViewNavigation, DiffViewState},
+        symbol_diff::{build_button_ui, DiffViewAction, DiffViewNavigation, DiffViewState},
         write_text,
     },
 };
@@ -23,6 +27,16 @@ fn find_section(obj: &ObjInfo, section_name: &str) -> Option<usize> {
     obj.sections.iter().position(|section| section.name == section_name)
 }
 
+/// Draws diagonal lines over alignment padding bytes.
+fn paint_hatching(painter: &Painter, rect: Rect, stroke: Stroke) {
+    let painter = painter.with_clip_rect(rect);
+    let mut x = rect.left() - rect.height();
+    while x < rect.right() {
+        painter.line_segment([pos2(x, rect.bottom()), pos2(x + rect.height(), rect.top())], stroke);
+        x += 4.0;
+    }
+}
+
 fn data_row_ui(ui: &mut egui::Ui, address: usize, diffs: &[ObjDataDiff], appearance: &Appearance) {
     if diffs.iter().any(|d| d.kind != ObjDataDiffKind::None) {
         ui.painter().rect_filled(ui.available_rect_before_wrap(), 0.0, ui.visuals().faint_bg_color);
@@ -34,6 +48,8 @@ fn data_row_ui(ui: &mut egui::Ui, address: usize, diffs: &[ObjDataDiff], appeara
         &mut job,
         appearance.code_font.clone(),
     );
+    // Text ranges of padding bytes, to be hatched
+    let mut padding_ranges = Vec::<Range<usize>>::new();
     let mut cur_addr = 0usize;
     for diff in diffs {
         let base_color = match diff.kind {
@@ -56,6 +72,10 @@ fn data_row_ui(ui: &mut egui::Ui, address: usize, diffs: &[ObjDataDiff], appeara
                     text.push(' ');
                 }
             }
+            if diff.padding {
+                let start = job.text.len();
+                padding_ranges.push(start..start + text.trim_end().len());
+            }
             write_text(text.as_str(), base_color, &mut job, appearance.code_font.clone());
         }
     }
@@ -91,10 +111,27 @@ fn data_row_ui(ui: &mut egui::Ui, address: usize, diffs: &[ObjDataDiff], appeara
                     text.push('.');
                 }
             }
+            if diff.padding {
+                let start = job.text.len();
+                padding_ranges.push(start..start + text.len());
+            }
             write_text(text.as_str(), base_color, &mut job, appearance.code_font.clone());
         }
     }
-    Label::new(job).sense(Sense::click()).ui(ui);
+    let galley = ui.fonts(|f| f.layout_job(job));
+    let response = Label::new(galley.clone()).sense(Sense::click()).ui(ui);
+    if !padding_ranges.is_empty() {
+        let stroke = Stroke::new(1.0, appearance.deemphasized_text_color.gamma_multiply(0.6));
+        for range in padding_ranges {
+            // The row text is ASCII, so byte offsets are character offsets
+            let start = galley.pos_from_ccursor(CCursor::new(range.start));
+            let end = galley.pos_from_ccursor(CCursor::new(range.end));
+            let rect = Rect::from_min_max(start.min, pos2(end.min.x, start.max.y))
+                .translate(response.rect.min.to_vec2());
+            paint_hatching(ui.painter(), rect, stroke);
+        }
+        response.on_hover_text("Hatched bytes are alignment padding, ignored in the match percent");
+    }
     //     .on_hover_ui_at_pointer(|ui| ins_hover_ui(ui, ins))
     //     .context_menu(|ui| ins_context_menu(ui, ins));
 }
@@ -119,6 +156,7 @@ fn split_diffs(diffs: &[ObjDataDiff]) -> Vec<Vec<ObjDataDiff>> {
                 len,
                 // TODO
                 symbol: String::new(),
+                padding: diff.padding,
             });
             remaining_in_row -= len;
             cur_len += len;
@@ -154,6 +192,50 @@ impl<'a> SectionDiffContext<'a> {
     pub fn has_section(&self) -> bool { self.section_index.is_some() }
 }
 
+/// Shows the size of the selected data symbol and how code accesses it.
+fn symbol_details_ui(
+    ui: &mut egui::Ui,
+    ctx: Option<SectionDiffContext<'_>>,
+    symbol_name: Option<&str>,
+    appearance: &Appearance,
+) {
+    let Some((ctx, symbol_name)) = ctx.zip(symbol_name) else {
+        return;
+    };
+    let Some((symbol_ref, symbol)) = ctx.section_index.and_then(|section_idx| {
+        let symbols = &ctx.obj.sections[section_idx].symbols;
+        let symbol_idx = symbols.iter().position(|s| s.name == symbol_name)?;
+        Some((SymbolRef { section_idx, symbol_idx }, &symbols[symbol_idx]))
+    }) else {
+        return;
+    };
+    CollapsingHeader::new("Symbol details").id_salt(Id::new(symbol_name)).default_open(true).show(
+        ui,
+        |ui| {
+            ui.style_mut().override_text_style = Some(egui::TextStyle::Monospace);
+            let name = symbol.demangled_name.as_deref().unwrap_or(&symbol.name);
+            ui.colored_label(appearance.highlight_color, name);
+            ui.label(format!("Address: {:#x}, size: {:#x}", symbol.address, symbol.size));
+            if let Some(summary) = ctx.diff.data_types.get(&symbol_ref) {
+                let color = if summary.is_mixed() {
+                    appearance.replace_color
+                } else {
+                    appearance.text_color
+                };
+                ui.colored_label(color, format!("Accessed as: {summary}"));
+                if summary.is_mixed() {
+                    ui.colored_label(
+                        appearance.replace_color,
+                        "Accessed as multiple types; the variable may be declared incorrectly",
+                    );
+                }
+            } else {
+                ui.colored_label(appearance.deemphasized_text_color, "Not referenced from code");
+            }
+        },
+    );
+}
+
 fn data_table_ui(
     ui: &mut egui::Ui,
     available_width: f32,
@@ -181,7 +263,7 @@ fn data_table_ui(
 
     hotkeys::check_scroll_hotkeys(ui, true);
 
-    render_table(ui, available_width, 2, config.code_font.size, total_rows, |row, column| {
+    render_table(ui, available_width, 2, config.code_font.size, total_rows, None, |row, column| {
         let i = row.index();
         let address = i * BYTES_PER_ROW;
         row.col(|ui| {
@@ -248,11 +330,17 @@ pub fn data_diff_ui(
                         .color(appearance.replace_color),
                 );
             }
+            symbol_details_ui(
+                ui,
+                left_ctx,
+                state.symbol_state.left_symbol.as_ref().map(|s| s.symbol_name.as_str()),
+                appearance,
+            );
         } else if column == 1 {
             // Right column
             ui.horizontal(|ui| {
-                if ui.add_enabled(!state.build_running, egui::Button::new("Build")).clicked() {
-                    ret = Some(DiffViewAction::Build);
+                if let Some(action) = build_button_ui(ui, state.build_running) {
+                    ret = Some(action);
                 }
                 ui.scope(|ui| {
                     ui.style_mut().override_text_style = Some(egui::TextStyle::Monospace);
@@ -283,6 +371,12 @@ pub fn data_diff_ui(
                         .color(appearance.replace_color),
                 );
             }
+            symbol_details_ui(
+                ui,
+                right_ctx,
+                state.symbol_state.right_symbol.as_ref().map(|s| s.symbol_name.as_str()),
+                appearance,
+            );
         }
     });
 
@@ -1,20 +1,131 @@
-use crate::views::{appearance::Appearance, frame_history::FrameHistory};
+use std::time::Duration;
+
+use egui::{DragValue, ProgressBar};
+
+use crate::{
+    app::AppStateRef,
+    views::{appearance::Appearance, frame_history::FrameHistory, symbol_diff::DiffViewState},
+};
 
 pub fn debug_window(
     ctx: &egui::Context,
     show: &mut bool,
     frame_history: &mut FrameHistory,
+    state: &AppStateRef,
+    diff_state: &DiffViewState,
     appearance: &Appearance,
 ) {
     egui::Window::new("Debug").open(show).show(ctx, |ui| {
-        debug_ui(ui, frame_history, appearance);
+        debug_ui(ui, frame_history, state, diff_state, appearance);
     });
 }
 
-fn debug_ui(ui: &mut egui::Ui, frame_history: &mut FrameHistory, _appearance: &Appearance) {
+fn debug_ui(
+    ui: &mut egui::Ui,
+    frame_history: &mut FrameHistory,
+    state: &AppStateRef,
+    diff_state: &DiffViewState,
+    appearance: &Appearance,
+) {
     if ui.button("Clear memory").clicked() {
         ui.memory_mut(|m| *m = Default::default());
     }
     ui.label(format!("Repainting the UI each frame. FPS: {:.1}", frame_history.fps()));
     frame_history.ui(ui);
+
+    ui.separator();
+    timings_ui(ui, diff_state, appearance);
+
+    ui.separator();
+    memory_ui(ui, state, diff_state, appearance);
+}
+
+fn format_ms(duration: Duration) -> String { format!("{:.2} ms", duration.as_secs_f64() * 1000.0) }
+
+/// Time spent in each stage of the last build, and in the diff view in the last frame.
+fn timings_ui(ui: &mut egui::Ui, diff_state: &DiffViewState, appearance: &Appearance) {
+    ui.label("Timings:").on_hover_text(
+        "Stages of the last build. Run with RUST_LOG=debug to log them for every diff.",
+    );
+    let Some(build) = &diff_state.build else {
+        ui.colored_label(appearance.deemphasized_text_color, "No build");
+        return;
+    };
+    let total = build.timings.total();
+    egui::Grid::new("timings").num_columns(3).striped(true).show(ui, |ui| {
+        for &(stage, duration) in &build.timings.stages {
+            ui.label(stage);
+            ui.label(format_ms(duration));
+            ui.add(
+                ProgressBar::new(duration.as_secs_f32() / total.as_secs_f32().max(f32::EPSILON))
+                    .desired_width(100.0),
+            );
+            ui.end_row();
+        }
+        ui.colored_label(appearance.highlight_color, "Total");
+        ui.colored_label(appearance.highlight_color, format_ms(total));
+        ui.end_row();
+        ui.label("Display (last frame)");
+        ui.label(format_ms(diff_state.display_time));
+        ui.end_row();
+    });
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes >= 1 << 20 {
+        format!("{:.1} MiB", bytes as f64 / (1 << 20) as f64)
+    } else if bytes >= 1 << 10 {
+        format!("{:.1} KiB", bytes as f64 / (1 << 10) as f64)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
+/// Approximate memory used by loaded objects and diff results, per unit.
+fn memory_ui(
+    ui: &mut egui::Ui,
+    state: &AppStateRef,
+    diff_state: &DiffViewState,
+    appearance: &Appearance,
+) {
+    let Ok(mut state) = state.write() else {
+        return;
+    };
+    let mut limit_mb = state.config.memory_limit_mb;
+    ui.horizontal(|ui| {
+        ui.label("Memory limit:");
+        if ui
+            .add(DragValue::new(&mut limit_mb).range(64..=65536).speed(16).suffix(" MiB"))
+            .on_hover_text("Recently loaded objects are kept until this limit is exceeded")
+            .changed()
+        {
+            state.set_memory_limit(limit_mb);
+        }
+    });
+    let cached = state.obj_cache.usage();
+    let in_use = if diff_state.build.is_some() { diff_state.build_memory_usage } else { 0 };
+    let total = in_use + cached.iter().map(|c| c.size).sum::<usize>();
+    let limit = state.obj_cache.limit();
+    ui.add(ProgressBar::new((total as f32 / limit.max(1) as f32).min(1.0)).text(format!(
+        "{} / {}",
+        format_size(total),
+        format_size(limit)
+    )));
+    egui::Grid::new("memory_usage").num_columns(2).striped(true).show(ui, |ui| {
+        if diff_state.build.is_some() {
+            ui.colored_label(appearance.highlight_color, &diff_state.build_unit);
+            ui.label(format!("{} (in use)", format_size(in_use)));
+            ui.end_row();
+        }
+        for entry in &cached {
+            ui.label(&entry.unit).on_hover_text(entry.path.display().to_string());
+            ui.label(format_size(entry.size));
+            ui.end_row();
+        }
+    });
+    if cached.is_empty() {
+        ui.colored_label(appearance.deemphasized_text_color, "No cached objects");
+    } else if ui.button("Clear object cache").clicked() {
+        state.obj_cache.clear();
+    }
 }
@@ -1,7 +1,11 @@
 use egui::{RichText, ScrollArea};
 use objdiff_core::{
     arch::ppc::ExceptionInfo,
-    obj::{ObjInfo, ObjSymbol},
+    diff::{
+        unwind::{diff_seh_unwind_info, diff_unwind_info, UnwindDiffRow},
+        ObjInsDiffKind,
+    },
+    obj::{seh::SehUnwindInfo, unwind::UnwindInfo, ObjInfo, ObjSymbol},
 };
 use time::format_description;
 
@@ -12,8 +16,8 @@ use crate::{
         column_layout::{render_header, render_strips},
         function_diff::FunctionDiffContext,
         symbol_diff::{
-            match_color_for_symbol, DiffViewAction, DiffViewNavigation, DiffViewState,
-            SymbolRefByName, View,
+            build_button_ui, match_color_for_symbol, DiffViewAction, DiffViewNavigation,
+            DiffViewState, SymbolRefByName, View,
         },
     },
 };
@@ -57,11 +61,30 @@ fn extab_text_ui(
     None
 }
 
+fn unwind_text_ui(
+    ui: &mut egui::Ui,
+    rows: &[UnwindDiffRow],
+    appearance: &Appearance,
+    column: usize,
+) {
+    for row in rows {
+        let text = if column == 0 { &row.left } else { &row.right };
+        let color = match row.kind {
+            ObjInsDiffKind::None => appearance.text_color,
+            ObjInsDiffKind::Delete => appearance.delete_color,
+            ObjInsDiffKind::Insert => appearance.insert_color,
+            _ => appearance.replace_color,
+        };
+        ui.colored_label(color, text.as_deref().unwrap_or(""));
+    }
+}
+
 fn extab_ui(
     ui: &mut egui::Ui,
     ctx: FunctionDiffContext<'_>,
+    unwind_rows: &[UnwindDiffRow],
     appearance: &Appearance,
-    _column: usize,
+    column: usize,
 ) {
     ScrollArea::both().auto_shrink([false, false]).show(ui, |ui| {
         ui.scope(|ui| {
@@ -71,12 +94,26 @@ fn extab_ui(
             if let Some((_section, symbol)) =
                 ctx.symbol_ref.map(|symbol_ref| ctx.obj.section_symbol(symbol_ref))
             {
-                extab_text_ui(ui, ctx, symbol, appearance);
+                if extab_text_ui(ui, ctx, symbol, appearance).is_none() {
+                    unwind_text_ui(ui, unwind_rows, appearance, column);
+                }
             }
         });
     });
 }
 
+fn find_unwind_info<'a>(ctx: Option<FunctionDiffContext<'a>>) -> Option<&'a UnwindInfo> {
+    let ctx = ctx?;
+    let (_section, symbol) = ctx.obj.section_symbol(ctx.symbol_ref?);
+    ctx.obj.unwind_info_for_symbol(symbol)
+}
+
+fn find_seh_unwind_info<'a>(ctx: Option<FunctionDiffContext<'a>>) -> Option<&'a SehUnwindInfo> {
+    let ctx = ctx?;
+    let (_section, symbol) = ctx.obj.section_symbol(ctx.symbol_ref?);
+    ctx.obj.seh_unwind_info_for_symbol(symbol)
+}
+
 #[must_use]
 pub fn extab_diff_ui(
     ui: &mut egui::Ui,
@@ -181,8 +218,8 @@ pub fn extab_diff_ui(
         } else if column == 1 {
             // Right column
             ui.horizontal(|ui| {
-                if ui.add_enabled(!state.build_running, egui::Button::new("Build")).clicked() {
-                    ret = Some(DiffViewAction::Build);
+                if let Some(action) = build_button_ui(ui, state.build_running) {
+                    ret = Some(action);
                 }
                 ui.scope(|ui| {
                     ui.style_mut().override_text_style = Some(egui::TextStyle::Monospace);
@@ -237,15 +274,22 @@ pub fn extab_diff_ui(
 
     hotkeys::check_scroll_hotkeys(ui, true);
 
+    let (left_seh, right_seh) = (find_seh_unwind_info(left_ctx), find_seh_unwind_info(right_ctx));
+    let unwind_rows = if left_seh.is_some() || right_seh.is_some() {
+        diff_seh_unwind_info(left_seh, right_seh)
+    } else {
+        diff_unwind_info(find_unwind_info(left_ctx), find_unwind_info(right_ctx))
+    };
+
     // Table
     render_strips(ui, available_width, 2, |ui, column| {
         if column == 0 {
             if let Some(ctx) = left_ctx {
-                extab_ui(ui, ctx, appearance, column);
+                extab_ui(ui, ctx, &unwind_rows, appearance, column);
             }
         } else if column == 1 {
             if let Some(ctx) = right_ctx {
-                extab_ui(ui, ctx, appearance, column);
+                extab_ui(ui, ctx, &unwind_rows, appearance, column);
             }
         }
     });
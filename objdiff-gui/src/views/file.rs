@@ -11,6 +11,15 @@ pub enum FileDialogResult {
     TargetDir(PathBuf),
     BaseDir(PathBuf),
     Object(PathBuf),
+    ImportMappings(PathBuf),
+    ExportMappings(PathBuf),
+    OpenSession(PathBuf),
+    PreviousReport(PathBuf),
+    CurrentReport(PathBuf),
+    ExportPrintableReport(PathBuf),
+    ExportUsageStats(PathBuf),
+    ExportSymbolOrder(PathBuf),
+    ExportSession(PathBuf),
 }
 
 #[derive(Default)]
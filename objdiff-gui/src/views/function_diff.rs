@@ -1,11 +1,19 @@
-use std::{cmp::Ordering, default::Default};
+use std::{
+    cell::Cell, cmp::Ordering, collections::BTreeSet, default::Default, fs, ops::Range,
+    path::PathBuf,
+};
 
-use egui::{text::LayoutJob, Id, Label, Layout, Response, RichText, Sense, Widget};
+use egui::{
+    text::LayoutJob, vec2, CollapsingHeader, Id, Key, Label, Layout, Rect, Response, RichText,
+    ScrollArea, Sense, TextEdit, Widget,
+};
 use egui_extras::TableRow;
 use objdiff_core::{
     diff::{
-        display::{display_diff, DiffText, HighlightKind},
-        ObjDiff, ObjInsDiff, ObjInsDiffKind,
+        code::InsDiffStats,
+        display::{display_diff, DiffText, DiffTextSearch, HighlightKind},
+        AlignmentAnchor, DiffObjConfig, ObjDiff, ObjInsDiff, ObjInsDiffKind, PpcCrFieldMismatch,
+        SizeMismatchFix,
     },
     obj::{
         ObjInfo, ObjIns, ObjInsArg, ObjInsArgValue, ObjSection, ObjSectionKind, ObjSymbol,
@@ -15,13 +23,15 @@ use objdiff_core::{
 use time::format_description;
 
 use crate::{
+    editor::symbol_line,
     hotkeys,
     views::{
         appearance::Appearance,
         column_layout::{render_header, render_strips, render_table},
         symbol_diff::{
-            match_color_for_symbol, symbol_list_ui, DiffViewAction, DiffViewNavigation,
-            DiffViewState, SymbolDiffContext, SymbolFilter, SymbolRefByName, SymbolViewState, View,
+            build_button_ui, match_color_for_symbol, symbol_list_ui, DiffViewAction,
+            DiffViewNavigation, DiffViewState, SymbolDiffContext, SymbolFilter, SymbolRefByName,
+            SymbolViewState, View,
         },
     },
 };
@@ -30,9 +40,175 @@ use crate::{
 pub struct FunctionViewState {
     left_highlight: HighlightKind,
     right_highlight: HighlightKind,
+    /// Text search query within the current function
+    pub search: String,
+    /// Index of the current search match
+    pub search_index: usize,
+    /// Row to scroll into view this frame
+    pub scroll_to_row: Option<usize>,
+    /// How the two columns scroll relative to each other
+    pub scroll_sync: ScrollSync,
+    /// Row to scroll into view this frame in each column, when they scroll separately
+    pub column_scroll_to_row: [Option<usize>; 2],
+    /// Scroll the right column to match the left column this frame
+    pub resync_scroll: bool,
+    /// Bookmarked instructions of each column's symbol, as offsets into the symbol
+    pub bookmarks: [BTreeSet<u64>; 2],
+    /// Row of the last bookmark jumped to
+    pub bookmark_row: Option<usize>,
+    /// Manual alignment anchors of the current function
+    pub anchors: Vec<AlignmentAnchor>,
+    /// How the current function is diffed if its size differs between both sides
+    pub size_fix: Option<SizeMismatchFix>,
+    /// Instruction marked to be anchored to an instruction of the other column, as its column
+    /// and offset into the symbol
+    pub pending_anchor: Option<(usize, u64)>,
+    /// Group instruction rows into collapsible blocks by source line
+    pub line_blocks: bool,
+    /// The unit's source file, for the line block headers
+    pub source_text: Option<SourceText>,
+}
+
+/// Lines of a unit's source file.
+pub struct SourceText {
+    pub path: PathBuf,
+    pub lines: Vec<String>,
+}
+
+/// How the two columns of the function diff scroll relative to each other.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum ScrollSync {
+    /// Both columns scroll together, row by row.
+    #[default]
+    Row,
+    /// Scrolling one column scrolls the other to the same offset into the function.
+    Address,
+    /// Scrolling one column scrolls the other to the same source line.
+    Line,
+    /// The columns scroll independently.
+    Free,
+}
+
+impl ScrollSync {
+    pub const ALL: [ScrollSync; 4] =
+        [ScrollSync::Row, ScrollSync::Address, ScrollSync::Line, ScrollSync::Free];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ScrollSync::Row => "Lock rows",
+            ScrollSync::Address => "Lock by address",
+            ScrollSync::Line => "Lock by line",
+            ScrollSync::Free => "Unlocked",
+        }
+    }
+
+    /// Finds the row of `to` corresponding to `row` of `from`. Each side is given as its
+    /// instruction diffs and the function's base address.
+    fn sync_row(
+        self,
+        (from, from_address): (&[ObjInsDiff], u64),
+        (to, to_address): (&[ObjInsDiff], u64),
+        row: usize,
+    ) -> Option<usize> {
+        // Start from the nearest instruction, skipping rows of the other side's insertions
+        let (before, after) = from.split_at(row.min(from.len()));
+        let from_ins =
+            || after.iter().chain(before.iter().rev()).filter_map(|ins_diff| ins_diff.ins.as_ref());
+        match self {
+            ScrollSync::Row | ScrollSync::Free => Some(row.min(to.len().saturating_sub(1))),
+            ScrollSync::Address => {
+                let offset = from_ins().next()?.address - from_address;
+                let rows = to.iter().enumerate().filter_map(|(i, d)| Some((i, d.ins.as_ref()?)));
+                rows.clone()
+                    .find(|(_, ins)| ins.address - to_address >= offset)
+                    .or_else(|| rows.last())
+                    .map(|(i, _)| i)
+            }
+            ScrollSync::Line => {
+                let line = from_ins().find_map(|ins| ins.line)?;
+                let lines =
+                    to.iter().enumerate().filter_map(|(i, d)| Some((i, d.ins.as_ref()?.line?)));
+                lines
+                    .clone()
+                    .find(|&(_, l)| l == line)
+                    .or_else(|| lines.filter(|&(_, l)| l > line).min_by_key(|&(_, l)| l))
+                    .map(|(i, _)| i)
+            }
+        }
+    }
+}
+
+/// Rows of the current function matching the search query.
+struct FunctionSearch {
+    search: DiffTextSearch,
+    rows: Vec<usize>,
+    current_row: Option<usize>,
+}
+
+impl FunctionSearch {
+    /// `Some(true)` for the current match, `Some(false)` for other matches.
+    fn row_match(&self, row: usize) -> Option<bool> {
+        self.rows.binary_search(&row).ok().map(|_| self.current_row == Some(row))
+    }
+}
+
+const FUNCTION_SEARCH_ID: &str = "function_search";
+
+/// Focuses the function search field, if the function diff view is shown.
+pub fn focus_function_search(ctx: &egui::Context) {
+    ctx.memory_mut(|m| m.request_focus(Id::new(FUNCTION_SEARCH_ID)));
 }
 
 impl FunctionViewState {
+    pub fn set_search(&mut self, search: String, first_row: Option<usize>) {
+        self.search = search;
+        self.search_index = 0;
+        self.scroll_to_row = first_row;
+    }
+
+    pub fn select_search_match(&mut self, index: usize, row: usize) {
+        self.search_index = index;
+        self.scroll_to_row = Some(row);
+    }
+
+    pub fn select_bookmark(&mut self, row: usize) {
+        self.bookmark_row = Some(row);
+        self.scroll_to_row = Some(row);
+    }
+
+    /// Whether the instruction at `offset` into the symbol of a column is anchored.
+    fn is_anchored(&self, column: usize, offset: u64) -> bool {
+        self.anchors.iter().any(|a| if column == 0 { a.left == offset } else { a.right == offset })
+    }
+
+    /// Loads the unit's source file for the line block headers, when they're shown. The file is
+    /// read again if it changed or `reload` is set, e.g. after a rebuild.
+    pub fn load_source_text(&mut self, path: Option<PathBuf>, reload: bool) {
+        if !self.line_blocks {
+            return;
+        }
+        let Some(path) = path else {
+            self.source_text = None;
+            return;
+        };
+        if !reload && self.source_text.as_ref().is_some_and(|text| text.path == path) {
+            return;
+        }
+        let lines = match fs::read(&path) {
+            Ok(data) => String::from_utf8_lossy(&data).lines().map(str::to_string).collect(),
+            Err(e) => {
+                log::warn!("Failed to read source file {}: {e}", path.display());
+                vec![]
+            }
+        };
+        self.source_text = Some(SourceText { path, lines });
+    }
+
+    pub fn set_scroll_sync(&mut self, scroll_sync: ScrollSync) {
+        self.scroll_sync = scroll_sync;
+        self.resync_scroll = true;
+    }
+
     pub fn highlight(&self, column: usize) -> &HighlightKind {
         match column {
             0 => &self.left_highlight,
@@ -90,10 +266,8 @@ fn ins_hover_ui(
         ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
 
         let offset = ins.address - section.address;
-        ui.label(format!(
-            "{:02x?}",
-            &section.data[offset as usize..(offset + ins.size as u64) as usize]
-        ));
+        let code = &section.data[offset as usize..(offset + ins.size as u64) as usize];
+        ui.label(format!("{:02x?}", code));
 
         if let Some(virtual_address) = symbol.virtual_address {
             let offset = ins.address - symbol.address;
@@ -107,6 +281,10 @@ fn ins_hover_ui(
             ui.label(format!("Original: {}", orig));
         }
 
+        for line in obj.arch.instruction_hover(ins, code) {
+            ui.colored_label(appearance.replace_color, line);
+        }
+
         for arg in &ins.args {
             if let ObjInsArg::Arg(arg) = arg {
                 match arg {
@@ -123,6 +301,12 @@ fn ins_hover_ui(
 
         if let Some(reloc) = &ins.reloc {
             ui.label(format!("Relocation type: {}", obj.arch.display_reloc(reloc.flags)));
+            if reloc.pseudo {
+                ui.colored_label(
+                    appearance.deemphasized_text_color,
+                    "Pseudo relocation, inferred by flow analysis",
+                );
+            }
             let addend_str = match reloc.addend.cmp(&0i64) {
                 Ordering::Greater => format!("+{:x}", reloc.addend),
                 Ordering::Less => format!("-{:x}", -reloc.addend),
@@ -132,6 +316,17 @@ fn ins_hover_ui(
                 appearance.highlight_color,
                 format!("Name: {}{}", reloc.target.name, addend_str),
             );
+            if let Some(original) = &reloc.original {
+                let addend_str = match original.addend.cmp(&0i64) {
+                    Ordering::Greater => format!("+{:x}", original.addend),
+                    Ordering::Less => format!("-{:x}", -original.addend),
+                    _ => "".to_string(),
+                };
+                ui.colored_label(
+                    appearance.highlight_color,
+                    format!("Encoded as: {}{}", original.name, addend_str),
+                );
+            }
             if let Some(orig_section_index) = reloc.target.orig_section_index {
                 if let Some(section) =
                     obj.sections.iter().find(|s| s.orig_index == orig_section_index)
@@ -160,9 +355,53 @@ fn ins_hover_ui(
                 ui.colored_label(appearance.highlight_color, "Extern".to_string());
             }
         }
+    });
+}
 
-        if let Some(decoded) = rlwinmdec::decode(&ins.formatted) {
-            ui.colored_label(appearance.highlight_color, decoded.trim());
+/// Number of bytes of a relocation target shown in the preview.
+const RELOC_PREVIEW_BYTES: usize = 16;
+
+/// The bytes referenced by an instruction's relocation, from the target symbol plus addend.
+fn reloc_target_bytes(ins: &ObjIns) -> Option<&[u8]> {
+    let reloc = ins.reloc.as_ref()?;
+    reloc.target.bytes.get(usize::try_from(reloc.addend).ok()?..).filter(|b| !b.is_empty())
+}
+
+/// Shown when hovering a relocation target: the referenced bytes and their interpreted value on
+/// both sides, highlighted if they differ.
+fn reloc_preview_ui(
+    ui: &mut egui::Ui,
+    sides: [Option<(&ObjInfo, &ObjIns)>; 2],
+    appearance: &Appearance,
+) {
+    let bytes = sides.map(|side| {
+        side.and_then(|(_, ins)| reloc_target_bytes(ins))
+            .map(|b| &b[..b.len().min(RELOC_PREVIEW_BYTES)])
+    });
+    let differs = matches!(bytes, [Some(l), Some(r)] if l != r);
+    ui.scope(|ui| {
+        ui.style_mut().override_text_style = Some(egui::TextStyle::Monospace);
+        ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+        for ((label, side), bytes) in ["Target", "Base"].into_iter().zip(sides).zip(bytes) {
+            let Some((obj, ins)) = side else {
+                continue;
+            };
+            let Some(reloc) = &ins.reloc else {
+                continue;
+            };
+            let name = reloc.target.demangled_name.as_deref().unwrap_or(&reloc.target.name);
+            ui.label(RichText::new(format!("{label}: {name}")).color(appearance.highlight_color));
+            let Some(bytes) = bytes else {
+                ui.colored_label(appearance.deemphasized_text_color, "  No data");
+                continue;
+            };
+            let color = if differs { appearance.replace_color } else { appearance.text_color };
+            ui.colored_label(color, format!("  {bytes:02x?}"));
+            if let Some(value) = obj.arch.guess_data_type(ins).and_then(|ty| {
+                obj.arch.display_data_type(ty, reloc_target_bytes(ins).unwrap_or_default())
+            }) {
+                ui.colored_label(color, format!("  {value}"));
+            }
         }
     });
 }
@@ -248,6 +487,23 @@ fn find_symbol(obj: &ObjInfo, selected_symbol: &SymbolRefByName) -> Option<Symbo
     None
 }
 
+const HOVERED_ARG_DIFF: &str = "hovered_arg_diff";
+const NEXT_HOVERED_ARG_DIFF: &str = "next_hovered_arg_diff";
+
+/// The differing operand pair hovered during the previous frame. Hovering an operand in one
+/// column highlights its counterpart in the other.
+fn hovered_arg_diff(ctx: &egui::Context) -> Option<usize> {
+    ctx.data(|d| d.get_temp::<usize>(Id::new(HOVERED_ARG_DIFF)))
+}
+
+/// Promotes the operand pair hovered during the previous frame, to be highlighted this frame.
+fn update_hovered_arg_diff(ctx: &egui::Context) {
+    ctx.data_mut(|d| match d.remove_temp::<usize>(Id::new(NEXT_HOVERED_ARG_DIFF)) {
+        Some(idx) => d.insert_temp(Id::new(HOVERED_ARG_DIFF), idx),
+        None => d.remove::<usize>(Id::new(HOVERED_ARG_DIFF)),
+    });
+}
+
 #[must_use]
 #[expect(clippy::too_many_arguments)]
 fn diff_text_ui(
@@ -256,12 +512,19 @@ fn diff_text_ui(
     ins_diff: &ObjInsDiff,
     appearance: &Appearance,
     ins_view_state: &FunctionViewState,
+    search: Option<&DiffTextSearch>,
     column: usize,
     space_width: f32,
-    response_cb: impl Fn(Response) -> Response,
+    response_cb: impl Fn(Response, bool) -> Response,
 ) -> Option<DiffViewAction> {
     let mut ret = None;
     let label_text;
+    let arg_diff_idx = match text {
+        DiffText::Argument(_, diff) | DiffText::BranchDest(_, diff) | DiffText::Symbol(_, diff) => {
+            diff.map(|d| d.idx)
+        }
+        _ => None,
+    };
     let mut base_color = match ins_diff.kind {
         ObjInsDiffKind::None | ObjInsDiffKind::OpMismatch | ObjInsDiffKind::ArgMismatch => {
             appearance.text_color
@@ -269,6 +532,7 @@ fn diff_text_ui(
         ObjInsDiffKind::Replace => appearance.replace_color,
         ObjInsDiffKind::Delete => appearance.delete_color,
         ObjInsDiffKind::Insert => appearance.insert_color,
+        ObjInsDiffKind::Quirk | ObjInsDiffKind::SoftMismatch => appearance.quirk_color,
     };
     let mut pad_to = 0;
     match text {
@@ -326,14 +590,20 @@ fn diff_text_ui(
     }
 
     let len = label_text.len();
-    let highlight = *ins_view_state.highlight(column) == text;
-    let mut response = Label::new(LayoutJob::single_section(
-        label_text,
-        appearance.code_text_format(base_color, highlight),
-    ))
-    .sense(Sense::click())
-    .ui(ui);
-    response = response_cb(response);
+    let highlight = *ins_view_state.highlight(column) == text
+        || (arg_diff_idx.is_some() && arg_diff_idx == hovered_arg_diff(ui.ctx()));
+    let mut format = appearance.code_text_format(base_color, highlight);
+    if !highlight && search.is_some_and(|s| s.matches_text(text)) {
+        format.background = appearance.replace_color.gamma_multiply(0.35);
+    }
+    let mut response =
+        Label::new(LayoutJob::single_section(label_text, format)).sense(Sense::click()).ui(ui);
+    if let Some(idx) = arg_diff_idx {
+        if response.hovered() {
+            ui.ctx().data_mut(|d| d.insert_temp(Id::new(NEXT_HOVERED_ARG_DIFF), idx));
+        }
+    }
+    response = response_cb(response, matches!(text, DiffText::Symbol(..)));
     if response.clicked() {
         ret = Some(DiffViewAction::SetDiffHighlight(column, text.into()));
     }
@@ -344,20 +614,37 @@ fn diff_text_ui(
 }
 
 #[must_use]
+#[expect(clippy::too_many_arguments)]
 fn asm_row_ui(
     ui: &mut egui::Ui,
     ins_diff: &ObjInsDiff,
     symbol: &ObjSymbol,
     appearance: &Appearance,
     ins_view_state: &FunctionViewState,
+    search: Option<&DiffTextSearch>,
+    current_match: bool,
+    bookmarked: bool,
+    anchored: bool,
     column: usize,
-    response_cb: impl Fn(Response) -> Response,
+    response_cb: impl Fn(Response, bool) -> Response,
 ) -> Option<DiffViewAction> {
     let mut ret = None;
     ui.spacing_mut().item_spacing.x = 0.0;
     ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+    let rect = ui.available_rect_before_wrap();
     if ins_diff.kind != ObjInsDiffKind::None {
-        ui.painter().rect_filled(ui.available_rect_before_wrap(), 0.0, ui.visuals().faint_bg_color);
+        ui.painter().rect_filled(rect, 0.0, ui.visuals().faint_bg_color);
+    }
+    if current_match {
+        ui.painter().rect_filled(rect, 0.0, ui.visuals().selection.bg_fill.gamma_multiply(0.5));
+    }
+    if bookmarked {
+        let marker = Rect::from_min_size(rect.min, vec2(3.0, rect.height()));
+        ui.painter().rect_filled(marker, 0.0, appearance.highlight_color);
+    }
+    if anchored {
+        let marker = Rect::from_min_size(rect.min + vec2(3.0, 0.0), vec2(3.0, rect.height()));
+        ui.painter().rect_filled(marker, 0.0, appearance.insert_color);
     }
     let space_width = ui.fonts(|f| f.glyph_width(&appearance.code_font, ' '));
     display_diff(ins_diff, symbol.address, |text| {
@@ -367,6 +654,7 @@ fn asm_row_ui(
             ins_diff,
             appearance,
             ins_view_state,
+            search,
             column,
             space_width,
             &response_cb,
@@ -379,37 +667,248 @@ fn asm_row_ui(
     ret
 }
 
+/// `other_ctx` is the opposite column, for previews comparing both sides of a row.
 #[must_use]
 fn asm_col_ui(
     row: &mut TableRow<'_, '_>,
     ctx: FunctionDiffContext<'_>,
+    other_ctx: Option<FunctionDiffContext<'_>>,
     appearance: &Appearance,
     ins_view_state: &FunctionViewState,
+    search: Option<&FunctionSearch>,
     column: usize,
+) -> Option<DiffViewAction> {
+    let row_index = row.index();
+    asm_cell_ui(
+        row_index,
+        ctx,
+        other_ctx,
+        appearance,
+        ins_view_state,
+        search,
+        column,
+        |add_contents| row.col(add_contents).1,
+    )
+}
+
+/// Draws the instruction at `row_index` using `layout`, which places the contents in a cell
+/// and returns the cell's response.
+#[must_use]
+#[expect(clippy::too_many_arguments)]
+fn asm_cell_ui(
+    row_index: usize,
+    ctx: FunctionDiffContext<'_>,
+    other_ctx: Option<FunctionDiffContext<'_>>,
+    appearance: &Appearance,
+    ins_view_state: &FunctionViewState,
+    search: Option<&FunctionSearch>,
+    column: usize,
+    layout: impl FnOnce(&mut dyn FnMut(&mut egui::Ui)) -> Response,
 ) -> Option<DiffViewAction> {
     let mut ret = None;
     let symbol_ref = ctx.symbol_ref?;
+    let search_match = search.and_then(|s| s.row_match(row_index));
     let (section, symbol) = ctx.obj.section_symbol(symbol_ref);
     let section = section?;
-    let ins_diff = &ctx.diff.symbol_diff(symbol_ref).instructions[row.index()];
-    let response_cb = |response: Response| {
-        if let Some(ins) = &ins_diff.ins {
-            response.context_menu(|ui| ins_context_menu(ui, section, ins, symbol));
+    let ins_diff = &ctx.diff.symbol_diff(symbol_ref).instructions[row_index];
+    let offset = ins_diff.ins.as_ref().map(|ins| ins.address - symbol.address);
+    let bookmarked =
+        offset.is_some_and(|offset| ins_view_state.bookmarks[column].contains(&offset));
+    let anchored = offset.is_some_and(|offset| ins_view_state.is_anchored(column, offset));
+    let can_anchor = other_ctx.is_some_and(|other| other.symbol_ref.is_some());
+    let menu_action = Cell::new(None);
+    let other_ins = other_ctx.and_then(|other| {
+        let (instructions, _) = other.instructions()?;
+        Some((other.obj, instructions.get(row_index)?.ins.as_ref()?))
+    });
+    let response_cb = |response: Response, on_reloc: bool| {
+        if let (Some(ins), Some(offset)) = (&ins_diff.ins, offset) {
+            response.context_menu(|ui| {
+                ins_context_menu(ui, section, ins, symbol);
+                ui.separator();
+                let label = if bookmarked { "Remove bookmark" } else { "Add bookmark" };
+                if ui.button(label).clicked() {
+                    menu_action.set(Some(DiffViewAction::ToggleBookmark(column, offset)));
+                    ui.close_menu();
+                }
+                if can_anchor {
+                    anchor_menu_ui(ui, ins_view_state, column, offset, anchored, &menu_action);
+                }
+            });
             response.on_hover_ui_at_pointer(|ui| {
-                ins_hover_ui(ui, ctx.obj, section, ins, symbol, appearance)
+                ins_hover_ui(ui, ctx.obj, section, ins, symbol, appearance);
+                if on_reloc && ins.reloc.is_some() {
+                    ui.separator();
+                    let this = Some((ctx.obj, ins));
+                    let sides = if column == 0 { [this, other_ins] } else { [other_ins, this] };
+                    reloc_preview_ui(ui, sides, appearance);
+                }
             })
         } else {
             response
         }
     };
-    let (_, response) = row.col(|ui| {
-        if let Some(action) =
-            asm_row_ui(ui, ins_diff, symbol, appearance, ins_view_state, column, response_cb)
-        {
+    let response = layout(&mut |ui| {
+        if let Some(action) = asm_row_ui(
+            ui,
+            ins_diff,
+            symbol,
+            appearance,
+            ins_view_state,
+            search_match.and(search.map(|s| &s.search)),
+            search_match == Some(true),
+            bookmarked,
+            anchored,
+            column,
+            &response_cb,
+        ) {
             ret = Some(action);
         }
     });
-    response_cb(response);
+    response_cb(response, false);
+    menu_action.take().or(ret)
+}
+
+/// Context menu entries for anchoring an instruction to an instruction of the other column.
+fn anchor_menu_ui(
+    ui: &mut egui::Ui,
+    ins_view_state: &FunctionViewState,
+    column: usize,
+    offset: u64,
+    anchored: bool,
+    menu_action: &Cell<Option<DiffViewAction>>,
+) {
+    ui.separator();
+    match ins_view_state.pending_anchor {
+        Some((pending_column, pending_offset)) if pending_column != column => {
+            if ui.button("Anchor to marked instruction").clicked() {
+                let anchor = if column == 0 {
+                    AlignmentAnchor { left: offset, right: pending_offset }
+                } else {
+                    AlignmentAnchor { left: pending_offset, right: offset }
+                };
+                menu_action.set(Some(DiffViewAction::AddAnchor(anchor)));
+                ui.close_menu();
+            }
+        }
+        Some(pending) if pending == (column, offset) => {
+            if ui.button("Cancel anchor").clicked() {
+                menu_action.set(Some(DiffViewAction::MarkAnchor(None)));
+                ui.close_menu();
+            }
+        }
+        _ => {
+            if ui
+                .button("Anchor to…")
+                .on_hover_text(
+                    "Mark this instruction, then right-click an instruction in the other column \
+                    to align both on the same row",
+                )
+                .clicked()
+            {
+                menu_action.set(Some(DiffViewAction::MarkAnchor(Some((column, offset)))));
+                ui.close_menu();
+            }
+        }
+    }
+    if anchored && ui.button("Remove anchor").clicked() {
+        menu_action.set(Some(DiffViewAction::RemoveAnchor(column, Some(offset))));
+        ui.close_menu();
+    }
+}
+
+/// A run of consecutive rows attributed to the same source line.
+struct LineBlock {
+    line: Option<u32>,
+    rows: Range<usize>,
+    /// Number of rows with differences
+    changed: usize,
+}
+
+fn line_blocks(ctxs: [FunctionDiffContext<'_>; 2], len: usize) -> Vec<LineBlock> {
+    let instructions = ctxs.map(|ctx| ctx.instructions().map_or(&[][..], |(ins, _)| ins));
+    let mut blocks = Vec::<LineBlock>::new();
+    for row in 0..len {
+        // Prefer the base's line numbers, which refer to the source file being edited
+        let line =
+            [1, 0].into_iter().find_map(|column| instructions[column].get(row)?.ins.as_ref()?.line);
+        let changed = instructions
+            .iter()
+            .any(|ins| ins.get(row).is_some_and(|d| d.kind != ObjInsDiffKind::None));
+        match blocks.last_mut() {
+            // Rows without line info belong to the preceding line
+            Some(block) if line.is_none() || block.line == line => {
+                block.rows.end = row + 1;
+                block.changed += changed as usize;
+            }
+            _ => blocks.push(LineBlock { line, rows: row..row + 1, changed: changed as usize }),
+        }
+    }
+    blocks
+}
+
+/// Joint view with rows grouped into collapsible blocks by source line, each headed by the
+/// line's source text when the unit's source file is available.
+#[must_use]
+fn line_blocks_ui(
+    ui: &mut egui::Ui,
+    ctxs: [FunctionDiffContext<'_>; 2],
+    appearance: &Appearance,
+    ins_view_state: &FunctionViewState,
+    search: Option<&FunctionSearch>,
+    len: usize,
+) -> Option<DiffViewAction> {
+    let mut ret = None;
+    let source_lines = ins_view_state.source_text.as_ref().map(|text| text.lines.as_slice());
+    ScrollArea::both().auto_shrink([false, false]).show(ui, |ui| {
+        for (idx, block) in line_blocks(ctxs, len).into_iter().enumerate() {
+            let mut header = match block.line {
+                Some(line) => format!("{line:>5}"),
+                None => format!("{:>5}", "?"),
+            };
+            if let Some(source) = block.line.and_then(|line| {
+                source_lines?.get((line as usize).checked_sub(1)?).map(|s| s.trim())
+            }) {
+                header.push_str("  ");
+                header.push_str(source);
+            }
+            if block.changed > 0 {
+                header.push_str(&format!("  ({} changed)", block.changed));
+            }
+            let color =
+                if block.changed > 0 { appearance.replace_color } else { appearance.text_color };
+            CollapsingHeader::new(
+                RichText::new(header).font(appearance.code_font.clone()).color(color),
+            )
+            .id_salt(("line_block", idx, block.rows.start))
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::Grid::new(("line_block_rows", idx)).num_columns(2).show(ui, |ui| {
+                    for row in block.rows.clone() {
+                        for column in 0..2 {
+                            if let Some(action) = asm_cell_ui(
+                                row,
+                                ctxs[column],
+                                Some(ctxs[1 - column]),
+                                appearance,
+                                ins_view_state,
+                                search,
+                                column,
+                                |add_contents| {
+                                    ui.scope(|ui| add_contents(ui))
+                                        .response
+                                        .interact(Sense::click())
+                                },
+                            ) {
+                                ret = Some(action);
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        }
+    });
     ret
 }
 
@@ -422,6 +921,7 @@ fn asm_table_ui(
     right_ctx: Option<FunctionDiffContext<'_>>,
     appearance: &Appearance,
     ins_view_state: &FunctionViewState,
+    search: Option<&FunctionSearch>,
     symbol_state: &SymbolViewState,
     open_sections: (Option<bool>, Option<bool>),
 ) -> Option<DiffViewAction> {
@@ -447,6 +947,32 @@ fn asm_table_ui(
             return None;
         }
     };
+    if let (Some(left_ctx), Some(right_ctx), Some(_), Some(_)) =
+        (left_ctx, right_ctx, left_len, right_len)
+    {
+        if ins_view_state.line_blocks {
+            return line_blocks_ui(
+                ui,
+                [left_ctx, right_ctx],
+                appearance,
+                ins_view_state,
+                search,
+                instructions_len,
+            );
+        }
+        if ins_view_state.scroll_sync != ScrollSync::Row {
+            // Joint view, with each column scrolling separately
+            return synced_table_ui(
+                ui,
+                available_width,
+                [left_ctx, right_ctx],
+                appearance,
+                ins_view_state,
+                search,
+                instructions_len,
+            );
+        }
+    }
     if left_len.is_some() && right_len.is_some() {
         // Joint view
         hotkeys::check_scroll_hotkeys(ui, true);
@@ -456,20 +982,33 @@ fn asm_table_ui(
             2,
             appearance.code_font.size,
             instructions_len,
+            ins_view_state.scroll_to_row,
             |row, column| {
                 if column == 0 {
                     if let Some(ctx) = left_ctx {
-                        if let Some(action) =
-                            asm_col_ui(row, ctx, appearance, ins_view_state, column)
-                        {
+                        if let Some(action) = asm_col_ui(
+                            row,
+                            ctx,
+                            right_ctx,
+                            appearance,
+                            ins_view_state,
+                            search,
+                            column,
+                        ) {
                             ret = Some(action);
                         }
                     }
                 } else if column == 1 {
                     if let Some(ctx) = right_ctx {
-                        if let Some(action) =
-                            asm_col_ui(row, ctx, appearance, ins_view_state, column)
-                        {
+                        if let Some(action) = asm_col_ui(
+                            row,
+                            ctx,
+                            left_ctx,
+                            appearance,
+                            ins_view_state,
+                            search,
+                            column,
+                        ) {
                             ret = Some(action);
                         }
                     }
@@ -492,10 +1031,17 @@ fn asm_table_ui(
                             1,
                             appearance.code_font.size,
                             instructions_len,
+                            ins_view_state.scroll_to_row,
                             |row, column| {
-                                if let Some(action) =
-                                    asm_col_ui(row, ctx, appearance, ins_view_state, column)
-                                {
+                                if let Some(action) = asm_col_ui(
+                                    row,
+                                    ctx,
+                                    None,
+                                    appearance,
+                                    ins_view_state,
+                                    search,
+                                    column,
+                                ) {
                                     ret = Some(action);
                                 }
                                 if row.response().clicked() {
@@ -511,7 +1057,10 @@ fn asm_table_ui(
                             SymbolDiffContext { obj: ctx.obj, diff: ctx.diff },
                             None,
                             symbol_state,
-                            SymbolFilter::Mapping(right_symbol_ref),
+                            SymbolFilter::Mapping(
+                                right_symbol_ref,
+                                right_ctx.obj.section_symbol(right_symbol_ref).1.size,
+                            ),
                             appearance,
                             column,
                             open_sections.0,
@@ -551,10 +1100,17 @@ fn asm_table_ui(
                             1,
                             appearance.code_font.size,
                             instructions_len,
+                            ins_view_state.scroll_to_row,
                             |row, column| {
-                                if let Some(action) =
-                                    asm_col_ui(row, ctx, appearance, ins_view_state, column)
-                                {
+                                if let Some(action) = asm_col_ui(
+                                    row,
+                                    ctx,
+                                    None,
+                                    appearance,
+                                    ins_view_state,
+                                    search,
+                                    column,
+                                ) {
                                     ret = Some(action);
                                 }
                                 if row.response().clicked() {
@@ -570,7 +1126,10 @@ fn asm_table_ui(
                             SymbolDiffContext { obj: ctx.obj, diff: ctx.diff },
                             None,
                             symbol_state,
-                            SymbolFilter::Mapping(left_symbol_ref),
+                            SymbolFilter::Mapping(
+                                left_symbol_ref,
+                                left_ctx.obj.section_symbol(left_symbol_ref).1.size,
+                            ),
                             appearance,
                             column,
                             open_sections.1,
@@ -606,6 +1165,86 @@ fn asm_table_ui(
     ret
 }
 
+/// Renders both columns of the function diff as separately scrolling tables, keeping them in
+/// sync according to [`FunctionViewState::scroll_sync`].
+#[must_use]
+fn synced_table_ui(
+    ui: &mut egui::Ui,
+    available_width: f32,
+    ctxs: [FunctionDiffContext<'_>; 2],
+    appearance: &Appearance,
+    ins_view_state: &FunctionViewState,
+    search: Option<&FunctionSearch>,
+    instructions_len: usize,
+) -> Option<DiffViewAction> {
+    let mut ret = None;
+    // The row at the center of each column during the previous frame
+    let id = ui.id().with("scroll_sync");
+    let prev_centers = ui.ctx().data(|d| d.get_temp::<[Option<usize>; 2]>(id)).unwrap_or_default();
+    let mut centers = [None; 2];
+    let mut hovered = None;
+    render_strips(ui, available_width, 2, |ui, column| {
+        if ui.rect_contains_pointer(ui.max_rect()) {
+            hovered = Some(column);
+            hotkeys::check_scroll_hotkeys(ui, true);
+        }
+        let rows = render_table(
+            ui,
+            available_width / 2.0,
+            1,
+            appearance.code_font.size,
+            instructions_len,
+            ins_view_state.column_scroll_to_row[column].or(ins_view_state.scroll_to_row),
+            |row, _| {
+                if let Some(action) = asm_col_ui(
+                    row,
+                    ctxs[column],
+                    Some(ctxs[1 - column]),
+                    appearance,
+                    ins_view_state,
+                    search,
+                    column,
+                ) {
+                    ret = Some(action);
+                }
+                if row.response().clicked() {
+                    ret = Some(DiffViewAction::ClearDiffHighlight);
+                }
+            },
+        );
+        if !rows.is_empty() {
+            centers[column] = Some((rows.start + rows.end) / 2);
+        }
+    });
+    ui.ctx().data_mut(|d| d.insert_temp(id, centers));
+
+    // The column scrolled by the user leads, the other one follows
+    let leader = if ins_view_state.resync_scroll {
+        Some(0)
+    } else if matches!(ins_view_state.scroll_sync, ScrollSync::Address | ScrollSync::Line) {
+        hovered.filter(|&column| {
+            prev_centers[column].is_some() && centers[column] != prev_centers[column]
+        })
+    } else {
+        None
+    };
+    let (Some(column), Some(left), Some(right)) =
+        (leader, ctxs[0].instructions(), ctxs[1].instructions())
+    else {
+        return ret;
+    };
+    let sides = [left, right];
+    let other = 1 - column;
+    if let Some(row) = centers[column]
+        .and_then(|row| ins_view_state.scroll_sync.sync_row(sides[column], sides[other], row))
+    {
+        if ret.is_none() {
+            ret = Some(DiffViewAction::ScrollColumn(other, row));
+        }
+    }
+    ret
+}
+
 #[derive(Clone, Copy)]
 pub struct FunctionDiffContext<'a> {
     pub obj: &'a ObjInfo,
@@ -627,6 +1266,204 @@ impl<'a> FunctionDiffContext<'a> {
 
     #[inline]
     pub fn has_symbol(&self) -> bool { self.symbol_ref.is_some() }
+
+    /// The selected function's instruction diffs and base address.
+    fn instructions(&self) -> Option<(&'a [ObjInsDiff], u64)> {
+        let symbol_ref = self.symbol_ref?;
+        let (_, symbol) = self.obj.section_symbol(symbol_ref);
+        Some((self.diff.symbol_diff(symbol_ref).instructions.as_slice(), symbol.address))
+    }
+}
+
+#[must_use]
+fn function_search_ui(
+    ui: &mut egui::Ui,
+    view_state: &FunctionViewState,
+    search: Option<&FunctionSearch>,
+    columns: &[(&[ObjInsDiff], u64)],
+    appearance: &Appearance,
+) -> Option<DiffViewAction> {
+    let mut ret = None;
+    let mut query = view_state.search.clone();
+    let response = TextEdit::singleline(&mut query)
+        .id(Id::new(FUNCTION_SEARCH_ID))
+        .hint_text("Search (Ctrl+F)")
+        .desired_width(150.0)
+        .ui(ui);
+    if response.changed() {
+        let first_row = DiffTextSearch::new(&query)
+            .and_then(|search| search.find_rows(columns.iter().copied()).first().copied());
+        ret = Some(DiffViewAction::SetFunctionSearch(query, first_row));
+    }
+    let Some(search) = search else {
+        return ret;
+    };
+    let len = search.rows.len();
+    if len == 0 {
+        ui.colored_label(appearance.delete_color, "No results");
+        return ret;
+    }
+    let index = view_state.search_index.min(len - 1);
+    ui.label(format!("{}/{}", index + 1, len));
+    let prev = ui.small_button("⏶").on_hover_text_at_pointer("Previous match (Shift+Enter)");
+    let next = ui.small_button("⏷").on_hover_text_at_pointer("Next match (Enter)");
+    let enter = response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+    if enter {
+        // Keep focus so Enter can be pressed repeatedly
+        response.request_focus();
+    }
+    let step = if prev.clicked() || (enter && ui.input(|i| i.modifiers.shift)) {
+        len - 1
+    } else if next.clicked() || enter {
+        1
+    } else {
+        return ret;
+    };
+    let index = (index + step) % len;
+    Some(DiffViewAction::SelectSearchMatch(index, search.rows[index]))
+}
+
+/// Shows the pending anchor and clears the function's anchors, if there are any.
+#[must_use]
+fn anchors_ui(ui: &mut egui::Ui, view_state: &FunctionViewState) -> Option<DiffViewAction> {
+    let mut ret = None;
+    if view_state.pending_anchor.is_some() {
+        ui.separator();
+        ui.label("⚓ Right-click an instruction in the other column to anchor it");
+        if ui.small_button("Cancel").clicked() {
+            ret = Some(DiffViewAction::MarkAnchor(None));
+        }
+    } else if !view_state.anchors.is_empty() {
+        ui.separator();
+        if ui
+            .button(format!("⚓ Clear anchors ({})", view_state.anchors.len()))
+            .on_hover_text_at_pointer("Remove the manual alignment anchors of this function")
+            .clicked()
+        {
+            ret = Some(DiffViewAction::RemoveAnchor(0, None));
+        }
+    }
+    ret
+}
+
+/// Shows the size difference of a function whose size differs between both sides, with options
+/// to diff only the range both have in common or to extend the shorter side.
+#[must_use]
+fn size_mismatch_ui(
+    ui: &mut egui::Ui,
+    view_state: &FunctionViewState,
+    sizes: [u64; 2],
+    appearance: &Appearance,
+) -> Option<DiffViewAction> {
+    let [left_size, right_size] = sizes;
+    let delta = right_size as i64 - left_size as i64;
+    let mut ret = None;
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new(format!(
+                "Size {left_size:#x} vs {right_size:#x} ({}{:#x})",
+                if delta < 0 { "-" } else { "+" },
+                delta.unsigned_abs()
+            ))
+            .font(appearance.code_font.clone())
+            .color(if delta < 0 {
+                appearance.delete_color
+            } else {
+                appearance.insert_color
+            }),
+        )
+        .on_hover_text("Symbol size (target vs base)");
+        for (fix, label, hover) in [
+            (
+                SizeMismatchFix::Overlap,
+                "Overlap only",
+                "Only diff the range both functions have in common",
+            ),
+            (
+                SizeMismatchFix::Extend,
+                "Extend shorter",
+                "Extend the shorter function up to the next symbol, at most to the size of the \
+                 longer one",
+            ),
+        ] {
+            let selected = view_state.size_fix == Some(fix);
+            if ui.selectable_label(selected, label).on_hover_text_at_pointer(hover).clicked() {
+                ret = Some(DiffViewAction::SetSizeFix(if selected { None } else { Some(fix) }));
+            }
+        }
+    });
+    ret
+}
+
+/// Bookmarked rows of the current function, with the row's instruction offset and text.
+fn bookmark_rows(
+    view_state: &FunctionViewState,
+    columns: [Option<(&[ObjInsDiff], u64)>; 2],
+) -> Vec<(usize, u64, String)> {
+    let mut rows = Vec::<(usize, u64, String)>::new();
+    for (column, (instructions, address)) in
+        columns.into_iter().enumerate().filter_map(|(i, c)| Some((i, c?)))
+    {
+        for (row, ins) in instructions
+            .iter()
+            .enumerate()
+            .filter_map(|(row, ins_diff)| Some((row, ins_diff.ins.as_ref()?)))
+        {
+            let offset = ins.address - address;
+            if view_state.bookmarks[column].contains(&offset) {
+                rows.push((row, offset, ins.formatted.clone()));
+            }
+        }
+    }
+    rows.sort_by_key(|&(row, _, _)| row);
+    rows.dedup_by_key(|(row, _, _)| *row);
+    rows
+}
+
+#[must_use]
+fn bookmarks_ui(
+    ui: &mut egui::Ui,
+    view_state: &FunctionViewState,
+    rows: &[(usize, u64, String)],
+) -> Option<DiffViewAction> {
+    let mut ret = None;
+    ui.add_enabled_ui(!rows.is_empty(), |ui| {
+        ui.menu_button(format!("🔖 {}", rows.len()), |ui| {
+            ui.style_mut().override_text_style = Some(egui::TextStyle::Monospace);
+            ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+            for &(row, offset, ref text) in rows {
+                let selected = view_state.bookmark_row == Some(row);
+                if ui.selectable_label(selected, format!("{offset:>5x}: {text}")).clicked() {
+                    ret = Some(DiffViewAction::SelectBookmark(row));
+                    ui.close_menu();
+                }
+            }
+        })
+        .response
+        .on_hover_text_at_pointer("Jump to a bookmark")
+        .on_disabled_hover_text("Right-click an instruction to bookmark it");
+    });
+    if rows.is_empty() {
+        return ret;
+    }
+    let current = view_state.bookmark_row;
+    let prev = ui.small_button("⏶").on_hover_text_at_pointer("Previous bookmark (Shift+F2)");
+    let next = ui.small_button("⏷").on_hover_text_at_pointer("Next bookmark (F2)");
+    // Check Shift+F2 first, since the F2 shortcut also matches with Shift held
+    if prev.clicked() || hotkeys::consume_prev_bookmark_shortcut(ui.ctx()) {
+        let row = current
+            .and_then(|current| rows.iter().rev().find(|&&(row, _, _)| row < current))
+            .or(rows.last())
+            .map(|&(row, _, _)| row);
+        ret = row.map(DiffViewAction::SelectBookmark);
+    } else if next.clicked() || hotkeys::consume_next_bookmark_shortcut(ui.ctx()) {
+        let row = current
+            .and_then(|current| rows.iter().find(|&&(row, _, _)| row > current))
+            .or(rows.first())
+            .map(|&(row, _, _)| row);
+        ret = row.map(DiffViewAction::SelectBookmark);
+    }
+    ret
 }
 
 #[must_use]
@@ -639,6 +1476,7 @@ pub fn function_diff_ui(
     let Some(result) = &state.build else {
         return ret;
     };
+    update_hovered_arg_diff(ui.ctx());
 
     let mut left_ctx = FunctionDiffContext::new(
         result.first_obj.as_ref(),
@@ -685,6 +1523,30 @@ pub fn function_diff_ui(
         return Some(DiffViewAction::Navigate(DiffViewNavigation::symbol_diff()));
     }
 
+    let search_columns = [left_ctx, right_ctx]
+        .into_iter()
+        .flatten()
+        .filter_map(|ctx| ctx.instructions())
+        .collect::<Vec<_>>();
+    let search = DiffTextSearch::new(&state.function_state.search).map(|search| {
+        let rows = search.find_rows(search_columns.iter().copied());
+        let current_row =
+            rows.get(state.function_state.search_index.min(rows.len().saturating_sub(1))).copied();
+        FunctionSearch { search, rows, current_row }
+    });
+
+    let bookmarks = bookmark_rows(&state.function_state, [
+        left_ctx.and_then(|ctx| ctx.instructions()),
+        right_ctx.and_then(|ctx| ctx.instructions()),
+    ]);
+
+    let stack_frames = [left_ctx, right_ctx].map(|ctx| {
+        ctx.and_then(|ctx| {
+            ctx.symbol_ref
+                .and_then(|symbol_ref| ctx.diff.symbol_diff(symbol_ref).stack_frame.as_ref())
+        })
+    });
+
     // Header
     let available_width = ui.available_width();
     let mut open_sections = (None, None);
@@ -713,6 +1575,23 @@ pub fn function_diff_ui(
                         ret = Some(DiffViewAction::CreateScratch(symbol.name.clone()));
                     }
                 }
+                ui.separator();
+                if let Some(action) = function_search_ui(
+                    ui,
+                    &state.function_state,
+                    search.as_ref(),
+                    &search_columns,
+                    appearance,
+                ) {
+                    ret = Some(action);
+                }
+                ui.separator();
+                if let Some(action) = bookmarks_ui(ui, &state.function_state, &bookmarks) {
+                    ret = Some(action);
+                }
+                if let Some(action) = anchors_ui(ui, &state.function_state) {
+                    ret = Some(action);
+                }
             });
 
             if let Some((_section, symbol)) = left_ctx
@@ -724,6 +1603,22 @@ pub fn function_diff_ui(
                         .font(appearance.code_font.clone())
                         .color(appearance.highlight_color),
                 );
+                if let Some((flags, per_symbol)) = &state.compiler_flags {
+                    ui.label(
+                        RichText::new(format!("Flags: {flags}"))
+                            .font(appearance.code_font.clone())
+                            .color(if *per_symbol {
+                                appearance.replace_color
+                            } else {
+                                appearance.deemphasized_text_color
+                            }),
+                    )
+                    .on_hover_text(if *per_symbol {
+                        "Compiler flags specific to this function"
+                    } else {
+                        "Compiler flags of the unit"
+                    });
+                }
                 if right_ctx.is_some_and(|m| m.has_symbol())
                     && (ui
                         .button("Change target")
@@ -763,8 +1658,8 @@ pub fn function_diff_ui(
         } else if column == 1 {
             // Right column
             ui.horizontal(|ui| {
-                if ui.add_enabled(!state.build_running, egui::Button::new("Build")).clicked() {
-                    ret = Some(DiffViewAction::Build);
+                if let Some(action) = build_button_ui(ui, state.build_running) {
+                    ret = Some(action);
                 }
                 ui.scope(|ui| {
                     ui.style_mut().override_text_style = Some(egui::TextStyle::Monospace);
@@ -781,11 +1676,52 @@ pub fn function_diff_ui(
                 ui.separator();
                 if ui
                     .add_enabled(state.source_path_available, egui::Button::new("🖹 Source file"))
-                    .on_hover_text_at_pointer("Open the source file in the default editor")
+                    .on_hover_text_at_pointer(
+                        "Open the source file at this function in the configured editor",
+                    )
                     .on_disabled_hover_text("Source file metadata missing")
                     .clicked()
                 {
-                    ret = Some(DiffViewAction::OpenSourcePath);
+                    // Prefer the base object's line info, since it's built from the source file
+                    let line = [right_ctx, left_ctx].into_iter().flatten().find_map(|ctx| {
+                        let (section, symbol) = ctx.obj.section_symbol(ctx.symbol_ref?);
+                        symbol_line(section?, symbol)
+                    });
+                    ret = Some(DiffViewAction::OpenInEditor(line));
+                }
+                ui.separator();
+                let mut scroll_sync = state.function_state.scroll_sync;
+                egui::ComboBox::from_id_salt("scroll_sync")
+                    .selected_text(scroll_sync.label())
+                    .show_ui(ui, |ui| {
+                        for mode in ScrollSync::ALL {
+                            ui.selectable_value(&mut scroll_sync, mode, mode.label());
+                        }
+                    })
+                    .response
+                    .on_hover_text_at_pointer("How the two columns scroll relative to each other");
+                if scroll_sync != state.function_state.scroll_sync {
+                    ret = Some(DiffViewAction::SetScrollSync(scroll_sync));
+                }
+                if scroll_sync != ScrollSync::Row
+                    && ui
+                        .button("⟲ Re-sync")
+                        .on_hover_text_at_pointer(
+                            "Scroll the right column to match the left column",
+                        )
+                        .clicked()
+                {
+                    ret = Some(DiffViewAction::ResyncScroll);
+                }
+                let mut line_blocks = state.function_state.line_blocks;
+                if ui
+                    .toggle_value(&mut line_blocks, "¶ Lines")
+                    .on_hover_text_at_pointer(
+                        "Group instructions into blocks by source line, with the line's source",
+                    )
+                    .clicked()
+                {
+                    ret = Some(DiffViewAction::SetLineBlocks(line_blocks));
                 }
             });
 
@@ -808,6 +1744,17 @@ pub fn function_diff_ui(
                                 .color(match_color_for_symbol(match_percent, appearance)),
                         );
                     }
+                    if symbol_diff.too_different {
+                        ui.label(
+                            RichText::new("Unaligned")
+                                .font(appearance.code_font.clone())
+                                .color(appearance.delete_color),
+                        )
+                        .on_hover_text(
+                            "The functions are too different to align within the maximum \
+                            alignment edits, so instructions are paired by position.",
+                        );
+                    }
                     if left_ctx.is_some_and(|m| m.has_symbol()) {
                         ui.separator();
                         if ui
@@ -824,6 +1771,34 @@ pub fn function_diff_ui(
                         }
                     }
                 });
+                if let [Some(left_frame), Some(right_frame)] = stack_frames {
+                    ui.label(
+                        RichText::new(left_frame.compare(right_frame))
+                            .font(appearance.code_font.clone())
+                            .color(if left_frame == right_frame {
+                                appearance.deemphasized_text_color
+                            } else {
+                                appearance.replace_color
+                            }),
+                    )
+                    .on_hover_text(
+                        "Stack frame size and registers saved by the prologue (target vs base)",
+                    );
+                }
+                if let Some((_section, left_symbol)) = left_ctx.and_then(|ctx| {
+                    ctx.symbol_ref.map(|symbol_ref| ctx.obj.section_symbol(symbol_ref))
+                }) {
+                    if left_symbol.size != symbol.size {
+                        if let Some(action) = size_mismatch_ui(
+                            ui,
+                            &state.function_state,
+                            [left_symbol.size, symbol.size],
+                            appearance,
+                        ) {
+                            ret = Some(action);
+                        }
+                    }
+                }
             } else {
                 ui.label(
                     RichText::new("Missing")
@@ -852,6 +1827,14 @@ pub fn function_diff_ui(
         }
     });
 
+    // Footer
+    let instructions = [left_ctx, right_ctx].map(|ctx| ctx.and_then(|ctx| ctx.instructions()));
+    if let [Some((left, _)), Some((right, _))] = instructions {
+        let stats = InsDiffStats::new(left, right, &result.diff_obj_config);
+        egui::TopBottomPanel::bottom("function_diff_stats")
+            .show_inside(ui, |ui| diff_stats_ui(ui, &stats, &result.diff_obj_config, appearance));
+    }
+
     // Table
     let id = Id::new(state.symbol_state.left_symbol.as_ref().map(|s| s.symbol_name.as_str()))
         .with(state.symbol_state.right_symbol.as_ref().map(|s| s.symbol_name.as_str()));
@@ -864,6 +1847,7 @@ pub fn function_diff_ui(
                 right_ctx,
                 appearance,
                 &state.function_state,
+                search.as_ref(),
                 &state.symbol_state,
                 open_sections,
             )
@@ -874,3 +1858,72 @@ pub fn function_diff_ui(
     }
     ret
 }
+
+fn diff_stats_text(stats: &InsDiffStats) -> String {
+    let mut counts = vec![
+        format!("{} equal", stats.equal),
+        format!("{} op mismatch", stats.op_mismatch),
+        format!("{} arg mismatch", stats.arg_mismatch),
+        format!("{} replace", stats.replace),
+        format!("{} insert", stats.insert),
+        format!("{} delete", stats.delete),
+    ];
+    if stats.quirk > 0 {
+        counts.push(format!("{} quirk", stats.quirk));
+    }
+    if stats.soft_mismatch > 0 {
+        counts.push(format!("{} soft mismatch", stats.soft_mismatch));
+    }
+    if stats.pseudo_reloc > 0 {
+        counts.push(format!("{} pseudo reloc", stats.pseudo_reloc));
+    }
+    format!(
+        "{} rows: {} | {} / {} instructions, {} / {} bytes matched",
+        stats.rows,
+        counts.join(", "),
+        stats.total.saturating_sub(stats.diff_count),
+        stats.total,
+        stats.matched_bytes,
+        stats.total_bytes
+    )
+}
+
+/// Row counts behind the match percent, in a form that can be pasted into an issue.
+fn diff_stats_ui(
+    ui: &mut egui::Ui,
+    stats: &InsDiffStats,
+    config: &DiffObjConfig,
+    appearance: &Appearance,
+) {
+    let text = diff_stats_text(stats);
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new(&text)
+                .font(appearance.code_font.clone())
+                .color(appearance.deemphasized_text_color),
+        )
+        .on_hover_text(format!(
+            "Match percent = matched instructions / instructions in the larger function. Every \
+             row other than equal rows{} counts as one mismatch.{}",
+            match config.ppc_cr_field_mismatch {
+                PpcCrFieldMismatch::Ignore => " and soft mismatches",
+                _ => "",
+            },
+            match (stats.pseudo_reloc, config.ignore_pseudo_reloc_diffs) {
+                (0, _) => "",
+                (_, true) => {
+                    "\nArgument mismatches only in pseudo relocations (pseudo reloc) count as \
+                     matching."
+                }
+                (_, false) => {
+                    "\nArgument mismatches only in pseudo relocations (pseudo reloc), which are \
+                     inferred by flow analysis, count as mismatches. See \"Ignore pseudo \
+                     relocation diffs\"."
+                }
+            }
+        ));
+        if ui.small_button("📋").on_hover_text("Copy statistics").clicked() {
+            ui.output_mut(|output| output.copied_text = text.clone());
+        }
+    });
+}
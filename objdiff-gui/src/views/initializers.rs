@@ -0,0 +1,87 @@
+use egui::{Color32, RichText, ScrollArea, Ui};
+use objdiff_core::{
+    diff::initializers::{
+        diff_initializer_arrays, InitializerArrayDiff, InitializerDiffKind, InitializerEntry,
+    },
+    obj::ObjInfo,
+};
+
+use crate::views::appearance::Appearance;
+
+fn kind_color(kind: InitializerDiffKind, appearance: &Appearance) -> Color32 {
+    match kind {
+        InitializerDiffKind::None => appearance.text_color,
+        InitializerDiffKind::Reordered => appearance.replace_color,
+        InitializerDiffKind::Missing => appearance.delete_color,
+        InitializerDiffKind::Extra => appearance.insert_color,
+    }
+}
+
+fn entry_ui(
+    ui: &mut Ui,
+    entry: Option<&InitializerEntry>,
+    kind: InitializerDiffKind,
+    appearance: &Appearance,
+) {
+    match entry {
+        Some(entry) => {
+            ui.label(
+                RichText::new(format!("{:>5x}: {}", entry.offset, entry.name))
+                    .color(kind_color(kind, appearance)),
+            );
+        }
+        None => {
+            ui.label("");
+        }
+    }
+}
+
+fn array_ui(ui: &mut Ui, diff: &InitializerArrayDiff, appearance: &Appearance) {
+    ui.horizontal(|ui| {
+        ui.label(RichText::new(&diff.name).color(appearance.highlight_color));
+        if !diff.has_differences() {
+            ui.colored_label(appearance.insert_color, "matching");
+        }
+        for (kind, label) in [
+            (InitializerDiffKind::Reordered, "reordered"),
+            (InitializerDiffKind::Missing, "missing"),
+            (InitializerDiffKind::Extra, "extra"),
+        ] {
+            let count = diff.count(kind);
+            if count > 0 {
+                ui.colored_label(kind_color(kind, appearance), format!("{count} {label}"));
+            }
+        }
+    });
+    egui::Grid::new(&diff.name).num_columns(2).striped(true).show(ui, |ui| {
+        for row in &diff.rows {
+            entry_ui(ui, row.left.as_ref(), row.kind, appearance);
+            entry_ui(ui, row.right.as_ref(), row.kind, appearance);
+            ui.end_row();
+        }
+    });
+    ui.add_space(ui.spacing().item_spacing.y * 2.0);
+}
+
+/// Lists the functions of each initializer array (`.ctors`, `.init_array`, etc.) side by side,
+/// target on the left and base on the right, flagging entries that are reordered, missing from
+/// the base or extra in the base.
+pub fn initializers_ui(
+    ui: &mut Ui,
+    left: Option<&ObjInfo>,
+    right: Option<&ObjInfo>,
+    appearance: &Appearance,
+) {
+    let diffs = diff_initializer_arrays(left, right);
+    ScrollArea::both().auto_shrink([false, false]).show(ui, |ui| {
+        ui.style_mut().override_text_style = Some(egui::TextStyle::Monospace);
+        ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+        if diffs.is_empty() {
+            ui.colored_label(appearance.deemphasized_text_color, "No initializer arrays");
+            return;
+        }
+        for diff in &diffs {
+            array_ui(ui, diff, appearance);
+        }
+    });
+}
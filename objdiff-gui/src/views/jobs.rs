@@ -1,9 +1,9 @@
 use std::cmp::Ordering;
 
-use egui::{ProgressBar, RichText, Widget};
+use egui::{DragValue, ProgressBar, RichText, Widget};
 use objdiff_core::jobs::{JobQueue, JobStatus};
 
-use crate::views::appearance::Appearance;
+use crate::{app::AppStateRef, views::appearance::Appearance};
 
 pub fn jobs_ui(ui: &mut egui::Ui, jobs: &mut JobQueue, appearance: &Appearance) {
     if ui.button("Clear").clicked() {
@@ -22,9 +22,7 @@ pub fn jobs_ui(ui: &mut egui::Ui, jobs: &mut JobQueue, appearance: &Appearance)
             ui.label(&status.title);
             if ui.small_button("✖").clicked() {
                 if job.handle.is_some() {
-                    if let Err(e) = job.cancel.send(()) {
-                        log::error!("Failed to cancel job: {e:?}");
-                    }
+                    job.cancel.cancel();
                 } else {
                     remove_job = Some(job.id);
                 }
@@ -65,6 +63,18 @@ pub fn jobs_ui(ui: &mut egui::Ui, jobs: &mut JobQueue, appearance: &Appearance)
                 }
             });
         }
+        if job.handle.is_some() && !status.output.is_empty() {
+            egui::CollapsingHeader::new("Output").id_salt(("job_output", job.id)).show(ui, |ui| {
+                egui::ScrollArea::vertical().max_height(200.0).stick_to_bottom(true).show(
+                    ui,
+                    |ui| {
+                        for line in &status.output {
+                            ui.label(RichText::new(line).font(appearance.code_font.clone()));
+                        }
+                    },
+                );
+            });
+        }
     }
     if !any_jobs {
         ui.label("No jobs");
@@ -148,13 +158,43 @@ pub fn jobs_menu_ui(ui: &mut egui::Ui, jobs: &mut JobQueue, appearance: &Appeara
     clicked
 }
 
+/// Limits the number of jobs running at once. Waiting jobs start in priority order.
+fn concurrency_ui(ui: &mut egui::Ui, jobs: &mut JobQueue, state: &AppStateRef) {
+    let Ok(mut state) = state.write() else {
+        return;
+    };
+    let mut max = state.config.max_concurrent_jobs;
+    ui.horizontal(|ui| {
+        ui.label("Max concurrent jobs:");
+        if ui
+            .add(DragValue::new(&mut max).range(0..=64).custom_formatter(|n, _| {
+                if n == 0.0 {
+                    "Unlimited".to_string()
+                } else {
+                    n.to_string()
+                }
+            }))
+            .on_hover_text(
+                "Jobs past the limit wait until a running job finishes. Rebuilding the selected \
+                unit starts before background indexing and reports.",
+            )
+            .changed()
+        {
+            state.config.max_concurrent_jobs = max;
+            jobs.set_max_concurrent(max);
+        }
+    });
+}
+
 pub fn jobs_window(
     ctx: &egui::Context,
     show: &mut bool,
     jobs: &mut JobQueue,
+    state: &AppStateRef,
     appearance: &Appearance,
 ) {
     egui::Window::new("Jobs").open(show).show(ctx, |ui| {
+        concurrency_ui(ui, jobs, state);
         jobs_ui(ui, jobs, appearance);
     });
 }
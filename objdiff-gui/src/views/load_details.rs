@@ -0,0 +1,119 @@
+use egui::{CollapsingHeader, Grid, RichText, ScrollArea, TextStyle};
+use objdiff_core::{
+    jobs::objdiff::ObjDiffResult,
+    obj::{
+        abi_info::{diff_abi_sections, AbiFieldDiff},
+        LoadDiagnostic, LoadDiagnosticKind,
+    },
+};
+
+use crate::views::appearance::Appearance;
+
+pub fn load_details_window(
+    ctx: &egui::Context,
+    show: &mut bool,
+    build: Option<&ObjDiffResult>,
+    appearance: &Appearance,
+) {
+    egui::Window::new("Load Details").open(show).show(ctx, |ui| {
+        let Some(build) = build else {
+            ui.label("No object loaded.");
+            return;
+        };
+        ui.label("Sections, symbols and relocations skipped or adjusted while loading.");
+        ui.separator();
+        ScrollArea::vertical().auto_shrink([false, true]).show(ui, |ui| {
+            let abi_diffs = diff_abi_sections(
+                build
+                    .first_obj
+                    .as_ref()
+                    .map(|(o, _)| o.abi_sections.as_slice())
+                    .unwrap_or_default(),
+                build
+                    .second_obj
+                    .as_ref()
+                    .map(|(o, _)| o.abi_sections.as_slice())
+                    .unwrap_or_default(),
+            );
+            if !abi_diffs.is_empty() {
+                abi_ui(ui, &abi_diffs, appearance);
+            }
+            for (title, obj) in [("Target", &build.first_obj), ("Base", &build.second_obj)] {
+                let Some((obj, _)) = obj else {
+                    continue;
+                };
+                ui.label(
+                    RichText::new(format!("{title} ({} items)", obj.diagnostics.len()))
+                        .color(appearance.highlight_color),
+                );
+                for (label, kind) in [
+                    ("Sections", LoadDiagnosticKind::Section),
+                    ("Symbols", LoadDiagnosticKind::Symbol),
+                    ("Relocations", LoadDiagnosticKind::Relocation),
+                ] {
+                    let items =
+                        obj.diagnostics.iter().filter(|d| d.kind == kind).collect::<Vec<_>>();
+                    if items.is_empty() {
+                        continue;
+                    }
+                    CollapsingHeader::new(format!("{label} ({})", items.len()))
+                        .id_salt((title, label))
+                        .show(ui, |ui| {
+                            ui.style_mut().override_text_style = Some(TextStyle::Monospace);
+                            for item in items {
+                                diagnostic_ui(ui, item, appearance);
+                            }
+                        });
+                }
+            }
+        });
+    });
+}
+
+/// Decoded ABI metadata sections of both objects, with mismatching fields highlighted.
+fn abi_ui(ui: &mut egui::Ui, diffs: &[AbiFieldDiff], appearance: &Appearance) {
+    let mismatches = diffs.iter().filter(|d| !d.is_match()).count();
+    let title = if mismatches > 0 {
+        RichText::new(format!("ABI metadata ({mismatches} mismatching)"))
+            .color(appearance.replace_color)
+    } else {
+        RichText::new("ABI metadata (matching)").color(appearance.highlight_color)
+    };
+    CollapsingHeader::new(title).id_salt("abi_metadata").default_open(mismatches > 0).show(
+        ui,
+        |ui| {
+            Grid::new("abi_metadata_grid").striped(true).num_columns(3).show(ui, |ui| {
+                ui.label("");
+                ui.label("Target");
+                ui.label("Base");
+                ui.end_row();
+                let mut section = None;
+                for diff in diffs {
+                    if section != Some(&diff.section) {
+                        ui.colored_label(appearance.emphasized_text_color, &diff.section);
+                        ui.end_row();
+                        section = Some(&diff.section);
+                    }
+                    let color = if diff.is_match() {
+                        appearance.text_color
+                    } else {
+                        appearance.replace_color
+                    };
+                    ui.label(RichText::new(format!("  {}", diff.field)).monospace());
+                    for value in [&diff.left, &diff.right] {
+                        ui.colored_label(color, value.as_deref().unwrap_or("(missing)"));
+                    }
+                    ui.end_row();
+                }
+            });
+        },
+    );
+    ui.separator();
+}
+
+fn diagnostic_ui(ui: &mut egui::Ui, diagnostic: &LoadDiagnostic, appearance: &Appearance) {
+    ui.horizontal(|ui| {
+        ui.colored_label(appearance.emphasized_text_color, &diagnostic.name);
+        ui.colored_label(appearance.text_color, &diagnostic.reason);
+    });
+}
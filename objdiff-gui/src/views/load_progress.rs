@@ -0,0 +1,99 @@
+use egui::{ProgressBar, RichText, ScrollArea, TextStyle, Widget};
+use objdiff_core::{
+    jobs::{
+        objdiff::{ObjPreview, SectionPreview},
+        Job, JobQueue,
+    },
+    obj::ObjSectionKind,
+};
+
+use crate::views::appearance::Appearance;
+
+/// A row of the flattened preview list: a section header, or one of its symbols.
+enum PreviewRow<'a> {
+    Section(&'a SectionPreview),
+    Symbol(&'a str),
+}
+
+/// Shows the sections and symbols of the objects being loaded by the running build job,
+/// as they're parsed. Large objects can take a while to load, so this keeps the first load
+/// from showing an empty view.
+pub fn load_progress_ui(ui: &mut egui::Ui, jobs: &mut JobQueue, appearance: &Appearance) {
+    let Some(job) = jobs.iter_mut().find(|job| job.kind == Job::ObjDiff && job.handle.is_some())
+    else {
+        return;
+    };
+    let Ok(status) = job.context.status.read() else {
+        return;
+    };
+
+    ui.horizontal(|ui| {
+        ui.spinner();
+        ui.label(&status.status);
+        if ui.button("Cancel").clicked() {
+            job.cancel.cancel();
+        }
+    });
+    ProgressBar::new(status.progress_percent).ui(ui);
+    ui.separator();
+
+    let Some(preview) = &status.load_preview else {
+        return;
+    };
+    ui.columns(2, |columns| {
+        for (ui, (title, obj)) in columns
+            .iter_mut()
+            .zip([("Target object", &preview.target), ("Base object", &preview.base)])
+        {
+            ui.push_id(title, |ui| {
+                ui.label(RichText::new(title).text_style(TextStyle::Monospace));
+                match obj {
+                    Some(obj) => obj_preview_ui(ui, obj, appearance),
+                    None => {
+                        ui.colored_label(appearance.deemphasized_text_color, "Waiting…");
+                    }
+                }
+            });
+        }
+    });
+}
+
+fn obj_preview_ui(ui: &mut egui::Ui, obj: &ObjPreview, appearance: &Appearance) {
+    let rows = obj
+        .sections
+        .iter()
+        .flat_map(|section| {
+            let symbols =
+                section.symbols.iter().flatten().map(|name| PreviewRow::Symbol(name.as_str()));
+            std::iter::once(PreviewRow::Section(section)).chain(symbols)
+        })
+        .collect::<Vec<_>>();
+    ui.style_mut().override_text_style = Some(TextStyle::Monospace);
+    let row_height = ui.text_style_height(&TextStyle::Monospace);
+    ScrollArea::vertical().auto_shrink([false, false]).show_rows(
+        ui,
+        row_height,
+        rows.len(),
+        |ui, range| {
+            for row in &rows[range] {
+                match row {
+                    PreviewRow::Section(section) => {
+                        let kind = match section.kind {
+                            ObjSectionKind::Code => "code",
+                            ObjSectionKind::Data => "data",
+                            ObjSectionKind::Bss => "bss",
+                        };
+                        let mut text = format!("{} ({kind}, {:#x})", section.name, section.size);
+                        if section.symbols.is_none() {
+                            text.push_str(" loading…");
+                        }
+                        ui.colored_label(appearance.highlight_color, text);
+                    }
+                    PreviewRow::Symbol(name) => {
+                        ui.colored_label(appearance.text_color, format!("  {name}"));
+                    }
+                }
+            }
+        },
+    );
+}
@@ -0,0 +1,482 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use egui::{Checkbox, Grid, RichText, ScrollArea, TextStyle};
+use objdiff_core::{
+    config::{
+        mappings::{
+            export_mappings, parse_mappings, validate_import, MappingImportEntry,
+            MappingImportStatus, MappingsFormat,
+        },
+        SymbolMappings,
+    },
+    jobs::objdiff::ObjDiffResult,
+    obj::ObjInfo,
+};
+
+use crate::{
+    app::AppStateRef,
+    views::{
+        appearance::Appearance,
+        file::{FileDialogResult, FileDialogState},
+    },
+};
+
+struct PendingImport {
+    path: PathBuf,
+    entries: Vec<MappingImportEntry>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum EditField {
+    Target,
+    Base,
+}
+
+/// A mapping being added or edited.
+struct MappingEdit {
+    /// Target symbol of the mapping being edited, or `None` if it's a new mapping
+    previous: Option<String>,
+    target: String,
+    base: String,
+    /// The field completions are shown for
+    focus: Option<EditField>,
+}
+
+enum MappingAction {
+    Set { previous: Option<String>, target: String, base: String },
+    Remove(String),
+}
+
+#[derive(Default)]
+pub struct MappingsViewState {
+    file_dialog_state: FileDialogState,
+    pending: Option<PendingImport>,
+    /// Result of the last import or export, and whether it was an error.
+    message: Option<(String, bool)>,
+    editing: Option<MappingEdit>,
+}
+
+const MAX_COMPLETIONS: usize = 8;
+
+fn symbol_names(obj: Option<&ObjInfo>) -> Option<HashSet<&str>> {
+    obj.map(|obj| obj.sections.iter().flat_map(|s| &s.symbols).map(|s| s.name.as_str()).collect())
+}
+
+/// Symbol names containing `query`, case-insensitively, with prefix matches and shorter names
+/// first.
+fn completions<'a>(names: Option<&HashSet<&'a str>>, query: &str) -> Vec<&'a str> {
+    let Some(names) = names else {
+        return vec![];
+    };
+    if query.is_empty() {
+        return vec![];
+    }
+    let lower = query.to_ascii_lowercase();
+    let mut matches = names
+        .iter()
+        .copied()
+        .filter(|name| *name != query && name.to_ascii_lowercase().contains(&lower))
+        .collect::<Vec<_>>();
+    matches.sort_by_key(|name| (!name.to_ascii_lowercase().starts_with(&lower), name.len(), *name));
+    matches.truncate(MAX_COMPLETIONS);
+    matches
+}
+
+/// Checks an edited mapping against the objects and the existing mappings. Returns an error
+/// that prevents saving it, or a warning about mappings it replaces.
+fn validate_edit(
+    edit: &MappingEdit,
+    mappings: &SymbolMappings,
+    target_symbols: Option<&HashSet<&str>>,
+    base_symbols: Option<&HashSet<&str>>,
+) -> (Option<String>, Option<String>) {
+    let (target, base) = (edit.target.trim(), edit.base.trim());
+    if target.is_empty() || base.is_empty() {
+        return (Some("Both symbols are required".to_string()), None);
+    }
+    if target == base {
+        return (Some("Symbols with the same name are paired automatically".to_string()), None);
+    }
+    if target_symbols.is_some_and(|s| !s.contains(target)) {
+        return (Some(format!("Target symbol {target} not found")), None);
+    }
+    if base_symbols.is_some_and(|s| !s.contains(base)) {
+        return (Some(format!("Base symbol {base} not found")), None);
+    }
+    let mut replaced = vec![];
+    if let Some(existing) = mappings.get_by_left(target) {
+        if existing != base && edit.previous.as_deref() != Some(target) {
+            replaced.push(format!("{target} → {existing}"));
+        }
+    }
+    if let Some(existing) = mappings.get_by_right(base) {
+        if existing != target && edit.previous.as_deref() != Some(existing.as_str()) {
+            replaced.push(format!("{existing} → {base}"));
+        }
+    }
+    let warning = (!replaced.is_empty()).then(|| format!("Replaces {}", replaced.join(", ")));
+    (None, warning)
+}
+
+/// Lists the selected unit's mappings with buttons to edit or remove each, and the form to
+/// add or edit one.
+fn mappings_list_ui(
+    ui: &mut egui::Ui,
+    state: &mut MappingsViewState,
+    mappings: &SymbolMappings,
+    build: Option<&ObjDiffResult>,
+    appearance: &Appearance,
+) -> Option<MappingAction> {
+    let target_symbols = symbol_names(build.and_then(|b| b.first_obj.as_ref()).map(|(o, _)| o));
+    let base_symbols = symbol_names(build.and_then(|b| b.second_obj.as_ref()).map(|(o, _)| o));
+    let mut action = None;
+    if !mappings.is_empty() {
+        ScrollArea::vertical()
+            .id_salt("mappings_list")
+            .max_height(240.0)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                Grid::new("mappings_list").striped(true).num_columns(4).show(ui, |ui| {
+                    ui.label("Target");
+                    ui.label("Base");
+                    ui.label("");
+                    ui.label("");
+                    ui.end_row();
+                    for (target, base) in mappings.iter() {
+                        let missing = target_symbols
+                            .as_ref()
+                            .is_some_and(|s| !s.contains(target.as_str()))
+                            || base_symbols.as_ref().is_some_and(|s| !s.contains(base.as_str()));
+                        ui.label(RichText::new(target).text_style(TextStyle::Monospace));
+                        ui.label(RichText::new(base).text_style(TextStyle::Monospace));
+                        if missing {
+                            ui.colored_label(appearance.delete_color, "Missing");
+                        } else {
+                            ui.label("");
+                        }
+                        ui.horizontal(|ui| {
+                            if ui.small_button("✏").on_hover_text("Edit mapping").clicked() {
+                                state.editing = Some(MappingEdit {
+                                    previous: Some(target.clone()),
+                                    target: target.clone(),
+                                    base: base.clone(),
+                                    focus: None,
+                                });
+                            }
+                            if ui.small_button("🗑").on_hover_text("Remove mapping").clicked() {
+                                action = Some(MappingAction::Remove(target.clone()));
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+            });
+    }
+
+    let Some(edit) = &mut state.editing else {
+        if ui.button("Add mapping…").clicked() {
+            state.editing = Some(MappingEdit {
+                previous: None,
+                target: String::new(),
+                base: String::new(),
+                focus: Some(EditField::Target),
+            });
+        }
+        return action;
+    };
+    ui.separator();
+    let title = match &edit.previous {
+        Some(previous) => format!("Edit mapping of {previous}"),
+        None => "Add mapping".to_string(),
+    };
+    ui.label(RichText::new(title).color(appearance.highlight_color));
+    Grid::new("mapping_edit").num_columns(2).show(ui, |ui| {
+        for (field, label) in [(EditField::Target, "Target"), (EditField::Base, "Base")] {
+            let text = match field {
+                EditField::Target => &mut edit.target,
+                EditField::Base => &mut edit.base,
+            };
+            ui.label(label);
+            let response = ui.add(
+                egui::TextEdit::singleline(text).font(TextStyle::Monospace).desired_width(320.0),
+            );
+            if response.gained_focus() || response.changed() {
+                edit.focus = Some(field);
+            }
+            ui.end_row();
+        }
+    });
+    if let Some(field) = edit.focus {
+        let (names, text) = match field {
+            EditField::Target => (target_symbols.as_ref(), &mut edit.target),
+            EditField::Base => (base_symbols.as_ref(), &mut edit.base),
+        };
+        for name in completions(names, text.trim()) {
+            if ui
+                .selectable_label(false, RichText::new(name).text_style(TextStyle::Monospace))
+                .clicked()
+            {
+                *text = name.to_string();
+            }
+        }
+    }
+    let (error, warning) =
+        validate_edit(edit, mappings, target_symbols.as_ref(), base_symbols.as_ref());
+    if let Some(error) = &error {
+        ui.colored_label(appearance.delete_color, error);
+    } else if let Some(warning) = &warning {
+        ui.colored_label(appearance.replace_color, warning);
+    }
+    let mut cancel = false;
+    ui.horizontal(|ui| {
+        if ui.add_enabled(error.is_none(), egui::Button::new("Save")).clicked() {
+            action = Some(MappingAction::Set {
+                previous: edit.previous.clone(),
+                target: edit.target.trim().to_string(),
+                base: edit.base.trim().to_string(),
+            });
+        }
+        cancel = ui.button("Cancel").clicked();
+    });
+    if cancel {
+        state.editing = None;
+    }
+    action
+}
+
+fn status_text(status: &MappingImportStatus, appearance: &Appearance) -> RichText {
+    match status {
+        MappingImportStatus::New => RichText::new("New").color(appearance.insert_color),
+        MappingImportStatus::Unchanged => {
+            RichText::new("Unchanged").color(appearance.deemphasized_text_color)
+        }
+        MappingImportStatus::Conflict { existing_base, existing_target } => {
+            let mut text = "Replaces".to_string();
+            if let Some(base) = existing_base {
+                text.push_str(&format!(" → {base}"));
+            }
+            if let Some(target) = existing_target {
+                text.push_str(&format!(" {target} ←"));
+            }
+            RichText::new(text).color(appearance.replace_color)
+        }
+        MappingImportStatus::Missing { target, base } => {
+            let text = match (target, base) {
+                (true, true) => "Both symbols missing",
+                (true, false) => "Target symbol missing",
+                _ => "Base symbol missing",
+            };
+            RichText::new(text).color(appearance.delete_color)
+        }
+        MappingImportStatus::Duplicate => {
+            RichText::new("Duplicate in file").color(appearance.delete_color)
+        }
+    }
+}
+
+fn handle_file_dialog(
+    state: &mut MappingsViewState,
+    app_state: &AppStateRef,
+    build: Option<&ObjDiffResult>,
+) {
+    match state.file_dialog_state.poll() {
+        FileDialogResult::ImportMappings(path) => {
+            let Ok(app_state) = app_state.read() else {
+                return;
+            };
+            let Some(object) = &app_state.config.selected_obj else {
+                return;
+            };
+            let result = std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|data| parse_mappings(&data, MappingsFormat::from_path(&path)));
+            match result {
+                Ok(imported) => {
+                    let target_symbols =
+                        symbol_names(build.and_then(|b| b.first_obj.as_ref()).map(|(o, _)| o));
+                    let base_symbols =
+                        symbol_names(build.and_then(|b| b.second_obj.as_ref()).map(|(o, _)| o));
+                    let entries = validate_import(
+                        &object.symbol_mappings,
+                        imported,
+                        |name| target_symbols.as_ref().map_or(true, |s| s.contains(name)),
+                        |name| base_symbols.as_ref().map_or(true, |s| s.contains(name)),
+                    );
+                    state.message = None;
+                    state.pending = Some(PendingImport { path, entries });
+                }
+                Err(e) => state.message = Some((format!("{e:#}"), true)),
+            }
+        }
+        FileDialogResult::ExportMappings(path) => {
+            let Ok(app_state) = app_state.read() else {
+                return;
+            };
+            let Some(object) = &app_state.config.selected_obj else {
+                return;
+            };
+            let result = export_mappings(&object.symbol_mappings, MappingsFormat::from_path(&path))
+                .and_then(|data| std::fs::write(&path, data).map_err(anyhow::Error::from));
+            state.message = Some(match result {
+                Ok(()) => (
+                    format!(
+                        "Exported {} mappings to {}",
+                        object.symbol_mappings.len(),
+                        path.display()
+                    ),
+                    false,
+                ),
+                Err(e) => (format!("Failed to export mappings: {e:#}"), true),
+            });
+        }
+        _ => {}
+    }
+}
+
+/// Lists and edits the selected unit's manual symbol mappings, with completion of symbol names
+/// from both objects. Also exports them, or imports mappings produced by external tools,
+/// letting the user review conflicts before applying them.
+pub fn mappings_window(
+    ctx: &egui::Context,
+    show: &mut bool,
+    state: &mut MappingsViewState,
+    app_state: &AppStateRef,
+    build: Option<&ObjDiffResult>,
+    appearance: &Appearance,
+) {
+    handle_file_dialog(state, app_state, build);
+    let mut apply = false;
+    let mut action = None;
+    egui::Window::new("Symbol Mappings").open(show).show(ctx, |ui| {
+        let (object_name, mappings) = {
+            let Ok(app_state) = app_state.read() else {
+                return;
+            };
+            let Some(object) = &app_state.config.selected_obj else {
+                ui.label("No object selected.");
+                return;
+            };
+            (object.name.clone(), object.symbol_mappings.clone())
+        };
+        let mapping_count = mappings.len();
+        ui.label(format!("{object_name}: {mapping_count} mappings"));
+        action = mappings_list_ui(ui, state, &mappings, build, appearance);
+        ui.separator();
+        ui.horizontal(|ui| {
+            for (label, extension) in [("Export CSV…", "csv"), ("Export JSON…", "json")] {
+                if ui.add_enabled(mapping_count > 0, egui::Button::new(label)).clicked() {
+                    let file_name =
+                        format!("{}_mappings.{extension}", object_name.replace('/', "_"));
+                    state.file_dialog_state.queue(
+                        move || {
+                            Box::pin(
+                                rfd::AsyncFileDialog::new()
+                                    .set_file_name(file_name)
+                                    .add_filter(extension.to_ascii_uppercase(), &[extension])
+                                    .save_file(),
+                            )
+                        },
+                        FileDialogResult::ExportMappings,
+                    );
+                }
+            }
+            if ui.button("Import…").clicked() {
+                state.file_dialog_state.queue(
+                    || {
+                        Box::pin(
+                            rfd::AsyncFileDialog::new()
+                                .add_filter("Symbol mappings", &["csv", "json"])
+                                .pick_file(),
+                        )
+                    },
+                    FileDialogResult::ImportMappings,
+                );
+            }
+        });
+        if let Some((message, is_error)) = &state.message {
+            let color = if *is_error {
+                appearance.delete_color
+            } else {
+                appearance.deemphasized_text_color
+            };
+            ui.colored_label(color, message);
+        }
+
+        let Some(pending) = &mut state.pending else {
+            return;
+        };
+        ui.separator();
+        ui.label(
+            RichText::new(format!("Import from {}", pending.path.display()))
+                .color(appearance.highlight_color),
+        );
+        if pending.entries.is_empty() {
+            ui.label("No mappings found.");
+        }
+        ui.horizontal(|ui| {
+            if ui.button("Select all").clicked() {
+                for entry in &mut pending.entries {
+                    entry.apply = entry.status != MappingImportStatus::Duplicate;
+                }
+            }
+            if ui.button("Select none").clicked() {
+                for entry in &mut pending.entries {
+                    entry.apply = false;
+                }
+            }
+        });
+        ScrollArea::vertical().max_height(320.0).auto_shrink([false, true]).show(ui, |ui| {
+            Grid::new("mappings_import").striped(true).num_columns(4).show(ui, |ui| {
+                ui.label("");
+                ui.label("Target");
+                ui.label("Base");
+                ui.label("Status");
+                ui.end_row();
+                for entry in &mut pending.entries {
+                    let enabled = entry.status != MappingImportStatus::Duplicate;
+                    ui.add_enabled(enabled, Checkbox::without_text(&mut entry.apply));
+                    ui.label(RichText::new(&entry.target).text_style(TextStyle::Monospace));
+                    ui.label(RichText::new(&entry.base).text_style(TextStyle::Monospace));
+                    ui.label(status_text(&entry.status, appearance));
+                    ui.end_row();
+                }
+            });
+        });
+        let selected = pending.entries.iter().filter(|e| e.apply).count();
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(selected > 0, egui::Button::new(format!("Apply {selected}")))
+                .clicked()
+            {
+                apply = true;
+            }
+            if ui.button("Cancel").clicked() {
+                state.pending = None;
+            }
+        });
+    });
+
+    if let Some(action) = action {
+        let Ok(mut app_state) = app_state.write() else {
+            return;
+        };
+        match action {
+            MappingAction::Set { previous, target, base } => {
+                app_state.replace_symbol_mapping(previous.as_deref(), target, base);
+                state.editing = None;
+            }
+            MappingAction::Remove(target) => app_state.remove_symbol_mapping(&target),
+        }
+    }
+
+    if apply {
+        let Some(pending) = state.pending.take() else {
+            return;
+        };
+        let Ok(mut app_state) = app_state.write() else {
+            return;
+        };
+        let count = app_state.import_symbol_mappings(&pending.entries);
+        state.message = Some((format!("Imported {count} mappings"), false));
+    }
+}
@@ -2,18 +2,34 @@ use egui::{text::LayoutJob, Color32, FontId, TextFormat};
 
 pub(crate) mod appearance;
 pub(crate) mod column_layout;
+pub(crate) mod command_palette;
 pub(crate) mod config;
+pub(crate) mod cross_diff;
 pub(crate) mod data_diff;
 pub(crate) mod debug;
 pub(crate) mod demangle;
 pub(crate) mod extab_diff;
 pub(crate) mod file;
 pub(crate) mod frame_history;
+pub(crate) mod initializers;
 pub(crate) mod function_diff;
 pub(crate) mod graphics;
 pub(crate) mod jobs;
+pub(crate) mod load_details;
+pub(crate) mod load_progress;
+pub(crate) mod mappings;
+pub(crate) mod notes;
+pub(crate) mod recent_projects;
+pub(crate) mod report_changes;
 pub(crate) mod rlwinm;
+pub(crate) mod search;
+pub(crate) mod section_layout;
+pub(crate) mod session;
 pub(crate) mod symbol_diff;
+pub(crate) mod symbol_lookup;
+pub(crate) mod terminal;
+pub(crate) mod unit_overview;
+pub(crate) mod watch;
 
 #[inline]
 fn write_text(str: &str, color: Color32, job: &mut LayoutJob, font_id: FontId) {
@@ -0,0 +1,302 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use egui::{Color32, RichText, TextStyle};
+use filetime::FileTime;
+
+use crate::views::appearance::Appearance;
+
+/// Notes for the selected unit, loaded from `metadata.notes_path`.
+#[derive(Default)]
+pub struct NotesViewState {
+    path: Option<PathBuf>,
+    timestamp: Option<FileTime>,
+    last_mod_check: Option<Instant>,
+    blocks: Vec<Block>,
+    error: Option<String>,
+}
+
+impl NotesViewState {
+    /// Loads the notes file if the selected unit changed, or reloads it if it was modified.
+    pub fn update(&mut self, path: Option<PathBuf>) {
+        if path != self.path {
+            self.path = path;
+            self.reload();
+            return;
+        }
+        let Some(path) = &self.path else { return };
+        if self.last_mod_check.is_some_and(|t| t.elapsed().as_millis() < 500) {
+            return;
+        }
+        self.last_mod_check = Some(Instant::now());
+        if file_timestamp(path) != self.timestamp {
+            self.reload();
+        }
+    }
+
+    fn reload(&mut self) {
+        self.blocks.clear();
+        self.error = None;
+        self.timestamp = None;
+        let Some(path) = &self.path else { return };
+        self.timestamp = file_timestamp(path);
+        match fs::read_to_string(path) {
+            Ok(text) => self.blocks = parse_markdown(&text),
+            Err(e) => self.error = Some(format!("Failed to read {}: {e}", path.display())),
+        }
+    }
+
+    pub fn has_notes(&self) -> bool { self.path.is_some() }
+}
+
+fn file_timestamp(path: &Path) -> Option<FileTime> {
+    fs::metadata(path).ok().map(|m| FileTime::from_last_modification_time(&m))
+}
+
+pub fn notes_panel(
+    ctx: &egui::Context,
+    show: bool,
+    state: &NotesViewState,
+    appearance: &Appearance,
+) {
+    egui::SidePanel::right("notes_panel").show_animated(ctx, show && state.has_notes(), |ui| {
+        ui.heading("Notes");
+        if let Some(path) = &state.path {
+            ui.label(
+                RichText::new(path.file_name().unwrap_or_default().to_string_lossy())
+                    .color(appearance.deemphasized_text_color),
+            );
+        }
+        ui.separator();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            if let Some(error) = &state.error {
+                ui.colored_label(appearance.delete_color, error);
+            }
+            markdown_ui(ui, &state.blocks, appearance);
+        });
+    });
+}
+
+/// A block of a (small) subset of markdown: headings, paragraphs, lists, quotes and code.
+#[derive(Debug, Clone, PartialEq)]
+enum Block {
+    Heading(u8, String),
+    Paragraph(String),
+    ListItem(Option<String>, String),
+    Quote(String),
+    Code(String),
+    Rule,
+}
+
+fn parse_markdown(text: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut paragraph = String::new();
+    let mut code: Option<String> = None;
+    let flush = |paragraph: &mut String, blocks: &mut Vec<Block>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(std::mem::take(paragraph)));
+        }
+    };
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(code_text) = &mut code {
+            if trimmed.starts_with("```") {
+                blocks.push(Block::Code(std::mem::take(code_text)));
+                code = None;
+            } else {
+                if !code_text.is_empty() {
+                    code_text.push('\n');
+                }
+                code_text.push_str(line);
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") {
+            flush(&mut paragraph, &mut blocks);
+            code = Some(String::new());
+        } else if trimmed.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+        } else if let Some((level, heading)) = parse_heading(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Heading(level, heading.to_string()));
+        } else if trimmed.len() >= 3 && trimmed.chars().all(|c| c == '-' || c == '*' || c == '_') {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Rule);
+        } else if let Some(item) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* "))
+        {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::ListItem(None, item.to_string()));
+        } else if let Some((number, item)) = parse_numbered(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::ListItem(Some(number.to_string()), item.to_string()));
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Quote(quote.trim_start().to_string()));
+        } else if paragraph.is_empty() {
+            // Lines indented under a list item continue it
+            match blocks.last_mut() {
+                Some(Block::ListItem(_, item)) if line.starts_with(' ') => {
+                    item.push(' ');
+                    item.push_str(trimmed);
+                }
+                _ => paragraph.push_str(trimmed),
+            }
+        } else {
+            paragraph.push(' ');
+            paragraph.push_str(trimmed);
+        }
+    }
+    flush(&mut paragraph, &mut blocks);
+    if let Some(code_text) = code {
+        blocks.push(Block::Code(code_text));
+    }
+    blocks
+}
+
+fn parse_heading(line: &str) -> Option<(u8, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    rest.starts_with(' ').then(|| (level as u8, rest.trim()))
+}
+
+fn parse_numbered(line: &str) -> Option<(&str, &str)> {
+    let (number, rest) = line.split_once(". ")?;
+    (!number.is_empty() && number.chars().all(|c| c.is_ascii_digit())).then_some((number, rest))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Span<'a> {
+    Text(&'a str),
+    Strong(&'a str),
+    Emphasis(&'a str),
+    Code(&'a str),
+    Link(&'a str, &'a str),
+}
+
+/// Splits inline markdown into spans. Unterminated markers are shown as-is.
+fn parse_inline(mut text: &str) -> Vec<Span<'_>> {
+    let mut spans = Vec::new();
+    while !text.is_empty() {
+        let Some(start) = text.find(['`', '*', '_', '[']) else {
+            spans.push(Span::Text(text));
+            break;
+        };
+        let (before, rest) = text.split_at(start);
+        let parsed = if let Some(rest) = rest.strip_prefix('`') {
+            rest.split_once('`').map(|(code, after)| (Span::Code(code), after))
+        } else if let Some(rest) = rest.strip_prefix("**").or_else(|| rest.strip_prefix("__")) {
+            let marker = &text[start..start + 2];
+            rest.split_once(marker).map(|(strong, after)| (Span::Strong(strong), after))
+        } else if let Some(rest) = rest.strip_prefix('[') {
+            rest.split_once("](").and_then(|(label, rest)| {
+                rest.split_once(')').map(|(url, after)| (Span::Link(label, url), after))
+            })
+        } else {
+            // Single `*` or `_`, but not within a word (e.g. snake_case names)
+            let marker = &rest[..1];
+            let in_word = before.chars().last().is_some_and(|c| c.is_alphanumeric());
+            (!in_word)
+                .then(|| rest[1..].split_once(marker))
+                .flatten()
+                .filter(|(emphasis, _)| {
+                    !emphasis.is_empty()
+                        && !emphasis.starts_with(char::is_whitespace)
+                        && !emphasis.ends_with(char::is_whitespace)
+                })
+                .map(|(emphasis, after)| (Span::Emphasis(emphasis), after))
+        };
+        match parsed {
+            Some((span, after)) => {
+                if !before.is_empty() {
+                    spans.push(Span::Text(before));
+                }
+                spans.push(span);
+                text = after;
+            }
+            None => {
+                // Not a marker: emit through it as text
+                let end = start + rest.chars().next().map_or(1, |c| c.len_utf8());
+                spans.push(Span::Text(&text[..end]));
+                text = &text[end..];
+            }
+        }
+    }
+    spans
+}
+
+fn inline_ui(ui: &mut egui::Ui, text: &str, color: Color32, appearance: &Appearance) {
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing.x = 0.0;
+        for span in parse_inline(text) {
+            match span {
+                Span::Text(text) => {
+                    ui.label(RichText::new(text).color(color));
+                }
+                Span::Strong(text) => {
+                    ui.label(RichText::new(text).color(appearance.emphasized_text_color).strong());
+                }
+                Span::Emphasis(text) => {
+                    ui.label(RichText::new(text).color(color).italics());
+                }
+                Span::Code(text) => {
+                    ui.label(RichText::new(text).code().color(appearance.replace_color));
+                }
+                Span::Link(label, url) => {
+                    ui.hyperlink_to(label, url);
+                }
+            }
+        }
+    });
+}
+
+fn markdown_ui(ui: &mut egui::Ui, blocks: &[Block], appearance: &Appearance) {
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => {
+                ui.add_space(4.0);
+                let text = RichText::new(text).color(appearance.emphasized_text_color).strong();
+                ui.label(if *level == 1 { text.heading() } else { text });
+            }
+            Block::Paragraph(text) => {
+                inline_ui(ui, text, appearance.text_color, appearance);
+                ui.add_space(4.0);
+            }
+            Block::ListItem(number, text) => {
+                ui.horizontal(|ui| {
+                    let bullet = match number {
+                        Some(number) => format!("{number}."),
+                        None => "•".to_string(),
+                    };
+                    ui.label(RichText::new(bullet).color(appearance.deemphasized_text_color));
+                    inline_ui(ui, text, appearance.text_color, appearance);
+                });
+            }
+            Block::Quote(text) => {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("│").color(appearance.deemphasized_text_color));
+                    inline_ui(ui, text, appearance.deemphasized_text_color, appearance);
+                });
+            }
+            Block::Code(text) => {
+                egui::Frame::group(ui.style()).show(ui, |ui| {
+                    ui.set_min_width(ui.available_width());
+                    ui.label(
+                        RichText::new(text)
+                            .text_style(TextStyle::Monospace)
+                            .color(appearance.replace_color),
+                    );
+                });
+                ui.add_space(4.0);
+            }
+            Block::Rule => {
+                ui.separator();
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use std::path::{Path, PathBuf};
+
+use egui::{RichText, ScrollArea};
+
+use crate::{app::AppStateRef, views::appearance::Appearance};
+
+enum RecentProjectAction {
+    Open(PathBuf),
+    OpenNewWindow(PathBuf),
+    SetPinned(PathBuf, bool),
+    Remove(PathBuf),
+}
+
+fn recent_projects(state: &AppStateRef) -> Vec<(PathBuf, bool)> {
+    let Ok(state) = state.read() else {
+        return vec![];
+    };
+    state.recent_projects().map(|(path, pinned)| (path.to_path_buf(), pinned)).collect()
+}
+
+/// Launches another instance of objdiff with the project loaded.
+fn open_in_new_window(path: &Path) {
+    let result = std::env::current_exe()
+        .and_then(|exe| std::process::Command::new(exe).arg("--project").arg(path).spawn());
+    if let Err(e) = result {
+        log::error!("Failed to open {} in a new window: {e}", path.display());
+    }
+}
+
+fn apply_action(state: &AppStateRef, action: RecentProjectAction) {
+    match action {
+        RecentProjectAction::Open(path) => state.write().unwrap().set_project_dir(path),
+        RecentProjectAction::OpenNewWindow(path) => open_in_new_window(&path),
+        RecentProjectAction::SetPinned(path, pinned) => {
+            state.write().unwrap().set_project_pinned(&path, pinned)
+        }
+        RecentProjectAction::Remove(path) => state.write().unwrap().remove_recent_project(&path),
+    }
+}
+
+fn project_context_menu(
+    ui: &mut egui::Ui,
+    path: &Path,
+    pinned: bool,
+    action: &mut Option<RecentProjectAction>,
+) {
+    if ui.button("Open in new window").clicked() {
+        *action = Some(RecentProjectAction::OpenNewWindow(path.to_path_buf()));
+        ui.close_menu();
+    }
+    if ui.button(if pinned { "Unpin" } else { "Pin" }).clicked() {
+        *action = Some(RecentProjectAction::SetPinned(path.to_path_buf(), !pinned));
+        ui.close_menu();
+    }
+    if ui.button("Remove from list").clicked() {
+        *action = Some(RecentProjectAction::Remove(path.to_path_buf()));
+        ui.close_menu();
+    }
+}
+
+/// The "Recent projects" submenu of the File menu.
+pub fn recent_projects_menu_ui(ui: &mut egui::Ui, state: &AppStateRef) {
+    let projects = recent_projects(state);
+    if projects.is_empty() {
+        ui.add_enabled(false, egui::Button::new("Recent projects…"));
+        return;
+    }
+    let mut action = None;
+    ui.menu_button("Recent Projects…", |ui| {
+        if ui.button("Clear").on_hover_text("Pinned projects are kept").clicked() {
+            state.write().unwrap().config.recent_projects.clear();
+        }
+        ui.separator();
+        for (path, pinned) in &projects {
+            let label = if *pinned {
+                format!("📌 {}", path.display())
+            } else {
+                path.display().to_string()
+            };
+            ui.menu_button(label, |ui| {
+                if ui.button("Open").clicked() {
+                    action = Some(RecentProjectAction::Open(path.clone()));
+                    ui.close_menu();
+                }
+                project_context_menu(ui, path, *pinned, &mut action);
+            });
+        }
+    });
+    if let Some(action) = action {
+        apply_action(state, action);
+    }
+}
+
+/// Shown in place of the symbol list when no project is loaded.
+pub fn start_screen_ui(
+    ui: &mut egui::Ui,
+    state: &AppStateRef,
+    show_project_config: &mut bool,
+    appearance: &Appearance,
+) {
+    let projects = recent_projects(state);
+    let mut action = None;
+    ui.vertical_centered(|ui| {
+        ui.add_space(ui.available_height() / 8.0);
+        ui.heading("objdiff");
+        ui.add_space(8.0);
+        if ui.button("Open project…").clicked() {
+            *show_project_config = true;
+        }
+        ui.add_space(16.0);
+        if projects.is_empty() {
+            ui.colored_label(appearance.deemphasized_text_color, "No recent projects");
+            return;
+        }
+        ui.label(RichText::new("Recent projects").strong());
+        ui.add_space(4.0);
+        ScrollArea::vertical().auto_shrink([true, true]).show(ui, |ui| {
+            for (path, pinned) in &projects {
+                ui.horizontal(|ui| {
+                    let pin = if *pinned { "📌" } else { "  " };
+                    ui.colored_label(appearance.deemphasized_text_color, pin);
+                    let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+                    let response = ui
+                        .link(RichText::new(name).color(appearance.highlight_color))
+                        .on_hover_text(path.display().to_string());
+                    if response.clicked() {
+                        action = Some(RecentProjectAction::Open(path.clone()));
+                    }
+                    response.context_menu(|ui| {
+                        project_context_menu(ui, path, *pinned, &mut action);
+                    });
+                    ui.colored_label(
+                        appearance.deemphasized_text_color,
+                        path.parent().map(|p| p.display().to_string()).unwrap_or_default(),
+                    );
+                });
+            }
+        });
+        ui.colored_label(
+            appearance.deemphasized_text_color,
+            "Right-click a project to pin it or open it in a new window",
+        );
+    });
+    if let Some(action) = action {
+        apply_action(state, action);
+    }
+}
@@ -0,0 +1,469 @@
+use std::{
+    cmp::Ordering,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use egui::{CollapsingHeader, Color32, Grid, RichText, ScrollArea, TextEdit, TextStyle};
+use objdiff_core::bindings::{
+    report::{ChangeItem, ChangeUnit, Changes, Measures, Report, ReportCloneGroup},
+    report_summary::SummaryFormat,
+};
+
+use crate::views::{
+    appearance::Appearance,
+    file::{FileDialogResult, FileDialogState},
+};
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+enum ChangeSort {
+    #[default]
+    Name,
+    /// Change in fuzzy match percent
+    Delta,
+    /// Current code size
+    Size,
+}
+
+impl ChangeSort {
+    const ALL: [ChangeSort; 3] = [ChangeSort::Name, ChangeSort::Delta, ChangeSort::Size];
+
+    fn label(self) -> &'static str {
+        match self {
+            ChangeSort::Name => "Name",
+            ChangeSort::Delta => "Change",
+            ChangeSort::Size => "Size",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+enum ChangeFilter {
+    #[default]
+    All,
+    Improved,
+    Regressed,
+    Added,
+    Removed,
+}
+
+impl ChangeFilter {
+    const ALL: [ChangeFilter; 5] = [
+        ChangeFilter::All,
+        ChangeFilter::Improved,
+        ChangeFilter::Regressed,
+        ChangeFilter::Added,
+        ChangeFilter::Removed,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            ChangeFilter::All => "All",
+            ChangeFilter::Improved => "Improved",
+            ChangeFilter::Regressed => "Regressed",
+            ChangeFilter::Added => "Added",
+            ChangeFilter::Removed => "Removed",
+        }
+    }
+
+    fn matches(self, from: Option<f32>, to: Option<f32>) -> bool {
+        match (self, from, to) {
+            (ChangeFilter::All, _, _) => true,
+            (ChangeFilter::Improved, Some(from), Some(to)) => to > from,
+            (ChangeFilter::Regressed, Some(from), Some(to)) => to < from,
+            (ChangeFilter::Added, None, Some(_)) => true,
+            (ChangeFilter::Removed, Some(_), None) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Compares two progress reports unit by unit, without loading any objects.
+#[derive(Default)]
+pub struct ReportChangesViewState {
+    file_dialog_state: FileDialogState,
+    previous: Option<(PathBuf, Report)>,
+    current: Option<(PathBuf, Report)>,
+    changes: Option<Changes>,
+    error: Option<String>,
+    search: String,
+    sort: ChangeSort,
+    sort_descending: bool,
+    filter: ChangeFilter,
+    /// Functions duplicated across units in the current report (or the previous one, if it's
+    /// the only one loaded)
+    clones: Vec<ReportCloneGroup>,
+}
+
+fn read_report(path: &Path) -> Result<Report> {
+    let data = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut report = Report::parse(&data)
+        .with_context(|| format!("Failed to load report {}", path.display()))?;
+    report.migrate()?;
+    Ok(report)
+}
+
+impl ReportChangesViewState {
+    fn handle_file_dialog(&mut self) {
+        let (path, is_current) = match self.file_dialog_state.poll() {
+            FileDialogResult::PreviousReport(path) => (path, false),
+            FileDialogResult::CurrentReport(path) => (path, true),
+            FileDialogResult::ExportPrintableReport(path) => {
+                self.error = self.export_printable(&path).err().map(|e| format!("{e:#}"));
+                return;
+            }
+            _ => return,
+        };
+        match read_report(&path) {
+            Ok(report) => {
+                let slot = if is_current { &mut self.current } else { &mut self.previous };
+                *slot = Some((path, report));
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("{e:#}")),
+        }
+        self.changes = match (&self.previous, &self.current) {
+            (Some((_, previous)), Some((_, current))) => Some(Changes::between(previous, current)),
+            _ => None,
+        };
+        // Reports generated without `--clones` still have the signatures to group by
+        self.clones = match self.current.as_ref().or(self.previous.as_ref()) {
+            Some((_, report)) if !report.clones.is_empty() => report.clones.clone(),
+            Some((_, report)) => report.find_clones(),
+            None => vec![],
+        };
+    }
+
+    /// Writes the current report (or the previous one, if it's the only one loaded) as a
+    /// printable page and opens it, so it can be printed or saved as a PDF from the browser.
+    fn export_printable(&self, path: &Path) -> Result<()> {
+        let Some((_, report)) = self.current.as_ref().or(self.previous.as_ref()) else {
+            return Ok(());
+        };
+        std::fs::write(path, report.summary(SummaryFormat::Printable))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        open::that_detached(path).with_context(|| format!("Failed to open {}", path.display()))
+    }
+}
+
+fn fuzzy(measures: Option<&Measures>) -> Option<f32> { measures.map(|m| m.fuzzy_match_percent) }
+
+fn item_fuzzy(item: &ChangeItem) -> (Option<f32>, Option<f32>) {
+    (
+        item.from.as_ref().map(|i| i.fuzzy_match_percent),
+        item.to.as_ref().map(|i| i.fuzzy_match_percent),
+    )
+}
+
+fn delta(from: Option<f32>, to: Option<f32>) -> f32 { to.unwrap_or(0.0) - from.unwrap_or(0.0) }
+
+fn change_text(from: Option<f32>, to: Option<f32>, appearance: &Appearance) -> RichText {
+    let (text, color) = match (from, to) {
+        (Some(from), Some(to)) => {
+            let color = match to.partial_cmp(&from) {
+                Some(Ordering::Greater) => appearance.insert_color,
+                Some(Ordering::Less) => appearance.delete_color,
+                _ => appearance.text_color,
+            };
+            (format!("{from:.2}% → {to:.2}% ({:+.2}%)", to - from), color)
+        }
+        (None, Some(to)) => (format!("new, {to:.2}%"), appearance.insert_color),
+        (Some(from), None) => (format!("removed, was {from:.2}%"), appearance.delete_color),
+        (None, None) => (String::new(), Color32::TRANSPARENT),
+    };
+    RichText::new(text).color(color)
+}
+
+fn measures_summary_ui(ui: &mut egui::Ui, changes: &Changes, appearance: &Appearance) {
+    let (Some(from), Some(to)) = (&changes.from, &changes.to) else {
+        return;
+    };
+    Grid::new("report_changes_summary").num_columns(2).show(ui, |ui| {
+        ui.label("Fuzzy match:");
+        ui.label(change_text(
+            Some(from.fuzzy_match_percent),
+            Some(to.fuzzy_match_percent),
+            appearance,
+        ));
+        ui.end_row();
+        ui.label("Matched code:");
+        ui.label(change_text(
+            Some(from.matched_code_percent),
+            Some(to.matched_code_percent),
+            appearance,
+        ));
+        ui.end_row();
+        ui.label("Matched functions:");
+        ui.label(format!(
+            "{} → {} ({:+})",
+            from.matched_functions,
+            to.matched_functions,
+            to.matched_functions as i64 - from.matched_functions as i64
+        ));
+        ui.end_row();
+        ui.label("Code size:");
+        ui.label(format!(
+            "{} → {} ({:+})",
+            from.total_code,
+            to.total_code,
+            to.total_code as i64 - from.total_code as i64
+        ));
+        ui.end_row();
+    });
+}
+
+fn unit_size(unit: &ChangeUnit) -> u64 {
+    unit.to.as_ref().or(unit.from.as_ref()).map_or(0, |m| m.total_code)
+}
+
+fn unit_ui(ui: &mut egui::Ui, unit: &ChangeUnit, search: &str, appearance: &Appearance) {
+    let (from, to) = (fuzzy(unit.from.as_ref()), fuzzy(unit.to.as_ref()));
+    let header = RichText::new(format!("{} ", unit.name)).color(appearance.highlight_color);
+    let mut job = egui::text::LayoutJob::default();
+    header.append_to(&mut job, ui.style(), egui::FontSelection::Default, egui::Align::Center);
+    change_text(from, to, appearance).append_to(
+        &mut job,
+        ui.style(),
+        egui::FontSelection::Default,
+        egui::Align::Center,
+    );
+    CollapsingHeader::new(job).id_salt(&unit.name).show(ui, |ui| {
+        if unit.functions.is_empty() {
+            ui.colored_label(appearance.deemphasized_text_color, "No function changes");
+            return;
+        }
+        Grid::new(("report_changes_unit", &unit.name)).num_columns(3).striped(true).show(
+            ui,
+            |ui| {
+                for item in &unit.functions {
+                    let name = item
+                        .metadata
+                        .as_ref()
+                        .and_then(|m| m.demangled_name.as_deref())
+                        .unwrap_or(&item.name);
+                    if !search.is_empty() && !name.to_ascii_lowercase().contains(search) {
+                        continue;
+                    }
+                    ui.label(name).on_hover_text(&item.name);
+                    let (from, to) = item_fuzzy(item);
+                    ui.label(change_text(from, to, appearance));
+                    let from_size = item.from.as_ref().map(|i| i.size);
+                    let to_size = item.to.as_ref().map(|i| i.size);
+                    match (from_size, to_size) {
+                        (Some(from), Some(to)) if from != to => {
+                            ui.label(format!("{from} → {to} bytes"));
+                        }
+                        (_, Some(size)) | (Some(size), None) => {
+                            ui.colored_label(
+                                appearance.deemphasized_text_color,
+                                format!("{size} bytes"),
+                            );
+                        }
+                        (None, None) => {
+                            ui.label("");
+                        }
+                    }
+                    ui.end_row();
+                }
+            },
+        );
+    });
+}
+
+fn clones_ui(ui: &mut egui::Ui, clones: &[ReportCloneGroup], appearance: &Appearance) {
+    let total_size = clones.iter().map(|group| group.total_size()).sum::<u64>();
+    CollapsingHeader::new(format!(
+        "Duplicated code: {} group(s), {total_size} bytes",
+        clones.len()
+    ))
+    .id_salt("report_changes_clones")
+    .show(ui, |ui| {
+        ui.colored_label(
+            appearance.deemphasized_text_color,
+            "Functions in different units with the same instruction sequence, ignoring operands. \
+             These are likely inlined or copy-pasted code.",
+        );
+        ScrollArea::vertical().id_salt("report_changes_clones").max_height(200.0).show(ui, |ui| {
+            ui.style_mut().override_text_style = Some(TextStyle::Monospace);
+            for group in clones {
+                let first = &group.members[0];
+                let name = first.demangled_name.as_deref().unwrap_or(&first.name);
+                let header = format!(
+                    "{name} ({} copies, {} bytes)",
+                    group.members.len(),
+                    group.total_size()
+                );
+                CollapsingHeader::new(RichText::new(header).color(appearance.highlight_color))
+                    .id_salt(("report_clone", group.signature))
+                    .show(ui, |ui| {
+                        Grid::new(("report_clone_members", group.signature))
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for member in &group.members {
+                                    ui.label(&member.unit);
+                                    ui.label(
+                                        member.demangled_name.as_deref().unwrap_or(&member.name),
+                                    )
+                                    .on_hover_text(&member.name);
+                                    let color = if member.fuzzy_match_percent >= 100.0 {
+                                        appearance.insert_color
+                                    } else {
+                                        appearance.text_color
+                                    };
+                                    ui.colored_label(
+                                        color,
+                                        format!(
+                                            "{:.2}%, {} bytes",
+                                            member.fuzzy_match_percent, member.size
+                                        ),
+                                    );
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            }
+        });
+    });
+}
+
+pub fn report_changes_window(
+    ctx: &egui::Context,
+    show: &mut bool,
+    state: &mut ReportChangesViewState,
+    appearance: &Appearance,
+) {
+    state.handle_file_dialog();
+    egui::Window::new("Report Changes").open(show).default_width(600.0).show(ctx, |ui| {
+        for (label, is_current) in [("Previous:", false), ("Current:", true)] {
+            ui.horizontal(|ui| {
+                ui.label(label);
+                let report = if is_current { &state.current } else { &state.previous };
+                match report {
+                    Some((path, _)) => ui.label(
+                        RichText::new(path.display().to_string()).color(appearance.highlight_color),
+                    ),
+                    None => ui.colored_label(appearance.deemphasized_text_color, "No report"),
+                };
+                if ui.button("Open…").clicked() {
+                    state.file_dialog_state.queue(
+                        || {
+                            Box::pin(
+                                rfd::AsyncFileDialog::new()
+                                    .add_filter("Report", &["json", "binpb", "pb"])
+                                    .pick_file(),
+                            )
+                        },
+                        if is_current {
+                            FileDialogResult::CurrentReport
+                        } else {
+                            FileDialogResult::PreviousReport
+                        },
+                    );
+                }
+            });
+        }
+        if state.current.is_some() || state.previous.is_some() {
+            if ui
+                .button("Export printable…")
+                .on_hover_text(
+                    "Save the current report's unit and function progress as a page to print or \
+                     save as a PDF",
+                )
+                .clicked()
+            {
+                state.file_dialog_state.queue(
+                    || {
+                        Box::pin(
+                            rfd::AsyncFileDialog::new()
+                                .set_file_name("report.html")
+                                .add_filter("HTML", &["html"])
+                                .save_file(),
+                        )
+                    },
+                    FileDialogResult::ExportPrintableReport,
+                );
+            }
+        }
+        if let Some(error) = &state.error {
+            ui.colored_label(appearance.delete_color, error);
+        }
+        if !state.clones.is_empty() {
+            clones_ui(ui, &state.clones, appearance);
+        }
+        let Some(changes) = &state.changes else {
+            ui.label(
+                "Open two reports generated with `objdiff-cli report generate` to compare them.",
+            );
+            return;
+        };
+        ui.separator();
+        measures_summary_ui(ui, changes, appearance);
+        ui.separator();
+        ui.horizontal(|ui| {
+            TextEdit::singleline(&mut state.search)
+                .hint_text("Filter")
+                .desired_width(150.0)
+                .show(ui);
+            egui::ComboBox::from_id_salt("report_changes_filter")
+                .selected_text(state.filter.label())
+                .show_ui(ui, |ui| {
+                    for filter in ChangeFilter::ALL {
+                        ui.selectable_value(&mut state.filter, filter, filter.label());
+                    }
+                });
+            ui.label("Sort by");
+            egui::ComboBox::from_id_salt("report_changes_sort")
+                .selected_text(state.sort.label())
+                .show_ui(ui, |ui| {
+                    for sort in ChangeSort::ALL {
+                        ui.selectable_value(&mut state.sort, sort, sort.label());
+                    }
+                });
+            let arrow = if state.sort_descending { "⏷" } else { "⏶" };
+            if ui.small_button(arrow).on_hover_text("Reverse the sort order").clicked() {
+                state.sort_descending = !state.sort_descending;
+            }
+        });
+
+        let search = state.search.to_ascii_lowercase();
+        let mut units = changes
+            .units
+            .iter()
+            .filter(|unit| state.filter.matches(fuzzy(unit.from.as_ref()), fuzzy(unit.to.as_ref())))
+            .filter(|unit| {
+                search.is_empty()
+                    || unit.name.to_ascii_lowercase().contains(&search)
+                    || unit.functions.iter().any(|item| {
+                        item.metadata
+                            .as_ref()
+                            .and_then(|m| m.demangled_name.as_deref())
+                            .unwrap_or(&item.name)
+                            .to_ascii_lowercase()
+                            .contains(&search)
+                    })
+            })
+            .collect::<Vec<_>>();
+        match state.sort {
+            ChangeSort::Name => units.sort_by(|a, b| a.name.cmp(&b.name)),
+            ChangeSort::Delta => units.sort_by(|a, b| {
+                delta(fuzzy(a.from.as_ref()), fuzzy(a.to.as_ref()))
+                    .total_cmp(&delta(fuzzy(b.from.as_ref()), fuzzy(b.to.as_ref())))
+            }),
+            ChangeSort::Size => units.sort_by_key(|unit| unit_size(unit)),
+        }
+        if state.sort_descending {
+            units.reverse();
+        }
+        ui.label(format!("{} of {} changed units", units.len(), changes.units.len()));
+        ScrollArea::vertical().auto_shrink([false, true]).show(ui, |ui| {
+            ui.style_mut().override_text_style = Some(TextStyle::Monospace);
+            // Show a unit's functions in full if its name matches the filter
+            for unit in units {
+                let function_search =
+                    if unit.name.to_ascii_lowercase().contains(&search) { "" } else { &search };
+                unit_ui(ui, unit, function_search, appearance);
+            }
+        });
+    });
+}
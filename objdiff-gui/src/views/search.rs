@@ -0,0 +1,91 @@
+use egui::{RichText, ScrollArea, TextStyle};
+use objdiff_core::{
+    jobs::objdiff::ObjDiffResult,
+    obj::{
+        search::{search_bytes, BytePattern, SearchHit},
+        ObjInfo,
+    },
+};
+
+use crate::views::appearance::Appearance;
+
+#[derive(Default)]
+pub struct SearchViewState {
+    pub query: String,
+    pub results: Option<Result<[Vec<String>; 2], String>>,
+}
+
+pub fn search_window(
+    ctx: &egui::Context,
+    show: &mut bool,
+    state: &mut SearchViewState,
+    build: Option<&ObjDiffResult>,
+    appearance: &Appearance,
+) {
+    egui::Window::new("Byte Search").open(show).show(ctx, |ui| {
+        let mut search = false;
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut state.query)
+                    .hint_text("3c 60 ?? ?? or #0x80001234")
+                    .font(TextStyle::Monospace),
+            );
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                search = true;
+            }
+            if ui.add_enabled(build.is_some(), egui::Button::new("Search")).clicked() {
+                search = true;
+            }
+        });
+        if search {
+            if let Some(build) = build {
+                state.results = Some(run_search(&state.query, build));
+            }
+        }
+        ui.separator();
+        match &state.results {
+            None => {}
+            Some(Err(e)) => {
+                ui.colored_label(appearance.delete_color, e);
+            }
+            Some(Ok(results)) => {
+                ScrollArea::vertical().auto_shrink([false, true]).show(ui, |ui| {
+                    ui.style_mut().override_text_style = Some(TextStyle::Monospace);
+                    for (title, hits) in ["Target", "Base"].iter().zip(results) {
+                        ui.label(
+                            RichText::new(format!("{title} ({} hits)", hits.len()))
+                                .color(appearance.highlight_color),
+                        );
+                        for hit in hits {
+                            if ui.selectable_label(false, hit).clicked() {
+                                ui.output_mut(|o| o.copied_text = hit.clone());
+                            }
+                        }
+                        ui.add_space(4.0);
+                    }
+                });
+            }
+        }
+    });
+}
+
+fn run_search(query: &str, build: &ObjDiffResult) -> Result<[Vec<String>; 2], String> {
+    let mut results: [Vec<String>; 2] = Default::default();
+    for (result, obj) in results.iter_mut().zip([&build.first_obj, &build.second_obj]) {
+        let Some((obj, _)) = obj else { continue };
+        let pattern = BytePattern::parse(query, obj.endianness).map_err(|e| e.to_string())?;
+        *result = search_bytes(obj, &pattern).iter().map(|hit| format_hit(obj, hit)).collect();
+    }
+    Ok(results)
+}
+
+fn format_hit(obj: &ObjInfo, hit: &SearchHit) -> String {
+    let section = &obj.sections[hit.section_idx];
+    match hit.symbol {
+        Some(symbol_ref) => {
+            let (_, symbol) = obj.section_symbol(symbol_ref);
+            format!("{}:{:x} {}+{:#x}", section.name, hit.address, symbol.name, hit.symbol_offset)
+        }
+        None => format!("{}:{:x}", section.name, hit.address),
+    }
+}
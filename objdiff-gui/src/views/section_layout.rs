@@ -0,0 +1,226 @@
+use egui::{
+    epaint::PathShape, pos2, vec2, Color32, Pos2, Rect, RichText, ScrollArea, Sense, Shape, Stroke,
+    Ui,
+};
+use objdiff_core::{
+    diff::layout::{diff_section_layouts, LayoutSymbol, SectionLayout, SectionLayoutDiff},
+    obj::ObjSectionKind,
+};
+
+use crate::views::{
+    appearance::Appearance,
+    symbol_diff::{
+        match_color_for_symbol, DiffViewAction, DiffViewNavigation, SymbolDiffContext, View,
+    },
+};
+
+const STRIP_HEIGHT: f32 = 14.0;
+const RIBBON_HEIGHT: f32 = 28.0;
+
+fn segment_color(symbol: &LayoutSymbol, appearance: &Appearance) -> Color32 {
+    match symbol.match_percent {
+        Some(match_percent) => match_color_for_symbol(match_percent, appearance),
+        None => appearance.deemphasized_text_color,
+    }
+}
+
+fn segment_rect(strip: Rect, scale: f32, symbol: &LayoutSymbol) -> Rect {
+    let min = pos2(strip.left() + symbol.offset as f32 * scale, strip.top());
+    let width = (symbol.size as f32 * scale).max(1.0);
+    Rect::from_min_size(min, vec2(width, strip.height()))
+}
+
+/// Paints one side of a section and returns the index of the hovered segment, if any.
+fn strip_ui(
+    ui: &Ui,
+    strip: Rect,
+    scale: f32,
+    layout: &SectionLayout,
+    hover_pos: Option<Pos2>,
+    appearance: &Appearance,
+) -> Option<usize> {
+    let painter = ui.painter_at(strip.expand(1.0));
+    let used = Rect::from_min_size(strip.min, vec2(layout.size as f32 * scale, strip.height()));
+    painter.rect_stroke(used, 0.0, Stroke::new(1.0, appearance.deemphasized_text_color));
+    let mut hovered = None;
+    for (idx, symbol) in layout.symbols.iter().enumerate() {
+        let rect = segment_rect(strip, scale, symbol);
+        painter.rect_filled(rect.shrink2(vec2(0.0, 1.0)), 0.0, segment_color(symbol, appearance));
+        if hover_pos.is_some_and(|pos| rect.contains(pos)) {
+            hovered = Some(idx);
+        }
+    }
+    if let Some(idx) = hovered {
+        let rect = segment_rect(strip, scale, &layout.symbols[idx]);
+        painter.rect_stroke(rect, 0.0, Stroke::new(1.0, appearance.highlight_color));
+    }
+    hovered
+}
+
+/// Paints ribbons connecting matched symbols between the two strips.
+#[expect(clippy::too_many_arguments)]
+fn ribbons_ui(
+    ui: &Ui,
+    rect: Rect,
+    top: Rect,
+    bottom: Rect,
+    scale: f32,
+    left: &SectionLayout,
+    right: &SectionLayout,
+    highlighted: Option<usize>,
+    appearance: &Appearance,
+) {
+    let painter = ui.painter_at(rect);
+    for (idx, symbol) in left.symbols.iter().enumerate() {
+        let Some(target) = symbol.target else {
+            continue;
+        };
+        let from = segment_rect(top, scale, symbol);
+        let to = segment_rect(bottom, scale, &right.symbols[target]);
+        let alpha = if highlighted == Some(idx) { 0.6 } else { 0.2 };
+        let color = segment_color(symbol, appearance).linear_multiply(alpha);
+        let points = vec![
+            pos2(from.left(), top.bottom()),
+            pos2(from.right(), top.bottom()),
+            pos2(to.right(), bottom.top()),
+            pos2(to.left(), bottom.top()),
+        ];
+        painter.add(Shape::Path(PathShape::convex_polygon(points, color, Stroke::NONE)));
+    }
+}
+
+fn hover_text(
+    ctx: SymbolDiffContext<'_>,
+    symbol: &LayoutSymbol,
+    other: Option<&LayoutSymbol>,
+) -> String {
+    let (_, obj_symbol) = ctx.obj.section_symbol(symbol.symbol_ref);
+    let name = obj_symbol.demangled_name.as_deref().unwrap_or(&obj_symbol.name);
+    let mut text = format!("{name}\nOffset: {:#x}\nSize: {:#x}", symbol.offset, symbol.size);
+    if let Some(match_percent) = symbol.match_percent {
+        text.push_str(&format!("\nMatch: {:.0}%", match_percent.floor()));
+    }
+    match other {
+        Some(other) => {
+            text.push_str(&format!("\nOther side: {:#x} (size {:#x})", other.offset, other.size))
+        }
+        None => text.push_str("\nNo match on the other side"),
+    }
+    text
+}
+
+fn section_ui(
+    ui: &mut Ui,
+    diff: &SectionLayoutDiff,
+    left_ctx: Option<SymbolDiffContext<'_>>,
+    right_ctx: Option<SymbolDiffContext<'_>>,
+    appearance: &Appearance,
+) -> Option<DiffViewAction> {
+    let mut ret = None;
+    ui.horizontal(|ui| {
+        ui.label(RichText::new(&diff.name).color(appearance.highlight_color));
+        let left_size = diff.left.as_ref().map_or(0, |l| l.size);
+        let right_size = diff.right.as_ref().map_or(0, |r| r.size);
+        ui.label(format!("{left_size:#x} / {right_size:#x}"));
+        let delta = diff.size_delta();
+        if delta != 0 {
+            ui.colored_label(appearance.replace_color, format!("{delta:+}"));
+        }
+        if diff.reordered() {
+            ui.colored_label(appearance.delete_color, "reordered");
+        }
+        let moved = diff.moved_symbols();
+        if moved > 0 {
+            ui.colored_label(appearance.replace_color, format!("{moved} moved"));
+        }
+    });
+
+    let max_size =
+        diff.left.iter().chain(diff.right.iter()).map(|l| l.size).max().unwrap_or(0).max(1);
+    let (rect, response) = ui.allocate_exact_size(
+        vec2(ui.available_width(), STRIP_HEIGHT * 2.0 + RIBBON_HEIGHT),
+        Sense::click(),
+    );
+    let scale = rect.width() / max_size as f32;
+    let top = Rect::from_min_size(rect.min, vec2(rect.width(), STRIP_HEIGHT));
+    let bottom = Rect::from_min_size(
+        pos2(rect.left(), rect.bottom() - STRIP_HEIGHT),
+        vec2(rect.width(), STRIP_HEIGHT),
+    );
+    let hover_pos = response.hover_pos();
+
+    let left_hovered =
+        diff.left.as_ref().and_then(|l| strip_ui(ui, top, scale, l, hover_pos, appearance));
+    let right_hovered =
+        diff.right.as_ref().and_then(|r| strip_ui(ui, bottom, scale, r, hover_pos, appearance));
+    if let (Some(left), Some(right)) = (&diff.left, &diff.right) {
+        let highlighted =
+            left_hovered.or_else(|| right_hovered.and_then(|idx| right.symbols[idx].target));
+        ribbons_ui(ui, rect, top, bottom, scale, left, right, highlighted, appearance);
+    }
+
+    // Resolve the hovered segment to its symbol, the other side and the column it's in
+    let hovered = match (left_hovered, right_hovered) {
+        (Some(idx), _) => diff.left.as_ref().zip(left_ctx).map(|(layout, ctx)| {
+            let symbol = &layout.symbols[idx];
+            let other = symbol.target.and_then(|t| diff.right.as_ref().map(|r| &r.symbols[t]));
+            (symbol, other, ctx, right_ctx, 0)
+        }),
+        (None, Some(idx)) => diff.right.as_ref().zip(right_ctx).map(|(layout, ctx)| {
+            let symbol = &layout.symbols[idx];
+            let other = symbol.target.and_then(|t| diff.left.as_ref().map(|l| &l.symbols[t]));
+            (symbol, other, ctx, left_ctx, 1)
+        }),
+        (None, None) => None,
+    };
+    if let Some((symbol, other, ctx, other_ctx, column)) = hovered {
+        let response = response.on_hover_text_at_pointer(hover_text(ctx, symbol, other));
+        if response.clicked() {
+            let (section, obj_symbol) = ctx.obj.section_symbol(symbol.symbol_ref);
+            if let Some(section) = section {
+                let view = match diff.kind {
+                    ObjSectionKind::Code => View::FunctionDiff,
+                    ObjSectionKind::Data | ObjSectionKind::Bss => View::DataDiff,
+                };
+                ret = Some(DiffViewAction::Navigate(DiffViewNavigation::with_symbols(
+                    view,
+                    other_ctx,
+                    obj_symbol,
+                    section,
+                    ctx.diff.symbol_diff(symbol.symbol_ref),
+                    column,
+                )));
+            }
+        }
+    }
+    ui.add_space(ui.spacing().item_spacing.y * 2.0);
+    ret
+}
+
+/// Draws each section as a pair of address-space strips, target on top and base below, with
+/// ribbons linking matched symbols to show size drift and reordering.
+#[must_use]
+pub fn section_layout_ui(
+    ui: &mut Ui,
+    left_ctx: Option<SymbolDiffContext<'_>>,
+    right_ctx: Option<SymbolDiffContext<'_>>,
+    appearance: &Appearance,
+) -> Option<DiffViewAction> {
+    let mut ret = None;
+    let layouts = diff_section_layouts(
+        left_ctx.map(|ctx| (ctx.obj, ctx.diff)),
+        right_ctx.map(|ctx| (ctx.obj, ctx.diff)),
+    );
+    ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+        ui.style_mut().override_text_style = Some(egui::TextStyle::Monospace);
+        for diff in &layouts {
+            if diff.left.iter().chain(diff.right.iter()).all(|l| l.symbols.is_empty()) {
+                continue;
+            }
+            if let Some(action) = section_ui(ui, diff, left_ctx, right_ctx, appearance) {
+                ret = Some(action);
+            }
+        }
+    });
+    ret
+}
@@ -0,0 +1,54 @@
+use egui::RichText;
+
+use crate::{
+    app::AppStateRef,
+    views::{appearance::Appearance, symbol_diff::DiffViewState},
+};
+
+/// Maximum number of changed symbols listed in the tooltip.
+const MAX_CHANGED_SYMBOLS: usize = 20;
+
+/// A banner shown while viewing an exported diff session.
+pub fn session_panel(
+    ctx: &egui::Context,
+    state: &AppStateRef,
+    diff_state: &DiffViewState,
+    appearance: &Appearance,
+) {
+    let Some((name, objdiff_version)) = state.read().ok().and_then(|state| {
+        state.session.as_ref().map(|s| (s.name.clone(), s.objdiff_version.clone()))
+    }) else {
+        return;
+    };
+    let mut close = false;
+    egui::TopBottomPanel::top("session_panel").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("📦 Session").color(appearance.highlight_color));
+            ui.label(format!("{name}, exported with objdiff {objdiff_version}"));
+            ui.colored_label(appearance.deemphasized_text_color, "(read-only)");
+            let changed = &diff_state.session_changed_symbols;
+            if !changed.is_empty() {
+                ui.colored_label(
+                    appearance.replace_color,
+                    format!("{} symbols differ from the exported diff", changed.len()),
+                )
+                .on_hover_ui(|ui| {
+                    for name in changed.iter().take(MAX_CHANGED_SYMBOLS) {
+                        ui.label(name);
+                    }
+                    if changed.len() > MAX_CHANGED_SYMBOLS {
+                        ui.label(format!("…and {} more", changed.len() - MAX_CHANGED_SYMBOLS));
+                    }
+                });
+            }
+            if ui.button("Close session").clicked() {
+                close = true;
+            }
+        });
+    });
+    if close {
+        if let Ok(mut state) = state.write() {
+            state.clear_selected_obj();
+        }
+    }
+}
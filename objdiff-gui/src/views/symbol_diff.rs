@@ -1,28 +1,52 @@
-use std::{collections::BTreeMap, mem::take, ops::Bound};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    mem::{replace, take},
+    ops::Bound,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
 use egui::{
-    style::ScrollAnimation, text::LayoutJob, CollapsingHeader, Color32, Id, Layout, OpenUrl,
-    ScrollArea, SelectableLabel, TextEdit, Ui, Widget,
+    style::ScrollAnimation, text::LayoutJob, CollapsingHeader, Color32, CursorIcon, Id, Layout,
+    OpenUrl, ScrollArea, SelectableLabel, Sense, Stroke, TextEdit, Ui, Widget,
 };
 use objdiff_core::{
-    arch::ObjArch,
+    bindings::diff::DiffResult,
     build::BuildStatus,
-    diff::{display::HighlightKind, ObjDiff, ObjSymbolDiff},
+    config::{
+        session_file::{DiffSession, SessionHeader, SESSION_EXTENSION},
+        SymbolReviewState,
+    },
+    diff::{
+        display::HighlightKind, initializers::is_initializer_section, order::suggest_symbol_order,
+        AlignmentAnchor, ObjDiff, ObjSymbolDiff, SizeMismatchFix,
+    },
     jobs::{create_scratch::CreateScratchResult, objdiff::ObjDiffResult, Job, JobQueue, JobResult},
     obj::{
-        ObjInfo, ObjSection, ObjSectionKind, ObjSymbol, ObjSymbolFlags, SymbolRef, SECTION_COMMON,
+        cache::MemoryUsage, seh::SehUnwindData, ObjInfo, ObjSection, ObjSectionKind, ObjSymbol,
+        ObjSymbolFlags, SymbolRef, SECTION_COMMON,
     },
 };
 use regex::{Regex, RegexBuilder};
 
 use crate::{
     app::AppStateRef,
+    deep_link::DeepLink,
+    editor::{open_in_editor, symbol_line},
     hotkeys,
-    jobs::{is_create_scratch_available, start_create_scratch},
+    jobs::{create_objdiff_config, is_create_scratch_available, start_create_scratch},
+    notify::send_notification,
     views::{
         appearance::Appearance,
         column_layout::{render_header, render_strips},
-        function_diff::FunctionViewState,
+        cross_diff::CrossDiffSource,
+        file::{FileDialogResult, FileDialogState},
+        function_diff::{FunctionViewState, ScrollSync},
+        initializers::initializers_ui,
+        section_layout::section_layout_ui,
+        unit_overview::unit_overview_ui,
+        watch::WatchState,
         write_text,
     },
 };
@@ -53,6 +77,8 @@ pub enum View {
 pub enum DiffViewAction {
     /// Queue a rebuild of the current object(s)
     Build,
+    /// Queue a rebuild of the current object(s), ignoring the build cache
+    ForceBuild,
     /// Navigate to a new diff view
     Navigate(DiffViewNavigation),
     /// Set the highlighted symbols in the symbols view, optionally scrolling them into view.
@@ -63,10 +89,18 @@ pub enum DiffViewAction {
     CreateScratch(String),
     /// Open the source path of the current object
     OpenSourcePath,
+    /// Open the source path of the current object in the configured editor, at a line
+    OpenInEditor(Option<u32>),
+    /// Copy an `objdiff://` link to a symbol of the current unit, opened in the given view
+    CopyLink(String, View),
     /// Set the highlight for a diff column
     SetDiffHighlight(usize, HighlightKind),
     /// Clear the highlight for all diff columns
     ClearDiffHighlight,
+    /// Set the function diff search query, scrolling to the first matching row
+    SetFunctionSearch(String, Option<usize>),
+    /// Select a function diff search match by index, scrolling to its row
+    SelectSearchMatch(usize, usize),
     /// Start selecting a left symbol for mapping.
     /// The symbol reference is the right symbol to map to.
     SelectingLeft(SymbolRefByName),
@@ -77,6 +111,49 @@ pub enum DiffViewAction {
     SetMapping(View, SymbolRefByName, SymbolRefByName),
     /// Set the show_mapped_symbols flag
     SetShowMappedSymbols(bool),
+    /// Set the similar_size_candidates flag
+    SetSimilarSizeCandidates(bool),
+    /// Toggle the unit overview in place of the symbol lists
+    SetShowOverview(bool),
+    /// Show the section address-space layout instead of the symbol lists.
+    SetShowLayout(bool),
+    /// Show the initializer array diff instead of the symbol lists.
+    SetShowInitializers(bool),
+    /// Set the manual review state of a symbol, keyed by target symbol name.
+    SetReviewState(String, SymbolReviewState),
+    /// Watch or unwatch a symbol's match percent, keyed by target symbol name.
+    SetWatched(String, bool),
+    /// Open the "Diff with…" picker for a symbol (handled by the app)
+    DiffWith(CrossDiffSource),
+    /// Save the target object's symbol order as a linker order file
+    ExportSymbolOrder,
+    /// Save the current objects and diff configuration as a diff session
+    ExportSession,
+    /// Set the maximum width of symbol names in the symbol lists, or `None` for no limit
+    SetNameColumnWidth(Option<f32>),
+    /// Set how the function diff columns scroll relative to each other
+    SetScrollSync(ScrollSync),
+    /// Scroll the right function diff column to match the left column
+    ResyncScroll,
+    /// Group function diff rows into blocks by source line
+    SetLineBlocks(bool),
+    /// Scroll one function diff column to a row, when the columns scroll separately
+    ScrollColumn(usize, usize),
+    /// Add or remove a bookmark on an instruction of a function diff column, given as an offset
+    /// into the symbol
+    ToggleBookmark(usize, u64),
+    /// Jump to a bookmarked function diff row
+    SelectBookmark(usize),
+    /// Mark an instruction of a function diff column, given as an offset into the symbol, to be
+    /// anchored to an instruction of the other column. `None` cancels.
+    MarkAnchor(Option<(usize, u64)>),
+    /// Align two instructions of the current function on the same row
+    AddAnchor(AlignmentAnchor),
+    /// Remove the alignment anchor on an instruction of a function diff column, or all of the
+    /// function's anchors if `None`
+    RemoveAnchor(usize, Option<u64>),
+    /// Set how the current function is diffed if its size differs between both sides
+    SetSizeFix(Option<SizeMismatchFix>),
 }
 
 #[derive(Debug, Clone, Default)]
@@ -129,6 +206,47 @@ pub struct DiffViewState {
     pub source_path_available: bool,
     pub post_build_nav: Option<DiffViewNavigation>,
     pub object_name: String,
+    pub watch_state: WatchState,
+    /// Symbols whose match percent differs from the one recorded in the open diff session
+    pub session_changed_symbols: Vec<String>,
+    /// Compiler flags of the selected function, and whether they're specific to the function
+    /// rather than the unit
+    pub compiler_flags: Option<(String, bool)>,
+    /// Unit the current build was loaded for
+    pub build_unit: String,
+    /// Approximate memory used by the current build's objects and diff results, in bytes
+    pub build_memory_usage: usize,
+    /// When the running build was first seen
+    pub build_started: Option<Instant>,
+    /// Title and body of a notification for the last finished build, sent in `post_update`
+    pub build_notification: Option<(String, String)>,
+    /// Time spent generating and laying out the diff view in the last frame
+    pub display_time: Duration,
+    pub file_dialog_state: FileDialogState,
+    /// Contents of the symbol order file being exported, written once a path is picked
+    pub symbol_order: Option<String>,
+    /// The diff session being exported, written once a path is picked
+    pub session_export: Option<SessionExport>,
+}
+
+/// A diff session to export: its objects are read when it's written.
+pub struct SessionExport {
+    header: SessionHeader,
+    target_path: Option<PathBuf>,
+    base_path: Option<PathBuf>,
+    diff_result: DiffResult,
+}
+
+impl SessionExport {
+    fn save(self, path: &Path) -> anyhow::Result<()> {
+        let mut session = DiffSession::from_paths(
+            self.header,
+            self.target_path.as_deref(),
+            self.base_path.as_deref(),
+        )?;
+        session.set_diff_result(&self.diff_result);
+        session.save(path)
+    }
 }
 
 #[derive(Default)]
@@ -141,13 +259,141 @@ pub struct SymbolViewState {
     pub disable_reverse_fn_order: bool,
     pub show_hidden_symbols: bool,
     pub show_mapped_symbols: bool,
+    /// While mapping, only list candidates within half to twice the size of the symbol being
+    /// mapped
+    pub similar_size_candidates: bool,
+    pub show_overview: bool,
+    pub show_layout: bool,
+    pub show_initializers: bool,
+    /// Show unqualified names without parameters in the symbol lists
+    pub short_symbol_names: bool,
+    /// Show the opcode-only match percent next to mismatched functions
+    pub show_opcode_percent: bool,
+    /// Maximum width of symbol names in the symbol lists, in points. Longer names are
+    /// truncated in the middle.
+    pub name_column_width: Option<f32>,
+    /// Manual review states of the selected object, keyed by target symbol name
+    pub review_states: BTreeMap<String, SymbolReviewState>,
+    /// Watched symbols of the selected object, keyed by target symbol name
+    pub watched_symbols: BTreeSet<String>,
+    /// Whether the selected object has a source file to open from the symbol context menu
+    pub source_path_available: bool,
+}
+
+/// Summarizes a finished build: whether it succeeded, and the match percent of the selected
+/// symbol if there is one.
+fn build_notification(
+    build: &ObjDiffResult,
+    unit: &str,
+    selected: Option<&SymbolRefByName>,
+    build_time: Duration,
+) -> (String, String) {
+    let unit = if unit.is_empty() { "objdiff" } else { unit };
+    if !build.first_status.success || !build.second_status.success {
+        return (
+            format!("{unit}: build failed"),
+            format!("Build failed after {}s", build_time.as_secs()),
+        );
+    }
+    let title = format!("{unit}: build finished");
+    let symbol = selected.and_then(|selected| {
+        let (obj, diff) = build.first_obj.as_ref()?;
+        obj.sections.iter().zip(&diff.sections).find_map(|(section, section_diff)| {
+            section.symbols.iter().zip(&section_diff.symbols).find(|(symbol, _)| {
+                symbol.name == selected.symbol_name
+                    && selected.section_name.as_ref().map_or(true, |name| *name == section.name)
+            })
+        })
+    });
+    let body = match symbol {
+        Some((symbol, symbol_diff)) => {
+            let name = symbol.demangled_name.as_deref().unwrap_or(&symbol.name);
+            match symbol_diff.match_percent {
+                Some(percent) => format!("{name}: {percent:.2}% match"),
+                None => format!("{name}: no match"),
+            }
+        }
+        None => format!("Finished in {}s", build_time.as_secs()),
+    };
+    (title, body)
 }
 
 impl DiffViewState {
     pub fn pre_update(&mut self, jobs: &mut JobQueue, state: &AppStateRef) {
+        let mut new_build = false;
         jobs.results.retain_mut(|result| match result {
             JobResult::ObjDiff(result) => {
-                self.build = take(result);
+                new_build = true;
+                let build_time = self.build_started.take().map(|start| start.elapsed());
+                let prev_build = replace(&mut self.build, take(result));
+                if let Ok(state) = state.read() {
+                    // Keep the previous objects around in case they're needed again
+                    if let Some(prev_build) = prev_build {
+                        let ObjDiffResult { first_obj, second_obj, diff_obj_config, .. } =
+                            *prev_build;
+                        let in_use = |obj: &ObjInfo| {
+                            self.build.as_ref().is_some_and(|build| {
+                                build
+                                    .first_obj
+                                    .iter()
+                                    .chain(&build.second_obj)
+                                    .any(|(o, _)| o.path == obj.path)
+                            })
+                        };
+                        for (obj, _) in first_obj.into_iter().chain(second_obj) {
+                            if !in_use(&obj) {
+                                state.obj_cache.insert(&self.build_unit, obj, &diff_obj_config);
+                            }
+                        }
+                    }
+                    self.build_unit = state
+                        .config
+                        .selected_obj
+                        .as_ref()
+                        .map(|o| o.name.clone())
+                        .unwrap_or_default();
+                    self.build_memory_usage = self.build.as_ref().map_or(0, |build| {
+                        build
+                            .first_obj
+                            .iter()
+                            .chain(&build.second_obj)
+                            .map(|(obj, diff)| obj.memory_usage() + diff.memory_usage())
+                            .sum()
+                    });
+                    state.obj_cache.set_in_use(self.build_memory_usage);
+                }
+                if let (Some(build), Ok(mut state)) = (&self.build, state.write()) {
+                    state.record_match_history(build);
+                    state.record_usage_build(build);
+                    self.session_changed_symbols = match (&state.session, &build.first_obj) {
+                        (Some(session), Some((obj, diff))) => session
+                            .changed_symbols(&SessionHeader::collect_match_percents(obj, diff))
+                            .map(str::to_string)
+                            .collect(),
+                        _ => vec![],
+                    };
+                    if let Some(obj_config) = &state.config.selected_obj {
+                        self.watch_state.update(
+                            &obj_config.name,
+                            build,
+                            &obj_config.watched_symbols,
+                        );
+                    }
+                    let min_time = Duration::from_secs(state.config.build_notification_min_secs);
+                    self.build_notification = match build_time {
+                        Some(build_time)
+                            if state.config.build_notifications && build_time >= min_time =>
+                        {
+                            Some(build_notification(
+                                build,
+                                &self.build_unit,
+                                self.symbol_state.left_symbol.as_ref(),
+                                build_time,
+                            ))
+                        }
+                        _ => None,
+                    };
+                }
 
                 // TODO: where should this go?
                 if let Some(result) = self.post_build_nav.take() {
@@ -167,6 +413,9 @@ impl DiffViewState {
             _ => true,
         });
         self.build_running = jobs.is_running(Job::ObjDiff);
+        if self.build_running && self.build_started.is_none() {
+            self.build_started = Some(Instant::now());
+        }
         self.scratch_running = jobs.is_running(Job::CreateScratch);
 
         self.symbol_state.disable_reverse_fn_order = false;
@@ -177,13 +426,97 @@ impl DiffViewState {
                     self.symbol_state.disable_reverse_fn_order = true;
                 }
                 self.source_path_available = obj_config.source_path.is_some();
+                self.symbol_state.source_path_available = self.source_path_available;
+                self.function_state.load_source_text(
+                    state
+                        .config
+                        .project_dir
+                        .as_ref()
+                        .zip(obj_config.source_path.as_ref())
+                        .map(|(dir, path)| dir.join(path)),
+                    new_build,
+                );
+                self.symbol_state.review_states.clone_from(&obj_config.review_states);
+                self.symbol_state.watched_symbols.clone_from(&obj_config.watched_symbols);
+                self.compiler_flags = self
+                    .symbol_state
+                    .left_symbol
+                    .as_ref()
+                    .or(self.symbol_state.right_symbol.as_ref())
+                    .and_then(|symbol_ref| obj_config.compiler_flags_for(&symbol_ref.symbol_name))
+                    .map(|(flags, per_symbol)| (flags.to_string(), per_symbol));
+                let object_bookmarks = state.config.bookmarks.get(&obj_config.name);
+                for (bookmarks, symbol_ref) in self
+                    .function_state
+                    .bookmarks
+                    .iter_mut()
+                    .zip([&self.symbol_state.left_symbol, &self.symbol_state.right_symbol])
+                {
+                    match symbol_ref
+                        .as_ref()
+                        .and_then(|symbol_ref| object_bookmarks?.get(&symbol_ref.symbol_name))
+                    {
+                        Some(symbol_bookmarks) => bookmarks.clone_from(symbol_bookmarks),
+                        None => bookmarks.clear(),
+                    }
+                }
+                match self.symbol_state.left_symbol.as_ref().and_then(|symbol_ref| {
+                    state
+                        .config
+                        .alignment_anchors
+                        .get(&obj_config.name)?
+                        .get(&symbol_ref.symbol_name)
+                }) {
+                    Some(anchors) => self.function_state.anchors.clone_from(anchors),
+                    None => self.function_state.anchors.clear(),
+                }
+                self.function_state.size_fix =
+                    self.symbol_state.left_symbol.as_ref().and_then(|symbol_ref| {
+                        state
+                            .config
+                            .size_fixes
+                            .get(&obj_config.name)?
+                            .get(&symbol_ref.symbol_name)
+                            .copied()
+                    });
             } else {
                 self.source_path_available = false;
+                self.symbol_state.source_path_available = false;
+                self.function_state.source_text = None;
+                self.symbol_state.review_states.clear();
+                self.symbol_state.watched_symbols.clear();
+                self.compiler_flags = None;
+                self.function_state.bookmarks.iter_mut().for_each(BTreeSet::clear);
+                self.function_state.anchors.clear();
+                self.function_state.pending_anchor = None;
+                self.function_state.size_fix = None;
             }
             self.scratch_available = is_create_scratch_available(&state.config);
             self.object_name =
                 state.config.selected_obj.as_ref().map(|o| o.name.clone()).unwrap_or_default();
         }
+
+        // Check async file dialog results
+        match self.file_dialog_state.poll() {
+            FileDialogResult::ExportSymbolOrder(path) => {
+                if let Some(contents) = self.symbol_order.take() {
+                    if let Err(e) = std::fs::write(&path, contents) {
+                        log::error!("Failed to write {}: {e}", path.display());
+                    }
+                }
+            }
+            FileDialogResult::ExportSession(path) => {
+                if let Some(export) = self.session_export.take() {
+                    // Reads both objects, so keep it off the UI thread
+                    std::thread::spawn(move || {
+                        if let Err(e) = export.save(&path) {
+                            log::error!("Failed to export session: {e:#}");
+                        }
+                    });
+                }
+            }
+            _ => {}
+        }
     }
 
     pub fn post_update(
@@ -197,8 +530,22 @@ impl DiffViewState {
             ctx.output_mut(|o| o.open_url = Some(OpenUrl::new_tab(result.scratch_url)));
         }
 
-        // Clear the autoscroll flag so that it doesn't scroll continuously.
+        if let Some((title, body)) = take(&mut self.build_notification) {
+            // The result is already on screen while objdiff is focused
+            if !ctx.input(|i| i.focused) {
+                let sound = state.read().is_ok_and(|state| state.config.build_notification_sound);
+                send_notification(&title, &body, sound);
+                ctx.send_viewport_cmd(egui::ViewportCommand::RequestUserAttention(
+                    egui::UserAttentionType::Informational,
+                ));
+            }
+        }
+
+        // Clear the autoscroll flags so that it doesn't scroll continuously.
         self.symbol_state.autoscroll_to_highlighted_symbols = false;
+        self.function_state.scroll_to_row = None;
+        self.function_state.column_scroll_to_row = [None; 2];
+        self.function_state.resync_scroll = false;
 
         let Some(action) = action else {
             return;
@@ -209,11 +556,18 @@ impl DiffViewState {
                     state.queue_build = true;
                 }
             }
+            DiffViewAction::ForceBuild => {
+                if let Ok(mut state) = state.write() {
+                    state.queue_build = true;
+                    state.force_build = true;
+                }
+            }
             DiffViewAction::Navigate(nav) => {
                 if self.post_build_nav.is_some() {
                     // Ignore action if we're already navigating
                     return;
                 }
+                self.function_state.pending_anchor = None;
                 let Ok(mut state) = state.write() else {
                     return;
                 };
@@ -270,6 +624,93 @@ impl DiffViewState {
                 };
                 start_create_scratch(ctx, jobs, &state, function_name);
             }
+            DiffViewAction::DiffWith(_) => {}
+            DiffViewAction::SetShowOverview(value) => {
+                self.symbol_state.show_overview = value;
+            }
+            DiffViewAction::SetNameColumnWidth(width) => {
+                self.symbol_state.name_column_width = width;
+            }
+            DiffViewAction::SetShowLayout(value) => {
+                self.symbol_state.show_layout = value;
+            }
+            DiffViewAction::SetShowInitializers(value) => {
+                self.symbol_state.show_initializers = value;
+            }
+            DiffViewAction::SetReviewState(symbol_name, review_state) => {
+                if let Ok(mut state) = state.write() {
+                    state.set_review_state(symbol_name, review_state);
+                }
+            }
+            DiffViewAction::SetWatched(symbol_name, watched) => {
+                if let Ok(mut state) = state.write() {
+                    state.set_watched(symbol_name, watched);
+                }
+            }
+            DiffViewAction::ExportSymbolOrder => {
+                let Some((target, target_diff)) =
+                    self.build.as_ref().and_then(|b| b.first_obj.as_ref())
+                else {
+                    return;
+                };
+                let base = self.build.as_ref().and_then(|b| b.second_obj.as_ref()).map(|(o, _)| o);
+                let suggestion = suggest_symbol_order(target, target_diff, base);
+                if base.is_some() && !suggestion.sections.iter().any(|s| s.reordered) {
+                    log::info!("Base symbol order already matches the target");
+                }
+                self.symbol_order = Some(suggestion.to_order_file());
+                let file_name = format!(
+                    "{}.order.txt",
+                    self.object_name.rsplit('/').next().unwrap_or("symbols")
+                );
+                self.file_dialog_state.queue(
+                    move || {
+                        Box::pin(rfd::AsyncFileDialog::new().set_file_name(file_name).save_file())
+                    },
+                    FileDialogResult::ExportSymbolOrder,
+                );
+            }
+            DiffViewAction::ExportSession => {
+                let Ok(state) = state.read() else {
+                    return;
+                };
+                let Some(obj_config) = &state.config.selected_obj else {
+                    return;
+                };
+                let header = SessionHeader {
+                    objdiff_version: env!("CARGO_PKG_VERSION").to_string(),
+                    name: obj_config.name.clone(),
+                    diff_obj_config: create_objdiff_config(&state).diff_obj_config,
+                    symbol_mappings: obj_config.symbol_mappings.clone(),
+                    match_percents: self
+                        .build
+                        .as_ref()
+                        .and_then(|b| b.first_obj.as_ref())
+                        .map(|(obj, diff)| SessionHeader::collect_match_percents(obj, diff))
+                        .unwrap_or_default(),
+                    ..Default::default()
+                };
+                let diff_result = DiffResult::new(
+                    self.build.as_ref().and_then(|b| b.first_obj.as_ref()).map(|(o, d)| (o, d)),
+                    self.build.as_ref().and_then(|b| b.second_obj.as_ref()).map(|(o, d)| (o, d)),
+                );
+                self.session_export = Some(SessionExport {
+                    header,
+                    target_path: obj_config.target_path.clone(),
+                    base_path: obj_config.base_path.clone(),
+                    diff_result,
+                });
+                let file_name = format!(
+                    "{}.{SESSION_EXTENSION}",
+                    obj_config.name.rsplit('/').next().unwrap_or("session")
+                );
+                self.file_dialog_state.queue(
+                    move || {
+                        Box::pin(rfd::AsyncFileDialog::new().set_file_name(file_name).save_file())
+                    },
+                    FileDialogResult::ExportSession,
+                );
+            }
             DiffViewAction::OpenSourcePath => {
                 let Ok(state) = state.read() else {
                     return;
@@ -285,12 +726,107 @@ impl DiffViewState {
                     });
                 }
             }
+            DiffViewAction::OpenInEditor(line) => {
+                let Ok(state) = state.read() else {
+                    return;
+                };
+                if let (Some(project_dir), Some(source_path)) = (
+                    &state.config.project_dir,
+                    state.config.selected_obj.as_ref().and_then(|obj| obj.source_path.as_ref()),
+                ) {
+                    let source_path = project_dir.join(source_path);
+                    if let Err(e) = open_in_editor(&state.config.editor_command, &source_path, line)
+                    {
+                        log::error!("Failed to open source file: {e:#}");
+                    }
+                }
+            }
+            DiffViewAction::CopyLink(symbol_name, view) => {
+                let Ok(state) = state.read() else {
+                    return;
+                };
+                let Some(obj_config) = state.config.selected_obj.as_ref() else {
+                    return;
+                };
+                if obj_config.cross_unit {
+                    log::warn!("Links can't refer to a \"Diff with…\" pairing");
+                    return;
+                }
+                let link = DeepLink::new(state.config.project_dir.as_deref(), &obj_config.name)
+                    .with_symbol(&symbol_name, view);
+                ctx.output_mut(|output| output.copied_text = link.to_string());
+            }
             DiffViewAction::SetDiffHighlight(column, kind) => {
                 self.function_state.set_highlight(column, kind);
             }
             DiffViewAction::ClearDiffHighlight => {
                 self.function_state.clear_highlight();
             }
+            DiffViewAction::SetFunctionSearch(search, first_row) => {
+                self.function_state.set_search(search, first_row);
+            }
+            DiffViewAction::SelectSearchMatch(index, row) => {
+                self.function_state.select_search_match(index, row);
+            }
+            DiffViewAction::SetScrollSync(scroll_sync) => {
+                self.function_state.set_scroll_sync(scroll_sync);
+            }
+            DiffViewAction::ResyncScroll => {
+                self.function_state.resync_scroll = true;
+            }
+            DiffViewAction::SetLineBlocks(value) => {
+                self.function_state.line_blocks = value;
+            }
+            DiffViewAction::ScrollColumn(column, row) => {
+                if let Some(scroll_to_row) =
+                    self.function_state.column_scroll_to_row.get_mut(column)
+                {
+                    *scroll_to_row = Some(row);
+                }
+            }
+            DiffViewAction::ToggleBookmark(column, offset) => {
+                let symbol_ref = match column {
+                    0 => &self.symbol_state.left_symbol,
+                    _ => &self.symbol_state.right_symbol,
+                };
+                let Some(symbol_ref) = symbol_ref else {
+                    return;
+                };
+                if let Ok(mut state) = state.write() {
+                    state.toggle_bookmark(symbol_ref.symbol_name.clone(), offset);
+                }
+            }
+            DiffViewAction::SelectBookmark(row) => {
+                self.function_state.select_bookmark(row);
+            }
+            DiffViewAction::MarkAnchor(pending) => {
+                self.function_state.pending_anchor = pending;
+            }
+            DiffViewAction::AddAnchor(anchor) => {
+                self.function_state.pending_anchor = None;
+                let Some(symbol_ref) = &self.symbol_state.left_symbol else {
+                    return;
+                };
+                if let Ok(mut state) = state.write() {
+                    state.add_alignment_anchor(symbol_ref.symbol_name.clone(), anchor);
+                }
+            }
+            DiffViewAction::RemoveAnchor(column, offset) => {
+                let Some(symbol_ref) = &self.symbol_state.left_symbol else {
+                    return;
+                };
+                if let Ok(mut state) = state.write() {
+                    state.remove_alignment_anchor(&symbol_ref.symbol_name, column, offset);
+                }
+            }
+            DiffViewAction::SetSizeFix(fix) => {
+                let Some(symbol_ref) = &self.symbol_state.left_symbol else {
+                    return;
+                };
+                if let Ok(mut state) = state.write() {
+                    state.set_size_fix(&symbol_ref.symbol_name, fix);
+                }
+            }
             DiffViewAction::SelectingLeft(right_ref) => {
                 if self.post_build_nav.is_some() {
                     // Ignore action if we're already navigating
@@ -346,10 +882,30 @@ impl DiffViewState {
             DiffViewAction::SetShowMappedSymbols(value) => {
                 self.symbol_state.show_mapped_symbols = value;
             }
+            DiffViewAction::SetSimilarSizeCandidates(value) => {
+                self.symbol_state.similar_size_candidates = value;
+            }
         }
     }
 }
 
+/// The build button shown in diff view headers. Shift+click forces a rebuild, ignoring the
+/// build cache.
+#[must_use]
+pub fn build_button_ui(ui: &mut Ui, build_running: bool) -> Option<DiffViewAction> {
+    let response = ui
+        .add_enabled(!build_running, egui::Button::new("Build"))
+        .on_hover_text_at_pointer("Shift+click to force a rebuild");
+    if !response.clicked() {
+        return None;
+    }
+    Some(if ui.input(|i| i.modifiers.shift) {
+        DiffViewAction::ForceBuild
+    } else {
+        DiffViewAction::Build
+    })
+}
+
 pub fn match_color_for_symbol(match_percent: f32, appearance: &Appearance) -> Color32 {
     if match_percent == 100.0 {
         appearance.insert_color
@@ -360,6 +916,26 @@ pub fn match_color_for_symbol(match_percent: f32, appearance: &Appearance) -> Co
     }
 }
 
+/// Review states and watched symbols are keyed by the target symbol's name, so symbols in the
+/// base column use the name of the symbol they're matched to.
+fn review_key(
+    symbol: &ObjSymbol,
+    symbol_diff: &ObjSymbolDiff,
+    other_ctx: Option<SymbolDiffContext<'_>>,
+    column: usize,
+) -> String {
+    if column == 0 {
+        return symbol.name.clone();
+    }
+    match (other_ctx, symbol_diff.target_symbol) {
+        (Some(other_ctx), Some(target_ref)) => {
+            other_ctx.obj.section_symbol(target_ref).1.name.clone()
+        }
+        _ => symbol.name.clone(),
+    }
+}
+
+#[expect(clippy::too_many_arguments)]
 fn symbol_context_menu_ui(
     ui: &mut Ui,
     ctx: SymbolDiffContext<'_>,
@@ -367,8 +943,11 @@ fn symbol_context_menu_ui(
     symbol: &ObjSymbol,
     symbol_diff: &ObjSymbolDiff,
     section: Option<&ObjSection>,
+    review_state: SymbolReviewState,
+    watched: bool,
+    source_path_available: bool,
     column: usize,
-) -> Option<DiffViewNavigation> {
+) -> Option<DiffViewAction> {
     let mut ret = None;
     ui.scope(|ui| {
         ui.style_mut().override_text_style = Some(egui::TextStyle::Monospace);
@@ -393,46 +972,146 @@ fn symbol_context_menu_ui(
         if let Some(section) = section {
             let has_extab =
                 ctx.obj.arch.ppc().and_then(|ppc| ppc.extab_for_symbol(symbol)).is_some();
-            if has_extab && ui.button("Decode exception table").clicked() {
-                ret = Some(DiffViewNavigation::with_symbols(
+            let has_unwind_info = ctx.obj.unwind_info_for_symbol(symbol).is_some()
+                || ctx.obj.seh_unwind_info_for_symbol(symbol).is_some();
+            if (has_extab && ui.button("Decode exception table").clicked())
+                || (!has_extab && has_unwind_info && ui.button("Decode unwind info").clicked())
+            {
+                ret = Some(DiffViewAction::Navigate(DiffViewNavigation::with_symbols(
                     View::ExtabDiff,
                     other_ctx,
                     symbol,
                     section,
                     symbol_diff,
                     column,
-                ));
+                )));
                 ui.close_menu();
             }
 
             if ui.button("Map symbol").clicked() {
                 let symbol_ref = SymbolRefByName::new(symbol, Some(section));
                 if column == 0 {
-                    ret = Some(DiffViewNavigation {
+                    ret = Some(DiffViewAction::Navigate(DiffViewNavigation {
                         view: Some(View::FunctionDiff),
                         left_symbol: Some(symbol_ref),
                         right_symbol: None,
-                    });
+                    }));
                 } else {
-                    ret = Some(DiffViewNavigation {
+                    ret = Some(DiffViewAction::Navigate(DiffViewNavigation {
                         view: Some(View::FunctionDiff),
                         left_symbol: None,
                         right_symbol: Some(symbol_ref),
-                    });
+                    }));
                 }
                 ui.close_menu();
             }
+
+            if section.kind == ObjSectionKind::Code {
+                ui.menu_button("Review state", |ui| {
+                    for (value, label) in [
+                        (SymbolReviewState::Unverified, "Unverified"),
+                        (SymbolReviewState::Matching, "Matching"),
+                        (SymbolReviewState::VerifiedEquivalent, "Verified equivalent"),
+                    ] {
+                        if ui.radio(review_state == value, label).clicked() {
+                            ret = Some(DiffViewAction::SetReviewState(
+                                review_key(symbol, symbol_diff, other_ctx, column),
+                                value,
+                            ));
+                            ui.close_menu();
+                        }
+                    }
+                });
+            }
+
+            let mut checked = watched;
+            if ui
+                .checkbox(&mut checked, "Watch match percent")
+                .on_hover_text("Notify when the match percent changes after a rebuild")
+                .clicked()
+            {
+                ret = Some(DiffViewAction::SetWatched(
+                    review_key(symbol, symbol_diff, other_ctx, column),
+                    checked,
+                ));
+                ui.close_menu();
+            }
+
+            if section.kind == ObjSectionKind::Code
+                && ui
+                    .add_enabled(source_path_available, egui::Button::new("Open in editor"))
+                    .on_hover_text("Open the source file at this function in the configured editor")
+                    .on_disabled_hover_text("Source file metadata missing")
+                    .clicked()
+            {
+                // Line info comes from the base object, which is built from the source file
+                let base_line = if column == 1 {
+                    symbol_line(section, symbol)
+                } else {
+                    other_ctx.zip(symbol_diff.target_symbol).and_then(|(other, symbol_ref)| {
+                        let (section, symbol) = other.obj.section_symbol(symbol_ref);
+                        symbol_line(section?, symbol)
+                    })
+                };
+                ret = Some(DiffViewAction::OpenInEditor(
+                    base_line.or_else(|| symbol_line(section, symbol)),
+                ));
+                ui.close_menu();
+            }
+
+            if ui
+                .button("Copy link")
+                .on_hover_text("Copy an objdiff:// link that opens this symbol")
+                .clicked()
+            {
+                let view = match section.kind {
+                    ObjSectionKind::Code => View::FunctionDiff,
+                    ObjSectionKind::Data | ObjSectionKind::Bss => View::DataDiff,
+                };
+                ret = Some(DiffViewAction::CopyLink(
+                    review_key(symbol, symbol_diff, other_ctx, column),
+                    view,
+                ));
+                ui.close_menu();
+            }
+
+            if section.kind == ObjSectionKind::Code {
+                if let Some(path) = &ctx.obj.path {
+                    if ui.button("Diff with…").clicked() {
+                        ret = Some(DiffViewAction::DiffWith(CrossDiffSource {
+                            symbol_name: symbol.name.clone(),
+                            display_name: symbol
+                                .demangled_name
+                                .clone()
+                                .unwrap_or_else(|| symbol.name.clone()),
+                            path: path.clone(),
+                        }));
+                        ui.close_menu();
+                    }
+                }
+            }
         }
     });
     ret
 }
 
-fn symbol_hover_ui(ui: &mut Ui, arch: &dyn ObjArch, symbol: &ObjSymbol, appearance: &Appearance) {
+fn symbol_hover_ui(
+    ui: &mut Ui,
+    ctx: SymbolDiffContext<'_>,
+    other_ctx: Option<SymbolDiffContext<'_>>,
+    symbol: &ObjSymbol,
+    symbol_diff: &ObjSymbolDiff,
+    appearance: &Appearance,
+) {
+    let arch = ctx.obj.arch.as_ref();
     ui.scope(|ui| {
         ui.style_mut().override_text_style = Some(egui::TextStyle::Monospace);
         ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
 
         ui.colored_label(appearance.highlight_color, format!("Name: {}", symbol.name));
+        if let Some(demangled) = &symbol.demangled_name {
+            ui.colored_label(appearance.highlight_color, format!("Demangled: {demangled}"));
+        }
         ui.colored_label(appearance.highlight_color, format!("Address: {:x}", symbol.address));
         if symbol.size_known {
             ui.colored_label(appearance.highlight_color, format!("Size: {:x}", symbol.size));
@@ -442,6 +1121,54 @@ fn symbol_hover_ui(ui: &mut Ui, arch: &dyn ObjArch, symbol: &ObjSymbol, appearan
                 format!("Size: {:x} (assumed)", symbol.size),
             );
         }
+        let (section, _) = ctx.obj.section_symbol(symbol_diff.symbol_ref);
+        if let Some(section) = section {
+            ui.colored_label(appearance.highlight_color, format!("Section: {}", section.name));
+            if let Some(summary) = ctx.diff.data_types.get(&symbol_diff.symbol_ref) {
+                let color = if summary.is_mixed() {
+                    appearance.replace_color
+                } else {
+                    appearance.highlight_color
+                };
+                ui.colored_label(color, format!("Accessed as: {summary}"));
+            }
+        }
+        let padding = ctx.obj.symbol_padding(symbol_diff.symbol_ref);
+        if let Some(padding) = padding {
+            ui.colored_label(appearance.highlight_color, format!("Padding: {:x}", padding));
+        }
+        if let (Some(other_ctx), Some(target_ref)) = (other_ctx, symbol_diff.target_symbol) {
+            let (other_section, _) = other_ctx.obj.section_symbol(target_ref);
+            if let (Some(section), Some(other_section)) = (section, other_section) {
+                if section.name != other_section.name {
+                    ui.colored_label(
+                        appearance.replace_color,
+                        format!("Other side is in section: {}", other_section.name),
+                    );
+                }
+            }
+            if symbol_diff.ambiguous_match {
+                ui.colored_label(
+                    appearance.replace_color,
+                    "Matched by section and order among symbols with the same name",
+                );
+            }
+            if symbol_diff.too_different {
+                ui.colored_label(
+                    appearance.delete_color,
+                    "Too different to align, instructions paired by position",
+                );
+            }
+            let other_padding = other_ctx.obj.symbol_padding(target_ref);
+            if let (Some(padding), Some(other_padding)) = (padding, other_padding) {
+                if padding != other_padding {
+                    ui.colored_label(
+                        appearance.replace_color,
+                        format!("Other side padding: {:x}", other_padding),
+                    );
+                }
+            }
+        }
         if let Some(address) = symbol.virtual_address {
             ui.colored_label(appearance.replace_color, format!("Virtual address: {:#x}", address));
         }
@@ -455,6 +1182,37 @@ fn symbol_hover_ui(ui: &mut Ui, arch: &dyn ObjArch, symbol: &ObjSymbol, appearan
                 format!("extabindex symbol: {}", &extab.eti_symbol.name),
             );
         }
+        if let Some(unwind) = ctx.obj.unwind_info_for_symbol(symbol) {
+            ui.colored_label(
+                appearance.highlight_color,
+                format!("FDE: .eh_frame+{:#x}", unwind.fde_offset),
+            );
+            if let Some(lsda) = &unwind.lsda {
+                ui.colored_label(
+                    appearance.highlight_color,
+                    format!(
+                        "LSDA: {}+{:#x} ({} call sites)",
+                        lsda.section_name,
+                        lsda.offset,
+                        lsda.call_sites.len()
+                    ),
+                );
+            }
+        }
+        if let Some(seh) = ctx.obj.seh_unwind_info_for_symbol(symbol) {
+            for entry in &seh.entries {
+                let location = match &entry.data {
+                    SehUnwindData::Packed { .. } => "packed".to_string(),
+                    SehUnwindData::Xdata(xdata) => {
+                        format!("{}+{:#x}", xdata.section_name, xdata.offset)
+                    }
+                };
+                ui.colored_label(
+                    appearance.highlight_color,
+                    format!(".pdata+{:#x}: +{:#x} ({location})", entry.pdata_offset, entry.start),
+                );
+            }
+        }
     });
 }
 
@@ -478,6 +1236,7 @@ fn symbol_ui(
     let mut job = LayoutJob::default();
     let name: &str =
         if let Some(demangled) = &symbol.demangled_name { demangled } else { &symbol.name };
+    let name = if state.short_symbol_names { short_symbol_name(name) } else { Cow::Borrowed(name) };
     let mut selected = false;
     if let Some(sym_ref) =
         if column == 0 { state.highlighted_symbol.0 } else { state.highlighted_symbol.1 }
@@ -509,6 +1268,21 @@ fn symbol_ui(
         }
         write_text("] ", appearance.text_color, &mut job, appearance.code_font.clone());
     }
+    let key = review_key(symbol, symbol_diff, other_ctx, column);
+    let review_state = state.review_states.get(&key).copied().unwrap_or_default();
+    let watched = state.watched_symbols.contains(&key);
+    match review_state {
+        SymbolReviewState::Unverified => {}
+        SymbolReviewState::Matching => {
+            write_text("✔ ", appearance.insert_color, &mut job, appearance.code_font.clone());
+        }
+        SymbolReviewState::VerifiedEquivalent => {
+            write_text("≡ ", appearance.quirk_color, &mut job, appearance.code_font.clone());
+        }
+    }
+    if watched {
+        write_text("👁 ", appearance.replace_color, &mut job, appearance.code_font.clone());
+    }
     if let Some(match_percent) = symbol_diff.match_percent {
         write_text("(", appearance.text_color, &mut job, appearance.code_font.clone());
         write_text(
@@ -519,15 +1293,62 @@ fn symbol_ui(
         );
         write_text(") ", appearance.text_color, &mut job, appearance.code_font.clone());
     }
-    write_text(name, appearance.highlight_color, &mut job, appearance.code_font.clone());
+    if let Some(opcode_percent) = symbol_diff
+        .opcode_match_percent
+        .filter(|_| state.show_opcode_percent && symbol_diff.match_percent != Some(100.0))
+    {
+        write_text(
+            &format!("[{:.0}% ops] ", opcode_percent.floor()),
+            appearance.deemphasized_text_color,
+            &mut job,
+            appearance.code_font.clone(),
+        );
+    }
+    let name = match state.name_column_width {
+        Some(width) => {
+            let char_width = ui.fonts(|f| f.glyph_width(&appearance.code_font, 'M'));
+            let max_chars = (width / char_width) as usize;
+            middle_ellipsis(&name, max_chars.saturating_sub(job.text.chars().count()))
+        }
+        None => name,
+    };
+    write_text(&name, appearance.highlight_color, &mut job, appearance.code_font.clone());
+    if let (Some(section), Some(other_ctx), Some(target_ref)) =
+        (section, other_ctx, symbol_diff.target_symbol)
+    {
+        // Flag functions that were placed in a different input section on the other side,
+        // e.g. hot/cold splitting into .text.hot or .text.unlikely
+        if let (Some(other_section), _) = other_ctx.obj.section_symbol(target_ref) {
+            if other_section.name != section.name {
+                write_text(
+                    &format!(" [{}]", other_section.name),
+                    appearance.replace_color,
+                    &mut job,
+                    appearance.code_font.clone(),
+                );
+            }
+        }
+    }
+    if symbol_diff.ambiguous_match {
+        write_text(" ?", appearance.replace_color, &mut job, appearance.code_font.clone());
+    }
     let response = SelectableLabel::new(selected, job).ui(ui).on_hover_ui_at_pointer(|ui| {
-        symbol_hover_ui(ui, ctx.obj.arch.as_ref(), symbol, appearance)
+        symbol_hover_ui(ui, ctx, other_ctx, symbol, symbol_diff, appearance)
     });
     response.context_menu(|ui| {
-        if let Some(result) =
-            symbol_context_menu_ui(ui, ctx, other_ctx, symbol, symbol_diff, section, column)
-        {
-            ret = Some(DiffViewAction::Navigate(result));
+        if let Some(result) = symbol_context_menu_ui(
+            ui,
+            ctx,
+            other_ctx,
+            symbol,
+            symbol_diff,
+            section,
+            review_state,
+            watched,
+            state.source_path_available,
+            column,
+        ) {
+            ret = Some(result);
         }
     });
     if selected && state.autoscroll_to_highlighted_symbols {
@@ -582,7 +1403,112 @@ fn symbol_ui(
     ret
 }
 
-fn symbol_matches_filter(
+/// Minimum number of characters kept when truncating a symbol name.
+const MIN_NAME_CHARS: usize = 8;
+
+/// Shortens a name to at most `max_chars` characters by replacing its middle with an ellipsis,
+/// keeping the start of the qualified name and the end of the parameter list visible.
+fn middle_ellipsis(name: &str, max_chars: usize) -> Cow<'_, str> {
+    let max_chars = max_chars.max(MIN_NAME_CHARS);
+    let len = name.chars().count();
+    if len <= max_chars {
+        return Cow::Borrowed(name);
+    }
+    let tail = (max_chars - 1) / 2;
+    let head = max_chars - 1 - tail;
+    let mut result = name.chars().take(head).collect::<String>();
+    result.push('…');
+    result.extend(name.chars().skip(len - tail));
+    Cow::Owned(result)
+}
+
+/// Returns the unqualified name of a demangled C++ symbol, without scope qualifiers, return
+/// type or parameters, e.g. `ns::Foo<int>::bar(int, float) const` becomes `bar(…)`.
+fn short_symbol_name(name: &str) -> Cow<'_, str> {
+    let bytes = name.as_bytes();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut end = name.len();
+    let mut i = 0;
+    while i < bytes.len() {
+        if i == start && name[i..].starts_with("operator") {
+            // Skip operator symbols such as `operator<` and `operator()`
+            i += "operator".len();
+            if name[i..].starts_with("()") {
+                i += 2;
+            }
+            while i < bytes.len() && bytes[i] != b'(' {
+                i += 1;
+            }
+            end = i;
+            break;
+        }
+        match bytes[i] {
+            b'<' => depth += 1,
+            b'>' => depth = depth.saturating_sub(1),
+            b'(' if depth == 0 => {
+                end = i;
+                break;
+            }
+            b':' if depth == 0 && bytes.get(i + 1) == Some(&b':') => {
+                start = i + 2;
+                i += 1;
+            }
+            b' ' if depth == 0 => start = i + 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    let params = &name[end..];
+    if start == 0 && params.is_empty() {
+        return Cow::Borrowed(name);
+    }
+    let suffix = if params.is_empty() {
+        ""
+    } else if params.starts_with("()") || params.starts_with("(void)") {
+        "()"
+    } else {
+        "(…)"
+    };
+    Cow::Owned(format!("{}{}", &name[start..end], suffix))
+}
+
+/// Minimum width of the symbol name column, in points.
+const MIN_NAME_COLUMN_WIDTH: f32 = 80.0;
+
+/// Draws a handle above the symbol list that sets the maximum symbol name width when dragged,
+/// or removes the limit when double-clicked.
+fn name_column_handle_ui(
+    ui: &mut Ui,
+    state: &SymbolViewState,
+    appearance: &Appearance,
+) -> Option<DiffViewAction> {
+    let (rect, response) =
+        ui.allocate_exact_size(egui::vec2(ui.available_width(), 6.0), Sense::click_and_drag());
+    let response = response
+        .on_hover_cursor(CursorIcon::ResizeHorizontal)
+        .on_hover_text_at_pointer("Drag to limit the symbol name width, double-click to reset");
+    let width = state.name_column_width.unwrap_or(rect.width()).min(rect.width());
+    let color = if response.hovered() || response.dragged() {
+        appearance.highlight_color
+    } else {
+        appearance.deemphasized_text_color
+    };
+    let x = rect.left() + width;
+    ui.painter().hline(rect.left()..=x, rect.center().y, Stroke::new(1.0, color));
+    ui.painter().vline(x - 1.0, rect.y_range(), Stroke::new(2.0, color));
+    if response.double_clicked() {
+        return Some(DiffViewAction::SetNameColumnWidth(None));
+    }
+    if response.dragged() {
+        let pos = response.interact_pointer_pos()?;
+        let width = (pos.x - rect.left()).max(MIN_NAME_COLUMN_WIDTH);
+        return Some(DiffViewAction::SetNameColumnWidth(Some(width)));
+    }
+    None
+}
+
+pub fn symbol_matches_filter(
     symbol: &ObjSymbol,
     diff: &ObjSymbolDiff,
     filter: SymbolFilter<'_>,
@@ -593,7 +1519,7 @@ fn symbol_matches_filter(
             regex.is_match(&symbol.name)
                 || symbol.demangled_name.as_ref().map(|s| regex.is_match(s)).unwrap_or(false)
         }
-        SymbolFilter::Mapping(symbol_ref) => diff.target_symbol == Some(symbol_ref),
+        SymbolFilter::Mapping(symbol_ref, _) => diff.target_symbol == Some(symbol_ref),
     }
 }
 
@@ -601,7 +1527,13 @@ fn symbol_matches_filter(
 pub enum SymbolFilter<'a> {
     None,
     Search(&'a Regex),
-    Mapping(SymbolRef),
+    /// Candidates for mapping the given symbol of the other object, with its size
+    Mapping(SymbolRef, u64),
+}
+
+/// Whether the sizes are within a factor of two of each other.
+fn is_similar_size(size: u64, target_size: u64) -> bool {
+    target_size == 0 || (size >= target_size / 2 && size <= target_size.saturating_mul(2))
 }
 
 #[must_use]
@@ -616,14 +1548,24 @@ pub fn symbol_list_ui(
     column: usize,
     open_sections: Option<bool>,
 ) -> Option<DiffViewAction> {
-    let mut ret = None;
+    let mut ret = name_column_handle_ui(ui, state, appearance);
     ScrollArea::both().auto_shrink([false, false]).show(ui, |ui| {
         let mut mapping = BTreeMap::new();
-        if let SymbolFilter::Mapping(target_ref) = filter {
+        if let SymbolFilter::Mapping(target_ref, target_size) = filter {
             let mut show_mapped_symbols = state.show_mapped_symbols;
-            if ui.checkbox(&mut show_mapped_symbols, "Show mapped symbols").changed() {
-                ret = Some(DiffViewAction::SetShowMappedSymbols(show_mapped_symbols));
-            }
+            let mut similar_size_candidates = state.similar_size_candidates;
+            ui.horizontal(|ui| {
+                if ui.checkbox(&mut show_mapped_symbols, "Show mapped symbols").changed() {
+                    ret = Some(DiffViewAction::SetShowMappedSymbols(show_mapped_symbols));
+                }
+                if ui
+                    .checkbox(&mut similar_size_candidates, "Similar size only")
+                    .on_hover_text("Hide symbols less than half or more than twice the size")
+                    .changed()
+                {
+                    ret = Some(DiffViewAction::SetSimilarSizeCandidates(similar_size_candidates));
+                }
+            });
             for mapping_diff in &ctx.diff.mapping_symbols {
                 if mapping_diff.target_symbol == Some(target_ref) {
                     if !show_mapped_symbols {
@@ -632,6 +1574,12 @@ pub fn symbol_list_ui(
                             continue;
                         }
                     }
+                    if similar_size_candidates {
+                        let (_, symbol) = ctx.obj.section_symbol(mapping_diff.symbol_ref);
+                        if !is_similar_size(symbol.size, target_size) {
+                            continue;
+                        }
+                    }
                     mapping.insert(mapping_diff.symbol_ref, mapping_diff);
                 }
             }
@@ -904,6 +1852,42 @@ pub fn symbol_diff_ui(
                 if response.changed() {
                     ret = Some(DiffViewAction::SetSearch(search));
                 }
+                let mut show_overview = state.symbol_state.show_overview;
+                if ui
+                    .toggle_value(&mut show_overview, "▤ Overview")
+                    .on_hover_text_at_pointer(
+                        "Show every function as a bar of matching and mismatching instructions",
+                    )
+                    .clicked()
+                {
+                    ret = Some(DiffViewAction::SetShowOverview(show_overview));
+                }
+                let mut show_layout = state.symbol_state.show_layout;
+                if ui
+                    .toggle_value(&mut show_layout, "▥ Layout")
+                    .on_hover_text_at_pointer(
+                        "Compare the symbol layout of each section between both objects",
+                    )
+                    .clicked()
+                {
+                    ret = Some(DiffViewAction::SetShowLayout(show_layout));
+                }
+                let has_initializers = [&result.first_obj, &result.second_obj]
+                    .into_iter()
+                    .flatten()
+                    .any(|(obj, _)| obj.sections.iter().any(|s| is_initializer_section(&s.name)));
+                if has_initializers {
+                    let mut show_initializers = state.symbol_state.show_initializers;
+                    if ui
+                        .toggle_value(&mut show_initializers, "⇅ Initializers")
+                        .on_hover_text_at_pointer(
+                            "Compare the order of static initializers and finalizers",
+                        )
+                        .clicked()
+                    {
+                        ret = Some(DiffViewAction::SetShowInitializers(show_initializers));
+                    }
+                }
 
                 ui.with_layout(Layout::right_to_left(egui::Align::TOP), |ui| {
                     if ui.small_button("⏷").on_hover_text_at_pointer("Expand all").clicked() {
@@ -912,6 +1896,28 @@ pub fn symbol_diff_ui(
                     if ui.small_button("⏶").on_hover_text_at_pointer("Collapse all").clicked() {
                         open_sections.0 = Some(false);
                     }
+                    if ui
+                        .add_enabled(
+                            result.first_obj.is_some() || result.second_obj.is_some(),
+                            egui::Button::new("📦").small(),
+                        )
+                        .on_hover_text_at_pointer(
+                            "Export the objects and diff settings as a session file for \
+                            offline review",
+                        )
+                        .clicked()
+                    {
+                        ret = Some(DiffViewAction::ExportSession);
+                    }
+                    if ui
+                        .add_enabled(result.first_obj.is_some(), egui::Button::new("⇅").small())
+                        .on_hover_text_at_pointer(
+                            "Export the target symbol order as a linker order file",
+                        )
+                        .clicked()
+                    {
+                        ret = Some(DiffViewAction::ExportSymbolOrder);
+                    }
                 })
             });
         } else if column == 1 {
@@ -946,8 +1952,8 @@ pub fn symbol_diff_ui(
             });
 
             ui.horizontal(|ui| {
-                if ui.add_enabled(!state.build_running, egui::Button::new("Build")).clicked() {
-                    ret = Some(DiffViewAction::Build);
+                if let Some(action) = build_button_ui(ui, state.build_running) {
+                    ret = Some(action);
                 }
 
                 ui.with_layout(Layout::right_to_left(egui::Align::TOP), |ui| {
@@ -967,6 +1973,35 @@ pub fn symbol_diff_ui(
         Some(regex) => SymbolFilter::Search(regex),
         _ => SymbolFilter::None,
     };
+    if state.symbol_state.show_initializers {
+        let first = result.first_obj.as_ref().map(|(obj, _)| obj);
+        let second = result.second_obj.as_ref().map(|(obj, _)| obj);
+        initializers_ui(ui, first, second, appearance);
+        return ret;
+    }
+    if state.symbol_state.show_layout {
+        let first = result.first_obj.as_ref().map(|(obj, diff)| SymbolDiffContext { obj, diff });
+        let second = result.second_obj.as_ref().map(|(obj, diff)| SymbolDiffContext { obj, diff });
+        if let Some(result) = section_layout_ui(ui, first, second, appearance) {
+            ret = Some(result);
+        }
+        return ret;
+    }
+    if state.symbol_state.show_overview {
+        let first = result.first_obj.as_ref().map(|(obj, diff)| SymbolDiffContext { obj, diff });
+        let second = result.second_obj.as_ref().map(|(obj, diff)| SymbolDiffContext { obj, diff });
+        let (ctx, other_ctx, column) = match (first, second) {
+            (Some(first), second) => (first, second, 0),
+            (None, Some(second)) => (second, None, 1),
+            (None, None) => return ret,
+        };
+        if let Some(result) =
+            unit_overview_ui(ui, ctx, other_ctx, &state.symbol_state, filter, appearance, column)
+        {
+            ret = Some(result);
+        }
+        return ret;
+    }
     render_strips(ui, available_width, 2, |ui, column| {
         if column == 0 {
             // Left column
@@ -0,0 +1,165 @@
+use std::mem::take;
+
+use egui::{RichText, ScrollArea, SelectableLabel, TextEdit, TextStyle};
+use objdiff_core::jobs::{
+    symbol_index::{start_symbol_index, SymbolIndexConfig, SymbolIndexResult},
+    Job, JobQueue, JobResult,
+};
+
+use crate::{
+    app::{AppStateRef, ObjectConfig},
+    jobs::egui_waker,
+    views::{
+        appearance::Appearance,
+        symbol_diff::{
+            match_color_for_symbol, DiffViewNavigation, DiffViewState, SymbolRefByName, View,
+        },
+    },
+};
+
+/// Maximum number of results listed.
+const MAX_RESULTS: usize = 200;
+
+/// Finds which unit contains a function, using a project-wide index built by diffing every unit.
+#[derive(Default)]
+pub struct SymbolLookupViewState {
+    query: String,
+    index: Option<Box<SymbolIndexResult>>,
+    running: bool,
+    queue_index: bool,
+}
+
+impl SymbolLookupViewState {
+    pub fn pre_update(&mut self, jobs: &mut JobQueue) {
+        jobs.results.retain_mut(|result| {
+            if let JobResult::SymbolIndex(result) = result {
+                self.index = take(result);
+                false
+            } else {
+                true
+            }
+        });
+        self.running = jobs.is_running(Job::SymbolIndex);
+    }
+
+    pub fn post_update(&mut self, ctx: &egui::Context, jobs: &mut JobQueue, state: &AppStateRef) {
+        if !take(&mut self.queue_index) {
+            return;
+        }
+        let Ok(state) = state.read() else {
+            return;
+        };
+        let config = SymbolIndexConfig {
+            objects: state.objects.clone(),
+            diff_obj_config: state.config.diff_obj_config.clone(),
+        };
+        jobs.push_once(Job::SymbolIndex, || start_symbol_index(egui_waker(ctx), config));
+    }
+}
+
+pub fn symbol_lookup_window(
+    ctx: &egui::Context,
+    show: &mut bool,
+    state: &mut SymbolLookupViewState,
+    app_state: &AppStateRef,
+    diff_state: &mut DiffViewState,
+    appearance: &Appearance,
+) {
+    let mut selected = None;
+    egui::Window::new("Symbol Lookup").open(show).show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            TextEdit::singleline(&mut state.query)
+                .hint_text("Function name")
+                .font(TextStyle::Monospace)
+                .show(ui);
+            let label = if state.index.is_some() { "Rebuild index" } else { "Build index" };
+            if ui
+                .add_enabled(!state.running, egui::Button::new(label))
+                .on_hover_text("Diff every unit in the project to index its functions")
+                .clicked()
+            {
+                state.queue_index = true;
+            }
+        });
+        if state.running {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Indexing…");
+            });
+        }
+        let Some(index) = &state.index else {
+            if !state.running {
+                ui.label("Build the index to look up functions across all units.");
+            }
+            return;
+        };
+        ui.label(format!("{} units indexed", index.report.units.len())).on_hover_ui(|ui| {
+            for (unit, error) in &index.errors {
+                ui.colored_label(appearance.delete_color, format!("{unit}: {error}"));
+            }
+        });
+        if !index.errors.is_empty() {
+            ui.colored_label(
+                appearance.delete_color,
+                format!("{} units failed to load", index.errors.len()),
+            );
+        }
+        ui.separator();
+        if state.query.is_empty() {
+            return;
+        }
+        let results = index.report.lookup_symbol(&state.query);
+        if results.is_empty() {
+            ui.label("No matching functions");
+            return;
+        }
+        ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+            ui.style_mut().override_text_style = Some(TextStyle::Monospace);
+            for result in results.iter().take(MAX_RESULTS) {
+                let name = result.demangled_name().unwrap_or(&result.function.name);
+                ui.horizontal(|ui| {
+                    let match_percent = result.function.fuzzy_match_percent;
+                    ui.label(
+                        RichText::new(format!("{:>3.0}%", match_percent.floor()))
+                            .color(match_color_for_symbol(match_percent, appearance)),
+                    );
+                    if ui
+                        .add(SelectableLabel::new(false, format!("{}: {name}", result.unit.name)))
+                        .on_hover_text("Open the function diff")
+                        .clicked()
+                    {
+                        selected = Some((result.unit.name.clone(), result.function.name.clone()));
+                    }
+                });
+            }
+            if results.len() > MAX_RESULTS {
+                ui.label(format!("…and {} more", results.len() - MAX_RESULTS));
+            }
+        });
+    });
+
+    let Some((unit_name, symbol_name)) = selected else {
+        return;
+    };
+    let Ok(mut app_state) = app_state.write() else {
+        return;
+    };
+    let Some(object) = app_state.objects.iter().find(|o| o.name() == unit_name) else {
+        return;
+    };
+    let config = ObjectConfig::from(object);
+    let symbol_ref = SymbolRefByName { symbol_name, section_name: None };
+    if app_state.config.selected_obj.as_ref() == Some(&config) && diff_state.build.is_some() {
+        // Already loaded, navigate directly
+        diff_state.current_view = View::FunctionDiff;
+        diff_state.symbol_state.left_symbol = Some(symbol_ref.clone());
+        diff_state.symbol_state.right_symbol = Some(symbol_ref);
+        return;
+    }
+    app_state.set_selected_obj(config);
+    diff_state.post_build_nav = Some(DiffViewNavigation {
+        view: Some(View::FunctionDiff),
+        left_symbol: Some(symbol_ref.clone()),
+        right_symbol: Some(symbol_ref),
+    });
+}
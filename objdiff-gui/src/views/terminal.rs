@@ -0,0 +1,221 @@
+use std::{collections::VecDeque, mem::take};
+
+use egui::{Color32, Key, Modifiers, RichText, ScrollArea, TextEdit};
+use objdiff_core::{
+    build::BuildConfig,
+    jobs::{
+        command::{start_command, CommandConfig, CommandResult},
+        Job, JobQueue, JobResult,
+    },
+};
+
+use crate::{app::AppStateRef, jobs::egui_waker, views::appearance::Appearance};
+
+/// Maximum number of lines kept in the terminal scrollback.
+const MAX_LINES: usize = 5000;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum LineKind {
+    Command,
+    Output,
+    Warning,
+    Error,
+}
+
+/// A panel for running commands (e.g. `ninja` or `git status`) in the project directory.
+#[derive(Default)]
+pub struct TerminalViewState {
+    input: String,
+    lines: VecDeque<(LineKind, String)>,
+    /// Previously run command lines, most recent last
+    history: Vec<String>,
+    /// Position in `history` while browsing it with the arrow keys
+    history_index: Option<usize>,
+    queue_command: Option<String>,
+    running: bool,
+}
+
+impl TerminalViewState {
+    pub fn pre_update(&mut self, jobs: &mut JobQueue) {
+        jobs.results.retain_mut(|result| {
+            if let JobResult::Command(result) = result {
+                if let Some(result) = take(result) {
+                    self.push_result(*result);
+                }
+                false
+            } else {
+                true
+            }
+        });
+        self.running = jobs.is_running(Job::Command);
+    }
+
+    pub fn post_update(&mut self, ctx: &egui::Context, jobs: &mut JobQueue, state: &AppStateRef) {
+        let Some(cmdline) = self.queue_command.take() else {
+            return;
+        };
+        let Ok(state) = state.read() else {
+            return;
+        };
+        let config = CommandConfig { build_config: BuildConfig::from(&state.config), cmdline };
+        jobs.push_once(Job::Command, || start_command(egui_waker(ctx), config));
+    }
+
+    fn push_line(&mut self, kind: LineKind, line: String) {
+        if self.lines.len() >= MAX_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back((kind, line));
+    }
+
+    fn push_result(&mut self, result: CommandResult) {
+        for line in result.output {
+            self.push_line(classify_line(&line), line);
+        }
+        if !result.success {
+            self.push_line(LineKind::Error, format!("Command failed: {}", result.cmdline));
+        }
+    }
+
+    fn submit(&mut self) {
+        let cmdline = self.input.trim().to_string();
+        self.input.clear();
+        self.history_index = None;
+        if cmdline.is_empty() {
+            return;
+        }
+        self.push_line(LineKind::Command, format!("$ {cmdline}"));
+        if self.history.last() != Some(&cmdline) {
+            self.history.push(cmdline.clone());
+        }
+        self.queue_command = Some(cmdline);
+    }
+
+    /// Steps through the command history, towards older entries if `back` is set.
+    fn browse_history(&mut self, back: bool) {
+        let index = match (self.history_index, back) {
+            (None, true) => self.history.len().checked_sub(1),
+            (None, false) => None,
+            (Some(i), true) => Some(i.saturating_sub(1)),
+            (Some(i), false) => Some(i + 1).filter(|&i| i < self.history.len()),
+        };
+        self.history_index = index;
+        self.input = index.map(|i| self.history[i].clone()).unwrap_or_default();
+    }
+}
+
+/// Classifies a line of output by the diagnostics it reports, such as
+/// `src/main.c:12: error: …` or ninja's `FAILED: build/main.o`.
+fn classify_line(line: &str) -> LineKind {
+    let mut kind = LineKind::Output;
+    for word in line.split(|c: char| !c.is_ascii_alphabetic()) {
+        if word.eq_ignore_ascii_case("error")
+            || word.eq_ignore_ascii_case("fatal")
+            || word.eq_ignore_ascii_case("failed")
+        {
+            return LineKind::Error;
+        }
+        if word.eq_ignore_ascii_case("warning") {
+            kind = LineKind::Warning;
+        }
+    }
+    kind
+}
+
+fn line_color(kind: LineKind, appearance: &Appearance) -> Color32 {
+    match kind {
+        LineKind::Command => appearance.emphasized_text_color,
+        LineKind::Output => appearance.text_color,
+        LineKind::Warning => appearance.replace_color,
+        LineKind::Error => appearance.delete_color,
+    }
+}
+
+pub fn terminal_panel(
+    ctx: &egui::Context,
+    show: &mut bool,
+    state: &mut TerminalViewState,
+    jobs: &mut JobQueue,
+    app_state: &AppStateRef,
+    appearance: &Appearance,
+) {
+    let has_project_dir = app_state.read().is_ok_and(|state| state.config.project_dir.is_some());
+    egui::TopBottomPanel::bottom("terminal_panel")
+        .resizable(true)
+        .default_height(200.0)
+        .show_animated(ctx, *show, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading("Terminal");
+                if ui.button("Clear").clicked() {
+                    state.lines.clear();
+                }
+                if state.running && ui.button("Cancel").clicked() {
+                    for job in jobs.iter_mut().filter(|job| job.kind == Job::Command) {
+                        job.cancel.cancel();
+                    }
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.small_button("✖").on_hover_text("Close terminal").clicked() {
+                        *show = false;
+                    }
+                });
+            });
+            ui.separator();
+
+            let input_height = ui.spacing().interact_size.y + ui.spacing().item_spacing.y * 2.0;
+            ScrollArea::both()
+                .auto_shrink([false, false])
+                .max_height((ui.available_height() - input_height).max(0.0))
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    ui.style_mut().override_text_style = Some(egui::TextStyle::Monospace);
+                    ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+                    for (kind, line) in &state.lines {
+                        ui.label(RichText::new(line).color(line_color(*kind, appearance)));
+                    }
+                    // Output of the running command, until its result is collected
+                    for job in jobs
+                        .iter_mut()
+                        .filter(|job| job.kind == Job::Command && job.handle.is_some())
+                    {
+                        let Ok(status) = job.context.status.read() else {
+                            continue;
+                        };
+                        for line in &status.output {
+                            let color = line_color(classify_line(line), appearance);
+                            ui.label(RichText::new(line).color(color));
+                        }
+                    }
+                });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label(RichText::new("$").font(appearance.code_font.clone()));
+                let enabled = has_project_dir && !state.running;
+                let hint = if has_project_dir {
+                    "Run a command in the project directory"
+                } else {
+                    "Open a project to run commands"
+                };
+                let response = ui.add_enabled(
+                    enabled,
+                    TextEdit::singleline(&mut state.input)
+                        .font(appearance.code_font.clone())
+                        .hint_text(hint)
+                        .desired_width(f32::INFINITY),
+                );
+                if response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+                    state.submit();
+                    response.request_focus();
+                }
+                if response.has_focus() {
+                    if ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::ArrowUp)) {
+                        state.browse_history(true);
+                    }
+                    if ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::ArrowDown)) {
+                        state.browse_history(false);
+                    }
+                }
+            });
+        });
+}
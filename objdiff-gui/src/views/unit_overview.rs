@@ -0,0 +1,131 @@
+use egui::{pos2, vec2, Color32, Label, Rect, ScrollArea, Sense, Ui};
+use objdiff_core::{
+    diff::{ObjInsDiffKind, ObjSymbolDiff},
+    obj::{ObjSectionKind, ObjSymbolFlags, ObjSymbolKind},
+};
+
+use crate::views::{
+    appearance::Appearance,
+    symbol_diff::{
+        match_color_for_symbol, symbol_matches_filter, DiffViewAction, DiffViewNavigation,
+        SymbolDiffContext, SymbolFilter, SymbolViewState, View,
+    },
+};
+
+/// Fraction of the available width used for function names.
+const NAME_WIDTH_FRACTION: f32 = 0.3;
+const BAR_HEIGHT: f32 = 10.0;
+
+fn segment_color(kind: ObjInsDiffKind, appearance: &Appearance) -> Color32 {
+    match kind {
+        ObjInsDiffKind::None => appearance.insert_color,
+        ObjInsDiffKind::Quirk | ObjInsDiffKind::SoftMismatch => appearance.quirk_color,
+        ObjInsDiffKind::OpMismatch | ObjInsDiffKind::ArgMismatch | ObjInsDiffKind::Replace => {
+            appearance.replace_color
+        }
+        ObjInsDiffKind::Delete | ObjInsDiffKind::Insert => appearance.delete_color,
+    }
+}
+
+/// Collapses consecutive instructions with the same color into `(start, len, color)` runs.
+fn bar_segments(
+    symbol_diff: &ObjSymbolDiff,
+    appearance: &Appearance,
+) -> Vec<(usize, usize, Color32)> {
+    let mut segments: Vec<(usize, usize, Color32)> = Vec::new();
+    for (i, ins_diff) in symbol_diff.instructions.iter().enumerate() {
+        let color = segment_color(ins_diff.kind, appearance);
+        match segments.last_mut() {
+            Some((_, len, last)) if *last == color => *len += 1,
+            _ => segments.push((i, 1, color)),
+        }
+    }
+    segments
+}
+
+/// Draws every function in the object as a bar segmented by instruction match status.
+#[must_use]
+pub fn unit_overview_ui(
+    ui: &mut Ui,
+    ctx: SymbolDiffContext<'_>,
+    other_ctx: Option<SymbolDiffContext<'_>>,
+    state: &SymbolViewState,
+    filter: SymbolFilter<'_>,
+    appearance: &Appearance,
+    column: usize,
+) -> Option<DiffViewAction> {
+    let mut ret = None;
+    ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+        ui.style_mut().override_text_style = Some(egui::TextStyle::Monospace);
+        let name_width = ui.available_width() * NAME_WIDTH_FRACTION;
+        for (section, section_diff) in ctx.obj.sections.iter().zip(&ctx.diff.sections) {
+            if section.kind != ObjSectionKind::Code {
+                continue;
+            }
+            for (symbol, symbol_diff) in section.symbols.iter().zip(&section_diff.symbols) {
+                if symbol.kind != ObjSymbolKind::Function
+                    || (symbol.flags.0.contains(ObjSymbolFlags::Hidden)
+                        && !state.show_hidden_symbols)
+                    || !symbol_matches_filter(symbol, symbol_diff, filter)
+                {
+                    continue;
+                }
+                let name = symbol.demangled_name.as_deref().unwrap_or(&symbol.name);
+                let total = symbol_diff.instructions.len();
+                let matched = symbol_diff
+                    .instructions
+                    .iter()
+                    .filter(|i| matches!(i.kind, ObjInsDiffKind::None | ObjInsDiffKind::Quirk))
+                    .count();
+                let mut clicked = false;
+                ui.horizontal(|ui| {
+                    let label = ui.add_sized(
+                        vec2(name_width, ui.spacing().interact_size.y),
+                        Label::new(name).truncate().sense(Sense::click()),
+                    );
+                    clicked |= label.on_hover_text_at_pointer(name).clicked();
+
+                    let percent_width = 48.0;
+                    let bar_width =
+                        (ui.available_width() - percent_width - ui.spacing().item_spacing.x)
+                            .max(BAR_HEIGHT);
+                    let (rect, response) =
+                        ui.allocate_exact_size(vec2(bar_width, BAR_HEIGHT), Sense::click());
+                    let painter = ui.painter_at(rect);
+                    painter.rect_filled(rect, 0.0, appearance.deemphasized_text_color);
+                    if total > 0 {
+                        let scale = rect.width() / total as f32;
+                        for (start, len, color) in bar_segments(symbol_diff, appearance) {
+                            let min = pos2(rect.left() + start as f32 * scale, rect.top());
+                            let size = vec2((len as f32 * scale).max(1.0), rect.height());
+                            painter.rect_filled(Rect::from_min_size(min, size), 0.0, color);
+                        }
+                    }
+                    clicked |= response
+                        .on_hover_text_at_pointer(format!(
+                            "{name}\n{matched}/{total} instructions match"
+                        ))
+                        .clicked();
+
+                    if let Some(match_percent) = symbol_diff.match_percent {
+                        ui.colored_label(
+                            match_color_for_symbol(match_percent, appearance),
+                            format!("{:.0}%", match_percent.floor()),
+                        );
+                    }
+                });
+                if clicked {
+                    ret = Some(DiffViewAction::Navigate(DiffViewNavigation::with_symbols(
+                        View::FunctionDiff,
+                        other_ctx,
+                        symbol,
+                        section,
+                        symbol_diff,
+                        column,
+                    )));
+                }
+            }
+        }
+    });
+    ret
+}
@@ -0,0 +1,134 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use egui::{Grid, RichText, ScrollArea, TextStyle};
+use objdiff_core::jobs::objdiff::ObjDiffResult;
+
+use crate::views::{appearance::Appearance, symbol_diff::match_color_for_symbol};
+
+/// A change in a watched symbol's match percent between two builds.
+#[derive(Debug, Clone)]
+pub struct WatchChange {
+    pub display_name: String,
+    pub before: Option<f32>,
+    pub after: Option<f32>,
+}
+
+/// Tracks the match percent of watched symbols across rebuilds of the selected object.
+#[derive(Default)]
+pub struct WatchState {
+    /// Name of the unit the percents were recorded for
+    unit: String,
+    /// Match percents from the previous build, keyed by target symbol name
+    percents: BTreeMap<String, Option<f32>>,
+    /// Changes not yet dismissed
+    pub changes: Vec<WatchChange>,
+}
+
+impl WatchState {
+    /// Compares the match percents of the watched symbols against the previous build. Symbols
+    /// without a previous build (e.g. newly watched) only record their current percent.
+    /// Switching to another unit discards the recorded percents and pending changes.
+    pub fn update(&mut self, unit: &str, build: &ObjDiffResult, watched: &BTreeSet<String>) {
+        if self.unit != unit {
+            *self = Self { unit: unit.to_string(), ..Default::default() };
+        }
+        let mut current = BTreeMap::new();
+        let mut display_names = BTreeMap::new();
+        if let Some((obj, diff)) = &build.first_obj {
+            for (section, section_diff) in obj.sections.iter().zip(&diff.sections) {
+                for (symbol, symbol_diff) in section.symbols.iter().zip(&section_diff.symbols) {
+                    if watched.contains(&symbol.name) {
+                        current.insert(symbol.name.clone(), symbol_diff.match_percent);
+                        display_names.insert(
+                            symbol.name.as_str(),
+                            symbol.demangled_name.as_deref().unwrap_or(&symbol.name),
+                        );
+                    }
+                }
+            }
+        }
+        for name in watched {
+            let after = current.get(name).copied().flatten();
+            let Some(&before) = self.percents.get(name) else {
+                continue;
+            };
+            if rounded(before) != rounded(after) {
+                let display_name =
+                    display_names.get(name.as_str()).copied().unwrap_or(name.as_str());
+                log::info!(
+                    "Watched symbol {display_name}: {} -> {}",
+                    format_percent(before),
+                    format_percent(after)
+                );
+                self.changes.retain(|c| c.display_name != display_name);
+                self.changes.push(WatchChange {
+                    display_name: display_name.to_string(),
+                    before,
+                    after,
+                });
+            }
+        }
+        self.percents = watched
+            .iter()
+            .map(|name| (name.clone(), current.get(name).copied().flatten()))
+            .collect();
+    }
+}
+
+/// Ignores floating point noise below the displayed precision.
+fn rounded(percent: Option<f32>) -> Option<i32> { percent.map(|p| (p * 100.0).round() as i32) }
+
+fn format_percent(percent: Option<f32>) -> String {
+    match percent {
+        Some(percent) => format!("{percent:.2}%"),
+        None => "missing".to_string(),
+    }
+}
+
+pub fn watch_notification_window(
+    ctx: &egui::Context,
+    state: &mut WatchState,
+    appearance: &Appearance,
+) {
+    if state.changes.is_empty() {
+        return;
+    }
+    let mut open = true;
+    let mut dismiss = false;
+    egui::Window::new("Watched Symbols Changed")
+        .open(&mut open)
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-10.0, -10.0])
+        .resizable(false)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                Grid::new("watch_changes").striped(true).show(ui, |ui| {
+                    ui.style_mut().override_text_style = Some(TextStyle::Monospace);
+                    for change in &state.changes {
+                        ui.label(
+                            RichText::new(&change.display_name).color(appearance.highlight_color),
+                        );
+                        percent_label(ui, change.before, appearance);
+                        ui.label("→");
+                        percent_label(ui, change.after, appearance);
+                        ui.end_row();
+                    }
+                });
+            });
+            ui.separator();
+            if ui.button("Dismiss").clicked() {
+                dismiss = true;
+            }
+        });
+    if !open || dismiss {
+        state.changes.clear();
+    }
+}
+
+fn percent_label(ui: &mut egui::Ui, percent: Option<f32>, appearance: &Appearance) {
+    let color = match percent {
+        Some(percent) => match_color_for_symbol(percent, appearance),
+        None => appearance.deemphasized_text_color,
+    };
+    ui.label(RichText::new(format_percent(percent)).color(color));
+}
@@ -0,0 +1,24 @@
+__version__: str
+
+def run_diff_proto(left: bytes | None, right: bytes | None, config: str | None = None) -> bytes:
+    """Diffs two objects, returning a protobuf-encoded `objdiff.diff.DiffResult`.
+
+    `config` is a JSON-encoded `DiffObjConfig`. Passing only one object loads it without diffing.
+    """
+
+def run_diff_json(left: bytes | None, right: bytes | None, config: str | None = None) -> str:
+    """Diffs two objects, returning an `objdiff.diff.DiffResult` as JSON.
+
+    `config` is a JSON-encoded `DiffObjConfig`. Passing only one object loads it without diffing.
+    """
+
+def report_unit_proto(
+    name: str, left: bytes | None, right: bytes | None, complete: bool = False
+) -> bytes:
+    """Generates a progress report for a single unit as a protobuf-encoded
+    `objdiff.report.ReportUnit`."""
+
+def report_unit_json(
+    name: str, left: bytes | None, right: bytes | None, complete: bool = False
+) -> str:
+    """Generates a progress report for a single unit as an `objdiff.report.ReportUnit` in JSON."""
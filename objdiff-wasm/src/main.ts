@@ -5,12 +5,21 @@ import type {
     DiffObjConfig,
     MipsAbi,
     MipsInstrCategory,
+    PpcCrFieldMismatch,
     X86Formatter
 } from '../pkg';
 import {AnyHandlerData, InMessage, OutMessage} from './worker';
 
 // Export wasm types
-export {ArmArchVersion, ArmR9Usage, MipsAbi, MipsInstrCategory, X86Formatter, DiffObjConfig};
+export {
+    ArmArchVersion,
+    ArmR9Usage,
+    MipsAbi,
+    MipsInstrCategory,
+    PpcCrFieldMismatch,
+    X86Formatter,
+    DiffObjConfig
+};
 
 // Export protobuf types
 export * from '../gen/diff_pb';